@@ -5,6 +5,7 @@
 
 use crate::types::*;
 use super::flags::ArrayFlags;
+use std::any::Any;
 use std::sync::{Arc, Weak, atomic::{AtomicUsize, Ordering}};
 
 /// Maximum number of dimensions for arrays
@@ -72,8 +73,24 @@ pub struct Array {
     base_weak: Option<Weak<Array>>,
     /// Owned data flag
     owns_data: bool,
+    /// External owner of the data (a byte buffer, foreign allocation, ...),
+    /// kept alive by the array and every view of it
+    owner: Option<Arc<dyn Any + Send + Sync>>,
+    /// The owner does not allow writes to the data, so the array (and every
+    /// view of it) can never be made writeable
+    readonly_owner: bool,
 }
 
+// SAFETY: an array holds no thread-affine state. `data` is either an
+// allocation the array owns, freed exactly once when it is dropped, or memory
+// kept alive by `base` or `owner`, whose reference counts are atomic, so the
+// memory stays valid on whichever thread the array is moved to. A view moved
+// to another thread keeps sharing its base there; safe code can only read
+// through that shared base, since every write through `&Array`
+// (`index_assign`, exported data pointers) is `unsafe` and requires the
+// caller to rule out concurrent access. `Array` is deliberately not `Sync`.
+unsafe impl Send for Array {}
+
 impl Array {
     /// Create a new array
     pub fn new(shape: Vec<i64>, dtype: DType) -> Result<Self, ArrayError> {
@@ -122,6 +139,8 @@ impl Array {
             base: None,
             base_weak: None,
             owns_data: true,
+            owner: None,
+            readonly_owner: false,
         };
         
         // Update flags based on memory layout
@@ -130,6 +149,32 @@ impl Array {
         Ok(array)
     }
     
+    /// Create a new array with the given memory order
+    ///
+    /// `Order::F` lays the data out column-major. `Order::A` and `Order::K`
+    /// have no prototype to follow here and give C order.
+    pub fn new_with_order(shape: Vec<i64>, dtype: DType, order: Order) -> Result<Self, ArrayError> {
+        if order == Order::F {
+            let axes: Vec<usize> = (0..shape.len()).rev().collect();
+            Array::new_with_axis_order(shape, dtype, &axes)
+        } else {
+            Array::new(shape, dtype)
+        }
+    }
+    
+    /// Create a new array whose strides follow `axes`, listed from the
+    /// slowest-varying axis to the fastest
+    pub(crate) fn new_with_axis_order(shape: Vec<i64>, dtype: DType, axes: &[usize]) -> Result<Self, ArrayError> {
+        let mut array = Array::new(shape, dtype)?;
+        let mut stride = array.itemsize as i64;
+        for &axis in axes.iter().rev() {
+            array.strides[axis] = stride;
+            stride *= array.shape[axis];
+        }
+        array.update_flags();
+        Ok(array)
+    }
+    
     /// Get the shape of the array
     pub fn shape(&self) -> &[i64] {
         &self.shape
@@ -210,7 +255,10 @@ impl Array {
         }
         
         // Set default flags for new arrays
-        new_flags |= ArrayFlags::WRITEABLE | ArrayFlags::ALIGNED;
+        new_flags |= ArrayFlags::ALIGNED;
+        if !self.readonly_owner {
+            new_flags |= ArrayFlags::WRITEABLE;
+        }
         
         // Set OWNDATA based on internal flag
         if self.owns_data {
@@ -304,6 +352,8 @@ impl Array {
             base: None,
             base_weak: None,
             owns_data,
+            owner: None,
+            readonly_owner: false,
         };
         
         array.update_flags();
@@ -311,6 +361,62 @@ impl Array {
         Ok(array)
     }
     
+    /// Create an array over memory owned by `owner`
+    ///
+    /// The array does not free `data`; instead it holds `owner` (and every
+    /// view of the array holds it too), so the memory stays valid for as
+    /// long as any of them is alive. The array is read-only unless
+    /// `writeable` is set, and then stays read-only: `setflags` refuses to
+    /// make it or any view of it writeable.
+    ///
+    /// # Safety
+    /// `data` must be valid for the extent described by `shape`, `strides`
+    /// and `dtype` for as long as `owner` is alive, and must only be
+    /// written through the array if `writeable` is set.
+    pub unsafe fn from_owner(
+        data: *mut u8,
+        shape: Vec<i64>,
+        strides: Vec<i64>,
+        dtype: DType,
+        owner: Arc<dyn Any + Send + Sync>,
+        writeable: bool,
+    ) -> Result<Self, ArrayError> {
+        if data.is_null() || shape.len() != strides.len() {
+            return Err(ArrayError::InvalidShape);
+        }
+        let itemsize = dtype.itemsize();
+        let align = dtype.align().max(1);
+        let mut array = Array {
+            data,
+            ndim: shape.len(),
+            shape,
+            strides,
+            dtype,
+            flags: ArrayFlags::empty(),
+            itemsize,
+            base: None,
+            base_weak: None,
+            owns_data: false,
+            owner: Some(owner),
+            readonly_owner: !writeable,
+        };
+        array.update_flags();
+        if !writeable {
+            array.setflags(ArrayFlags::WRITEABLE, false)?;
+        }
+        let misaligned = !(data as usize).is_multiple_of(align) || array.strides.iter().any(|&s| s % align as i64 != 0);
+        if misaligned {
+            array.setflags(ArrayFlags::ALIGNED, false)?;
+        }
+        Ok(array)
+    }
+
+    /// External owner of the data, for arrays created with `from_owner`
+    /// and views of them
+    pub fn owner(&self) -> Option<&Arc<dyn Any + Send + Sync>> {
+        self.owner.as_ref()
+    }
+
     /// Create a zero-copy view with new shape and strides
     /// 
     /// The view shares memory with this array. 
@@ -353,9 +459,14 @@ impl Array {
             base: None, // Will be set if base is provided as Arc
             base_weak,
             owns_data: false, // Views never own data
+            owner: self.owner.clone(),
+            readonly_owner: self.readonly_owner,
         };
         
         view_array.update_flags();
+        if !self.is_writeable() {
+            view_array.setflags(ArrayFlags::WRITEABLE, false)?;
+        }
         
         Ok(view_array)
     }
@@ -392,18 +503,60 @@ impl Array {
             base: Some(Arc::clone(base)),
             base_weak: None,
             owns_data: false,
+            owner: base.owner.clone(),
+            readonly_owner: base.readonly_owner,
         };
         
         view_array.update_flags();
         
         // Set writeable flag to match base
         if !is_writeable {
-            view_array.setflags(ArrayFlags::WRITEABLE, false);
+            view_array.setflags(ArrayFlags::WRITEABLE, false)?;
         }
         
         Ok(view_array)
     }
     
+    /// Create a view of an Arc-wrapped base array that starts `offset` bytes
+    /// into the base's data
+    ///
+    /// Fails unless every element the view can reach is an element of the
+    /// base, so slices and strided sub-views can be taken safely.
+    pub fn view_from_arc_at(
+        base: &Arc<Array>,
+        offset: isize,
+        shape: Vec<i64>,
+        strides: Vec<i64>,
+    ) -> Result<Self, ArrayError> {
+        let mut view_array = Array::view_from_arc(base, shape, strides)?;
+        base.check_view_extent(&view_array, offset)?;
+        view_array.data = base.data.wrapping_offset(offset);
+        Ok(view_array)
+    }
+    
+    /// Create a view that starts `offset` bytes into this array's data
+    ///
+    /// As with `view`, the base must be kept alive externally; prefer
+    /// `view_from_arc_at` when the base is shared.
+    pub fn view_at(&self, offset: isize, shape: Vec<i64>, strides: Vec<i64>) -> Result<Self, ArrayError> {
+        let mut view_array = self.view(shape, strides)?;
+        self.check_view_extent(&view_array, offset)?;
+        view_array.data = self.data.wrapping_offset(offset);
+        Ok(view_array)
+    }
+    
+    /// Check that a view starting `offset` bytes in only reaches our elements
+    fn check_view_extent(&self, view: &Array, offset: isize) -> Result<(), ArrayError> {
+        if view.size() > 0 {
+            let (low, high) = byte_extent(&view.shape, &view.strides, view.itemsize);
+            let (base_low, base_high) = byte_extent(&self.shape, &self.strides, self.itemsize);
+            if offset as i64 + low < base_low || offset as i64 + high > base_high {
+                return Err(ArrayError::InvalidShape);
+            }
+        }
+        Ok(())
+    }
+    
     /// Create a view with a different dtype
     /// 
     /// This creates a view that interprets the same memory with a different dtype.
@@ -430,9 +583,14 @@ impl Array {
             base: None,
             base_weak,
             owns_data: false,
+            owner: self.owner.clone(),
+            readonly_owner: self.readonly_owner,
         };
         
         view_array.update_flags();
+        if !self.is_writeable() {
+            view_array.setflags(ArrayFlags::WRITEABLE, false)?;
+        }
         
         Ok(view_array)
     }
@@ -463,13 +621,15 @@ impl Array {
             base: Some(Arc::clone(base)),
             base_weak: None,
             owns_data: false,
+            owner: base.owner.clone(),
+            readonly_owner: base.readonly_owner,
         };
         
         view_array.update_flags();
         
         // Set writeable flag to match base
         if !is_writeable {
-            view_array.setflags(ArrayFlags::WRITEABLE, false);
+            view_array.setflags(ArrayFlags::WRITEABLE, false)?;
         }
         
         Ok(view_array)
@@ -509,24 +669,35 @@ impl Array {
                         panic!("Failed to allocate memory for array copy");
                     }
                     // Copy data from view (respecting strides)
-                    // For now, copy the contiguous block - a full implementation would
-                    // need to handle non-contiguous arrays properly by iterating
-                    std::ptr::copy_nonoverlapping(self.data, ptr, size);
+                    if self.is_c_contiguous() {
+                        std::ptr::copy_nonoverlapping(self.data, ptr, size);
+                    } else {
+                        let bytes = crate::utils::to_contiguous_bytes(self);
+                        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, size);
+                    }
                     ptr
                 }
             };
             
+            // A strided view is gathered into C order
+            let strides = if self.is_c_contiguous() {
+                self.strides.clone()
+            } else {
+                crate::shape::shape::compute_reshape_strides(&self.shape, self.itemsize)
+            };
             let mut copy = Array {
                 data,
                 ndim: self.ndim,
                 shape: self.shape.clone(),
-                strides: self.strides.clone(),
+                strides,
                 dtype: self.dtype.clone(),
                 flags: ArrayFlags::empty(), // Will be updated
                 itemsize: self.itemsize,
                 base: None,
                 base_weak: None,
                 owns_data: true,
+                owner: None,
+                readonly_owner: false,
             };
             
             copy.update_flags();
@@ -535,12 +706,19 @@ impl Array {
     }
     
     /// Set flag values
-    pub fn setflags(&mut self, flags: ArrayFlags, value: bool) {
+    ///
+    /// Fails when setting `WRITEABLE` on an array over memory whose owner
+    /// does not allow writes (see `from_owner`).
+    pub fn setflags(&mut self, flags: ArrayFlags, value: bool) -> Result<(), ArrayError> {
+        if value && flags.contains(ArrayFlags::WRITEABLE) && self.readonly_owner {
+            return Err(ArrayError::InvalidValue("cannot set WRITEABLE flag to True of this array".to_string()));
+        }
         if value {
             self.flags |= flags;
         } else {
             self.flags &= !flags;
         }
+        Ok(())
     }
     
     /// Ensure array has at least 1 dimension
@@ -672,6 +850,8 @@ pub enum Order {
     F,
     /// Keep current order
     A,
+    /// Keep the axis ordering of an existing array as closely as possible
+    K,
 }
 
 impl Array {
@@ -682,7 +862,7 @@ impl Array {
         let is_contiguous = match order {
             Order::C => self.is_c_contiguous(),
             Order::F => self.is_f_contiguous(),
-            Order::A => self.is_c_contiguous() || self.is_f_contiguous(),
+            Order::A | Order::K => self.is_c_contiguous() || self.is_f_contiguous(),
         };
         
         if is_contiguous {
@@ -953,6 +1133,8 @@ impl Clone for Array {
                 base: None,
                 base_weak: None,
                 owns_data: true,
+                owner: None,
+                readonly_owner: false,
             }
         } else {
             // View - clone the Arc (incrementing reference count)
@@ -967,6 +1149,8 @@ impl Clone for Array {
                 base: self.base.clone(),
                 base_weak: self.base_weak.clone(),
                 owns_data: false,
+                owner: self.owner.clone(),
+                readonly_owner: self.readonly_owner,
             }
         }
     }
@@ -1016,6 +1200,23 @@ fn is_f_contiguous_layout(shape: &[i64], strides: &[i64], itemsize: usize) -> bo
     true
 }
 
+/// Lowest and one-past-highest byte offsets an array layout can reach
+///
+/// The layout must have at least one element.
+fn byte_extent(shape: &[i64], strides: &[i64], itemsize: usize) -> (i64, i64) {
+    let mut low = 0;
+    let mut high = itemsize as i64;
+    for (&n, &stride) in shape.iter().zip(strides) {
+        let span = (n - 1) * stride;
+        if span < 0 {
+            low += span;
+        } else {
+            high += span;
+        }
+    }
+    (low, high)
+}
+
 impl Drop for Array {
     fn drop(&mut self) {
         if self.owns_data && !self.data.is_null() {
@@ -1086,6 +1287,8 @@ pub enum ArrayError {
     ViewOutOfBounds,
     /// Invalid view parameters
     InvalidView,
+    /// Argument value out of range
+    InvalidValue(String),
 }
 
 impl std::fmt::Display for ArrayError {
@@ -1097,6 +1300,7 @@ impl std::fmt::Display for ArrayError {
             ArrayError::TypeMismatch => write!(f, "Type mismatch"),
             ArrayError::ViewOutOfBounds => write!(f, "View bounds out of range"),
             ArrayError::InvalidView => write!(f, "Invalid view parameters"),
+            ArrayError::InvalidValue(msg) => write!(f, "{}", msg),
         }
     }
 }
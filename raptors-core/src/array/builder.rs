@@ -2,7 +2,8 @@
 //!
 //! This module provides a builder pattern for creating arrays with various options.
 
-use crate::array::{Array, ArrayError};
+use crate::array::creation::fill_scalar;
+use crate::array::{Array, ArrayError, Order, Scalar};
 use crate::types::DType;
 
 /// Memory order for array layout
@@ -21,7 +22,7 @@ pub struct ArrayBuilder {
     shape: Option<Vec<i64>>,
     dtype: Option<DType>,
     order: Option<MemoryOrder>,
-    fill_value: Option<f64>,
+    fill_value: Option<Scalar>,
 }
 
 impl ArrayBuilder {
@@ -54,8 +55,10 @@ impl ArrayBuilder {
     }
     
     /// Set a fill value (for creating filled arrays)
-    pub fn with_fill_value(mut self, value: f64) -> Self {
-        self.fill_value = Some(value);
+    ///
+    /// The value is cast to the array's dtype, as in `full`.
+    pub fn with_fill_value(mut self, value: impl Into<Scalar>) -> Self {
+        self.fill_value = Some(value.into());
         self
     }
     
@@ -64,26 +67,16 @@ impl ArrayBuilder {
         let shape = self.shape.ok_or(ArrayError::InvalidShape)?;
         let dtype = self.dtype.unwrap_or_else(|| DType::new(crate::types::NpyType::Double));
         
-        let mut array = Array::new(shape, dtype)?;
+        let order = match self.order {
+            Some(MemoryOrder::F) => Order::F,
+            _ => Order::C,
+        };
+        let mut array = Array::new_with_order(shape, dtype, order)?;
         
-        // Apply fill value if specified
         if let Some(fill) = self.fill_value {
-            // Simple fill for double precision
-            if array.dtype().type_() == crate::types::NpyType::Double {
-                unsafe {
-                    let ptr = array.data_ptr_mut() as *mut f64;
-                    let size = array.size();
-                    for i in 0..size {
-                        *ptr.add(i) = fill;
-                    }
-                }
-            }
+            fill_scalar(&mut array, &fill)?;
         }
         
-        // Note: Memory order (C vs F) is determined by strides,
-        // which are computed automatically. For now, we always use C order.
-        // Full F-order support would require stride computation changes.
-        
         Ok(array)
     }
 }
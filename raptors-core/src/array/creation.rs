@@ -2,9 +2,206 @@
 //!
 //! This module provides array creation functionality,
 //! equivalent to NumPy's array creation functions from ctors.c
+//! and `numpy/lib/_twodim_base_impl.py`
 
-use crate::array::{Array, ArrayError};
-use crate::types::{DType, NpyType};
+use crate::array::{Array, ArrayError, Order};
+use crate::types::{Complex128, DType, NpyType};
+use crate::utils::{element_offsets, to_contiguous_bytes};
+
+/// Fill value for `full` and related functions
+///
+/// The value is cast to the array's dtype when written: numbers convert
+/// with `as` semantics, complex values keep their real part for real
+/// dtypes, and string dtypes store the value's text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    /// Boolean value
+    Bool(bool),
+    /// Signed integer value
+    Int(i64),
+    /// Unsigned integer value
+    UInt(u64),
+    /// Floating point value
+    Float(f64),
+    /// Complex value
+    Complex(Complex128),
+    /// Text value (for `String` and `Unicode` dtypes)
+    Str(String),
+}
+
+macro_rules! scalar_from {
+    ($($t:ty => $variant:ident as $as:ty),* $(,)?) => {
+        $(impl From<$t> for Scalar {
+            fn from(value: $t) -> Self {
+                Scalar::$variant(value as $as)
+            }
+        })*
+    };
+}
+
+scalar_from!(
+    i8 => Int as i64, i16 => Int as i64, i32 => Int as i64, i64 => Int as i64,
+    u8 => UInt as u64, u16 => UInt as u64, u32 => UInt as u64, u64 => UInt as u64,
+    f32 => Float as f64, f64 => Float as f64,
+);
+
+impl From<bool> for Scalar {
+    fn from(value: bool) -> Self {
+        Scalar::Bool(value)
+    }
+}
+
+impl From<Complex128> for Scalar {
+    fn from(value: Complex128) -> Self {
+        Scalar::Complex(value)
+    }
+}
+
+impl From<&str> for Scalar {
+    fn from(value: &str) -> Self {
+        Scalar::Str(value.to_string())
+    }
+}
+
+impl From<String> for Scalar {
+    fn from(value: String) -> Self {
+        Scalar::Str(value)
+    }
+}
+
+impl Scalar {
+    fn to_complex(&self) -> Option<Complex128> {
+        match self {
+            Scalar::Bool(b) => Some(Complex128::new(*b as u8 as f64, 0.0)),
+            Scalar::Int(i) => Some(Complex128::new(*i as f64, 0.0)),
+            Scalar::UInt(u) => Some(Complex128::new(*u as f64, 0.0)),
+            Scalar::Float(f) => Some(Complex128::new(*f, 0.0)),
+            Scalar::Complex(z) => Some(*z),
+            Scalar::Str(_) => None,
+        }
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            Scalar::Bool(b) => if *b { "True" } else { "False" }.to_string(),
+            Scalar::Int(i) => i.to_string(),
+            Scalar::UInt(u) => u.to_string(),
+            Scalar::Float(f) => f.to_string(),
+            Scalar::Complex(z) => format!("({}{:+}j)", z.re, z.im),
+            Scalar::Str(s) => s.clone(),
+        }
+    }
+}
+
+/// Convert to IEEE 754 half precision bits, rounding to nearest even
+fn f16_bits(value: f64) -> u16 {
+    let bits = (value as f32).to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let e = exponent - 127 + 15;
+    if e >= 31 {
+        return sign | 0x7c00;
+    }
+    let (half, rem, halfway) = if e <= 0 {
+        if e < -10 {
+            return sign;
+        }
+        let m = mantissa | 0x80_0000;
+        let shift = (14 - e) as u32;
+        (m >> shift, m & ((1 << shift) - 1), 1 << (shift - 1))
+    } else {
+        (((e as u32) << 10) | (mantissa >> 13), mantissa & 0x1fff, 0x1000)
+    };
+    let rounded = if rem > halfway || (rem == halfway && half & 1 == 1) { half + 1 } else { half };
+    sign | rounded as u16
+}
+
+/// Encode `value` as one element of `dtype` into `out` (native byte order)
+pub(crate) fn encode_scalar(value: &Scalar, dtype: &DType, out: &mut [u8]) -> Result<(), ArrayError> {
+    macro_rules! put {
+        ($v:expr) => {{
+            let bytes = $v.to_ne_bytes();
+            out[..bytes.len()].copy_from_slice(&bytes);
+        }};
+    }
+    macro_rules! int {
+        ($t:ty) => {
+            match value {
+                Scalar::Bool(b) => put!(*b as u8 as $t),
+                Scalar::Int(i) => put!(*i as $t),
+                Scalar::UInt(u) => put!(*u as $t),
+                Scalar::Float(f) => put!(*f as $t),
+                Scalar::Complex(z) => put!(z.re as $t),
+                Scalar::Str(_) => return Err(ArrayError::TypeMismatch),
+            }
+        };
+    }
+    let complex = value.to_complex();
+    match dtype.type_() {
+        NpyType::Bool => {
+            let z = complex.ok_or(ArrayError::TypeMismatch)?;
+            out[0] = (z.re != 0.0 || z.im != 0.0) as u8;
+        }
+        NpyType::Byte => int!(i8),
+        NpyType::UByte => int!(u8),
+        NpyType::Short => int!(i16),
+        NpyType::UShort => int!(u16),
+        NpyType::Int => int!(i32),
+        NpyType::UInt => int!(u32),
+        NpyType::Long | NpyType::LongLong | NpyType::DateTime | NpyType::Timedelta => int!(i64),
+        NpyType::ULong | NpyType::ULongLong => int!(u64),
+        NpyType::Half => put!(f16_bits(complex.ok_or(ArrayError::TypeMismatch)?.re)),
+        NpyType::Float => put!(complex.ok_or(ArrayError::TypeMismatch)?.re as f32),
+        NpyType::Double => put!(complex.ok_or(ArrayError::TypeMismatch)?.re),
+        NpyType::CFloat => {
+            let z = complex.ok_or(ArrayError::TypeMismatch)?;
+            put!(z.re as f32);
+            out[4..8].copy_from_slice(&(z.im as f32).to_ne_bytes());
+        }
+        NpyType::CDouble => {
+            let z = complex.ok_or(ArrayError::TypeMismatch)?;
+            put!(z.re);
+            out[8..16].copy_from_slice(&z.im.to_ne_bytes());
+        }
+        NpyType::String => {
+            let text = value.to_text();
+            let len = text.len().min(dtype.itemsize());
+            out[..dtype.itemsize()].fill(0);
+            out[..len].copy_from_slice(&text.as_bytes()[..len]);
+        }
+        NpyType::Unicode => {
+            out[..dtype.itemsize()].fill(0);
+            for (slot, ch) in out[..dtype.itemsize()].chunks_exact_mut(4).zip(value.to_text().chars()) {
+                slot.copy_from_slice(&(ch as u32).to_ne_bytes());
+            }
+        }
+        _ => return Err(ArrayError::TypeMismatch),
+    }
+    Ok(())
+}
+
+/// Copy one encoded element into every element of `array`
+pub(crate) fn fill_bytes(array: &mut Array, element: &[u8]) {
+    let offsets = element_offsets(array.shape(), array.strides());
+    let base = array.data_ptr_mut();
+    for offset in offsets {
+        unsafe {
+            std::ptr::copy_nonoverlapping(element.as_ptr(), base.offset(offset), element.len());
+        }
+    }
+}
+
+/// Fill every element of an array with `value`
+pub(crate) fn fill_scalar(array: &mut Array, value: &Scalar) -> Result<(), ArrayError> {
+    let mut element = vec![0u8; array.itemsize()];
+    encode_scalar(value, array.dtype(), &mut element)?;
+    fill_bytes(array, &element);
+    Ok(())
+}
 
 /// Create an empty array with the specified shape and dtype
 ///
@@ -16,58 +213,315 @@ pub fn empty(shape: Vec<i64>, dtype: DType) -> Result<Array, ArrayError> {
 /// Create a zero-filled array with the specified shape and dtype
 pub fn zeros(shape: Vec<i64>, dtype: DType) -> Result<Array, ArrayError> {
     let mut array = Array::new(shape, dtype)?;
-    
+
     // Zero-fill the memory
     unsafe {
         let size = array.size() * array.itemsize();
         std::ptr::write_bytes(array.data_ptr_mut(), 0, size);
     }
-    
+
     Ok(array)
 }
 
 /// Create a one-filled array with the specified shape and dtype
 pub fn ones(shape: Vec<i64>, dtype: DType) -> Result<Array, ArrayError> {
-    let mut array = Array::new(shape, dtype.clone())?;
-    
-    // Fill with ones based on dtype
-    match dtype.type_() {
-        NpyType::Bool => fill_with_value(&mut array, true)?,
-        NpyType::Byte => fill_with_value(&mut array, 1i8)?,
-        NpyType::UByte => fill_with_value(&mut array, 1u8)?,
-        NpyType::Short => fill_with_value(&mut array, 1i16)?,
-        NpyType::UShort => fill_with_value(&mut array, 1u16)?,
-        NpyType::Int => fill_with_value(&mut array, 1i32)?,
-        NpyType::UInt => fill_with_value(&mut array, 1u32)?,
-        NpyType::Long | NpyType::LongLong => fill_with_value(&mut array, 1i64)?,
-        NpyType::ULong | NpyType::ULongLong => fill_with_value(&mut array, 1u64)?,
-        NpyType::Float | NpyType::Half => fill_with_value(&mut array, 1.0f32)?,
-        NpyType::Double | NpyType::LongDouble => fill_with_value(&mut array, 1.0f64)?,
-        _ => return Err(ArrayError::TypeMismatch),
+    full(shape, 1i64, dtype)
+}
+
+/// Create an array filled with `value`
+///
+/// Equivalent to `numpy.full`. The value is cast to `dtype`; see `Scalar`.
+pub fn full(shape: Vec<i64>, value: impl Into<Scalar>, dtype: DType) -> Result<Array, ArrayError> {
+    let mut array = Array::new(shape, dtype)?;
+    fill_scalar(&mut array, &value.into())?;
+    Ok(array)
+}
+
+/// Allocate an array shaped like `prototype` with the layout chosen by `order`
+///
+/// `Order::K` keeps the prototype's axis ordering (sorted by stride),
+/// `Order::A` uses Fortran order only for Fortran-contiguous prototypes.
+fn allocate_like(prototype: &Array, dtype: Option<DType>, order: Order) -> Result<Array, ArrayError> {
+    let shape = prototype.shape().to_vec();
+    let dtype = dtype.unwrap_or_else(|| prototype.dtype().clone());
+    let fortran = prototype.is_f_contiguous() && !prototype.is_c_contiguous();
+    match order {
+        Order::C => Array::new(shape, dtype),
+        Order::F => Array::new_with_order(shape, dtype, Order::F),
+        Order::A | Order::K if prototype.is_c_contiguous() => Array::new(shape, dtype),
+        Order::A | Order::K if fortran => Array::new_with_order(shape, dtype, Order::F),
+        Order::A => Array::new(shape, dtype),
+        Order::K => {
+            let strides = prototype.strides();
+            let mut axes: Vec<usize> = (0..shape.len()).collect();
+            axes.sort_by_key(|&axis| std::cmp::Reverse(strides[axis].unsigned_abs()));
+            Array::new_with_axis_order(shape, dtype, &axes)
+        }
+    }
+}
+
+/// Create an uninitialized array with the shape of `prototype`
+///
+/// Equivalent to `numpy.empty_like`. `dtype` defaults to the prototype's;
+/// with `Order::K` the memory layout follows the prototype's.
+pub fn empty_like(prototype: &Array, dtype: Option<DType>, order: Order) -> Result<Array, ArrayError> {
+    allocate_like(prototype, dtype, order)
+}
+
+/// Create a zero-filled array with the shape of `prototype`
+///
+/// Equivalent to `numpy.zeros_like`.
+pub fn zeros_like(prototype: &Array, dtype: Option<DType>, order: Order) -> Result<Array, ArrayError> {
+    let mut array = allocate_like(prototype, dtype, order)?;
+    unsafe {
+        let size = array.size() * array.itemsize();
+        std::ptr::write_bytes(array.data_ptr_mut(), 0, size);
     }
-    
     Ok(array)
 }
 
-/// Fill array with a value (helper function)
-fn fill_with_value<T: Copy>(array: &mut Array, value: T) -> Result<(), ArrayError> {
-    let size = array.size();
-    if size == 0 {
-        return Ok(());
+/// Create a one-filled array with the shape of `prototype`
+///
+/// Equivalent to `numpy.ones_like`.
+pub fn ones_like(prototype: &Array, dtype: Option<DType>, order: Order) -> Result<Array, ArrayError> {
+    full_like(prototype, 1i64, dtype, order)
+}
+
+/// Create an array with the shape of `prototype` filled with `value`
+///
+/// Equivalent to `numpy.full_like`.
+pub fn full_like(
+    prototype: &Array,
+    value: impl Into<Scalar>,
+    dtype: Option<DType>,
+    order: Order,
+) -> Result<Array, ArrayError> {
+    let mut array = allocate_like(prototype, dtype, order)?;
+    fill_scalar(&mut array, &value.into())?;
+    Ok(array)
+}
+
+/// Write one encoded element at `(row, col)` of a 2-D array
+fn put_2d(array: &mut Array, row: usize, col: usize, element: &[u8]) {
+    let offset = row as isize * array.strides()[0] as isize + col as isize * array.strides()[1] as isize;
+    unsafe {
+        std::ptr::copy_nonoverlapping(element.as_ptr(), array.data_ptr_mut().offset(offset), element.len());
     }
-    
-    let itemsize = std::mem::size_of::<T>();
-    if itemsize != array.itemsize() {
+}
+
+/// Encoded value one for `dtype`
+fn one_element(dtype: &DType) -> Result<Vec<u8>, ArrayError> {
+    let mut element = vec![0u8; dtype.itemsize()];
+    encode_scalar(&Scalar::Int(1), dtype, &mut element)?;
+    Ok(element)
+}
+
+/// Create an `n x m` array with ones on the `k`-th diagonal
+///
+/// Equivalent to `numpy.eye`. `m` defaults to `n`; positive `k` selects
+/// an upper diagonal and negative `k` a lower one.
+pub fn eye(n: usize, m: Option<usize>, k: i64, dtype: DType) -> Result<Array, ArrayError> {
+    let m = m.unwrap_or(n);
+    let mut array = zeros(vec![n as i64, m as i64], dtype)?;
+    let one = one_element(array.dtype())?;
+    for row in 0..n {
+        let col = row as i64 + k;
+        if (0..m as i64).contains(&col) {
+            put_2d(&mut array, row, col as usize, &one);
+        }
+    }
+    Ok(array)
+}
+
+/// Create the `n x n` identity matrix
+///
+/// Equivalent to `numpy.identity`.
+pub fn identity(n: usize, dtype: DType) -> Result<Array, ArrayError> {
+    eye(n, None, 0, dtype)
+}
+
+/// Extract a diagonal or construct a diagonal array
+///
+/// Equivalent to `numpy.diag`. For a 1-D input, returns a square 2-D array
+/// with the input on the `k`-th diagonal; for a 2-D input, returns a copy
+/// of its `k`-th diagonal.
+pub fn diag(v: &Array, k: i64) -> Result<Array, ArrayError> {
+    let itemsize = v.itemsize();
+    let bytes = to_contiguous_bytes(v);
+    match v.ndim() {
+        1 => {
+            let len = v.shape()[0] as usize;
+            let n = len + k.unsigned_abs() as usize;
+            let mut array = zeros(vec![n as i64, n as i64], v.dtype().clone())?;
+            let (row0, col0) = if k >= 0 { (0, k as usize) } else { ((-k) as usize, 0) };
+            for (i, element) in bytes.chunks_exact(itemsize).enumerate() {
+                put_2d(&mut array, row0 + i, col0 + i, element);
+            }
+            Ok(array)
+        }
+        2 => {
+            let (rows, cols) = (v.shape()[0], v.shape()[1]);
+            let (row0, col0) = if k >= 0 { (0, k) } else { (-k, 0) };
+            let len = (rows - row0).min(cols - col0).max(0) as usize;
+            let mut out = Vec::with_capacity(len * itemsize);
+            for i in 0..len {
+                let start = (((row0 as usize + i) * cols as usize) + col0 as usize + i) * itemsize;
+                out.extend_from_slice(&bytes[start..start + itemsize]);
+            }
+            let mut array = Array::new(vec![len as i64], v.dtype().clone())?;
+            unsafe {
+                std::ptr::copy_nonoverlapping(out.as_ptr(), array.data_ptr_mut(), out.len());
+            }
+            Ok(array)
+        }
+        _ => Err(ArrayError::InvalidShape),
+    }
+}
+
+/// Create an `n x m` array with ones at and below the `k`-th diagonal
+///
+/// Equivalent to `numpy.tri`. `m` defaults to `n`.
+pub fn tri(n: usize, m: Option<usize>, k: i64, dtype: DType) -> Result<Array, ArrayError> {
+    let m = m.unwrap_or(n);
+    let mut array = zeros(vec![n as i64, m as i64], dtype)?;
+    let one = one_element(array.dtype())?;
+    for row in 0..n {
+        for col in 0..m {
+            if col as i64 <= row as i64 + k {
+                put_2d(&mut array, row, col, &one);
+            }
+        }
+    }
+    Ok(array)
+}
+
+/// Copy `a`, zeroing elements of the last two axes where `keep(row, col)` is false
+///
+/// A 1-D input is treated as a single row repeated into a square matrix,
+/// as NumPy does.
+fn triangle(a: &Array, keep: impl Fn(i64, i64) -> bool) -> Result<Array, ArrayError> {
+    let itemsize = a.itemsize();
+    let mut bytes = to_contiguous_bytes(a);
+    let shape = match a.ndim() {
+        0 => return Err(ArrayError::InvalidShape),
+        1 => {
+            let n = a.shape()[0];
+            bytes = bytes.repeat(n as usize);
+            vec![n, n]
+        }
+        _ => a.shape().to_vec(),
+    };
+    let (rows, cols) = (shape[shape.len() - 2], shape[shape.len() - 1]);
+    if rows > 0 && cols > 0 {
+        for (index, element) in bytes.chunks_exact_mut(itemsize).enumerate() {
+            let col = index as i64 % cols;
+            let row = (index as i64 / cols) % rows;
+            if !keep(row, col) {
+                element.fill(0);
+            }
+        }
+    }
+    let mut array = Array::new(shape, a.dtype().clone())?;
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), array.data_ptr_mut(), bytes.len());
+    }
+    Ok(array)
+}
+
+/// Lower triangle of an array
+///
+/// Equivalent to `numpy.tril`: elements above the `k`-th diagonal of the
+/// last two axes are zeroed.
+pub fn tril(a: &Array, k: i64) -> Result<Array, ArrayError> {
+    triangle(a, |row, col| col <= row + k)
+}
+
+/// Upper triangle of an array
+///
+/// Equivalent to `numpy.triu`: elements below the `k`-th diagonal of the
+/// last two axes are zeroed.
+pub fn triu(a: &Array, k: i64) -> Result<Array, ArrayError> {
+    triangle(a, |row, col| col >= row + k)
+}
+
+/// Generate a Vandermonde matrix
+///
+/// Equivalent to `numpy.vander`: column `j` holds `x ** (n - 1 - j)`, or
+/// `x ** j` when `increasing`. `n` defaults to `len(x)`. Integer and
+/// boolean inputs give `Long`, complex inputs `CDouble` and other real
+/// inputs `Double`.
+pub fn vander(x: &Array, n: Option<usize>, increasing: bool) -> Result<Array, ArrayError> {
+    if x.ndim() != 1 {
+        return Err(ArrayError::InvalidShape);
+    }
+    let rows = x.shape()[0] as usize;
+    let n = n.unwrap_or(rows);
+    let shape = vec![rows as i64, n as i64];
+    let column = |j: usize| if increasing { j } else { n - 1 - j };
+
+    fn powers<T: Copy>(values: &[T], n: usize, column: impl Fn(usize) -> usize, one: T, mul: impl Fn(T, T) -> T) -> Vec<T> {
+        let mut out = vec![one; values.len() * n];
+        for (row, &v) in values.iter().enumerate() {
+            let mut power = one;
+            for p in 0..n {
+                out[row * n + column(p)] = power;
+                power = mul(power, v);
+            }
+        }
+        out
+    }
+
+    match x.dtype().type_() {
+        NpyType::CFloat | NpyType::CDouble => {
+            let values = crate::utils::to_complex128_vec(x).ok_or(ArrayError::TypeMismatch)?;
+            let out = powers(&values, n, column, Complex128::new(1.0, 0.0), |a, b| a * b);
+            Array::from_slice(&out, shape, DType::new(NpyType::CDouble))
+        }
+        NpyType::Float | NpyType::Double | NpyType::Half => {
+            let values = crate::utils::to_f64_vec(x).ok_or(ArrayError::TypeMismatch)?;
+            let out = powers(&values, n, column, 1.0, |a, b| a * b);
+            Array::from_slice(&out, shape, DType::new(NpyType::Double))
+        }
+        _ => {
+            let values: Vec<i64> = crate::utils::to_f64_vec(x)
+                .ok_or(ArrayError::TypeMismatch)?
+                .into_iter()
+                .map(|v| v as i64)
+                .collect();
+            let out = powers(&values, n, column, 1i64, i64::wrapping_mul);
+            Array::from_slice(&out, shape, DType::new(NpyType::Long))
+        }
+    }
+}
+
+/// Construct an array by evaluating `f` at each index
+///
+/// Equivalent to `numpy.fromfunction`, except that `f` is called once per
+/// element with that element's index rather than with index arrays. The
+/// size of `T` must match the dtype's item size.
+pub fn fromfunction<T: Copy, F: FnMut(&[usize]) -> T>(
+    shape: Vec<i64>,
+    dtype: DType,
+    mut f: F,
+) -> Result<Array, ArrayError> {
+    if std::mem::size_of::<T>() != dtype.itemsize() {
         return Err(ArrayError::TypeMismatch);
     }
-    
+    let mut array = Array::new(shape.clone(), dtype)?;
+    let size = array.size();
+    let mut index = vec![0usize; shape.len()];
     unsafe {
         let data = array.data_ptr_mut() as *mut T;
         for i in 0..size {
-            *data.add(i) = value;
+            std::ptr::write_unaligned(data.add(i), f(&index));
+            for axis in (0..index.len()).rev() {
+                index[axis] += 1;
+                if (index[axis] as i64) < shape[axis] {
+                    break;
+                }
+                index[axis] = 0;
+            }
         }
     }
-    
-    Ok(())
+    Ok(array)
 }
-
@@ -8,6 +8,7 @@ mod creation;
 mod flags;
 mod builder;
 mod iter_ops;
+mod ranges;
 mod subclassing;
 
 pub use arrayobject::*;
@@ -15,5 +16,6 @@ pub use creation::*;
 pub use flags::*;
 pub use builder::{ArrayBuilder, MemoryOrder};
 pub use iter_ops::ArrayIterOps;
+pub use ranges::*;
 pub use subclassing::*;
 
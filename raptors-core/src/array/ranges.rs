@@ -0,0 +1,350 @@
+//! Numerical ranges and grids
+//!
+//! This module provides `arange`, `linspace`, `logspace`, `geomspace`,
+//! `meshgrid` and the `mgrid`/`ogrid` helpers, equivalent to NumPy's
+//! functions from multiarraymodule.c, `_function_base_impl.py` and
+//! `_index_tricks_impl.py`
+
+use crate::array::creation::{encode_scalar, Scalar};
+use crate::array::{Array, ArrayError};
+use crate::types::{DType, NpyType};
+use crate::utils::to_contiguous_bytes;
+
+fn is_integer(ty: NpyType) -> bool {
+    use NpyType::*;
+    matches!(ty, Bool | Byte | UByte | Short | UShort | Int | UInt | Long | ULong | LongLong | ULongLong)
+}
+
+/// Build a C-contiguous array of `dtype` from `f64` values
+fn from_f64_values(values: &[f64], shape: Vec<i64>, dtype: DType) -> Result<Array, ArrayError> {
+    if dtype.type_() == NpyType::Double {
+        return Array::from_slice(values, shape, dtype);
+    }
+    let mut array = Array::new(shape, dtype)?;
+    let itemsize = array.itemsize();
+    let data = unsafe { std::slice::from_raw_parts_mut(array.data_ptr_mut(), values.len() * itemsize) };
+    for (slot, &value) in data.chunks_exact_mut(itemsize).zip(values) {
+        encode_scalar(&Scalar::Float(value), array.dtype(), slot)?;
+    }
+    Ok(array)
+}
+
+/// Return evenly spaced values within `[start, stop)`
+///
+/// Equivalent to `numpy.arange`. The length is `ceil((stop - start) /
+/// step)` evaluated in floating point, as NumPy computes it, so
+/// `arange(1.0, 1.3, 0.1)` has three elements. Element `i` is
+/// `first + i * delta`, where `first` and `delta` are taken from the first
+/// two values after casting to `dtype`.
+pub fn arange(start: f64, stop: f64, step: f64, dtype: DType) -> Result<Array, ArrayError> {
+    if step == 0.0 {
+        return Err(ArrayError::InvalidValue("arange: step must not be zero".to_string()));
+    }
+    let len = ((stop - start) / step).ceil();
+    if len.is_nan() || len >= i64::MAX as f64 {
+        return Err(ArrayError::InvalidValue("arange: maximum allowed size exceeded".to_string()));
+    }
+    let len = len.max(0.0) as usize;
+    let (first, second) = if is_integer(dtype.type_()) {
+        (start.trunc(), (start + step).trunc())
+    } else {
+        (start, start + step)
+    };
+    let delta = second - first;
+    let values: Vec<f64> = (0..len).map(|i| first + i as f64 * delta).collect();
+    from_f64_values(&values, vec![len as i64], dtype)
+}
+
+/// Return evenly spaced integers within `[start, stop)`
+///
+/// `arange` for integer arguments. With an integer `dtype` the values are
+/// computed exactly, where `arange` would round them through `f64` beyond
+/// 2^53; other dtypes get the values `arange` gives.
+pub fn arange_int(start: i64, stop: i64, step: i64, dtype: DType) -> Result<Array, ArrayError> {
+    if !is_integer(dtype.type_()) {
+        return arange(start as f64, stop as f64, step as f64, dtype);
+    }
+    if step == 0 {
+        return Err(ArrayError::InvalidValue("arange: step must not be zero".to_string()));
+    }
+    // ceil((stop - start) / step), in i128 so the span cannot overflow
+    let (start, step) = (start as i128, step as i128);
+    let len = (stop as i128 - start + step - step.signum()) / step;
+    if len >= i64::MAX as i128 {
+        return Err(ArrayError::InvalidValue("arange: maximum allowed size exceeded".to_string()));
+    }
+    let len = len.max(0) as usize;
+    let mut array = Array::new(vec![len as i64], dtype)?;
+    let itemsize = array.itemsize();
+    let data = unsafe { std::slice::from_raw_parts_mut(array.data_ptr_mut(), len * itemsize) };
+    for (i, slot) in data.chunks_exact_mut(itemsize).enumerate() {
+        // Every value lies in [start, stop), so it fits in i64
+        let value = (start + i as i128 * step) as i64;
+        encode_scalar(&Scalar::Int(value), array.dtype(), slot)?;
+    }
+    Ok(array)
+}
+
+/// Return `num` evenly spaced samples over `[start, stop]` and the step
+///
+/// Equivalent to `numpy.linspace(..., retstep=True)`. With `endpoint` the
+/// last sample is exactly `stop`; otherwise `stop` is excluded. The step is
+/// NaN when fewer than two samples define it. Integer dtypes round the
+/// samples towards negative infinity, as NumPy 2 does.
+pub fn linspace_retstep(
+    start: f64,
+    stop: f64,
+    num: usize,
+    endpoint: bool,
+    dtype: DType,
+) -> Result<(Array, f64), ArrayError> {
+    let div = if endpoint { num.saturating_sub(1) } else { num };
+    let delta = stop - start;
+    let mut values: Vec<f64> = (0..num).map(|i| i as f64).collect();
+    let step = if div > 0 {
+        let step = delta / div as f64;
+        if step == 0.0 {
+            values.iter_mut().for_each(|v| *v = *v / div as f64 * delta + start);
+        } else {
+            values.iter_mut().for_each(|v| *v = *v * step + start);
+        }
+        step
+    } else {
+        values.iter_mut().for_each(|v| *v = *v * delta + start);
+        f64::NAN
+    };
+    if endpoint && num > 1 {
+        values[num - 1] = stop;
+    }
+    if is_integer(dtype.type_()) {
+        values.iter_mut().for_each(|v| *v = v.floor());
+    }
+    Ok((from_f64_values(&values, vec![num as i64], dtype)?, step))
+}
+
+/// Return `num` evenly spaced samples over `[start, stop]`
+///
+/// Equivalent to `numpy.linspace`; see `linspace_retstep`.
+pub fn linspace(start: f64, stop: f64, num: usize, endpoint: bool, dtype: DType) -> Result<Array, ArrayError> {
+    Ok(linspace_retstep(start, stop, num, endpoint, dtype)?.0)
+}
+
+/// Return numbers spaced evenly on a log scale
+///
+/// Equivalent to `numpy.logspace`: the samples are `base ** linspace(start,
+/// stop, num, endpoint)`.
+pub fn logspace(
+    start: f64,
+    stop: f64,
+    num: usize,
+    endpoint: bool,
+    base: f64,
+    dtype: DType,
+) -> Result<Array, ArrayError> {
+    let exponents = linspace(start, stop, num, endpoint, DType::new(NpyType::Double))?;
+    let values: Vec<f64> = unsafe { exponents.as_slice::<f64>() }.iter().map(|&e| base.powf(e)).collect();
+    from_f64_values(&values, vec![num as i64], dtype)
+}
+
+/// Return numbers spaced evenly on a log scale between two endpoints
+///
+/// Equivalent to `numpy.geomspace` for real inputs: each sample is a
+/// constant multiple of the previous one, and the endpoints are returned
+/// exactly. `start` and `stop` must be nonzero and of the same sign.
+pub fn geomspace(start: f64, stop: f64, num: usize, endpoint: bool, dtype: DType) -> Result<Array, ArrayError> {
+    if start == 0.0 || stop == 0.0 {
+        return Err(ArrayError::InvalidValue("geomspace: endpoints cannot be zero".to_string()));
+    }
+    if start.signum() != stop.signum() {
+        return Err(ArrayError::InvalidValue("geomspace: endpoints must have the same sign".to_string()));
+    }
+    let sign = start.signum();
+    let exponents = linspace(
+        (start * sign).log10(),
+        (stop * sign).log10(),
+        num,
+        endpoint,
+        DType::new(NpyType::Double),
+    )?;
+    let mut values: Vec<f64> = unsafe { exponents.as_slice::<f64>() }
+        .iter()
+        .map(|&e| sign * 10f64.powf(e))
+        .collect();
+    if num > 0 {
+        values[0] = start;
+        if endpoint && num > 1 {
+            values[num - 1] = stop;
+        }
+    }
+    if is_integer(dtype.type_()) {
+        values.iter_mut().for_each(|v| *v = v.floor());
+    }
+    from_f64_values(&values, vec![num as i64], dtype)
+}
+
+/// Output indexing convention for `meshgrid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshIndexing {
+    /// Cartesian indexing: the first two output axes are swapped
+    Xy,
+    /// Matrix indexing: output axis `i` follows input `i`
+    Ij,
+}
+
+/// Return coordinate matrices from coordinate vectors
+///
+/// Equivalent to `numpy.meshgrid`. Inputs are flattened; output `i` keeps
+/// the dtype of input `i`. With `sparse`, each output has length 1 on every
+/// axis but its own, ready for broadcasting.
+pub fn meshgrid(xi: &[&Array], indexing: MeshIndexing, sparse: bool) -> Result<Vec<Array>, ArrayError> {
+    let ndim = xi.len();
+    let lengths: Vec<i64> = xi.iter().map(|x| x.size() as i64).collect();
+    let axis_of = |i: usize| match (indexing, i) {
+        (MeshIndexing::Xy, 0) if ndim > 1 => 1,
+        (MeshIndexing::Xy, 1) => 0,
+        _ => i,
+    };
+    let mut full_shape = vec![0i64; ndim];
+    for (i, &len) in lengths.iter().enumerate() {
+        full_shape[axis_of(i)] = len;
+    }
+
+    let mut outputs = Vec::with_capacity(ndim);
+    for (i, x) in xi.iter().enumerate() {
+        let axis = axis_of(i);
+        let bytes = to_contiguous_bytes(x);
+        let itemsize = x.itemsize();
+        let shape = if sparse {
+            let mut shape = vec![1i64; ndim];
+            shape[axis] = lengths[i];
+            shape
+        } else {
+            full_shape.clone()
+        };
+        // Elements repeat in runs of `inner` and the pattern tiles `outer` times
+        let inner: i64 = shape[axis + 1..].iter().product();
+        let outer: i64 = shape[..axis].iter().product();
+        let mut data = Vec::with_capacity((outer * lengths[i] * inner).max(0) as usize * itemsize);
+        for _ in 0..outer {
+            for element in bytes.chunks_exact(itemsize) {
+                for _ in 0..inner {
+                    data.extend_from_slice(element);
+                }
+            }
+        }
+        let mut array = Array::new(shape, x.dtype().clone())?;
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), array.data_ptr_mut(), data.len());
+        }
+        outputs.push(array);
+    }
+    Ok(outputs)
+}
+
+/// Spacing of one `GridRange`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridStep {
+    /// Fixed step; `stop` is excluded (NumPy's `start:stop:step`)
+    Step(f64),
+    /// Number of points; `stop` is included (NumPy's `start:stop:numj`)
+    Num(usize),
+}
+
+/// One axis of an `mgrid`/`ogrid` specification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridRange {
+    /// First value
+    pub start: f64,
+    /// End value
+    pub stop: f64,
+    /// Step or number of points
+    pub step: GridStep,
+}
+
+impl GridRange {
+    /// Range from `start` to `stop` (exclusive) in steps of `step`
+    pub fn new(start: f64, stop: f64, step: f64) -> Self {
+        GridRange { start, stop, step: GridStep::Step(step) }
+    }
+
+    /// `num` points from `start` to `stop` inclusive
+    pub fn num(start: f64, stop: f64, num: usize) -> Self {
+        GridRange { start, stop, step: GridStep::Num(num) }
+    }
+
+    fn is_integral(&self) -> bool {
+        match self.step {
+            GridStep::Step(step) => [self.start, self.stop, step].iter().all(|v| v.fract() == 0.0),
+            GridStep::Num(_) => false,
+        }
+    }
+
+    fn values(&self) -> Result<Vec<f64>, ArrayError> {
+        match self.step {
+            GridStep::Step(step) => {
+                if step == 0.0 {
+                    return Err(ArrayError::InvalidValue("grid step must not be zero".to_string()));
+                }
+                let len = ((self.stop - self.start) / step).ceil().max(0.0) as usize;
+                Ok((0..len).map(|i| self.start + i as f64 * step).collect())
+            }
+            GridStep::Num(num) => {
+                let step = if num > 1 { (self.stop - self.start) / (num - 1) as f64 } else { 0.0 };
+                Ok((0..num).map(|i| self.start + i as f64 * step).collect())
+            }
+        }
+    }
+}
+
+/// Grid dtype: `Long` when every range is integral, `Double` otherwise
+fn grid_dtype(ranges: &[GridRange]) -> DType {
+    if ranges.iter().all(GridRange::is_integral) {
+        DType::new(NpyType::Long)
+    } else {
+        DType::new(NpyType::Double)
+    }
+}
+
+/// Dense multi-dimensional mesh grid
+///
+/// Equivalent to `numpy.mgrid[...]`. With several ranges the result has
+/// shape `(len(ranges), n0, n1, ...)`; a single range gives a 1-D array.
+pub fn mgrid(ranges: &[GridRange]) -> Result<Array, ArrayError> {
+    let dtype = grid_dtype(ranges);
+    let axes = ranges.iter().map(GridRange::values).collect::<Result<Vec<_>, _>>()?;
+    if let [values] = axes.as_slice() {
+        return from_f64_values(values, vec![values.len() as i64], dtype);
+    }
+    let lengths: Vec<i64> = axes.iter().map(|v| v.len() as i64).collect();
+    let plane: i64 = lengths.iter().product();
+    let mut data = Vec::with_capacity(axes.len() * plane as usize);
+    for (axis, values) in axes.iter().enumerate() {
+        let inner: i64 = lengths[axis + 1..].iter().product();
+        for flat in 0..plane {
+            data.push(values[((flat / inner) % lengths[axis]) as usize]);
+        }
+    }
+    let mut shape = vec![axes.len() as i64];
+    shape.extend(&lengths);
+    from_f64_values(&data, shape, dtype)
+}
+
+/// Open (sparse) multi-dimensional mesh grid
+///
+/// Equivalent to `numpy.ogrid[...]`: output `i` has the values of range `i`
+/// along axis `i` and length 1 on every other axis. A single range gives
+/// one 1-D array.
+pub fn ogrid(ranges: &[GridRange]) -> Result<Vec<Array>, ArrayError> {
+    let dtype = grid_dtype(ranges);
+    let ndim = ranges.len();
+    ranges
+        .iter()
+        .enumerate()
+        .map(|(axis, range)| {
+            let values = range.values()?;
+            let mut shape = vec![1i64; ndim];
+            shape[axis] = values.len() as i64;
+            from_f64_values(&values, shape, dtype.clone())
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+//! Creation and conversion helpers of the array API standard
+//!
+//! Python scalars mixed with arrays are "weak": they take the dtype of the
+//! array when it has the same or a higher kind, and the namespace's default
+//! dtype of their own kind otherwise.
+
+use crate::array::{Array, Scalar};
+use crate::types::{DType, NpyType};
+
+use super::values::Values;
+use super::{canonical_type, default_dtypes, dtype_kind, iinfo, ArrayApiError, DTypeKind};
+
+/// Kind of a Python scalar
+fn scalar_kind(value: &Scalar) -> Result<DTypeKind, ArrayApiError> {
+    Ok(match value {
+        Scalar::Bool(_) => DTypeKind::Bool,
+        Scalar::Int(_) => DTypeKind::SignedInteger,
+        Scalar::UInt(_) => DTypeKind::UnsignedInteger,
+        Scalar::Float(_) => DTypeKind::RealFloating,
+        Scalar::Complex(_) => DTypeKind::ComplexFloating,
+        Scalar::Str(s) => return Err(ArrayApiError::InvalidArgument(format!("cannot convert '{}' to a number", s))),
+    })
+}
+
+/// Dtype a Python scalar takes when combined with an array of `like`
+pub fn scalar_dtype(value: &Scalar, like: &DType) -> Result<DType, ArrayApiError> {
+    let array_kind = dtype_kind(like)?;
+    let like = DType::new(canonical_type(like.type_()));
+    let scalar_kind = match scalar_kind(value)? {
+        DTypeKind::UnsignedInteger => DTypeKind::SignedInteger,
+        kind => kind,
+    };
+    let defaults = default_dtypes();
+    let dtype = match (scalar_kind, array_kind) {
+        (DTypeKind::Bool, _) => like,
+        (DTypeKind::SignedInteger, kind) if kind != DTypeKind::Bool => like,
+        (DTypeKind::SignedInteger, _) => defaults.integral,
+        (DTypeKind::RealFloating, kind) if kind.is_floating() => like,
+        (DTypeKind::RealFloating, _) => defaults.real_floating,
+        (DTypeKind::ComplexFloating, DTypeKind::ComplexFloating) => like,
+        (DTypeKind::ComplexFloating, DTypeKind::RealFloating) if like.type_() == NpyType::Float => {
+            DType::new(NpyType::CFloat)
+        }
+        (DTypeKind::ComplexFloating, _) => defaults.complex_floating,
+        (DTypeKind::UnsignedInteger, _) => unreachable!("unsigned scalars are treated as integers"),
+    };
+    // Integers must be representable in the array's integer dtype
+    if let (Some(value), Ok(info)) = (scalar_integer(value), iinfo(&dtype)) {
+        if value < info.min || value > info.max {
+            return Err(ArrayApiError::InvalidArgument(format!(
+                "Python integer {} out of bounds for {}",
+                value,
+                dtype.name()
+            )));
+        }
+    }
+    Ok(dtype)
+}
+
+fn scalar_integer(value: &Scalar) -> Option<i128> {
+    match value {
+        Scalar::Int(i) => Some(*i as i128),
+        Scalar::UInt(u) => Some(*u as i128),
+        _ => None,
+    }
+}
+
+/// Zero-dimensional array of a Python scalar combined with an array of
+/// `like`
+pub fn scalar_array(value: &Scalar, like: &DType) -> Result<Array, ArrayApiError> {
+    let dtype = scalar_dtype(value, like)?;
+    Values::from_scalars(std::slice::from_ref(value), dtype_kind(&dtype)?)?.into_array(vec![], dtype)
+}
+
+/// Array of `shape` holding `values` in C order
+///
+/// Without `dtype` the result is `bool` if every value is a boolean, the
+/// default integer dtype if they are integers (`uint64` if one only fits
+/// there), and the default real or complex floating dtype otherwise.
+pub fn asarray_scalars(values: &[Scalar], shape: Vec<i64>, dtype: Option<&DType>) -> Result<Array, ArrayApiError> {
+    if shape.iter().product::<i64>() != values.len() as i64 {
+        return Err(ArrayApiError::InvalidArgument(format!(
+            "{} values do not fill shape {:?}",
+            values.len(),
+            shape
+        )));
+    }
+    let dtype = match dtype {
+        Some(dtype) => {
+            dtype_kind(dtype)?;
+            DType::new(canonical_type(dtype.type_()))
+        }
+        None => {
+            let kinds = values.iter().map(scalar_kind).collect::<Result<Vec<_>, _>>()?;
+            let defaults = default_dtypes();
+            match kinds.iter().copied().max() {
+                None => defaults.real_floating,
+                Some(DTypeKind::Bool) => DType::new(NpyType::Bool),
+                Some(DTypeKind::UnsignedInteger) if !kinds.contains(&DTypeKind::SignedInteger) => {
+                    DType::new(NpyType::ULongLong)
+                }
+                Some(DTypeKind::SignedInteger | DTypeKind::UnsignedInteger) => {
+                    if kinds.contains(&DTypeKind::UnsignedInteger) {
+                        return Err(ArrayApiError::InvalidArgument(
+                            "integers do not fit in a common integer dtype".to_string(),
+                        ));
+                    }
+                    defaults.integral
+                }
+                Some(DTypeKind::RealFloating) => defaults.real_floating,
+                Some(DTypeKind::ComplexFloating) => defaults.complex_floating,
+            }
+        }
+    };
+    Values::from_scalars(values, dtype_kind(&dtype)?)?.into_array(shape, dtype)
+}
+
+/// Copy of `x` cast to `dtype`
+///
+/// Casting follows NumPy: integers wrap, floats truncate toward zero and
+/// complex values lose their imaginary part when cast to real dtypes.
+pub fn astype(x: &Array, dtype: &DType) -> Result<Array, ArrayApiError> {
+    let kind = dtype_kind(dtype)?;
+    Values::read(x)?.convert(kind).into_array(x.shape().to_vec(), DType::new(canonical_type(dtype.type_())))
+}
@@ -0,0 +1,309 @@
+//! Data types of the array API standard
+//!
+//! Promotion follows the standard's table within each kind. Mixed-kind
+//! promotion, which the standard leaves to implementations, follows NumPy:
+//! booleans promote to the other type, and integers mixed with floating
+//! types promote to the narrowest floating type that holds them exactly.
+
+use crate::types::{DType, NpyType};
+
+use super::ArrayApiError;
+
+/// Kind of an array API dtype
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DTypeKind {
+    /// `bool`
+    Bool,
+    /// `int8`, `int16`, `int32` and `int64`
+    SignedInteger,
+    /// `uint8`, `uint16`, `uint32` and `uint64`
+    UnsignedInteger,
+    /// `float32` and `float64`
+    RealFloating,
+    /// `complex64` and `complex128`
+    ComplexFloating,
+}
+
+impl DTypeKind {
+    /// Whether this is a signed or unsigned integer kind
+    pub fn is_integral(self) -> bool {
+        matches!(self, DTypeKind::SignedInteger | DTypeKind::UnsignedInteger)
+    }
+
+    /// Whether this is a real or complex floating kind
+    pub fn is_floating(self) -> bool {
+        matches!(self, DTypeKind::RealFloating | DTypeKind::ComplexFloating)
+    }
+
+    /// Whether this is any kind but `Bool`
+    pub fn is_numeric(self) -> bool {
+        self != DTypeKind::Bool
+    }
+}
+
+/// Kind of `ty`, or `None` if it is not an array API type
+pub fn type_kind(ty: NpyType) -> Option<DTypeKind> {
+    match ty {
+        NpyType::Bool => Some(DTypeKind::Bool),
+        NpyType::Byte | NpyType::Short | NpyType::Int | NpyType::Long | NpyType::LongLong => {
+            Some(DTypeKind::SignedInteger)
+        }
+        NpyType::UByte | NpyType::UShort | NpyType::UInt | NpyType::ULong | NpyType::ULongLong => {
+            Some(DTypeKind::UnsignedInteger)
+        }
+        NpyType::Float | NpyType::Double => Some(DTypeKind::RealFloating),
+        NpyType::CFloat | NpyType::CDouble => Some(DTypeKind::ComplexFloating),
+        _ => None,
+    }
+}
+
+/// Kind of `dtype`, failing for dtypes outside the standard
+pub fn dtype_kind(dtype: &DType) -> Result<DTypeKind, ArrayApiError> {
+    if dtype.custom_type_id().is_some() || dtype.fields().is_some() {
+        return Err(ArrayApiError::UnsupportedDtype(dtype.name().to_string()));
+    }
+    type_kind(dtype.type_()).ok_or_else(|| ArrayApiError::UnsupportedDtype(dtype.name().to_string()))
+}
+
+/// Width of `ty` in bits (both parts together for complex types)
+fn type_bits(ty: NpyType) -> usize {
+    DType::new(ty).itemsize() * 8
+}
+
+/// The array API type of a kind and width
+fn type_of(kind: DTypeKind, bits: usize) -> NpyType {
+    match (kind, bits) {
+        (DTypeKind::Bool, _) => NpyType::Bool,
+        (DTypeKind::SignedInteger, 8) => NpyType::Byte,
+        (DTypeKind::SignedInteger, 16) => NpyType::Short,
+        (DTypeKind::SignedInteger, 32) => NpyType::Int,
+        (DTypeKind::SignedInteger, _) => NpyType::LongLong,
+        (DTypeKind::UnsignedInteger, 8) => NpyType::UByte,
+        (DTypeKind::UnsignedInteger, 16) => NpyType::UShort,
+        (DTypeKind::UnsignedInteger, 32) => NpyType::UInt,
+        (DTypeKind::UnsignedInteger, _) => NpyType::ULongLong,
+        (DTypeKind::RealFloating, 32) => NpyType::Float,
+        (DTypeKind::RealFloating, _) => NpyType::Double,
+        (DTypeKind::ComplexFloating, 64) => NpyType::CFloat,
+        (DTypeKind::ComplexFloating, _) => NpyType::CDouble,
+    }
+}
+
+/// `ty` with the platform-dependent 64-bit integers replaced by `int64`
+/// and `uint64`
+pub(crate) fn canonical_type(ty: NpyType) -> NpyType {
+    match ty {
+        NpyType::Long => NpyType::LongLong,
+        NpyType::ULong => NpyType::ULongLong,
+        other => other,
+    }
+}
+
+/// Promote two array API types
+pub fn promote_api_types(a: NpyType, b: NpyType) -> Result<NpyType, ArrayApiError> {
+    let no_common = || ArrayApiError::NoCommonType(DType::new(a).name().to_string(), DType::new(b).name().to_string());
+    let kind_a = type_kind(a).ok_or_else(no_common)?;
+    let kind_b = type_kind(b).ok_or_else(no_common)?;
+    let (a, b) = (canonical_type(a), canonical_type(b));
+    if a == b {
+        return Ok(a);
+    }
+    let (bits_a, bits_b) = (type_bits(a), type_bits(b));
+    // Order the pair so that `low` has the lower kind
+    let ((low, low_bits), (high, high_bits)) =
+        if kind_a <= kind_b { ((kind_a, bits_a), (kind_b, bits_b)) } else { ((kind_b, bits_b), (kind_a, bits_a)) };
+
+    Ok(match (low, high) {
+        (DTypeKind::Bool, _) => type_of(high, high_bits),
+        (l, h) if l == h => type_of(l, low_bits.max(high_bits)),
+        (DTypeKind::SignedInteger, DTypeKind::UnsignedInteger) => {
+            let (signed_bits, unsigned_bits) = (low_bits, high_bits);
+            if signed_bits > unsigned_bits {
+                type_of(DTypeKind::SignedInteger, signed_bits)
+            } else if unsigned_bits < 64 {
+                type_of(DTypeKind::SignedInteger, unsigned_bits * 2)
+            } else {
+                // No integer type holds both int64 and uint64
+                NpyType::Double
+            }
+        }
+        (l, DTypeKind::RealFloating) if l.is_integral() => {
+            let float_bits = if low_bits <= 16 { high_bits } else { 64 };
+            type_of(DTypeKind::RealFloating, float_bits)
+        }
+        (l, DTypeKind::ComplexFloating) if l.is_integral() => {
+            let complex_bits = if low_bits <= 16 { high_bits } else { 128 };
+            type_of(DTypeKind::ComplexFloating, complex_bits)
+        }
+        (DTypeKind::RealFloating, DTypeKind::ComplexFloating) => {
+            type_of(DTypeKind::ComplexFloating, (low_bits * 2).max(high_bits))
+        }
+        _ => return Err(no_common()),
+    })
+}
+
+/// Common dtype of `dtypes` under the promotion rules
+///
+/// Equivalent to the standard's `result_type`.
+pub fn result_type(dtypes: &[DType]) -> Result<DType, ArrayApiError> {
+    let (first, rest) = dtypes
+        .split_first()
+        .ok_or_else(|| ArrayApiError::InvalidArgument("at least one dtype is required".to_string()))?;
+    dtype_kind(first)?;
+    let mut ty = canonical_type(first.type_());
+    for dtype in rest {
+        dtype_kind(dtype)?;
+        ty = promote_api_types(ty, dtype.type_())?;
+    }
+    Ok(DType::new(ty))
+}
+
+/// Whether `from` can be cast to `to` without changing values, i.e. whether
+/// promoting the two gives `to`
+pub fn can_cast(from: &DType, to: &DType) -> Result<bool, ArrayApiError> {
+    dtype_kind(from)?;
+    dtype_kind(to)?;
+    Ok(match promote_api_types(from.type_(), to.type_()) {
+        Ok(ty) => ty == canonical_type(to.type_()),
+        Err(_) => false,
+    })
+}
+
+/// Whether `dtype` belongs to the named kind
+///
+/// `kind` is one of `"bool"`, `"signed integer"`, `"unsigned integer"`,
+/// `"integral"`, `"real floating"`, `"complex floating"` or `"numeric"`.
+pub fn isdtype(dtype: &DType, kind: &str) -> Result<bool, ArrayApiError> {
+    let Ok(actual) = dtype_kind(dtype) else {
+        return Ok(false);
+    };
+    Ok(match kind {
+        "bool" => actual == DTypeKind::Bool,
+        "signed integer" => actual == DTypeKind::SignedInteger,
+        "unsigned integer" => actual == DTypeKind::UnsignedInteger,
+        "integral" => actual.is_integral(),
+        "real floating" => actual == DTypeKind::RealFloating,
+        "complex floating" => actual == DTypeKind::ComplexFloating,
+        "numeric" => actual.is_numeric(),
+        other => return Err(ArrayApiError::InvalidArgument(format!("unknown dtype kind '{}'", other))),
+    })
+}
+
+/// Every array API dtype, in the standard's order
+pub fn api_dtypes() -> Vec<DType> {
+    [
+        NpyType::Bool,
+        NpyType::Byte,
+        NpyType::Short,
+        NpyType::Int,
+        NpyType::LongLong,
+        NpyType::UByte,
+        NpyType::UShort,
+        NpyType::UInt,
+        NpyType::ULongLong,
+        NpyType::Float,
+        NpyType::Double,
+        NpyType::CFloat,
+        NpyType::CDouble,
+    ]
+    .into_iter()
+    .map(DType::new)
+    .collect()
+}
+
+/// Default dtypes of the namespace
+#[derive(Debug, Clone)]
+pub struct DefaultDTypes {
+    /// Default real floating dtype (`float64`)
+    pub real_floating: DType,
+    /// Default complex floating dtype (`complex128`)
+    pub complex_floating: DType,
+    /// Default integral dtype (`int64`)
+    pub integral: DType,
+    /// Default dtype of index arrays (`int64`)
+    pub indexing: DType,
+}
+
+/// Default dtypes of the namespace
+pub fn default_dtypes() -> DefaultDTypes {
+    DefaultDTypes {
+        real_floating: DType::new(NpyType::Double),
+        complex_floating: DType::new(NpyType::CDouble),
+        integral: DType::new(NpyType::LongLong),
+        indexing: DType::new(NpyType::LongLong),
+    }
+}
+
+/// Machine limits of a floating dtype, as returned by `finfo`
+#[derive(Debug, Clone)]
+pub struct FloatInfo {
+    /// Number of bits
+    pub bits: usize,
+    /// Difference between 1.0 and the next larger value
+    pub eps: f64,
+    /// Largest finite value
+    pub max: f64,
+    /// Smallest finite value
+    pub min: f64,
+    /// Smallest positive normal value
+    pub smallest_normal: f64,
+    /// Real floating dtype described
+    pub dtype: DType,
+}
+
+/// Machine limits of a floating dtype
+///
+/// Complex dtypes describe their real component.
+pub fn finfo(dtype: &DType) -> Result<FloatInfo, ArrayApiError> {
+    match dtype_kind(dtype)? {
+        DTypeKind::RealFloating | DTypeKind::ComplexFloating => {}
+        _ => return Err(ArrayApiError::UnsupportedDtype(format!("finfo of {}", dtype.name()))),
+    }
+    Ok(match dtype.type_() {
+        NpyType::Float | NpyType::CFloat => FloatInfo {
+            bits: 32,
+            eps: f32::EPSILON as f64,
+            max: f32::MAX as f64,
+            min: f32::MIN as f64,
+            smallest_normal: f32::MIN_POSITIVE as f64,
+            dtype: DType::new(NpyType::Float),
+        },
+        _ => FloatInfo {
+            bits: 64,
+            eps: f64::EPSILON,
+            max: f64::MAX,
+            min: f64::MIN,
+            smallest_normal: f64::MIN_POSITIVE,
+            dtype: DType::new(NpyType::Double),
+        },
+    })
+}
+
+/// Machine limits of an integer dtype, as returned by `iinfo`
+#[derive(Debug, Clone)]
+pub struct IntInfo {
+    /// Number of bits
+    pub bits: usize,
+    /// Largest value
+    pub max: i128,
+    /// Smallest value
+    pub min: i128,
+    /// Integer dtype described
+    pub dtype: DType,
+}
+
+/// Machine limits of an integer dtype
+pub fn iinfo(dtype: &DType) -> Result<IntInfo, ArrayApiError> {
+    let bits = dtype.itemsize() * 8;
+    let (min, max) = match dtype_kind(dtype)? {
+        DTypeKind::SignedInteger => (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1),
+        DTypeKind::UnsignedInteger => (0, (1i128 << bits) - 1),
+        _ => return Err(ArrayApiError::UnsupportedDtype(format!("iinfo of {}", dtype.name()))),
+    };
+    Ok(IntInfo {
+        bits,
+        max,
+        min,
+        dtype: DType::new(canonical_type(dtype.type_())),
+    })
+}
@@ -0,0 +1,755 @@
+//! Element-wise functions of the array API standard
+//!
+//! Binary functions broadcast their inputs and compute in the promoted
+//! dtype. Functions defined on floating values (`divide`, `sqrt`, `exp`,
+//! ...) compute integer and boolean inputs in the default floating dtype,
+//! as NumPy does. Integer arithmetic wraps on overflow and integer division
+//! by zero gives zero.
+
+use crate::array::Array;
+use crate::types::{Complex128, DType, NpyType};
+
+use super::values::{broadcast_shape, Values};
+use super::{canonical_type, dtype_kind, promote_api_types, ArrayApiError, DTypeKind};
+
+macro_rules! named_ops {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident => $text:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)*
+        }
+
+        impl $name {
+            /// Every operation, in alphabetical order of name
+            pub const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            /// Operation with the standard's function name
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($text => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// The standard's function name
+            pub fn name(self) -> &'static str {
+                match self {
+                    $($name::$variant => $text,)*
+                }
+            }
+        }
+    };
+}
+
+named_ops! {
+    /// Element-wise functions of one array
+    pub enum UnaryOp {
+        /// Absolute value (magnitude of complex values)
+        Abs => "abs",
+        /// Inverse cosine
+        Acos => "acos",
+        /// Inverse hyperbolic cosine
+        Acosh => "acosh",
+        /// Inverse sine
+        Asin => "asin",
+        /// Inverse hyperbolic sine
+        Asinh => "asinh",
+        /// Inverse tangent
+        Atan => "atan",
+        /// Inverse hyperbolic tangent
+        Atanh => "atanh",
+        /// Bitwise NOT of integers, logical NOT of booleans
+        BitwiseInvert => "bitwise_invert",
+        /// Round toward positive infinity
+        Ceil => "ceil",
+        /// Complex conjugate
+        Conj => "conj",
+        /// Cosine
+        Cos => "cos",
+        /// Hyperbolic cosine
+        Cosh => "cosh",
+        /// Exponential
+        Exp => "exp",
+        /// `exp(x) - 1`, accurate near zero
+        Expm1 => "expm1",
+        /// Round toward negative infinity
+        Floor => "floor",
+        /// Imaginary component
+        Imag => "imag",
+        /// Whether values are finite
+        IsFinite => "isfinite",
+        /// Whether values are infinite
+        IsInf => "isinf",
+        /// Whether values are NaN
+        IsNan => "isnan",
+        /// Natural logarithm
+        Log => "log",
+        /// `log(1 + x)`, accurate near zero
+        Log1p => "log1p",
+        /// Base-2 logarithm
+        Log2 => "log2",
+        /// Base-10 logarithm
+        Log10 => "log10",
+        /// Logical NOT
+        LogicalNot => "logical_not",
+        /// Numerical negative
+        Negative => "negative",
+        /// Numerical positive (a copy)
+        Positive => "positive",
+        /// Real component
+        Real => "real",
+        /// Round half to even
+        Round => "round",
+        /// Sign (`x / |x|` for complex values)
+        Sign => "sign",
+        /// Whether the sign bit is set
+        Signbit => "signbit",
+        /// Sine
+        Sin => "sin",
+        /// Hyperbolic sine
+        Sinh => "sinh",
+        /// Square
+        Square => "square",
+        /// Square root
+        Sqrt => "sqrt",
+        /// Tangent
+        Tan => "tan",
+        /// Hyperbolic tangent
+        Tanh => "tanh",
+        /// Round toward zero
+        Trunc => "trunc",
+    }
+}
+
+named_ops! {
+    /// Element-wise functions of two arrays
+    pub enum BinaryOp {
+        /// Addition
+        Add => "add",
+        /// Quadrant-aware inverse tangent of `x1 / x2`
+        Atan2 => "atan2",
+        /// Bitwise AND
+        BitwiseAnd => "bitwise_and",
+        /// Shift bits left
+        BitwiseLeftShift => "bitwise_left_shift",
+        /// Bitwise OR
+        BitwiseOr => "bitwise_or",
+        /// Shift bits right
+        BitwiseRightShift => "bitwise_right_shift",
+        /// Bitwise XOR
+        BitwiseXor => "bitwise_xor",
+        /// Magnitude of `x1` with the sign of `x2`
+        Copysign => "copysign",
+        /// True division
+        Divide => "divide",
+        /// Equality
+        Equal => "equal",
+        /// Division rounded toward negative infinity
+        FloorDivide => "floor_divide",
+        /// `x1 > x2`
+        Greater => "greater",
+        /// `x1 >= x2`
+        GreaterEqual => "greater_equal",
+        /// `sqrt(x1² + x2²)` without overflow
+        Hypot => "hypot",
+        /// `x1 < x2`
+        Less => "less",
+        /// `x1 <= x2`
+        LessEqual => "less_equal",
+        /// `log(exp(x1) + exp(x2))`
+        LogAddExp => "logaddexp",
+        /// Logical AND
+        LogicalAnd => "logical_and",
+        /// Logical OR
+        LogicalOr => "logical_or",
+        /// Logical XOR
+        LogicalXor => "logical_xor",
+        /// Larger value, propagating NaNs
+        Maximum => "maximum",
+        /// Smaller value, propagating NaNs
+        Minimum => "minimum",
+        /// Multiplication
+        Multiply => "multiply",
+        /// Inequality
+        NotEqual => "not_equal",
+        /// `x1` raised to `x2`
+        Pow => "pow",
+        /// Remainder with the sign of the divisor
+        Remainder => "remainder",
+        /// Subtraction
+        Subtract => "subtract",
+    }
+}
+
+fn unsupported(op: &str, dtype: &DType) -> ArrayApiError {
+    ArrayApiError::UnsupportedDtype(format!("{} is not defined for {}", op, dtype.name()))
+}
+
+/// Floating dtype a floating function computes `dtype` in
+fn floating_dtype(dtype: &DType, kind: DTypeKind) -> DType {
+    if kind.is_floating() {
+        DType::new(canonical_type(dtype.type_()))
+    } else {
+        DType::new(NpyType::Double)
+    }
+}
+
+/// Real dtype of the components of a complex dtype
+fn component_dtype(dtype: &DType) -> DType {
+    match dtype.type_() {
+        NpyType::CFloat => DType::new(NpyType::Float),
+        NpyType::CDouble => DType::new(NpyType::Double),
+        other => DType::new(canonical_type(other)),
+    }
+}
+
+/// Apply an element-wise function of one array
+pub fn unary(op: UnaryOp, x: &Array) -> Result<Array, ArrayApiError> {
+    use UnaryOp::*;
+    let kind = dtype_kind(x.dtype())?;
+    let dtype = DType::new(canonical_type(x.dtype().type_()));
+    let shape = x.shape().to_vec();
+    let values = Values::read(x)?;
+    let bool_dtype = DType::new(NpyType::Bool);
+
+    macro_rules! map {
+        ($variant:ident, $v:expr, $f:expr) => {
+            Values::$variant($v.into_iter().map($f).collect())
+        };
+    }
+
+    match op {
+        Acos | Acosh | Asin | Asinh | Atan | Atanh | Cos | Cosh | Exp | Expm1 | Log | Log1p | Log2 | Log10 | Sin
+        | Sinh | Sqrt | Tan | Tanh => {
+            let out_dtype = floating_dtype(&dtype, kind);
+            let result = match values.convert(dtype_kind(&out_dtype)?) {
+                Values::Float(v) => map!(Float, v, |x| real_function(op, x)),
+                Values::Complex(v) => map!(Complex, v, |z| complex_function(op, z)),
+                _ => unreachable!(),
+            };
+            result.into_array(shape, out_dtype)
+        }
+        Abs => match values {
+            Values::Int(v) => map!(Int, v, |x: i64| x.wrapping_abs()),
+            Values::Float(v) => map!(Float, v, f64::abs),
+            Values::Complex(v) => return map!(Float, v, |z: Complex128| z.abs()).into_array(shape, component_dtype(&dtype)),
+            other => other,
+        }
+        .into_array(shape, dtype),
+        Positive => {
+            if kind == DTypeKind::Bool {
+                return Err(unsupported(op.name(), &dtype));
+            }
+            values.into_array(shape, dtype)
+        }
+        Negative => match values {
+            Values::Int(v) => map!(Int, v, |x: i64| x.wrapping_neg()),
+            Values::UInt(v) => map!(UInt, v, |x: u64| x.wrapping_neg()),
+            Values::Float(v) => map!(Float, v, |x: f64| -x),
+            Values::Complex(v) => map!(Complex, v, |z: Complex128| -z),
+            Values::Bool(_) => return Err(unsupported(op.name(), &dtype)),
+        }
+        .into_array(shape, dtype),
+        Square => match values {
+            Values::Int(v) => map!(Int, v, |x: i64| x.wrapping_mul(x)),
+            Values::UInt(v) => map!(UInt, v, |x: u64| x.wrapping_mul(x)),
+            Values::Float(v) => map!(Float, v, |x: f64| x * x),
+            Values::Complex(v) => map!(Complex, v, |z: Complex128| z * z),
+            Values::Bool(_) => return Err(unsupported(op.name(), &dtype)),
+        }
+        .into_array(shape, dtype),
+        Sign => match values {
+            Values::Int(v) => map!(Int, v, i64::signum),
+            Values::UInt(v) => map!(UInt, v, |x: u64| (x != 0) as u64),
+            Values::Float(v) => map!(Float, v, |x: f64| if x.is_nan() || x == 0.0 { x } else { x.signum() }),
+            Values::Complex(v) => map!(Complex, v, |z: Complex128| {
+                let r = z.abs();
+                if r == 0.0 { Complex128::new(0.0, 0.0) } else { Complex128::new(z.re / r, z.im / r) }
+            }),
+            Values::Bool(_) => return Err(unsupported(op.name(), &dtype)),
+        }
+        .into_array(shape, dtype),
+        Ceil | Floor | Round | Trunc => {
+            let round = |x: f64| match op {
+                Ceil => x.ceil(),
+                Floor => x.floor(),
+                Round => x.round_ties_even(),
+                _ => x.trunc(),
+            };
+            match values {
+                Values::Float(v) => map!(Float, v, round),
+                Values::Complex(v) if op == Round => map!(Complex, v, |z: Complex128| Complex128::new(round(z.re), round(z.im))),
+                Values::Complex(_) => return Err(unsupported(op.name(), &dtype)),
+                // Integers and booleans are already whole
+                other => other,
+            }
+            .into_array(shape, dtype)
+        }
+        Conj => match values {
+            Values::Complex(v) => map!(Complex, v, |z: Complex128| z.conj()),
+            other => other,
+        }
+        .into_array(shape, dtype),
+        Real => match values {
+            Values::Complex(v) => map!(Float, v, |z: Complex128| z.re).into_array(shape, component_dtype(&dtype)),
+            other => other.into_array(shape, dtype),
+        },
+        Imag => match values {
+            Values::Complex(v) => map!(Float, v, |z: Complex128| z.im).into_array(shape, component_dtype(&dtype)),
+            other => Values::Bool(vec![false; other.len()]).into_array(shape, dtype),
+        },
+        IsFinite | IsInf | IsNan => {
+            let test = |x: f64| match op {
+                IsFinite => x.is_finite(),
+                IsInf => x.is_infinite(),
+                _ => x.is_nan(),
+            };
+            match values {
+                Values::Float(v) => map!(Bool, v, test),
+                Values::Complex(v) => map!(Bool, v, |z: Complex128| match op {
+                    IsFinite => z.re.is_finite() && z.im.is_finite(),
+                    _ => test(z.re) || test(z.im),
+                }),
+                other => Values::Bool(vec![op == IsFinite; other.len()]),
+            }
+            .into_array(shape, bool_dtype)
+        }
+        Signbit => match values {
+            Values::Float(v) => map!(Bool, v, |x: f64| x.is_sign_negative()),
+            Values::Int(v) => map!(Bool, v, |x: i64| x < 0),
+            Values::Complex(_) => return Err(unsupported(op.name(), &dtype)),
+            other => Values::Bool(vec![false; other.len()]),
+        }
+        .into_array(shape, bool_dtype),
+        LogicalNot => match values.convert(DTypeKind::Bool) {
+            Values::Bool(v) => map!(Bool, v, |b: bool| !b),
+            _ => unreachable!(),
+        }
+        .into_array(shape, bool_dtype),
+        BitwiseInvert => match values {
+            Values::Bool(v) => map!(Bool, v, |b: bool| !b),
+            Values::Int(v) => map!(Int, v, |x: i64| !x),
+            Values::UInt(v) => map!(UInt, v, |x: u64| !x),
+            _ => return Err(unsupported(op.name(), &dtype)),
+        }
+        .into_array(shape, dtype),
+    }
+}
+
+/// Apply an element-wise function of two arrays, broadcasting them together
+pub fn binary(op: BinaryOp, x1: &Array, x2: &Array) -> Result<Array, ArrayApiError> {
+    use BinaryOp::*;
+    let common = DType::new(promote_api_types(x1.dtype().type_(), x2.dtype().type_())?);
+    let common_kind = dtype_kind(&common)?;
+    let shape = broadcast_shape(&[x1.shape(), x2.shape()])?;
+    let a = Values::read_broadcast(x1, &shape)?;
+    let b = Values::read_broadcast(x2, &shape)?;
+
+    let (compute, out) = match op {
+        Equal | NotEqual | Less | LessEqual | Greater | GreaterEqual => (common.clone(), DType::new(NpyType::Bool)),
+        LogicalAnd | LogicalOr | LogicalXor => (DType::new(NpyType::Bool), DType::new(NpyType::Bool)),
+        Divide | Atan2 | Copysign | Hypot | LogAddExp => {
+            let floating = floating_dtype(&common, common_kind);
+            (floating.clone(), floating)
+        }
+        _ => (common.clone(), common.clone()),
+    };
+    let kind = dtype_kind(&compute)?;
+    let result = match (a.convert(kind), b.convert(kind)) {
+        (Values::Bool(a), Values::Bool(b)) => bool_binary(op, &a, &b),
+        (Values::Int(a), Values::Int(b)) => int_binary(op, &a, &b),
+        (Values::UInt(a), Values::UInt(b)) => uint_binary(op, &a, &b),
+        (Values::Float(a), Values::Float(b)) => float_binary(op, &a, &b),
+        (Values::Complex(a), Values::Complex(b)) => complex_binary(op, &a, &b),
+        _ => unreachable!("both operands converted to one kind"),
+    }
+    .ok_or_else(|| unsupported(op.name(), &compute))?;
+    result.into_array(shape, out)
+}
+
+fn zip<T: Copy, U>(a: &[T], b: &[T], f: impl Fn(T, T) -> U) -> Vec<U> {
+    a.iter().zip(b).map(|(&x, &y)| f(x, y)).collect()
+}
+
+fn bool_binary(op: BinaryOp, a: &[bool], b: &[bool]) -> Option<Values> {
+    use BinaryOp::*;
+    let f: fn(bool, bool) -> bool = match op {
+        Add | Maximum | BitwiseOr | LogicalOr => |x, y| x | y,
+        Multiply | Minimum | BitwiseAnd | LogicalAnd => |x, y| x & y,
+        BitwiseXor | LogicalXor | NotEqual => |x, y| x ^ y,
+        Equal => |x, y| x == y,
+        Less => |x, y| !x & y,
+        LessEqual => |x, y| x <= y,
+        Greater => |x, y| x & !y,
+        GreaterEqual => |x, y| x >= y,
+        _ => return None,
+    };
+    Some(Values::Bool(zip(a, b, f)))
+}
+
+macro_rules! compare {
+    ($op:expr, $a:expr, $b:expr) => {
+        match $op {
+            BinaryOp::Equal => Some(Values::Bool(zip($a, $b, |x, y| x == y))),
+            BinaryOp::NotEqual => Some(Values::Bool(zip($a, $b, |x, y| x != y))),
+            BinaryOp::Less => Some(Values::Bool(zip($a, $b, |x, y| x < y))),
+            BinaryOp::LessEqual => Some(Values::Bool(zip($a, $b, |x, y| x <= y))),
+            BinaryOp::Greater => Some(Values::Bool(zip($a, $b, |x, y| x > y))),
+            BinaryOp::GreaterEqual => Some(Values::Bool(zip($a, $b, |x, y| x >= y))),
+            _ => None,
+        }
+    };
+}
+
+fn int_binary(op: BinaryOp, a: &[i64], b: &[i64]) -> Option<Values> {
+    use BinaryOp::*;
+    let f: fn(i64, i64) -> i64 = match op {
+        Add => i64::wrapping_add,
+        Subtract => i64::wrapping_sub,
+        Multiply => i64::wrapping_mul,
+        FloorDivide => |x, y| {
+            if y == 0 {
+                return 0;
+            }
+            let q = x.wrapping_div(y);
+            if x.wrapping_rem(y) != 0 && ((x < 0) != (y < 0)) { q - 1 } else { q }
+        },
+        Remainder => |x, y| {
+            if y == 0 {
+                return 0;
+            }
+            let r = x.wrapping_rem(y);
+            if r != 0 && ((r < 0) != (y < 0)) { r + y } else { r }
+        },
+        Pow => |x, y| {
+            if y >= 0 {
+                return wrapping_pow(x as u64, y as u64) as i64;
+            }
+            match x {
+                1 => 1,
+                -1 => if y % 2 == 0 { 1 } else { -1 },
+                _ => 0,
+            }
+        },
+        Maximum => i64::max,
+        Minimum => i64::min,
+        BitwiseAnd => |x, y| x & y,
+        BitwiseOr => |x, y| x | y,
+        BitwiseXor => |x, y| x ^ y,
+        BitwiseLeftShift => |x, y| if (0..64).contains(&y) { x << y } else { 0 },
+        BitwiseRightShift => |x, y| if (0..64).contains(&y) { x >> y } else { x >> 63 },
+        _ => return compare!(op, a, b),
+    };
+    Some(Values::Int(zip(a, b, f)))
+}
+
+fn uint_binary(op: BinaryOp, a: &[u64], b: &[u64]) -> Option<Values> {
+    use BinaryOp::*;
+    let f: fn(u64, u64) -> u64 = match op {
+        Add => u64::wrapping_add,
+        Subtract => u64::wrapping_sub,
+        Multiply => u64::wrapping_mul,
+        FloorDivide => |x, y| x.checked_div(y).unwrap_or(0),
+        Remainder => |x, y| x.checked_rem(y).unwrap_or(0),
+        Pow => wrapping_pow,
+        Maximum => u64::max,
+        Minimum => u64::min,
+        BitwiseAnd => |x, y| x & y,
+        BitwiseOr => |x, y| x | y,
+        BitwiseXor => |x, y| x ^ y,
+        BitwiseLeftShift => |x, y| if y < 64 { x << y } else { 0 },
+        BitwiseRightShift => |x, y| if y < 64 { x >> y } else { 0 },
+        _ => return compare!(op, a, b),
+    };
+    Some(Values::UInt(zip(a, b, f)))
+}
+
+/// `base` to the power `exp`, wrapping on overflow
+fn wrapping_pow(mut base: u64, mut exp: u64) -> u64 {
+    let mut result = 1u64;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Python's `divmod` for floats: floor quotient and remainder with the
+/// divisor's sign
+fn float_divmod(x: f64, y: f64) -> (f64, f64) {
+    if y == 0.0 {
+        return (x / y, f64::NAN);
+    }
+    let mut rem = x % y;
+    let mut div = (x - rem) / y;
+    if rem != 0.0 {
+        if (y < 0.0) != (rem < 0.0) {
+            rem += y;
+            div -= 1.0;
+        }
+    } else {
+        rem = 0.0f64.copysign(y);
+    }
+    let floordiv = if div != 0.0 {
+        let floor = div.floor();
+        if div - floor > 0.5 { floor + 1.0 } else { floor }
+    } else {
+        0.0f64.copysign(x / y)
+    };
+    (floordiv, rem)
+}
+
+fn logaddexp(x: f64, y: f64) -> f64 {
+    if x == y {
+        // Also covers both infinite with the same sign
+        return x + std::f64::consts::LN_2;
+    }
+    let diff = x - y;
+    if diff > 0.0 {
+        x + (-diff).exp().ln_1p()
+    } else if diff <= 0.0 {
+        y + diff.exp().ln_1p()
+    } else {
+        // NaN
+        diff
+    }
+}
+
+fn float_binary(op: BinaryOp, a: &[f64], b: &[f64]) -> Option<Values> {
+    use BinaryOp::*;
+    let f: fn(f64, f64) -> f64 = match op {
+        Add => |x, y| x + y,
+        Subtract => |x, y| x - y,
+        Multiply => |x, y| x * y,
+        Divide => |x, y| x / y,
+        FloorDivide => |x, y| float_divmod(x, y).0,
+        Remainder => |x, y| float_divmod(x, y).1,
+        Pow => f64::powf,
+        Maximum => |x, y| if x.is_nan() || y.is_nan() { f64::NAN } else { x.max(y) },
+        Minimum => |x, y| if x.is_nan() || y.is_nan() { f64::NAN } else { x.min(y) },
+        Atan2 => f64::atan2,
+        Copysign => f64::copysign,
+        Hypot => f64::hypot,
+        LogAddExp => logaddexp,
+        _ => return compare!(op, a, b),
+    };
+    Some(Values::Float(zip(a, b, f)))
+}
+
+fn complex_binary(op: BinaryOp, a: &[Complex128], b: &[Complex128]) -> Option<Values> {
+    use BinaryOp::*;
+    let f: fn(Complex128, Complex128) -> Complex128 = match op {
+        Add => |x, y| x + y,
+        Subtract => |x, y| x - y,
+        Multiply => |x, y| x * y,
+        Divide => |x, y| x / y,
+        Pow => complex_pow,
+        Equal => return Some(Values::Bool(zip(a, b, |x, y| x == y))),
+        NotEqual => return Some(Values::Bool(zip(a, b, |x, y| x != y))),
+        _ => return None,
+    };
+    Some(Values::Complex(zip(a, b, f)))
+}
+
+fn real_function(op: UnaryOp, x: f64) -> f64 {
+    use UnaryOp::*;
+    match op {
+        Acos => x.acos(),
+        Acosh => x.acosh(),
+        Asin => x.asin(),
+        Asinh => x.asinh(),
+        Atan => x.atan(),
+        Atanh => x.atanh(),
+        Cos => x.cos(),
+        Cosh => x.cosh(),
+        Exp => x.exp(),
+        Expm1 => x.exp_m1(),
+        Log => x.ln(),
+        Log1p => x.ln_1p(),
+        Log2 => x.log2(),
+        Log10 => x.log10(),
+        Sin => x.sin(),
+        Sinh => x.sinh(),
+        Sqrt => x.sqrt(),
+        Tan => x.tan(),
+        Tanh => x.tanh(),
+        _ => unreachable!("not a floating function"),
+    }
+}
+
+fn complex_function(op: UnaryOp, z: Complex128) -> Complex128 {
+    use UnaryOp::*;
+    let one = Complex128::new(1.0, 0.0);
+    let i = Complex128::new(0.0, 1.0);
+    match op {
+        Exp => complex_exp(z),
+        Expm1 => complex_exp(z) - one,
+        Log => complex_log(z),
+        Log1p => complex_log(z + one),
+        Log2 => complex_log(z).scale(std::f64::consts::LOG2_E),
+        Log10 => complex_log(z).scale(std::f64::consts::LOG10_E),
+        Sqrt => complex_sqrt(z),
+        Sin => Complex128::new(z.re.sin() * z.im.cosh(), z.re.cos() * z.im.sinh()),
+        Cos => Complex128::new(z.re.cos() * z.im.cosh(), -(z.re.sin() * z.im.sinh())),
+        Sinh => Complex128::new(z.re.sinh() * z.im.cos(), z.re.cosh() * z.im.sin()),
+        Cosh => Complex128::new(z.re.cosh() * z.im.cos(), z.re.sinh() * z.im.sin()),
+        Tanh => complex_tanh(z),
+        // tan(z) = -i tanh(iz)
+        Tan => {
+            let t = complex_tanh(Complex128::new(-z.im, z.re));
+            Complex128::new(t.im, -t.re)
+        }
+        // asin(z) = -i log(iz + sqrt(1 - z²))
+        Asin => {
+            let w = complex_log(i * z + complex_sqrt(one - z * z));
+            Complex128::new(w.im, -w.re)
+        }
+        // acos(z) = π/2 - asin(z)
+        Acos => Complex128::new(std::f64::consts::FRAC_PI_2, 0.0) - complex_function(Asin, z),
+        // atan(z) = (i/2) log((i + z) / (i - z))
+        Atan => {
+            let w = complex_log((i + z) / (i - z));
+            Complex128::new(-w.im / 2.0, w.re / 2.0)
+        }
+        Asinh => complex_log(z + complex_sqrt(z * z + one)),
+        Acosh => complex_log(z + complex_sqrt(z + one) * complex_sqrt(z - one)),
+        Atanh => complex_log((one + z) / (one - z)).scale(0.5),
+        _ => unreachable!("not a floating function"),
+    }
+}
+
+fn complex_exp(z: Complex128) -> Complex128 {
+    let r = z.re.exp();
+    if z.im == 0.0 {
+        return Complex128::new(r, z.im);
+    }
+    Complex128::new(r * z.im.cos(), r * z.im.sin())
+}
+
+fn complex_log(z: Complex128) -> Complex128 {
+    Complex128::new(z.re.hypot(z.im).ln(), z.im.atan2(z.re))
+}
+
+fn complex_sqrt(z: Complex128) -> Complex128 {
+    if z.re == 0.0 && z.im == 0.0 {
+        return Complex128::new(0.0, z.im);
+    }
+    if z.im.is_infinite() {
+        return Complex128::new(f64::INFINITY, z.im);
+    }
+    let t = ((z.re.abs() + z.re.hypot(z.im)) / 2.0).sqrt();
+    if z.re >= 0.0 {
+        Complex128::new(t, z.im / (2.0 * t))
+    } else {
+        Complex128::new(z.im.abs() / (2.0 * t), t.copysign(z.im))
+    }
+}
+
+fn complex_tanh(z: Complex128) -> Complex128 {
+    // Large real parts would overflow cosh/sinh
+    if z.re.abs() > 20.0 {
+        return Complex128::new(z.re.signum(), 4.0 * z.im.sin() * z.im.cos() * (-2.0 * z.re.abs()).exp());
+    }
+    let denom = (2.0 * z.re).cosh() + (2.0 * z.im).cos();
+    Complex128::new((2.0 * z.re).sinh() / denom, (2.0 * z.im).sin() / denom)
+}
+
+fn complex_pow(x: Complex128, y: Complex128) -> Complex128 {
+    if y.re == 0.0 && y.im == 0.0 {
+        return Complex128::new(1.0, 0.0);
+    }
+    if x.re == 0.0 && x.im == 0.0 {
+        return if y.re > 0.0 && y.im == 0.0 {
+            Complex128::new(0.0, 0.0)
+        } else {
+            Complex128::new(f64::NAN, f64::NAN)
+        };
+    }
+    // Small integer powers by repeated multiplication, as NumPy does
+    if y.im == 0.0 && y.re.fract() == 0.0 && y.re.abs() <= 100.0 {
+        let mut result = Complex128::new(1.0, 0.0);
+        for _ in 0..y.re.abs() as u32 {
+            result *= x;
+        }
+        return if y.re < 0.0 { Complex128::new(1.0, 0.0) / result } else { result };
+    }
+    complex_exp(y * complex_log(x))
+}
+
+/// Clamp `x` to `[min, max]`, broadcasting the bounds
+///
+/// The result has the dtype of `x`; bounds are cast to it. NaN elements or
+/// bounds give NaN.
+pub fn clip(x: &Array, min: Option<&Array>, max: Option<&Array>) -> Result<Array, ArrayApiError> {
+    let kind = dtype_kind(x.dtype())?;
+    let dtype = DType::new(canonical_type(x.dtype().type_()));
+    if kind == DTypeKind::ComplexFloating {
+        return Err(unsupported("clip", &dtype));
+    }
+    let mut shapes: Vec<&[i64]> = vec![x.shape()];
+    shapes.extend(min.iter().chain(max.iter()).map(|bound| bound.shape()));
+    let shape = broadcast_shape(&shapes)?;
+    let mut values = Values::read_broadcast(x, &shape)?.convert(kind);
+    for (bound, is_min) in [(min, true), (max, false)] {
+        let Some(bound) = bound else { continue };
+        dtype_kind(bound.dtype())?;
+        let bound = Values::read_broadcast(bound, &shape)?.convert(kind);
+        values = match (values, bound) {
+            (Values::Bool(v), Values::Bool(b)) => {
+                Values::Bool(zip(&v, &b, |x, y| if is_min { x | y } else { x & y }))
+            }
+            (Values::Int(v), Values::Int(b)) => Values::Int(zip(&v, &b, |x, y| if is_min { x.max(y) } else { x.min(y) })),
+            (Values::UInt(v), Values::UInt(b)) => {
+                Values::UInt(zip(&v, &b, |x, y| if is_min { x.max(y) } else { x.min(y) }))
+            }
+            (Values::Float(v), Values::Float(b)) => Values::Float(zip(&v, &b, |x, y| {
+                if x.is_nan() || y.is_nan() {
+                    f64::NAN
+                } else if is_min {
+                    x.max(y)
+                } else {
+                    x.min(y)
+                }
+            })),
+            _ => unreachable!("bounds converted to the kind of x"),
+        };
+    }
+    values.into_array(shape, dtype)
+}
+
+/// Elements of `x1` where `condition` is true and of `x2` elsewhere
+///
+/// All three arrays broadcast together; the result has the promoted dtype
+/// of `x1` and `x2`.
+pub fn where_(condition: &Array, x1: &Array, x2: &Array) -> Result<Array, ArrayApiError> {
+    let dtype = DType::new(promote_api_types(x1.dtype().type_(), x2.dtype().type_())?);
+    let kind = dtype_kind(&dtype)?;
+    let shape = broadcast_shape(&[condition.shape(), x1.shape(), x2.shape()])?;
+    let Values::Bool(mask) = Values::read_broadcast(condition, &shape)?.convert(DTypeKind::Bool) else {
+        unreachable!()
+    };
+    let a = Values::read_broadcast(x1, &shape)?.convert(kind);
+    let b = Values::read_broadcast(x2, &shape)?.convert(kind);
+    let result = match (a, b) {
+        (Values::Bool(a), Values::Bool(b)) => Values::Bool(select(&mask, &a, &b)),
+        (Values::Int(a), Values::Int(b)) => Values::Int(select(&mask, &a, &b)),
+        (Values::UInt(a), Values::UInt(b)) => Values::UInt(select(&mask, &a, &b)),
+        (Values::Float(a), Values::Float(b)) => Values::Float(select(&mask, &a, &b)),
+        (Values::Complex(a), Values::Complex(b)) => Values::Complex(select(&mask, &a, &b)),
+        _ => unreachable!("operands converted to one kind"),
+    };
+    result.into_array(shape, dtype)
+}
+
+fn select<T: Copy>(mask: &[bool], a: &[T], b: &[T]) -> Vec<T> {
+    mask.iter().zip(a.iter().zip(b)).map(|(&m, (&x, &y))| if m { x } else { y }).collect()
+}
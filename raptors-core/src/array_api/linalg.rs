@@ -0,0 +1,197 @@
+//! Linear algebra functions of the array API standard
+//!
+//! `matmul` follows the standard's rules for one-dimensional operands and
+//! broadcasts the leading (batch) dimensions of stacked matrices. Integer
+//! products wrap on overflow.
+
+use crate::array::Array;
+use crate::types::{Complex128, DType};
+
+use super::values::{axes_last, broadcast_shape, broadcast_view, permuted_view, Values};
+use super::{dtype_kind, normalize_axes, normalize_axis, promote_api_types, ArrayApiError, DTypeKind};
+
+/// Common numeric dtype of two operands
+fn numeric_dtype(x1: &Array, x2: &Array, name: &str) -> Result<(DType, DTypeKind), ArrayApiError> {
+    let dtype = DType::new(promote_api_types(x1.dtype().type_(), x2.dtype().type_())?);
+    let kind = dtype_kind(&dtype)?;
+    if !kind.is_numeric() {
+        return Err(ArrayApiError::UnsupportedDtype(format!("{} is not defined for {}", name, dtype.name())));
+    }
+    Ok((dtype, kind))
+}
+
+/// `batch` products of `n x k` and `k x m` matrices stored one after the
+/// other in C order
+fn matmul_values(a: Values, b: Values, batch: usize, n: usize, k: usize, m: usize) -> Values {
+    macro_rules! product {
+        ($a:expr, $b:expr, $zero:expr, $mul_add:expr) => {{
+            let mut out = vec![$zero; batch * n * m];
+            for p in 0..batch {
+                let (a, b) = (&$a[p * n * k..], &$b[p * k * m..]);
+                let out = &mut out[p * n * m..(p + 1) * n * m];
+                for i in 0..n {
+                    for l in 0..k {
+                        let x = a[i * k + l];
+                        for j in 0..m {
+                            out[i * m + j] = $mul_add(out[i * m + j], x, b[l * m + j]);
+                        }
+                    }
+                }
+            }
+            out
+        }};
+    }
+    match (a, b) {
+        (Values::Int(a), Values::Int(b)) => {
+            Values::Int(product!(a, b, 0i64, |acc: i64, x: i64, y| acc.wrapping_add(x.wrapping_mul(y))))
+        }
+        (Values::UInt(a), Values::UInt(b)) => {
+            Values::UInt(product!(a, b, 0u64, |acc: u64, x: u64, y| acc.wrapping_add(x.wrapping_mul(y))))
+        }
+        (Values::Float(a), Values::Float(b)) => Values::Float(product!(a, b, 0.0f64, |acc, x, y| acc + x * y)),
+        (Values::Complex(a), Values::Complex(b)) => {
+            Values::Complex(product!(a, b, Complex128::new(0.0, 0.0), |acc, x, y| acc + x * y))
+        }
+        _ => unreachable!("operands converted to one numeric kind"),
+    }
+}
+
+/// Matrix product of `x1` and `x2`
+///
+/// A 1-D `x1` is treated as a row vector and a 1-D `x2` as a column
+/// vector; the added dimension is removed from the result. Zero-dimensional
+/// operands are rejected.
+pub fn matmul(x1: &Array, x2: &Array) -> Result<Array, ArrayApiError> {
+    if x1.ndim() == 0 || x2.ndim() == 0 {
+        return Err(ArrayApiError::InvalidArgument("matmul does not accept zero-dimensional arrays".to_string()));
+    }
+    let (dtype, kind) = numeric_dtype(x1, x2, "matmul")?;
+    let mut a_shape = x1.shape().to_vec();
+    let mut b_shape = x2.shape().to_vec();
+    if x1.ndim() == 1 {
+        a_shape.insert(0, 1);
+    }
+    if x2.ndim() == 1 {
+        b_shape.push(1);
+    }
+    let (n, k) = (a_shape[a_shape.len() - 2], a_shape[a_shape.len() - 1]);
+    let (k2, m) = (b_shape[b_shape.len() - 2], b_shape[b_shape.len() - 1]);
+    if k != k2 {
+        return Err(ArrayApiError::InvalidArgument(format!(
+            "matmul: shapes {:?} and {:?} are not aligned",
+            x1.shape(),
+            x2.shape()
+        )));
+    }
+    let batch = broadcast_shape(&[&a_shape[..a_shape.len() - 2], &b_shape[..b_shape.len() - 2]])?;
+    let a_view = x1.view(a_shape.clone(), stacked_strides(x1, x1.ndim() == 1, false))?;
+    let b_view = x2.view(b_shape.clone(), stacked_strides(x2, false, x2.ndim() == 1))?;
+    let a = Values::read_broadcast(&a_view, &[batch.as_slice(), &[n, k]].concat())?.convert(kind);
+    let b = Values::read_broadcast(&b_view, &[batch.as_slice(), &[k, m]].concat())?.convert(kind);
+    let count = batch.iter().product::<i64>() as usize;
+    let product = matmul_values(a, b, count, n as usize, k as usize, m as usize);
+
+    let mut shape = batch;
+    if x1.ndim() > 1 {
+        shape.push(n);
+    }
+    if x2.ndim() > 1 {
+        shape.push(m);
+    }
+    product.into_array(shape, dtype)
+}
+
+/// Strides of `x` with a length-one axis added in front or at the end
+fn stacked_strides(x: &Array, front: bool, back: bool) -> Vec<i64> {
+    let mut strides = x.strides().to_vec();
+    if front {
+        strides.insert(0, 0);
+    }
+    if back {
+        strides.push(0);
+    }
+    strides
+}
+
+/// Axes contracted by `tensordot`
+#[derive(Debug, Clone)]
+pub enum TensorAxes {
+    /// The last `n` axes of `x1` with the first `n` axes of `x2`
+    Count(usize),
+    /// The listed axes of `x1` with the listed axes of `x2`, pairwise
+    Pairs(Vec<isize>, Vec<isize>),
+}
+
+/// Tensor contraction of `x1` and `x2` over `axes`
+///
+/// The result has the uncontracted axes of `x1` followed by those of `x2`.
+pub fn tensordot(x1: &Array, x2: &Array, axes: TensorAxes) -> Result<Array, ArrayApiError> {
+    let (dtype, kind) = numeric_dtype(x1, x2, "tensordot")?;
+    let (axes1, axes2) = match axes {
+        TensorAxes::Count(n) => {
+            if n > x1.ndim() || n > x2.ndim() {
+                return Err(ArrayApiError::InvalidArgument(format!("cannot contract {} axes", n)));
+            }
+            ((x1.ndim() - n..x1.ndim()).collect::<Vec<_>>(), (0..n).collect::<Vec<_>>())
+        }
+        TensorAxes::Pairs(a, b) => {
+            if a.len() != b.len() {
+                return Err(ArrayApiError::InvalidArgument("axes lists must have the same length".to_string()));
+            }
+            (normalize_axes(Some(&a), x1.ndim())?, normalize_axes(Some(&b), x2.ndim())?)
+        }
+    };
+    if axes1.iter().zip(&axes2).any(|(&a, &b)| x1.shape()[a] != x2.shape()[b]) {
+        return Err(ArrayApiError::InvalidArgument(format!(
+            "tensordot: shapes {:?} and {:?} do not match on the contracted axes",
+            x1.shape(),
+            x2.shape()
+        )));
+    }
+    let free1: Vec<usize> = (0..x1.ndim()).filter(|a| !axes1.contains(a)).collect();
+    let free2: Vec<usize> = (0..x2.ndim()).filter(|a| !axes2.contains(a)).collect();
+    let perm2: Vec<usize> = axes2.iter().chain(&free2).copied().collect();
+    let a = Values::read(&permuted_view(x1, &[free1.as_slice(), &axes1].concat())?)?.convert(kind);
+    let b = Values::read(&permuted_view(x2, &perm2)?)?.convert(kind);
+    let dims = |x: &Array, axes: &[usize]| axes.iter().map(|&a| x.shape()[a] as usize).product::<usize>();
+    let product = matmul_values(a, b, 1, dims(x1, &free1), dims(x1, &axes1), dims(x2, &free2));
+    let shape = free1.iter().map(|&a| x1.shape()[a]).chain(free2.iter().map(|&a| x2.shape()[a])).collect();
+    product.into_array(shape, dtype)
+}
+
+/// Dot product of vectors along `axis`, conjugating `x1`
+///
+/// The operands broadcast against each other and must have the same
+/// length along `axis`, which is removed from the result.
+pub fn vecdot(x1: &Array, x2: &Array, axis: isize) -> Result<Array, ArrayApiError> {
+    let (dtype, kind) = numeric_dtype(x1, x2, "vecdot")?;
+    let ndim = x1.ndim().max(x2.ndim());
+    let resolved = normalize_axis(axis, ndim)?;
+    // The contracted axis counts from the end of each operand
+    let from_end = ndim - resolved;
+    let len_of = |x: &Array| if from_end <= x.ndim() { Some(x.shape()[x.ndim() - from_end]) } else { None };
+    if len_of(x1).is_none() || len_of(x1) != len_of(x2) {
+        return Err(ArrayApiError::InvalidArgument(format!(
+            "vecdot: shapes {:?} and {:?} do not match along axis {}",
+            x1.shape(),
+            x2.shape(),
+            axis
+        )));
+    }
+    let shape = broadcast_shape(&[x1.shape(), x2.shape()])?;
+    let perm = axes_last(ndim, &[resolved]);
+    let read = |x: &Array| -> Result<Values, ArrayApiError> {
+        let broadcast = broadcast_view(x, &shape)?;
+        Ok(Values::read(&permuted_view(&broadcast, &perm)?)?.convert(kind))
+    };
+    let a = match read(x1)? {
+        Values::Complex(v) => Values::Complex(v.into_iter().map(|z| z.conj()).collect()),
+        other => other,
+    };
+    let b = read(x2)?;
+    let len = shape[resolved] as usize;
+    let mut out_shape = shape;
+    out_shape.remove(resolved);
+    let count = out_shape.iter().product::<i64>() as usize;
+    matmul_values(a, b, count, 1, len, 1).into_array(out_shape, dtype)
+}
@@ -0,0 +1,349 @@
+//! Manipulation functions of the array API standard
+//!
+//! Functions that only change the shape or strides of an array return
+//! views that keep the input alive through its `Arc`; the others return
+//! new arrays in the promoted dtype of their inputs.
+
+use std::sync::Arc;
+
+use crate::array::{Array, ArrayFlags};
+use crate::types::DType;
+
+use super::values::{broadcast_shape, element_strides, remap, Values};
+use super::{canonical_type, dtype_kind, normalize_axes, normalize_axis, result_type, ArrayApiError};
+
+/// Read-only view of `x` broadcast to `shape`
+pub fn broadcast_to(x: &Arc<Array>, shape: &[i64]) -> Result<Array, ArrayApiError> {
+    let error = || ArrayApiError::BroadcastError(x.shape().to_vec(), shape.to_vec());
+    if x.ndim() > shape.len() || shape.iter().any(|&dim| dim < 0) {
+        return Err(error());
+    }
+    let lead = shape.len() - x.ndim();
+    let mut strides = vec![0i64; shape.len()];
+    for (axis, (&dim, &stride)) in x.shape().iter().zip(x.strides()).enumerate() {
+        if dim == shape[lead + axis] {
+            strides[lead + axis] = stride;
+        } else if dim != 1 {
+            return Err(error());
+        }
+    }
+    let mut view = Array::view_from_arc(x, shape.to_vec(), strides)?;
+    // Broadcast elements alias each other, so writes are not allowed
+    view.setflags(ArrayFlags::WRITEABLE, false)?;
+    Ok(view)
+}
+
+/// Read-only views of `arrays` broadcast to their common shape
+pub fn broadcast_arrays(arrays: &[Arc<Array>]) -> Result<Vec<Array>, ArrayApiError> {
+    let shapes: Vec<&[i64]> = arrays.iter().map(|x| x.shape()).collect();
+    let shape = broadcast_shape(&shapes)?;
+    arrays.iter().map(|x| broadcast_to(x, &shape)).collect()
+}
+
+/// Common dtype and values of `arrays`
+fn promoted_values(arrays: &[&Array]) -> Result<(DType, Vec<Values>), ArrayApiError> {
+    let dtypes: Vec<DType> = arrays.iter().map(|x| x.dtype().clone()).collect();
+    let dtype = result_type(&dtypes)?;
+    let kind = dtype_kind(&dtype)?;
+    let values = arrays.iter().map(|x| Ok(Values::read(x)?.convert(kind))).collect::<Result<_, ArrayApiError>>()?;
+    Ok((dtype, values))
+}
+
+/// Interleave `parts`: `outer` rounds, each taking `chunks[i]` elements of
+/// part `i` in turn
+fn interleave(parts: Vec<Values>, outer: usize, chunks: &[usize]) -> Values {
+    let kind = parts[0].kind();
+    let mut starts = Vec::with_capacity(parts.len());
+    let mut total = 0;
+    for part in &parts {
+        starts.push(total);
+        total += part.len();
+    }
+    let mut indices = Vec::with_capacity(total);
+    for round in 0..outer {
+        for (&start, &chunk) in starts.iter().zip(chunks) {
+            indices.extend(start + round * chunk..start + (round + 1) * chunk);
+        }
+    }
+    Values::concat(parts, kind).gather(&indices)
+}
+
+/// Join `arrays` along an existing axis (flattening them if `axis` is
+/// `None`)
+pub fn concat(arrays: &[&Array], axis: Option<isize>) -> Result<Array, ArrayApiError> {
+    if arrays.is_empty() {
+        return Err(ArrayApiError::InvalidArgument("need at least one array to concatenate".to_string()));
+    }
+    let (dtype, parts) = promoted_values(arrays)?;
+    let Some(axis) = axis else {
+        let total = parts.iter().map(|part| part.len()).sum::<usize>();
+        let chunks: Vec<usize> = parts.iter().map(|part| part.len()).collect();
+        return interleave(parts, 1, &chunks).into_array(vec![total as i64], dtype);
+    };
+    let first = arrays[0].shape();
+    let axis = normalize_axis(axis, first.len())?;
+    let mut shape = first.to_vec();
+    shape[axis] = 0;
+    for x in arrays {
+        let compatible = x.ndim() == first.len()
+            && x.shape().iter().zip(first).enumerate().all(|(a, (&dim, &expected))| a == axis || dim == expected);
+        if !compatible {
+            return Err(ArrayApiError::InvalidArgument(format!(
+                "cannot concatenate shapes {:?} and {:?} along axis {}",
+                first,
+                x.shape(),
+                axis
+            )));
+        }
+        shape[axis] += x.shape()[axis];
+    }
+    let outer = first[..axis].iter().product::<i64>() as usize;
+    let chunks: Vec<usize> = arrays.iter().map(|x| x.shape()[axis..].iter().product::<i64>() as usize).collect();
+    interleave(parts, outer, &chunks).into_array(shape, dtype)
+}
+
+/// Join arrays of the same shape along a new axis
+pub fn stack(arrays: &[&Array], axis: isize) -> Result<Array, ArrayApiError> {
+    if arrays.is_empty() {
+        return Err(ArrayApiError::InvalidArgument("need at least one array to stack".to_string()));
+    }
+    let first = arrays[0].shape();
+    if arrays.iter().any(|x| x.shape() != first) {
+        return Err(ArrayApiError::InvalidArgument("all input arrays must have the same shape".to_string()));
+    }
+    let axis = normalize_axis(axis, first.len() + 1)?;
+    let (dtype, parts) = promoted_values(arrays)?;
+    let mut shape = first.to_vec();
+    shape.insert(axis, arrays.len() as i64);
+    let outer = first[..axis].iter().product::<i64>() as usize;
+    let chunk = first[axis..].iter().product::<i64>() as usize;
+    interleave(parts, outer, &vec![chunk; arrays.len()]).into_array(shape, dtype)
+}
+
+/// View of `x` with a new axis of length one at `axis`
+///
+/// `axis` counts positions in the result, so it lies in
+/// `[-x.ndim - 1, x.ndim]`.
+pub fn expand_dims(x: &Arc<Array>, axis: isize) -> Result<Array, ArrayApiError> {
+    let axis = normalize_axis(axis, x.ndim() + 1)?;
+    let mut shape = x.shape().to_vec();
+    let mut strides = x.strides().to_vec();
+    let stride = if axis < x.ndim() { strides[axis] * shape[axis] } else { x.itemsize() as i64 };
+    shape.insert(axis, 1);
+    strides.insert(axis, stride);
+    Ok(Array::view_from_arc(x, shape, strides)?)
+}
+
+/// View of `x` with the order of elements reversed along `axis` (every
+/// axis if `None`)
+pub fn flip(x: &Arc<Array>, axis: Option<&[isize]>) -> Result<Array, ArrayApiError> {
+    let axes = normalize_axes(axis, x.ndim())?;
+    let mut strides = x.strides().to_vec();
+    let mut offset = 0isize;
+    for axis in axes {
+        offset += ((x.shape()[axis] - 1).max(0) * strides[axis]) as isize;
+        strides[axis] = -strides[axis];
+    }
+    Ok(Array::view_from_arc_at(x, offset, x.shape().to_vec(), strides)?)
+}
+
+/// View of `x` with its axes in the order `axes`
+pub fn permute_dims(x: &Arc<Array>, axes: &[isize]) -> Result<Array, ArrayApiError> {
+    if axes.len() != x.ndim() {
+        return Err(ArrayApiError::InvalidArgument(format!(
+            "axes {:?} do not match an array of dimension {}",
+            axes,
+            x.ndim()
+        )));
+    }
+    let axes = normalize_axes(Some(axes), x.ndim())?;
+    permuted(x, &axes)
+}
+
+fn permuted(x: &Arc<Array>, axes: &[usize]) -> Result<Array, ArrayApiError> {
+    let shape = axes.iter().map(|&axis| x.shape()[axis]).collect();
+    let strides = axes.iter().map(|&axis| x.strides()[axis]).collect();
+    Ok(Array::view_from_arc(x, shape, strides)?)
+}
+
+/// View of `x` with the axes `source` moved to `destination`
+pub fn moveaxis(x: &Arc<Array>, source: &[isize], destination: &[isize]) -> Result<Array, ArrayApiError> {
+    if source.len() != destination.len() {
+        return Err(ArrayApiError::InvalidArgument(
+            "source and destination must have the same number of axes".to_string(),
+        ));
+    }
+    let source = normalize_axes(Some(source), x.ndim())?;
+    let destination = normalize_axes(Some(destination), x.ndim())?;
+    let mut order: Vec<usize> = (0..x.ndim()).filter(|axis| !source.contains(axis)).collect();
+    let mut moves: Vec<(usize, usize)> = destination.into_iter().zip(source).collect();
+    moves.sort_unstable();
+    for (dst, src) in moves {
+        order.insert(dst, src);
+    }
+    permuted(x, &order)
+}
+
+/// View of the last two axes of `x` swapped
+pub fn matrix_transpose(x: &Arc<Array>) -> Result<Array, ArrayApiError> {
+    let ndim = x.ndim();
+    if ndim < 2 {
+        return Err(ArrayApiError::InvalidArgument("matrix_transpose requires at least two dimensions".to_string()));
+    }
+    let mut axes: Vec<usize> = (0..ndim).collect();
+    axes.swap(ndim - 2, ndim - 1);
+    permuted(x, &axes)
+}
+
+/// `x` with a new shape, as a view when the layout allows it
+///
+/// One dimension of `shape` may be -1 and is inferred. `copy` forces a
+/// copy when true and forbids one when false.
+pub fn reshape(x: &Arc<Array>, shape: &[i64], copy: Option<bool>) -> Result<Array, ArrayApiError> {
+    let size = x.size() as i64;
+    let known: i64 = shape.iter().filter(|&&dim| dim != -1).product();
+    let inferred = shape.iter().filter(|&&dim| dim == -1).count();
+    let invalid = || ArrayApiError::InvalidArgument(format!("cannot reshape array of size {} into {:?}", size, shape));
+    if inferred > 1 || shape.iter().any(|&dim| dim < -1) {
+        return Err(invalid());
+    }
+    let shape: Vec<i64> = if inferred == 1 {
+        if known == 0 || size % known != 0 {
+            return Err(invalid());
+        }
+        shape.iter().map(|&dim| if dim == -1 { size / known } else { dim }).collect()
+    } else {
+        shape.to_vec()
+    };
+    if shape.iter().product::<i64>() != size {
+        return Err(invalid());
+    }
+    if x.is_c_contiguous() && copy != Some(true) {
+        let itemsize = x.itemsize() as i64;
+        let strides = element_strides(&shape).into_iter().map(|s| s as i64 * itemsize).collect();
+        return Ok(Array::view_from_arc(x, shape, strides)?);
+    }
+    if copy == Some(false) {
+        return Err(ArrayApiError::InvalidArgument("reshape needs a copy but copy=False".to_string()));
+    }
+    Values::read(x)?.into_array(shape, DType::new(canonical_type(x.dtype().type_())))
+}
+
+/// View of `x` without the length-one axes `axis`
+pub fn squeeze(x: &Arc<Array>, axis: &[isize]) -> Result<Array, ArrayApiError> {
+    let axes = normalize_axes(Some(axis), x.ndim())?;
+    if let Some(&axis) = axes.iter().find(|&&axis| x.shape()[axis] != 1) {
+        return Err(ArrayApiError::InvalidArgument(format!(
+            "cannot squeeze axis {} of length {}",
+            axis,
+            x.shape()[axis]
+        )));
+    }
+    let keep: Vec<usize> = (0..x.ndim()).filter(|axis| !axes.contains(axis)).collect();
+    permuted(x, &keep)
+}
+
+/// Views of the slices of `x` along `axis`
+pub fn unstack(x: &Arc<Array>, axis: isize) -> Result<Vec<Array>, ArrayApiError> {
+    let axis = normalize_axis(axis, x.ndim())?;
+    let mut shape = x.shape().to_vec();
+    let mut strides = x.strides().to_vec();
+    let len = shape.remove(axis);
+    let stride = strides.remove(axis);
+    (0..len)
+        .map(|i| Ok(Array::view_from_arc_at(x, (i * stride) as isize, shape.clone(), strides.clone())?))
+        .collect()
+}
+
+/// `x` with its elements shifted by `shift` along `axis`
+///
+/// Without `axis` the flattened array is rolled and the shape restored. A
+/// single shift applies to every axis given.
+pub fn roll(x: &Array, shift: &[i64], axis: Option<&[isize]>) -> Result<Array, ArrayApiError> {
+    let dtype = DType::new(canonical_type(x.dtype().type_()));
+    let values = Values::read(x)?;
+    let Some(axis) = axis else {
+        let [shift] = shift else {
+            return Err(ArrayApiError::InvalidArgument("roll without axis takes a single shift".to_string()));
+        };
+        let size = x.size() as i64;
+        let flat = [size];
+        let rolled = remap(&values, &flat, &flat, |coords, from| from[0] = (coords[0] - shift).rem_euclid(size.max(1)));
+        return rolled.into_array(x.shape().to_vec(), dtype);
+    };
+    let shifts = match shift.len() {
+        1 => vec![shift[0]; axis.len()],
+        n if n == axis.len() => shift.to_vec(),
+        _ => return Err(ArrayApiError::InvalidArgument("shift and axis must have the same length".to_string())),
+    };
+    let mut total = vec![0i64; x.ndim()];
+    for (&axis, shift) in axis.iter().zip(shifts) {
+        total[normalize_axis(axis, x.ndim())?] += shift;
+    }
+    let shape = x.shape();
+    let rolled = remap(&values, shape, shape, |coords, from| {
+        for axis in 0..coords.len() {
+            from[axis] = (coords[axis] - total[axis]).rem_euclid(shape[axis].max(1));
+        }
+    });
+    rolled.into_array(shape.to_vec(), dtype)
+}
+
+/// Number of repetitions of each element for `repeat`
+#[derive(Debug, Clone)]
+pub enum Repeats<'a> {
+    /// The same count for every element
+    Scalar(usize),
+    /// One count per element along the axis
+    PerElement(&'a [usize]),
+}
+
+/// `x` with each element repeated along `axis` (flattened if `None`)
+pub fn repeat(x: &Array, repeats: Repeats<'_>, axis: Option<isize>) -> Result<Array, ArrayApiError> {
+    let dtype = DType::new(canonical_type(x.dtype().type_()));
+    let values = Values::read(x)?;
+    let flat = [x.size() as i64];
+    let (source_shape, axis) = match axis {
+        Some(axis) => (x.shape(), normalize_axis(axis, x.ndim())?),
+        None => (&flat[..], 0),
+    };
+    let len = source_shape[axis] as usize;
+    let counts = match repeats {
+        Repeats::Scalar(count) => vec![count; len],
+        Repeats::PerElement(counts) if counts.len() == len => counts.to_vec(),
+        Repeats::PerElement(counts) => {
+            return Err(ArrayApiError::InvalidArgument(format!(
+                "{} repeats do not match an axis of length {}",
+                counts.len(),
+                len
+            )))
+        }
+    };
+    let source: Vec<i64> = counts.iter().enumerate().flat_map(|(i, &count)| std::iter::repeat_n(i as i64, count)).collect();
+    let mut shape = source_shape.to_vec();
+    shape[axis] = source.len() as i64;
+    let repeated = remap(&values, source_shape, &shape, |coords, from| {
+        from.copy_from_slice(coords);
+        from[axis] = source[coords[axis] as usize];
+    });
+    repeated.into_array(shape, dtype)
+}
+
+/// `x` repeated `reps` times along each axis
+///
+/// Missing leading repetitions or dimensions are taken as one.
+pub fn tile(x: &Array, reps: &[usize]) -> Result<Array, ArrayApiError> {
+    let ndim = x.ndim().max(reps.len());
+    let mut source_shape = vec![1i64; ndim - x.ndim()];
+    source_shape.extend_from_slice(x.shape());
+    let mut counts = vec![1usize; ndim - reps.len()];
+    counts.extend_from_slice(reps);
+    let shape: Vec<i64> = source_shape.iter().zip(&counts).map(|(&dim, &count)| dim * count as i64).collect();
+    let values = Values::read(x)?;
+    let tiled = remap(&values, &source_shape, &shape, |coords, from| {
+        for axis in 0..coords.len() {
+            from[axis] = coords[axis] % source_shape[axis];
+        }
+    });
+    tiled.into_array(shape, DType::new(canonical_type(x.dtype().type_())))
+}
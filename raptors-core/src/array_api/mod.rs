@@ -0,0 +1,112 @@
+//! Array API standard support
+//!
+//! This module implements the semantics of the Python array API standard
+//! (version 2023.12) over `Array`: the type promotion table, element-wise
+//! functions with broadcasting, reductions over several axes, and the
+//! manipulation, searching, set, sorting and linear algebra functions of the
+//! standard. The `raptors.array_api` namespace in the Python bindings only
+//! converts arguments and calls into this module.
+//!
+//! The standard's dtypes are bool, int8 through int64, uint8 through uint64,
+//! float32, float64, complex64 and complex128; other dtypes are rejected.
+//! Where the standard leaves behavior to the implementation (mixed-kind
+//! promotion, integer overflow, division by zero), NumPy's behavior is
+//! followed.
+
+mod creation;
+mod dtypes;
+mod elementwise;
+mod linalg;
+mod manipulation;
+mod searching;
+mod sets;
+mod sorting;
+mod statistical;
+mod values;
+
+pub use creation::*;
+pub use dtypes::*;
+pub use elementwise::*;
+pub use linalg::*;
+pub use manipulation::*;
+pub use searching::*;
+pub use sets::*;
+pub use sorting::*;
+pub use statistical::*;
+
+use crate::array::ArrayError;
+
+/// Array API version implemented by this module
+pub const ARRAY_API_VERSION: &str = "2023.12";
+
+/// Array API error
+#[derive(Debug)]
+pub enum ArrayApiError {
+    /// Array error
+    ArrayError(ArrayError),
+    /// Dtype is not one of the standard's dtypes, or not allowed here
+    UnsupportedDtype(String),
+    /// Dtypes have no common type under the promotion rules
+    NoCommonType(String, String),
+    /// Shapes cannot be broadcast together
+    BroadcastError(Vec<i64>, Vec<i64>),
+    /// Axis out of range for the array
+    InvalidAxis(isize, usize),
+    /// Invalid argument
+    InvalidArgument(String),
+    /// Reduction over zero elements without an identity
+    EmptyReduction(&'static str),
+}
+
+impl std::fmt::Display for ArrayApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrayApiError::ArrayError(e) => write!(f, "Array error: {}", e),
+            ArrayApiError::UnsupportedDtype(msg) => write!(f, "Unsupported dtype: {}", msg),
+            ArrayApiError::NoCommonType(a, b) => write!(f, "{} and {} have no common dtype", a, b),
+            ArrayApiError::BroadcastError(a, b) => {
+                write!(f, "Shapes {:?} and {:?} cannot be broadcast together", a, b)
+            }
+            ArrayApiError::InvalidAxis(axis, ndim) => {
+                write!(f, "Axis {} is out of bounds for array of dimension {}", axis, ndim)
+            }
+            ArrayApiError::InvalidArgument(msg) => write!(f, "{}", msg),
+            ArrayApiError::EmptyReduction(name) => {
+                write!(f, "Zero-size array to reduction operation {} which has no identity", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArrayApiError {}
+
+impl From<ArrayError> for ArrayApiError {
+    fn from(err: ArrayError) -> Self {
+        ArrayApiError::ArrayError(err)
+    }
+}
+
+/// Normalize a possibly negative axis against `ndim`
+pub(crate) fn normalize_axis(axis: isize, ndim: usize) -> Result<usize, ArrayApiError> {
+    let resolved = if axis < 0 { axis + ndim as isize } else { axis };
+    if resolved < 0 || resolved >= ndim as isize {
+        return Err(ArrayApiError::InvalidAxis(axis, ndim));
+    }
+    Ok(resolved as usize)
+}
+
+/// Normalize a list of axes, rejecting repeats; `None` means every axis
+pub(crate) fn normalize_axes(axes: Option<&[isize]>, ndim: usize) -> Result<Vec<usize>, ArrayApiError> {
+    let Some(axes) = axes else {
+        return Ok((0..ndim).collect());
+    };
+    let mut resolved = Vec::with_capacity(axes.len());
+    for &axis in axes {
+        let axis = normalize_axis(axis, ndim)?;
+        if resolved.contains(&axis) {
+            return Err(ArrayApiError::InvalidArgument(format!("repeated axis {}", axis)));
+        }
+        resolved.push(axis);
+    }
+    Ok(resolved)
+}
@@ -0,0 +1,173 @@
+//! Searching functions of the array API standard
+//!
+//! Index results use the default indexing dtype, `int64`.
+
+use std::cmp::Ordering;
+
+use crate::array::Array;
+use crate::types::{DType, NpyType};
+
+use super::values::{element_strides, read_lanes, reduced_shape, remap, Values};
+use super::{canonical_type, dtype_kind, normalize_axes, normalize_axis, promote_api_types, ArrayApiError, DTypeKind};
+
+fn index_dtype() -> DType {
+    DType::new(NpyType::LongLong)
+}
+
+fn arg_extremum(x: &Array, axis: Option<isize>, keepdims: bool, is_max: bool) -> Result<Array, ArrayApiError> {
+    let name = if is_max { "argmax" } else { "argmin" };
+    let axes = match axis {
+        Some(axis) => vec![normalize_axis(axis, x.ndim())?],
+        None => normalize_axes(None, x.ndim())?,
+    };
+    let (values, len) = read_lanes(x, &axes)?;
+    let shape = reduced_shape(x.shape(), &axes, keepdims);
+    let count = shape.iter().product::<i64>() as usize;
+    if len == 0 && count > 0 {
+        return Err(ArrayApiError::EmptyReduction(name));
+    }
+    let wanted = if is_max { Ordering::Greater } else { Ordering::Less };
+    let indices = (0..count)
+        .map(|lane| {
+            let start = lane * len;
+            let mut best = start;
+            for i in start..start + len {
+                // The first NaN wins, as in NumPy
+                if values.is_nan(i) {
+                    best = i;
+                    break;
+                }
+                if values.compare(i, best) == wanted {
+                    best = i;
+                }
+            }
+            (best - start) as i64
+        })
+        .collect();
+    Values::Int(indices).into_array(shape, index_dtype())
+}
+
+/// Index of the largest element over `axis` (every axis if `None`)
+pub fn argmax(x: &Array, axis: Option<isize>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    arg_extremum(x, axis, keepdims, true)
+}
+
+/// Index of the smallest element over `axis` (every axis if `None`)
+pub fn argmin(x: &Array, axis: Option<isize>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    arg_extremum(x, axis, keepdims, false)
+}
+
+/// Indices of the nonzero elements, one `int64` array per dimension
+///
+/// Zero-dimensional arrays are rejected, as the standard requires.
+pub fn nonzero(x: &Array) -> Result<Vec<Array>, ArrayApiError> {
+    if x.ndim() == 0 {
+        return Err(ArrayApiError::InvalidArgument("nonzero is not defined for zero-dimensional arrays".to_string()));
+    }
+    let values = Values::read(x)?;
+    let strides = element_strides(x.shape());
+    let positions: Vec<usize> = (0..values.len()).filter(|&i| values.is_nonzero(i)).collect();
+    strides
+        .iter()
+        .zip(x.shape())
+        .map(|(&stride, &dim)| {
+            let coords = positions.iter().map(|&p| ((p / stride) % dim as usize) as i64).collect();
+            Values::Int(coords).into_array(vec![positions.len() as i64], index_dtype())
+        })
+        .collect()
+}
+
+/// Side of equal elements `searchsorted` inserts at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSide {
+    /// Before equal elements
+    Left,
+    /// After equal elements
+    Right,
+}
+
+/// Indices at which to insert `x2` into the sorted 1-D array `x1`
+///
+/// `sorter`, if given, holds the indices that sort `x1`.
+pub fn searchsorted(
+    x1: &Array,
+    x2: &Array,
+    side: SearchSide,
+    sorter: Option<&Array>,
+) -> Result<Array, ArrayApiError> {
+    if x1.ndim() != 1 {
+        return Err(ArrayApiError::InvalidArgument("searchsorted requires a one-dimensional x1".to_string()));
+    }
+    let common = DType::new(promote_api_types(x1.dtype().type_(), x2.dtype().type_())?);
+    let kind = dtype_kind(&common)?;
+    let sorted = Values::read(x1)?.convert(kind);
+    let needles = Values::read(x2)?.convert(kind);
+    let order: Vec<usize> = match sorter {
+        Some(sorter) => index_values(sorter, sorted.len())?,
+        None => (0..sorted.len()).collect(),
+    };
+    let indices = (0..needles.len())
+        .map(|j| {
+            order.partition_point(|&i| match sorted.compare_with(i, &needles, j) {
+                Ordering::Less => true,
+                Ordering::Equal => side == SearchSide::Right,
+                Ordering::Greater => false,
+            }) as i64
+        })
+        .collect();
+    Values::Int(indices).into_array(x2.shape().to_vec(), index_dtype())
+}
+
+/// Integer indices of `indices`, wrapped and bounds-checked against `len`
+fn index_values(indices: &Array, len: usize) -> Result<Vec<usize>, ArrayApiError> {
+    if !dtype_kind(indices.dtype())?.is_integral() {
+        return Err(ArrayApiError::UnsupportedDtype(format!(
+            "indices must be integers, not {}",
+            indices.dtype().name()
+        )));
+    }
+    let Values::Int(values) = Values::read(indices)?.convert(DTypeKind::SignedInteger) else {
+        unreachable!()
+    };
+    values
+        .into_iter()
+        .map(|i| {
+            let resolved = if i < 0 { i + len as i64 } else { i };
+            if resolved < 0 || resolved >= len as i64 {
+                return Err(ArrayApiError::InvalidArgument(format!(
+                    "index {} is out of bounds for axis with size {}",
+                    i, len
+                )));
+            }
+            Ok(resolved as usize)
+        })
+        .collect()
+}
+
+/// Elements of `x` at `indices` along `axis`
+///
+/// `indices` is a 1-D integer array; negative indices count from the end.
+/// `axis` may be omitted only for 1-D arrays.
+pub fn take(x: &Array, indices: &Array, axis: Option<isize>) -> Result<Array, ArrayApiError> {
+    if indices.ndim() != 1 {
+        return Err(ArrayApiError::InvalidArgument("take requires one-dimensional indices".to_string()));
+    }
+    let axis = match axis {
+        Some(axis) => normalize_axis(axis, x.ndim())?,
+        None if x.ndim() == 1 => 0,
+        None => {
+            return Err(ArrayApiError::InvalidArgument(
+                "axis must be given for arrays of more than one dimension".to_string(),
+            ))
+        }
+    };
+    let picks = index_values(indices, x.shape()[axis] as usize)?;
+    let values = Values::read(x)?;
+    let mut shape = x.shape().to_vec();
+    shape[axis] = picks.len() as i64;
+    let taken = remap(&values, x.shape(), &shape, |coords, from| {
+        from.copy_from_slice(coords);
+        from[axis] = picks[coords[axis] as usize] as i64;
+    });
+    taken.into_array(shape, DType::new(canonical_type(x.dtype().type_())))
+}
@@ -0,0 +1,67 @@
+//! Set functions of the array API standard
+//!
+//! Unique values are returned sorted, as in NumPy. NaNs never compare
+//! equal, so each NaN is a unique value of its own.
+
+use crate::array::Array;
+use crate::types::{DType, NpyType};
+
+use super::sorting::stable_order;
+use super::values::Values;
+use super::{canonical_type, ArrayApiError};
+
+/// Result of `unique_all`
+#[derive(Debug)]
+pub struct UniqueAll {
+    /// Sorted unique values of the flattened input
+    pub values: Array,
+    /// Index of the first occurrence of each unique value
+    pub indices: Array,
+    /// Index into `values` of each input element, in the input's shape
+    pub inverse_indices: Array,
+    /// Number of occurrences of each unique value
+    pub counts: Array,
+}
+
+/// Unique values of `x` with first indices, inverse indices and counts
+pub fn unique_all(x: &Array) -> Result<UniqueAll, ArrayApiError> {
+    let values = Values::read(x)?;
+    let order = stable_order(&values);
+    let mut firsts: Vec<usize> = Vec::new();
+    let mut counts: Vec<i64> = Vec::new();
+    let mut inverse = vec![0i64; values.len()];
+    for (position, &i) in order.iter().enumerate() {
+        let is_new = position == 0 || !values.equal(order[position - 1], i);
+        if is_new {
+            firsts.push(i);
+            counts.push(0);
+        }
+        *counts.last_mut().unwrap() += 1;
+        inverse[i] = firsts.len() as i64 - 1;
+    }
+    let index_dtype = DType::new(NpyType::LongLong);
+    let len = vec![firsts.len() as i64];
+    Ok(UniqueAll {
+        values: values.gather(&firsts).into_array(len.clone(), DType::new(canonical_type(x.dtype().type_())))?,
+        indices: Values::Int(firsts.iter().map(|&i| i as i64).collect()).into_array(len.clone(), index_dtype.clone())?,
+        inverse_indices: Values::Int(inverse).into_array(x.shape().to_vec(), index_dtype.clone())?,
+        counts: Values::Int(counts).into_array(len, index_dtype)?,
+    })
+}
+
+/// Unique values of `x` and the number of times each occurs
+pub fn unique_counts(x: &Array) -> Result<(Array, Array), ArrayApiError> {
+    let unique = unique_all(x)?;
+    Ok((unique.values, unique.counts))
+}
+
+/// Unique values of `x` and the index into them of each element of `x`
+pub fn unique_inverse(x: &Array) -> Result<(Array, Array), ArrayApiError> {
+    let unique = unique_all(x)?;
+    Ok((unique.values, unique.inverse_indices))
+}
+
+/// Sorted unique values of `x`
+pub fn unique_values(x: &Array) -> Result<Array, ArrayApiError> {
+    Ok(unique_all(x)?.values)
+}
@@ -0,0 +1,63 @@
+//! Sorting functions of the array API standard
+//!
+//! Sorts work on every array API dtype along any axis. NaNs sort last in
+//! ascending order (first in descending order) and complex values sort by
+//! real part, then imaginary part.
+
+use crate::array::Array;
+use crate::types::{DType, NpyType};
+
+use super::values::{axes_last, read_lanes, unpermute, Values};
+use super::{canonical_type, normalize_axis, ArrayApiError};
+
+/// Sorted positions of each lane of `x` along `axis`, with the values and
+/// the axis order of the lanes
+fn sort_lanes(
+    x: &Array,
+    axis: isize,
+    descending: bool,
+    stable: bool,
+) -> Result<(Values, Vec<usize>, usize, Vec<usize>), ArrayApiError> {
+    let axis = normalize_axis(axis, x.ndim())?;
+    let (values, len) = read_lanes(x, &[axis])?;
+    let count = values.len().checked_div(len).unwrap_or(0);
+    let order = |&i: &usize, &j: &usize| {
+        let ordering = values.compare(i, j);
+        if descending { ordering.reverse() } else { ordering }
+    };
+    let mut positions: Vec<usize> = (0..values.len()).collect();
+    for lane in 0..count {
+        let lane = &mut positions[lane * len..(lane + 1) * len];
+        // Reversing the comparison keeps equal elements in their original
+        // order, as the standard asks of stable descending sorts
+        if stable {
+            lane.sort_by(order);
+        } else {
+            lane.sort_unstable_by(order);
+        }
+    }
+    Ok((values, positions, len, axes_last(x.ndim(), &[axis])))
+}
+
+/// Sorted copy of `x` along `axis`
+pub fn sort(x: &Array, axis: isize, descending: bool, stable: bool) -> Result<Array, ArrayApiError> {
+    let (values, positions, _, perm) = sort_lanes(x, axis, descending, stable)?;
+    let shape = perm.iter().map(|&a| x.shape()[a]).collect();
+    let sorted = values.gather(&positions).into_array(shape, DType::new(canonical_type(x.dtype().type_())))?;
+    unpermute(sorted, &perm)
+}
+
+/// Indices that sort `x` along `axis`, as `int64`
+pub fn argsort(x: &Array, axis: isize, descending: bool, stable: bool) -> Result<Array, ArrayApiError> {
+    let (_, positions, len, perm) = sort_lanes(x, axis, descending, stable)?;
+    let indices = positions.into_iter().map(|p| (p % len.max(1)) as i64).collect();
+    let shape = perm.iter().map(|&a| x.shape()[a]).collect();
+    unpermute(Values::Int(indices).into_array(shape, DType::new(NpyType::LongLong))?, &perm)
+}
+
+/// Positions of `values` in sorted order, ties broken by position
+pub(crate) fn stable_order(values: &Values) -> Vec<usize> {
+    let mut positions: Vec<usize> = (0..values.len()).collect();
+    positions.sort_by(|&i, &j| values.compare(i, j).then(i.cmp(&j)));
+    positions
+}
@@ -0,0 +1,251 @@
+//! Statistical and utility functions of the array API standard
+//!
+//! Reductions take an optional list of axes (`None` reduces every axis)
+//! and a `keepdims` flag. Sums and products of integers accumulate in the
+//! 64-bit integer of the same signedness unless a dtype is given.
+
+use crate::array::Array;
+use crate::types::{Complex128, DType, NpyType};
+
+use super::values::{axes_last, lanes, read_lanes, reduced_shape, unpermute, Values};
+use super::{canonical_type, dtype_kind, normalize_axes, normalize_axis, ArrayApiError, DTypeKind};
+
+/// Accumulator dtype of `sum`, `prod` and `cumulative_sum`
+fn accumulator_dtype(x: &Array, dtype: Option<&DType>) -> Result<DType, ArrayApiError> {
+    if let Some(dtype) = dtype {
+        dtype_kind(dtype)?;
+        return Ok(DType::new(canonical_type(dtype.type_())));
+    }
+    Ok(match dtype_kind(x.dtype())? {
+        DTypeKind::Bool | DTypeKind::SignedInteger => DType::new(NpyType::LongLong),
+        DTypeKind::UnsignedInteger => DType::new(NpyType::ULongLong),
+        _ => DType::new(canonical_type(x.dtype().type_())),
+    })
+}
+
+/// Values of `x` in lanes along `axis`, with the result shape and lane count
+fn reduction_lanes(
+    x: &Array,
+    axis: Option<&[isize]>,
+    keepdims: bool,
+) -> Result<(Values, usize, usize, Vec<i64>), ArrayApiError> {
+    let axes = normalize_axes(axis, x.ndim())?;
+    let (values, lane_len) = read_lanes(x, &axes)?;
+    let shape = reduced_shape(x.shape(), &axes, keepdims);
+    let count = shape.iter().product::<i64>() as usize;
+    Ok((values, count, lane_len, shape))
+}
+
+macro_rules! fold_lanes {
+    ($values:expr, $count:expr, $len:expr, {
+        $($variant:ident => $out:ident($f:expr),)*
+    }) => {
+        match $values {
+            $(Values::$variant(v) => Values::$out(lanes(&v, $count, $len).map($f).collect()),)*
+            #[allow(unreachable_patterns)]
+            _ => unreachable!("values converted to the accumulator kind"),
+        }
+    };
+}
+
+/// Sum of the elements over `axis`
+pub fn sum(x: &Array, axis: Option<&[isize]>, dtype: Option<&DType>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    let out = accumulator_dtype(x, dtype)?;
+    let (values, count, len, shape) = reduction_lanes(x, axis, keepdims)?;
+    let result = fold_lanes!(values.convert(dtype_kind(&out)?), count, len, {
+        Bool => Bool(|lane: &[bool]| lane.iter().any(|&b| b)),
+        Int => Int(|lane: &[i64]| lane.iter().fold(0i64, |acc, &x| acc.wrapping_add(x))),
+        UInt => UInt(|lane: &[u64]| lane.iter().fold(0u64, |acc, &x| acc.wrapping_add(x))),
+        Float => Float(|lane: &[f64]| lane.iter().sum()),
+        Complex => Complex(|lane: &[Complex128]| lane.iter().fold(Complex128::new(0.0, 0.0), |acc, &z| acc + z)),
+    });
+    result.into_array(shape, out)
+}
+
+/// Product of the elements over `axis`
+pub fn prod(x: &Array, axis: Option<&[isize]>, dtype: Option<&DType>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    let out = accumulator_dtype(x, dtype)?;
+    let (values, count, len, shape) = reduction_lanes(x, axis, keepdims)?;
+    let result = fold_lanes!(values.convert(dtype_kind(&out)?), count, len, {
+        Bool => Bool(|lane: &[bool]| lane.iter().all(|&b| b)),
+        Int => Int(|lane: &[i64]| lane.iter().fold(1i64, |acc, &x| acc.wrapping_mul(x))),
+        UInt => UInt(|lane: &[u64]| lane.iter().fold(1u64, |acc, &x| acc.wrapping_mul(x))),
+        Float => Float(|lane: &[f64]| lane.iter().product()),
+        Complex => Complex(|lane: &[Complex128]| lane.iter().fold(Complex128::new(1.0, 0.0), |acc, &z| acc * z)),
+    });
+    result.into_array(shape, out)
+}
+
+fn extremum(x: &Array, axis: Option<&[isize]>, keepdims: bool, name: &'static str) -> Result<Array, ArrayApiError> {
+    let kind = dtype_kind(x.dtype())?;
+    if kind == DTypeKind::ComplexFloating {
+        return Err(ArrayApiError::UnsupportedDtype(format!("{} is not defined for {}", name, x.dtype().name())));
+    }
+    let (values, count, len, shape) = reduction_lanes(x, axis, keepdims)?;
+    if len == 0 && count > 0 {
+        return Err(ArrayApiError::EmptyReduction(name));
+    }
+    let is_max = name == "max";
+    let result = fold_lanes!(values, count, len, {
+        Bool => Bool(|lane: &[bool]| if is_max { lane.iter().any(|&b| b) } else { lane.iter().all(|&b| b) }),
+        Int => Int(|lane: &[i64]| if is_max { *lane.iter().max().unwrap() } else { *lane.iter().min().unwrap() }),
+        UInt => UInt(|lane: &[u64]| if is_max { *lane.iter().max().unwrap() } else { *lane.iter().min().unwrap() }),
+        Float => Float(|lane: &[f64]| lane.iter().copied().fold(lane[0], |acc, x| {
+            if acc.is_nan() || x.is_nan() {
+                f64::NAN
+            } else if is_max {
+                acc.max(x)
+            } else {
+                acc.min(x)
+            }
+        })),
+    });
+    result.into_array(shape, DType::new(canonical_type(x.dtype().type_())))
+}
+
+/// Largest element over `axis`, propagating NaNs
+pub fn max(x: &Array, axis: Option<&[isize]>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    extremum(x, axis, keepdims, "max")
+}
+
+/// Smallest element over `axis`, propagating NaNs
+pub fn min(x: &Array, axis: Option<&[isize]>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    extremum(x, axis, keepdims, "min")
+}
+
+/// Floating dtype of `mean`, `var` and `std` (integers use `float64`)
+fn floating_result(x: &Array) -> Result<DType, ArrayApiError> {
+    Ok(match dtype_kind(x.dtype())? {
+        DTypeKind::Bool => {
+            return Err(ArrayApiError::UnsupportedDtype("statistics are not defined for bool".to_string()))
+        }
+        kind if kind.is_floating() => DType::new(canonical_type(x.dtype().type_())),
+        _ => DType::new(NpyType::Double),
+    })
+}
+
+/// Arithmetic mean over `axis`; the mean of no elements is NaN
+pub fn mean(x: &Array, axis: Option<&[isize]>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    let out = floating_result(x)?;
+    let (values, count, len, shape) = reduction_lanes(x, axis, keepdims)?;
+    let n = len as f64;
+    let result = fold_lanes!(values.convert(dtype_kind(&out)?), count, len, {
+        Float => Float(|lane: &[f64]| lane.iter().sum::<f64>() / n),
+        Complex => Complex(|lane: &[Complex128]| {
+            let total = lane.iter().fold(Complex128::new(0.0, 0.0), |acc, &z| acc + z);
+            Complex128::new(total.re / n, total.im / n)
+        }),
+    });
+    result.into_array(shape, out)
+}
+
+/// Variance over `axis` with `correction` degrees of freedom removed
+///
+/// Complex inputs give the real variance of their magnitudes. A divisor
+/// `N - correction` at or below zero gives NaN or infinity, as in NumPy.
+pub fn var(x: &Array, axis: Option<&[isize]>, correction: f64, keepdims: bool) -> Result<Array, ArrayApiError> {
+    let out = floating_result(x)?;
+    let (values, count, len, shape) = reduction_lanes(x, axis, keepdims)?;
+    let divisor = (len as f64 - correction).max(0.0);
+    let result = fold_lanes!(values.convert(dtype_kind(&out)?), count, len, {
+        Float => Float(|lane: &[f64]| {
+            let mean = lane.iter().sum::<f64>() / len as f64;
+            lane.iter().map(|&x| (x - mean) * (x - mean)).sum::<f64>() / divisor
+        }),
+        Complex => Float(|lane: &[Complex128]| {
+            let total = lane.iter().fold(Complex128::new(0.0, 0.0), |acc, &z| acc + z);
+            let mean = Complex128::new(total.re / len as f64, total.im / len as f64);
+            lane.iter().map(|&z| (z - mean).norm_sqr()).sum::<f64>() / divisor
+        }),
+    });
+    let out = match out.type_() {
+        NpyType::CFloat => DType::new(NpyType::Float),
+        NpyType::CDouble => DType::new(NpyType::Double),
+        _ => out,
+    };
+    result.into_array(shape, out)
+}
+
+/// Standard deviation over `axis`; see [`var`]
+pub fn std(x: &Array, axis: Option<&[isize]>, correction: f64, keepdims: bool) -> Result<Array, ArrayApiError> {
+    let variance = var(x, axis, correction, keepdims)?;
+    let dtype = variance.dtype().clone();
+    let Values::Float(v) = Values::read(&variance)? else {
+        unreachable!("variance is real")
+    };
+    Values::Float(v.into_iter().map(f64::sqrt).collect()).into_array(variance.shape().to_vec(), dtype)
+}
+
+/// Cumulative sum along `axis`
+///
+/// `axis` may be omitted only for arrays of at most one dimension. With
+/// `include_initial` the result starts with a zero, so it is one longer
+/// along `axis`.
+pub fn cumulative_sum(
+    x: &Array,
+    axis: Option<isize>,
+    dtype: Option<&DType>,
+    include_initial: bool,
+) -> Result<Array, ArrayApiError> {
+    let out = accumulator_dtype(x, dtype)?;
+    if x.ndim() == 0 {
+        return Err(ArrayApiError::InvalidArgument("cumulative_sum requires at least one dimension".to_string()));
+    }
+    let axis = match axis {
+        Some(axis) => normalize_axis(axis, x.ndim())?,
+        None if x.ndim() == 1 => 0,
+        None => {
+            return Err(ArrayApiError::InvalidArgument(
+                "axis must be given for arrays of more than one dimension".to_string(),
+            ))
+        }
+    };
+    let (values, len) = read_lanes(x, &[axis])?;
+    let perm = axes_last(x.ndim(), &[axis]);
+    let mut shape: Vec<i64> = perm.iter().map(|&a| x.shape()[a]).collect();
+    let count = shape[..shape.len() - 1].iter().product::<i64>() as usize;
+    *shape.last_mut().unwrap() += include_initial as i64;
+
+    macro_rules! scan {
+        ($v:expr, $zero:expr, $add:expr) => {{
+            let mut result = Vec::with_capacity(count * (len + include_initial as usize));
+            for lane in lanes(&$v, count, len) {
+                let mut acc = $zero;
+                if include_initial {
+                    result.push(acc);
+                }
+                for &x in lane {
+                    acc = $add(acc, x);
+                    result.push(acc);
+                }
+            }
+            result
+        }};
+    }
+    let result = match values.convert(dtype_kind(&out)?) {
+        Values::Bool(v) => Values::Bool(scan!(v, false, |a: bool, b: bool| a | b)),
+        Values::Int(v) => Values::Int(scan!(v, 0i64, i64::wrapping_add)),
+        Values::UInt(v) => Values::UInt(scan!(v, 0u64, u64::wrapping_add)),
+        Values::Float(v) => Values::Float(scan!(v, 0.0f64, |a: f64, b: f64| a + b)),
+        Values::Complex(v) => Values::Complex(scan!(v, Complex128::new(0.0, 0.0), |a: Complex128, b| a + b)),
+    };
+    unpermute(result.into_array(shape, out)?, &perm)
+}
+
+/// Whether every element over `axis` is nonzero
+pub fn all(x: &Array, axis: Option<&[isize]>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    let (values, count, len, shape) = reduction_lanes(x, axis, keepdims)?;
+    let result = fold_lanes!(values.convert(DTypeKind::Bool), count, len, {
+        Bool => Bool(|lane: &[bool]| lane.iter().all(|&b| b)),
+    });
+    result.into_array(shape, DType::new(NpyType::Bool))
+}
+
+/// Whether any element over `axis` is nonzero
+pub fn any(x: &Array, axis: Option<&[isize]>, keepdims: bool) -> Result<Array, ArrayApiError> {
+    let (values, count, len, shape) = reduction_lanes(x, axis, keepdims)?;
+    let result = fold_lanes!(values.convert(DTypeKind::Bool), count, len, {
+        Bool => Bool(|lane: &[bool]| lane.iter().any(|&b| b)),
+    });
+    result.into_array(shape, DType::new(NpyType::Bool))
+}
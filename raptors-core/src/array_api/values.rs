@@ -0,0 +1,434 @@
+//! Element values of arrays, widened to one Rust type per dtype kind
+//!
+//! Kernels in this module read their inputs into `Values`, compute in
+//! `i64`, `u64`, `f64` or `Complex128`, and write the result back with
+//! NumPy's casting semantics: integers wrap, floats truncate toward zero
+//! and complex values keep their real part.
+
+use std::cmp::Ordering;
+
+use crate::array::{Array, Scalar};
+use crate::types::{Complex, Complex128, DType, NpyType};
+use crate::utils::element_offsets;
+
+use super::{canonical_type, dtype_kind, ArrayApiError, DTypeKind};
+
+/// Elements of an array in C order
+#[derive(Debug, Clone)]
+pub(crate) enum Values {
+    Bool(Vec<bool>),
+    Int(Vec<i64>),
+    UInt(Vec<u64>),
+    Float(Vec<f64>),
+    Complex(Vec<Complex128>),
+}
+
+unsafe fn gather<T: Copy, U>(array: &Array, f: impl Fn(T) -> U) -> Vec<U> {
+    let base = array.data_ptr();
+    element_offsets(array.shape(), array.strides())
+        .into_iter()
+        .map(|offset| f(std::ptr::read_unaligned(base.offset(offset) as *const T)))
+        .collect()
+}
+
+unsafe fn store<T>(array: &mut Array, values: impl Iterator<Item = T>) {
+    let base = array.data_ptr_mut() as *mut T;
+    for (i, value) in values.enumerate() {
+        std::ptr::write_unaligned(base.add(i), value);
+    }
+}
+
+impl Values {
+    /// Read every element of `array`, in C order
+    pub(crate) fn read(array: &Array) -> Result<Values, ArrayApiError> {
+        dtype_kind(array.dtype())?;
+        // SAFETY: each read is of the element type at an offset of the array
+        unsafe {
+            Ok(match canonical_type(array.dtype().type_()) {
+                NpyType::Bool => Values::Bool(gather(array, |b: u8| b != 0)),
+                NpyType::Byte => Values::Int(gather(array, |x: i8| x as i64)),
+                NpyType::Short => Values::Int(gather(array, |x: i16| x as i64)),
+                NpyType::Int => Values::Int(gather(array, |x: i32| x as i64)),
+                NpyType::LongLong => Values::Int(gather(array, |x: i64| x)),
+                NpyType::UByte => Values::UInt(gather(array, |x: u8| x as u64)),
+                NpyType::UShort => Values::UInt(gather(array, |x: u16| x as u64)),
+                NpyType::UInt => Values::UInt(gather(array, |x: u32| x as u64)),
+                NpyType::ULongLong => Values::UInt(gather(array, |x: u64| x)),
+                NpyType::Float => Values::Float(gather(array, |x: f32| x as f64)),
+                NpyType::Double => Values::Float(gather(array, |x: f64| x)),
+                NpyType::CFloat => Values::Complex(gather(array, |z: Complex<f32>| z.to_complex128())),
+                NpyType::CDouble => Values::Complex(gather(array, |z: Complex128| z)),
+                _ => return Err(ArrayApiError::UnsupportedDtype(array.dtype().name().to_string())),
+            })
+        }
+    }
+
+    /// Read every element of `array` broadcast to `shape`
+    pub(crate) fn read_broadcast(array: &Array, shape: &[i64]) -> Result<Values, ArrayApiError> {
+        if array.shape() == shape {
+            return Values::read(array);
+        }
+        Values::read(&broadcast_view(array, shape)?)
+    }
+
+    /// Values of Python-like scalars, converted to `kind`
+    pub(crate) fn from_scalars(scalars: &[Scalar], kind: DTypeKind) -> Result<Values, ArrayApiError> {
+        let complex = scalars
+            .iter()
+            .map(|s| match s {
+                Scalar::Bool(b) => Ok(Complex128::new(*b as u8 as f64, 0.0)),
+                Scalar::Int(i) => Ok(Complex128::new(*i as f64, 0.0)),
+                Scalar::UInt(u) => Ok(Complex128::new(*u as f64, 0.0)),
+                Scalar::Float(f) => Ok(Complex128::new(*f, 0.0)),
+                Scalar::Complex(z) => Ok(*z),
+                Scalar::Str(s) => Err(ArrayApiError::InvalidArgument(format!("cannot convert '{}' to a number", s))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Integers go through exact paths so 64-bit values keep every bit
+        Ok(match kind {
+            DTypeKind::Bool => Values::Bool(complex.iter().map(|z| z.re != 0.0 || z.im != 0.0).collect()),
+            DTypeKind::SignedInteger | DTypeKind::UnsignedInteger => {
+                let ints = scalars.iter().zip(&complex).map(|(s, z)| match s {
+                    Scalar::Int(i) => *i as i128,
+                    Scalar::UInt(u) => *u as i128,
+                    Scalar::Bool(b) => *b as i128,
+                    _ => z.re as i128,
+                });
+                if kind == DTypeKind::SignedInteger {
+                    Values::Int(ints.map(|i| i as i64).collect())
+                } else {
+                    Values::UInt(ints.map(|i| i as u64).collect())
+                }
+            }
+            DTypeKind::RealFloating => Values::Float(complex.iter().map(|z| z.re).collect()),
+            DTypeKind::ComplexFloating => Values::Complex(complex),
+        })
+    }
+
+    /// Number of elements
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Values::Bool(v) => v.len(),
+            Values::Int(v) => v.len(),
+            Values::UInt(v) => v.len(),
+            Values::Float(v) => v.len(),
+            Values::Complex(v) => v.len(),
+        }
+    }
+
+    /// Kind of the values
+    pub(crate) fn kind(&self) -> DTypeKind {
+        match self {
+            Values::Bool(_) => DTypeKind::Bool,
+            Values::Int(_) => DTypeKind::SignedInteger,
+            Values::UInt(_) => DTypeKind::UnsignedInteger,
+            Values::Float(_) => DTypeKind::RealFloating,
+            Values::Complex(_) => DTypeKind::ComplexFloating,
+        }
+    }
+
+    /// Values cast to `kind`
+    pub(crate) fn convert(self, kind: DTypeKind) -> Values {
+        if self.kind() == kind {
+            return self;
+        }
+        macro_rules! map {
+            ($v:expr, $f:expr) => {
+                $v.into_iter().map($f).collect()
+            };
+        }
+        match (self, kind) {
+            (Values::Bool(v), DTypeKind::SignedInteger) => Values::Int(map!(v, |b| b as i64)),
+            (Values::Bool(v), DTypeKind::UnsignedInteger) => Values::UInt(map!(v, |b| b as u64)),
+            (Values::Bool(v), DTypeKind::RealFloating) => Values::Float(map!(v, |b| b as u8 as f64)),
+            (Values::Bool(v), DTypeKind::ComplexFloating) => {
+                Values::Complex(map!(v, |b| Complex128::new(b as u8 as f64, 0.0)))
+            }
+            (Values::Int(v), DTypeKind::Bool) => Values::Bool(map!(v, |x| x != 0)),
+            (Values::Int(v), DTypeKind::UnsignedInteger) => Values::UInt(map!(v, |x| x as u64)),
+            (Values::Int(v), DTypeKind::RealFloating) => Values::Float(map!(v, |x| x as f64)),
+            (Values::Int(v), DTypeKind::ComplexFloating) => Values::Complex(map!(v, |x| Complex128::new(x as f64, 0.0))),
+            (Values::UInt(v), DTypeKind::Bool) => Values::Bool(map!(v, |x| x != 0)),
+            (Values::UInt(v), DTypeKind::SignedInteger) => Values::Int(map!(v, |x| x as i64)),
+            (Values::UInt(v), DTypeKind::RealFloating) => Values::Float(map!(v, |x| x as f64)),
+            (Values::UInt(v), DTypeKind::ComplexFloating) => {
+                Values::Complex(map!(v, |x| Complex128::new(x as f64, 0.0)))
+            }
+            (Values::Float(v), DTypeKind::Bool) => Values::Bool(map!(v, |x| x != 0.0)),
+            (Values::Float(v), DTypeKind::SignedInteger) => Values::Int(map!(v, |x| x as i64)),
+            (Values::Float(v), DTypeKind::UnsignedInteger) => Values::UInt(map!(v, float_to_u64)),
+            (Values::Float(v), DTypeKind::ComplexFloating) => Values::Complex(map!(v, |x| Complex128::new(x, 0.0))),
+            (Values::Complex(v), DTypeKind::Bool) => Values::Bool(map!(v, |z| z.re != 0.0 || z.im != 0.0)),
+            (Values::Complex(v), DTypeKind::SignedInteger) => Values::Int(map!(v, |z| z.re as i64)),
+            (Values::Complex(v), DTypeKind::UnsignedInteger) => Values::UInt(map!(v, |z| float_to_u64(z.re))),
+            (Values::Complex(v), DTypeKind::RealFloating) => Values::Float(map!(v, |z| z.re)),
+            (values, _) => values,
+        }
+    }
+
+    /// Elements at `indices`
+    pub(crate) fn gather(&self, indices: &[usize]) -> Values {
+        macro_rules! pick {
+            ($variant:ident, $v:expr) => {
+                Values::$variant(indices.iter().map(|&i| $v[i]).collect())
+            };
+        }
+        match self {
+            Values::Bool(v) => pick!(Bool, v),
+            Values::Int(v) => pick!(Int, v),
+            Values::UInt(v) => pick!(UInt, v),
+            Values::Float(v) => pick!(Float, v),
+            Values::Complex(v) => pick!(Complex, v),
+        }
+    }
+
+    /// Compare elements `i` and `j` in ascending sort order
+    ///
+    /// NaNs sort after every other value; complex values compare by real
+    /// part, then imaginary part.
+    pub(crate) fn compare(&self, i: usize, j: usize) -> Ordering {
+        self.compare_with(i, self, j)
+    }
+
+    /// Compare element `i` with element `j` of `other`, which must have the
+    /// same kind
+    pub(crate) fn compare_with(&self, i: usize, other: &Values, j: usize) -> Ordering {
+        match (self, other) {
+            (Values::Bool(a), Values::Bool(b)) => a[i].cmp(&b[j]),
+            (Values::Int(a), Values::Int(b)) => a[i].cmp(&b[j]),
+            (Values::UInt(a), Values::UInt(b)) => a[i].cmp(&b[j]),
+            (Values::Float(a), Values::Float(b)) => compare_f64(a[i], b[j]),
+            (Values::Complex(a), Values::Complex(b)) => {
+                compare_f64(a[i].re, b[j].re).then(compare_f64(a[i].im, b[j].im))
+            }
+            _ => unreachable!("values of different kinds"),
+        }
+    }
+
+    /// Whether element `i` is NaN (or has a NaN component)
+    pub(crate) fn is_nan(&self, i: usize) -> bool {
+        match self {
+            Values::Float(v) => v[i].is_nan(),
+            Values::Complex(v) => v[i].re.is_nan() || v[i].im.is_nan(),
+            _ => false,
+        }
+    }
+
+    /// Whether element `i` is nonzero
+    pub(crate) fn is_nonzero(&self, i: usize) -> bool {
+        match self {
+            Values::Bool(v) => v[i],
+            Values::Int(v) => v[i] != 0,
+            Values::UInt(v) => v[i] != 0,
+            Values::Float(v) => v[i] != 0.0,
+            Values::Complex(v) => v[i].re != 0.0 || v[i].im != 0.0,
+        }
+    }
+
+    /// Concatenate values of the same kind
+    pub(crate) fn concat(parts: Vec<Values>, kind: DTypeKind) -> Values {
+        let mut result = Values::from_scalars(&[], kind).expect("no scalars to convert");
+        for part in parts {
+            match (&mut result, part.convert(kind)) {
+                (Values::Bool(r), Values::Bool(v)) => r.extend(v),
+                (Values::Int(r), Values::Int(v)) => r.extend(v),
+                (Values::UInt(r), Values::UInt(v)) => r.extend(v),
+                (Values::Float(r), Values::Float(v)) => r.extend(v),
+                (Values::Complex(r), Values::Complex(v)) => r.extend(v),
+                _ => unreachable!("parts converted to one kind"),
+            }
+        }
+        result
+    }
+
+    /// Whether elements `i` and `j` are equal (NaNs are never equal)
+    pub(crate) fn equal(&self, i: usize, j: usize) -> bool {
+        match self {
+            Values::Bool(v) => v[i] == v[j],
+            Values::Int(v) => v[i] == v[j],
+            Values::UInt(v) => v[i] == v[j],
+            Values::Float(v) => v[i] == v[j],
+            Values::Complex(v) => v[i] == v[j],
+        }
+    }
+
+    /// Write the values into a new C-contiguous array of `shape` and `dtype`
+    pub(crate) fn into_array(self, shape: Vec<i64>, dtype: DType) -> Result<Array, ArrayApiError> {
+        let kind = dtype_kind(&dtype)?;
+        let mut array = Array::new(shape, dtype)?;
+        debug_assert_eq!(array.size(), self.len());
+        let ty = canonical_type(array.dtype().type_());
+        // SAFETY: the array holds `len` elements of the type written
+        unsafe {
+            match (ty, self.convert(kind)) {
+                (NpyType::Bool, Values::Bool(v)) => store(&mut array, v.into_iter().map(|b| b as u8)),
+                (NpyType::Byte, Values::Int(v)) => store(&mut array, v.into_iter().map(|x| x as i8)),
+                (NpyType::Short, Values::Int(v)) => store(&mut array, v.into_iter().map(|x| x as i16)),
+                (NpyType::Int, Values::Int(v)) => store(&mut array, v.into_iter().map(|x| x as i32)),
+                (NpyType::LongLong, Values::Int(v)) => store(&mut array, v.into_iter()),
+                (NpyType::UByte, Values::UInt(v)) => store(&mut array, v.into_iter().map(|x| x as u8)),
+                (NpyType::UShort, Values::UInt(v)) => store(&mut array, v.into_iter().map(|x| x as u16)),
+                (NpyType::UInt, Values::UInt(v)) => store(&mut array, v.into_iter().map(|x| x as u32)),
+                (NpyType::ULongLong, Values::UInt(v)) => store(&mut array, v.into_iter()),
+                (NpyType::Float, Values::Float(v)) => store(&mut array, v.into_iter().map(|x| x as f32)),
+                (NpyType::Double, Values::Float(v)) => store(&mut array, v.into_iter()),
+                (NpyType::CFloat, Values::Complex(v)) => store(&mut array, v.into_iter().map(|z| z.to_complex64())),
+                (NpyType::CDouble, Values::Complex(v)) => store(&mut array, v.into_iter()),
+                _ => unreachable!("values converted to the dtype's kind"),
+            }
+        }
+        Ok(array)
+    }
+}
+
+/// Float to `u64` with NumPy's wrapping for negative values
+fn float_to_u64(x: f64) -> u64 {
+    if x < 0.0 {
+        x as i64 as u64
+    } else {
+        x as u64
+    }
+}
+
+/// Total order on floats with NaNs last
+pub(crate) fn compare_f64(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+/// View of `array` broadcast to `shape`
+///
+/// The view borrows the data of `array`, which must outlive it.
+pub(crate) fn broadcast_view(array: &Array, shape: &[i64]) -> Result<Array, ArrayApiError> {
+    let error = || ArrayApiError::BroadcastError(array.shape().to_vec(), shape.to_vec());
+    if array.ndim() > shape.len() {
+        return Err(error());
+    }
+    let lead = shape.len() - array.ndim();
+    let mut strides = vec![0i64; shape.len()];
+    for (axis, (&dim, &stride)) in array.shape().iter().zip(array.strides()).enumerate() {
+        let target = shape[lead + axis];
+        if dim == target {
+            strides[lead + axis] = stride;
+        } else if dim != 1 {
+            return Err(error());
+        }
+    }
+    Ok(array.view(shape.to_vec(), strides)?)
+}
+
+/// Broadcast shape of several shapes
+pub(crate) fn broadcast_shape(shapes: &[&[i64]]) -> Result<Vec<i64>, ArrayApiError> {
+    let ndim = shapes.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut result = vec![1i64; ndim];
+    for shape in shapes {
+        let lead = ndim - shape.len();
+        for (axis, &dim) in shape.iter().enumerate() {
+            let current = &mut result[lead + axis];
+            if *current == 1 {
+                *current = dim;
+            } else if dim != 1 && dim != *current {
+                return Err(ArrayApiError::BroadcastError(shapes[0].to_vec(), shape.to_vec()));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// View of `array` with its axes reordered so that axis `i` of the view is
+/// axis `perm[i]` of `array`
+///
+/// The view borrows the data of `array`, which must outlive it.
+pub(crate) fn permuted_view(array: &Array, perm: &[usize]) -> Result<Array, ArrayApiError> {
+    let shape = perm.iter().map(|&axis| array.shape()[axis]).collect();
+    let strides = perm.iter().map(|&axis| array.strides()[axis]).collect();
+    Ok(array.view(shape, strides)?)
+}
+
+/// Axis order that moves `axes` to the end, keeping the other axes in order
+pub(crate) fn axes_last(ndim: usize, axes: &[usize]) -> Vec<usize> {
+    let mut perm: Vec<usize> = (0..ndim).filter(|axis| !axes.contains(axis)).collect();
+    perm.extend_from_slice(axes);
+    perm
+}
+
+/// Elements of `array` grouped into lanes along `axes`
+///
+/// Returns the values in C order of `array` with `axes` moved to the end,
+/// and the length of each lane (the product of the `axes` dimensions).
+pub(crate) fn read_lanes(array: &Array, axes: &[usize]) -> Result<(Values, usize), ArrayApiError> {
+    let lane_len = axes.iter().map(|&axis| array.shape()[axis] as usize).product();
+    let view = permuted_view(array, &axes_last(array.ndim(), axes))?;
+    Ok((Values::read(&view)?, lane_len))
+}
+
+/// Consecutive slices of `len` elements, `count` of them
+pub(crate) fn lanes<T>(values: &[T], count: usize, len: usize) -> impl Iterator<Item = &[T]> {
+    (0..count).map(move |i| &values[i * len..(i + 1) * len])
+}
+
+/// Shape of `shape` reduced over `axes`
+pub(crate) fn reduced_shape(shape: &[i64], axes: &[usize], keepdims: bool) -> Vec<i64> {
+    shape
+        .iter()
+        .enumerate()
+        .filter_map(|(axis, &dim)| match (axes.contains(&axis), keepdims) {
+            (false, _) => Some(dim),
+            (true, true) => Some(1),
+            (true, false) => None,
+        })
+        .collect()
+}
+
+/// C-contiguous copy of `array` with its axes put back in order, where
+/// axis `i` of `array` is axis `perm[i]` of the result
+pub(crate) fn unpermute(array: Array, perm: &[usize]) -> Result<Array, ArrayApiError> {
+    if perm.iter().enumerate().all(|(i, &axis)| i == axis) {
+        return Ok(array);
+    }
+    let mut inverse = vec![0; perm.len()];
+    for (i, &axis) in perm.iter().enumerate() {
+        inverse[axis] = i;
+    }
+    Ok(permuted_view(&array, &inverse)?.copy())
+}
+
+/// C-order strides, in elements, of `shape`
+pub(crate) fn element_strides(shape: &[i64]) -> Vec<usize> {
+    let mut strides = vec![1usize; shape.len()];
+    for axis in (0..shape.len().saturating_sub(1)).rev() {
+        strides[axis] = strides[axis + 1] * shape[axis + 1] as usize;
+    }
+    strides
+}
+
+/// Values of an array of `shape` where element `coords` is element
+/// `source(coords)` of `values`, laid out in C order over `source_shape`
+pub(crate) fn remap(
+    values: &Values,
+    source_shape: &[i64],
+    shape: &[i64],
+    source: impl Fn(&[i64], &mut [i64]),
+) -> Values {
+    let source_strides = element_strides(source_shape);
+    let size: i64 = shape.iter().product();
+    let mut coords = vec![0i64; shape.len()];
+    let mut from = vec![0i64; source_shape.len()];
+    let mut indices = Vec::with_capacity(size.max(0) as usize);
+    for _ in 0..size {
+        source(&coords, &mut from);
+        indices.push(from.iter().zip(&source_strides).map(|(&c, &s)| c as usize * s).sum());
+        for axis in (0..shape.len()).rev() {
+            coords[axis] += 1;
+            if coords[axis] < shape[axis] {
+                break;
+            }
+            coords[axis] = 0;
+        }
+    }
+    values.gather(&indices)
+}
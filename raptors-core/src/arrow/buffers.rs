@@ -0,0 +1,204 @@
+//! Conversion between array elements and Arrow buffers
+//!
+//! Shared by the C Data Interface and the IPC format. Values are taken in
+//! C order; validity bitmaps and boolean values are packed LSB first, with
+//! a set validity bit meaning the slot is valid.
+
+use super::datatype::{ArrowError, ArrowType};
+use crate::array::Array;
+use crate::types::{DType, NpyType};
+
+/// An Arrow buffer, either pointing into an array's memory or built from it
+pub(crate) enum Buffer {
+    /// `len` bytes of memory owned by an array
+    Borrowed(*const u8, usize),
+    /// Bytes converted from the array's elements
+    Owned(Vec<u8>),
+}
+
+impl Buffer {
+    pub(crate) fn as_ptr(&self) -> *const u8 {
+        match self {
+            Buffer::Borrowed(ptr, _) => *ptr,
+            Buffer::Owned(bytes) => bytes.as_ptr(),
+        }
+    }
+
+    /// The buffer contents; borrowed buffers must point into a live array
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        match self {
+            Buffer::Borrowed(_, 0) => &[],
+            Buffer::Borrowed(ptr, len) => unsafe { std::slice::from_raw_parts(*ptr, *len) },
+            Buffer::Owned(bytes) => bytes,
+        }
+    }
+}
+
+fn invalid(msg: impl Into<String>) -> ArrowError {
+    ArrowError::InvalidData(msg.into())
+}
+
+/// Whether bit `i` of an LSB-first bitmap is set
+pub(crate) fn get_bit(bitmap: &[u8], i: usize) -> bool {
+    bitmap[i / 8] & (1 << (i % 8)) != 0
+}
+
+/// Pack booleans into an LSB-first bitmap
+pub(crate) fn pack_bits(bits: impl Iterator<Item = bool>) -> Vec<u8> {
+    let mut bitmap = Vec::new();
+    for (i, bit) in bits.enumerate() {
+        if i % 8 == 0 {
+            bitmap.push(0);
+        }
+        if bit {
+            bitmap[i / 8] |= 1 << (i % 8);
+        }
+    }
+    bitmap
+}
+
+/// Validity bitmap for a boolean mask (true = masked) and its null count
+///
+/// Returns no bitmap when nothing is masked, as Arrow allows.
+pub(crate) fn validity_bitmap(mask: &Array) -> Result<(Option<Vec<u8>>, usize), ArrowError> {
+    if mask.dtype().type_() != NpyType::Bool {
+        return Err(invalid("mask must be a boolean array"));
+    }
+    let bytes = crate::utils::to_contiguous_bytes(mask);
+    let nulls = bytes.iter().filter(|&&b| b != 0).count();
+    if nulls == 0 {
+        return Ok((None, 0));
+    }
+    Ok((Some(pack_bits(bytes.iter().map(|&b| b == 0))), nulls))
+}
+
+/// Mask bytes (1 = null) for `len` slots of a validity bitmap from `offset`
+pub(crate) fn unpack_validity(bitmap: &[u8], offset: usize, len: usize) -> Result<Vec<u8>, ArrowError> {
+    if bitmap.len() * 8 < offset + len {
+        return Err(invalid("validity bitmap is too short"));
+    }
+    Ok((offset..offset + len).map(|i| !get_bit(bitmap, i) as u8).collect())
+}
+
+/// Characters of a unicode element, without the trailing NUL padding
+fn ucs4_string(element: &[u8]) -> Result<String, ArrowError> {
+    element
+        .chunks(4)
+        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+        .take_while(|&c| c != 0)
+        .map(|c| char::from_u32(c).ok_or_else(|| invalid(format!("invalid code point {:#x}", c))))
+        .collect()
+}
+
+/// Arrow buffers for the elements of `array`, excluding validity
+///
+/// C-contiguous fixed-width data is borrowed; booleans are packed into a
+/// bitmap and unicode strings are re-encoded as UTF-8 with 32-bit
+/// (`Utf8`) or 64-bit (`LargeUtf8`) offsets.
+pub(crate) fn value_buffers(array: &Array, ty: &ArrowType) -> Result<Vec<Buffer>, ArrowError> {
+    match ty {
+        ArrowType::Boolean => {
+            let bytes = crate::utils::to_contiguous_bytes(array);
+            Ok(vec![Buffer::Owned(pack_bits(bytes.iter().map(|&b| b != 0)))])
+        }
+        ArrowType::Utf8 | ArrowType::LargeUtf8 => {
+            let itemsize = array.itemsize();
+            let bytes = crate::utils::to_contiguous_bytes(array);
+            let large = *ty == ArrowType::LargeUtf8;
+            let mut offsets = Vec::with_capacity((array.size() + 1) * if large { 8 } else { 4 });
+            let mut data = Vec::new();
+            let push_offset = |offsets: &mut Vec<u8>, n: usize| -> Result<(), ArrowError> {
+                if large {
+                    offsets.extend_from_slice(&(n as i64).to_ne_bytes());
+                } else {
+                    let n = i32::try_from(n).map_err(|_| invalid("string data exceeds 2 GiB; use LargeUtf8"))?;
+                    offsets.extend_from_slice(&n.to_ne_bytes());
+                }
+                Ok(())
+            };
+            push_offset(&mut offsets, 0)?;
+            if itemsize > 0 {
+                for element in bytes.chunks(itemsize) {
+                    data.extend_from_slice(ucs4_string(element)?.as_bytes());
+                    push_offset(&mut offsets, data.len())?;
+                }
+            } else {
+                for _ in 0..array.size() {
+                    push_offset(&mut offsets, 0)?;
+                }
+            }
+            Ok(vec![Buffer::Owned(offsets), Buffer::Owned(data)])
+        }
+        ArrowType::FixedSizeList(..) => Err(invalid("fixed-size lists have no values buffer")),
+        _ => {
+            let len = array.size() * array.itemsize();
+            if array.is_c_contiguous() {
+                Ok(vec![Buffer::Borrowed(array.data_ptr(), len)])
+            } else {
+                Ok(vec![Buffer::Owned(crate::utils::to_contiguous_bytes(array))])
+            }
+        }
+    }
+}
+
+/// Build an array of `shape` and `dtype` from raw C-order element bytes
+pub(crate) fn array_from_bytes(bytes: &[u8], shape: Vec<i64>, dtype: DType) -> Result<Array, ArrowError> {
+    if shape.iter().product::<i64>() as usize * dtype.itemsize() != bytes.len() {
+        return Err(invalid("buffer size does not match shape"));
+    }
+    let mut array = Array::new(shape, dtype)?;
+    if !bytes.is_empty() {
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), array.data_ptr_mut(), bytes.len()) };
+    }
+    Ok(array)
+}
+
+/// Boolean element bytes from `len` bits of a bitmap from `offset`
+pub(crate) fn decode_bools(bitmap: &[u8], offset: usize, len: usize) -> Result<Vec<u8>, ArrowError> {
+    if bitmap.len() * 8 < offset + len {
+        return Err(invalid("boolean values buffer is too short"));
+    }
+    Ok((offset..offset + len).map(|i| get_bit(bitmap, i) as u8).collect())
+}
+
+/// Read offset `i` of a string offsets buffer
+pub(crate) fn string_offset(offsets: &[u8], i: usize, large: bool) -> Result<usize, ArrowError> {
+    let width = if large { 8 } else { 4 };
+    let bytes = offsets.get(i * width..(i + 1) * width).ok_or_else(|| invalid("string offsets buffer is too short"))?;
+    let value = if large {
+        i64::from_ne_bytes(bytes.try_into().unwrap())
+    } else {
+        i32::from_ne_bytes(bytes.try_into().unwrap()) as i64
+    };
+    usize::try_from(value).map_err(|_| invalid("negative string offset"))
+}
+
+/// Unicode element bytes for `len` strings of an offsets and data buffer
+/// pair, starting at slot `offset`
+///
+/// The returned dtype is as wide as the longest string (at least one
+/// character).
+pub(crate) fn decode_strings(
+    offsets: &[u8],
+    data: &[u8],
+    offset: usize,
+    len: usize,
+    large: bool,
+) -> Result<(Vec<u8>, DType), ArrowError> {
+    let mut strings = Vec::with_capacity(len);
+    for i in offset..offset + len {
+        let (start, end) = (string_offset(offsets, i, large)?, string_offset(offsets, i + 1, large)?);
+        let bytes = data.get(start..end).ok_or_else(|| invalid("string offsets out of range"))?;
+        let text = std::str::from_utf8(bytes).map_err(|e| invalid(format!("invalid UTF-8 in string {}: {}", i, e)))?;
+        strings.push(text.chars().map(|c| c as u32).collect::<Vec<u32>>());
+    }
+    let width = strings.iter().map(Vec::len).max().unwrap_or(0).max(1);
+    let mut bytes = vec![0u8; len * width * 4];
+    for (i, chars) in strings.iter().enumerate() {
+        for (j, c) in chars.iter().enumerate() {
+            let at = (i * width + j) * 4;
+            bytes[at..at + 4].copy_from_slice(&c.to_ne_bytes());
+        }
+    }
+    Ok((bytes, DType::unicode_with_itemsize(width * 4)))
+}
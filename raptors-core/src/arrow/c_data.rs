@@ -0,0 +1,375 @@
+//! Export and import through the Arrow C Data Interface
+//!
+//! A 1-D array is exported as a primitive Arrow array; each further
+//! dimension adds a fixed-size list level, so a `(n, 3)` array becomes an
+//! array of `n` lists of three values. C-contiguous numeric, datetime and
+//! byte-string data is shared without copying, and the release callback
+//! drops the exporter's reference to the array. Imported fixed-width data
+//! is likewise viewed in place, with the Arrow array released when the
+//! last view of it is dropped.
+
+use super::buffers::{array_from_bytes, decode_bools, decode_strings, unpack_validity, validity_bitmap, value_buffers, Buffer};
+use super::datatype::{ArrowError, ArrowType};
+use super::ffi::{ArrowArray, ArrowSchema, ARROW_FLAG_NULLABLE};
+use crate::array::Array;
+use crate::masked::MaskedArray;
+use std::any::Any;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::sync::Arc;
+
+fn invalid(msg: impl Into<String>) -> ArrowError {
+    ArrowError::InvalidData(msg.into())
+}
+
+/// Producer state behind an exported `ArrowArray`
+struct ExportedArray {
+    /// Keeps the source array (and so any borrowed buffer) alive
+    _keep_alive: Arc<dyn Any>,
+    /// Owned buffers and the borrowed buffers' locations
+    _buffers: Vec<Buffer>,
+    buffer_ptrs: Vec<*const c_void>,
+    children: Vec<*mut ArrowArray>,
+}
+
+/// Producer state behind an exported `ArrowSchema`
+struct ExportedSchema {
+    _format: CString,
+    _name: CString,
+    children: Vec<*mut ArrowSchema>,
+}
+
+unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+    let Some(array) = array.as_mut() else { return };
+    if !array.private_data.is_null() {
+        let private = Box::from_raw(array.private_data as *mut ExportedArray);
+        // Children that the consumer moved out are already marked released
+        for &child in &private.children {
+            drop(Box::from_raw(child));
+        }
+    }
+    array.private_data = std::ptr::null_mut();
+    array.release = None;
+}
+
+unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+    let Some(schema) = schema.as_mut() else { return };
+    if !schema.private_data.is_null() {
+        let private = Box::from_raw(schema.private_data as *mut ExportedSchema);
+        for &child in &private.children {
+            drop(Box::from_raw(child));
+        }
+    }
+    schema.private_data = std::ptr::null_mut();
+    schema.release = None;
+}
+
+fn new_schema(ty: &ArrowType, name: &str) -> Result<ArrowSchema, ArrowError> {
+    let mut children = Vec::new();
+    if let ArrowType::FixedSizeList(child, _) = ty {
+        children.push(Box::into_raw(Box::new(new_schema(child, "item")?)));
+    }
+    let format = CString::new(ty.format()).map_err(|e| invalid(e.to_string()))?;
+    let name = CString::new(name).map_err(|_| invalid("field name contains a NUL byte"))?;
+    let mut private = Box::new(ExportedSchema { _format: format, _name: name, children });
+    Ok(ArrowSchema {
+        format: private._format.as_ptr(),
+        name: private._name.as_ptr(),
+        metadata: std::ptr::null(),
+        flags: ARROW_FLAG_NULLABLE,
+        n_children: private.children.len() as i64,
+        children: private.children.as_mut_ptr(),
+        dictionary: std::ptr::null_mut(),
+        release: Some(release_schema),
+        private_data: Box::into_raw(private) as *mut c_void,
+    })
+}
+
+fn new_array(
+    keep_alive: &Arc<dyn Any>,
+    length: usize,
+    null_count: usize,
+    buffers: Vec<Option<Buffer>>,
+    children: Vec<ArrowArray>,
+) -> ArrowArray {
+    let buffer_ptrs = buffers
+        .iter()
+        .map(|b| b.as_ref().map_or(std::ptr::null(), |b| b.as_ptr() as *const c_void))
+        .collect();
+    let mut private = Box::new(ExportedArray {
+        _keep_alive: keep_alive.clone(),
+        _buffers: buffers.into_iter().flatten().collect(),
+        buffer_ptrs,
+        children: children.into_iter().map(|c| Box::into_raw(Box::new(c))).collect(),
+    });
+    ArrowArray {
+        length: length as i64,
+        null_count: null_count as i64,
+        offset: 0,
+        n_buffers: private.buffer_ptrs.len() as i64,
+        n_children: private.children.len() as i64,
+        buffers: private.buffer_ptrs.as_mut_ptr(),
+        children: private.children.as_mut_ptr(),
+        dictionary: std::ptr::null_mut(),
+        release: Some(release_array),
+        private_data: Box::into_raw(private) as *mut c_void,
+    }
+}
+
+fn export(keep_alive: Arc<dyn Any>, data: &Array, mask: Option<&Array>) -> Result<(ArrowArray, ArrowSchema), ArrowError> {
+    if data.ndim() == 0 {
+        return Err(invalid("cannot export a 0-d array; reshape it to 1-D"));
+    }
+    let leaf = ArrowType::from_dtype(data.dtype())?;
+    let (validity, null_count) = match mask {
+        Some(mask) if mask.shape() != data.shape() => return Err(invalid("mask shape does not match data shape")),
+        Some(mask) => validity_bitmap(mask)?,
+        None => (None, 0),
+    };
+    let mut buffers = vec![validity.map(Buffer::Owned)];
+    buffers.extend(value_buffers(data, &leaf)?.into_iter().map(Some));
+    let mut array = new_array(&keep_alive, data.size(), null_count, buffers, Vec::new());
+
+    // Wrap the flat values in one fixed-size list level per inner dimension
+    let shape = data.shape();
+    let mut ty = leaf;
+    for axis in (1..shape.len()).rev() {
+        let size = shape[axis] as usize;
+        ty = ArrowType::FixedSizeList(Box::new(ty), size);
+        let length = shape[..axis].iter().product::<i64>() as usize;
+        array = new_array(&keep_alive, length, 0, vec![None], vec![array]);
+    }
+    Ok((array, new_schema(&ty, "")?))
+}
+
+/// Export an array through the Arrow C Data Interface
+///
+/// The returned structures may be handed to any Arrow consumer (moved into
+/// its `ArrowArray*`/`ArrowSchema*` out-parameters with `ptr::write`).
+/// C-contiguous fixed-width data is not copied: the exported buffers point
+/// into the array, which stays alive until the consumer calls the release
+/// callback. Booleans are bit-packed and unicode strings re-encoded as
+/// UTF-8, so those are copied. Arrays of more than one dimension are
+/// exported as nested fixed-size lists.
+pub fn export_array(array: Arc<Array>) -> Result<(ArrowArray, ArrowSchema), ArrowError> {
+    export(array.clone(), &array, None)
+}
+
+/// Export a masked array, with masked elements as Arrow nulls
+///
+/// As `export_array`; the validity bitmap is built from the mask.
+pub fn export_masked(array: Arc<MaskedArray>) -> Result<(ArrowArray, ArrowSchema), ArrowError> {
+    export(array.clone(), array.data(), Some(array.mask()))
+}
+
+/// An imported `ArrowArray`, released when the last view of it is dropped
+struct ImportedArray {
+    _array: ArrowArray,
+}
+
+// The C Data Interface allows the release callback to be called from any
+// thread, and the buffers are never written through imported arrays
+unsafe impl Send for ImportedArray {}
+unsafe impl Sync for ImportedArray {}
+
+/// Arrow type described by a schema, including fixed-size list children
+unsafe fn schema_type(schema: &ArrowSchema) -> Result<ArrowType, ArrowError> {
+    if schema.is_released() || schema.format.is_null() {
+        return Err(invalid("schema has been released"));
+    }
+    if !schema.dictionary.is_null() {
+        return Err(ArrowError::UnsupportedType("dictionary-encoded arrays".to_string()));
+    }
+    let format = CStr::from_ptr(schema.format).to_str().map_err(|_| invalid("format is not UTF-8"))?;
+    match ArrowType::from_format(format)? {
+        ArrowType::FixedSizeList(_, size) => {
+            if schema.n_children != 1 || schema.children.is_null() {
+                return Err(invalid("fixed-size list schema must have one child"));
+            }
+            let child = &**schema.children;
+            Ok(ArrowType::FixedSizeList(Box::new(schema_type(child)?), size))
+        }
+        ty => Ok(ty),
+    }
+}
+
+/// One level of an imported array: a fixed-size list or the values
+struct Level {
+    /// Slot of this level's first element
+    start: usize,
+    /// Number of leaf elements per slot
+    inner: usize,
+    /// Validity bitmap pointer, if the level has nulls
+    validity: *const u8,
+}
+
+/// Decoded view of an imported (possibly nested) array
+struct Layout {
+    shape: Vec<i64>,
+    levels: Vec<Level>,
+    /// Buffers of the innermost (values) array
+    leaf_buffers: Vec<*const u8>,
+    leaf_type: ArrowType,
+}
+
+unsafe fn buffer(array: &ArrowArray, i: usize) -> *const u8 {
+    *array.buffers.add(i) as *const u8
+}
+
+unsafe fn layout(array: &ArrowArray, ty: &ArrowType) -> Result<Layout, ArrowError> {
+    if array.is_released() {
+        return Err(invalid("array has been released"));
+    }
+    if array.length < 0 || array.offset < 0 {
+        return Err(invalid("negative length or offset"));
+    }
+    let mut shape = vec![array.length];
+    let mut levels = Vec::new();
+    let (mut current, mut ty, mut start) = (array, ty, array.offset as usize);
+    loop {
+        if current.n_buffers < 1 || current.buffers.is_null() {
+            return Err(invalid("array has no buffers"));
+        }
+        let validity = if current.null_count != 0 { buffer(current, 0) } else { std::ptr::null() };
+        levels.push(Level { start, inner: 1, validity });
+        let ArrowType::FixedSizeList(child_type, size) = ty else { break };
+        if current.n_children != 1 || current.children.is_null() {
+            return Err(invalid("fixed-size list array must have one child"));
+        }
+        let child = &**current.children;
+        if child.is_released() || child.offset < 0 {
+            return Err(invalid("invalid fixed-size list child"));
+        }
+        shape.push(*size as i64);
+        start = child.offset as usize + start * size;
+        current = child;
+        ty = child_type;
+    }
+    // Leaf elements covered by one slot of each level
+    let mut inner = 1;
+    for (level, &dim) in levels.iter_mut().rev().zip(shape.iter().rev()) {
+        level.inner = inner;
+        inner *= dim as usize;
+    }
+    let expected = match ty {
+        ArrowType::Utf8 | ArrowType::LargeUtf8 => 3,
+        _ => 2,
+    };
+    if current.n_buffers != expected {
+        return Err(invalid(format!("expected {} buffers for {:?}, found {}", expected, ty, current.n_buffers)));
+    }
+    let leaf_buffers = (0..expected as usize).map(|i| buffer(current, i)).collect();
+    Ok(Layout { shape, levels, leaf_buffers, leaf_type: ty.clone() })
+}
+
+impl Layout {
+    fn size(&self) -> usize {
+        self.shape.iter().product::<i64>() as usize
+    }
+
+    /// Mask bytes (1 = null) combining the validity of every level
+    unsafe fn mask(&self) -> Result<Option<Vec<u8>>, ArrowError> {
+        let size = self.size();
+        if size == 0 {
+            return Ok(None);
+        }
+        let mut mask: Option<Vec<u8>> = None;
+        for level in self.levels.iter().filter(|l| !l.validity.is_null()) {
+            let slots = size / level.inner;
+            if slots == 0 {
+                continue;
+            }
+            let bitmap = std::slice::from_raw_parts(level.validity, (level.start + slots).div_ceil(8));
+            let nulls = unpack_validity(bitmap, level.start, slots)?;
+            let mask = mask.get_or_insert_with(|| vec![0; size]);
+            for (i, m) in mask.iter_mut().enumerate() {
+                *m |= nulls[i / level.inner];
+            }
+        }
+        Ok(mask)
+    }
+
+    /// The values, viewed in place when they are fixed-width
+    unsafe fn values(&self, array: ArrowArray) -> Result<Array, ArrowError> {
+        let size = self.size();
+        let start = self.levels.last().map_or(0, |l| l.start);
+        let dtype = self.leaf_type.to_dtype();
+        if size == 0 {
+            return Ok(Array::new(self.shape.clone(), dtype)?);
+        }
+        match &self.leaf_type {
+            ArrowType::Boolean => {
+                let bitmap = std::slice::from_raw_parts(self.leaf_buffers[1], (start + size).div_ceil(8));
+                array_from_bytes(&decode_bools(bitmap, start, size)?, self.shape.clone(), dtype)
+            }
+            ArrowType::Utf8 | ArrowType::LargeUtf8 => {
+                let large = self.leaf_type == ArrowType::LargeUtf8;
+                let width = if large { 8 } else { 4 };
+                let offsets = std::slice::from_raw_parts(self.leaf_buffers[1], (start + size + 1) * width);
+                let end = super::buffers::string_offset(offsets, start + size, large)?;
+                let data = match end {
+                    0 => &[][..],
+                    _ => std::slice::from_raw_parts(self.leaf_buffers[2], end),
+                };
+                let (bytes, dtype) = decode_strings(offsets, data, start, size, large)?;
+                array_from_bytes(&bytes, self.shape.clone(), dtype)
+            }
+            ty => {
+                let width = ty.value_width().ok_or_else(|| ArrowError::UnsupportedType(format!("{:?}", ty)))?;
+                let data = self.leaf_buffers[1];
+                if data.is_null() {
+                    return Err(invalid("values buffer is null"));
+                }
+                let mut strides = vec![width as i64; self.shape.len()];
+                for axis in (0..self.shape.len().saturating_sub(1)).rev() {
+                    strides[axis] = strides[axis + 1] * self.shape[axis + 1];
+                }
+                let data = data.add(start * width) as *mut u8;
+                let owner: Arc<dyn Any + Send + Sync> = Arc::new(ImportedArray { _array: array });
+                // Arrow data is immutable, so the view is read-only
+                Ok(Array::from_owner(data, self.shape.clone(), strides, dtype, owner, false)?)
+            }
+        }
+    }
+}
+
+/// Import an array from the Arrow C Data Interface
+///
+/// Takes ownership of `array`. Fixed-width values are viewed without
+/// copying and the Arrow array is released once the returned array and
+/// all its views are dropped; booleans and strings are copied and the
+/// Arrow array released immediately. Nested fixed-size lists become extra
+/// dimensions. Fails if the array contains nulls; use `import_masked` for
+/// those.
+///
+/// # Safety
+/// `array` and `schema` must be valid C Data Interface structures
+/// describing the same data.
+pub unsafe fn import_array(array: ArrowArray, schema: &ArrowSchema) -> Result<Array, ArrowError> {
+    let ty = schema_type(schema)?;
+    let layout = layout(&array, &ty)?;
+    if let Some(mask) = layout.mask()? {
+        let nulls = mask.iter().filter(|&&m| m != 0).count();
+        if nulls > 0 {
+            return Err(invalid(format!("array has {} nulls; import it with import_masked", nulls)));
+        }
+    }
+    layout.values(array)
+}
+
+/// Import an array whose nulls become masked elements
+///
+/// As `import_array`, with the validity bitmaps (of the values and of any
+/// fixed-size list level) combined into the mask.
+///
+/// # Safety
+/// `array` and `schema` must be valid C Data Interface structures
+/// describing the same data.
+pub unsafe fn import_masked(array: ArrowArray, schema: &ArrowSchema) -> Result<MaskedArray, ArrowError> {
+    let ty = schema_type(schema)?;
+    let layout = layout(&array, &ty)?;
+    let mask = layout.mask()?.unwrap_or_else(|| vec![0; layout.size()]);
+    let mask = array_from_bytes(&mask, layout.shape.clone(), crate::types::DType::new(crate::types::NpyType::Bool))?;
+    let data = layout.values(array)?;
+    Ok(MaskedArray::new(data, mask)?)
+}
@@ -0,0 +1,245 @@
+//! Arrow data types and their mapping to dtypes
+
+use crate::array::ArrayError;
+use crate::datetime::TimeUnit;
+use crate::masked::MaskedError;
+use crate::types::{DType, NpyType};
+
+/// Arrow interchange error
+#[derive(Debug, Clone)]
+pub enum ArrowError {
+    /// Array error
+    ArrayError(ArrayError),
+    /// File I/O error
+    FileError(String),
+    /// The dtype or Arrow type has no counterpart on the other side
+    UnsupportedType(String),
+    /// Malformed C Data Interface structure or IPC file
+    InvalidData(String),
+}
+
+impl std::fmt::Display for ArrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrowError::ArrayError(e) => write!(f, "Array error: {}", e),
+            ArrowError::FileError(msg) => write!(f, "File error: {}", msg),
+            ArrowError::UnsupportedType(msg) => write!(f, "Unsupported Arrow type: {}", msg),
+            ArrowError::InvalidData(msg) => write!(f, "Invalid Arrow data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ArrowError {}
+
+impl From<ArrayError> for ArrowError {
+    fn from(err: ArrayError) -> Self {
+        ArrowError::ArrayError(err)
+    }
+}
+
+impl From<MaskedError> for ArrowError {
+    fn from(err: MaskedError) -> Self {
+        match err {
+            MaskedError::ArrayError(e) => ArrowError::ArrayError(e),
+            other => ArrowError::InvalidData(other.to_string()),
+        }
+    }
+}
+
+/// Arrow logical type of a column
+///
+/// Only the types that have a dtype counterpart are represented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrowType {
+    /// Bit-packed booleans
+    Boolean,
+    /// Integer of the given bit width
+    Int {
+        /// Width in bits: 8, 16, 32 or 64
+        bits: u8,
+        /// Whether the integer is signed
+        signed: bool,
+    },
+    /// IEEE float of the given bit width: 16, 32 or 64
+    Float(u8),
+    /// Variable-length UTF-8 strings with 32-bit offsets
+    Utf8,
+    /// Variable-length UTF-8 strings with 64-bit offsets
+    LargeUtf8,
+    /// Byte strings of a fixed width
+    FixedSizeBinary(usize),
+    /// 64-bit timestamp, with an optional timezone name
+    Timestamp(TimeUnit, Option<String>),
+    /// 64-bit duration
+    Duration(TimeUnit),
+    /// Lists of exactly `size` values of the child type
+    FixedSizeList(Box<ArrowType>, usize),
+}
+
+fn arrow_unit(unit: Option<TimeUnit>) -> Result<TimeUnit, ArrowError> {
+    match unit {
+        Some(unit @ (TimeUnit::Second | TimeUnit::Millisecond | TimeUnit::Microsecond | TimeUnit::Nanosecond)) => {
+            Ok(unit)
+        }
+        Some(unit) => Err(ArrowError::UnsupportedType(format!("time unit '{}' (Arrow supports s, ms, us, ns)", unit.code()))),
+        None => Err(ArrowError::UnsupportedType("generic time unit".to_string())),
+    }
+}
+
+fn unit_code(unit: TimeUnit) -> char {
+    match unit {
+        TimeUnit::Second => 's',
+        TimeUnit::Millisecond => 'm',
+        TimeUnit::Microsecond => 'u',
+        _ => 'n',
+    }
+}
+
+fn unit_from_code(code: char) -> Option<TimeUnit> {
+    Some(match code {
+        's' => TimeUnit::Second,
+        'm' => TimeUnit::Millisecond,
+        'u' => TimeUnit::Microsecond,
+        'n' => TimeUnit::Nanosecond,
+        _ => return None,
+    })
+}
+
+impl ArrowType {
+    /// Arrow type of the elements of a dtype
+    ///
+    /// Unicode dtypes map to `Utf8` and byte-string dtypes to
+    /// `FixedSizeBinary`; datetime and timedelta dtypes need a unit from
+    /// seconds down to nanoseconds.
+    pub fn from_dtype(dtype: &DType) -> Result<ArrowType, ArrowError> {
+        Ok(match dtype.type_() {
+            NpyType::Bool => ArrowType::Boolean,
+            NpyType::Byte => ArrowType::Int { bits: 8, signed: true },
+            NpyType::UByte => ArrowType::Int { bits: 8, signed: false },
+            NpyType::Short => ArrowType::Int { bits: 16, signed: true },
+            NpyType::UShort => ArrowType::Int { bits: 16, signed: false },
+            NpyType::Int => ArrowType::Int { bits: 32, signed: true },
+            NpyType::UInt => ArrowType::Int { bits: 32, signed: false },
+            NpyType::Long | NpyType::LongLong => ArrowType::Int { bits: 64, signed: true },
+            NpyType::ULong | NpyType::ULongLong => ArrowType::Int { bits: 64, signed: false },
+            NpyType::Half => ArrowType::Float(16),
+            NpyType::Float => ArrowType::Float(32),
+            NpyType::Double => ArrowType::Float(64),
+            NpyType::Unicode => ArrowType::Utf8,
+            NpyType::String => ArrowType::FixedSizeBinary(dtype.itemsize()),
+            NpyType::DateTime => ArrowType::Timestamp(arrow_unit(dtype.time_unit())?, None),
+            NpyType::Timedelta => ArrowType::Duration(arrow_unit(dtype.time_unit())?),
+            _ => return Err(ArrowError::UnsupportedType(dtype.name().to_string())),
+        })
+    }
+
+    /// Dtype of the elements of this type
+    ///
+    /// Variable-length strings have no fixed width, so the returned
+    /// unicode dtype holds a single character; callers size it to the
+    /// longest value. Fixed-size lists map to their innermost type.
+    pub fn to_dtype(&self) -> DType {
+        match self {
+            ArrowType::Boolean => DType::new(NpyType::Bool),
+            ArrowType::Int { bits, signed } => DType::new(match (bits, signed) {
+                (8, true) => NpyType::Byte,
+                (8, false) => NpyType::UByte,
+                (16, true) => NpyType::Short,
+                (16, false) => NpyType::UShort,
+                (32, true) => NpyType::Int,
+                (32, false) => NpyType::UInt,
+                (_, true) => NpyType::Long,
+                (_, false) => NpyType::ULong,
+            }),
+            ArrowType::Float(16) => DType::new(NpyType::Half),
+            ArrowType::Float(32) => DType::new(NpyType::Float),
+            ArrowType::Float(_) => DType::new(NpyType::Double),
+            ArrowType::Utf8 | ArrowType::LargeUtf8 => DType::unicode_with_itemsize(4),
+            ArrowType::FixedSizeBinary(width) => DType::string_with_itemsize(*width),
+            ArrowType::Timestamp(unit, _) => DType::datetime(*unit),
+            ArrowType::Duration(unit) => DType::timedelta(*unit),
+            ArrowType::FixedSizeList(child, _) => child.to_dtype(),
+        }
+    }
+
+    /// Byte width of a value in the values buffer, for fixed-width types
+    /// stored one value per slot (everything but booleans, strings and lists)
+    pub(crate) fn value_width(&self) -> Option<usize> {
+        match self {
+            ArrowType::Int { bits, .. } | ArrowType::Float(bits) => Some(*bits as usize / 8),
+            ArrowType::FixedSizeBinary(width) => Some(*width),
+            ArrowType::Timestamp(..) | ArrowType::Duration(_) => Some(8),
+            _ => None,
+        }
+    }
+
+    /// C Data Interface format string
+    pub fn format(&self) -> String {
+        match self {
+            ArrowType::Boolean => "b".to_string(),
+            ArrowType::Int { bits, signed } => {
+                let code = match bits {
+                    8 => 'c',
+                    16 => 's',
+                    32 => 'i',
+                    _ => 'l',
+                };
+                if *signed { code.to_string() } else { code.to_ascii_uppercase().to_string() }
+            }
+            ArrowType::Float(16) => "e".to_string(),
+            ArrowType::Float(32) => "f".to_string(),
+            ArrowType::Float(_) => "g".to_string(),
+            ArrowType::Utf8 => "u".to_string(),
+            ArrowType::LargeUtf8 => "U".to_string(),
+            ArrowType::FixedSizeBinary(width) => format!("w:{}", width),
+            ArrowType::Timestamp(unit, tz) => format!("ts{}:{}", unit_code(*unit), tz.as_deref().unwrap_or("")),
+            ArrowType::Duration(unit) => format!("tD{}", unit_code(*unit)),
+            ArrowType::FixedSizeList(_, size) => format!("+w:{}", size),
+        }
+    }
+
+    /// Parse a C Data Interface format string
+    ///
+    /// Fixed-size lists (`+w:N`) are returned with a placeholder child,
+    /// which the caller replaces with the type of the child schema.
+    pub fn from_format(format: &str) -> Result<ArrowType, ArrowError> {
+        let unsupported = || ArrowError::UnsupportedType(format!("format '{}'", format));
+        Ok(match format {
+            "b" => ArrowType::Boolean,
+            "c" => ArrowType::Int { bits: 8, signed: true },
+            "C" => ArrowType::Int { bits: 8, signed: false },
+            "s" => ArrowType::Int { bits: 16, signed: true },
+            "S" => ArrowType::Int { bits: 16, signed: false },
+            "i" => ArrowType::Int { bits: 32, signed: true },
+            "I" => ArrowType::Int { bits: 32, signed: false },
+            "l" => ArrowType::Int { bits: 64, signed: true },
+            "L" => ArrowType::Int { bits: 64, signed: false },
+            "e" => ArrowType::Float(16),
+            "f" => ArrowType::Float(32),
+            "g" => ArrowType::Float(64),
+            "u" => ArrowType::Utf8,
+            "U" => ArrowType::LargeUtf8,
+            _ => {
+                if let Some(width) = format.strip_prefix("w:") {
+                    ArrowType::FixedSizeBinary(width.parse().map_err(|_| unsupported())?)
+                } else if let Some(size) = format.strip_prefix("+w:") {
+                    ArrowType::FixedSizeList(Box::new(ArrowType::Boolean), size.parse().map_err(|_| unsupported())?)
+                } else if let Some(rest) = format.strip_prefix("ts") {
+                    let mut chars = rest.chars();
+                    let unit = chars.next().and_then(unit_from_code).ok_or_else(unsupported)?;
+                    let tz = chars.as_str().strip_prefix(':').ok_or_else(unsupported)?;
+                    ArrowType::Timestamp(unit, (!tz.is_empty()).then(|| tz.to_string()))
+                } else if let Some(rest) = format.strip_prefix("tD") {
+                    let mut chars = rest.chars();
+                    let unit = chars.next().and_then(unit_from_code).ok_or_else(unsupported)?;
+                    if chars.next().is_some() {
+                        return Err(unsupported());
+                    }
+                    ArrowType::Duration(unit)
+                } else {
+                    return Err(unsupported());
+                }
+            }
+        })
+    }
+}
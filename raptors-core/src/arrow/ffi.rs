@@ -0,0 +1,127 @@
+//! Arrow C Data Interface structures
+//!
+//! These match `struct ArrowSchema` and `struct ArrowArray` from the Arrow
+//! C Data Interface specification, so they can be passed by pointer to any
+//! Arrow implementation (pyarrow, arrow-rs, Arrow C++, DuckDB, ...).
+
+use std::os::raw::{c_char, c_void};
+
+/// Schema flag: the field may contain nulls
+pub const ARROW_FLAG_NULLABLE: i64 = 2;
+
+/// Arrow C Data Interface type description
+///
+/// Dropping a schema that has not been released calls its release
+/// callback, so a schema received from a producer is freed with it.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrowSchema {
+    /// Null-terminated format string describing the type
+    pub format: *const c_char,
+    /// Null-terminated field name, or null
+    pub name: *const c_char,
+    /// Binary field metadata, or null
+    pub metadata: *const c_char,
+    /// `ARROW_FLAG_*` bits
+    pub flags: i64,
+    /// Number of children
+    pub n_children: i64,
+    /// Child schemas (length `n_children`)
+    pub children: *mut *mut ArrowSchema,
+    /// Dictionary value type, or null
+    pub dictionary: *mut ArrowSchema,
+    /// Release callback; null once the schema has been released
+    pub release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    /// Producer-specific data
+    pub private_data: *mut c_void,
+}
+
+/// Arrow C Data Interface array data
+///
+/// Dropping an array that has not been released calls its release
+/// callback, freeing (or giving up its hold on) the buffers.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrowArray {
+    /// Number of logical elements
+    pub length: i64,
+    /// Number of null elements, or -1 if not computed
+    pub null_count: i64,
+    /// Logical offset of the first element in the buffers
+    pub offset: i64,
+    /// Number of buffers
+    pub n_buffers: i64,
+    /// Number of children
+    pub n_children: i64,
+    /// Buffer pointers (length `n_buffers`); the validity buffer may be null
+    pub buffers: *mut *const c_void,
+    /// Child arrays (length `n_children`)
+    pub children: *mut *mut ArrowArray,
+    /// Dictionary values, or null
+    pub dictionary: *mut ArrowArray,
+    /// Release callback; null once the array has been released
+    pub release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    /// Producer-specific data
+    pub private_data: *mut c_void,
+}
+
+impl ArrowSchema {
+    /// A released (empty) schema, for a producer to fill in
+    pub fn empty() -> Self {
+        ArrowSchema {
+            format: std::ptr::null(),
+            name: std::ptr::null(),
+            metadata: std::ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: std::ptr::null_mut(),
+            dictionary: std::ptr::null_mut(),
+            release: None,
+            private_data: std::ptr::null_mut(),
+        }
+    }
+
+    /// Whether the schema has been released
+    pub fn is_released(&self) -> bool {
+        self.release.is_none()
+    }
+}
+
+impl ArrowArray {
+    /// A released (empty) array, for a producer to fill in
+    pub fn empty() -> Self {
+        ArrowArray {
+            length: 0,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 0,
+            n_children: 0,
+            buffers: std::ptr::null_mut(),
+            children: std::ptr::null_mut(),
+            dictionary: std::ptr::null_mut(),
+            release: None,
+            private_data: std::ptr::null_mut(),
+        }
+    }
+
+    /// Whether the array has been released
+    pub fn is_released(&self) -> bool {
+        self.release.is_none()
+    }
+}
+
+impl Drop for ArrowSchema {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
+
+impl Drop for ArrowArray {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) };
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Minimal FlatBuffers encoding and decoding
+//!
+//! Just enough of the FlatBuffers wire format for Arrow IPC metadata:
+//! tables holding scalars, strings, tables, vectors of tables and vectors
+//! of fixed-size structs. Unions are a `u8` type field next to a table
+//! field. The encoder writes each object before the objects it refers to,
+//! since FlatBuffers offsets must point forward.
+
+use super::datatype::ArrowError;
+
+/// A table field value
+pub(crate) enum Value {
+    Bool(bool),
+    U8(u8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Str(String),
+    Table(Table),
+    Tables(Vec<Table>),
+    /// Vector of structs: little-endian element bytes, struct size and
+    /// alignment
+    Structs(Vec<u8>, usize, usize),
+}
+
+/// A table under construction: field values by slot
+#[derive(Default)]
+pub(crate) struct Table {
+    fields: Vec<(u16, Value)>,
+}
+
+impl Table {
+    pub(crate) fn new() -> Self {
+        Table::default()
+    }
+
+    /// Set the field in `slot`
+    pub(crate) fn with(mut self, slot: u16, value: Value) -> Self {
+        self.fields.push((slot, value));
+        self
+    }
+}
+
+impl Value {
+    /// Size and alignment of the field inside its table
+    fn inline_layout(&self) -> (usize, usize) {
+        match self {
+            Value::Bool(_) | Value::U8(_) => (1, 1),
+            Value::I16(_) => (2, 2),
+            Value::I32(_) => (4, 4),
+            Value::I64(_) => (8, 8),
+            _ => (4, 4),
+        }
+    }
+}
+
+/// Serialize a buffer with `root` as its root table
+pub(crate) fn finish(root: &Table) -> Vec<u8> {
+    let mut buf = vec![0u8; 4];
+    let pos = write_table(&mut buf, root);
+    patch(&mut buf, 0, pos);
+    buf
+}
+
+/// Pad with zeros until the length is `rem` modulo `align`
+fn pad(buf: &mut Vec<u8>, align: usize, rem: usize) {
+    while buf.len() % align != rem {
+        buf.push(0);
+    }
+}
+
+/// Point the offset stored at `at` to `target`
+fn patch(buf: &mut [u8], at: usize, target: usize) {
+    buf[at..at + 4].copy_from_slice(&((target - at) as u32).to_le_bytes());
+}
+
+fn write_table(buf: &mut Vec<u8>, table: &Table) -> usize {
+    let slots = table.fields.iter().map(|(slot, _)| *slot as usize + 1).max().unwrap_or(0);
+    // Field positions relative to the table start, after the vtable offset
+    let mut offsets = vec![0u16; slots];
+    let mut size: usize = 4;
+    let mut positions = Vec::with_capacity(table.fields.len());
+    for (slot, value) in &table.fields {
+        let (width, align) = value.inline_layout();
+        size = size.next_multiple_of(align);
+        offsets[*slot as usize] = size as u16;
+        positions.push(size);
+        size += width;
+    }
+
+    pad(buf, 2, 0);
+    let vtable = buf.len();
+    buf.extend_from_slice(&((4 + 2 * slots) as u16).to_le_bytes());
+    buf.extend_from_slice(&(size as u16).to_le_bytes());
+    for offset in offsets {
+        buf.extend_from_slice(&offset.to_le_bytes());
+    }
+
+    pad(buf, 8, 0);
+    let start = buf.len();
+    buf.resize(start + size, 0);
+    buf[start..start + 4].copy_from_slice(&((start - vtable) as i32).to_le_bytes());
+    let mut deferred = Vec::new();
+    for ((_, value), &at) in table.fields.iter().zip(&positions) {
+        let at = start + at;
+        match value {
+            Value::Bool(v) => buf[at] = *v as u8,
+            Value::U8(v) => buf[at] = *v,
+            Value::I16(v) => buf[at..at + 2].copy_from_slice(&v.to_le_bytes()),
+            Value::I32(v) => buf[at..at + 4].copy_from_slice(&v.to_le_bytes()),
+            Value::I64(v) => buf[at..at + 8].copy_from_slice(&v.to_le_bytes()),
+            _ => deferred.push((at, value)),
+        }
+    }
+    for (at, value) in deferred {
+        let target = write_object(buf, value);
+        patch(buf, at, target);
+    }
+    start
+}
+
+fn write_object(buf: &mut Vec<u8>, value: &Value) -> usize {
+    match value {
+        Value::Str(s) => {
+            pad(buf, 4, 0);
+            let pos = buf.len();
+            buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            buf.extend_from_slice(s.as_bytes());
+            buf.push(0);
+            pos
+        }
+        Value::Table(table) => write_table(buf, table),
+        Value::Tables(tables) => {
+            pad(buf, 4, 0);
+            let pos = buf.len();
+            buf.extend_from_slice(&(tables.len() as u32).to_le_bytes());
+            buf.resize(pos + 4 + 4 * tables.len(), 0);
+            for (i, table) in tables.iter().enumerate() {
+                let target = write_table(buf, table);
+                patch(buf, pos + 4 + 4 * i, target);
+            }
+            pos
+        }
+        Value::Structs(bytes, size, align) => {
+            // The elements, not the length prefix, must be aligned
+            let align = (*align).max(4);
+            pad(buf, align, align - 4);
+            let pos = buf.len();
+            buf.extend_from_slice(&((bytes.len() / size) as u32).to_le_bytes());
+            buf.extend_from_slice(bytes);
+            pos
+        }
+        _ => unreachable!("scalars are stored inline"),
+    }
+}
+
+fn malformed() -> ArrowError {
+    ArrowError::InvalidData("malformed flatbuffer".to_string())
+}
+
+fn read<const N: usize>(buf: &[u8], at: usize) -> Result<[u8; N], ArrowError> {
+    buf.get(at..at.checked_add(N).ok_or_else(malformed)?)
+        .map(|b| b.try_into().unwrap())
+        .ok_or_else(malformed)
+}
+
+fn read_u32(buf: &[u8], at: usize) -> Result<usize, ArrowError> {
+    Ok(u32::from_le_bytes(read(buf, at)?) as usize)
+}
+
+/// A table inside a serialized buffer
+#[derive(Clone, Copy)]
+pub(crate) struct TableRef<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    vtable: usize,
+}
+
+/// The root table of a buffer
+pub(crate) fn root(buf: &[u8]) -> Result<TableRef<'_>, ArrowError> {
+    TableRef::at(buf, read_u32(buf, 0)?)
+}
+
+impl<'a> TableRef<'a> {
+    fn at(buf: &'a [u8], pos: usize) -> Result<Self, ArrowError> {
+        let soffset = i32::from_le_bytes(read(buf, pos)?) as i64;
+        let vtable = usize::try_from(pos as i64 - soffset).map_err(|_| malformed())?;
+        read::<4>(buf, vtable)?;
+        Ok(TableRef { buf, pos, vtable })
+    }
+
+    /// Absolute position of the field in `slot`, if present
+    fn field(&self, slot: u16) -> Result<Option<usize>, ArrowError> {
+        let vtable_len = u16::from_le_bytes(read(self.buf, self.vtable)?) as usize;
+        let entry = 4 + 2 * slot as usize;
+        if entry + 2 > vtable_len {
+            return Ok(None);
+        }
+        match u16::from_le_bytes(read(self.buf, self.vtable + entry)?) {
+            0 => Ok(None),
+            offset => Ok(Some(self.pos + offset as usize)),
+        }
+    }
+
+    fn scalar<const N: usize>(&self, slot: u16) -> Result<Option<[u8; N]>, ArrowError> {
+        self.field(slot)?.map(|at| read(self.buf, at)).transpose()
+    }
+
+    pub(crate) fn bool(&self, slot: u16, default: bool) -> Result<bool, ArrowError> {
+        Ok(self.scalar::<1>(slot)?.map_or(default, |b| b[0] != 0))
+    }
+
+    pub(crate) fn u8(&self, slot: u16, default: u8) -> Result<u8, ArrowError> {
+        Ok(self.scalar::<1>(slot)?.map_or(default, |b| b[0]))
+    }
+
+    pub(crate) fn i16(&self, slot: u16, default: i16) -> Result<i16, ArrowError> {
+        Ok(self.scalar(slot)?.map_or(default, i16::from_le_bytes))
+    }
+
+    pub(crate) fn i32(&self, slot: u16, default: i32) -> Result<i32, ArrowError> {
+        Ok(self.scalar(slot)?.map_or(default, i32::from_le_bytes))
+    }
+
+    /// Position of the object referenced by the offset field in `slot`
+    fn target(&self, slot: u16) -> Result<Option<usize>, ArrowError> {
+        self.field(slot)?
+            .map(|at| at.checked_add(read_u32(self.buf, at)?).ok_or_else(malformed))
+            .transpose()
+    }
+
+    pub(crate) fn table(&self, slot: u16) -> Result<Option<TableRef<'a>>, ArrowError> {
+        self.target(slot)?.map(|pos| TableRef::at(self.buf, pos)).transpose()
+    }
+
+    pub(crate) fn string(&self, slot: u16) -> Result<Option<&'a str>, ArrowError> {
+        let Some(pos) = self.target(slot)? else { return Ok(None) };
+        let len = read_u32(self.buf, pos)?;
+        let bytes = self.buf.get(pos + 4..pos + 4 + len).ok_or_else(malformed)?;
+        std::str::from_utf8(bytes).map(Some).map_err(|_| malformed())
+    }
+
+    pub(crate) fn tables(&self, slot: u16) -> Result<Vec<TableRef<'a>>, ArrowError> {
+        let Some(pos) = self.target(slot)? else { return Ok(Vec::new()) };
+        let len = read_u32(self.buf, pos)?;
+        (0..len)
+            .map(|i| {
+                let at = pos + 4 + 4 * i;
+                TableRef::at(self.buf, at + read_u32(self.buf, at)?)
+            })
+            .collect()
+    }
+
+    /// Elements of a vector of `size`-byte structs
+    pub(crate) fn structs(&self, slot: u16, size: usize) -> Result<Vec<&'a [u8]>, ArrowError> {
+        let Some(pos) = self.target(slot)? else { return Ok(Vec::new()) };
+        let len = read_u32(self.buf, pos)?;
+        let end = len.checked_mul(size).and_then(|n| n.checked_add(pos + 4)).ok_or_else(malformed)?;
+        let bytes = self.buf.get(pos + 4..end).ok_or_else(malformed)?;
+        Ok(bytes.chunks(size).collect())
+    }
+}
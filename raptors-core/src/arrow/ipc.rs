@@ -0,0 +1,489 @@
+//! Arrow IPC file format
+//!
+//! Reads and writes the Arrow IPC file format (Feather v2): a schema
+//! message, record batches and a footer indexing them, framed by the
+//! `ARROW1` magic. Columns are 1-D arrays of a numeric, boolean, string,
+//! fixed-width byte-string, timestamp or duration type, optionally with a
+//! mask whose set elements are written as nulls. Compressed files and
+//! dictionary-encoded columns are not supported.
+
+use super::buffers::{array_from_bytes, decode_bools, decode_strings, unpack_validity, validity_bitmap, value_buffers};
+use super::datatype::{ArrowError, ArrowType};
+use super::flatbuf::{self, TableRef, Table, Value};
+use crate::array::Array;
+use crate::datetime::TimeUnit;
+use crate::masked::MaskedArray;
+use crate::types::{DType, NpyType};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Magic bytes at the start and end of an IPC file
+const MAGIC: &[u8; 6] = b"ARROW1";
+
+/// Marker before each encapsulated message
+const CONTINUATION: u32 = 0xFFFF_FFFF;
+
+/// `MetadataVersion::V5`
+const METADATA_VERSION: i16 = 4;
+
+// `MessageHeader` union tags
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+
+// `Type` union tags
+const TYPE_INT: u8 = 2;
+const TYPE_FLOATING_POINT: u8 = 3;
+const TYPE_UTF8: u8 = 5;
+const TYPE_BOOL: u8 = 6;
+const TYPE_TIMESTAMP: u8 = 10;
+const TYPE_FIXED_SIZE_BINARY: u8 = 15;
+const TYPE_DURATION: u8 = 18;
+const TYPE_LARGE_UTF8: u8 = 20;
+
+/// `Endianness` of this platform
+const ENDIANNESS: i16 = if cfg!(target_endian = "little") { 0 } else { 1 };
+
+/// A named column of an IPC file
+///
+/// `mask` marks null elements (true = null), as in a `MaskedArray`.
+#[derive(Debug, Clone)]
+pub struct ArrowColumn {
+    /// Column name
+    pub name: String,
+    /// Column values; null slots hold unspecified values
+    pub values: Array,
+    /// Boolean null mask of the same shape, if the column has nulls
+    pub mask: Option<Array>,
+}
+
+impl ArrowColumn {
+    /// A column without nulls
+    pub fn new(name: impl Into<String>, values: Array) -> Self {
+        ArrowColumn { name: name.into(), values, mask: None }
+    }
+
+    /// A column whose masked elements are null
+    pub fn from_masked(name: impl Into<String>, masked: &MaskedArray) -> Self {
+        ArrowColumn { name: name.into(), values: masked.data().clone(), mask: Some(masked.mask().clone()) }
+    }
+
+    /// Number of null elements
+    pub fn null_count(&self) -> usize {
+        self.mask.as_ref().map_or(0, |mask| crate::utils::to_contiguous_bytes(mask).iter().filter(|&&b| b != 0).count())
+    }
+
+    /// The column as a masked array (with an empty mask if it has no nulls)
+    pub fn into_masked(self) -> Result<MaskedArray, ArrowError> {
+        let mask = match self.mask {
+            Some(mask) => mask,
+            None => {
+                let len = self.values.size();
+                array_from_bytes(&vec![0; len], self.values.shape().to_vec(), DType::new(NpyType::Bool))?
+            }
+        };
+        Ok(MaskedArray::new(self.values, mask)?)
+    }
+}
+
+fn io_err(e: std::io::Error) -> ArrowError {
+    ArrowError::FileError(e.to_string())
+}
+
+fn invalid(msg: impl Into<String>) -> ArrowError {
+    ArrowError::InvalidData(msg.into())
+}
+
+fn time_unit_code(unit: TimeUnit) -> i16 {
+    match unit {
+        TimeUnit::Second => 0,
+        TimeUnit::Millisecond => 1,
+        TimeUnit::Microsecond => 2,
+        _ => 3,
+    }
+}
+
+fn time_unit_from_code(code: i16) -> Result<TimeUnit, ArrowError> {
+    Ok(match code {
+        0 => TimeUnit::Second,
+        1 => TimeUnit::Millisecond,
+        2 => TimeUnit::Microsecond,
+        3 => TimeUnit::Nanosecond,
+        _ => return Err(invalid(format!("unknown time unit {}", code))),
+    })
+}
+
+/// `Type` union tag and table for an Arrow type
+fn type_table(ty: &ArrowType) -> Result<(u8, Table), ArrowError> {
+    Ok(match ty {
+        ArrowType::Boolean => (TYPE_BOOL, Table::new()),
+        ArrowType::Int { bits, signed } => {
+            (TYPE_INT, Table::new().with(0, Value::I32(*bits as i32)).with(1, Value::Bool(*signed)))
+        }
+        ArrowType::Float(bits) => {
+            let precision = match bits {
+                16 => 0,
+                32 => 1,
+                _ => 2,
+            };
+            (TYPE_FLOATING_POINT, Table::new().with(0, Value::I16(precision)))
+        }
+        ArrowType::Utf8 => (TYPE_UTF8, Table::new()),
+        ArrowType::LargeUtf8 => (TYPE_LARGE_UTF8, Table::new()),
+        ArrowType::FixedSizeBinary(width) => {
+            (TYPE_FIXED_SIZE_BINARY, Table::new().with(0, Value::I32(*width as i32)))
+        }
+        ArrowType::Timestamp(unit, tz) => {
+            let mut table = Table::new().with(0, Value::I16(time_unit_code(*unit)));
+            if let Some(tz) = tz {
+                table = table.with(1, Value::Str(tz.clone()));
+            }
+            (TYPE_TIMESTAMP, table)
+        }
+        ArrowType::Duration(unit) => (TYPE_DURATION, Table::new().with(0, Value::I16(time_unit_code(*unit)))),
+        ArrowType::FixedSizeList(..) => {
+            return Err(ArrowError::UnsupportedType("IPC columns must be 1-D".to_string()))
+        }
+    })
+}
+
+fn parse_type(field: &TableRef) -> Result<ArrowType, ArrowError> {
+    let tag = field.u8(2, 0)?;
+    let table = field.table(3)?;
+    let table = || table.ok_or_else(|| invalid("field type is missing"));
+    Ok(match tag {
+        TYPE_BOOL => ArrowType::Boolean,
+        TYPE_INT => {
+            let table = table()?;
+            let bits = table.i32(0, 0)?;
+            if ![8, 16, 32, 64].contains(&bits) {
+                return Err(invalid(format!("unsupported integer width {}", bits)));
+            }
+            ArrowType::Int { bits: bits as u8, signed: table.bool(1, false)? }
+        }
+        TYPE_FLOATING_POINT => ArrowType::Float(match table()?.i16(0, 0)? {
+            0 => 16,
+            1 => 32,
+            _ => 64,
+        }),
+        TYPE_UTF8 => ArrowType::Utf8,
+        TYPE_LARGE_UTF8 => ArrowType::LargeUtf8,
+        TYPE_FIXED_SIZE_BINARY => ArrowType::FixedSizeBinary(
+            usize::try_from(table()?.i32(0, 0)?).map_err(|_| invalid("negative byte width"))?,
+        ),
+        TYPE_TIMESTAMP => {
+            let table = table()?;
+            ArrowType::Timestamp(time_unit_from_code(table.i16(0, 0)?)?, table.string(1)?.map(str::to_string))
+        }
+        TYPE_DURATION => ArrowType::Duration(time_unit_from_code(table()?.i16(0, 1)?)?),
+        tag => return Err(ArrowError::UnsupportedType(format!("IPC type tag {}", tag))),
+    })
+}
+
+fn schema_table(fields: &[(String, ArrowType)]) -> Result<Table, ArrowError> {
+    let fields = fields
+        .iter()
+        .map(|(name, ty)| {
+            let (tag, table) = type_table(ty)?;
+            Ok(Table::new()
+                .with(0, Value::Str(name.clone()))
+                .with(1, Value::Bool(true))
+                .with(2, Value::U8(tag))
+                .with(3, Value::Table(table))
+                .with(5, Value::Tables(Vec::new())))
+        })
+        .collect::<Result<Vec<_>, ArrowError>>()?;
+    Ok(Table::new().with(0, Value::I16(ENDIANNESS)).with(1, Value::Tables(fields)))
+}
+
+fn message(tag: u8, header: Table, body_length: usize) -> Vec<u8> {
+    flatbuf::finish(
+        &Table::new()
+            .with(0, Value::I16(METADATA_VERSION))
+            .with(1, Value::U8(tag))
+            .with(2, Value::Table(header))
+            .with(3, Value::I64(body_length as i64)),
+    )
+}
+
+/// Writer that tracks its position for the footer's block index
+struct Counted<W> {
+    inner: W,
+    written: usize,
+}
+
+impl<W: Write> Counted<W> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), ArrowError> {
+        self.inner.write_all(bytes).map_err(io_err)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+
+    fn pad(&mut self) -> Result<(), ArrowError> {
+        let padding = self.written.next_multiple_of(8) - self.written;
+        self.write(&[0; 8][..padding])
+    }
+
+    /// Write an encapsulated message; returns the metadata length
+    /// including the prefix and padding
+    fn message(&mut self, metadata: &[u8]) -> Result<usize, ArrowError> {
+        let padded = (8 + metadata.len()).next_multiple_of(8);
+        self.write(&CONTINUATION.to_le_bytes())?;
+        self.write(&((padded - 8) as i32).to_le_bytes())?;
+        self.write(metadata)?;
+        self.write(&vec![0; padded - 8 - metadata.len()])?;
+        Ok(padded)
+    }
+}
+
+/// Write columns as an Arrow IPC file with a single record batch
+///
+/// All columns must be 1-D and of the same length. Unicode columns are
+/// written as `Utf8`, byte-string columns as `FixedSizeBinary`, and
+/// datetime and timedelta columns as `Timestamp` and `Duration`.
+pub fn write_ipc<W: Write>(writer: W, columns: &[ArrowColumn]) -> Result<(), ArrowError> {
+    let length = columns.first().map_or(0, |c| c.values.size());
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut nodes = Vec::new();
+    let mut buffer_specs = Vec::new();
+    let mut body: Vec<u8> = Vec::new();
+    for column in columns {
+        if column.values.ndim() != 1 {
+            return Err(ArrowError::UnsupportedType(format!("column '{}' is not 1-D", column.name)));
+        }
+        if column.values.size() != length {
+            return Err(invalid(format!("column '{}' has {} rows, expected {}", column.name, column.values.size(), length)));
+        }
+        let ty = ArrowType::from_dtype(column.values.dtype())?;
+        let (validity, null_count) = match &column.mask {
+            Some(mask) if mask.shape() != column.values.shape() => {
+                return Err(invalid(format!("mask of column '{}' does not match its shape", column.name)))
+            }
+            Some(mask) => validity_bitmap(mask)?,
+            None => (None, 0),
+        };
+        nodes.extend_from_slice(&(length as i64).to_le_bytes());
+        nodes.extend_from_slice(&(null_count as i64).to_le_bytes());
+        let values = value_buffers(&column.values, &ty)?;
+        let validity = validity.unwrap_or_default();
+        for buffer in std::iter::once(&validity[..]).chain(values.iter().map(|b| b.as_slice())) {
+            buffer_specs.extend_from_slice(&(body.len() as i64).to_le_bytes());
+            buffer_specs.extend_from_slice(&(buffer.len() as i64).to_le_bytes());
+            body.extend_from_slice(buffer);
+            body.resize(body.len().next_multiple_of(8), 0);
+        }
+        fields.push((column.name.clone(), ty));
+    }
+
+    let mut out = Counted { inner: writer, written: 0 };
+    out.write(MAGIC)?;
+    out.pad()?;
+    out.message(&message(HEADER_SCHEMA, schema_table(&fields)?, 0))?;
+
+    let batch = Table::new()
+        .with(0, Value::I64(length as i64))
+        .with(1, Value::Structs(nodes, 16, 8))
+        .with(2, Value::Structs(buffer_specs, 16, 8));
+    let offset = out.written;
+    let metadata_length = out.message(&message(HEADER_RECORD_BATCH, batch, body.len()))?;
+    out.write(&body)?;
+
+    // End-of-stream marker
+    out.write(&CONTINUATION.to_le_bytes())?;
+    out.write(&0u32.to_le_bytes())?;
+
+    let mut block = Vec::with_capacity(24);
+    block.extend_from_slice(&(offset as i64).to_le_bytes());
+    block.extend_from_slice(&(metadata_length as i32).to_le_bytes());
+    block.extend_from_slice(&[0; 4]);
+    block.extend_from_slice(&(body.len() as i64).to_le_bytes());
+    let footer = flatbuf::finish(
+        &Table::new()
+            .with(0, Value::I16(METADATA_VERSION))
+            .with(1, Value::Table(schema_table(&fields)?))
+            .with(2, Value::Structs(Vec::new(), 24, 8))
+            .with(3, Value::Structs(block, 24, 8)),
+    );
+    out.write(&footer)?;
+    out.write(&(footer.len() as i32).to_le_bytes())?;
+    out.write(MAGIC)?;
+    out.inner.flush().map_err(io_err)
+}
+
+/// Write columns to an Arrow IPC (Feather v2) file, as `write_ipc` does
+pub fn save_ipc(path: impl AsRef<Path>, columns: &[ArrowColumn]) -> Result<(), ArrowError> {
+    let file = File::create(path).map_err(io_err)?;
+    write_ipc(BufWriter::new(file), columns)
+}
+
+fn le_i64(bytes: &[u8], at: usize) -> i64 {
+    i64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+fn le_i32(bytes: &[u8], at: usize) -> Result<i32, ArrowError> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| i32::from_le_bytes(b.try_into().unwrap()))
+        .ok_or_else(|| invalid("file is truncated"))
+}
+
+fn to_usize(value: i64, what: &str) -> Result<usize, ArrowError> {
+    usize::try_from(value).map_err(|_| invalid(format!("negative {}", what)))
+}
+
+/// Values of one column accumulated over record batches
+struct ColumnData {
+    bytes: Vec<u8>,
+    dtype: DType,
+    mask: Vec<u8>,
+    has_nulls: bool,
+}
+
+impl ColumnData {
+    /// Append a batch, widening unicode columns to the widest batch
+    fn append(&mut self, bytes: Vec<u8>, dtype: DType, mask: Option<Vec<u8>>, len: usize) {
+        let (old, new) = (self.dtype.itemsize(), dtype.itemsize());
+        if new > old && old > 0 {
+            self.bytes = widen(&self.bytes, old, new);
+            self.dtype = dtype;
+        }
+        let width = self.dtype.itemsize();
+        self.bytes.extend(if new < width { widen(&bytes, new, width) } else { bytes });
+        self.has_nulls |= mask.is_some();
+        self.mask.extend(mask.unwrap_or_else(|| vec![0; len]));
+    }
+}
+
+/// Zero-pad each `from`-byte element to `to` bytes
+fn widen(bytes: &[u8], from: usize, to: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len() / from * to);
+    for element in bytes.chunks(from) {
+        out.extend_from_slice(element);
+        out.resize(out.len() + to - from, 0);
+    }
+    out
+}
+
+/// One column of a record batch: element bytes, dtype and null mask
+type BatchColumn = (Vec<u8>, DType, Option<Vec<u8>>);
+
+/// Decode one column of a record batch
+///
+/// `buffers` yields the column's body slices in order.
+fn read_column<'a>(
+    ty: &ArrowType,
+    length: usize,
+    null_count: usize,
+    buffers: &mut impl Iterator<Item = Result<&'a [u8], ArrowError>>,
+) -> Result<BatchColumn, ArrowError> {
+    let mut next = || buffers.next().unwrap_or_else(|| Err(invalid("record batch has too few buffers")));
+    let validity = next()?;
+    let mask = if null_count > 0 { Some(unpack_validity(validity, 0, length)?) } else { None };
+    let (bytes, dtype) = match ty {
+        ArrowType::Boolean => (decode_bools(next()?, 0, length)?, ty.to_dtype()),
+        ArrowType::Utf8 | ArrowType::LargeUtf8 => {
+            let offsets = next()?;
+            decode_strings(offsets, next()?, 0, length, *ty == ArrowType::LargeUtf8)?
+        }
+        ty => {
+            let width = ty.value_width().ok_or_else(|| ArrowError::UnsupportedType(format!("{:?}", ty)))?;
+            let values = next()?;
+            let bytes = values.get(..length * width).ok_or_else(|| invalid("values buffer is too short"))?;
+            (bytes.to_vec(), ty.to_dtype())
+        }
+    };
+    Ok((bytes, dtype, mask))
+}
+
+/// Read every column of an Arrow IPC file
+///
+/// Record batches are concatenated. Columns with nulls get a mask; their
+/// null slots keep whatever values the file holds. Strings become unicode
+/// arrays as wide as the longest value, and timezones are dropped.
+pub fn read_ipc<R: Read>(mut reader: R) -> Result<Vec<ArrowColumn>, ArrowError> {
+    let mut file = Vec::new();
+    reader.read_to_end(&mut file).map_err(io_err)?;
+    if file.len() < 2 * MAGIC.len() + 4 || &file[..6] != MAGIC || &file[file.len() - 6..] != MAGIC {
+        return Err(invalid("not an Arrow IPC file"));
+    }
+    let footer_len = to_usize(le_i32(&file, file.len() - 10)? as i64, "footer length")?;
+    let footer_start = (file.len() - 10).checked_sub(footer_len).ok_or_else(|| invalid("footer length out of range"))?;
+    let footer = flatbuf::root(&file[footer_start..file.len() - 10])?;
+
+    let schema = footer.table(1)?.ok_or_else(|| invalid("footer has no schema"))?;
+    if schema.i16(0, 0)? != ENDIANNESS {
+        return Err(ArrowError::UnsupportedType("file byte order differs from this platform".to_string()));
+    }
+    let fields = schema
+        .tables(1)?
+        .iter()
+        .map(|field| {
+            if field.table(4)?.is_some() {
+                return Err(ArrowError::UnsupportedType("dictionary-encoded columns".to_string()));
+            }
+            Ok((field.string(0)?.unwrap_or("").to_string(), parse_type(field)?))
+        })
+        .collect::<Result<Vec<_>, ArrowError>>()?;
+    let mut data: Vec<ColumnData> = fields
+        .iter()
+        .map(|(_, ty)| ColumnData { bytes: Vec::new(), dtype: ty.to_dtype(), mask: Vec::new(), has_nulls: false })
+        .collect();
+
+    for block in footer.structs(3, 24)? {
+        let offset = to_usize(le_i64(block, 0), "block offset")?;
+        let metadata_length = to_usize(i32::from_le_bytes(block[8..12].try_into().unwrap()) as i64, "metadata length")?;
+        let body_length = to_usize(le_i64(block, 16), "body length")?;
+        // Pre-1.0 files lack the continuation marker
+        let prefix = if le_i32(&file, offset)? == -1 { 8 } else { 4 };
+        let metadata_size = to_usize(le_i32(&file, offset + prefix - 4)? as i64, "metadata size")?;
+        let metadata = file
+            .get(offset + prefix..offset + prefix + metadata_size)
+            .ok_or_else(|| invalid("message out of range"))?;
+        let body_start = offset + metadata_length;
+        let body = file.get(body_start..body_start + body_length).ok_or_else(|| invalid("body out of range"))?;
+
+        let message = flatbuf::root(metadata)?;
+        if message.u8(1, 0)? != HEADER_RECORD_BATCH {
+            return Err(invalid("footer block is not a record batch"));
+        }
+        let batch = message.table(2)?.ok_or_else(|| invalid("record batch header is missing"))?;
+        if batch.table(3)?.is_some() {
+            return Err(ArrowError::UnsupportedType("compressed record batches".to_string()));
+        }
+        let nodes = batch.structs(1, 16)?;
+        if nodes.len() != fields.len() {
+            return Err(invalid("record batch does not match the schema"));
+        }
+        let mut buffers = batch.structs(2, 16)?.into_iter().map(|spec| {
+            let start = to_usize(le_i64(spec, 0), "buffer offset")?;
+            let len = to_usize(le_i64(spec, 8), "buffer length")?;
+            body.get(start..start + len).ok_or_else(|| invalid("buffer out of range"))
+        });
+        for (((_, ty), node), column) in fields.iter().zip(nodes).zip(data.iter_mut()) {
+            let length = to_usize(le_i64(node, 0), "length")?;
+            let null_count = to_usize(le_i64(node, 8), "null count")?;
+            let (bytes, dtype, mask) = read_column(ty, length, null_count, &mut buffers)?;
+            column.append(bytes, dtype, mask, length);
+        }
+    }
+
+    fields
+        .into_iter()
+        .zip(data)
+        .map(|((name, _), column)| {
+            let len = column.mask.len() as i64;
+            let values = array_from_bytes(&column.bytes, vec![len], column.dtype)?;
+            let mask = match column.has_nulls {
+                true => Some(array_from_bytes(&column.mask, vec![len], DType::new(NpyType::Bool))?),
+                false => None,
+            };
+            Ok(ArrowColumn { name, values, mask })
+        })
+        .collect()
+}
+
+/// Read an Arrow IPC (Feather v2) file, as `read_ipc` does
+pub fn load_ipc(path: impl AsRef<Path>) -> Result<Vec<ArrowColumn>, ArrowError> {
+    let file = File::open(path).map_err(io_err)?;
+    read_ipc(BufReader::new(file))
+}
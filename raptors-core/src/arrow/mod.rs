@@ -0,0 +1,18 @@
+//! Apache Arrow interchange
+//!
+//! This module exchanges arrays with Arrow-based tools without going
+//! through NumPy: zero-copy export and import through the Arrow C Data
+//! Interface (`ArrowSchema`/`ArrowArray`), with validity bitmaps mapped
+//! to masked arrays, and reading and writing of the Arrow IPC file format.
+
+mod datatype;
+mod ffi;
+mod buffers;
+mod c_data;
+mod flatbuf;
+mod ipc;
+
+pub use datatype::*;
+pub use ffi::*;
+pub use c_data::*;
+pub use ipc::*;
@@ -2,7 +2,9 @@
 
 use crate::array::{Array, ArrayError};
 use crate::buffer::{BufferError, FormatString};
-use crate::types::NpyType;
+use crate::utils::to_contiguous_bytes;
+use std::any::Any;
+use std::sync::Arc;
 
 /// Buffer information for exported arrays
 #[derive(Debug, Clone)]
@@ -68,7 +70,7 @@ impl Buffer {
 /// suitable for sharing with other libraries.
 pub fn export_buffer(array: &Array) -> Result<BufferInfo, BufferError> {
     // Generate format string from dtype
-    let format = format_string_from_dtype(array.dtype().type_())?;
+    let format = FormatString::from_dtype(array.dtype())?.to_string();
     
     // Calculate size
     let shape = array.shape();
@@ -103,81 +105,61 @@ pub unsafe fn import_buffer(
     format: &str,
     shape: Vec<i64>,
     strides: Option<Vec<i64>>,
-    _read_only: bool,
+    read_only: bool,
+) -> Result<Array, BufferError> {
+    import_buffer_with_owner(ptr, format, shape, strides, read_only, Arc::new(()))
+}
+
+/// Import a buffer whose memory is kept alive by `owner`
+///
+/// The array references the buffer's memory with the given strides (C
+/// order when `None`), is read-only if the buffer is, and holds `owner`
+/// until it is dropped. Data in non-native byte order is copied into a
+/// native array instead.
+///
+/// # Safety
+/// `ptr`, `shape` and `strides` must describe memory that stays valid while
+/// `owner` is alive.
+pub unsafe fn import_buffer_with_owner(
+    ptr: *mut u8,
+    format: &str,
+    shape: Vec<i64>,
+    strides: Option<Vec<i64>>,
+    read_only: bool,
+    owner: Arc<dyn Any + Send + Sync>,
 ) -> Result<Array, BufferError> {
     // Parse format string
     let format_str = FormatString::parse(format)?;
     
     // Convert format to dtype
-    let dtype = dtype_from_format(&format_str)?;
+    let dtype = format_str.to_dtype()?;
     
     // Calculate strides if not provided
-    let _calculated_strides = strides.unwrap_or_else(|| {
+    let strides = strides.unwrap_or_else(|| {
         compute_strides_from_shape(&shape, dtype.itemsize())
     });
     
-    // Validate buffer size
-    let _size: usize = shape.iter()
-        .map(|&s| s.max(0) as usize)
-        .product::<usize>()
-        * dtype.itemsize();
-    
-    // Create array from external data
-    // Note: from_external_memory doesn't take strides, it computes them
-    // For custom strides, we would need a different approach
-    let owns_data = false; // Buffer is externally managed
+    let size: i64 = shape.iter().product();
+    if size == 0 && ptr.is_null() {
+        return Array::new(shape, dtype).map_err(array_error);
+    }
     
-    Array::from_external_memory(ptr, shape, dtype, owns_data)
-        .map_err(|e| match e {
-            ArrayError::InvalidShape => BufferError::BufferTooSmall,
-            _ => BufferError::Unsupported(format!("Array creation failed: {:?}", e)),
-        })
-}
-
-/// Generate format string from NumPy type
-fn format_string_from_dtype(ty: NpyType) -> Result<String, BufferError> {
-    let format = match ty {
-        NpyType::Byte => "b",
-        NpyType::UByte => "B",
-        NpyType::Short => "h",
-        NpyType::UShort => "H",
-        NpyType::Int => "i",
-        NpyType::UInt => "I",
-        NpyType::Long => "l",
-        NpyType::ULong => "L",
-        NpyType::LongLong => "q",
-        NpyType::ULongLong => "Q",
-        NpyType::Float => "f",
-        NpyType::Double => "d",
-        _ => return Err(BufferError::Unsupported(
-            format!("Unsupported type for buffer protocol: {:?}", ty)
-        )),
-    };
+    let array = Array::from_owner(ptr, shape, strides, dtype, owner, !read_only).map_err(array_error)?;
+    if format_str.is_native() {
+        return Ok(array);
+    }
     
-    Ok(format.to_string())
+    // Swap into a native copy
+    let mut bytes = to_contiguous_bytes(&array);
+    format_str.byteswap(&mut bytes);
+    Array::from_slice(&bytes, array.shape().to_vec(), array.dtype().clone()).map_err(array_error)
 }
 
-/// Convert format string to dtype
-fn dtype_from_format(format: &FormatString) -> Result<crate::types::DType, BufferError> {
-    let npy_type = match format.type_char {
-        'b' => NpyType::Byte,
-        'B' => NpyType::UByte,
-        'h' => NpyType::Short,
-        'H' => NpyType::UShort,
-        'i' => NpyType::Int,
-        'I' => NpyType::UInt,
-        'l' => NpyType::Long,
-        'L' => NpyType::ULong,
-        'q' => NpyType::LongLong,
-        'Q' => NpyType::ULongLong,
-        'f' => NpyType::Float,
-        'd' => NpyType::Double,
-        _ => return Err(BufferError::InvalidFormat(
-            format!("Unsupported format character: {}", format.type_char)
-        )),
-    };
-    
-    Ok(crate::types::DType::new(npy_type))
+fn array_error(e: ArrayError) -> BufferError {
+    match e {
+        ArrayError::InvalidShape => BufferError::BufferTooSmall,
+        _ => BufferError::Unsupported(format!("Array creation failed: {:?}", e)),
+    }
 }
 
 /// Compute strides from shape
@@ -197,4 +179,3 @@ fn compute_strides_from_shape(shape: &[i64], itemsize: usize) -> Vec<i64> {
     
     strides
 }
-
@@ -1,89 +1,460 @@
 //! Buffer format string parsing
 //!
-//! Parses Python buffer protocol format strings
+//! Parses Python buffer protocol format strings, the `struct`-module syntax
+//! extended by PEP 3118: byte-order prefixes, repeat counts, `(d0,d1)`
+//! subarray shapes, `Zf`/`Zd` complex codes, `Ns` byte strings, `Nw` UCS-4
+//! strings and `T{...}` struct layouts with `:name:` fields and `x` padding.
 
 use crate::buffer::BufferError;
+use crate::structured::{Field, StructuredDType};
+use crate::types::{DType, NpyType};
+
+/// Byte-order character for native multi-byte data
+const NATIVE: char = if cfg!(target_endian = "little") { '<' } else { '>' };
 
 /// Parsed format string
 #[derive(Debug, Clone)]
 pub struct FormatString {
-    /// Endianness indicator (<, >, =, !)
+    /// Endianness indicator (@, <, >, =, !)
     pub endian: Option<char>,
-    /// Type character (b, B, h, H, i, I, l, L, q, Q, f, d)
+    /// Type character (?, b, B, h, H, i, I, l, L, q, Q, n, N, e, f, d, g,
+    /// c, s, w, x), `T` for struct layouts, and NumPy's `F`, `D` and `G`
+    /// for the complex codes `Zf`, `Zd` and `Zg`
     pub type_char: char,
-    /// Item count (for arrays)
+    /// Repeat count, or the length of `s` and `w` strings and `x` padding
     pub count: Option<usize>,
+    /// Subarray shape from a `(d0,d1,...)` prefix
+    pub shape: Vec<usize>,
+    /// Fields of a `T{...}` layout, including `x` padding
+    pub fields: Vec<FormatField>,
+}
+
+/// A field of a `T{...}` struct layout
+#[derive(Debug, Clone)]
+pub struct FormatField {
+    /// Field name, empty for padding and unnamed fields
+    pub name: String,
+    /// Byte offset within the struct
+    pub offset: usize,
+    /// Format of the field
+    pub format: FormatString,
 }
 
 impl FormatString {
     /// Parse a format string
     ///
-    /// Supports format strings like "d", "<d", ">i", "=f", etc.
+    /// Supports format strings like "d", "<d", "Zd", "10s", "3w" and
+    /// "T{<i:id:(2)<d:pos:}". Several items, or named ones, form an
+    /// implicit struct layout, as in NumPy.
     pub fn parse(format: &str) -> Result<Self, BufferError> {
         let trimmed = format.trim();
-        
+
         if trimmed.is_empty() {
             return Err(BufferError::InvalidFormat("Empty format string".to_string()));
         }
-        
-        let mut chars = trimmed.chars().peekable();
-        
-        // Parse endianness (optional)
-        let endian = match chars.peek() {
-            Some(&'<') | Some(&'>') | Some(&'=') | Some(&'!') => {
-                chars.next()
+
+        let mut parser = Parser { chars: trimmed.chars().collect(), pos: 0 };
+        let mut fields = parser.fields(None, false)?;
+        if fields.iter().all(|f| f.format.type_char == 'x') {
+            return Err(BufferError::InvalidFormat(format!("Format has no items: {}", trimmed)));
+        }
+        if fields.len() == 1 && fields[0].name.is_empty() {
+            return Ok(fields.remove(0).format);
+        }
+        Ok(FormatString::layout(fields))
+    }
+
+    /// Format string describing elements of `dtype`
+    ///
+    /// Scalars use native sizes and no byte-order prefix; struct layout
+    /// fields carry an explicit byte order, with standard sizes.
+    pub fn from_dtype(dtype: &DType) -> Result<Self, BufferError> {
+        Self::for_dtype(dtype, None)
+    }
+
+    fn for_dtype(dtype: &DType, endian: Option<char>) -> Result<Self, BufferError> {
+        if let Some(layout) = dtype.fields() {
+            let mut sorted: Vec<&Field> = layout.fields().iter().collect();
+            sorted.sort_by_key(|f| f.offset);
+            let mut fields = Vec::with_capacity(sorted.len());
+            let mut offset = 0;
+            for field in sorted {
+                if field.offset < offset {
+                    return Err(BufferError::Unsupported("overlapping fields cannot be exported".to_string()));
+                }
+                if field.offset > offset {
+                    fields.push(FormatField::padding(offset, field.offset - offset));
+                }
+                let mut format = Self::for_dtype(&field.dtype, Some(NATIVE))?;
+                format.shape = field.shape.iter().map(|&n| n as usize).collect();
+                fields.push(FormatField { name: field.name.clone(), offset: field.offset, format });
+                offset = field.offset + field.size();
             }
-            _ => None,
-        };
-        
-        // Parse type character
-        let type_char = chars.next().ok_or_else(|| {
-            BufferError::InvalidFormat("Missing type character".to_string())
-        })?;
-        
-        // Parse count (optional)
-        let count = if chars.peek().is_some() {
-            let count_str: String = chars.collect();
-            if !count_str.is_empty() {
-                Some(count_str.parse().map_err(|_| {
-                    BufferError::InvalidFormat(format!("Invalid count: {}", count_str))
-                })?)
-            } else {
-                None
+            if layout.itemsize() > offset {
+                fields.push(FormatField::padding(offset, layout.itemsize() - offset));
+            }
+            return Ok(FormatString::layout(fields));
+        }
+
+        let standard = endian.is_some();
+        let mut count = None;
+        let type_char = match dtype.type_() {
+            NpyType::Bool => '?',
+            NpyType::Byte => 'b',
+            NpyType::UByte => 'B',
+            NpyType::Short => 'h',
+            NpyType::UShort => 'H',
+            NpyType::Int => 'i',
+            NpyType::UInt => 'I',
+            NpyType::Long if standard => 'q',
+            NpyType::Long => 'l',
+            NpyType::ULong if standard => 'Q',
+            NpyType::ULong => 'L',
+            NpyType::LongLong => 'q',
+            NpyType::ULongLong => 'Q',
+            NpyType::Half => 'e',
+            NpyType::Float => 'f',
+            NpyType::Double => 'd',
+            NpyType::LongDouble => 'g',
+            NpyType::CFloat => 'F',
+            NpyType::CDouble => 'D',
+            NpyType::CLongDouble => 'G',
+            NpyType::String if dtype.itemsize() > 0 => {
+                count = Some(dtype.itemsize());
+                's'
+            }
+            NpyType::Unicode if dtype.itemsize() > 0 => {
+                count = Some(dtype.itemsize() / 4);
+                'w'
             }
-        } else {
-            None
+            ty => return Err(BufferError::Unsupported(
+                format!("Unsupported type for buffer protocol: {:?}", ty)
+            )),
+        };
+        Ok(FormatString { endian, type_char, count, shape: Vec::new(), fields: Vec::new() })
+    }
+
+    fn layout(fields: Vec<FormatField>) -> Self {
+        FormatString { endian: None, type_char: 'T', count: None, shape: Vec::new(), fields }
+    }
+
+    /// Whether sizes and alignment are the platform's (`@` or no prefix)
+    fn is_native_size(&self) -> bool {
+        matches!(self.endian, None | Some('@'))
+    }
+
+    /// Size in bytes of one element, ignoring count and shape
+    fn base_size(&self) -> usize {
+        let native = self.is_native_size();
+        match self.type_char {
+            '?' | 'b' | 'B' | 'c' | 's' | 'x' => 1,
+            'h' | 'H' | 'e' => 2,
+            'i' | 'I' | 'f' | 'w' => 4,
+            'l' | 'L' if native => std::mem::size_of::<std::os::raw::c_long>(),
+            'l' | 'L' => 4,
+            'n' | 'N' => std::mem::size_of::<usize>(),
+            'q' | 'Q' | 'd' | 'F' => 8,
+            'D' => 16,
+            'g' => DType::new(NpyType::LongDouble).itemsize(),
+            'G' => DType::new(NpyType::CLongDouble).itemsize(),
+            'T' => self.fields.last().map(|f| f.offset + f.format.itemsize()).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Alignment of an element under native (`@`) rules
+    fn alignment(&self) -> usize {
+        match self.type_char {
+            'T' => self.fields.iter().map(|f| f.format.alignment()).max().unwrap_or(1),
+            'F' | 'D' | 'G' => self.base_size() / 2,
+            'c' | 's' | 'x' => 1,
+            _ => self.base_size().max(1),
+        }
+    }
+
+    /// Number of elements from the repeat count and subarray shape
+    fn repeat(&self) -> usize {
+        self.count.unwrap_or(1) * self.shape.iter().product::<usize>()
+    }
+
+    /// Size in bytes of the described item
+    pub fn itemsize(&self) -> usize {
+        self.base_size() * self.repeat()
+    }
+
+    /// Whether all multi-byte data is in native byte order
+    pub fn is_native(&self) -> bool {
+        let mut swaps = Vec::new();
+        self.swaps(0, &mut swaps);
+        swaps.is_empty()
+    }
+
+    /// Collect the `(offset, width)` byte runs in non-native order
+    fn swaps(&self, base: usize, out: &mut Vec<(usize, usize)>) {
+        let size = self.base_size();
+        let swapped = match self.endian {
+            Some('<') => NATIVE == '>',
+            Some('>') | Some('!') => NATIVE == '<',
+            _ => false,
         };
-        
-        // Validate type character
-        match type_char {
-            'b' | 'B' | 'h' | 'H' | 'i' | 'I' | 'l' | 'L' | 'q' | 'Q' | 'f' | 'd' => {}
-            _ => {
-                return Err(BufferError::InvalidFormat(
-                    format!("Invalid type character: {}", type_char)
-                ));
+        for i in 0..self.repeat() {
+            let offset = base + i * size;
+            match self.type_char {
+                'T' => self.fields.iter().for_each(|f| f.format.swaps(offset + f.offset, out)),
+                _ if !swapped || size < 2 => {}
+                'F' | 'D' | 'G' => out.extend([(offset, size / 2), (offset + size / 2, size / 2)]),
+                's' | 'c' | 'x' => {}
+                _ => out.push((offset, size)),
+            }
+        }
+    }
+
+    /// Reverse the bytes of every non-native value in `data`, a run of
+    /// whole items
+    pub fn byteswap(&self, data: &mut [u8]) {
+        let itemsize = self.itemsize();
+        let mut swaps = Vec::new();
+        self.swaps(0, &mut swaps);
+        if swaps.is_empty() || itemsize == 0 {
+            return;
+        }
+        for element in data.chunks_exact_mut(itemsize) {
+            for &(offset, width) in &swaps {
+                element[offset..offset + width].reverse();
             }
         }
-        
-        Ok(FormatString {
-            endian,
-            type_char,
-            count,
-        })
     }
-    
+
+    /// Dtype of the described item
+    ///
+    /// Repeat counts and subarray shapes are only supported on struct
+    /// fields, where they become the field's shape.
+    pub fn to_dtype(&self) -> Result<DType, BufferError> {
+        if !self.subarray_shape().is_empty() {
+            return Err(BufferError::Unsupported(
+                format!("Subarray format outside a struct layout: {}", self.to_string())
+            ));
+        }
+        self.element_dtype()
+    }
+
+    /// Field shape from the subarray shape and, for non-string codes, the
+    /// repeat count
+    fn subarray_shape(&self) -> Vec<i64> {
+        let mut shape: Vec<i64> = self.shape.iter().map(|&n| n as i64).collect();
+        match (self.type_char, self.count) {
+            ('s' | 'w' | 'x', _) | (_, None) | (_, Some(1)) => {}
+            (_, Some(n)) => shape.push(n as i64),
+        }
+        shape
+    }
+
+    /// Dtype of one element, ignoring count and shape
+    fn element_dtype(&self) -> Result<DType, BufferError> {
+        let size = self.base_size();
+        let npy_type = match self.type_char {
+            '?' => NpyType::Bool,
+            'b' => NpyType::Byte,
+            'B' => NpyType::UByte,
+            'h' => NpyType::Short,
+            'H' => NpyType::UShort,
+            'i' => NpyType::Int,
+            'I' => NpyType::UInt,
+            'l' | 'n' if size == 8 => NpyType::Long,
+            'l' | 'n' => NpyType::Int,
+            'L' | 'N' if size == 8 => NpyType::ULong,
+            'L' | 'N' => NpyType::UInt,
+            'q' => NpyType::LongLong,
+            'Q' => NpyType::ULongLong,
+            'e' => NpyType::Half,
+            'f' => NpyType::Float,
+            'd' => NpyType::Double,
+            'g' => NpyType::LongDouble,
+            'F' => NpyType::CFloat,
+            'D' => NpyType::CDouble,
+            'G' => NpyType::CLongDouble,
+            'c' => return Ok(DType::string_with_itemsize(1)),
+            's' => return Ok(DType::string_with_itemsize(self.count.unwrap_or(1))),
+            'w' => return Ok(DType::unicode_with_itemsize(4 * self.count.unwrap_or(1))),
+            'T' => return self.struct_dtype(),
+            c => return Err(BufferError::InvalidFormat(
+                format!("Unsupported format character: {}", c)
+            )),
+        };
+        Ok(DType::new(npy_type))
+    }
+
+    /// Structured dtype of a `T{...}` layout; unnamed fields become `f0`, `f1`, ...
+    fn struct_dtype(&self) -> Result<DType, BufferError> {
+        let mut fields = Vec::new();
+        for field in self.fields.iter().filter(|f| f.format.type_char != 'x') {
+            let name = if field.name.is_empty() { format!("f{}", fields.len()) } else { field.name.clone() };
+            let dtype = field.format.element_dtype()?;
+            fields.push(Field::new(name, dtype, field.offset).with_shape(field.format.subarray_shape()));
+        }
+        let layout = StructuredDType::with_offsets(fields, self.base_size())
+            .map_err(|e| BufferError::InvalidFormat(format!("Invalid struct layout: {}", e)))?;
+        Ok(DType::structured(layout))
+    }
+
     /// Convert format string back to string
     #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         let mut result = String::new();
+        if !self.shape.is_empty() {
+            let dims: Vec<String> = self.shape.iter().map(|n| n.to_string()).collect();
+            result.push_str(&format!("({})", dims.join(",")));
+        }
         if let Some(endian) = self.endian {
             result.push(endian);
         }
-        result.push(self.type_char);
         if let Some(count) = self.count {
             result.push_str(&count.to_string());
         }
+        match self.type_char {
+            'F' => result.push_str("Zf"),
+            'D' => result.push_str("Zd"),
+            'G' => result.push_str("Zg"),
+            'T' => {
+                result.push_str("T{");
+                for field in &self.fields {
+                    result.push_str(&field.format.to_string());
+                    if !field.name.is_empty() {
+                        result.push_str(&format!(":{}:", field.name));
+                    }
+                }
+                result.push('}');
+            }
+            c => result.push(c),
+        }
         result
     }
 }
 
+impl FormatField {
+    fn padding(offset: usize, size: usize) -> Self {
+        let format = FormatString { endian: None, type_char: 'x', count: Some(size), shape: Vec::new(), fields: Vec::new() };
+        FormatField { name: String::new(), offset, format }
+    }
+}
+
+/// Recursive-descent parser over the characters of a format string
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, msg: &str) -> BufferError {
+        let text: String = self.chars.iter().collect();
+        BufferError::InvalidFormat(format!("{} at position {} of '{}'", msg, self.pos, text))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+
+    /// Items up to the end of input, or up to the closing `}` of a layout
+    ///
+    /// A byte-order prefix applies to every following item of the layout.
+    fn fields(&mut self, mut endian: Option<char>, nested: bool) -> Result<Vec<FormatField>, BufferError> {
+        let mut fields = Vec::new();
+        let mut offset = 0usize;
+        loop {
+            match self.peek() {
+                None if nested => return Err(self.error("Unterminated struct layout")),
+                None => break,
+                Some('}') if nested => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(c) if c.is_whitespace() => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(c @ ('@' | '<' | '>' | '=' | '!')) => {
+                    self.pos += 1;
+                    endian = Some(c);
+                    continue;
+                }
+                _ => {}
+            }
+
+            let mut shape = Vec::new();
+            if self.peek() == Some('(') {
+                self.pos += 1;
+                loop {
+                    shape.push(self.number().ok_or_else(|| self.error("Invalid subarray shape"))?);
+                    match self.next() {
+                        Some(',') => continue,
+                        Some(')') => break,
+                        _ => return Err(self.error("Invalid subarray shape")),
+                    }
+                }
+            }
+            // NumPy writes the byte order of a subarray after its shape
+            while let Some(c @ ('@' | '<' | '>' | '=' | '!')) = self.peek() {
+                self.pos += 1;
+                endian = Some(c);
+            }
+            let count = self.number();
+            let mut format = FormatString { endian, type_char: ' ', count, shape, fields: Vec::new() };
+            format.type_char = match self.next() {
+                Some('Z') => match self.next() {
+                    Some('f') => 'F',
+                    Some('d') => 'D',
+                    Some('g') => 'G',
+                    _ => return Err(self.error("Invalid complex type")),
+                },
+                Some('T') => {
+                    if self.next() != Some('{') {
+                        return Err(self.error("Expected '{' after 'T'"));
+                    }
+                    format.fields = self.fields(endian, true)?;
+                    'T'
+                }
+                Some(c @ ('?' | 'b' | 'B' | 'h' | 'H' | 'i' | 'I' | 'l' | 'L' | 'q' | 'Q' | 'n' | 'N'
+                    | 'e' | 'f' | 'd' | 'g' | 'c' | 's' | 'w' | 'x')) => c,
+                Some(c) => {
+                    self.pos -= 1;
+                    return Err(BufferError::InvalidFormat(format!("Invalid type character: {}", c)));
+                }
+                None => return Err(self.error("Missing type character")),
+            };
+
+            // Native mode aligns items like a C struct
+            if format.is_native_size() && format.type_char != 'x' {
+                offset = offset.next_multiple_of(format.alignment());
+            }
+            let mut name = String::new();
+            if self.peek() == Some(':') {
+                self.pos += 1;
+                while let Some(c) = self.next() {
+                    if c == ':' {
+                        break;
+                    }
+                    name.push(c);
+                }
+            }
+            let size = format.itemsize();
+            fields.push(FormatField { name, offset, format });
+            offset += size;
+        }
+        Ok(fields)
+    }
+}
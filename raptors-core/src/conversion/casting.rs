@@ -4,6 +4,7 @@
 
 use crate::types::NpyType;
 use crate::array::Array;
+use crate::array::{encode_scalar, Scalar};
 use crate::types::DType;
 
 /// Casting safety level
@@ -177,7 +178,7 @@ pub fn convert_array(array: &Array, target_dtype: DType) -> Result<Array, Conver
             let dst_ptr = output.data_ptr_mut().add(dst_offset);
             
             // Perform type conversion based on source and target types
-            convert_element(src_ptr, dst_ptr, source_type, target_type)?;
+            convert_element(src_ptr, dst_ptr, source_type, &target_dtype)?;
         }
     }
     
@@ -193,10 +194,11 @@ unsafe fn convert_element(
     src_ptr: *const u8,
     dst_ptr: *mut u8,
     src_type: NpyType,
-    dst_type: NpyType,
+    dst_dtype: &DType,
 ) -> Result<(), ConversionError> {
     use NpyType::*;
     
+    let dst_type = dst_dtype.type_();
     match (src_type, dst_type) {
         // Integer to integer conversions
         (Int, Double) => {
@@ -229,8 +231,20 @@ unsafe fn convert_element(
             let src_itemsize = crate::types::DType::new(src_type).itemsize();
             std::ptr::copy_nonoverlapping(src_ptr, dst_ptr, src_itemsize);
         }
+        // Any other real numeric source goes through a scalar, keeping
+        // 64-bit integers exact
         _ => {
-            return Err(ConversionError::UnsupportedConversion);
+            let value = crate::utils::read_element_f64(src_ptr, src_type)
+                .ok_or(ConversionError::UnsupportedConversion)?;
+            let scalar = match src_type {
+                Bool => Scalar::Bool(value != 0.0),
+                Long | LongLong => Scalar::Int(std::ptr::read_unaligned(src_ptr as *const i64)),
+                ULong | ULongLong => Scalar::UInt(std::ptr::read_unaligned(src_ptr as *const u64)),
+                Float | Double => Scalar::Float(value),
+                _ => Scalar::Int(value as i64),
+            };
+            let out = std::slice::from_raw_parts_mut(dst_ptr, dst_dtype.itemsize());
+            encode_scalar(&scalar, dst_dtype, out).map_err(|_| ConversionError::UnsupportedConversion)?;
         }
     }
     
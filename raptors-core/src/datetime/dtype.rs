@@ -26,6 +26,39 @@ pub enum TimeUnit {
 }
 
 impl TimeUnit {
+    /// NumPy unit code, as in `datetime64[ns]`
+    pub fn code(&self) -> &'static str {
+        match self {
+            TimeUnit::Year => "Y",
+            TimeUnit::Month => "M",
+            TimeUnit::Week => "W",
+            TimeUnit::Day => "D",
+            TimeUnit::Hour => "h",
+            TimeUnit::Minute => "m",
+            TimeUnit::Second => "s",
+            TimeUnit::Millisecond => "ms",
+            TimeUnit::Microsecond => "us",
+            TimeUnit::Nanosecond => "ns",
+        }
+    }
+
+    /// Parse a NumPy unit code
+    pub fn from_code(code: &str) -> Option<TimeUnit> {
+        Some(match code {
+            "Y" => TimeUnit::Year,
+            "M" => TimeUnit::Month,
+            "W" => TimeUnit::Week,
+            "D" => TimeUnit::Day,
+            "h" => TimeUnit::Hour,
+            "m" => TimeUnit::Minute,
+            "s" => TimeUnit::Second,
+            "ms" => TimeUnit::Millisecond,
+            "us" | "μs" => TimeUnit::Microsecond,
+            "ns" => TimeUnit::Nanosecond,
+            _ => return None,
+        })
+    }
+
     /// Get nanoseconds per unit
     pub fn nanoseconds_per_unit(&self) -> i64 {
         match self {
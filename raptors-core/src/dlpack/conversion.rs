@@ -1,9 +1,73 @@
 //! DLPack conversion functions
+//!
+//! Arrays are exported as `DLManagedTensor` or `DLManagedTensorVersioned`
+//! values whose deleter drops an `Arc<Array>`, so the data stays alive for
+//! as long as the consumer holds the tensor. Imports share the tensor's
+//! memory and call its deleter when the last array using it is dropped.
+//! DLPack strides count elements, not bytes.
+
+use std::ffi::c_void;
+use std::sync::Arc;
 
 use crate::array::Array;
 use crate::types::{DType, NpyType};
 
-use super::{DLTensor, DLDevice, DLDeviceType, DLDataType, DLDataTypeCode, DLPackError};
+use super::{
+    DLDataType, DLDataTypeCode, DLDevice, DLDeviceType, DLManagedTensor, DLManagedTensorVersioned, DLPackError,
+    DLPackVersion, DLTensor, DLPACK_FLAG_BITMASK_READ_ONLY, DLPACK_MAJOR_VERSION, DLPACK_MINOR_VERSION,
+};
+
+/// Shape and strides an exported tensor points into, and the array that
+/// keeps its data alive
+struct ExportContext {
+    _array: Arc<Array>,
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+}
+
+impl ExportContext {
+    fn new(array: Arc<Array>) -> Result<Box<Self>, DLPackError> {
+        npy_type_to_dlpack_dtype(array.dtype().type_())?;
+        let shape = array.shape().to_vec();
+        let strides = element_strides(&array)?;
+        Ok(Box::new(ExportContext { _array: array, shape, strides }))
+    }
+
+    /// Tensor over the array's data, pointing into this context
+    fn tensor(&mut self) -> DLTensor {
+        let array = &self._array;
+        DLTensor {
+            data: array.data_ptr() as *mut c_void,
+            device: DLDevice {
+                device_type: DLDeviceType::CPU,
+                device_id: 0,
+            },
+            ndim: self.shape.len() as i32,
+            dtype: npy_type_to_dlpack_dtype(array.dtype().type_()).expect("checked in ExportContext::new"),
+            shape: self.shape.as_mut_ptr(),
+            strides: self.strides.as_mut_ptr(),
+            byte_offset: 0,
+        }
+    }
+}
+
+/// Array strides in elements
+///
+/// Dimensions of length 0 or 1 may have any stride and export 0 when it is
+/// not a whole number of elements.
+fn element_strides(array: &Array) -> Result<Vec<i64>, DLPackError> {
+    let itemsize = array.itemsize().max(1) as i64;
+    array
+        .strides()
+        .iter()
+        .zip(array.shape())
+        .map(|(&stride, &dim)| match stride % itemsize {
+            0 => Ok(stride / itemsize),
+            _ if dim <= 1 => Ok(0),
+            _ => Err(DLPackError::UnsupportedStrides),
+        })
+        .collect()
+}
 
 /// Convert Array to DLPack tensor
 ///
@@ -15,25 +79,25 @@ use super::{DLTensor, DLDevice, DLDeviceType, DLDataType, DLDataTypeCode, DLPack
 /// * `Err(DLPackError)` if conversion fails
 ///
 /// # Safety
-/// The returned DLTensor must be freed using `delete_dlpack_tensor`
-/// Note: This creates a copy of shape/strides data to ensure memory safety
+/// The returned DLTensor must be freed using `delete_dlpack_tensor`, and
+/// only points at the array's data while `array` is alive. Use
+/// `array_to_managed_tensor` to hand a tensor to another library.
 pub unsafe fn array_to_dlpack(array: &Array) -> Result<*mut DLTensor, DLPackError> {
     // Convert dtype
     let dtype = npy_type_to_dlpack_dtype(array.dtype().type_())?;
-    
+
     // Copy shape and strides (need owned data for DLTensor)
     let ndim = array.ndim();
     let shape_vec: Vec<i64> = array.shape().to_vec();
-    let strides_vec: Vec<i64> = array.strides().to_vec();
-    
-    // Allocate shape and strides arrays
+    let strides_vec: Vec<i64> = element_strides(array)?;
+
+    // Allocate shape and strides arrays; delete_dlpack_tensor frees them
     let shape_ptr = Box::into_raw(shape_vec.into_boxed_slice()) as *mut i64;
     let strides_ptr = Box::into_raw(strides_vec.into_boxed_slice()) as *mut i64;
-    
+
     // Allocate DLTensor structure
-    // Note: Full implementation would need a custom deleter to free shape/strides
     let tensor = Box::into_raw(Box::new(DLTensor {
-        data: array.data_ptr() as *mut std::ffi::c_void,
+        data: array.data_ptr() as *mut c_void,
         device: DLDevice {
             device_type: DLDeviceType::CPU,
             device_id: 0,
@@ -44,10 +108,192 @@ pub unsafe fn array_to_dlpack(array: &Array) -> Result<*mut DLTensor, DLPackErro
         strides: strides_ptr,
         byte_offset: 0,
     }));
-    
+
     Ok(tensor)
 }
 
+/// Export an array as a `DLManagedTensor`
+///
+/// The tensor's deleter drops the `Arc`, keeping the array's data alive
+/// until the consumer is done. DLPack 0.x has no read-only flag, so
+/// read-only arrays are refused.
+pub fn array_to_managed_tensor(array: Arc<Array>) -> Result<*mut DLManagedTensor, DLPackError> {
+    if !array.is_writeable() {
+        return Err(DLPackError::ReadOnly);
+    }
+    let mut context = ExportContext::new(array)?;
+    let dl_tensor = context.tensor();
+    Ok(Box::into_raw(Box::new(DLManagedTensor {
+        dl_tensor,
+        manager_ctx: Box::into_raw(context) as *mut c_void,
+        deleter: Some(delete_managed_tensor),
+    })))
+}
+
+/// Export an array as a `DLManagedTensorVersioned`
+///
+/// Like `array_to_managed_tensor`, with read-only arrays flagged as such.
+/// `extra_flags` are added to the tensor's flags, for example
+/// `DLPACK_FLAG_BITMASK_IS_COPIED` when `array` is a copy made for the export.
+pub fn array_to_managed_tensor_versioned(
+    array: Arc<Array>,
+    extra_flags: u64,
+) -> Result<*mut DLManagedTensorVersioned, DLPackError> {
+    let read_only = if array.is_writeable() { 0 } else { DLPACK_FLAG_BITMASK_READ_ONLY };
+    let mut context = ExportContext::new(array)?;
+    let dl_tensor = context.tensor();
+    Ok(Box::into_raw(Box::new(DLManagedTensorVersioned {
+        version: DLPackVersion {
+            major: DLPACK_MAJOR_VERSION,
+            minor: DLPACK_MINOR_VERSION,
+        },
+        manager_ctx: Box::into_raw(context) as *mut c_void,
+        deleter: Some(delete_managed_tensor_versioned),
+        flags: read_only | extra_flags,
+        dl_tensor,
+    })))
+}
+
+/// Deleter of tensors made by `array_to_managed_tensor`
+unsafe extern "C" fn delete_managed_tensor(tensor: *mut DLManagedTensor) {
+    if !tensor.is_null() {
+        let tensor = Box::from_raw(tensor);
+        drop(Box::from_raw(tensor.manager_ctx as *mut ExportContext));
+    }
+}
+
+/// Deleter of tensors made by `array_to_managed_tensor_versioned`
+unsafe extern "C" fn delete_managed_tensor_versioned(tensor: *mut DLManagedTensorVersioned) {
+    if !tensor.is_null() {
+        let tensor = Box::from_raw(tensor);
+        drop(Box::from_raw(tensor.manager_ctx as *mut ExportContext));
+    }
+}
+
+/// An imported tensor, whose deleter runs when the last array using its
+/// memory is dropped
+enum ImportedTensor {
+    Legacy(*mut DLManagedTensor),
+    Versioned(*mut DLManagedTensorVersioned),
+}
+
+// SAFETY: the tensor is only touched again to call its deleter, which
+// DLPack allows from any thread
+unsafe impl Send for ImportedTensor {}
+unsafe impl Sync for ImportedTensor {}
+
+impl Drop for ImportedTensor {
+    fn drop(&mut self) {
+        unsafe {
+            match *self {
+                ImportedTensor::Legacy(tensor) => {
+                    if let Some(deleter) = (*tensor).deleter {
+                        deleter(tensor);
+                    }
+                }
+                ImportedTensor::Versioned(tensor) => {
+                    if let Some(deleter) = (*tensor).deleter {
+                        deleter(tensor);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Dtype, shape, byte strides and first-element pointer of a CPU tensor
+struct TensorLayout {
+    data: *mut u8,
+    shape: Vec<i64>,
+    strides: Vec<i64>,
+    dtype: DType,
+}
+
+unsafe fn tensor_layout(tensor: &DLTensor) -> Result<TensorLayout, DLPackError> {
+    // Validate device (only CPU memory can be read here)
+    if tensor.device.device_type != DLDeviceType::CPU {
+        return Err(DLPackError::InvalidDevice);
+    }
+    if tensor.ndim < 0 || (tensor.ndim > 0 && tensor.shape.is_null()) {
+        return Err(DLPackError::InvalidTensor);
+    }
+
+    // Convert dtype
+    let dtype = DType::new(dlpack_dtype_to_npy_type(tensor.dtype)?);
+    let itemsize = dtype.itemsize() as i64;
+
+    // Extract shape
+    let ndim = tensor.ndim as usize;
+    let shape: Vec<i64> = (0..ndim).map(|i| *tensor.shape.add(i)).collect();
+
+    // Extract strides, in bytes; NULL means C-contiguous
+    let strides = if tensor.strides.is_null() {
+        let mut strides = vec![itemsize; ndim];
+        for i in (0..ndim.saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1].max(1);
+        }
+        strides
+    } else {
+        (0..ndim).map(|i| *tensor.strides.add(i) * itemsize).collect()
+    };
+
+    let data = (tensor.data as *mut u8).wrapping_add(tensor.byte_offset as usize);
+    Ok(TensorLayout { data, shape, strides, dtype })
+}
+
+/// Array over the memory of `layout`, holding `owner`
+unsafe fn shared_array(
+    layout: TensorLayout,
+    owner: impl FnOnce() -> Arc<dyn std::any::Any + Send + Sync>,
+    writeable: bool,
+) -> Result<Array, DLPackError> {
+    if layout.shape.contains(&0) {
+        // Nothing to share; dropping the owner releases the tensor
+        drop(owner());
+        return Ok(Array::new(layout.shape, layout.dtype)?);
+    }
+    if layout.data.is_null() {
+        return Err(DLPackError::InvalidTensor);
+    }
+    Ok(Array::from_owner(layout.data, layout.shape, layout.strides, layout.dtype, owner(), writeable)?)
+}
+
+/// Import a `DLManagedTensor` without copying
+///
+/// On success the array owns the tensor and calls its deleter once the
+/// array and every view of it are dropped. On error the tensor is left to
+/// the caller.
+///
+/// # Safety
+/// `tensor` must point to a valid `DLManagedTensor` that the caller owns.
+pub unsafe fn managed_tensor_to_array(tensor: *mut DLManagedTensor) -> Result<Array, DLPackError> {
+    if tensor.is_null() {
+        return Err(DLPackError::InvalidTensor);
+    }
+    let layout = tensor_layout(&(*tensor).dl_tensor)?;
+    shared_array(layout, || Arc::new(ImportedTensor::Legacy(tensor)), true)
+}
+
+/// Import a `DLManagedTensorVersioned` without copying
+///
+/// Like `managed_tensor_to_array`; tensors flagged read-only give read-only
+/// arrays, and tensors from another major version are refused.
+///
+/// # Safety
+/// `tensor` must point to a valid `DLManagedTensorVersioned` that the caller owns.
+pub unsafe fn managed_tensor_versioned_to_array(tensor: *mut DLManagedTensorVersioned) -> Result<Array, DLPackError> {
+    if tensor.is_null() {
+        return Err(DLPackError::InvalidTensor);
+    }
+    let managed = &*tensor;
+    if managed.version.major != DLPACK_MAJOR_VERSION {
+        return Err(DLPackError::UnsupportedVersion(managed.version.major));
+    }
+    let layout = tensor_layout(&managed.dl_tensor)?;
+    let writeable = managed.flags & DLPACK_FLAG_BITMASK_READ_ONLY == 0;
+    shared_array(layout, || Arc::new(ImportedTensor::Versioned(tensor)), writeable)
+}
+
 /// Convert DLPack tensor to Array
 ///
 /// # Arguments
@@ -58,61 +304,24 @@ pub unsafe fn array_to_dlpack(array: &Array) -> Result<*mut DLTensor, DLPackErro
 /// * `Err(DLPackError)` if conversion fails
 ///
 /// # Safety
-/// This function assumes the DLTensor is valid and properly initialized
+/// This function assumes the DLTensor is valid and properly initialized.
+/// A bare DLTensor has no owner to keep alive, so the data is copied; use
+/// `managed_tensor_to_array` to share memory.
 pub unsafe fn dlpack_to_array(dlpack: *mut DLTensor) -> Result<Array, DLPackError> {
     if dlpack.is_null() {
         return Err(DLPackError::InvalidTensor);
     }
-    
-    let tensor = &*dlpack;
-    
-    // Validate device (only CPU supported for now)
-    if tensor.device.device_type != DLDeviceType::CPU {
-        return Err(DLPackError::InvalidDevice);
-    }
-    
-    // Convert dtype
-    let npy_type = dlpack_dtype_to_npy_type(tensor.dtype)?;
-    let dtype = DType::new(npy_type);
-    
-    // Extract shape
-    let ndim = tensor.ndim as usize;
-    let mut shape = Vec::with_capacity(ndim);
-    for i in 0..ndim {
-        shape.push(*tensor.shape.add(i));
-    }
-    
-    // Extract strides (if present)
-    let mut strides = Vec::with_capacity(ndim);
-    if tensor.strides.is_null() {
-        // Compute default strides (C-contiguous)
-        let mut stride = dtype.itemsize() as i64;
-        for &dim in shape.iter().rev() {
-            strides.push(stride);
-            stride *= dim;
-        }
-        strides.reverse();
-    } else {
-        for i in 0..ndim {
-            strides.push(*tensor.strides.add(i));
-        }
-    }
-    
-    // Create array (this will copy data - full implementation might share memory)
-    let mut array = Array::new(shape, dtype)?;
-    
-    // Copy data
-    let data_size = array.size() * array.itemsize();
-    let src_ptr = (tensor.data as *const u8).add(tensor.byte_offset as usize);
-    std::ptr::copy_nonoverlapping(src_ptr, array.data_ptr_mut(), data_size);
-    
+    let layout = tensor_layout(&*dlpack)?;
+    let borrowed = shared_array(layout, || Arc::new(()), false)?;
+    let mut array = borrowed.copy();
+    array.setflags(crate::array::ArrayFlags::WRITEABLE, true)?;
     Ok(array)
 }
 
 /// Convert NumPy type to DLPack dtype
 fn npy_type_to_dlpack_dtype(npy_type: NpyType) -> Result<DLDataType, DLPackError> {
     let (code, bits) = match npy_type {
-        NpyType::Bool => (DLDataTypeCode::UInt, 8),
+        NpyType::Bool => (DLDataTypeCode::Bool, 8),
         NpyType::Byte => (DLDataTypeCode::Int, 8),
         NpyType::UByte => (DLDataTypeCode::UInt, 8),
         NpyType::Short => (DLDataTypeCode::Int, 16),
@@ -123,11 +332,14 @@ fn npy_type_to_dlpack_dtype(npy_type: NpyType) -> Result<DLDataType, DLPackError
         NpyType::ULong => (DLDataTypeCode::UInt, 64),
         NpyType::LongLong => (DLDataTypeCode::Int, 64),
         NpyType::ULongLong => (DLDataTypeCode::UInt, 64),
+        NpyType::Half => (DLDataTypeCode::Float, 16),
         NpyType::Float => (DLDataTypeCode::Float, 32),
         NpyType::Double => (DLDataTypeCode::Float, 64),
+        NpyType::CFloat => (DLDataTypeCode::Complex, 64),
+        NpyType::CDouble => (DLDataTypeCode::Complex, 128),
         _ => return Err(DLPackError::UnsupportedDtype),
     };
-    
+
     Ok(DLDataType {
         code: code as u8,
         bits,
@@ -140,10 +352,10 @@ fn dlpack_dtype_to_npy_type(dtype: DLDataType) -> Result<NpyType, DLPackError> {
     if dtype.lanes != 1 {
         return Err(DLPackError::UnsupportedDtype);
     }
-    
+
     let code = dtype.code;
     let bits = dtype.bits;
-    
+
     match code {
         x if x == DLDataTypeCode::Int as u8 => {
             match bits {
@@ -165,12 +377,20 @@ fn dlpack_dtype_to_npy_type(dtype: DLDataType) -> Result<NpyType, DLPackError> {
         }
         x if x == DLDataTypeCode::Float as u8 => {
             match bits {
+                16 => Ok(NpyType::Half),
                 32 => Ok(NpyType::Float),
                 64 => Ok(NpyType::Double),
                 _ => Err(DLPackError::UnsupportedDtype),
             }
         }
+        x if x == DLDataTypeCode::Complex as u8 => {
+            match bits {
+                64 => Ok(NpyType::CFloat),
+                128 => Ok(NpyType::CDouble),
+                _ => Err(DLPackError::UnsupportedDtype),
+            }
+        }
+        x if x == DLDataTypeCode::Bool as u8 && bits == 8 => Ok(NpyType::Bool),
         _ => Err(DLPackError::UnsupportedDtype),
     }
 }
-
@@ -44,6 +44,12 @@ pub enum DLDataTypeCode {
     Float = 2,
     /// Opaque handle type
     OpaqueHandle = 3,
+    /// bfloat16 type
+    Bfloat = 4,
+    /// Complex type, with `bits` covering both parts
+    Complex = 5,
+    /// Boolean type
+    Bool = 6,
 }
 
 /// DLPack data type structure
@@ -79,6 +85,56 @@ pub struct DLTensor {
     pub byte_offset: u64,
 }
 
+/// DLPack ABI version
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DLPackVersion {
+    /// Major version, bumped on ABI changes
+    pub major: u32,
+    /// Minor version
+    pub minor: u32,
+}
+
+/// Major DLPack version implemented here
+pub const DLPACK_MAJOR_VERSION: u32 = 1;
+
+/// Minor DLPack version implemented here
+pub const DLPACK_MINOR_VERSION: u32 = 0;
+
+/// `DLManagedTensorVersioned` flag: the tensor must not be written to
+pub const DLPACK_FLAG_BITMASK_READ_ONLY: u64 = 1 << 0;
+
+/// `DLManagedTensorVersioned` flag: the tensor is a copy made for the export
+pub const DLPACK_FLAG_BITMASK_IS_COPIED: u64 = 1 << 1;
+
+/// DLPack tensor with its owner and deleter (DLPack 0.x)
+///
+/// The consumer calls `deleter` once it no longer needs `dl_tensor`.
+#[repr(C)]
+pub struct DLManagedTensor {
+    /// The tensor
+    pub dl_tensor: DLTensor,
+    /// Producer context, used by `deleter`
+    pub manager_ctx: *mut c_void,
+    /// Frees the tensor and releases its memory
+    pub deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
+}
+
+/// Versioned DLPack tensor with its owner, deleter and flags (DLPack 1.x)
+#[repr(C)]
+pub struct DLManagedTensorVersioned {
+    /// ABI version of the producer
+    pub version: DLPackVersion,
+    /// Producer context, used by `deleter`
+    pub manager_ctx: *mut c_void,
+    /// Frees the tensor and releases its memory
+    pub deleter: Option<unsafe extern "C" fn(*mut DLManagedTensorVersioned)>,
+    /// `DLPACK_FLAG_BITMASK_*` flags
+    pub flags: u64,
+    /// The tensor
+    pub dl_tensor: DLTensor,
+}
+
 /// DLPack error
 #[derive(Debug, Clone)]
 pub enum DLPackError {
@@ -90,6 +146,12 @@ pub enum DLPackError {
     UnsupportedDtype,
     /// Invalid tensor structure
     InvalidTensor,
+    /// Strides that are not a whole number of elements
+    UnsupportedStrides,
+    /// Read-only array exported without a way to signal it
+    ReadOnly,
+    /// Tensor from an incompatible major DLPack version
+    UnsupportedVersion(u32),
     /// Memory management error
     MemoryError(String),
 }
@@ -101,6 +163,9 @@ impl std::fmt::Display for DLPackError {
             DLPackError::InvalidDevice => write!(f, "Invalid device type"),
             DLPackError::UnsupportedDtype => write!(f, "Unsupported dtype"),
             DLPackError::InvalidTensor => write!(f, "Invalid tensor structure"),
+            DLPackError::UnsupportedStrides => write!(f, "Strides must be a multiple of the item size"),
+            DLPackError::ReadOnly => write!(f, "Cannot export a read-only array without a versioned DLPack tensor"),
+            DLPackError::UnsupportedVersion(major) => write!(f, "Unsupported DLPack major version {}", major),
             DLPackError::MemoryError(msg) => write!(f, "Memory error: {}", msg),
         }
     }
@@ -44,7 +44,10 @@ pub unsafe fn to_dlpack(array: &Array) -> Result<*mut DLTensor, DLPackError> {
 /// This function assumes the pointer is valid and was allocated by array_to_dlpack
 pub unsafe fn delete_dlpack_tensor(tensor: *mut DLTensor) {
     if !tensor.is_null() {
-        drop(Box::from_raw(tensor));
+        let tensor = Box::from_raw(tensor);
+        let ndim = tensor.ndim as usize;
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(tensor.shape, ndim)));
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(tensor.strides, ndim)));
     }
 }
 
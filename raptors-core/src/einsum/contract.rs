@@ -87,6 +87,10 @@ fn execute_binary_einsum(spec: &EinsumSpec, a: &Array, b: &Array) -> Result<Arra
             crate::linalg::LinalgError::ArrayError(ae) => ae,
             crate::linalg::LinalgError::ShapeMismatch => ArrayError::InvalidShape,
             crate::linalg::LinalgError::InvalidDimension => ArrayError::InvalidShape,
+            crate::linalg::LinalgError::UnsupportedDtype => ArrayError::TypeMismatch,
+            crate::linalg::LinalgError::ConvergenceFailure => {
+                ArrayError::InvalidValue("Algorithm did not converge".to_string())
+            }
         }));
     }
     
@@ -230,6 +234,10 @@ fn inner_product(a: &Array, b: &Array) -> Result<Array, EinsumError> {
         crate::linalg::LinalgError::ArrayError(ae) => ae,
         crate::linalg::LinalgError::ShapeMismatch => ArrayError::InvalidShape,
         crate::linalg::LinalgError::InvalidDimension => ArrayError::InvalidShape,
+        crate::linalg::LinalgError::UnsupportedDtype => ArrayError::TypeMismatch,
+        crate::linalg::LinalgError::ConvergenceFailure => {
+            ArrayError::InvalidValue("Algorithm did not converge".to_string())
+        }
     }))
 }
 
@@ -321,6 +329,10 @@ fn contract_binary(spec: &EinsumSpec, a: &Array, b: &Array) -> Result<Array, Ein
             crate::linalg::LinalgError::ArrayError(ae) => ae,
             crate::linalg::LinalgError::ShapeMismatch => ArrayError::InvalidShape,
             crate::linalg::LinalgError::InvalidDimension => ArrayError::InvalidShape,
+            crate::linalg::LinalgError::UnsupportedDtype => ArrayError::TypeMismatch,
+            crate::linalg::LinalgError::ConvergenceFailure => {
+                ArrayError::InvalidValue("Algorithm did not converge".to_string())
+            }
         }))
     } else {
         Err(EinsumError::ShapeMismatch("Complex binary contraction not yet fully implemented".to_string()))
@@ -72,6 +72,20 @@ pub unsafe fn array_to_pyarray_ptr(array: &Array) -> *mut PyArrayObject {
     pyarray_ptr
 }
 
+/// Convert an owned Array into a heap-allocated PyArrayObject* for C API
+///
+/// Unlike `array_to_pyarray_ptr`, the returned object takes over the
+/// array's data buffer, so the data stays valid after the Rust array is
+/// gone. The buffer is not released by `free_pyarray`.
+///
+/// # Safety
+/// The returned pointer must be freed using `free_pyarray` to avoid leaking the header.
+pub unsafe fn array_into_pyarray_ptr(array: Array) -> *mut PyArrayObject {
+    let ptr = array_to_pyarray_ptr(&array);
+    std::mem::forget(array);
+    ptr
+}
+
 /// Free a heap-allocated PyArrayObject
 ///
 /// # Safety
@@ -212,8 +226,15 @@ pub fn type_num_to_npytype(type_num: c_int) -> Option<NpyType> {
         6 => Some(NpyType::UInt),
         7 => Some(NpyType::Long),
         8 => Some(NpyType::ULong),
+        9 => Some(NpyType::LongLong),
+        10 => Some(NpyType::ULongLong),
         11 => Some(NpyType::Float),
         12 => Some(NpyType::Double),
+        14 => Some(NpyType::CFloat),
+        15 => Some(NpyType::CDouble),
+        21 => Some(NpyType::DateTime),
+        22 => Some(NpyType::Timedelta),
+        23 => Some(NpyType::Half),
         _ => None,
     }
 }
@@ -230,8 +251,15 @@ pub fn npytype_to_type_num(npy_type: NpyType) -> c_int {
         NpyType::UInt => 6,
         NpyType::Long => 7,
         NpyType::ULong => 8,
+        NpyType::LongLong => 9,
+        NpyType::ULongLong => 10,
         NpyType::Float => 11,
         NpyType::Double => 12,
+        NpyType::CFloat => 14,
+        NpyType::CDouble => 15,
+        NpyType::DateTime => 21,
+        NpyType::Timedelta => 22,
+        NpyType::Half => 23,
         _ => -1, // Unsupported
     }
 }
@@ -0,0 +1,545 @@
+//! Array creation C API
+//!
+//! This module provides C API wrappers for the array creation routines:
+//! numerical ranges, filled and `*_like` arrays, identity and triangular
+//! matrices, grids and `fromfunction`
+
+use crate::array::{self, Array, ArrayError, GridRange, MeshIndexing, Order};
+use crate::ffi::{conversion, PyArrayObject};
+use crate::types::DType;
+use libc::{c_int, c_void};
+use std::ptr;
+
+/// NumPy's `NPY_ANYORDER`
+const NPY_ANYORDER: c_int = -1;
+/// NumPy's `NPY_CORDER`
+const NPY_CORDER: c_int = 0;
+/// NumPy's `NPY_FORTRANORDER`
+const NPY_FORTRANORDER: c_int = 1;
+/// NumPy's `NPY_KEEPORDER`
+const NPY_KEEPORDER: c_int = 2;
+
+fn order_from_c(order: c_int) -> Option<Order> {
+    match order {
+        NPY_ANYORDER => Some(Order::A),
+        NPY_CORDER => Some(Order::C),
+        NPY_FORTRANORDER => Some(Order::F),
+        NPY_KEEPORDER => Some(Order::K),
+        _ => None,
+    }
+}
+
+fn dtype_from_c(type_num: c_int) -> Option<DType> {
+    conversion::type_num_to_npytype(type_num).map(DType::new)
+}
+
+/// Hand a creation result over to C, or return NULL on error
+fn into_ptr(result: Result<Array, ArrayError>) -> *mut PyArrayObject {
+    match result {
+        Ok(array) => unsafe { conversion::array_into_pyarray_ptr(array) },
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Read `nd` dimensions from a C array
+///
+/// # Safety
+/// `dims` must point to at least `nd` elements if not null.
+unsafe fn read_dims(nd: c_int, dims: *const i64) -> Option<Vec<i64>> {
+    if dims.is_null() || !(0..=64).contains(&nd) {
+        return None;
+    }
+    let shape = std::slice::from_raw_parts(dims, nd as usize).to_vec();
+    if shape.iter().any(|&d| d < 0) {
+        return None;
+    }
+    Some(shape)
+}
+
+/// Write a list of arrays to `out`, returning 0 on success and -1 on error
+///
+/// # Safety
+/// `out` must point to room for every array in `result` if not null.
+unsafe fn write_all(result: Result<Vec<Array>, ArrayError>, out: *mut *mut PyArrayObject) -> c_int {
+    match result {
+        Ok(arrays) if !out.is_null() => {
+            for (i, array) in arrays.into_iter().enumerate() {
+                *out.add(i) = conversion::array_into_pyarray_ptr(array);
+            }
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Evenly spaced values within a half-open interval
+///
+/// Equivalent to NumPy's PyArray_Arange function.
+#[no_mangle]
+pub extern "C" fn PyArray_Arange(start: f64, stop: f64, step: f64, type_num: c_int) -> *mut PyArrayObject {
+    match dtype_from_c(type_num) {
+        Some(dtype) => into_ptr(array::arange(start, stop, step, dtype)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Evenly spaced samples over an interval
+///
+/// Equivalent to `numpy.linspace`. If `retstep` is not null the spacing
+/// between samples is written to it.
+///
+/// # Safety
+/// The caller must ensure `retstep` is a valid pointer to a double if not null.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Linspace(
+    start: f64,
+    stop: f64,
+    num: i64,
+    endpoint: c_int,
+    retstep: *mut f64,
+    type_num: c_int,
+) -> *mut PyArrayObject {
+    let dtype = match dtype_from_c(type_num) {
+        Some(dtype) if num >= 0 => dtype,
+        _ => return ptr::null_mut(),
+    };
+    match array::linspace_retstep(start, stop, num as usize, endpoint != 0, dtype) {
+        Ok((array, step)) => {
+            if !retstep.is_null() {
+                unsafe { *retstep = step };
+            }
+            into_ptr(Ok(array))
+        }
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Numbers spaced evenly on a log scale
+///
+/// Equivalent to `numpy.logspace`.
+#[no_mangle]
+pub extern "C" fn PyArray_Logspace(
+    start: f64,
+    stop: f64,
+    num: i64,
+    endpoint: c_int,
+    base: f64,
+    type_num: c_int,
+) -> *mut PyArrayObject {
+    match dtype_from_c(type_num) {
+        Some(dtype) if num >= 0 => into_ptr(array::logspace(start, stop, num as usize, endpoint != 0, base, dtype)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Numbers spaced evenly on a log scale between two endpoints
+///
+/// Equivalent to `numpy.geomspace`.
+#[no_mangle]
+pub extern "C" fn PyArray_Geomspace(
+    start: f64,
+    stop: f64,
+    num: i64,
+    endpoint: c_int,
+    type_num: c_int,
+) -> *mut PyArrayObject {
+    match dtype_from_c(type_num) {
+        Some(dtype) if num >= 0 => into_ptr(array::geomspace(start, stop, num as usize, endpoint != 0, dtype)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Create an array filled with one element
+///
+/// Equivalent to `numpy.full`. `fill_value` points to a single element of
+/// the requested dtype, which is copied into every position.
+///
+/// # Safety
+/// The caller must ensure `dims` points to at least `nd` elements and
+/// `fill_value` to one element of the dtype given by `type_num`.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Full(
+    nd: c_int,
+    dims: *const i64,
+    type_num: c_int,
+    fill_value: *const c_void,
+    is_f_order: c_int,
+) -> *mut PyArrayObject {
+    let (shape, dtype) = match (unsafe { read_dims(nd, dims) }, dtype_from_c(type_num)) {
+        (Some(shape), Some(dtype)) if !fill_value.is_null() => (shape, dtype),
+        _ => return ptr::null_mut(),
+    };
+    let order = if is_f_order != 0 { Order::F } else { Order::C };
+    into_ptr(Array::new_with_order(shape, dtype, order).map(|mut array| {
+        let element = unsafe { std::slice::from_raw_parts(fill_value as *const u8, array.itemsize()) };
+        array::fill_bytes(&mut array, element);
+        array
+    }))
+}
+
+/// Shared body of the `*_like` functions
+///
+/// # Safety
+/// `prototype` must be a valid PyArrayObject pointer if not null.
+unsafe fn like(
+    prototype: *mut PyArrayObject,
+    type_num: c_int,
+    order: c_int,
+    make: impl FnOnce(&Array, Option<DType>, Order) -> Result<Array, ArrayError>,
+) -> *mut PyArrayObject {
+    if prototype.is_null() {
+        return ptr::null_mut();
+    }
+    let dtype = if type_num < 0 {
+        None
+    } else {
+        match dtype_from_c(type_num) {
+            Some(dtype) => Some(dtype),
+            None => return ptr::null_mut(),
+        }
+    };
+    let (proto, order) = match (conversion::pyarray_to_array_view(prototype), order_from_c(order)) {
+        (Ok(proto), Some(order)) => (proto, order),
+        _ => return ptr::null_mut(),
+    };
+    // The view is built C-contiguous; restore the prototype's strides so
+    // NPY_KEEPORDER and NPY_ANYORDER see its real layout
+    let nd = (*prototype).nd as usize;
+    let strides = (0..nd).map(|i| (*prototype).strides[i]).collect();
+    let proto = match proto.view(proto.shape().to_vec(), strides) {
+        Ok(proto) => proto,
+        Err(_) => return ptr::null_mut(),
+    };
+    into_ptr(make(&proto, dtype, order))
+}
+
+/// Uninitialized array with the shape of `prototype`
+///
+/// Equivalent to `numpy.empty_like`. A negative `type_num` keeps the
+/// prototype's dtype; `order` is an `NPY_ORDER` value.
+///
+/// # Safety
+/// The caller must ensure `prototype` is a valid pointer to a PyArrayObject.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_EmptyLike(prototype: *mut PyArrayObject, type_num: c_int, order: c_int) -> *mut PyArrayObject {
+    unsafe { like(prototype, type_num, order, array::empty_like) }
+}
+
+/// Zero-filled array with the shape of `prototype`
+///
+/// Equivalent to `numpy.zeros_like`; see `PyArray_EmptyLike`.
+///
+/// # Safety
+/// The caller must ensure `prototype` is a valid pointer to a PyArrayObject.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_ZerosLike(prototype: *mut PyArrayObject, type_num: c_int, order: c_int) -> *mut PyArrayObject {
+    unsafe { like(prototype, type_num, order, array::zeros_like) }
+}
+
+/// One-filled array with the shape of `prototype`
+///
+/// Equivalent to `numpy.ones_like`; see `PyArray_EmptyLike`.
+///
+/// # Safety
+/// The caller must ensure `prototype` is a valid pointer to a PyArrayObject.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_OnesLike(prototype: *mut PyArrayObject, type_num: c_int, order: c_int) -> *mut PyArrayObject {
+    unsafe { like(prototype, type_num, order, array::ones_like) }
+}
+
+/// Array with the shape of `prototype` filled with one element
+///
+/// Equivalent to `numpy.full_like`. `fill_value` points to a single element
+/// of the result dtype; see `PyArray_EmptyLike` for the other arguments.
+///
+/// # Safety
+/// The caller must ensure `prototype` is a valid pointer to a PyArrayObject
+/// and `fill_value` points to one element of the result dtype.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_FullLike(
+    prototype: *mut PyArrayObject,
+    fill_value: *const c_void,
+    type_num: c_int,
+    order: c_int,
+) -> *mut PyArrayObject {
+    if fill_value.is_null() {
+        return ptr::null_mut();
+    }
+    unsafe {
+        like(prototype, type_num, order, |proto, dtype, order| {
+            let mut array = array::empty_like(proto, dtype, order)?;
+            let element = std::slice::from_raw_parts(fill_value as *const u8, array.itemsize());
+            array::fill_bytes(&mut array, element);
+            Ok(array)
+        })
+    }
+}
+
+/// Array with ones on the `k`-th diagonal
+///
+/// Equivalent to `numpy.eye`. A negative `m` means `m = n`.
+#[no_mangle]
+pub extern "C" fn PyArray_Eye(n: i64, m: i64, k: i64, type_num: c_int) -> *mut PyArrayObject {
+    match dtype_from_c(type_num) {
+        Some(dtype) if n >= 0 => into_ptr(array::eye(n as usize, (m >= 0).then_some(m as usize), k, dtype)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Identity matrix
+///
+/// Equivalent to `numpy.identity`.
+#[no_mangle]
+pub extern "C" fn PyArray_Identity(n: i64, type_num: c_int) -> *mut PyArrayObject {
+    PyArray_Eye(n, -1, 0, type_num)
+}
+
+/// Extract a diagonal or construct a diagonal array
+///
+/// Equivalent to `numpy.diag`.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a PyArrayObject.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Diag(arr: *mut PyArrayObject, k: i64) -> *mut PyArrayObject {
+    if arr.is_null() {
+        return ptr::null_mut();
+    }
+    match unsafe { conversion::pyarray_to_array_view(arr) } {
+        Ok(v) => into_ptr(array::diag(&v, k)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Array with ones at and below the `k`-th diagonal
+///
+/// Equivalent to `numpy.tri`. A negative `m` means `m = n`.
+#[no_mangle]
+pub extern "C" fn PyArray_Tri(n: i64, m: i64, k: i64, type_num: c_int) -> *mut PyArrayObject {
+    match dtype_from_c(type_num) {
+        Some(dtype) if n >= 0 => into_ptr(array::tri(n as usize, (m >= 0).then_some(m as usize), k, dtype)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Lower triangle of an array
+///
+/// Equivalent to `numpy.tril`.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a PyArrayObject.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Tril(arr: *mut PyArrayObject, k: i64) -> *mut PyArrayObject {
+    if arr.is_null() {
+        return ptr::null_mut();
+    }
+    match unsafe { conversion::pyarray_to_array_view(arr) } {
+        Ok(a) => into_ptr(array::tril(&a, k)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Upper triangle of an array
+///
+/// Equivalent to `numpy.triu`.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a PyArrayObject.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Triu(arr: *mut PyArrayObject, k: i64) -> *mut PyArrayObject {
+    if arr.is_null() {
+        return ptr::null_mut();
+    }
+    match unsafe { conversion::pyarray_to_array_view(arr) } {
+        Ok(a) => into_ptr(array::triu(&a, k)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Vandermonde matrix
+///
+/// Equivalent to `numpy.vander`. A negative `n` means `n = len(x)`.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a PyArrayObject.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Vander(arr: *mut PyArrayObject, n: i64, increasing: c_int) -> *mut PyArrayObject {
+    if arr.is_null() {
+        return ptr::null_mut();
+    }
+    match unsafe { conversion::pyarray_to_array_view(arr) } {
+        Ok(x) => into_ptr(array::vander(&x, (n >= 0).then_some(n as usize), increasing != 0)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Coordinate matrices from coordinate vectors
+///
+/// Equivalent to `numpy.meshgrid`. Writes `n` new arrays to `out` and
+/// returns 0, or returns -1 on error. `indexing_ij` selects matrix
+/// indexing instead of Cartesian.
+///
+/// # Safety
+/// The caller must ensure `arrays` points to `n` valid PyArrayObject
+/// pointers and `out` has room for `n` pointers.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Meshgrid(
+    n: c_int,
+    arrays: *const *mut PyArrayObject,
+    indexing_ij: c_int,
+    sparse: c_int,
+    out: *mut *mut PyArrayObject,
+) -> c_int {
+    if arrays.is_null() || n < 0 {
+        return -1;
+    }
+    unsafe {
+        let inputs: Result<Vec<Array>, ArrayError> = std::slice::from_raw_parts(arrays, n as usize)
+            .iter()
+            .map(|&a| conversion::pyarray_to_array_view(a))
+            .collect();
+        let inputs = match inputs {
+            Ok(inputs) => inputs,
+            Err(_) => return -1,
+        };
+        let refs: Vec<&Array> = inputs.iter().collect();
+        let indexing = if indexing_ij != 0 { MeshIndexing::Ij } else { MeshIndexing::Xy };
+        write_all(array::meshgrid(&refs, indexing, sparse != 0), out)
+    }
+}
+
+/// Read grid ranges from C arrays
+///
+/// `counts[i] > 0` requests that many points from `starts[i]` to
+/// `stops[i]` inclusive; otherwise `steps[i]` is the step.
+///
+/// # Safety
+/// `starts`, `stops` and `steps` must point to `n` elements; `counts` to
+/// `n` elements if not null.
+unsafe fn read_ranges(
+    n: c_int,
+    starts: *const f64,
+    stops: *const f64,
+    steps: *const f64,
+    counts: *const i64,
+) -> Option<Vec<GridRange>> {
+    if starts.is_null() || stops.is_null() || steps.is_null() || n < 0 {
+        return None;
+    }
+    Some(
+        (0..n as usize)
+            .map(|i| {
+                let count = if counts.is_null() { 0 } else { *counts.add(i) };
+                if count > 0 {
+                    GridRange::num(*starts.add(i), *stops.add(i), count as usize)
+                } else {
+                    GridRange::new(*starts.add(i), *stops.add(i), *steps.add(i))
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Dense mesh grid
+///
+/// Equivalent to `numpy.mgrid[...]`; see `read_ranges` for how ranges are
+/// given.
+///
+/// # Safety
+/// `starts`, `stops` and `steps` must point to `n` elements; `counts` to
+/// `n` elements if not null.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Mgrid(
+    n: c_int,
+    starts: *const f64,
+    stops: *const f64,
+    steps: *const f64,
+    counts: *const i64,
+) -> *mut PyArrayObject {
+    match unsafe { read_ranges(n, starts, stops, steps, counts) } {
+        Some(ranges) => into_ptr(array::mgrid(&ranges)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Open mesh grid
+///
+/// Equivalent to `numpy.ogrid[...]`. Writes `n` arrays to `out` and returns
+/// 0, or returns -1 on error.
+///
+/// # Safety
+/// As for `PyArray_Mgrid`; `out` must have room for `n` pointers.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Ogrid(
+    n: c_int,
+    starts: *const f64,
+    stops: *const f64,
+    steps: *const f64,
+    counts: *const i64,
+    out: *mut *mut PyArrayObject,
+) -> c_int {
+    match unsafe { read_ranges(n, starts, stops, steps, counts) } {
+        Some(ranges) => unsafe { write_all(array::ogrid(&ranges), out) },
+        None => -1,
+    }
+}
+
+/// Callback for `PyArray_FromFunction`
+///
+/// Receives the element index (`nd` values) and writes one element of the
+/// array dtype to `out`.
+pub type FromFunctionCallback =
+    extern "C" fn(index: *const i64, nd: c_int, out: *mut c_void, user_data: *mut c_void);
+
+/// Construct an array by calling `func` for each element
+///
+/// Equivalent to `numpy.fromfunction`, with one call per element.
+///
+/// # Safety
+/// The caller must ensure `dims` points to at least `nd` elements and that
+/// `func` writes exactly one element of the dtype to `out`.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_FromFunction(
+    nd: c_int,
+    dims: *const i64,
+    type_num: c_int,
+    func: Option<FromFunctionCallback>,
+    user_data: *mut c_void,
+) -> *mut PyArrayObject {
+    let (shape, dtype, func) = match (unsafe { read_dims(nd, dims) }, dtype_from_c(type_num), func) {
+        (Some(shape), Some(dtype), Some(func)) => (shape, dtype, func),
+        _ => return ptr::null_mut(),
+    };
+    let mut array = match Array::new(shape.clone(), dtype) {
+        Ok(array) => array,
+        Err(_) => return ptr::null_mut(),
+    };
+    let itemsize = array.itemsize();
+    let mut index = vec![0i64; shape.len()];
+    let data = array.data_ptr_mut();
+    for i in 0..array.size() {
+        func(index.as_ptr(), nd, unsafe { data.add(i * itemsize) } as *mut c_void, user_data);
+        for axis in (0..index.len()).rev() {
+            index[axis] += 1;
+            if index[axis] < shape[axis] {
+                break;
+            }
+            index[axis] = 0;
+        }
+    }
+    into_ptr(Ok(array))
+}
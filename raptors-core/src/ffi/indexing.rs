@@ -1,13 +1,64 @@
 //! Indexing and selection C API
 //!
 //! This module provides C API wrappers for indexing and selection operations,
-//! equivalent to NumPy's indexing functions
+//! equivalent to NumPy's indexing functions. Each one builds an index
+//! expression and leaves the selection to `Array::index` and
+//! `Array::index_assign`.
 
+use crate::array::{Array, MAXDIMS};
+use crate::conversion::convert_array;
 use crate::ffi::{PyArrayObject, conversion};
-use crate::indexing::{fancy_index_array, boolean_index_array};
+use crate::indexing::{IndexError, IndexExpr, Slice};
+use crate::types::{DType, NpyType};
+use crate::utils::to_contiguous_bytes;
 use libc::c_int;
 use std::ptr;
 
+/// 1-D view of a C-contiguous array
+fn ravel(array: &Array) -> Result<Array, IndexError> {
+    if !array.is_c_contiguous() {
+        return Err(IndexError::InvalidValue("array must be C-contiguous".to_string()));
+    }
+    Ok(array.view(vec![array.size() as i64], vec![array.itemsize() as i64])?)
+}
+
+/// Index expression applying `expr` along `axis` of `array`
+///
+/// Negative axes count from the end. An axis of `MAXDIMS` or more applies
+/// `expr` to the flattened array, like NumPy's `NPY_RAVEL_AXIS`.
+fn along_axis(array: Array, axis: c_int, expr: IndexExpr) -> Result<(Array, Vec<IndexExpr>), IndexError> {
+    if axis >= MAXDIMS as c_int {
+        return Ok((ravel(&array)?, vec![expr]));
+    }
+    let ndim = array.ndim() as c_int;
+    let axis = if axis < 0 { axis + ndim } else { axis };
+    if axis < 0 || axis >= ndim {
+        return Err(IndexError::InvalidExpression(format!("axis {} is out of bounds for array of dimension {}", axis, ndim)));
+    }
+    let mut exprs = vec![IndexExpr::Slice(Slice::full()); axis as usize];
+    exprs.push(expr);
+    Ok((array, exprs))
+}
+
+/// Boolean copy of a condition or mask array
+fn as_mask(array: &Array) -> Result<Array, IndexError> {
+    if array.dtype().type_() == NpyType::Bool {
+        return Ok(array.copy());
+    }
+    convert_array(array, DType::new(NpyType::Bool)).map_err(|e| IndexError::InvalidValue(e.to_string()))
+}
+
+/// 1-D array of `count` elements repeating the elements of `values`
+fn cycle(values: &Array, count: usize) -> Result<Array, IndexError> {
+    let itemsize = values.itemsize();
+    let bytes = to_contiguous_bytes(values);
+    if bytes.is_empty() {
+        return Err(IndexError::InvalidValue("cannot take values from an empty array".to_string()));
+    }
+    let repeated: Vec<u8> = bytes.chunks(itemsize).cycle().take(count).flatten().copied().collect();
+    Ok(Array::from_slice(&repeated, vec![count as i64], values.dtype().clone())?)
+}
+
 /// Take elements using index array
 ///
 /// Equivalent to NumPy's PyArray_Take function.
@@ -19,43 +70,44 @@ use std::ptr;
 pub extern "C" fn PyArray_Take(
     arr: *mut PyArrayObject,
     indices: *mut PyArrayObject,
-    _axis: c_int, // Axis (simplified - not used yet)
+    axis: c_int,
     _out: *mut PyArrayObject, // Output array (simplified - not used yet)
     _mode: c_int, // Mode (simplified - not used yet)
 ) -> *mut PyArrayObject {
     if arr.is_null() || indices.is_null() {
         return ptr::null_mut();
     }
-    
+
     unsafe {
         // Convert PyArrayObject to Array
         let array = match conversion::pyarray_to_array_view(arr) {
             Ok(a) => a,
             Err(_) => return ptr::null_mut(),
         };
-        
+
         let index_array = match conversion::pyarray_to_array_view(indices) {
             Ok(a) => a,
             Err(_) => return ptr::null_mut(),
         };
-        
-        // Use fancy indexing
-        let result = match fancy_index_array(&array, &index_array) {
-            Ok(a) => a,
-            Err(_) => return ptr::null_mut(),
-        };
-        
-        // Convert back to PyArrayObject
-        conversion::array_to_pyarray_ptr(&result)
+
+        // The index array's dimensions replace the indexed axis
+        let result = along_axis(array, axis, IndexExpr::IntArray(index_array))
+            .and_then(|(array, exprs)| array.index(&exprs));
+        match result {
+            Ok(a) => conversion::array_into_pyarray_ptr(a),
+            Err(_) => ptr::null_mut(),
+        }
     }
 }
 
 /// Put values using index array
 ///
-/// Equivalent to NumPy's PyArray_Put function.
+/// Equivalent to NumPy's PyArray_Put function: `arr.flat[indices] = values`,
+/// repeating `values` as needed.
 ///
 /// # Safety
-/// The caller must ensure `arr`, `indices`, and `values` are valid pointers to PyArrayObject.
+/// The caller must ensure `arr`, `indices`, and `values` are valid pointers to PyArrayObject,
+/// and that nothing else accesses the data of `arr` during the call.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn PyArray_Put(
@@ -67,52 +119,47 @@ pub extern "C" fn PyArray_Put(
     if arr.is_null() || indices.is_null() || values.is_null() {
         return -1; // Error
     }
-    
+
     unsafe {
-        // Convert PyArrayObject to Array (mutable for arr)
-        let mut array = match conversion::pyarray_to_array_owned(arr) {
+        // The view writes straight into arr's data, which the caller
+        // guarantees nothing else accesses meanwhile
+        let array = match conversion::pyarray_to_array_view(arr) {
             Ok(a) => a,
             Err(_) => return -1,
         };
-        
+
         let index_array = match conversion::pyarray_to_array_view(indices) {
             Ok(a) => a,
             Err(_) => return -1,
         };
-        
+
         let value_array = match conversion::pyarray_to_array_view(values) {
             Ok(a) => a,
             Err(_) => return -1,
         };
-        
-        // Use fancy indexing to get indices, then copy values
-        // Simplified implementation - full version would handle broadcasting
-        if index_array.size() != value_array.size() {
-            return -1;
+
+        let result = (|| {
+            let flat = ravel(&array)?;
+            let flat_indices = ravel(&index_array)?;
+            let values = cycle(&value_array, flat_indices.size())?;
+            flat.index_assign(&[IndexExpr::IntArray(flat_indices)], &values)
+        })();
+        match result {
+            Ok(()) => 0, // Success
+            Err(_) => -1,
         }
-        
-        // Copy values at indexed positions (simplified)
-        // Full implementation would use proper indexing
-        let size = array.size().min(value_array.size()) * array.itemsize().min(value_array.itemsize());
-        std::ptr::copy_nonoverlapping(
-            value_array.data_ptr(),
-            array.data_ptr_mut(),
-            size,
-        );
-        
-        // Note: In a full implementation, we would need to update the original array
-        // For now, this is a simplified version
-        
-        0 // Success
     }
 }
 
 /// Put values using boolean mask
 ///
-/// Equivalent to NumPy's PyArray_PutMask function.
+/// Equivalent to NumPy's PyArray_PutMask function: every element of `arr`
+/// where `mask` is true is set to the element of `values` at the same flat
+/// position, repeating `values` as needed.
 ///
 /// # Safety
-/// The caller must ensure `arr`, `mask`, and `values` are valid pointers to PyArrayObject.
+/// The caller must ensure `arr`, `mask`, and `values` are valid pointers to PyArrayObject,
+/// and that nothing else accesses the data of `arr` during the call.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn PyArray_PutMask(
@@ -123,41 +170,43 @@ pub extern "C" fn PyArray_PutMask(
     if arr.is_null() || mask.is_null() || values.is_null() {
         return -1; // Error
     }
-    
+
     unsafe {
-        // Convert PyArrayObject to Array
-        let mut array = match conversion::pyarray_to_array_owned(arr) {
+        // The view writes straight into arr's data, which the caller
+        // guarantees nothing else accesses meanwhile
+        let array = match conversion::pyarray_to_array_view(arr) {
             Ok(a) => a,
             Err(_) => return -1,
         };
-        
+
         let mask_array = match conversion::pyarray_to_array_view(mask) {
             Ok(a) => a,
             Err(_) => return -1,
         };
-        
+
         let value_array = match conversion::pyarray_to_array_view(values) {
             Ok(a) => a,
             Err(_) => return -1,
         };
-        
-        // Simplified implementation - copy values where mask is true
-        // Full implementation would handle proper masking and broadcasting
-        let size = array.size().min(mask_array.size()).min(value_array.size()) * 
-                   array.itemsize().min(value_array.itemsize());
-        std::ptr::copy_nonoverlapping(
-            value_array.data_ptr(),
-            array.data_ptr_mut(),
-            size,
-        );
-        
-        0 // Success
+
+        let result = (|| {
+            let flat = ravel(&array)?;
+            let exprs = [IndexExpr::BoolArray(as_mask(&ravel(&mask_array)?)?)];
+            let picked = cycle(&value_array, flat.size())?.index(&exprs)?;
+            flat.index_assign(&exprs, &picked)
+        })();
+        match result {
+            Ok(()) => 0, // Success
+            Err(_) => -1,
+        }
     }
 }
 
 /// Choose elements from arrays
 ///
-/// Equivalent to NumPy's PyArray_Choose function.
+/// Equivalent to NumPy's PyArray_Choose function, with the choices stacked
+/// along the first axis of `choices`: element `i` of the result is
+/// `choices[arr[i], i]`.
 ///
 /// # Safety
 /// The caller must ensure `arr` and `choices` are valid pointers to PyArrayObject.
@@ -172,28 +221,36 @@ pub extern "C" fn PyArray_Choose(
     if arr.is_null() || choices.is_null() {
         return ptr::null_mut();
     }
-    
+
     unsafe {
         // Convert PyArrayObject to Array
         let array = match conversion::pyarray_to_array_view(arr) {
             Ok(a) => a,
             Err(_) => return ptr::null_mut(),
         };
-        
+
         let choices_array = match conversion::pyarray_to_array_view(choices) {
             Ok(a) => a,
             Err(_) => return ptr::null_mut(),
         };
-        
-        // Simplified implementation - use first choice array
-        // Full implementation would handle multiple choice arrays
-        let result = match fancy_index_array(&choices_array, &array) {
-            Ok(a) => a,
-            Err(_) => return ptr::null_mut(),
-        };
-        
-        // Convert back to PyArrayObject
-        conversion::array_to_pyarray_ptr(&result)
+
+        // arr picks the choice; an index array per remaining axis, each
+        // shaped to broadcast along its own axis, picks the position
+        let result = (|| {
+            let ndim = array.ndim();
+            let mut exprs = vec![IndexExpr::IntArray(array.copy())];
+            for (axis, &len) in array.shape().iter().enumerate() {
+                let mut shape = vec![1; ndim];
+                shape[axis] = len;
+                let positions: Vec<i64> = (0..len).collect();
+                exprs.push(IndexExpr::IntArray(Array::from_slice(&positions, shape, DType::new(NpyType::Long))?));
+            }
+            choices_array.index(&exprs)
+        })();
+        match result {
+            Ok(a) => conversion::array_into_pyarray_ptr(a),
+            Err(_) => ptr::null_mut(),
+        }
     }
 }
 
@@ -208,33 +265,32 @@ pub extern "C" fn PyArray_Choose(
 pub extern "C" fn PyArray_Compress(
     arr: *mut PyArrayObject,
     condition: *mut PyArrayObject,
-    _axis: c_int, // Axis (simplified - not used yet)
+    axis: c_int,
     _out: *mut PyArrayObject, // Output array (simplified - not used yet)
 ) -> *mut PyArrayObject {
     if arr.is_null() || condition.is_null() {
         return ptr::null_mut();
     }
-    
+
     unsafe {
         // Convert PyArrayObject to Array
         let array = match conversion::pyarray_to_array_view(arr) {
             Ok(a) => a,
             Err(_) => return ptr::null_mut(),
         };
-        
+
         let condition_array = match conversion::pyarray_to_array_view(condition) {
             Ok(a) => a,
             Err(_) => return ptr::null_mut(),
         };
-        
-        // Use boolean indexing
-        let result = match boolean_index_array(&array, &condition_array) {
-            Ok(a) => a,
-            Err(_) => return ptr::null_mut(),
-        };
-        
-        // Convert back to PyArrayObject
-        conversion::array_to_pyarray_ptr(&result)
+
+        // Use boolean indexing along the axis
+        let result = as_mask(&condition_array)
+            .and_then(|mask| along_axis(array, axis, IndexExpr::BoolArray(mask)))
+            .and_then(|(array, exprs)| array.index(&exprs));
+        match result {
+            Ok(a) => conversion::array_into_pyarray_ptr(a),
+            Err(_) => ptr::null_mut(),
+        }
     }
 }
-
@@ -4,9 +4,11 @@
 //! equivalent to NumPy's file I/O functions
 
 use crate::ffi::{PyArrayObject, conversion};
+use crate::array::Array;
 use crate::io::{save_npy, load_npy, save_text, load_text, SaveTextOptions, LoadTextOptions};
+use crate::io::{savez, savez_compressed, NpzFile};
 use libc::{c_char, c_int};
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::ptr;
 
 /// Save array to NPY file
@@ -190,3 +192,199 @@ pub extern "C" fn PyArray_LoadText(
     }
 }
 
+/// Save `n` named arrays to an NPZ archive
+///
+/// # Safety
+/// `filename` must be a valid null-terminated C string, and `names` and
+/// `arrays` must each point to `n` valid entries.
+unsafe fn savez_impl(
+    filename: *const c_char,
+    n: c_int,
+    names: *const *const c_char,
+    arrays: *const *mut PyArrayObject,
+    compress: bool,
+) -> c_int {
+    if filename.is_null() || n < 0 || (n > 0 && (names.is_null() || arrays.is_null())) {
+        return -1;
+    }
+    let filename_str = match CStr::from_ptr(filename).to_str() {
+        Ok(s) => s,
+        Err(_) => return -1,
+    };
+
+    let mut members: Vec<(&str, Array)> = Vec::with_capacity(n as usize);
+    for i in 0..n as usize {
+        let name = *names.add(i);
+        let arr = *arrays.add(i);
+        if name.is_null() || arr.is_null() {
+            return -1;
+        }
+        let name = match CStr::from_ptr(name).to_str() {
+            Ok(s) => s,
+            Err(_) => return -1,
+        };
+        match conversion::pyarray_to_array_view(arr) {
+            Ok(array) => members.push((name, array)),
+            Err(_) => return -1,
+        }
+    }
+
+    let members: Vec<(&str, &Array)> = members.iter().map(|(name, array)| (*name, array)).collect();
+    let result = if compress {
+        savez_compressed(filename_str, &members)
+    } else {
+        savez(filename_str, &members)
+    };
+    match result {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Save several arrays to an uncompressed NPZ archive
+///
+/// Equivalent to NumPy's `savez` with keyword names: array `i` is stored
+/// as the member `names[i]`.
+///
+/// # Safety
+/// The caller must ensure `filename` is a valid null-terminated C string.
+/// The caller must ensure `names` and `arrays` each point to `n` valid pointers.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_Savez(
+    filename: *const c_char,
+    n: c_int,
+    names: *const *const c_char,
+    arrays: *const *mut PyArrayObject,
+) -> c_int {
+    unsafe { savez_impl(filename, n, names, arrays, false) }
+}
+
+/// Save several arrays to a deflate-compressed NPZ archive
+///
+/// Equivalent to NumPy's `savez_compressed`.
+///
+/// # Safety
+/// The caller must ensure `filename` is a valid null-terminated C string.
+/// The caller must ensure `names` and `arrays` each point to `n` valid pointers.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_SavezCompressed(
+    filename: *const c_char,
+    n: c_int,
+    names: *const *const c_char,
+    arrays: *const *mut PyArrayObject,
+) -> c_int {
+    unsafe { savez_impl(filename, n, names, arrays, true) }
+}
+
+/// Opaque handle to an open NPZ archive
+///
+/// Created by `PyArray_NpzOpen` and released by `PyArray_NpzClose`.
+pub struct PyArrayNpzFile {
+    file: NpzFile,
+    names: Vec<CString>,
+}
+
+/// Open an NPZ archive for lazy loading
+///
+/// Only the archive directory is read; members are decoded by
+/// `PyArray_NpzLoad`. Returns null on error.
+///
+/// # Safety
+/// The caller must ensure `filename` is a valid null-terminated C string.
+/// The returned handle must be released with `PyArray_NpzClose`.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_NpzOpen(filename: *const c_char) -> *mut PyArrayNpzFile {
+    if filename.is_null() {
+        return ptr::null_mut();
+    }
+    let filename_str = match unsafe { CStr::from_ptr(filename) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return ptr::null_mut(),
+    };
+    let file = match NpzFile::open(filename_str) {
+        Ok(f) => f,
+        Err(_) => return ptr::null_mut(),
+    };
+    let names = match file.files().into_iter().map(CString::new).collect() {
+        Ok(names) => names,
+        Err(_) => return ptr::null_mut(),
+    };
+    Box::into_raw(Box::new(PyArrayNpzFile { file, names }))
+}
+
+/// Number of members in an NPZ archive, or -1 on error
+///
+/// # Safety
+/// The caller must ensure `npz` is a handle returned by `PyArray_NpzOpen`.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_NpzCount(npz: *const PyArrayNpzFile) -> c_int {
+    if npz.is_null() {
+        return -1;
+    }
+    let npz = unsafe { &*npz };
+    npz.names.len() as c_int
+}
+
+/// Name of member `i` of an NPZ archive, without the `.npy` suffix
+///
+/// The string is owned by the handle and stays valid until it is closed.
+/// Returns null if `i` is out of range.
+///
+/// # Safety
+/// The caller must ensure `npz` is a handle returned by `PyArray_NpzOpen`.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_NpzName(npz: *const PyArrayNpzFile, i: c_int) -> *const c_char {
+    if npz.is_null() || i < 0 {
+        return ptr::null();
+    }
+    let npz = unsafe { &*npz };
+    match npz.names.get(i as usize) {
+        Some(name) => name.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// Load a member of an NPZ archive
+///
+/// `name` may be given with or without the `.npy` suffix. The member is
+/// decoded and its checksum verified on each call. Returns null on error.
+///
+/// # Safety
+/// The caller must ensure `npz` is a handle returned by `PyArray_NpzOpen`.
+/// The caller must ensure `name` is a valid null-terminated C string.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_NpzLoad(npz: *mut PyArrayNpzFile, name: *const c_char) -> *mut PyArrayObject {
+    if npz.is_null() || name.is_null() {
+        return ptr::null_mut();
+    }
+    unsafe {
+        let npz = &mut *npz;
+        let name_str = match CStr::from_ptr(name).to_str() {
+            Ok(s) => s,
+            Err(_) => return ptr::null_mut(),
+        };
+        match npz.file.load(name_str) {
+            Ok(array) => conversion::array_into_pyarray_ptr(array),
+            Err(_) => ptr::null_mut(),
+        }
+    }
+}
+
+/// Close an NPZ archive handle
+///
+/// # Safety
+/// The caller must ensure `npz` is null or a handle returned by
+/// `PyArray_NpzOpen` that has not already been closed.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn PyArray_NpzClose(npz: *mut PyArrayNpzFile) {
+    if !npz.is_null() {
+        unsafe { drop(Box::from_raw(npz)) };
+    }
+}
@@ -5,6 +5,7 @@
 
 mod array_api;
 mod conversion;
+mod creation;
 mod views;
 mod manipulation;
 mod indexing;
@@ -17,6 +18,7 @@ mod einsum;
 
 pub use array_api::*;
 pub use conversion::*;
+pub use creation::*;
 pub use views::*;
 pub use manipulation::*;
 pub use indexing::*;
@@ -0,0 +1,92 @@
+//! FFT helper routines
+//!
+//! Sample frequencies and spectrum shifting, equivalent to NumPy's
+//! `fftfreq`, `rfftfreq`, `fftshift` and `ifftshift`.
+
+use crate::array::Array;
+use crate::manipulation::{roll, ManipulationError};
+use crate::types::{DType, NpyType};
+
+use super::FftError;
+
+impl From<ManipulationError> for FftError {
+    fn from(err: ManipulationError) -> Self {
+        match err {
+            ManipulationError::ArrayError(e) => FftError::ArrayError(e),
+            ManipulationError::InvalidAxis => FftError::InvalidAxis,
+        }
+    }
+}
+
+/// Return the sample frequencies for `fft` output of length `n`
+///
+/// Equivalent to `numpy.fft.fftfreq(n, d)`: `[0, 1, ..., n/2-1, -n/2, ..., -1] / (d*n)`
+/// for even `n`, where `d` is the sample spacing.
+pub fn fftfreq(n: usize, d: f64) -> Result<Array, FftError> {
+    if n == 0 {
+        return Err(FftError::InvalidLength);
+    }
+    let scale = 1.0 / (n as f64 * d);
+    let positive = (n - 1) / 2 + 1;
+    let values: Vec<f64> = (0..n)
+        .map(|i| {
+            let k = if i < positive { i as i64 } else { i as i64 - n as i64 };
+            k as f64 * scale
+        })
+        .collect();
+    Ok(Array::from_slice(&values, vec![n as i64], DType::new(NpyType::Double))?)
+}
+
+/// Return the sample frequencies for `rfft` output of a length-`n` signal
+///
+/// Equivalent to `numpy.fft.rfftfreq(n, d)`: `[0, 1, ..., n/2] / (d*n)`.
+pub fn rfftfreq(n: usize, d: f64) -> Result<Array, FftError> {
+    if n == 0 {
+        return Err(FftError::InvalidLength);
+    }
+    let scale = 1.0 / (n as f64 * d);
+    let values: Vec<f64> = (0..n / 2 + 1).map(|i| i as f64 * scale).collect();
+    Ok(Array::from_slice(&values, vec![values.len() as i64], DType::new(NpyType::Double))?)
+}
+
+fn shift(a: &Array, axes: Option<&[usize]>, inverse: bool) -> Result<Array, FftError> {
+    let axes: Vec<usize> = axes.map_or_else(|| (0..a.ndim()).collect(), |axes| axes.to_vec());
+    let mut result: Option<Array> = None;
+    for axis in axes {
+        if axis >= a.ndim() {
+            return Err(FftError::InvalidAxis);
+        }
+        let len = a.shape()[axis];
+        if len == 0 {
+            continue;
+        }
+        let amount = if inverse { -(len / 2) } else { len / 2 };
+        result = Some(roll(result.as_ref().unwrap_or(a), amount, Some(axis))?);
+    }
+    match result {
+        Some(result) => Ok(result),
+        None => {
+            // Nothing to roll: return a contiguous copy
+            let bytes = crate::utils::to_contiguous_bytes(a);
+            let mut copy = Array::new(a.shape().to_vec(), a.dtype().clone())?;
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), copy.data_ptr_mut(), bytes.len());
+            }
+            Ok(copy)
+        }
+    }
+}
+
+/// Shift the zero-frequency component to the center of the spectrum
+///
+/// Equivalent to `numpy.fft.fftshift(x, axes)`; shifts all axes by default.
+pub fn fftshift(a: &Array, axes: Option<&[usize]>) -> Result<Array, FftError> {
+    shift(a, axes, false)
+}
+
+/// Inverse of `fftshift`
+///
+/// Equivalent to `numpy.fft.ifftshift(x, axes)`.
+pub fn ifftshift(a: &Array, axes: Option<&[usize]>) -> Result<Array, FftError> {
+    shift(a, axes, true)
+}
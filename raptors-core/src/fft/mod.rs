@@ -0,0 +1,13 @@
+//! Discrete Fourier transform module
+//!
+//! This module provides FFTs over arrays, equivalent to NumPy's `numpy.fft`,
+//! using mixed-radix Cooley-Tukey for smooth lengths and Bluestein's
+//! algorithm for lengths with large prime factors
+
+mod helpers;
+mod plan;
+mod transform;
+
+pub use helpers::*;
+pub use plan::*;
+pub use transform::*;
@@ -0,0 +1,334 @@
+//! FFT plans
+//!
+//! A plan holds everything needed to transform one length: the radix
+//! factorization and twiddle factors for mixed-radix Cooley-Tukey, or the
+//! chirp and kernel for Bluestein's algorithm when the length has a large
+//! prime factor. Plans for the most recently used lengths are cached and
+//! shared between transforms.
+
+use crate::types::Complex128;
+use std::sync::{Arc, Mutex};
+
+/// Largest prime factor handled by the mixed-radix butterflies before
+/// switching to Bluestein's algorithm
+const MAX_RADIX: usize = 13;
+
+/// Number of plans kept by `cached_plan`
+pub const PLAN_CACHE_CAPACITY: usize = 32;
+
+/// Plans shared by `cached_plan`
+static PLAN_CACHE: Mutex<PlanCache> = Mutex::new(PlanCache::new(PLAN_CACHE_CAPACITY));
+
+/// Algorithm selected for a plan
+#[derive(Debug)]
+enum Algorithm {
+    /// Length 0 or 1: the transform is the identity
+    Identity,
+    /// Mixed-radix decimation in time over `(radix, remaining length)` stages
+    MixedRadix {
+        factors: Vec<(usize, usize)>,
+        twiddles: Vec<Complex128>,
+    },
+    /// Bluestein's chirp-z algorithm via a power-of-two convolution
+    Bluestein {
+        inner: Arc<FftPlan>,
+        chirp: Vec<Complex128>,
+        kernel: Vec<Complex128>,
+    },
+}
+
+/// Precomputed plan for complex FFTs of one length
+#[derive(Debug)]
+pub struct FftPlan {
+    len: usize,
+    algorithm: Algorithm,
+}
+
+/// Split `n` into radix stages, preferring radix 4, then 2, then odd primes
+fn factorize(mut n: usize) -> Vec<(usize, usize)> {
+    let mut factors = Vec::new();
+    let mut p = 4;
+    while n > 1 {
+        while !n.is_multiple_of(p) {
+            p = match p {
+                4 => 2,
+                2 => 3,
+                _ => p + 2,
+            };
+            if p * p > n {
+                p = n;
+            }
+        }
+        n /= p;
+        factors.push((p, n));
+    }
+    factors
+}
+
+fn twiddle(k: usize, n: usize) -> Complex128 {
+    Complex128::from_polar(1.0, -2.0 * std::f64::consts::PI * k as f64 / n as f64)
+}
+
+impl FftPlan {
+    /// Create a plan for transforms of length `len`
+    pub fn new(len: usize) -> Self {
+        if len <= 1 {
+            return FftPlan { len, algorithm: Algorithm::Identity };
+        }
+        let factors = factorize(len);
+        if factors.iter().all(|&(p, _)| p <= MAX_RADIX) {
+            let twiddles = (0..len).map(|k| twiddle(k, len)).collect();
+            return FftPlan { len, algorithm: Algorithm::MixedRadix { factors, twiddles } };
+        }
+
+        // Bluestein: X[k] = w[k] * sum_j (x[j] w[j]) conj(w[k - j]), w[k] = exp(-i pi k^2 / n)
+        let m = (2 * len - 1).next_power_of_two();
+        let inner = cached_plan(m);
+        let chirp: Vec<Complex128> = (0..len)
+            .map(|k| {
+                // k^2 mod 2n keeps the angle argument small and exact
+                let k2 = ((k as u128 * k as u128) % (2 * len as u128)) as f64;
+                Complex128::from_polar(1.0, -std::f64::consts::PI * k2 / len as f64)
+            })
+            .collect();
+        let mut kernel = vec![Complex128::default(); m];
+        kernel[0] = chirp[0].conj();
+        for k in 1..len {
+            kernel[k] = chirp[k].conj();
+            kernel[m - k] = chirp[k].conj();
+        }
+        inner.forward(&mut kernel);
+        FftPlan { len, algorithm: Algorithm::Bluestein { inner, chirp, kernel } }
+    }
+
+    /// Get the transform length
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this is a plan for empty input
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check whether this plan uses Bluestein's algorithm
+    pub fn is_bluestein(&self) -> bool {
+        matches!(self.algorithm, Algorithm::Bluestein { .. })
+    }
+
+    /// Compute the unnormalized forward transform in place
+    ///
+    /// `buffer` must have exactly `len()` elements.
+    pub fn forward(&self, buffer: &mut [Complex128]) {
+        assert_eq!(buffer.len(), self.len, "FFT buffer length does not match plan");
+        match &self.algorithm {
+            Algorithm::Identity => {}
+            Algorithm::MixedRadix { factors, twiddles } => {
+                let input = buffer.to_vec();
+                work(buffer, &input, 0, 1, factors, twiddles);
+            }
+            Algorithm::Bluestein { inner, chirp, kernel } => {
+                let m = kernel.len();
+                let mut conv = vec![Complex128::default(); m];
+                for k in 0..self.len {
+                    conv[k] = buffer[k] * chirp[k];
+                }
+                inner.forward(&mut conv);
+                for (c, &k) in conv.iter_mut().zip(kernel) {
+                    *c *= k;
+                }
+                inner.inverse(&mut conv);
+                let scale = 1.0 / m as f64;
+                for k in 0..self.len {
+                    buffer[k] = (conv[k] * chirp[k]).scale(scale);
+                }
+            }
+        }
+    }
+
+    /// Compute the unnormalized inverse transform in place
+    ///
+    /// Uses the identity `ifft(x) = conj(fft(conj(x)))`, so one plan serves
+    /// both directions.
+    pub fn inverse(&self, buffer: &mut [Complex128]) {
+        for v in buffer.iter_mut() {
+            *v = v.conj();
+        }
+        self.forward(buffer);
+        for v in buffer.iter_mut() {
+            *v = v.conj();
+        }
+    }
+}
+
+/// Recursive mixed-radix step: transform `input[offset + j * stride]` into `out`
+fn work(
+    out: &mut [Complex128],
+    input: &[Complex128],
+    offset: usize,
+    stride: usize,
+    factors: &[(usize, usize)],
+    twiddles: &[Complex128],
+) {
+    let (p, m) = factors[0];
+    if m == 1 {
+        for (j, o) in out.iter_mut().enumerate() {
+            *o = input[offset + j * stride];
+        }
+    } else {
+        for j in 0..p {
+            work(&mut out[j * m..(j + 1) * m], input, offset + j * stride, stride * p, &factors[1..], twiddles);
+        }
+    }
+    match p {
+        2 => butterfly2(out, stride, m, twiddles),
+        3 => butterfly3(out, stride, m, twiddles),
+        4 => butterfly4(out, stride, m, twiddles),
+        _ => butterfly_generic(out, stride, m, p, twiddles),
+    }
+}
+
+fn butterfly2(out: &mut [Complex128], stride: usize, m: usize, tw: &[Complex128]) {
+    for k in 0..m {
+        let t = out[k + m] * tw[k * stride];
+        out[k + m] = out[k] - t;
+        out[k] += t;
+    }
+}
+
+fn butterfly3(out: &mut [Complex128], stride: usize, m: usize, tw: &[Complex128]) {
+    let epi3 = tw[stride * m].im;
+    for k in 0..m {
+        let s1 = out[k + m] * tw[k * stride];
+        let s2 = out[k + 2 * m] * tw[2 * k * stride];
+        let s3 = s1 + s2;
+        let s0 = (s1 - s2).scale(epi3);
+        let mid = out[k] - s3.scale(0.5);
+        out[k] += s3;
+        out[k + 2 * m] = Complex128::new(mid.re + s0.im, mid.im - s0.re);
+        out[k + m] = Complex128::new(mid.re - s0.im, mid.im + s0.re);
+    }
+}
+
+fn butterfly4(out: &mut [Complex128], stride: usize, m: usize, tw: &[Complex128]) {
+    for k in 0..m {
+        let s0 = out[k + m] * tw[k * stride];
+        let s1 = out[k + 2 * m] * tw[2 * k * stride];
+        let s2 = out[k + 3 * m] * tw[3 * k * stride];
+        let s5 = out[k] - s1;
+        let f0 = out[k] + s1;
+        let s3 = s0 + s2;
+        let s4 = s0 - s2;
+        out[k + 2 * m] = f0 - s3;
+        out[k] = f0 + s3;
+        out[k + m] = Complex128::new(s5.re + s4.im, s5.im - s4.re);
+        out[k + 3 * m] = Complex128::new(s5.re - s4.im, s5.im + s4.re);
+    }
+}
+
+fn butterfly_generic(out: &mut [Complex128], stride: usize, m: usize, p: usize, tw: &[Complex128]) {
+    let n = tw.len();
+    let mut scratch = vec![Complex128::default(); p];
+    for u in 0..m {
+        for (q, s) in scratch.iter_mut().enumerate() {
+            *s = out[u + q * m];
+        }
+        for q1 in 0..p {
+            let k = u + q1 * m;
+            let mut acc = scratch[0];
+            let mut tw_index = 0;
+            for &s in &scratch[1..] {
+                tw_index += stride * k;
+                if tw_index >= n {
+                    tw_index %= n;
+                }
+                acc += s * tw[tw_index];
+            }
+            out[k] = acc;
+        }
+    }
+}
+
+/// Plans for the most recently used lengths
+///
+/// Holds at most `capacity` plans; adding one more drops the plan whose
+/// length was used least recently.
+#[derive(Debug)]
+pub struct PlanCache {
+    capacity: usize,
+    /// `(length, plan)` pairs, least recently used first
+    entries: Vec<(usize, Arc<FftPlan>)>,
+}
+
+impl PlanCache {
+    /// Create an empty cache holding at most `capacity` plans
+    pub const fn new(capacity: usize) -> Self {
+        PlanCache { capacity, entries: Vec::new() }
+    }
+
+    /// Get the plan for `len`, marking it most recently used
+    pub fn get(&mut self, len: usize) -> Option<Arc<FftPlan>> {
+        let position = self.entries.iter().position(|(n, _)| *n == len)?;
+        let entry = self.entries.remove(position);
+        let plan = entry.1.clone();
+        self.entries.push(entry);
+        Some(plan)
+    }
+
+    /// Add `plan` for `len`, evicting the least recently used plan if full
+    ///
+    /// Returns the cached plan, which is the existing one if `len` was
+    /// already cached.
+    pub fn insert(&mut self, len: usize, plan: Arc<FftPlan>) -> Arc<FftPlan> {
+        if let Some(existing) = self.get(len) {
+            return existing;
+        }
+        if self.capacity == 0 {
+            return plan;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.remove(0);
+        }
+        self.entries.push((len, plan.clone()));
+        plan
+    }
+
+    /// Number of cached plans
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no plans are cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop all cached plans
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Get the shared plan for length `len`, creating and caching it if needed
+///
+/// The cache holds the `PLAN_CACHE_CAPACITY` most recently used lengths,
+/// so code that transforms many distinct lengths does not keep every
+/// twiddle table alive.
+pub fn cached_plan(len: usize) -> Arc<FftPlan> {
+    if let Some(plan) = PLAN_CACHE.lock().unwrap().get(len) {
+        return plan;
+    }
+    // Build outside the lock: Bluestein plans request their inner plan
+    let plan = Arc::new(FftPlan::new(len));
+    PLAN_CACHE.lock().unwrap().insert(len, plan)
+}
+
+/// Number of plans currently cached
+pub fn plan_cache_size() -> usize {
+    PLAN_CACHE.lock().unwrap().len()
+}
+
+/// Drop all cached plans
+pub fn clear_plan_cache() {
+    PLAN_CACHE.lock().unwrap().clear();
+}
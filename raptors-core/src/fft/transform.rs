@@ -0,0 +1,345 @@
+//! Discrete Fourier transforms
+//!
+//! Complex and real transforms along one axis or over several axes,
+//! equivalent to NumPy's `numpy.fft` functions.
+
+use crate::array::{Array, ArrayError};
+use crate::types::{Complex128, DType, NpyType};
+
+use super::cached_plan;
+
+/// FFT error
+#[derive(Debug, Clone)]
+pub enum FftError {
+    /// Array error
+    ArrayError(ArrayError),
+    /// Axis out of range for the array
+    InvalidAxis,
+    /// Transform length must be at least 1
+    InvalidLength,
+    /// Unknown normalization mode
+    InvalidNorm(String),
+    /// `s` and `axes` have different lengths
+    ShapeMismatch,
+    /// Dtype is not supported by this transform
+    UnsupportedDtype,
+}
+
+impl std::fmt::Display for FftError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FftError::ArrayError(e) => write!(f, "Array error: {}", e),
+            FftError::InvalidAxis => write!(f, "Axis out of range"),
+            FftError::InvalidLength => write!(f, "Invalid number of FFT data points"),
+            FftError::InvalidNorm(norm) => {
+                write!(f, "Invalid norm value '{}'; should be \"backward\", \"ortho\" or \"forward\"", norm)
+            }
+            FftError::ShapeMismatch => write!(f, "Shape and axes have different lengths"),
+            FftError::UnsupportedDtype => write!(f, "Unsupported dtype"),
+        }
+    }
+}
+
+impl std::error::Error for FftError {}
+
+impl From<ArrayError> for FftError {
+    fn from(err: ArrayError) -> Self {
+        FftError::ArrayError(err)
+    }
+}
+
+/// Normalization mode, equivalent to NumPy's `norm` argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FftNorm {
+    /// No scaling on the forward transform, `1/n` on the inverse (NumPy's default)
+    #[default]
+    Backward,
+    /// `1/sqrt(n)` on both directions, making the transforms unitary
+    Ortho,
+    /// `1/n` on the forward transform, no scaling on the inverse
+    Forward,
+}
+
+impl FftNorm {
+    /// Parse a NumPy normalization name (`"backward"`, `"ortho"`, `"forward"`)
+    pub fn from_name(name: &str) -> Result<Self, FftError> {
+        match name {
+            "backward" => Ok(FftNorm::Backward),
+            "ortho" => Ok(FftNorm::Ortho),
+            "forward" => Ok(FftNorm::Forward),
+            other => Err(FftError::InvalidNorm(other.to_string())),
+        }
+    }
+
+    /// Scale factor applied to a transform of length `n`
+    fn factor(self, n: usize, inverse: bool) -> f64 {
+        match (self, inverse) {
+            (FftNorm::Backward, false) | (FftNorm::Forward, true) => 1.0,
+            (FftNorm::Ortho, _) => 1.0 / (n as f64).sqrt(),
+            (FftNorm::Backward, true) | (FftNorm::Forward, false) => 1.0 / n as f64,
+        }
+    }
+}
+
+/// Working buffer: complex values in C order with their shape
+struct Spectrum {
+    shape: Vec<usize>,
+    data: Vec<Complex128>,
+    single: bool,
+}
+
+impl Spectrum {
+    fn from_array(array: &Array) -> Result<Self, FftError> {
+        let data = crate::utils::to_complex128_vec(array).ok_or(FftError::UnsupportedDtype)?;
+        let single = matches!(array.dtype().type_(), NpyType::Float | NpyType::CFloat);
+        Ok(Spectrum {
+            shape: array.shape().iter().map(|&d| d as usize).collect(),
+            data,
+            single,
+        })
+    }
+
+    fn check_axis(&self, axis: usize) -> Result<(), FftError> {
+        if axis >= self.shape.len() {
+            Err(FftError::InvalidAxis)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Transform every lane along `axis`
+    ///
+    /// Each lane is truncated or zero-padded to `n` points before the
+    /// transform; `keep` output points per lane are retained.
+    fn transform_axis(&mut self, axis: usize, n: usize, keep: usize, inverse: bool, norm: FftNorm) {
+        let len = self.shape[axis];
+        let inner: usize = self.shape[axis + 1..].iter().product();
+        let outer: usize = self.shape[..axis].iter().product();
+        let plan = cached_plan(n);
+        let scale = norm.factor(n, inverse);
+        let copy_len = len.min(n);
+
+        let mut output = vec![Complex128::default(); outer * keep * inner];
+        let process_block = |(o, out_block): (usize, &mut [Complex128])| {
+            let mut lane = vec![Complex128::default(); n];
+            let in_block = &self.data[o * len * inner..(o + 1) * len * inner];
+            for i in 0..inner {
+                for (k, v) in lane.iter_mut().enumerate() {
+                    *v = if k < copy_len { in_block[k * inner + i] } else { Complex128::default() };
+                }
+                if inverse {
+                    plan.inverse(&mut lane);
+                } else {
+                    plan.forward(&mut lane);
+                }
+                for k in 0..keep {
+                    out_block[k * inner + i] = lane[k].scale(scale);
+                }
+            }
+        };
+
+        let block = keep * inner;
+        if block > 0 {
+            if crate::performance::threading::should_parallelize(outer * n * inner) && outer > 1 {
+                use rayon::prelude::*;
+                output.par_chunks_mut(block).enumerate().for_each(process_block);
+            } else {
+                output.chunks_mut(block).enumerate().for_each(process_block);
+            }
+        }
+
+        self.shape[axis] = keep;
+        self.data = output;
+    }
+
+    fn into_complex_array(self) -> Result<Array, FftError> {
+        let shape: Vec<i64> = self.shape.iter().map(|&d| d as i64).collect();
+        if self.single {
+            let narrow: Vec<_> = self.data.iter().map(|c| c.to_complex64()).collect();
+            Ok(Array::from_slice(&narrow, shape, DType::new(NpyType::CFloat))?)
+        } else {
+            Ok(Array::from_slice(&self.data, shape, DType::new(NpyType::CDouble))?)
+        }
+    }
+
+    fn into_real_array(self) -> Result<Array, FftError> {
+        let shape: Vec<i64> = self.shape.iter().map(|&d| d as i64).collect();
+        if self.single {
+            let narrow: Vec<f32> = self.data.iter().map(|c| c.re as f32).collect();
+            Ok(Array::from_slice(&narrow, shape, DType::new(NpyType::Float))?)
+        } else {
+            let real: Vec<f64> = self.data.iter().map(|c| c.re).collect();
+            Ok(Array::from_slice(&real, shape, DType::new(NpyType::Double))?)
+        }
+    }
+}
+
+fn transform_1d(
+    a: &Array,
+    n: Option<usize>,
+    axis: usize,
+    norm: FftNorm,
+    inverse: bool,
+) -> Result<Array, FftError> {
+    let mut spectrum = Spectrum::from_array(a)?;
+    spectrum.check_axis(axis)?;
+    let n = n.unwrap_or(spectrum.shape[axis]);
+    if n == 0 {
+        return Err(FftError::InvalidLength);
+    }
+    spectrum.transform_axis(axis, n, n, inverse, norm);
+    spectrum.into_complex_array()
+}
+
+/// Compute the one-dimensional discrete Fourier transform
+///
+/// Equivalent to `numpy.fft.fft(a, n, axis, norm)`.
+///
+/// # Arguments
+/// * `a` - Input array (real or complex)
+/// * `n` - Length of the transformed axis; the input is truncated or zero-padded
+/// * `axis` - Axis over which to compute the transform
+/// * `norm` - Normalization mode
+///
+/// # Returns
+/// A `CDouble` array (`CFloat` for single-precision input)
+pub fn fft(a: &Array, n: Option<usize>, axis: usize, norm: FftNorm) -> Result<Array, FftError> {
+    transform_1d(a, n, axis, norm, false)
+}
+
+/// Compute the one-dimensional inverse discrete Fourier transform
+///
+/// Equivalent to `numpy.fft.ifft(a, n, axis, norm)`.
+pub fn ifft(a: &Array, n: Option<usize>, axis: usize, norm: FftNorm) -> Result<Array, FftError> {
+    transform_1d(a, n, axis, norm, true)
+}
+
+/// Compute the one-dimensional transform of real input
+///
+/// Equivalent to `numpy.fft.rfft(a, n, axis, norm)`. Only the `n / 2 + 1`
+/// non-negative frequency terms are returned.
+pub fn rfft(a: &Array, n: Option<usize>, axis: usize, norm: FftNorm) -> Result<Array, FftError> {
+    if matches!(a.dtype().type_(), NpyType::CFloat | NpyType::CDouble) {
+        return Err(FftError::UnsupportedDtype);
+    }
+    let mut spectrum = Spectrum::from_array(a)?;
+    spectrum.check_axis(axis)?;
+    let n = n.unwrap_or(spectrum.shape[axis]);
+    if n == 0 {
+        return Err(FftError::InvalidLength);
+    }
+    spectrum.transform_axis(axis, n, n / 2 + 1, false, norm);
+    spectrum.into_complex_array()
+}
+
+/// Compute the inverse of `rfft`, producing real output
+///
+/// Equivalent to `numpy.fft.irfft(a, n, axis, norm)`. The output length
+/// defaults to `2 * (m - 1)` where `m` is the input length along `axis`.
+pub fn irfft(a: &Array, n: Option<usize>, axis: usize, norm: FftNorm) -> Result<Array, FftError> {
+    let mut spectrum = Spectrum::from_array(a)?;
+    spectrum.check_axis(axis)?;
+    let m = spectrum.shape[axis];
+    let n = n.unwrap_or(2 * m.saturating_sub(1));
+    if n == 0 {
+        return Err(FftError::InvalidLength);
+    }
+
+    // Rebuild the full Hermitian-symmetric spectrum of length n
+    let half = n / 2 + 1;
+    let inner: usize = spectrum.shape[axis + 1..].iter().product();
+    let outer: usize = spectrum.shape[..axis].iter().product();
+    let mut full = vec![Complex128::default(); outer * n * inner];
+    for o in 0..outer {
+        for i in 0..inner {
+            let src = |k: usize| spectrum.data[(o * m + k) * inner + i];
+            let dst = |k: usize| (o * n + k) * inner + i;
+            for k in 0..half.min(m) {
+                full[dst(k)] = src(k);
+            }
+            for k in half..n {
+                full[dst(k)] = full[dst(n - k)].conj();
+            }
+        }
+    }
+    spectrum.shape[axis] = n;
+    spectrum.data = full;
+
+    spectrum.transform_axis(axis, n, n, true, norm);
+    spectrum.into_real_array()
+}
+
+/// Resolve the `s`/`axes` arguments of the n-dimensional transforms
+fn resolve_axes(
+    shape: &[usize],
+    s: Option<&[usize]>,
+    axes: Option<&[usize]>,
+    default_axes: usize,
+) -> Result<Vec<(usize, usize)>, FftError> {
+    let axes: Vec<usize> = match (axes, s) {
+        (Some(axes), _) => axes.to_vec(),
+        (None, Some(s)) => (shape.len().saturating_sub(s.len())..shape.len()).collect(),
+        (None, None) => (shape.len().saturating_sub(default_axes)..shape.len()).collect(),
+    };
+    if let Some(s) = s {
+        if s.len() != axes.len() {
+            return Err(FftError::ShapeMismatch);
+        }
+    }
+    axes.iter()
+        .enumerate()
+        .map(|(i, &axis)| {
+            if axis >= shape.len() {
+                return Err(FftError::InvalidAxis);
+            }
+            let n = s.map_or(shape[axis], |s| s[i]);
+            if n == 0 {
+                return Err(FftError::InvalidLength);
+            }
+            Ok((axis, n))
+        })
+        .collect()
+}
+
+fn transform_nd(
+    a: &Array,
+    s: Option<&[usize]>,
+    axes: Option<&[usize]>,
+    norm: FftNorm,
+    inverse: bool,
+    default_axes: usize,
+) -> Result<Array, FftError> {
+    let mut spectrum = Spectrum::from_array(a)?;
+    for (axis, n) in resolve_axes(&spectrum.shape, s, axes, default_axes)? {
+        spectrum.transform_axis(axis, n, n, inverse, norm);
+    }
+    spectrum.into_complex_array()
+}
+
+/// Compute the two-dimensional discrete Fourier transform
+///
+/// Equivalent to `numpy.fft.fft2(a, s, axes, norm)`; defaults to the last two axes.
+pub fn fft2(a: &Array, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Result<Array, FftError> {
+    transform_nd(a, s, axes, norm, false, 2)
+}
+
+/// Compute the two-dimensional inverse discrete Fourier transform
+///
+/// Equivalent to `numpy.fft.ifft2(a, s, axes, norm)`.
+pub fn ifft2(a: &Array, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Result<Array, FftError> {
+    transform_nd(a, s, axes, norm, true, 2)
+}
+
+/// Compute the n-dimensional discrete Fourier transform
+///
+/// Equivalent to `numpy.fft.fftn(a, s, axes, norm)`; defaults to all axes.
+pub fn fftn(a: &Array, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Result<Array, FftError> {
+    transform_nd(a, s, axes, norm, false, a.ndim())
+}
+
+/// Compute the n-dimensional inverse discrete Fourier transform
+///
+/// Equivalent to `numpy.fft.ifftn(a, s, axes, norm)`.
+pub fn ifftn(a: &Array, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Result<Array, FftError> {
+    transform_nd(a, s, axes, norm, true, a.ndim())
+}
@@ -0,0 +1,494 @@
+//! Multi-axis index expressions
+//!
+//! Equivalent to NumPy's `mapping.c`. An index expression is a list of
+//! `IndexExpr` items applied in two passes. Basic items (integers, slices,
+//! `...` and new axes) narrow a strided view of the array, so an expression
+//! made only of them selects a view. Integer and boolean arrays then gather
+//! from that view into a copy: the index arrays broadcast together, and
+//! their dimensions replace the indexed ones in place when they are
+//! adjacent, or move to the front when a slice, `...` or new axis separates
+//! them. Assignment resolves the same selection and broadcasts the value
+//! into it.
+
+use super::{normalize_slice, slice_length, IndexError, Slice};
+use crate::array::Array;
+use crate::broadcasting::{broadcast_shapes_multi, broadcast_strides};
+use crate::conversion::convert_array;
+use crate::types::NpyType;
+use crate::utils::to_contiguous_bytes;
+use std::sync::Arc;
+
+/// One item of an index expression
+#[derive(Debug, Clone)]
+pub enum IndexExpr {
+    /// A single position; negative values count from the end
+    Int(i64),
+    /// A range of positions
+    Slice(Slice),
+    /// `...`: full slices over every dimension not otherwise indexed
+    Ellipsis,
+    /// `None` (`numpy.newaxis`): a new dimension of length one
+    NewAxis,
+    /// Integer index array, of any integer dtype and layout
+    IntArray(Array),
+    /// Boolean mask over as many dimensions as it has; a 0-d mask is a
+    /// scalar `True` or `False`
+    BoolArray(Array),
+}
+
+impl From<i64> for IndexExpr {
+    fn from(index: i64) -> Self {
+        IndexExpr::Int(index)
+    }
+}
+
+impl From<Slice> for IndexExpr {
+    fn from(slice: Slice) -> Self {
+        IndexExpr::Slice(slice)
+    }
+}
+
+impl IndexExpr {
+    /// Whether the item is an index array, which makes indexing advanced
+    pub fn is_advanced(&self) -> bool {
+        matches!(self, IndexExpr::IntArray(_) | IndexExpr::BoolArray(_))
+    }
+}
+
+/// Whether `exprs` selects a single element of an `ndim`-dimensional array
+///
+/// NumPy returns a scalar rather than a 0-d array exactly in this case.
+pub fn is_scalar_index(exprs: &[IndexExpr], ndim: usize) -> bool {
+    exprs.len() == ndim && exprs.iter().all(|expr| matches!(expr, IndexExpr::Int(_)))
+}
+
+/// Where the selected elements live, relative to the array's data pointer
+enum Selection {
+    /// A strided view starting `offset` bytes in
+    View { offset: isize, shape: Vec<i64>, strides: Vec<i64> },
+    /// Byte offsets of the gathered elements, in C order of `shape`
+    Gather { shape: Vec<i64>, offsets: Vec<isize> },
+}
+
+impl Selection {
+    fn shape(&self) -> &[i64] {
+        match self {
+            Selection::View { shape, .. } | Selection::Gather { shape, .. } => shape,
+        }
+    }
+
+    /// Byte range `[start, end)` the selection touches, if it is not empty
+    fn extent(&self, itemsize: usize) -> Option<(isize, isize)> {
+        match self {
+            Selection::View { offset, shape, strides } => {
+                extent(shape, strides, itemsize).map(|(start, end)| (offset + start, offset + end))
+            }
+            Selection::Gather { offsets, .. } => {
+                let start = *offsets.iter().min()?;
+                let end = *offsets.iter().max()? + itemsize as isize;
+                Some((start, end))
+            }
+        }
+    }
+}
+
+/// An index array applied to one dimension of the basic view
+struct Advanced {
+    dim: usize,
+    values: Vec<i64>,
+    shape: Vec<i64>,
+}
+
+impl Array {
+    /// Select `self[exprs]`
+    ///
+    /// Returns a view when `exprs` has no index arrays and a new array
+    /// otherwise. As with `view`, a returned view does not keep `self`
+    /// alive; use `index_from_arc` when the array is shared.
+    pub fn index(&self, exprs: &[IndexExpr]) -> Result<Array, IndexError> {
+        match select(self, exprs)? {
+            Selection::View { offset, shape, strides } => Ok(self.view_at(offset, shape, strides)?),
+            Selection::Gather { shape, offsets } => gather(self, shape, &offsets),
+        }
+    }
+
+    /// Select `base[exprs]`, with views keeping `base` alive
+    pub fn index_from_arc(base: &Arc<Array>, exprs: &[IndexExpr]) -> Result<Array, IndexError> {
+        match select(base, exprs)? {
+            Selection::View { offset, shape, strides } => Ok(Array::view_from_arc_at(base, offset, shape, strides)?),
+            Selection::Gather { shape, offsets } => gather(base, shape, &offsets),
+        }
+    }
+
+    /// Assign `self[exprs] = value`
+    ///
+    /// `value` is cast to this array's dtype and broadcast to the shape of
+    /// the selection. Elements are written through the data pointer, as
+    /// they are for every view of this array. `value` may overlap the
+    /// selection; it is then copied before anything is written.
+    ///
+    /// # Safety
+    /// The write goes through a shared reference, so the caller must ensure
+    /// that nothing else reads or writes the selected elements during the
+    /// call: not another thread holding this array, a view of it or an array
+    /// it is a view of, nor a consumer of memory exported from it (buffer
+    /// protocol, DLPack, the C API).
+    pub unsafe fn index_assign(&self, exprs: &[IndexExpr], value: &Array) -> Result<(), IndexError> {
+        if !self.is_writeable() {
+            return Err(IndexError::InvalidValue("assignment destination is read-only".to_string()));
+        }
+        let selection = select(self, exprs)?;
+        let shape = selection.shape();
+
+        let converted;
+        let source = if value.dtype().type_() == self.dtype().type_() && value.itemsize() == self.itemsize() {
+            value
+        } else {
+            converted = convert_array(value, self.dtype().clone()).map_err(|e| {
+                IndexError::InvalidValue(format!("cannot assign {:?} values to a {:?} array: {}", value.dtype().type_(), self.dtype().type_(), e))
+            })?;
+            &converted
+        };
+        let fits = source.ndim() <= shape.len()
+            && source.shape().iter().rev().zip(shape.iter().rev()).all(|(&s, &d)| s == d || s == 1);
+        let broadcast = |strides: &[i64]| {
+            broadcast_strides(source.shape(), strides, shape).ok().filter(|_| fits).ok_or_else(|| {
+                IndexError::InvalidValue(format!(
+                    "could not broadcast input array from shape {} into shape {}",
+                    shape_str(source.shape()),
+                    shape_str(shape)
+                ))
+            })
+        };
+        let mut strides = broadcast(source.strides())?;
+
+        // Stage `value` in a buffer only when writing could clobber parts of
+        // it that are still to be read
+        let itemsize = self.itemsize();
+        let dst = self.data_ptr() as *mut u8;
+        let mut src = source.data_ptr();
+        let staged;
+        let overlaps = match (selection.extent(itemsize), extent(source.shape(), source.strides(), itemsize)) {
+            (Some((start, end)), Some((src_start, src_end))) => {
+                let (dst, src) = (dst as isize, src as isize);
+                src + src_start < dst + end && dst + start < src + src_end
+            }
+            _ => false,
+        };
+        if overlaps {
+            staged = to_contiguous_bytes(source);
+            let contiguous: Vec<i64> = c_strides(source.shape()).iter().map(|s| s * itemsize as i64).collect();
+            strides = broadcast(&contiguous)?;
+            src = staged.as_ptr();
+        }
+
+        unsafe {
+            match &selection {
+                Selection::View { offset, shape, strides: dst_strides } => {
+                    copy_strided(dst.offset(*offset), dst_strides, src, &strides, shape, itemsize);
+                }
+                Selection::Gather { shape, offsets } => {
+                    for (&offset, src_offset) in offsets.iter().zip(strided_offsets(0, shape, &strides)) {
+                        std::ptr::copy(src.offset(src_offset), dst.offset(offset), itemsize);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copy the elements at `offsets` into a new array
+fn gather(array: &Array, shape: Vec<i64>, offsets: &[isize]) -> Result<Array, IndexError> {
+    let itemsize = array.itemsize();
+    let mut result = Array::new(shape, array.dtype().clone())?;
+    unsafe {
+        let src = array.data_ptr();
+        let dst = result.data_ptr_mut();
+        for (i, &offset) in offsets.iter().enumerate() {
+            std::ptr::copy_nonoverlapping(src.offset(offset), dst.add(i * itemsize), itemsize);
+        }
+    }
+    Ok(result)
+}
+
+/// Normalize an index against one axis
+fn normalize_index(index: i64, axis: usize, size: i64) -> Result<i64, IndexError> {
+    let normalized = if index < 0 { index + size } else { index };
+    if normalized < 0 || normalized >= size {
+        return Err(IndexError::AxisOutOfBounds { index, axis, size });
+    }
+    Ok(normalized)
+}
+
+/// Values of an integer index array applied to `axis`, in C order
+///
+/// Unsigned values above `i64::MAX` are out of bounds for any axis and are
+/// reported as `i64::MAX`.
+fn index_values(indices: &Array, axis: usize, size: i64) -> Result<Vec<i64>, IndexError> {
+    let ty = indices.dtype().type_();
+    let read: fn(&[u8]) -> Result<i64, IndexError> = match ty {
+        NpyType::Byte => |b| Ok(i8::from_ne_bytes(fixed(b)?) as i64),
+        NpyType::UByte => |b| Ok(u8::from_ne_bytes(fixed(b)?) as i64),
+        NpyType::Short => |b| Ok(i16::from_ne_bytes(fixed(b)?) as i64),
+        NpyType::UShort => |b| Ok(u16::from_ne_bytes(fixed(b)?) as i64),
+        NpyType::Int => |b| Ok(i32::from_ne_bytes(fixed(b)?) as i64),
+        NpyType::UInt => |b| Ok(u32::from_ne_bytes(fixed(b)?) as i64),
+        NpyType::Long | NpyType::LongLong => |b| Ok(i64::from_ne_bytes(fixed(b)?)),
+        NpyType::ULong | NpyType::ULongLong => |b| Ok(i64::try_from(u64::from_ne_bytes(fixed(b)?)).unwrap_or(i64::MAX)),
+        _ => {
+            return Err(IndexError::InvalidExpression(
+                "arrays used as indices must be of integer (or boolean) type".to_string(),
+            ))
+        }
+    };
+    to_contiguous_bytes(indices)
+        .chunks_exact(indices.itemsize().max(1))
+        .map(|b| normalize_index(read(b)?, axis, size))
+        .collect()
+}
+
+/// The bytes of one index element, which must match the width of its type
+fn fixed<const N: usize>(bytes: &[u8]) -> Result<[u8; N], IndexError> {
+    bytes.try_into().map_err(|_| {
+        IndexError::InvalidExpression(format!("index array itemsize {} does not match its dtype", bytes.len()))
+    })
+}
+
+/// Resolve an index expression against an array
+fn select(array: &Array, exprs: &[IndexExpr]) -> Result<Selection, IndexError> {
+    let ndim = array.ndim();
+    let advanced = exprs.iter().any(IndexExpr::is_advanced);
+    let ellipses = exprs.iter().filter(|expr| matches!(expr, IndexExpr::Ellipsis)).count();
+    if ellipses > 1 {
+        return Err(IndexError::InvalidExpression("an index can only have a single ellipsis ('...')".to_string()));
+    }
+    let consumed: usize = exprs
+        .iter()
+        .map(|expr| match expr {
+            IndexExpr::Int(_) | IndexExpr::Slice(_) | IndexExpr::IntArray(_) => 1,
+            IndexExpr::BoolArray(mask) => mask.ndim(),
+            IndexExpr::NewAxis | IndexExpr::Ellipsis => 0,
+        })
+        .sum();
+    if consumed > ndim {
+        return Err(IndexError::InvalidExpression(format!(
+            "too many indices for array: array is {}-dimensional, but {} were indexed",
+            ndim, consumed
+        )));
+    }
+
+    let (shape, strides) = (array.shape(), array.strides());
+    let mut offset = 0isize;
+    let mut view_shape = Vec::new();
+    let mut view_strides = Vec::new();
+    let mut adv = Vec::new();
+    let mut dim = 0;
+    for expr in exprs {
+        match expr {
+            // Next to index arrays, an integer acts as a 0-d index array
+            IndexExpr::Int(i) if advanced => {
+                let value = normalize_index(*i, dim, shape[dim])?;
+                adv.push(Advanced { dim: view_shape.len(), values: vec![value], shape: vec![] });
+                view_shape.push(shape[dim]);
+                view_strides.push(strides[dim]);
+                dim += 1;
+            }
+            IndexExpr::Int(i) => {
+                offset += (normalize_index(*i, dim, shape[dim])? * strides[dim]) as isize;
+                dim += 1;
+            }
+            IndexExpr::Slice(slice) => {
+                let (start, stop, step) = normalize_slice(slice, shape[dim])
+                    .map_err(|_| IndexError::InvalidValue("slice step cannot be zero".to_string()))?;
+                let len = slice_length(start, stop, step);
+                if len > 0 {
+                    offset += (start * strides[dim]) as isize;
+                }
+                view_shape.push(len);
+                view_strides.push(strides[dim] * step);
+                dim += 1;
+            }
+            IndexExpr::NewAxis => {
+                view_shape.push(1);
+                view_strides.push(0);
+            }
+            IndexExpr::Ellipsis => {
+                for _ in 0..ndim - consumed {
+                    view_shape.push(shape[dim]);
+                    view_strides.push(strides[dim]);
+                    dim += 1;
+                }
+            }
+            IndexExpr::IntArray(indices) => {
+                let values = index_values(indices, dim, shape[dim])?;
+                adv.push(Advanced { dim: view_shape.len(), values, shape: indices.shape().to_vec() });
+                view_shape.push(shape[dim]);
+                view_strides.push(strides[dim]);
+                dim += 1;
+            }
+            IndexExpr::BoolArray(mask) if mask.dtype().type_() != NpyType::Bool => {
+                return Err(IndexError::InvalidExpression("boolean index arrays must have dtype bool".to_string()));
+            }
+            IndexExpr::BoolArray(mask) if mask.ndim() == 0 => {
+                // `True` adds a length-1 axis and selects it; `False` selects nothing
+                let flag = unsafe { *mask.data_ptr() != 0 };
+                let values = if flag { vec![0] } else { vec![] };
+                adv.push(Advanced { dim: view_shape.len(), values, shape: vec![flag as i64] });
+                view_shape.push(1);
+                view_strides.push(0);
+            }
+            IndexExpr::BoolArray(mask) => {
+                let k = mask.ndim();
+                for (axis, (&m, &n)) in mask.shape().iter().zip(&shape[dim..dim + k]).enumerate() {
+                    if m != n {
+                        return Err(IndexError::InvalidExpression(format!(
+                            "boolean index did not match indexed array along axis {}; size of axis is {} \
+                             but size of corresponding boolean axis is {}",
+                            dim + axis,
+                            n,
+                            m
+                        )));
+                    }
+                }
+                // A k-dimensional mask is k index arrays of its true coordinates
+                let mut coords = vec![Vec::new(); k];
+                for (flat, _) in to_contiguous_bytes(mask).iter().enumerate().filter(|(_, &f)| f != 0) {
+                    let mut rest = flat as i64;
+                    for axis in (0..k).rev() {
+                        coords[axis].push(rest % mask.shape()[axis]);
+                        rest /= mask.shape()[axis];
+                    }
+                }
+                for values in coords {
+                    let count = values.len() as i64;
+                    adv.push(Advanced { dim: view_shape.len(), values, shape: vec![count] });
+                    view_shape.push(shape[dim]);
+                    view_strides.push(strides[dim]);
+                    dim += 1;
+                }
+            }
+        }
+    }
+    view_shape.extend_from_slice(&shape[dim..]);
+    view_strides.extend_from_slice(&strides[dim..]);
+
+    if adv.is_empty() {
+        return Ok(Selection::View { offset, shape: view_shape, strides: view_strides });
+    }
+
+    // Broadcast the index arrays and resolve each combination to an offset
+    let shapes: Vec<&[i64]> = adv.iter().map(|a| a.shape.as_slice()).collect();
+    let index_shape = broadcast_shapes_multi(&shapes).map_err(|_| {
+        IndexError::InvalidExpression(format!(
+            "shape mismatch: indexing arrays could not be broadcast together with shapes {}",
+            shapes.iter().map(|&s| shape_str(s)).collect::<Vec<_>>().join(" ")
+        ))
+    })?;
+    let mut adv_offsets = vec![0isize; index_shape.iter().product::<i64>() as usize];
+    for a in &adv {
+        let element_strides = c_strides(&a.shape);
+        let strides = broadcast_strides(&a.shape, &element_strides, &index_shape)
+            .map_err(|e| IndexError::InvalidExpression(e.to_string()))?;
+        for (slot, position) in adv_offsets.iter_mut().zip(strided_offsets(0, &index_shape, &strides)) {
+            *slot += (a.values[position as usize] * view_strides[a.dim]) as isize;
+        }
+    }
+
+    // Index dimensions stay in place when adjacent, else move to the front
+    let adjacent = adv.windows(2).all(|w| w[1].dim == w[0].dim + 1);
+    let first = adv[0].dim;
+    let is_adv = |d: usize| adv.iter().any(|a| a.dim == d);
+    let axis = |d: usize| (0..view_shape[d]).map(|i| (i * view_strides[d]) as isize).collect::<Vec<isize>>();
+    let mut axes = Vec::new();
+    let mut result_shape = Vec::new();
+    if !adjacent {
+        axes.push(adv_offsets.clone());
+        result_shape.extend_from_slice(&index_shape);
+    }
+    for (d, &len) in view_shape.iter().enumerate() {
+        if adjacent && d == first {
+            axes.push(adv_offsets.clone());
+            result_shape.extend_from_slice(&index_shape);
+        }
+        if !is_adv(d) {
+            axes.push(axis(d));
+            result_shape.push(len);
+        }
+    }
+    let mut offsets = vec![offset];
+    for axis in axes {
+        offsets = offsets.iter().flat_map(|&o| axis.iter().map(move |&a| o + a)).collect();
+    }
+    Ok(Selection::Gather { shape: result_shape, offsets })
+}
+
+/// Element strides of a C-contiguous shape
+fn c_strides(shape: &[i64]) -> Vec<i64> {
+    let mut strides = vec![1; shape.len()];
+    for d in (0..shape.len().saturating_sub(1)).rev() {
+        strides[d] = strides[d + 1] * shape[d + 1];
+    }
+    strides
+}
+
+/// Byte range `[start, end)` of a strided layout relative to its first
+/// element, or `None` if it has no elements
+fn extent(shape: &[i64], strides: &[i64], itemsize: usize) -> Option<(isize, isize)> {
+    if shape.contains(&0) {
+        return None;
+    }
+    let (mut start, mut end) = (0isize, itemsize as isize);
+    for (&n, &stride) in shape.iter().zip(strides) {
+        let span = ((n - 1) * stride) as isize;
+        if span < 0 {
+            start += span;
+        } else {
+            end += span;
+        }
+    }
+    Some((start, end))
+}
+
+/// Copy every element of a strided layout from `src` to `dst`
+///
+/// # Safety
+/// Both layouts must lie within their allocations; they may only overlap
+/// if they are the same layout.
+unsafe fn copy_strided(dst: *mut u8, dst_strides: &[i64], src: *const u8, src_strides: &[i64], shape: &[i64], itemsize: usize) {
+    match shape {
+        [] => std::ptr::copy(src, dst, itemsize),
+        [n] if dst_strides[0] == itemsize as i64 && src_strides[0] == itemsize as i64 => {
+            std::ptr::copy(src, dst, *n as usize * itemsize);
+        }
+        [n, rest @ ..] => {
+            for i in 0..*n {
+                copy_strided(
+                    dst.offset((i * dst_strides[0]) as isize),
+                    &dst_strides[1..],
+                    src.offset((i * src_strides[0]) as isize),
+                    &src_strides[1..],
+                    rest,
+                    itemsize,
+                );
+            }
+        }
+    }
+}
+
+/// Offsets of every element of a strided layout, in C order
+fn strided_offsets(base: isize, shape: &[i64], strides: &[i64]) -> Vec<isize> {
+    let mut offsets = vec![base];
+    for (&n, &stride) in shape.iter().zip(strides) {
+        offsets = offsets.iter().flat_map(|&o| (0..n).map(move |i| o + (i * stride) as isize)).collect();
+    }
+    offsets
+}
+
+/// Format a shape the way Python prints a tuple
+fn shape_str(shape: &[i64]) -> String {
+    match shape {
+        [n] => format!("({},)", n),
+        _ => format!("({})", shape.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")),
+    }
+}
@@ -13,6 +13,20 @@ pub enum IndexError {
     InvalidIndex,
     /// Dimension mismatch
     DimensionMismatch,
+    /// Index outside one axis of the array
+    AxisOutOfBounds {
+        /// The index as given
+        index: i64,
+        /// Axis it was applied to
+        axis: usize,
+        /// Length of that axis
+        size: i64,
+    },
+    /// Malformed index expression
+    InvalidExpression(String),
+    /// Index expression is valid but its value is not (zero slice step,
+    /// unbroadcastable assignment, read-only destination)
+    InvalidValue(String),
 }
 
 impl std::fmt::Display for IndexError {
@@ -21,6 +35,10 @@ impl std::fmt::Display for IndexError {
             IndexError::OutOfBounds => write!(f, "Index out of bounds"),
             IndexError::InvalidIndex => write!(f, "Invalid index"),
             IndexError::DimensionMismatch => write!(f, "Dimension mismatch"),
+            IndexError::AxisOutOfBounds { index, axis, size } => {
+                write!(f, "index {} is out of bounds for axis {} with size {}", index, axis, size)
+            }
+            IndexError::InvalidExpression(msg) | IndexError::InvalidValue(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -4,11 +4,13 @@
 //! equivalent to NumPy's indexing implementation
 
 mod advanced;
+mod expr;
 #[allow(clippy::module_inception)]
 mod indexing;
 mod slicing;
 
 pub use advanced::*;
+pub use expr::*;
 pub use indexing::*;
 pub use slicing::*;
 
@@ -86,10 +86,11 @@ pub fn normalize_slice(slice: &Slice, dim_size: i64) -> Result<(i64, i64, i64),
             start.min(dim_size - 1)
         };
         
-        let stop = if stop < 0 {
-            (dim_size + stop).max(-1)
-        } else {
-            stop.min(dim_size - 1)
+        // An omitted stop runs past index 0; only explicit negatives wrap
+        let stop = match slice.stop {
+            None => -1,
+            Some(_) if stop < 0 => (dim_size + stop).max(-1),
+            Some(_) => stop.min(dim_size - 1),
         };
         
         (start, stop)
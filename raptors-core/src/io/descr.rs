@@ -0,0 +1,263 @@
+//! NumPy dtype descriptors
+//!
+//! Conversion between `DType` and the `descr` value of NPY headers: an
+//! array-protocol type string such as `'<f8'`, `'|S10'` or `'<M8[ns]'`, or a
+//! list of `(name, descr[, shape])` tuples for structured dtypes. The same
+//! values describe arrays in the `__array_interface__` protocol.
+
+use super::literal::PyLiteral;
+use super::IoError;
+use crate::datetime::TimeUnit;
+use crate::structured::{Field, StructuredDType};
+use crate::types::{DType, NpyType};
+
+/// Byte-order character for native multi-byte data
+const NATIVE: char = if cfg!(target_endian = "little") { '<' } else { '>' };
+
+/// Array-protocol type string of a non-structured dtype
+pub(crate) fn typestr(dtype: &DType) -> Result<String, IoError> {
+    let size = dtype.itemsize();
+    let (order, kind) = match dtype.type_() {
+        NpyType::Bool => ('|', "b"),
+        NpyType::Byte => ('|', "i"),
+        NpyType::UByte => ('|', "u"),
+        NpyType::Short | NpyType::Int | NpyType::Long | NpyType::LongLong => (NATIVE, "i"),
+        NpyType::UShort | NpyType::UInt | NpyType::ULong | NpyType::ULongLong => (NATIVE, "u"),
+        NpyType::Half | NpyType::Float | NpyType::Double | NpyType::LongDouble => (NATIVE, "f"),
+        NpyType::CFloat | NpyType::CDouble | NpyType::CLongDouble => (NATIVE, "c"),
+        NpyType::String => ('|', "S"),
+        NpyType::Unicode => return Ok(format!("{}U{}", NATIVE, size / 4)),
+        NpyType::DateTime | NpyType::Timedelta => {
+            let kind = if dtype.type_() == NpyType::DateTime { 'M' } else { 'm' };
+            return Ok(match dtype.time_unit() {
+                Some(unit) => format!("{}{}8[{}]", NATIVE, kind, unit.code()),
+                None => format!("{}{}8", NATIVE, kind),
+            });
+        }
+        NpyType::Void if dtype.custom_type_id().is_none() => ('|', "V"),
+        _ => return Err(IoError::UnsupportedDtype),
+    };
+    Ok(format!("{}{}{}", order, kind, size))
+}
+
+/// `descr` literal for a dtype
+///
+/// Structured dtypes become a list of fields; gaps between fields are
+/// written as unnamed void padding, as NumPy does.
+pub(crate) fn dtype_to_descr(dtype: &DType) -> Result<PyLiteral, IoError> {
+    let layout = match dtype.fields() {
+        Some(layout) => layout,
+        None => return Ok(PyLiteral::Str(typestr(dtype)?)),
+    };
+    let mut fields: Vec<&Field> = layout.fields().iter().collect();
+    fields.sort_by_key(|f| f.offset);
+    let mut items = Vec::with_capacity(fields.len());
+    let mut offset = 0;
+    let padding = |n: usize| PyLiteral::Tuple(vec![PyLiteral::Str(String::new()), PyLiteral::Str(format!("|V{}", n))]);
+    for field in fields {
+        if field.offset < offset {
+            // Overlapping fields cannot be expressed as a descr list
+            return Err(IoError::UnsupportedDtype);
+        }
+        if field.offset > offset {
+            items.push(padding(field.offset - offset));
+        }
+        let mut entry = vec![PyLiteral::Str(field.name.clone()), dtype_to_descr(&field.dtype)?];
+        if !field.shape.is_empty() {
+            entry.push(PyLiteral::Tuple(field.shape.iter().map(|&n| PyLiteral::Int(n)).collect()));
+        }
+        items.push(PyLiteral::Tuple(entry));
+        offset = field.offset + field.size();
+    }
+    if layout.itemsize() > offset {
+        items.push(padding(layout.itemsize() - offset));
+    }
+    Ok(PyLiteral::List(items))
+}
+
+/// A dtype read from a `descr`, with the byte ranges that need swapping
+///
+/// Each `(offset, width)` run within an element is stored in non-native
+/// byte order and must be reversed after reading.
+#[derive(Debug, Clone)]
+pub struct Descr {
+    /// The described dtype
+    pub dtype: DType,
+    /// `(offset, width)` byte runs stored in non-native order
+    pub swaps: Vec<(usize, usize)>,
+}
+
+impl Descr {
+    /// Parse the Python `repr` of a `typestr` or `descr` value
+    pub fn parse(text: &str) -> Result<Descr, IoError> {
+        descr_to_dtype(&PyLiteral::parse(text)?)
+    }
+
+    /// Whether elements are stored in native byte order
+    pub fn is_native(&self) -> bool {
+        self.swaps.is_empty()
+    }
+
+    /// Convert the elements of `data` between stored and native byte order
+    pub fn byteswap(&self, data: &mut [u8]) {
+        byteswap(data, self.dtype.itemsize(), &self.swaps);
+    }
+}
+
+/// `typestr` of a dtype for the `__array_interface__` protocol
+///
+/// Structured dtypes are opaque `'|V<n>'` here; their fields are in
+/// `array_descr`.
+pub fn array_typestr(dtype: &DType) -> Result<String, IoError> {
+    typestr(dtype)
+}
+
+/// `descr` list of a dtype for the `__array_interface__` protocol
+pub fn array_descr(dtype: &DType) -> Result<PyLiteral, IoError> {
+    let descr = match dtype_to_descr(dtype)? {
+        PyLiteral::Str(s) => PyLiteral::List(vec![PyLiteral::Tuple(vec![PyLiteral::Str(String::new()), PyLiteral::Str(s)])]),
+        fields => fields,
+    };
+    Ok(descr)
+}
+
+/// Reverse the `swaps` byte runs of every `itemsize`-byte element of `data`
+pub(crate) fn byteswap(data: &mut [u8], itemsize: usize, swaps: &[(usize, usize)]) {
+    if swaps.is_empty() || itemsize == 0 {
+        return;
+    }
+    for element in data.chunks_exact_mut(itemsize) {
+        for &(offset, width) in swaps {
+            element[offset..offset + width].reverse();
+        }
+    }
+}
+
+/// Parse a `descr` literal
+pub(crate) fn descr_to_dtype(descr: &PyLiteral) -> Result<Descr, IoError> {
+    match descr {
+        PyLiteral::Str(s) => parse_typestr(s),
+        PyLiteral::List(items) => parse_fields(items),
+        _ => Err(IoError::InvalidHeader(format!("invalid descr {}", descr.repr()))),
+    }
+}
+
+/// Parse an array-protocol type string
+fn parse_typestr(s: &str) -> Result<Descr, IoError> {
+    let (order, rest) = match s.chars().next() {
+        Some(c @ ('<' | '>' | '|' | '=' | '!')) => (c, &s[1..]),
+        _ => ('=', s),
+    };
+    let swap = match order {
+        '<' => NATIVE == '>',
+        '>' | '!' => NATIVE == '<',
+        _ => false,
+    };
+    let mut chars = rest.chars();
+    let kind = chars.next().ok_or_else(|| IoError::InvalidHeader(format!("invalid descr '{}'", s)))?;
+    let rest = chars.as_str();
+
+    // datetime64/timedelta64 carry a unit: 'M8[ns]', or 'M8' for generic
+    if kind == 'M' || kind == 'm' {
+        let unit = match rest.strip_prefix('8') {
+            Some("") => None,
+            Some(unit) => {
+                let code = unit.strip_prefix('[').and_then(|u| u.strip_suffix(']'));
+                Some(code.and_then(TimeUnit::from_code).ok_or(IoError::UnsupportedDtype)?)
+            }
+            None => return Err(IoError::UnsupportedDtype),
+        };
+        let dtype = match (kind, unit) {
+            ('M', Some(unit)) => DType::datetime(unit),
+            ('m', Some(unit)) => DType::timedelta(unit),
+            ('M', None) => DType::new(NpyType::DateTime),
+            _ => DType::new(NpyType::Timedelta),
+        };
+        return Ok(Descr { dtype, swaps: if swap { vec![(0, 8)] } else { Vec::new() } });
+    }
+
+    let size: usize = rest.parse().map_err(|_| IoError::InvalidHeader(format!("invalid descr '{}'", s)))?;
+    let dtype = match (kind, size) {
+        ('b', 1) | ('?', 1) => DType::new(NpyType::Bool),
+        ('i', 1) => DType::new(NpyType::Byte),
+        ('u', 1) => DType::new(NpyType::UByte),
+        ('i', 2) => DType::new(NpyType::Short),
+        ('u', 2) => DType::new(NpyType::UShort),
+        ('i', 4) => DType::new(NpyType::Int),
+        ('u', 4) => DType::new(NpyType::UInt),
+        ('i', 8) => DType::new(NpyType::Long),
+        ('u', 8) => DType::new(NpyType::ULong),
+        ('f', 2) => DType::new(NpyType::Half),
+        ('f', 4) => DType::new(NpyType::Float),
+        ('f', 8) => DType::new(NpyType::Double),
+        ('f', 16) => DType::new(NpyType::LongDouble),
+        ('c', 8) => DType::new(NpyType::CFloat),
+        ('c', 16) => DType::new(NpyType::CDouble),
+        ('c', 32) => DType::new(NpyType::CLongDouble),
+        ('S', n) | ('a', n) => DType::string_with_itemsize(n),
+        ('U', n) => DType::unicode_with_itemsize(4 * n),
+        ('V', n) => DType::void_with_itemsize(n),
+        _ => return Err(IoError::UnsupportedDtype),
+    };
+    let swaps = if !swap {
+        Vec::new()
+    } else {
+        match kind {
+            'i' | 'u' | 'f' if size > 1 => vec![(0, size)],
+            'c' => vec![(0, size / 2), (size / 2, size / 2)],
+            'U' => (0..size).map(|i| (4 * i, 4)).collect(),
+            _ => Vec::new(),
+        }
+    };
+    Ok(Descr { dtype, swaps })
+}
+
+/// Parse the field list of a structured `descr`
+///
+/// Fields are laid out back to back; unnamed entries are padding.
+fn parse_fields(items: &[PyLiteral]) -> Result<Descr, IoError> {
+    let invalid = || IoError::InvalidHeader("invalid structured descr".to_string());
+    let mut fields = Vec::with_capacity(items.len());
+    let mut swaps = Vec::new();
+    let mut offset = 0;
+    for item in items {
+        let parts = match item {
+            PyLiteral::Tuple(parts) if parts.len() == 2 || parts.len() == 3 => parts,
+            _ => return Err(invalid()),
+        };
+        // Names may be given as (title, name)
+        let name = match &parts[0] {
+            PyLiteral::Str(name) => name.clone(),
+            PyLiteral::Tuple(pair) if pair.len() == 2 => match &pair[1] {
+                PyLiteral::Str(name) => name.clone(),
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        };
+        let descr = descr_to_dtype(&parts[1])?;
+        let shape: Vec<i64> = match parts.get(2) {
+            None => Vec::new(),
+            Some(PyLiteral::Int(n)) => vec![*n],
+            Some(PyLiteral::Tuple(dims)) => dims
+                .iter()
+                .map(|d| match d {
+                    PyLiteral::Int(n) if *n >= 0 => Ok(*n),
+                    _ => Err(invalid()),
+                })
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err(invalid()),
+        };
+        let field = Field::new(name, descr.dtype, offset).with_shape(shape);
+        let count = field.shape.iter().product::<i64>() as usize;
+        let width = field.dtype.itemsize();
+        for i in 0..count {
+            swaps.extend(descr.swaps.iter().map(|&(o, w)| (offset + i * width + o, w)));
+        }
+        offset += field.size();
+        if !field.name.is_empty() {
+            fields.push(field);
+        }
+    }
+    let layout = StructuredDType::with_offsets(fields, offset).map_err(|_| invalid())?;
+    Ok(Descr { dtype: DType::structured(layout), swaps })
+}
@@ -0,0 +1,760 @@
+//! Streaming delimited-text loader
+//!
+//! Equivalent to NumPy's `genfromtxt`: input is read one line at a time,
+//! columns may have their own dtypes (producing a structured array), and
+//! missing or empty cells are replaced by filling values and can be
+//! reported through a mask. Quoted fields, per-column converters and
+//! header rows with column names are supported.
+//!
+//! When every column dtype is known up front, rows are encoded as they are
+//! read, so only the binary result is held in memory. With
+//! `ColumnTypes::Infer` the cells are buffered until each column's type has
+//! been determined.
+
+use super::text::TextIoError;
+use crate::array::Array;
+use crate::masked::MaskedArray;
+use crate::structured::{Field, StructuredDType};
+use crate::types::{Complex128, DType, NpyType};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A converted cell value
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    /// Boolean value
+    Bool(bool),
+    /// Integer value
+    Int(i64),
+    /// Floating point value
+    Float(f64),
+    /// Complex value
+    Complex(Complex128),
+    /// String value
+    Str(String),
+}
+
+/// User converter for the raw text of a cell
+///
+/// Returning `None` marks the cell as missing.
+pub type TextConverter = Arc<dyn Fn(&str) -> Option<CellValue> + Send + Sync>;
+
+/// Where column names come from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ColumnNames {
+    /// No names: a plain array unless the column dtypes differ
+    #[default]
+    None,
+    /// Read names from the first line after `skip_header` (NumPy's
+    /// `names=True`); a leading comment marker on that line is ignored
+    FromHeader,
+    /// Use the given names, one per loaded column
+    Given(Vec<String>),
+}
+
+/// How column dtypes are chosen
+#[derive(Debug, Clone)]
+pub enum ColumnTypes {
+    /// The same dtype for every column
+    Single(DType),
+    /// One dtype per loaded column
+    PerColumn(Vec<DType>),
+    /// Infer each column's dtype from its values (NumPy's `dtype=None`):
+    /// bool, int64, float64, complex128 or a unicode string
+    Infer,
+}
+
+/// Options for `genfromtxt`
+#[derive(Clone)]
+pub struct GenFromTxtOptions {
+    /// Delimiter (runs of whitespace if None)
+    pub delimiter: Option<String>,
+    /// Comments prefix (default: "#"); empty disables comments
+    pub comments: String,
+    /// Number of lines to skip at the start of the input
+    pub skip_header: usize,
+    /// Column names
+    pub names: ColumnNames,
+    /// Column dtypes (default: float64)
+    pub dtype: ColumnTypes,
+    /// Columns to load; negative indices count from the end
+    pub usecols: Option<Vec<i64>>,
+    /// Maximum number of data rows to read
+    pub max_rows: Option<usize>,
+    /// Strings treated as missing in addition to the empty string
+    pub missing_values: Vec<String>,
+    /// Value used for missing cells of every column
+    ///
+    /// Defaults to NumPy's: false, -1, NaN or "???" depending on dtype.
+    pub filling_value: Option<CellValue>,
+    /// Per-column filling values, keyed by input column index
+    pub filling_values: HashMap<usize, CellValue>,
+    /// Per-column converters, keyed by input column index
+    pub converters: HashMap<usize, TextConverter>,
+    /// Quote character; delimiters and comment markers inside quotes are
+    /// literal and a doubled quote stands for the quote itself
+    pub quotechar: Option<char>,
+    /// Fail on rows with the wrong number of columns (default: true);
+    /// otherwise such rows are skipped
+    pub invalid_raise: bool,
+}
+
+impl Default for GenFromTxtOptions {
+    fn default() -> Self {
+        GenFromTxtOptions {
+            delimiter: None,
+            comments: "#".to_string(),
+            skip_header: 0,
+            names: ColumnNames::None,
+            dtype: ColumnTypes::Single(DType::new(NpyType::Double)),
+            usecols: None,
+            max_rows: None,
+            missing_values: Vec::new(),
+            filling_value: None,
+            filling_values: HashMap::new(),
+            converters: HashMap::new(),
+            quotechar: None,
+            invalid_raise: true,
+        }
+    }
+}
+
+impl std::fmt::Debug for GenFromTxtOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GenFromTxtOptions")
+            .field("delimiter", &self.delimiter)
+            .field("comments", &self.comments)
+            .field("skip_header", &self.skip_header)
+            .field("names", &self.names)
+            .field("dtype", &self.dtype)
+            .field("usecols", &self.usecols)
+            .field("max_rows", &self.max_rows)
+            .field("missing_values", &self.missing_values)
+            .field("filling_value", &self.filling_value)
+            .field("filling_values", &self.filling_values)
+            .field("converters", &self.converters.keys().collect::<Vec<_>>())
+            .field("quotechar", &self.quotechar)
+            .field("invalid_raise", &self.invalid_raise)
+            .finish()
+    }
+}
+
+/// Load a delimited text file
+///
+/// Returns a structured array when column names are given or the column
+/// dtypes differ, otherwise a plain array of shape `[rows, columns]`.
+/// As in NumPy, axes of length one are squeezed out.
+pub fn genfromtxt(path: impl AsRef<Path>, options: GenFromTxtOptions) -> Result<Array, TextIoError> {
+    genfromtxt_reader(open(path.as_ref())?, options)
+}
+
+/// Load a delimited text file, masking missing cells
+///
+/// Structured results get a structured mask with one boolean field per
+/// column.
+pub fn genfromtxt_masked(path: impl AsRef<Path>, options: GenFromTxtOptions) -> Result<MaskedArray, TextIoError> {
+    genfromtxt_masked_reader(open(path.as_ref())?, options)
+}
+
+/// Load delimited text from any buffered reader
+pub fn genfromtxt_reader<R: BufRead>(reader: R, options: GenFromTxtOptions) -> Result<Array, TextIoError> {
+    Loader::new(options).run(reader).map(|(data, _)| data)
+}
+
+/// Load delimited text from any buffered reader, masking missing cells
+pub fn genfromtxt_masked_reader<R: BufRead>(
+    reader: R,
+    options: GenFromTxtOptions,
+) -> Result<MaskedArray, TextIoError> {
+    let (data, mask) = Loader::new(options).run(reader)?;
+    MaskedArray::new(data, mask).map_err(|e| TextIoError::Unsupported(e.to_string()))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>, TextIoError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| TextIoError::FileError(e.to_string()))
+}
+
+/// A cell held until column dtypes are inferred
+enum Pending {
+    Missing,
+    Raw(String),
+    Value(CellValue),
+}
+
+/// Rows collected so far, in one of the two loading modes
+enum Rows {
+    /// Encoded records plus one mask byte per cell
+    Encoded { data: Vec<u8>, mask: Vec<u8> },
+    /// Cells awaiting type inference
+    Pending(Vec<Vec<Pending>>),
+}
+
+struct Loader {
+    options: GenFromTxtOptions,
+    /// Input column indices to load, once the column count is known
+    columns: Option<Vec<usize>>,
+    /// Input column count, fixed by the first data row
+    ncols: Option<usize>,
+    names: Option<Vec<String>>,
+    /// Column dtypes and byte offsets within a record, when known
+    layout: Option<(Vec<DType>, Vec<usize>)>,
+    rows: Rows,
+    nrows: usize,
+}
+
+impl Loader {
+    fn new(options: GenFromTxtOptions) -> Self {
+        let rows = match options.dtype {
+            ColumnTypes::Infer => Rows::Pending(Vec::new()),
+            _ => Rows::Encoded { data: Vec::new(), mask: Vec::new() },
+        };
+        let names = match &options.names {
+            ColumnNames::Given(names) => Some(names.clone()),
+            _ => None,
+        };
+        Loader { options, columns: None, ncols: None, names, layout: None, rows, nrows: 0 }
+    }
+
+    fn run<R: BufRead>(mut self, mut reader: R) -> Result<(Array, Array), TextIoError> {
+        let mut line = String::new();
+        let mut lineno = 0;
+        let mut header_names = None;
+        let mut need_header = self.options.names == ColumnNames::FromHeader;
+        loop {
+            if self.options.max_rows.is_some_and(|max| self.nrows >= max) {
+                break;
+            }
+            line.clear();
+            if reader.read_line(&mut line).map_err(|e| TextIoError::FileError(e.to_string()))? == 0 {
+                break;
+            }
+            lineno += 1;
+            if lineno <= self.options.skip_header {
+                continue;
+            }
+            let text = line.trim_end_matches(['\n', '\r']);
+
+            if need_header {
+                let mut text = text.trim_start();
+                if !self.options.comments.is_empty() {
+                    text = text.strip_prefix(self.options.comments.as_str()).unwrap_or(text);
+                }
+                let fields = split_line(text, self.options.delimiter.as_deref(), self.options.quotechar, "");
+                if !fields.is_empty() {
+                    header_names = Some(fields);
+                    need_header = false;
+                }
+                continue;
+            }
+
+            let fields = split_line(
+                text,
+                self.options.delimiter.as_deref(),
+                self.options.quotechar,
+                &self.options.comments,
+            );
+            if fields.is_empty() {
+                continue;
+            }
+            let ncols = match self.ncols {
+                Some(n) => n,
+                None => self.start(fields.len(), header_names.take())?,
+            };
+            if fields.len() != ncols {
+                if self.options.invalid_raise {
+                    return Err(TextIoError::ParseError(format!(
+                        "line {}: got {} columns instead of {}",
+                        lineno,
+                        fields.len(),
+                        ncols
+                    )));
+                }
+                continue;
+            }
+            self.push_row(&fields, lineno)?;
+        }
+        if self.ncols.is_none() {
+            // No data rows: the column count comes from the header, if any
+            let ncols = header_names.as_ref().or(self.names.as_ref()).map_or(1, |n| n.len());
+            self.start(ncols, header_names)?;
+        }
+        self.finish()
+    }
+
+    /// Fix the column selection, names and (if known) dtypes
+    fn start(&mut self, ncols: usize, header_names: Option<Vec<String>>) -> Result<usize, TextIoError> {
+        let columns = match &self.options.usecols {
+            Some(usecols) => usecols
+                .iter()
+                .map(|&c| {
+                    let i = if c < 0 { c + ncols as i64 } else { c };
+                    if i < 0 || i >= ncols as i64 {
+                        Err(TextIoError::ParseError(format!("usecols index {} out of range for {} columns", c, ncols)))
+                    } else {
+                        Ok(i as usize)
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => (0..ncols).collect(),
+        };
+        if let Some(header) = header_names {
+            let names = columns
+                .iter()
+                .map(|&i| header.get(i).map(|n| sanitize_name(n, i)).unwrap_or_else(|| format!("f{}", i)))
+                .collect();
+            self.names = Some(names);
+        }
+        if let Some(names) = &self.names {
+            if names.len() != columns.len() {
+                return Err(TextIoError::ParseError(format!(
+                    "{} names given for {} columns",
+                    names.len(),
+                    columns.len()
+                )));
+            }
+        }
+        let dtypes = match &self.options.dtype {
+            ColumnTypes::Single(dtype) => Some(vec![dtype.clone(); columns.len()]),
+            ColumnTypes::PerColumn(dtypes) if dtypes.len() == columns.len() => Some(dtypes.clone()),
+            ColumnTypes::PerColumn(dtypes) => {
+                return Err(TextIoError::ParseError(format!(
+                    "{} dtypes given for {} columns",
+                    dtypes.len(),
+                    columns.len()
+                )))
+            }
+            ColumnTypes::Infer => None,
+        };
+        if let Some(dtypes) = dtypes {
+            for dtype in &dtypes {
+                check_dtype(dtype)?;
+            }
+            self.layout = Some(packed_layout(dtypes));
+        }
+        self.columns = Some(columns);
+        self.ncols = Some(ncols);
+        Ok(ncols)
+    }
+
+    /// Classify a cell as missing, converted or raw text
+    fn cell(&self, column: usize, raw: &str) -> Pending {
+        if let Some(converter) = self.options.converters.get(&column) {
+            return converter(raw).map_or(Pending::Missing, Pending::Value);
+        }
+        if raw.is_empty() || self.options.missing_values.iter().any(|m| m == raw) {
+            Pending::Missing
+        } else {
+            Pending::Raw(raw.to_string())
+        }
+    }
+
+    fn push_row(&mut self, fields: &[String], lineno: usize) -> Result<(), TextIoError> {
+        let columns = self.columns.as_ref().unwrap();
+        let cells: Vec<Pending> = columns.iter().map(|&c| self.cell(c, &fields[c])).collect();
+        match &mut self.rows {
+            Rows::Pending(rows) => rows.push(cells),
+            Rows::Encoded { data, mask } => {
+                let (dtypes, offsets) = self.layout.as_ref().unwrap();
+                let start = data.len();
+                data.resize(start + record_size(dtypes, offsets), 0);
+                for (k, cell) in cells.iter().enumerate() {
+                    let out = &mut data[start + offsets[k]..start + offsets[k] + dtypes[k].itemsize()];
+                    let missing = encode_cell(cell, &dtypes[k], out, fill(&self.options, columns[k], &dtypes[k]))
+                        .map_err(|msg| TextIoError::ParseError(format!("line {}, column {}: {}", lineno, columns[k], msg)))?;
+                    mask.push(missing as u8);
+                }
+            }
+        }
+        self.nrows += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(Array, Array), TextIoError> {
+        let columns = self.columns.take().unwrap();
+        let (dtypes, offsets, data, mask) = match std::mem::replace(&mut self.rows, Rows::Pending(Vec::new())) {
+            Rows::Encoded { data, mask } => {
+                let (dtypes, offsets) = self.layout.take().unwrap();
+                (dtypes, offsets, data, mask)
+            }
+            Rows::Pending(rows) => {
+                let dtypes: Vec<DType> = (0..columns.len()).map(|k| infer_dtype(rows.iter().map(|r| &r[k]))).collect();
+                let (dtypes, offsets) = packed_layout(dtypes);
+                let size = record_size(&dtypes, &offsets);
+                let mut data = vec![0u8; size * rows.len()];
+                let mut mask = Vec::with_capacity(rows.len() * columns.len());
+                for (r, row) in rows.iter().enumerate() {
+                    for (k, cell) in row.iter().enumerate() {
+                        let start = r * size + offsets[k];
+                        let out = &mut data[start..start + dtypes[k].itemsize()];
+                        let missing = encode_cell(cell, &dtypes[k], out, fill(&self.options, columns[k], &dtypes[k]))
+                            .map_err(|msg| TextIoError::ParseError(format!("row {}, column {}: {}", r + 1, columns[k], msg)))?;
+                        mask.push(missing as u8);
+                    }
+                }
+                (dtypes, offsets, data, mask)
+            }
+        };
+
+        let nrows = self.nrows as i64;
+        let uniform = dtypes
+            .windows(2)
+            .all(|w| w[0].type_() == w[1].type_() && w[0].itemsize() == w[1].itemsize());
+        if self.names.is_none() && uniform {
+            let mut shape = vec![nrows, columns.len() as i64];
+            shape.retain(|&n| n != 1);
+            let data = from_bytes(&data, shape.clone(), dtypes[0].clone())?;
+            let mask = from_bytes(&mask, shape, DType::new(NpyType::Bool))?;
+            return Ok((data, mask));
+        }
+
+        let names = self
+            .names
+            .take()
+            .unwrap_or_else(|| columns.iter().map(|i| format!("f{}", i)).collect());
+        let size = record_size(&dtypes, &offsets);
+        let data_fields = names
+            .iter()
+            .zip(dtypes.iter().zip(&offsets))
+            .map(|(name, (dtype, &offset))| Field::new(name.clone(), dtype.clone(), offset))
+            .collect();
+        let mask_fields = names
+            .iter()
+            .enumerate()
+            .map(|(k, name)| Field::new(name.clone(), DType::new(NpyType::Bool), k))
+            .collect();
+        let data_dtype = structured(data_fields, size)?;
+        let mask_dtype = structured(mask_fields, names.len())?;
+        let shape = if nrows == 1 { vec![] } else { vec![nrows] };
+        Ok((from_bytes(&data, shape.clone(), data_dtype)?, from_bytes(&mask, shape, mask_dtype)?))
+    }
+}
+
+fn structured(fields: Vec<Field>, itemsize: usize) -> Result<DType, TextIoError> {
+    StructuredDType::with_offsets(fields, itemsize)
+        .map(DType::structured)
+        .map_err(|e| TextIoError::ParseError(e.to_string()))
+}
+
+fn from_bytes(bytes: &[u8], shape: Vec<i64>, dtype: DType) -> Result<Array, TextIoError> {
+    let mut array = Array::new(shape, dtype)?;
+    if !bytes.is_empty() {
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), array.data_ptr_mut(), bytes.len()) };
+    }
+    Ok(array)
+}
+
+/// Packed field offsets, as NumPy lays out `genfromtxt` records
+fn packed_layout(dtypes: Vec<DType>) -> (Vec<DType>, Vec<usize>) {
+    let mut offset = 0;
+    let offsets = dtypes
+        .iter()
+        .map(|d| {
+            let o = offset;
+            offset += d.itemsize();
+            o
+        })
+        .collect();
+    (dtypes, offsets)
+}
+
+fn record_size(dtypes: &[DType], offsets: &[usize]) -> usize {
+    dtypes.last().map_or(0, |d| offsets[offsets.len() - 1] + d.itemsize())
+}
+
+/// Make a header name usable as a field name
+fn sanitize_name(name: &str, column: usize) -> String {
+    let name: String = name.split_whitespace().collect::<Vec<_>>().join("_");
+    if name.is_empty() {
+        format!("f{}", column)
+    } else {
+        name
+    }
+}
+
+fn check_dtype(dtype: &DType) -> Result<(), TextIoError> {
+    match dtype.type_() {
+        NpyType::Bool
+        | NpyType::Byte
+        | NpyType::UByte
+        | NpyType::Short
+        | NpyType::UShort
+        | NpyType::Int
+        | NpyType::UInt
+        | NpyType::Long
+        | NpyType::ULong
+        | NpyType::LongLong
+        | NpyType::ULongLong
+        | NpyType::Float
+        | NpyType::Double
+        | NpyType::CFloat
+        | NpyType::CDouble
+        | NpyType::String
+        | NpyType::Unicode => Ok(()),
+        _ => Err(TextIoError::Unsupported(format!("genfromtxt does not support dtype {}", dtype))),
+    }
+}
+
+/// Filling value for missing cells of `column`
+fn fill(options: &GenFromTxtOptions, column: usize, dtype: &DType) -> CellValue {
+    if let Some(value) = options.filling_values.get(&column).or(options.filling_value.as_ref()) {
+        return value.clone();
+    }
+    match dtype.type_() {
+        NpyType::Bool => CellValue::Bool(false),
+        NpyType::Float | NpyType::Double => CellValue::Float(f64::NAN),
+        NpyType::CFloat | NpyType::CDouble => CellValue::Complex(Complex128::new(f64::NAN, 0.0)),
+        NpyType::String | NpyType::Unicode => CellValue::Str("???".to_string()),
+        _ => CellValue::Int(-1),
+    }
+}
+
+/// Split a line into fields
+///
+/// Returns no fields for blank and comment-only lines. Unquoted fields are
+/// trimmed; quoted fields are kept verbatim.
+fn split_line(line: &str, delimiter: Option<&str>, quote: Option<char>, comments: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    // Whether the current field has started (matters for "" and whitespace splitting)
+    let mut started = false;
+    let mut was_quoted = false;
+    let mut in_quotes = false;
+    let mut chars = line.char_indices().peekable();
+
+    let push = |field: &mut String, was_quoted: bool, fields: &mut Vec<String>| {
+        let value = std::mem::take(field);
+        fields.push(if was_quoted { value } else { value.trim().to_string() });
+    };
+
+    while let Some((i, c)) = chars.next() {
+        if in_quotes {
+            if Some(c) == quote {
+                if chars.peek().is_some_and(|&(_, n)| Some(n) == quote) {
+                    field.push(c);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+        let rest = &line[i..];
+        if !comments.is_empty() && rest.starts_with(comments) {
+            break;
+        }
+        match delimiter {
+            Some(d) if !d.is_empty() && rest.starts_with(d) => {
+                push(&mut field, was_quoted, &mut fields);
+                started = true;
+                was_quoted = false;
+                for _ in 1..d.chars().count() {
+                    chars.next();
+                }
+                continue;
+            }
+            None if c.is_whitespace() => {
+                if started {
+                    push(&mut field, was_quoted, &mut fields);
+                    started = false;
+                    was_quoted = false;
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if Some(c) == quote && !was_quoted && field.trim().is_empty() {
+            field.clear();
+            in_quotes = true;
+            was_quoted = true;
+        } else if !(c.is_whitespace() && was_quoted) {
+            field.push(c);
+        }
+        started = true;
+    }
+    if started {
+        push(&mut field, was_quoted, &mut fields);
+    }
+    fields
+}
+
+/// Parse a complex number such as `1+2j`, `-3.5j` or `(1-2j)`
+fn parse_complex(s: &str) -> Option<Complex128> {
+    let s = s.trim();
+    let s = s.strip_prefix('(').and_then(|s| s.strip_suffix(')')).unwrap_or(s);
+    let Some(body) = s.strip_suffix(['j', 'J']) else {
+        return s.parse().ok().map(|re| Complex128::new(re, 0.0));
+    };
+    // The imaginary part starts at the last sign not belonging to an exponent
+    let split = body
+        .char_indices()
+        .filter(|&(i, c)| i > 0 && (c == '+' || c == '-') && !body[..i].ends_with(['e', 'E']))
+        .map(|(i, _)| i)
+        .next_back();
+    match split {
+        Some(i) => Some(Complex128::new(body[..i].parse().ok()?, body[i..].parse().ok()?)),
+        None => Some(Complex128::new(0.0, body.parse().ok()?)),
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s {
+        _ if s.eq_ignore_ascii_case("true") => Some(true),
+        _ if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+/// Rank of a value kind in NumPy's upgrade order
+fn kind_rank(value: &CellValue) -> (u8, usize) {
+    match value {
+        CellValue::Bool(_) => (0, 0),
+        CellValue::Int(_) => (1, 0),
+        CellValue::Float(_) => (2, 0),
+        CellValue::Complex(_) => (3, 0),
+        CellValue::Str(s) => (4, s.chars().count()),
+    }
+}
+
+/// Interpret raw text as the narrowest kind that parses
+fn infer_value(raw: &str) -> CellValue {
+    if let Some(b) = parse_bool(raw) {
+        CellValue::Bool(b)
+    } else if let Ok(i) = raw.parse() {
+        CellValue::Int(i)
+    } else if let Ok(f) = raw.parse() {
+        CellValue::Float(f)
+    } else if let Some(c) = parse_complex(raw) {
+        CellValue::Complex(c)
+    } else {
+        CellValue::Str(raw.to_string())
+    }
+}
+
+/// Narrowest dtype holding every cell of a column
+fn infer_dtype<'a>(cells: impl Iterator<Item = &'a Pending>) -> DType {
+    let mut kind = None::<u8>;
+    let mut width = 1;
+    for cell in cells {
+        let (k, w) = match cell {
+            Pending::Missing => continue,
+            Pending::Raw(raw) => kind_rank(&infer_value(raw)),
+            Pending::Value(value) => kind_rank(value),
+        };
+        kind = kind.max(Some(k));
+        width = width.max(w.max(raw_width(cell)));
+    }
+    match kind {
+        Some(0) => DType::new(NpyType::Bool),
+        Some(1) => DType::new(NpyType::Long),
+        Some(3) => DType::new(NpyType::CDouble),
+        Some(4) => DType::unicode_with_itemsize(4 * width),
+        _ => DType::new(NpyType::Double),
+    }
+}
+
+/// Width of a raw cell should its column become a string column
+fn raw_width(cell: &Pending) -> usize {
+    match cell {
+        Pending::Raw(raw) => raw.chars().count(),
+        _ => 0,
+    }
+}
+
+/// Convert a cell to `dtype`'s kind
+fn convert(cell: &Pending, dtype: &DType) -> Result<Option<CellValue>, String> {
+    let raw = match cell {
+        Pending::Missing => return Ok(None),
+        Pending::Value(value) => return Ok(Some(value.clone())),
+        Pending::Raw(raw) => raw.as_str(),
+    };
+    let fail = || format!("cannot convert '{}' to {}", raw, dtype);
+    let value = match dtype.type_() {
+        NpyType::Bool => match parse_bool(raw) {
+            Some(b) => CellValue::Bool(b),
+            None => CellValue::Bool(raw.parse::<i64>().map_err(|_| fail())? != 0),
+        },
+        NpyType::Float | NpyType::Double => CellValue::Float(raw.parse().map_err(|_| fail())?),
+        NpyType::CFloat | NpyType::CDouble => CellValue::Complex(parse_complex(raw).ok_or_else(fail)?),
+        NpyType::String | NpyType::Unicode => CellValue::Str(raw.to_string()),
+        _ => CellValue::Int(raw.parse().map_err(|_| fail())?),
+    };
+    Ok(Some(value))
+}
+
+/// Encode a cell into `out`, substituting `fill` if it is missing
+///
+/// Returns whether the cell was missing.
+fn encode_cell(cell: &Pending, dtype: &DType, out: &mut [u8], fill: CellValue) -> Result<bool, String> {
+    let value = convert(cell, dtype)?;
+    let missing = value.is_none();
+    encode_value(&value.unwrap_or(fill), dtype, out)?;
+    Ok(missing)
+}
+
+pub(crate) fn encode_value(value: &CellValue, dtype: &DType, out: &mut [u8]) -> Result<(), String> {
+    let as_f64 = || match value {
+        CellValue::Bool(b) => Ok(*b as u8 as f64),
+        CellValue::Int(i) => Ok(*i as f64),
+        CellValue::Float(f) => Ok(*f),
+        CellValue::Complex(c) => Ok(c.re),
+        CellValue::Str(s) => s.trim().parse::<f64>().map_err(|_| format!("cannot convert '{}' to {}", s, dtype)),
+    };
+    let as_i64 = || match value {
+        CellValue::Int(i) => Ok(*i),
+        CellValue::Str(s) => s.trim().parse::<i64>().map_err(|_| format!("cannot convert '{}' to {}", s, dtype)),
+        _ => as_f64().map(|f| f as i64),
+    };
+    match dtype.type_() {
+        NpyType::Bool => out[0] = (as_f64()? != 0.0) as u8,
+        NpyType::Byte => out.copy_from_slice(&(as_i64()? as i8).to_ne_bytes()),
+        NpyType::UByte => out.copy_from_slice(&(as_i64()? as u8).to_ne_bytes()),
+        NpyType::Short => out.copy_from_slice(&(as_i64()? as i16).to_ne_bytes()),
+        NpyType::UShort => out.copy_from_slice(&(as_i64()? as u16).to_ne_bytes()),
+        NpyType::Int => out.copy_from_slice(&(as_i64()? as i32).to_ne_bytes()),
+        NpyType::UInt => out.copy_from_slice(&(as_i64()? as u32).to_ne_bytes()),
+        NpyType::Float => out.copy_from_slice(&(as_f64()? as f32).to_ne_bytes()),
+        NpyType::Double => out.copy_from_slice(&as_f64()?.to_ne_bytes()),
+        NpyType::CFloat | NpyType::CDouble => {
+            let c = match value {
+                CellValue::Complex(c) => *c,
+                CellValue::Str(s) => parse_complex(s).ok_or_else(|| format!("cannot convert '{}' to {}", s, dtype))?,
+                _ => Complex128::new(as_f64()?, 0.0),
+            };
+            if dtype.type_() == NpyType::CFloat {
+                out[..4].copy_from_slice(&(c.re as f32).to_ne_bytes());
+                out[4..].copy_from_slice(&(c.im as f32).to_ne_bytes());
+            } else {
+                out[..8].copy_from_slice(&c.re.to_ne_bytes());
+                out[8..].copy_from_slice(&c.im.to_ne_bytes());
+            }
+        }
+        NpyType::String | NpyType::Unicode => {
+            let text = match value {
+                CellValue::Str(s) => s.clone(),
+                CellValue::Bool(b) => if *b { "True" } else { "False" }.to_string(),
+                CellValue::Int(i) => i.to_string(),
+                CellValue::Float(f) => f.to_string(),
+                CellValue::Complex(c) => format!("({}{:+}j)", c.re, c.im),
+            };
+            out.fill(0);
+            if dtype.type_() == NpyType::String {
+                let n = text.len().min(out.len());
+                out[..n].copy_from_slice(&text.as_bytes()[..n]);
+            } else {
+                for (slot, ch) in out.chunks_exact_mut(4).zip(text.chars()) {
+                    slot.copy_from_slice(&(ch as u32).to_ne_bytes());
+                }
+            }
+        }
+        // Remaining integer types are 64-bit
+        _ => out.copy_from_slice(&as_i64()?.to_ne_bytes()),
+    }
+    Ok(())
+}
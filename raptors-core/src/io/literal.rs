@@ -0,0 +1,328 @@
+//! Python literal parsing and formatting
+//!
+//! NPY headers are Python dict literals written with `repr` and read back
+//! with `ast.literal_eval`. This module implements the subset of Python's
+//! literal syntax those headers use: strings (with escapes and `b`/`u`
+//! prefixes), integers, floats, `True`/`False`/`None`, tuples, lists and
+//! dicts.
+
+use super::IoError;
+
+/// A parsed Python literal
+#[derive(Debug, Clone, PartialEq)]
+pub enum PyLiteral {
+    /// `None`
+    None,
+    /// `True` or `False`
+    Bool(bool),
+    /// Integer
+    Int(i64),
+    /// Floating point number
+    Float(f64),
+    /// `str` (or `bytes`, decoded as Latin-1)
+    Str(String),
+    /// Tuple
+    Tuple(Vec<PyLiteral>),
+    /// List
+    List(Vec<PyLiteral>),
+    /// Dict, in source order
+    Dict(Vec<(PyLiteral, PyLiteral)>),
+}
+
+impl PyLiteral {
+    /// Parse a complete literal, allowing surrounding whitespace
+    pub(crate) fn parse(text: &str) -> Result<PyLiteral, IoError> {
+        let mut parser = Parser { chars: text.chars().collect(), pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.chars.len() {
+            return Err(parser.error("unexpected trailing characters"));
+        }
+        Ok(value)
+    }
+
+    /// Look up a string key in a dict literal
+    pub(crate) fn get(&self, key: &str) -> Option<&PyLiteral> {
+        match self {
+            PyLiteral::Dict(items) => items
+                .iter()
+                .find(|(k, _)| matches!(k, PyLiteral::Str(s) if s == key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Python `repr` of the literal
+    pub fn repr(&self) -> String {
+        match self {
+            PyLiteral::None => "None".to_string(),
+            PyLiteral::Bool(true) => "True".to_string(),
+            PyLiteral::Bool(false) => "False".to_string(),
+            PyLiteral::Int(i) => i.to_string(),
+            PyLiteral::Float(f) => repr_float(*f),
+            PyLiteral::Str(s) => repr_str(s),
+            PyLiteral::Tuple(items) if items.len() == 1 => format!("({},)", items[0].repr()),
+            PyLiteral::Tuple(items) => format!("({})", join(items)),
+            PyLiteral::List(items) => format!("[{}]", join(items)),
+            PyLiteral::Dict(items) => {
+                let body: Vec<String> = items.iter().map(|(k, v)| format!("{}: {}", k.repr(), v.repr())).collect();
+                format!("{{{}}}", body.join(", "))
+            }
+        }
+    }
+}
+
+fn join(items: &[PyLiteral]) -> String {
+    items.iter().map(PyLiteral::repr).collect::<Vec<_>>().join(", ")
+}
+
+fn repr_float(f: f64) -> String {
+    if f.is_nan() {
+        "nan".to_string()
+    } else if f.is_infinite() {
+        if f > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else if f == f.trunc() && f.abs() < 1e16 {
+        format!("{:.1}", f)
+    } else {
+        format!("{:?}", f)
+    }
+}
+
+/// Python `repr` of a `str`
+///
+/// Uses single quotes unless the string contains a single quote and no
+/// double quote, escaping like CPython.
+pub(crate) fn repr_str(s: &str) -> String {
+    let quote = if s.contains('\'') && !s.contains('"') { '"' } else { '\'' };
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push(quote);
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if (c as u32) < 0x20 || c as u32 == 0x7f => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c if c.is_control() => {
+                let code = c as u32;
+                if code <= 0xff {
+                    out.push_str(&format!("\\x{:02x}", code));
+                } else if code <= 0xffff {
+                    out.push_str(&format!("\\u{:04x}", code));
+                } else {
+                    out.push_str(&format!("\\U{:08x}", code));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.push(quote);
+    out
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, msg: &str) -> IoError {
+        IoError::InvalidHeader(format!("{} at position {}", msg, self.pos))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), IoError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn value(&mut self) -> Result<PyLiteral, IoError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => self.sequence(')').map(|(items, trailing_comma)| {
+                // A parenthesised single value without a comma is not a tuple
+                if items.len() == 1 && !trailing_comma {
+                    items.into_iter().next().unwrap()
+                } else {
+                    PyLiteral::Tuple(items)
+                }
+            }),
+            Some('[') => self.sequence(']').map(|(items, _)| PyLiteral::List(items)),
+            Some('{') => self.dict(),
+            Some('\'' | '"') => self.string(false),
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.number(),
+            Some(c) if c.is_ascii_alphabetic() => self.word(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// Parse comma-separated values up to `close`
+    ///
+    /// Returns the items and whether the last one was followed by a comma.
+    fn sequence(&mut self, close: char) -> Result<(Vec<PyLiteral>, bool), IoError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        let mut trailing_comma = false;
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok((items, trailing_comma));
+            }
+            if !items.is_empty() && !trailing_comma {
+                return Err(self.error(&format!("expected ',' or '{}'", close)));
+            }
+            items.push(self.value()?);
+            self.skip_whitespace();
+            trailing_comma = self.peek() == Some(',');
+            if trailing_comma {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn dict(&mut self) -> Result<PyLiteral, IoError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(PyLiteral::Dict(items));
+            }
+            let key = self.value()?;
+            self.expect(':')?;
+            let value = self.value()?;
+            items.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<PyLiteral, IoError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+        let word: String = self.chars[start..self.pos].iter().collect();
+        match word.as_str() {
+            "True" => Ok(PyLiteral::Bool(true)),
+            "False" => Ok(PyLiteral::Bool(false)),
+            "None" => Ok(PyLiteral::None),
+            "nan" => Ok(PyLiteral::Float(f64::NAN)),
+            "inf" => Ok(PyLiteral::Float(f64::INFINITY)),
+            // String prefixes: u'...' (Python 2 unicode) and b'...' (bytes)
+            prefix if matches!(self.peek(), Some('\'' | '"')) => match prefix.to_ascii_lowercase().as_str() {
+                "u" => self.string(false),
+                "b" => self.string(true),
+                _ => Err(self.error("unsupported string prefix")),
+            },
+            _ => {
+                self.pos = start;
+                Err(self.error("unexpected name"))
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<PyLiteral, IoError> {
+        let start = self.pos;
+        if matches!(self.peek(), Some('-' | '+')) {
+            self.pos += 1;
+        }
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '+' | '-'))
+        {
+            // A sign is only part of the number directly after an exponent
+            if matches!(self.peek(), Some('+' | '-')) && !matches!(self.chars[self.pos - 1], 'e' | 'E') {
+                break;
+            }
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().filter(|&&c| c != '_').collect();
+        // Python 2 long integers carry an `L` suffix
+        let int_text = text.strip_suffix(['L', 'l']).unwrap_or(&text);
+        if let Ok(i) = int_text.parse::<i64>() {
+            return Ok(PyLiteral::Int(i));
+        }
+        match text.trim_start_matches('+') {
+            "inf" => Ok(PyLiteral::Float(f64::INFINITY)),
+            "-inf" => Ok(PyLiteral::Float(f64::NEG_INFINITY)),
+            t => t.parse::<f64>().map(PyLiteral::Float).map_err(|_| {
+                self.pos = start;
+                self.error("invalid number")
+            }),
+        }
+    }
+
+    fn string(&mut self, bytes: bool) -> Result<PyLiteral, IoError> {
+        let quote = self.peek().ok_or_else(|| self.error("expected string"))?;
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let c = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match c {
+                c if c == quote => return Ok(PyLiteral::Str(out)),
+                '\n' => return Err(self.error("newline in string")),
+                '\\' => {
+                    let e = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    match e {
+                        '\\' | '\'' | '"' => out.push(e),
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        '0' => out.push('\0'),
+                        'a' => out.push('\x07'),
+                        'b' => out.push('\x08'),
+                        'f' => out.push('\x0c'),
+                        'v' => out.push('\x0b'),
+                        '\n' => {}
+                        'x' => out.push(self.hex_escape(2)?),
+                        'u' if !bytes => out.push(self.hex_escape(4)?),
+                        'U' if !bytes => out.push(self.hex_escape(8)?),
+                        other => {
+                            out.push('\\');
+                            out.push(other);
+                        }
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn hex_escape(&mut self, digits: usize) -> Result<char, IoError> {
+        if self.pos + digits > self.chars.len() {
+            return Err(self.error("truncated escape"));
+        }
+        let text: String = self.chars[self.pos..self.pos + digits].iter().collect();
+        self.pos += digits;
+        u32::from_str_radix(&text, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error("invalid escape"))
+    }
+}
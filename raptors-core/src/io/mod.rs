@@ -1,11 +1,27 @@
 //! I/O module
 //!
 //! This module provides file I/O functionality for arrays,
-//! including NPY format support and text file I/O
+//! including NPY and NPZ format support, streaming NPY reads, raw
+//! binary and flat text I/O (tofile/fromfile/frombuffer), and text
+//! file I/O with a genfromtxt-style loader. Dtype descriptors are shared
+//! with the `__array_interface__` protocol
 
+pub(crate) mod descr;
+mod genfromtxt;
+pub(crate) mod literal;
 mod npy;
+mod npz;
+mod printf;
+mod raw;
+mod stream;
 mod text;
+pub(crate) mod zip;
 
+pub use descr::{array_descr, array_typestr, Descr};
+pub use literal::PyLiteral;
+pub use genfromtxt::*;
 pub use npy::*;
+pub use npz::*;
+pub use raw::*;
+pub use stream::*;
 pub use text::*;
-
@@ -1,12 +1,16 @@
 //! NPY file format implementation
 //!
 //! This module provides save/load functionality for arrays in NPY format,
-//! equivalent to NumPy's .npy file format
+//! equivalent to NumPy's .npy file format. Versions 1.0, 2.0 (headers over
+//! 64 KiB) and 3.0 (UTF-8 headers) are read and written, for every dtype
+//! with an array-protocol descriptor, in C or Fortran order.
 
-use crate::array::{Array, ArrayError};
-use crate::types::{DType, NpyType};
+use super::descr::{byteswap, descr_to_dtype, dtype_to_descr};
+use super::literal::PyLiteral;
+use crate::array::{Array, ArrayError, Order};
+use crate::types::DType;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
 /// I/O error
@@ -18,6 +22,8 @@ pub enum IoError {
     FileError(String),
     /// Invalid format
     InvalidFormat,
+    /// Malformed NPY header
+    InvalidHeader(String),
     /// Unsupported dtype
     UnsupportedDtype,
 }
@@ -28,6 +34,7 @@ impl std::fmt::Display for IoError {
             IoError::ArrayError(e) => write!(f, "Array error: {}", e),
             IoError::FileError(msg) => write!(f, "File error: {}", msg),
             IoError::InvalidFormat => write!(f, "Invalid format"),
+            IoError::InvalidHeader(msg) => write!(f, "Invalid NPY header: {}", msg),
             IoError::UnsupportedDtype => write!(f, "Unsupported dtype"),
         }
     }
@@ -43,130 +50,239 @@ impl From<ArrayError> for IoError {
 
 /// NPY magic number
 const NPY_MAGIC: &[u8] = b"\x93NUMPY";
-const NPY_VERSION: u8 = 1;
-
-/// Convert NpyType to NumPy dtype string
-fn dtype_to_string(ty: NpyType) -> Result<String, IoError> {
-    match ty {
-        NpyType::Bool => Ok("|b1".to_string()),
-        NpyType::Int => Ok("<i4".to_string()),
-        NpyType::Float => Ok("<f4".to_string()),
-        NpyType::Double => Ok("<f8".to_string()),
-        _ => Err(IoError::UnsupportedDtype),
-    }
+
+/// Alignment of the data section, in bytes
+const ARRAY_ALIGN: usize = 64;
+
+/// Spare header space NumPy reserves so the growth axis can be rewritten
+/// in place (`GROWTH_AXIS_MAX_DIGITS`)
+const GROWTH_AXIS_MAX_DIGITS: usize = 21;
+
+fn io_err(e: std::io::Error) -> IoError {
+    IoError::FileError(e.to_string())
 }
 
-/// Save array to NPY file format
-pub fn save_npy(path: impl AsRef<Path>, array: &Array) -> Result<(), IoError> {
-    let mut file = File::create(path).map_err(|e| IoError::FileError(e.to_string()))?;
-    
-    // Write magic number
-    file.write_all(NPY_MAGIC).map_err(|e| IoError::FileError(e.to_string()))?;
-    
-    // Write version
-    file.write_all(&[NPY_VERSION, 0]).map_err(|e| IoError::FileError(e.to_string()))?;
-    
-    // Build header dict
-    let shape_str = format!("({})", array.shape().iter().map(|x| x.to_string()).collect::<Vec<_>>().join(","));
-    let dtype_str = dtype_to_string(array.dtype().type_())?;
-    let fortran_order = "False";
-    
-    let header_dict = format!("{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}", dtype_str, fortran_order, shape_str);
-    let header_str = format!("{}\n", header_dict);
-    
-    // Calculate header length (must be divisible by 64 for alignment)
-    let header_len = header_str.len();
-    let padded_header_len = header_len.div_ceil(64) * 64;
-    let padding_len = padded_header_len - header_len;
-    
-    // Write header length (little-endian u16)
-    let header_len_bytes = (padded_header_len as u16).to_le_bytes();
-    file.write_all(&header_len_bytes).map_err(|e| IoError::FileError(e.to_string()))?;
-    
-    // Write header
-    file.write_all(header_str.as_bytes()).map_err(|e| IoError::FileError(e.to_string()))?;
-    
-    // Write padding
-    let padding = vec![b' '; padding_len];
-    file.write_all(&padding).map_err(|e| IoError::FileError(e.to_string()))?;
-    
-    // Write array data
-    let data_size = array.size() * array.itemsize();
-    unsafe {
-        let data_ptr = array.data_ptr();
-        let data_slice = std::slice::from_raw_parts(data_ptr, data_size);
-        file.write_all(data_slice).map_err(|e| IoError::FileError(e.to_string()))?;
+/// Header of an NPY file
+///
+/// Describes the layout of the data that follows it: element dtype, shape
+/// and whether elements are stored in C or Fortran order.
+#[derive(Debug, Clone)]
+pub struct NpyHeader {
+    /// Element dtype
+    pub dtype: DType,
+    /// Whether the data is stored in Fortran (column-major) order
+    pub fortran_order: bool,
+    /// Array shape
+    pub shape: Vec<i64>,
+    /// Byte ranges of each element stored in non-native order
+    swaps: Vec<(usize, usize)>,
+}
+
+impl NpyHeader {
+    /// Create a header for native-endian data
+    pub fn new(dtype: DType, shape: Vec<i64>, fortran_order: bool) -> Self {
+        NpyHeader { dtype, fortran_order, shape, swaps: Vec::new() }
+    }
+
+    /// Header describing how `array` is written
+    ///
+    /// Like NumPy, Fortran order is used only for arrays that are
+    /// Fortran-contiguous but not C-contiguous.
+    pub fn for_array(array: &Array) -> Self {
+        let fortran_order = array.ndim() > 1 && array.is_f_contiguous() && !array.is_c_contiguous();
+        NpyHeader::new(array.dtype().clone(), array.shape().to_vec(), fortran_order)
+    }
+
+    /// Number of data bytes following the header
+    pub fn data_len(&self) -> usize {
+        self.shape.iter().product::<i64>() as usize * self.dtype.itemsize()
+    }
+
+    /// Whether the stored data is not in native byte order
+    pub fn needs_byteswap(&self) -> bool {
+        !self.swaps.is_empty()
+    }
+
+    /// Convert stored elements to native byte order in place
+    pub(crate) fn byteswap(&self, data: &mut [u8]) {
+        byteswap(data, self.dtype.itemsize(), &self.swaps);
+    }
+
+    /// Header dict as Python source, including NumPy's spare growth space
+    fn dict_source(&self) -> Result<String, IoError> {
+        let shape = PyLiteral::Tuple(self.shape.iter().map(|&n| PyLiteral::Int(n)).collect());
+        let mut source = format!(
+            "{{'descr': {}, 'fortran_order': {}, 'shape': {}, }}",
+            dtype_to_descr(&self.dtype)?.repr(),
+            PyLiteral::Bool(self.fortran_order).repr(),
+            shape.repr(),
+        );
+        let growth_axis = if self.fortran_order { self.shape.last() } else { self.shape.first() };
+        if let Some(n) = growth_axis {
+            source.push_str(&" ".repeat(GROWTH_AXIS_MAX_DIGITS.saturating_sub(n.to_string().len())));
+        }
+        Ok(source)
+    }
+
+    /// Encode the magic string, version, header length and padded header
+    ///
+    /// Version 1.0 is used when possible, 2.0 when the header exceeds
+    /// 64 KiB, and 3.0 when it is not Latin-1 encodable (UTF-8 field
+    /// names). The data that follows starts at a multiple of 64 bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, IoError> {
+        let source = self.dict_source()?;
+        let latin1: Option<Vec<u8>> = source.chars().map(|c| u8::try_from(c as u32).ok()).collect();
+        let (version, header) = match latin1 {
+            Some(bytes) => (1u8, bytes),
+            None => (3u8, source.into_bytes()),
+        };
+        let pad = |len_size: usize| ARRAY_ALIGN - (NPY_MAGIC.len() + 2 + len_size + header.len() + 1) % ARRAY_ALIGN;
+        let (version, len_size) = if version == 1 && header.len() + 1 + pad(2) <= u16::MAX as usize {
+            (1, 2)
+        } else {
+            (version.max(2), 4)
+        };
+        let padding = pad(len_size);
+        let total = header.len() + padding + 1;
+        if total > u32::MAX as usize {
+            return Err(IoError::InvalidHeader("header too large".to_string()));
+        }
+
+        let mut out = Vec::with_capacity(NPY_MAGIC.len() + 2 + len_size + total);
+        out.extend_from_slice(NPY_MAGIC);
+        out.extend_from_slice(&[version, 0]);
+        if len_size == 2 {
+            out.extend_from_slice(&(total as u16).to_le_bytes());
+        } else {
+            out.extend_from_slice(&(total as u32).to_le_bytes());
+        }
+        out.extend_from_slice(&header);
+        out.resize(out.len() + padding, b' ');
+        out.push(b'\n');
+        Ok(out)
+    }
+
+    /// Read and parse a header, leaving `reader` at the start of the data
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, IoError> {
+        let mut magic = [0u8; 6];
+        reader.read_exact(&mut magic).map_err(io_err)?;
+        if magic != NPY_MAGIC {
+            return Err(IoError::InvalidFormat);
+        }
+
+        // 1.0 uses a 2-byte header length, 2.0 and 3.0 a 4-byte one;
+        // 3.0 headers are UTF-8 rather than Latin-1
+        let mut version = [0u8; 2];
+        reader.read_exact(&mut version).map_err(io_err)?;
+        let header_len = match version {
+            [1, 0] => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len).map_err(io_err)?;
+                u16::from_le_bytes(len) as usize
+            }
+            [2, 0] | [3, 0] => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len).map_err(io_err)?;
+                u32::from_le_bytes(len) as usize
+            }
+            [major, minor] => {
+                return Err(IoError::InvalidHeader(format!("unsupported format version {}.{}", major, minor)))
+            }
+        };
+        let mut header = vec![0u8; header_len];
+        reader.read_exact(&mut header).map_err(io_err)?;
+        let source = if version[0] == 3 {
+            String::from_utf8(header).map_err(|_| IoError::InvalidHeader("header is not UTF-8".to_string()))?
+        } else {
+            header.iter().map(|&b| b as char).collect()
+        };
+        NpyHeader::parse(&source)
+    }
+
+    /// Parse the header dict
+    fn parse(source: &str) -> Result<Self, IoError> {
+        let dict = PyLiteral::parse(source)?;
+        let keys = match &dict {
+            PyLiteral::Dict(items) => items.len(),
+            _ => return Err(IoError::InvalidHeader(format!("header is not a dict: {}", source.trim()))),
+        };
+        let (descr, fortran_order, shape) = match (dict.get("descr"), dict.get("fortran_order"), dict.get("shape")) {
+            (Some(descr), Some(fortran_order), Some(shape)) if keys == 3 => (descr, fortran_order, shape),
+            _ => {
+                return Err(IoError::InvalidHeader(format!(
+                    "header does not contain the keys 'descr', 'fortran_order' and 'shape': {}",
+                    source.trim()
+                )))
+            }
+        };
+        let fortran_order = match fortran_order {
+            PyLiteral::Bool(b) => *b,
+            other => return Err(IoError::InvalidHeader(format!("fortran_order is not a bool: {}", other.repr()))),
+        };
+        let shape = match shape {
+            PyLiteral::Tuple(dims) => dims
+                .iter()
+                .map(|d| match d {
+                    PyLiteral::Int(n) if *n >= 0 => Ok(*n),
+                    _ => Err(IoError::InvalidHeader(format!("invalid shape {}", shape.repr()))),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            other => return Err(IoError::InvalidHeader(format!("shape is not a tuple: {}", other.repr()))),
+        };
+        let descr = descr_to_dtype(descr)?;
+        Ok(NpyHeader { dtype: descr.dtype, fortran_order, shape, swaps: descr.swaps })
     }
-    
-    Ok(())
 }
 
-/// Load array from NPY file format
-pub fn load_npy(path: impl AsRef<Path>) -> Result<Array, IoError> {
-    let mut file = File::open(path).map_err(|e| IoError::FileError(e.to_string()))?;
-    
-    // Read and validate magic number
-    let mut magic = [0u8; 6];
-    file.read_exact(&mut magic).map_err(|e| IoError::FileError(e.to_string()))?;
-    if magic != NPY_MAGIC {
-        return Err(IoError::InvalidFormat);
+/// Write an array in NPY format to any writer
+///
+/// Fortran-contiguous arrays are written in Fortran order; other
+/// non-contiguous views are written in logical (C) order. Pass `&mut w`
+/// to keep using the writer afterwards.
+pub fn write_npy<W: Write>(mut writer: W, array: &Array) -> Result<(), IoError> {
+    let header = NpyHeader::for_array(array);
+    writer.write_all(&header.to_bytes()?).map_err(io_err)?;
+
+    let len = header.data_len();
+    if len == 0 {
+        return Ok(());
     }
-    
-    // Read version
-    let mut version = [0u8; 2];
-    file.read_exact(&mut version).map_err(|e| IoError::FileError(e.to_string()))?;
-    if version[0] != NPY_VERSION {
-        return Err(IoError::InvalidFormat);
+    if header.fortran_order || array.is_c_contiguous() {
+        let data = unsafe { std::slice::from_raw_parts(array.data_ptr(), len) };
+        writer.write_all(data).map_err(io_err)
+    } else {
+        writer.write_all(&crate::utils::to_contiguous_bytes(array)).map_err(io_err)
     }
-    
-    // Read header length
-    let mut header_len_bytes = [0u8; 2];
-    file.read_exact(&mut header_len_bytes).map_err(|e| IoError::FileError(e.to_string()))?;
-    let header_len = u16::from_le_bytes(header_len_bytes) as usize;
-    
-    // Read header
-    let mut header = vec![0u8; header_len];
-    file.read_exact(&mut header).map_err(|e| IoError::FileError(e.to_string()))?;
-    let header_str = String::from_utf8_lossy(&header);
-    
-    // Parse header (simplified - full implementation would parse dict properly)
-    // For now, assume format: {'descr': '<f8', 'fortran_order': False, 'shape': (2, 3), }
-    // Extract shape and dtype (simplified parsing)
-    let shape_start = header_str.find("'shape': ").ok_or(IoError::InvalidFormat)? + 9;
-    let shape_end = header_str[shape_start..].find(')').ok_or(IoError::InvalidFormat)? + shape_start + 1;
-    let shape_str = &header_str[shape_start..shape_end];
-    
-    // Parse shape (simplified - assumes format (n, m, ...))
-    let shape: Vec<i64> = shape_str
-        .trim_matches(|c| c == '(' || c == ')' || c == ' ')
-        .split(',')
-        .filter_map(|s| s.trim().parse().ok())
-        .collect();
-    
-    // Extract dtype (simplified)
-    let descr_start = header_str.find("'descr': '").ok_or(IoError::InvalidFormat)? + 10;
-    let descr_end = header_str[descr_start..].find('\'').ok_or(IoError::InvalidFormat)? + descr_start;
-    let dtype_str = &header_str[descr_start..descr_end];
-    
-    // Parse dtype (simplified - only handles basic types)
-    let dtype = match dtype_str {
-        "<f8" | ">f8" | "=f8" => DType::new(NpyType::Double),
-        "<f4" | ">f4" | "=f4" => DType::new(NpyType::Float),
-        "<i4" | ">i4" | "=i4" => DType::new(NpyType::Int),
-        _ => return Err(IoError::UnsupportedDtype),
-    };
-    
-    // Create array
-    let mut array = Array::new(shape, dtype)?;
-    
-    // Read array data
-    let data_size = array.size() * array.itemsize();
-    unsafe {
-        let data_ptr = array.data_ptr_mut();
-        let data_slice = std::slice::from_raw_parts_mut(data_ptr, data_size);
-        file.read_exact(data_slice).map_err(|e| IoError::FileError(e.to_string()))?;
+}
+
+/// Read an array in NPY format from any reader
+///
+/// Fortran-order data is returned as a Fortran-contiguous array and
+/// non-native byte orders are converted to native. Exactly the header and
+/// data are consumed, so several arrays can be read from one stream.
+pub fn read_npy<R: Read>(mut reader: R) -> Result<Array, IoError> {
+    let header = NpyHeader::read_from(&mut reader)?;
+    let order = if header.fortran_order { Order::F } else { Order::C };
+    let mut array = Array::new_with_order(header.shape.clone(), header.dtype.clone(), order)?;
+    let len = header.data_len();
+    if len > 0 {
+        let data = unsafe { std::slice::from_raw_parts_mut(array.data_ptr_mut(), len) };
+        reader.read_exact(data).map_err(io_err)?;
+        header.byteswap(data);
     }
-    
     Ok(array)
 }
 
+/// Save array to NPY file format
+pub fn save_npy(path: impl AsRef<Path>, array: &Array) -> Result<(), IoError> {
+    let file = File::create(path).map_err(io_err)?;
+    let mut writer = BufWriter::new(file);
+    write_npy(&mut writer, array)?;
+    writer.flush().map_err(io_err)
+}
+
+/// Load array from NPY file format
+pub fn load_npy(path: impl AsRef<Path>) -> Result<Array, IoError> {
+    let file = File::open(path).map_err(io_err)?;
+    read_npy(BufReader::new(file))
+}
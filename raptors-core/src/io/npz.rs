@@ -0,0 +1,149 @@
+//! NPZ archive format implementation
+//!
+//! An `.npz` file is a ZIP archive with one NPY member per array, named
+//! `<name>.npy`, equivalent to NumPy's `savez`/`savez_compressed`/`load`.
+//! Members are encoded with the NPY codec and read lazily: opening an
+//! archive only reads its central directory.
+
+use super::npy::{read_npy, write_npy, NpyHeader};
+use super::zip::{ZipEntry, ZipIndex, ZipWriter};
+use super::IoError;
+use crate::array::Array;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+fn io_err(e: std::io::Error) -> IoError {
+    IoError::FileError(e.to_string())
+}
+
+/// Write `arrays` as an NPZ archive to any writer
+pub(crate) fn write_npz_to<W: Write>(writer: W, arrays: &[(&str, &Array)], compress: bool) -> Result<W, IoError> {
+    let mut seen = HashSet::with_capacity(arrays.len());
+    for (name, _) in arrays {
+        if !seen.insert(*name) {
+            return Err(IoError::FileError(format!("duplicate array name '{}'", name)));
+        }
+    }
+    let mut zip = ZipWriter::new(writer);
+    let mut buffer = Vec::new();
+    for (name, array) in arrays {
+        buffer.clear();
+        write_npy(&mut buffer, array)?;
+        zip.add_entry(&format!("{}.npy", name), &buffer, compress)?;
+    }
+    zip.finish()
+}
+
+fn save(path: &Path, arrays: &[(&str, &Array)], compress: bool) -> Result<(), IoError> {
+    let file = File::create(path).map_err(io_err)?;
+    let mut writer = write_npz_to(BufWriter::new(file), arrays, compress)?;
+    writer.flush().map_err(io_err)
+}
+
+/// Save several arrays into a single uncompressed NPZ archive
+///
+/// Each array is stored as the member `<name>.npy`. Names must be unique.
+pub fn savez(path: impl AsRef<Path>, arrays: &[(&str, &Array)]) -> Result<(), IoError> {
+    save(path.as_ref(), arrays, false)
+}
+
+/// Save several arrays into a single deflate-compressed NPZ archive
+pub fn savez_compressed(path: impl AsRef<Path>, arrays: &[(&str, &Array)]) -> Result<(), IoError> {
+    save(path.as_ref(), arrays, true)
+}
+
+/// A lazily loaded NPZ archive
+///
+/// Opening the archive reads only its directory; each array is decoded when
+/// it is loaded. Members may be named with or without the `.npy` suffix.
+pub struct NpzFile<R: Read + Seek = BufReader<File>> {
+    reader: R,
+    index: ZipIndex,
+}
+
+impl NpzFile<BufReader<File>> {
+    /// Open an NPZ archive on disk
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IoError> {
+        let file = File::open(path).map_err(io_err)?;
+        NpzFile::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> NpzFile<R> {
+    /// Read the archive directory from `reader`
+    pub fn new(mut reader: R) -> Result<Self, IoError> {
+        let index = ZipIndex::read(&mut reader)?;
+        Ok(NpzFile { reader, index })
+    }
+
+    /// Names of the arrays in the archive, in archive order
+    ///
+    /// The `.npy` suffix is stripped; other members are listed as stored.
+    pub fn files(&self) -> Vec<String> {
+        self.index
+            .entries
+            .iter()
+            .map(|e| e.name.strip_suffix(".npy").unwrap_or(&e.name).to_string())
+            .collect()
+    }
+
+    /// Number of members in the archive
+    pub fn len(&self) -> usize {
+        self.index.entries.len()
+    }
+
+    /// Whether the archive has no members
+    pub fn is_empty(&self) -> bool {
+        self.index.entries.is_empty()
+    }
+
+    /// Whether the archive has a member called `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry(name).is_some()
+    }
+
+    fn entry(&self, name: &str) -> Option<ZipEntry> {
+        self.index
+            .find(&format!("{}.npy", name))
+            .or_else(|| self.index.find(name))
+            .cloned()
+    }
+
+    fn npy_entry(&self, name: &str) -> Result<ZipEntry, IoError> {
+        let entry = self
+            .entry(name)
+            .ok_or_else(|| IoError::FileError(format!("'{}' is not a file in the archive", name)))?;
+        if !entry.name.ends_with(".npy") {
+            return Err(IoError::InvalidFormat);
+        }
+        Ok(entry)
+    }
+
+    /// Read only the NPY header of a member
+    pub fn header(&mut self, name: &str) -> Result<NpyHeader, IoError> {
+        let entry = self.npy_entry(name)?;
+        NpyHeader::read_from(&mut entry.open(&mut self.reader)?)
+    }
+
+    /// Decode a member, verifying its checksum
+    pub fn load(&mut self, name: &str) -> Result<Array, IoError> {
+        let entry = self.npy_entry(name)?;
+        let mut member = entry.open_checked(&mut self.reader)?;
+        let array = read_npy(&mut member)?;
+        member.finish()?;
+        Ok(array)
+    }
+
+    /// Decode every member, in archive order
+    pub fn load_all(&mut self) -> Result<Vec<(String, Array)>, IoError> {
+        self.files()
+            .into_iter()
+            .map(|name| {
+                let array = self.load(&name)?;
+                Ok((name, array))
+            })
+            .collect()
+    }
+}
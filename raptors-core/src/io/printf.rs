@@ -0,0 +1,349 @@
+//! printf-style value formatting
+//!
+//! Implements the `%` formatting used by NumPy's `savetxt` and `tofile`
+//! format arguments, following Python's semantics:
+//! `%[flags][width][.precision]conversion` with flags `-`, `+`, space, `0`
+//! and `#`, and conversions `d i u o x X e E f F g G s r c` plus `%%`.
+//! C length modifiers (`l`, `h`, ...) are accepted and ignored.
+
+/// A value to be formatted
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FormatValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    /// Complex value; one conversion renders it as `(re+imj)`
+    Complex(f64, f64),
+    Str(String),
+}
+
+/// One conversion specification
+#[derive(Debug, Clone, Copy)]
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    zero: bool,
+    alt: bool,
+    width: usize,
+    precision: Option<usize>,
+    conv: char,
+}
+
+#[derive(Debug, Clone)]
+enum Piece {
+    Literal(String),
+    Spec(Spec),
+}
+
+/// A parsed format string
+#[derive(Debug, Clone)]
+pub(crate) struct Format {
+    pieces: Vec<Piece>,
+}
+
+impl Format {
+    /// Parse a format string
+    pub(crate) fn parse(fmt: &str) -> Result<Self, String> {
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut chars = fmt.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                literal.push('%');
+                continue;
+            }
+            let mut spec = Spec {
+                left: false,
+                plus: false,
+                space: false,
+                zero: false,
+                alt: false,
+                width: 0,
+                precision: None,
+                conv: 's',
+            };
+            while let Some(&flag) = chars.peek() {
+                match flag {
+                    '-' => spec.left = true,
+                    '+' => spec.plus = true,
+                    ' ' => spec.space = true,
+                    '0' => spec.zero = true,
+                    '#' => spec.alt = true,
+                    _ => break,
+                }
+                chars.next();
+            }
+            spec.width = take_number(&mut chars).unwrap_or(0);
+            if chars.peek() == Some(&'.') {
+                chars.next();
+                spec.precision = Some(take_number(&mut chars).unwrap_or(0));
+            }
+            while chars.peek().is_some_and(|c| "hlLqjzt".contains(*c)) {
+                chars.next();
+            }
+            spec.conv = match chars.next() {
+                Some(conv) if "diuoxXeEfFgGsrc".contains(conv) => conv,
+                Some(conv) => return Err(format!("unsupported format character '{}' in '{}'", conv, fmt)),
+                None => return Err(format!("incomplete format '{}'", fmt)),
+            };
+            if !literal.is_empty() {
+                pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+            }
+            pieces.push(Piece::Spec(spec));
+        }
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+        Ok(Format { pieces })
+    }
+
+    /// Number of conversions in the format
+    pub(crate) fn slots(&self) -> usize {
+        self.pieces.iter().filter(|p| matches!(p, Piece::Spec(_))).count()
+    }
+
+    /// Format `values`, one per conversion
+    ///
+    /// If the format has one conversion more per complex value than there
+    /// are values, complex values are split into their real and imaginary
+    /// parts; otherwise each is rendered as `(re+imj)` by one conversion.
+    pub(crate) fn render(&self, values: &[FormatValue]) -> Result<String, String> {
+        let complex = values.iter().filter(|v| matches!(v, FormatValue::Complex(..))).count();
+        let slots = self.slots();
+        let expanded;
+        let values = if complex > 0 && slots == values.len() + complex {
+            expanded = values
+                .iter()
+                .flat_map(|v| match v {
+                    FormatValue::Complex(re, im) => vec![FormatValue::Float(*re), FormatValue::Float(*im)],
+                    v => vec![v.clone()],
+                })
+                .collect::<Vec<_>>();
+            &expanded[..]
+        } else {
+            values
+        };
+        if slots != values.len() {
+            return Err(format!("format has {} conversions for {} values", slots, values.len()));
+        }
+        let mut out = String::new();
+        let mut values = values.iter();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(text) => out.push_str(text),
+                Piece::Spec(spec) => match values.next().unwrap() {
+                    FormatValue::Complex(re, im) => {
+                        let imag = Spec { plus: true, space: false, ..*spec };
+                        out.push('(');
+                        out.push_str(&spec.format(&FormatValue::Float(*re))?);
+                        out.push_str(&imag.format(&FormatValue::Float(*im))?);
+                        out.push_str("j)");
+                    }
+                    value => out.push_str(&spec.format(value)?),
+                },
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<usize> {
+    let mut number = None;
+    while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+        number = Some(number.unwrap_or(0) * 10 + d as usize);
+        chars.next();
+    }
+    number
+}
+
+impl Spec {
+    fn format(&self, value: &FormatValue) -> Result<String, String> {
+        match self.conv {
+            'd' | 'i' | 'u' | 'o' | 'x' | 'X' => self.format_int(value),
+            'e' | 'E' | 'f' | 'F' | 'g' | 'G' => self.format_float(value),
+            'c' => {
+                let ch = match value {
+                    FormatValue::Str(s) if s.chars().count() == 1 => s.chars().next().unwrap(),
+                    FormatValue::Int(i) => char::from_u32(*i as u32).ok_or("%c arg not in range")?,
+                    FormatValue::UInt(u) => char::from_u32(*u as u32).ok_or("%c arg not in range")?,
+                    _ => return Err("%c requires an integer or a single character".to_string()),
+                };
+                Ok(self.pad("", "", &ch.to_string(), false))
+            }
+            _ => {
+                let mut text = match value {
+                    FormatValue::Str(s) if self.conv == 'r' => format!("'{}'", s),
+                    FormatValue::Str(s) => s.clone(),
+                    FormatValue::Bool(b) => if *b { "True" } else { "False" }.to_string(),
+                    FormatValue::Int(i) => i.to_string(),
+                    FormatValue::UInt(u) => u.to_string(),
+                    FormatValue::Float(f) => float_repr(*f),
+                    FormatValue::Complex(re, im) => format!("({}{}j)", float_repr(*re), signed(float_repr(*im))),
+                };
+                if let Some(p) = self.precision {
+                    text = text.chars().take(p).collect();
+                }
+                Ok(self.pad("", "", &text, false))
+            }
+        }
+    }
+
+    fn sign(&self, negative: bool) -> &'static str {
+        if negative {
+            "-"
+        } else if self.plus {
+            "+"
+        } else if self.space {
+            " "
+        } else {
+            ""
+        }
+    }
+
+    fn format_int(&self, value: &FormatValue) -> Result<String, String> {
+        let n: i128 = match value {
+            FormatValue::Bool(b) => *b as i128,
+            FormatValue::Int(i) => *i as i128,
+            FormatValue::UInt(u) => *u as i128,
+            FormatValue::Float(f) if f.is_finite() => f.trunc() as i128,
+            FormatValue::Float(f) => return Err(format!("cannot convert float {} to integer", f)),
+            _ => return Err(format!("%{} format: a number is required", self.conv)),
+        };
+        let magnitude = n.unsigned_abs();
+        let (prefix, mut digits) = match self.conv {
+            'o' => ("0o", format!("{:o}", magnitude)),
+            'x' => ("0x", format!("{:x}", magnitude)),
+            'X' => ("0X", format!("{:X}", magnitude)),
+            _ => ("", magnitude.to_string()),
+        };
+        if let Some(p) = self.precision {
+            if digits.len() < p {
+                digits = format!("{}{}", "0".repeat(p - digits.len()), digits);
+            }
+        }
+        let prefix = if self.alt { prefix } else { "" };
+        Ok(self.pad(self.sign(n < 0), prefix, &digits, true))
+    }
+
+    fn format_float(&self, value: &FormatValue) -> Result<String, String> {
+        let v = match value {
+            FormatValue::Bool(b) => *b as u8 as f64,
+            FormatValue::Int(i) => *i as f64,
+            FormatValue::UInt(u) => *u as f64,
+            FormatValue::Float(f) => *f,
+            _ => return Err(format!("%{} format: a real number is required", self.conv)),
+        };
+        let upper = self.conv.is_ascii_uppercase();
+        if !v.is_finite() {
+            let text = if v.is_nan() { "nan" } else { "inf" };
+            let text = if upper { text.to_uppercase() } else { text.to_string() };
+            return Ok(self.pad(self.sign(v < 0.0), "", &text, false));
+        }
+        let precision = self.precision.unwrap_or(6);
+        let body = match self.conv.to_ascii_lowercase() {
+            'e' => exponential(v.abs(), precision, self.alt),
+            'f' => fixed(v.abs(), precision, self.alt),
+            _ => {
+                let p = precision.max(1);
+                let exp = if v == 0.0 { 0 } else { decimal_exponent(v.abs(), p - 1) };
+                let mut text = if exp >= -4 && exp < p as i32 {
+                    fixed(v.abs(), (p as i32 - 1 - exp) as usize, self.alt)
+                } else {
+                    exponential(v.abs(), p - 1, self.alt)
+                };
+                if !self.alt {
+                    text = strip_zeros(&text);
+                }
+                text
+            }
+        };
+        let body = if upper { body.to_uppercase() } else { body };
+        Ok(self.pad(self.sign(v.is_sign_negative()), "", &body, true))
+    }
+
+    fn pad(&self, sign: &str, prefix: &str, body: &str, numeric: bool) -> String {
+        let len = sign.len() + prefix.len() + body.chars().count();
+        if len >= self.width {
+            return format!("{}{}{}", sign, prefix, body);
+        }
+        let fill = self.width - len;
+        if self.left {
+            format!("{}{}{}{}", sign, prefix, body, " ".repeat(fill))
+        } else if self.zero && numeric {
+            format!("{}{}{}{}", sign, prefix, "0".repeat(fill), body)
+        } else {
+            format!("{}{}{}{}", " ".repeat(fill), sign, prefix, body)
+        }
+    }
+}
+
+/// `%e` body: mantissa with `precision` decimals and a signed, two-digit exponent
+fn exponential(v: f64, precision: usize, alt: bool) -> String {
+    let text = format!("{:.*e}", precision, v);
+    let (mantissa, exp) = text.split_once('e').unwrap();
+    let exp: i32 = exp.parse().unwrap();
+    let point = if alt && precision == 0 { "." } else { "" };
+    format!("{}{}e{}{:02}", mantissa, point, if exp < 0 { '-' } else { '+' }, exp.abs())
+}
+
+fn fixed(v: f64, precision: usize, alt: bool) -> String {
+    let point = if alt && precision == 0 { "." } else { "" };
+    format!("{:.*}{}", precision, v, point)
+}
+
+/// Decimal exponent of `v` once rounded to `precision` decimals in `%e` form
+fn decimal_exponent(v: f64, precision: usize) -> i32 {
+    let text = format!("{:.*e}", precision, v);
+    text.split_once('e').unwrap().1.parse().unwrap()
+}
+
+/// Remove trailing zeros (and a bare decimal point) from the mantissa
+fn strip_zeros(text: &str) -> String {
+    let (mantissa, exp) = match text.find('e') {
+        Some(i) => text.split_at(i),
+        None => (text, ""),
+    };
+    let mantissa = if mantissa.contains('.') {
+        mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        mantissa
+    };
+    format!("{}{}", mantissa, exp)
+}
+
+/// Shortest round-tripping representation, as Python's `repr(float)`
+pub(crate) fn float_repr(v: f64) -> String {
+    if v.is_nan() {
+        return "nan".to_string();
+    }
+    if v.is_infinite() {
+        return if v < 0.0 { "-inf" } else { "inf" }.to_string();
+    }
+    let exp = if v == 0.0 { 0 } else { v.abs().log10().floor() as i32 };
+    if (-4..16).contains(&exp) {
+        // Debug formatting always keeps a decimal point ("2.0")
+        let text = format!("{:?}", v);
+        if !text.contains('e') {
+            return text;
+        }
+    }
+    let text = format!("{:e}", v);
+    let (mantissa, exp) = text.split_once('e').unwrap();
+    let exp: i32 = exp.parse().unwrap();
+    format!("{}e{}{:02}", mantissa, if exp < 0 { '-' } else { '+' }, exp.abs())
+}
+
+fn signed(text: String) -> String {
+    if text.starts_with('-') {
+        text
+    } else {
+        format!("+{}", text)
+    }
+}
@@ -0,0 +1,206 @@
+//! Raw binary and flat text array I/O
+//!
+//! Equivalent to NumPy's `tofile`, `fromfile`, `frombuffer`, `fromstring`
+//! and `fromiter`. Binary data is the raw element bytes in C order and
+//! native byte order, with no header, so shape and dtype must be known to
+//! read it back. With a non-empty separator, elements are written and read
+//! as text instead.
+
+use super::genfromtxt::{encode_value, CellValue};
+use super::printf::Format;
+use super::text::{check_text_dtype, value_at};
+use super::IoError;
+use crate::array::{Array, ArrayError};
+use crate::types::DType;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+fn io_err(e: std::io::Error) -> IoError {
+    IoError::FileError(e.to_string())
+}
+
+fn invalid(msg: impl Into<String>) -> IoError {
+    IoError::ArrayError(ArrayError::InvalidValue(msg.into()))
+}
+
+fn check_itemsize(dtype: &DType) -> Result<usize, IoError> {
+    match dtype.itemsize() {
+        0 => Err(invalid("cannot read or write elements of size zero")),
+        n => Ok(n),
+    }
+}
+
+impl Array {
+    /// Write the array to a file
+    ///
+    /// With an empty `sep` the raw element bytes are written in C order.
+    /// Otherwise elements are written as text separated by `sep`, each
+    /// formatted with the printf-style `format` (`"%s"` if empty). The
+    /// shape and dtype are not stored.
+    pub fn tofile(&self, path: impl AsRef<Path>, sep: &str, format: &str) -> Result<(), IoError> {
+        let file = File::create(path).map_err(io_err)?;
+        let mut writer = BufWriter::new(file);
+        self.tofile_writer(&mut writer, sep, format)?;
+        writer.flush().map_err(io_err)
+    }
+
+    /// Write the array to any writer, as `tofile` does
+    pub fn tofile_writer<W: Write>(&self, mut writer: W, sep: &str, format: &str) -> Result<(), IoError> {
+        let bytes = crate::utils::to_contiguous_bytes(self);
+        if sep.is_empty() {
+            return writer.write_all(&bytes).map_err(io_err);
+        }
+        check_text_dtype(self.dtype()).map_err(|e| invalid(e.to_string()))?;
+        let format = Format::parse(if format.is_empty() { "%s" } else { format }).map_err(invalid)?;
+        let itemsize = check_itemsize(self.dtype())?;
+        for (i, element) in bytes.chunks(itemsize).enumerate() {
+            if i > 0 {
+                writer.write_all(sep.as_bytes()).map_err(io_err)?;
+            }
+            let text = format.render(&[value_at(self.dtype(), element)]).map_err(invalid)?;
+            writer.write_all(text.as_bytes()).map_err(io_err)?;
+        }
+        Ok(())
+    }
+}
+
+/// Build a 1-D array from raw element bytes
+fn from_bytes(bytes: &[u8], dtype: DType) -> Result<Array, IoError> {
+    let count = bytes.len() / dtype.itemsize();
+    let mut array = Array::new(vec![count as i64], dtype)?;
+    if !bytes.is_empty() {
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), array.data_ptr_mut(), bytes.len()) };
+    }
+    Ok(array)
+}
+
+/// Parse separated text into a 1-D array
+///
+/// Whitespace around separators is ignored; a separator of only whitespace
+/// matches any run of whitespace. Returns fewer than `count` elements if
+/// the text runs out.
+fn parse_separated(text: &str, dtype: DType, count: Option<usize>, sep: &str) -> Result<Array, IoError> {
+    check_text_dtype(&dtype).map_err(|e| invalid(e.to_string()))?;
+    let itemsize = check_itemsize(&dtype)?;
+    let sep = sep.trim();
+    let tokens: Box<dyn Iterator<Item = &str>> = if sep.is_empty() {
+        Box::new(text.split_whitespace())
+    } else {
+        // A trailing separator does not start another element
+        Box::new(text.trim().trim_end_matches(sep).split(sep).map(str::trim))
+    };
+    let mut bytes = Vec::new();
+    for token in tokens.take(count.unwrap_or(usize::MAX)) {
+        if token.is_empty() && sep.is_empty() {
+            continue;
+        }
+        let start = bytes.len();
+        bytes.resize(start + itemsize, 0);
+        encode_value(&CellValue::Str(token.to_string()), &dtype, &mut bytes[start..])
+            .map_err(|e| invalid(format!("could not parse element {}: {}", start / itemsize, e)))?;
+    }
+    from_bytes(&bytes, dtype)
+}
+
+/// Read a 1-D array from a file written by `tofile`
+///
+/// `offset` bytes are skipped first. At most `count` elements are read
+/// (all of them if `None`); a shorter file yields fewer elements. With an
+/// empty `sep` the file holds raw element bytes and a trailing partial
+/// element is ignored; otherwise it is text separated by `sep`.
+pub fn fromfile(
+    path: impl AsRef<Path>,
+    dtype: DType,
+    count: Option<usize>,
+    sep: &str,
+    offset: u64,
+) -> Result<Array, IoError> {
+    let mut file = BufReader::new(File::open(path).map_err(io_err)?);
+    file.seek(SeekFrom::Start(offset)).map_err(io_err)?;
+    if !sep.is_empty() {
+        let mut text = String::new();
+        file.read_to_string(&mut text).map_err(io_err)?;
+        return parse_separated(&text, dtype, count, sep);
+    }
+    let itemsize = check_itemsize(&dtype)?;
+    let mut bytes = Vec::new();
+    match count {
+        Some(n) => file.take((n * itemsize) as u64).read_to_end(&mut bytes),
+        None => file.read_to_end(&mut bytes),
+    }
+    .map_err(io_err)?;
+    bytes.truncate(bytes.len() / itemsize * itemsize);
+    from_bytes(&bytes, dtype)
+}
+
+/// Interpret a byte buffer as a 1-D array without copying
+///
+/// The array views `buffer` from byte `offset` and keeps it alive, so the
+/// buffer may be dropped by the caller. The array is read-only, and is
+/// flagged unaligned if `offset` does not suit the dtype. Without `count`
+/// the rest of the buffer must be a whole number of elements; with it, the
+/// buffer must hold at least `count` elements.
+pub fn frombuffer<B>(buffer: Arc<B>, dtype: DType, count: Option<usize>, offset: usize) -> Result<Array, IoError>
+where
+    B: AsRef<[u8]> + ?Sized + Send + Sync + 'static,
+{
+    let itemsize = check_itemsize(&dtype)?;
+    let bytes = (*buffer).as_ref();
+    let available = bytes
+        .len()
+        .checked_sub(offset)
+        .ok_or_else(|| invalid("offset must be non-negative and no greater than buffer length"))?;
+    let count = match count {
+        Some(n) if n * itemsize > available => return Err(invalid("buffer is smaller than requested size")),
+        Some(n) => n,
+        None if available % itemsize != 0 => return Err(invalid("buffer size must be a multiple of element size")),
+        None => available / itemsize,
+    };
+    let data = bytes[offset..].as_ptr() as *mut u8;
+    // The array is read-only, so the data is never written through this pointer
+    unsafe { Array::from_owner(data, vec![count as i64], vec![itemsize as i64], dtype, Arc::new(buffer), false) }
+        .map_err(IoError::ArrayError)
+}
+
+/// Parse a 1-D array from text separated by `sep`
+///
+/// Unlike `fromfile`, it is an error for the text to hold fewer than
+/// `count` elements. Binary data should be read with `frombuffer`.
+pub fn fromstring(string: &str, dtype: DType, count: Option<usize>, sep: &str) -> Result<Array, IoError> {
+    if sep.is_empty() {
+        return Err(invalid("fromstring requires a separator; use frombuffer for binary data"));
+    }
+    let array = parse_separated(string, dtype, count, sep)?;
+    if count.is_some_and(|n| array.size() < n) {
+        return Err(invalid("string is smaller than requested size"));
+    }
+    Ok(array)
+}
+
+/// Build a 1-D array from an iterator of element values
+///
+/// `T` must have the dtype's size and layout. With `count`, exactly that
+/// many elements are taken and a shorter iterator is an error.
+pub fn fromiter<T, I>(iter: I, dtype: DType, count: Option<usize>) -> Result<Array, IoError>
+where
+    T: Copy,
+    I: IntoIterator<Item = T>,
+{
+    if std::mem::size_of::<T>() != dtype.itemsize() {
+        return Err(IoError::ArrayError(ArrayError::TypeMismatch));
+    }
+    let values: Vec<T> = match count {
+        Some(n) => {
+            let values: Vec<T> = iter.into_iter().take(n).collect();
+            if values.len() < n {
+                return Err(invalid(format!("iterator too short: expected {} but got {}", n, values.len())));
+            }
+            values
+        }
+        None => iter.into_iter().collect(),
+    };
+    let len = values.len() as i64;
+    Ok(Array::from_slice(&values, vec![len], dtype)?)
+}
@@ -0,0 +1,161 @@
+//! Streaming NPY reader
+//!
+//! `NpyReader` parses an NPY header from any reader and then reads the data
+//! in chunks of rows, so arrays larger than memory can be processed piece by
+//! piece. With a seekable reader, arbitrary row ranges can be read directly.
+//!
+//! A row is a slice along the outermost axis in storage order: the first
+//! axis for C-order data and the last axis for Fortran-order data.
+
+use super::npy::NpyHeader;
+use super::IoError;
+use crate::array::{Array, ArrayError, Order};
+use std::io::{Read, Seek, SeekFrom};
+
+fn io_err(e: std::io::Error) -> IoError {
+    IoError::FileError(e.to_string())
+}
+
+/// Chunked reader over the data of an NPY stream
+pub struct NpyReader<R> {
+    reader: R,
+    header: NpyHeader,
+    /// Index of the next row to be read
+    row: usize,
+}
+
+impl<R: Read> NpyReader<R> {
+    /// Parse the header, leaving the reader at the first row
+    pub fn new(mut reader: R) -> Result<Self, IoError> {
+        let header = NpyHeader::read_from(&mut reader)?;
+        Ok(NpyReader { reader, header, row: 0 })
+    }
+
+    /// Header of the stream
+    pub fn header(&self) -> &NpyHeader {
+        &self.header
+    }
+
+    /// Number of rows in the array
+    ///
+    /// A 0-d array is a single row.
+    pub fn rows(&self) -> usize {
+        let axis = if self.header.fortran_order { self.header.shape.last() } else { self.header.shape.first() };
+        axis.map_or(1, |&n| n as usize)
+    }
+
+    /// Number of bytes in one row
+    pub fn row_bytes(&self) -> usize {
+        let shape = &self.header.shape;
+        let inner = match shape.len() {
+            0 => &shape[..],
+            _ if self.header.fortran_order => &shape[..shape.len() - 1],
+            _ => &shape[1..],
+        };
+        inner.iter().product::<i64>() as usize * self.header.dtype.itemsize()
+    }
+
+    /// Index of the next row to be read
+    pub fn position(&self) -> usize {
+        self.row
+    }
+
+    /// Number of rows not yet read
+    pub fn remaining(&self) -> usize {
+        self.rows() - self.row
+    }
+
+    /// Shape of a chunk of `n` rows
+    fn chunk_shape(&self, n: usize) -> Vec<i64> {
+        let mut shape = self.header.shape.clone();
+        match shape.len() {
+            0 if n == 1 => {}
+            0 => shape.push(n as i64),
+            _ if self.header.fortran_order => *shape.last_mut().unwrap() = n as i64,
+            _ => shape[0] = n as i64,
+        }
+        shape
+    }
+
+    /// Read exactly `n` rows from the current position
+    fn read_exact_rows(&mut self, n: usize) -> Result<Array, IoError> {
+        let order = if self.header.fortran_order { Order::F } else { Order::C };
+        let mut array = Array::new_with_order(self.chunk_shape(n), self.header.dtype.clone(), order)?;
+        let len = n * self.row_bytes();
+        if len > 0 {
+            let data = unsafe { std::slice::from_raw_parts_mut(array.data_ptr_mut(), len) };
+            self.reader.read_exact(data).map_err(io_err)?;
+            self.header.byteswap(data);
+        }
+        self.row += n;
+        Ok(array)
+    }
+
+    /// Read the next chunk of up to `n` rows
+    ///
+    /// Returns `None` once every row has been read.
+    pub fn read_rows(&mut self, n: usize) -> Result<Option<Array>, IoError> {
+        let n = n.min(self.remaining());
+        if n == 0 {
+            return Ok(None);
+        }
+        self.read_exact_rows(n).map(Some)
+    }
+
+    /// Read every remaining row as one array
+    pub fn read_to_end(&mut self) -> Result<Array, IoError> {
+        self.read_exact_rows(self.remaining())
+    }
+
+    /// Iterate over the remaining rows in chunks of `rows` rows
+    ///
+    /// The last chunk may be shorter.
+    pub fn chunks(&mut self, rows: usize) -> NpyChunks<'_, R> {
+        NpyChunks { reader: self, rows: rows.max(1) }
+    }
+
+    /// Return the underlying reader, positioned after the last row read
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read + Seek> NpyReader<R> {
+    /// Move to row `row` so the next read starts there
+    pub fn seek_row(&mut self, row: usize) -> Result<(), IoError> {
+        if row > self.rows() {
+            return Err(IoError::ArrayError(ArrayError::ViewOutOfBounds));
+        }
+        // Seek relative to the current row so the stream need not start at
+        // the beginning of the NPY data
+        let delta = (row as i64 - self.row as i64) * self.row_bytes() as i64;
+        self.reader.seek(SeekFrom::Current(delta)).map_err(io_err)?;
+        self.row = row;
+        Ok(())
+    }
+
+    /// Read rows `start..stop`
+    ///
+    /// The reader is left positioned at `stop`.
+    pub fn read_slice(&mut self, start: usize, stop: usize) -> Result<Array, IoError> {
+        if start > stop || stop > self.rows() {
+            return Err(IoError::ArrayError(ArrayError::ViewOutOfBounds));
+        }
+        self.seek_row(start)?;
+        self.read_exact_rows(stop - start)
+    }
+}
+
+/// Iterator over row chunks of an `NpyReader`
+pub struct NpyChunks<'a, R> {
+    reader: &'a mut NpyReader<R>,
+    rows: usize,
+}
+
+impl<R: Read> Iterator for NpyChunks<'_, R> {
+    type Item = Result<Array, IoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_rows(self.rows).transpose()
+    }
+}
@@ -1,8 +1,10 @@
 //! Text file I/O implementation
 //!
 //! This module provides text-based file I/O functionality for arrays,
-//! equivalent to NumPy's savetxt and loadtxt functions
+//! equivalent to NumPy's savetxt and loadtxt functions. Values are
+//! written with printf-style formats (see the `printf` module).
 
+use super::printf::{Format, FormatValue};
 use crate::array::{Array, ArrayError};
 use crate::types::{DType, NpyType};
 use std::fs::File;
@@ -46,14 +48,24 @@ impl From<ArrayError> for TextIoError {
 pub struct SaveTextOptions {
     /// Delimiter (default: space)
     pub delimiter: String,
-    /// Format string for numbers (default: "%.18e")
+    /// printf-style format (default: "%.18e")
+    ///
+    /// A single conversion (`%d`, `%10.4f`, `%s`, `%e`, ...) is applied to
+    /// every column; a format with one conversion per column formats a
+    /// whole row, in which case `delimiter` is not used.
     pub fmt: String,
+    /// Per-column formats, overriding `fmt` when not empty
+    pub fmts: Vec<String>,
     /// Header line (optional)
     pub header: Option<String>,
     /// Footer line (optional)
     pub footer: Option<String>,
     /// Comments prefix (default: "#")
     pub comments: String,
+    /// Line separator (default: "\n")
+    pub newline: String,
+    /// Output encoding: "utf-8" (default), "latin-1" or "ascii"
+    pub encoding: String,
 }
 
 impl Default for SaveTextOptions {
@@ -61,9 +73,12 @@ impl Default for SaveTextOptions {
         SaveTextOptions {
             delimiter: " ".to_string(),
             fmt: "%.18e".to_string(),
+            fmts: Vec::new(),
             header: None,
             footer: None,
             comments: "#".to_string(),
+            newline: "\n".to_string(),
+            encoding: "utf-8".to_string(),
         }
     }
 }
@@ -95,153 +110,347 @@ impl Default for LoadTextOptions {
 /// Save array to text file
 ///
 /// Saves an array to a text file, equivalent to NumPy's savetxt.
-/// For multi-dimensional arrays, flattens to 2D (rows x columns).
+/// See `write_text` for the layout.
 pub fn save_text<P: AsRef<Path>>(
     path: P,
     array: &Array,
     options: SaveTextOptions,
 ) -> Result<(), TextIoError> {
-    let shape = array.shape();
-    let ndim = shape.len();
-    
-    if ndim == 0 {
-        return Err(TextIoError::Unsupported("Cannot save 0-dimensional array".to_string()));
-    }
-    
-    // Flatten to 2D if needed
-    let (rows, cols) = if ndim == 1 {
-        (shape[0], 1)
-    } else if ndim == 2 {
-        (shape[0], shape[1])
-    } else {
-        // For ND arrays, flatten all but last dimension
-        let rows: i64 = shape[..ndim - 1].iter().product();
-        (rows, shape[ndim - 1])
-    };
-    
     let file = File::create(path).map_err(|e| {
         TextIoError::FileError(format!("Failed to create file: {}", e))
     })?;
     let mut writer = BufWriter::new(file);
-    
-    // Write header
-    if let Some(ref header) = options.header {
-        writeln!(writer, "{}{}", options.comments, header).map_err(|e| {
-            TextIoError::FileError(format!("Failed to write header: {}", e))
-        })?;
-    }
-    
-    // Write data
-    match array.dtype().type_() {
-        NpyType::Double => write_float_data(&mut writer, array, rows, cols, &options)?,
-        NpyType::Float => write_float_data(&mut writer, array, rows, cols, &options)?,
-        NpyType::Int => write_int_data(&mut writer, array, rows, cols, &options)?,
-        NpyType::Long => write_int_data(&mut writer, array, rows, cols, &options)?,
-        _ => return Err(TextIoError::Unsupported(
-            format!("Unsupported dtype for text I/O: {:?}", array.dtype().type_())
-        )),
-    }
-    
-    // Write footer
-    if let Some(ref footer) = options.footer {
-        writeln!(writer, "{}{}", options.comments, footer).map_err(|e| {
-            TextIoError::FileError(format!("Failed to write footer: {}", e))
-        })?;
-    }
-    
+    write_text(&mut writer, array, options)?;
     writer.flush().map_err(|e| {
         TextIoError::FileError(format!("Failed to flush file: {}", e))
-    })?;
-    
-    Ok(())
+    })
 }
 
-/// Write float data to file
-fn write_float_data<W: Write>(
-    writer: &mut BufWriter<W>,
+/// Write an array as text to any writer
+///
+/// 1-D arrays are written one element per line and 2-D arrays one row per
+/// line. Arrays with more dimensions are written as a sequence of 2-D
+/// slices over the last two axes, each preceded by a comment line with its
+/// index, e.g. `#[1, :, :]`. Structured arrays are written one record per
+/// line with one column per field (subarray fields span several columns);
+/// unless a header is given, the field names form the header.
+///
+/// Complex values formatted with a single conversion are written as
+/// `(re+imj)`; giving two conversions per complex column formats the real
+/// and imaginary parts separately.
+pub fn write_text<W: Write>(
+    mut writer: W,
     array: &Array,
-    rows: i64,
-    cols: i64,
-    options: &SaveTextOptions,
+    options: SaveTextOptions,
 ) -> Result<(), TextIoError> {
-    unsafe {
-        let data_ptr = array.data_ptr();
+    if array.ndim() == 0 {
+        return Err(TextIoError::Unsupported("Cannot save 0-dimensional array".to_string()));
+    }
+    let encoding = TextEncoding::parse(&options.encoding)?;
+    let table = TextTable::new(array)?;
+    let formatter = RowFormatter::new(&table, &options)?;
+    let mut line = String::new();
+    let mut emit = |line: &str| -> Result<(), TextIoError> {
+        let mut bytes = encoding.encode(line)?;
+        bytes.extend_from_slice(&encoding.encode(&options.newline)?);
+        writer.write_all(&bytes).map_err(|e| {
+            TextIoError::FileError(format!("Failed to write line: {}", e))
+        })
+    };
+
+    // Header, defaulting to the field names of structured arrays
+    let header = match &options.header {
+        Some(header) => Some(header.clone()),
+        None => table.names.as_ref().map(|names| names.join(&options.delimiter)),
+    };
+    if let Some(header) = header.filter(|h| !h.is_empty()) {
+        for text in header.split('\n') {
+            emit(&format!("{}{}", options.comments, text))?;
+        }
+    }
+
+    let data = crate::utils::to_contiguous_bytes(array);
+    let record_size = table.record_size.max(1);
+    for (row, record) in data.chunks(record_size).take(table.rows).enumerate() {
+        if table.block_rows > 0 && row % table.block_rows == 0 {
+            let index = block_index(array.shape(), row / table.block_rows);
+            emit(&format!("{}[{}]", options.comments, index))?;
+        }
+        line.clear();
+        let values = table.values(record);
+        formatter.format_row(&values, &options.delimiter, &mut line)?;
+        emit(&line)?;
+    }
+    if table.record_size == 0 {
+        // Zero-width rows: nothing to format, one empty line per row
+        for _ in 0..table.rows {
+            emit("")?;
+        }
+    }
+
+    if let Some(footer) = options.footer.as_ref().filter(|f| !f.is_empty()) {
+        for text in footer.split('\n') {
+            emit(&format!("{}{}", options.comments, text))?;
+        }
+    }
+    Ok(())
+}
+
+/// Index label of 2-D slice `block` of an array with `shape`
+fn block_index(shape: &[i64], mut block: usize) -> String {
+    let lead = &shape[..shape.len() - 2];
+    let mut index = vec![0; lead.len()];
+    for (i, &n) in lead.iter().enumerate().rev() {
+        index[i] = block % n as usize;
+        block /= n as usize;
+    }
+    let mut parts: Vec<String> = index.iter().map(|i| i.to_string()).collect();
+    parts.extend([":".to_string(), ":".to_string()]);
+    parts.join(", ")
+}
+
+/// Text encodings supported for output
+#[derive(Debug, Clone, Copy)]
+enum TextEncoding {
+    Utf8,
+    Latin1,
+    Ascii,
+}
+
+impl TextEncoding {
+    fn parse(name: &str) -> Result<Self, TextIoError> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(TextEncoding::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" => Ok(TextEncoding::Latin1),
+            "ascii" | "us-ascii" => Ok(TextEncoding::Ascii),
+            _ => Err(TextIoError::Unsupported(format!("Unsupported encoding: {}", name))),
+        }
+    }
+
+    fn encode(self, text: &str) -> Result<Vec<u8>, TextIoError> {
+        let limit = match self {
+            TextEncoding::Utf8 => return Ok(text.as_bytes().to_vec()),
+            TextEncoding::Latin1 => 0xff,
+            TextEncoding::Ascii => 0x7f,
+        };
+        text.chars()
+            .map(|c| {
+                if (c as u32) <= limit {
+                    Ok(c as u8)
+                } else {
+                    Err(TextIoError::Unsupported(format!("Character {:?} cannot be encoded as {:?}", c, self)))
+                }
+            })
+            .collect()
+    }
+}
+
+/// A column of a text table: one element of a row or one field of a record
+struct TextColumn {
+    dtype: DType,
+    offset: usize,
+}
+
+/// An array viewed as rows of formatted columns
+struct TextTable {
+    columns: Vec<TextColumn>,
+    /// Column names for structured arrays
+    names: Option<Vec<String>>,
+    /// Bytes per row in the C-contiguous data
+    record_size: usize,
+    rows: usize,
+    /// Rows per 2-D slice of an array with more than two dimensions
+    block_rows: usize,
+}
+
+impl TextTable {
+    fn new(array: &Array) -> Result<Self, TextIoError> {
+        let shape = array.shape();
+        if let Some(layout) = array.dtype().fields() {
+            let mut columns = Vec::new();
+            let mut names = Vec::new();
+            flatten_fields(layout, 0, "", &mut columns, &mut names)?;
+            return Ok(TextTable {
+                columns,
+                names: Some(names),
+                record_size: array.itemsize(),
+                rows: array.size(),
+                block_rows: 0,
+            });
+        }
         let itemsize = array.itemsize();
-        
-        for i in 0..rows {
-            for j in 0..cols {
-                let offset = (i * cols + j) as usize * itemsize;
-                let value = match array.dtype().type_() {
-                    NpyType::Double => {
-                        *(data_ptr.add(offset) as *const f64)
-                    }
-                    NpyType::Float => {
-                        *(data_ptr.add(offset) as *const f32) as f64
-                    }
-                    _ => return Err(TextIoError::Unsupported("Invalid float type".to_string())),
-                };
-                
-                if j > 0 {
-                    write!(writer, "{}", options.delimiter).map_err(|e| {
-                        TextIoError::FileError(format!("Failed to write delimiter: {}", e))
-                    })?;
+        let cols = if shape.len() == 1 { 1 } else { shape[shape.len() - 1] as usize };
+        let columns = (0..cols)
+            .map(|j| TextColumn { dtype: array.dtype().clone(), offset: j * itemsize })
+            .collect::<Vec<_>>();
+        check_text_dtype(array.dtype())?;
+        Ok(TextTable {
+            columns,
+            names: None,
+            record_size: cols * itemsize,
+            rows: array
+                .size()
+                .checked_div(cols)
+                .unwrap_or_else(|| shape[..shape.len() - 1].iter().product::<i64>() as usize),
+            block_rows: if shape.len() > 2 { shape[shape.len() - 2] as usize } else { 0 },
+        })
+    }
+
+    fn values(&self, record: &[u8]) -> Vec<FormatValue> {
+        self.columns
+            .iter()
+            .map(|c| value_at(&c.dtype, &record[c.offset..c.offset + c.dtype.itemsize()]))
+            .collect()
+    }
+}
+
+/// Collect the scalar columns of a structured layout, expanding subarray
+/// and nested fields
+fn flatten_fields(
+    layout: &crate::structured::StructuredDType,
+    base: usize,
+    prefix: &str,
+    columns: &mut Vec<TextColumn>,
+    names: &mut Vec<String>,
+) -> Result<(), TextIoError> {
+    for field in layout.fields() {
+        let name = format!("{}{}", prefix, field.name);
+        let count: i64 = field.shape.iter().product();
+        for k in 0..count as usize {
+            let offset = base + field.offset + k * field.dtype.itemsize();
+            let name = if field.shape.is_empty() { name.clone() } else { format!("{}_{}", name, k) };
+            match field.dtype.fields() {
+                Some(nested) => flatten_fields(nested, offset, &format!("{}.", name), columns, names)?,
+                None => {
+                    check_text_dtype(&field.dtype)?;
+                    columns.push(TextColumn { dtype: field.dtype.clone(), offset });
+                    names.push(name);
                 }
-                
-                write!(writer, "{:.18e}", value).map_err(|e| {
-                    TextIoError::FileError(format!("Failed to write value: {}", e))
-                })?;
             }
-            writeln!(writer).map_err(|e| {
-                TextIoError::FileError(format!("Failed to write newline: {}", e))
-            })?;
         }
     }
-    
     Ok(())
 }
 
-/// Write integer data to file
-fn write_int_data<W: Write>(
-    writer: &mut BufWriter<W>,
-    array: &Array,
-    rows: i64,
-    cols: i64,
-    options: &SaveTextOptions,
-) -> Result<(), TextIoError> {
-    unsafe {
-        let data_ptr = array.data_ptr();
-        let itemsize = array.itemsize();
-        
-        for i in 0..rows {
-            for j in 0..cols {
-                let offset = (i * cols + j) as usize * itemsize;
-                let value: i64 = match array.dtype().type_() {
-                    NpyType::Int => {
-                        *(data_ptr.add(offset) as *const i32) as i64
-                    }
-                    NpyType::Long => {
-                        *(data_ptr.add(offset) as *const i64)
+/// Check that elements of `dtype` can be formatted as text
+pub(crate) fn check_text_dtype(dtype: &DType) -> Result<(), TextIoError> {
+    match dtype.type_() {
+        NpyType::Bool
+        | NpyType::Byte
+        | NpyType::UByte
+        | NpyType::Short
+        | NpyType::UShort
+        | NpyType::Int
+        | NpyType::UInt
+        | NpyType::Long
+        | NpyType::ULong
+        | NpyType::LongLong
+        | NpyType::ULongLong
+        | NpyType::Float
+        | NpyType::Double
+        | NpyType::CFloat
+        | NpyType::CDouble
+        | NpyType::String
+        | NpyType::Unicode => Ok(()),
+        _ => Err(TextIoError::Unsupported(format!("Unsupported dtype for text I/O: {}", dtype))),
+    }
+}
+
+/// Read one element, of a dtype accepted by `check_text_dtype`
+pub(crate) fn value_at(dtype: &DType, bytes: &[u8]) -> FormatValue {
+    let signed = |b: &[u8]| match b.len() {
+        1 => b[0] as i8 as i64,
+        2 => i16::from_ne_bytes(b.try_into().unwrap()) as i64,
+        4 => i32::from_ne_bytes(b.try_into().unwrap()) as i64,
+        _ => i64::from_ne_bytes(b.try_into().unwrap()),
+    };
+    let unsigned = |b: &[u8]| match b.len() {
+        1 => b[0] as u64,
+        2 => u16::from_ne_bytes(b.try_into().unwrap()) as u64,
+        4 => u32::from_ne_bytes(b.try_into().unwrap()) as u64,
+        _ => u64::from_ne_bytes(b.try_into().unwrap()),
+    };
+    match dtype.type_() {
+        NpyType::Bool => FormatValue::Bool(bytes[0] != 0),
+        NpyType::Float => FormatValue::Float(f32::from_ne_bytes(bytes.try_into().unwrap()) as f64),
+        NpyType::Double => FormatValue::Float(f64::from_ne_bytes(bytes.try_into().unwrap())),
+        NpyType::CFloat => FormatValue::Complex(
+            f32::from_ne_bytes(bytes[..4].try_into().unwrap()) as f64,
+            f32::from_ne_bytes(bytes[4..].try_into().unwrap()) as f64,
+        ),
+        NpyType::CDouble => FormatValue::Complex(
+            f64::from_ne_bytes(bytes[..8].try_into().unwrap()),
+            f64::from_ne_bytes(bytes[8..].try_into().unwrap()),
+        ),
+        NpyType::String => {
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            FormatValue::Str(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        }
+        NpyType::Unicode => FormatValue::Str(
+            bytes
+                .chunks_exact(4)
+                .map(|c| u32::from_ne_bytes(c.try_into().unwrap()))
+                .take_while(|&c| c != 0)
+                .filter_map(char::from_u32)
+                .collect(),
+        ),
+        NpyType::UByte | NpyType::UShort | NpyType::UInt | NpyType::ULong | NpyType::ULongLong => {
+            FormatValue::UInt(unsigned(bytes))
+        }
+        _ => FormatValue::Int(signed(bytes)),
+    }
+}
+
+/// Formats for one row of a text table
+enum RowFormatter {
+    /// One format per column, joined by the delimiter
+    Columns(Vec<Format>),
+    /// One format for the whole row
+    Row(Format),
+}
+
+impl RowFormatter {
+    fn new(table: &TextTable, options: &SaveTextOptions) -> Result<Self, TextIoError> {
+        let parse = |fmt: &str| Format::parse(fmt).map_err(|e| TextIoError::ParseError(format!("Invalid format: {}", e)));
+        let ncols = table.columns.len();
+        if !options.fmts.is_empty() {
+            if options.fmts.len() != ncols {
+                return Err(TextIoError::ParseError(format!(
+                    "{} formats given for {} columns",
+                    options.fmts.len(),
+                    ncols
+                )));
+            }
+            return options.fmts.iter().map(|f| parse(f)).collect::<Result<_, _>>().map(RowFormatter::Columns);
+        }
+        let format = parse(&options.fmt)?;
+        let complex = table
+            .columns
+            .iter()
+            .filter(|c| matches!(c.dtype.type_(), NpyType::CFloat | NpyType::CDouble))
+            .count();
+        match format.slots() {
+            1 => Ok(RowFormatter::Columns(vec![format; ncols])),
+            n if n == ncols || (complex > 0 && n == ncols + complex) => Ok(RowFormatter::Row(format)),
+            n => Err(TextIoError::ParseError(format!("fmt has wrong number of % formats: {} for {} columns", n, ncols))),
+        }
+    }
+
+    fn format_row(&self, values: &[FormatValue], delimiter: &str, line: &mut String) -> Result<(), TextIoError> {
+        let err = |e: String| TextIoError::ParseError(format!("Failed to format value: {}", e));
+        match self {
+            RowFormatter::Row(format) => line.push_str(&format.render(values).map_err(err)?),
+            RowFormatter::Columns(formats) => {
+                for (j, (format, value)) in formats.iter().zip(values).enumerate() {
+                    if j > 0 {
+                        line.push_str(delimiter);
                     }
-                    _ => return Err(TextIoError::Unsupported("Invalid int type".to_string())),
-                };
-                
-                if j > 0 {
-                    write!(writer, "{}", options.delimiter).map_err(|e| {
-                        TextIoError::FileError(format!("Failed to write delimiter: {}", e))
-                    })?;
+                    line.push_str(&format.render(std::slice::from_ref(value)).map_err(err)?);
                 }
-                
-                write!(writer, "{}", value).map_err(|e| {
-                    TextIoError::FileError(format!("Failed to write value: {}", e))
-                })?;
             }
-            writeln!(writer).map_err(|e| {
-                TextIoError::FileError(format!("Failed to write newline: {}", e))
-            })?;
         }
+        Ok(())
     }
-    
-    Ok(())
 }
 
 /// Load array from text file
@@ -0,0 +1,407 @@
+//! Minimal ZIP container support
+//!
+//! This module implements the subset of the ZIP format used by NumPy's
+//! `.npz` archives: stored and deflated entries, with ZIP64 extensions for
+//! large members. It is shared by the npz and sparse readers/writers.
+
+use super::IoError;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR_SIG: u32 = 0x0605_4b50;
+const ZIP64_END_OF_CENTRAL_DIR_SIG: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR_SIG: u32 = 0x0706_4b50;
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+
+/// Compression method: stored (no compression)
+pub(crate) const METHOD_STORED: u16 = 0;
+/// Compression method: deflate
+pub(crate) const METHOD_DEFLATED: u16 = 8;
+
+/// DOS date for 1980-01-01, matching Python's default `ZipInfo` timestamp
+const DOS_DATE: u16 = 0x0021;
+const DOS_TIME: u16 = 0;
+
+fn io_err(e: std::io::Error) -> IoError {
+    IoError::FileError(e.to_string())
+}
+
+/// A single member of a ZIP archive
+#[derive(Debug, Clone)]
+pub(crate) struct ZipEntry {
+    /// Member name
+    pub name: String,
+    /// Compression method
+    pub method: u16,
+    /// CRC-32 of the uncompressed data
+    pub crc32: u32,
+    /// Compressed size in bytes
+    pub compressed_size: u64,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    /// Offset of the local file header
+    pub header_offset: u64,
+}
+
+/// Central directory of a ZIP archive
+#[derive(Debug, Clone)]
+pub(crate) struct ZipIndex {
+    /// Archive members in central directory order
+    pub entries: Vec<ZipEntry>,
+}
+
+fn read_u16(buf: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([buf[pos], buf[pos + 1]])
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]])
+}
+
+fn read_u64(buf: &[u8], pos: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&buf[pos..pos + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+impl ZipIndex {
+    /// Read the central directory of an archive
+    pub fn read<R: Read + Seek>(reader: &mut R) -> Result<Self, IoError> {
+        let file_len = reader.seek(SeekFrom::End(0)).map_err(io_err)?;
+        // The end record is 22 bytes plus an optional comment of up to 64 KiB
+        let tail_len = file_len.min(22 + 0xFFFF);
+        reader.seek(SeekFrom::Start(file_len - tail_len)).map_err(io_err)?;
+        let mut tail = vec![0u8; tail_len as usize];
+        reader.read_exact(&mut tail).map_err(io_err)?;
+
+        let eocd = (0..tail.len().saturating_sub(21))
+            .rev()
+            .find(|&i| read_u32(&tail, i) == END_OF_CENTRAL_DIR_SIG)
+            .ok_or(IoError::InvalidFormat)?;
+        let mut count = read_u16(&tail, eocd + 10) as u64;
+        let mut cd_size = read_u32(&tail, eocd + 12) as u64;
+        let mut cd_offset = read_u32(&tail, eocd + 16) as u64;
+
+        // ZIP64 end of central directory locator sits right before the end record
+        if eocd >= 20 && read_u32(&tail, eocd - 20) == ZIP64_LOCATOR_SIG {
+            let zip64_offset = read_u64(&tail, eocd - 12);
+            reader.seek(SeekFrom::Start(zip64_offset)).map_err(io_err)?;
+            let mut record = [0u8; 56];
+            reader.read_exact(&mut record).map_err(io_err)?;
+            if read_u32(&record, 0) != ZIP64_END_OF_CENTRAL_DIR_SIG {
+                return Err(IoError::InvalidFormat);
+            }
+            count = read_u64(&record, 32);
+            cd_size = read_u64(&record, 40);
+            cd_offset = read_u64(&record, 48);
+        }
+
+        reader.seek(SeekFrom::Start(cd_offset)).map_err(io_err)?;
+        let mut cd = vec![0u8; cd_size as usize];
+        reader.read_exact(&mut cd).map_err(io_err)?;
+
+        let mut entries = Vec::with_capacity(count as usize);
+        let mut pos = 0usize;
+        for _ in 0..count {
+            if pos + 46 > cd.len() || read_u32(&cd, pos) != CENTRAL_HEADER_SIG {
+                return Err(IoError::InvalidFormat);
+            }
+            let method = read_u16(&cd, pos + 10);
+            let crc32 = read_u32(&cd, pos + 16);
+            let mut compressed_size = read_u32(&cd, pos + 20) as u64;
+            let mut size = read_u32(&cd, pos + 24) as u64;
+            let name_len = read_u16(&cd, pos + 28) as usize;
+            let extra_len = read_u16(&cd, pos + 30) as usize;
+            let comment_len = read_u16(&cd, pos + 32) as usize;
+            let mut header_offset = read_u32(&cd, pos + 42) as u64;
+            let name_start = pos + 46;
+            let extra_start = name_start + name_len;
+            if extra_start + extra_len + comment_len > cd.len() {
+                return Err(IoError::InvalidFormat);
+            }
+            let name = String::from_utf8_lossy(&cd[name_start..extra_start]).into_owned();
+
+            // Resolve ZIP64 extended information, present only for saturated fields
+            let extra = &cd[extra_start..extra_start + extra_len];
+            let mut epos = 0usize;
+            while epos + 4 <= extra.len() {
+                let id = read_u16(extra, epos);
+                let len = read_u16(extra, epos + 2) as usize;
+                let body = &extra[epos + 4..(epos + 4 + len).min(extra.len())];
+                if id == ZIP64_EXTRA_ID {
+                    let mut bpos = 0usize;
+                    if size == 0xFFFF_FFFF && bpos + 8 <= body.len() {
+                        size = read_u64(body, bpos);
+                        bpos += 8;
+                    }
+                    if compressed_size == 0xFFFF_FFFF && bpos + 8 <= body.len() {
+                        compressed_size = read_u64(body, bpos);
+                        bpos += 8;
+                    }
+                    if header_offset == 0xFFFF_FFFF && bpos + 8 <= body.len() {
+                        header_offset = read_u64(body, bpos);
+                    }
+                }
+                epos += 4 + len;
+            }
+
+            entries.push(ZipEntry {
+                name,
+                method,
+                crc32,
+                compressed_size,
+                size,
+                header_offset,
+            });
+            pos = extra_start + extra_len + comment_len;
+        }
+
+        Ok(ZipIndex { entries })
+    }
+
+    /// Find an entry by name
+    pub fn find(&self, name: &str) -> Option<&ZipEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+}
+
+impl ZipEntry {
+    /// Open a reader over the uncompressed contents of this entry
+    ///
+    /// The reader borrows the archive, so only one member can be streamed at a time.
+    pub fn open<'a, R: Read + Seek>(&self, reader: &'a mut R) -> Result<Box<dyn Read + 'a>, IoError> {
+        reader.seek(SeekFrom::Start(self.header_offset)).map_err(io_err)?;
+        let mut header = [0u8; 30];
+        reader.read_exact(&mut header).map_err(io_err)?;
+        if read_u32(&header, 0) != LOCAL_HEADER_SIG {
+            return Err(IoError::InvalidFormat);
+        }
+        let skip = read_u16(&header, 26) as i64 + read_u16(&header, 28) as i64;
+        reader.seek(SeekFrom::Current(skip)).map_err(io_err)?;
+
+        let raw = reader.take(self.compressed_size);
+        match self.method {
+            METHOD_STORED => Ok(Box::new(raw)),
+            METHOD_DEFLATED => Ok(Box::new(DeflateDecoder::new(raw))),
+            _ => Err(IoError::InvalidFormat),
+        }
+    }
+
+    /// Open a reader over this entry that can verify the checksum when done
+    pub fn open_checked<'a, R: Read + Seek>(&self, reader: &'a mut R) -> Result<CheckedReader<'a>, IoError> {
+        Ok(CheckedReader {
+            inner: self.open(reader)?,
+            hasher: crc32fast::Hasher::new(),
+            len: 0,
+            crc32: self.crc32,
+            size: self.size,
+        })
+    }
+
+    /// Read and decompress the whole entry, verifying its checksum
+    pub fn read_to_vec<R: Read + Seek>(&self, reader: &mut R) -> Result<Vec<u8>, IoError> {
+        let mut data = Vec::with_capacity(self.size as usize);
+        self.open(reader)?.read_to_end(&mut data).map_err(io_err)?;
+        if data.len() as u64 != self.size || crc32fast::hash(&data) != self.crc32 {
+            return Err(IoError::InvalidFormat);
+        }
+        Ok(data)
+    }
+}
+
+/// Reader over an entry's contents that verifies its size and CRC-32
+pub(crate) struct CheckedReader<'a> {
+    inner: Box<dyn Read + 'a>,
+    hasher: crc32fast::Hasher,
+    len: u64,
+    crc32: u32,
+    size: u64,
+}
+
+impl Read for CheckedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len += n as u64;
+        Ok(n)
+    }
+}
+
+impl CheckedReader<'_> {
+    /// Consume any unread data and check it against the central directory
+    pub fn finish(mut self) -> Result<(), IoError> {
+        std::io::copy(&mut self, &mut std::io::sink()).map_err(io_err)?;
+        if self.len != self.size || self.hasher.finalize() != self.crc32 {
+            return Err(IoError::InvalidFormat);
+        }
+        Ok(())
+    }
+}
+
+/// Streaming ZIP archive writer
+pub(crate) struct ZipWriter<W: Write> {
+    inner: W,
+    offset: u64,
+    entries: Vec<ZipEntry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    /// Create a writer that appends members to `inner`
+    pub fn new(inner: W) -> Self {
+        ZipWriter {
+            inner,
+            offset: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), IoError> {
+        self.inner.write_all(bytes).map_err(io_err)?;
+        self.offset += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Add a member holding `data`, deflating it if `compress` is set
+    pub fn add_entry(&mut self, name: &str, data: &[u8], compress: bool) -> Result<(), IoError> {
+        let (method, payload) = if compress {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).map_err(io_err)?;
+            (METHOD_DEFLATED, encoder.finish().map_err(io_err)?)
+        } else {
+            (METHOD_STORED, data.to_vec())
+        };
+
+        let entry = ZipEntry {
+            name: name.to_string(),
+            method,
+            crc32: crc32fast::hash(data),
+            compressed_size: payload.len() as u64,
+            size: data.len() as u64,
+            header_offset: self.offset,
+        };
+
+        let zip64 = entry.size >= 0xFFFF_FFFF || entry.compressed_size >= 0xFFFF_FFFF;
+        let mut header = Vec::with_capacity(30 + name.len() + 20);
+        header.extend_from_slice(&LOCAL_HEADER_SIG.to_le_bytes());
+        header.extend_from_slice(&(if zip64 { 45u16 } else { 20u16 }).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // flags
+        header.extend_from_slice(&method.to_le_bytes());
+        header.extend_from_slice(&DOS_TIME.to_le_bytes());
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&entry.crc32.to_le_bytes());
+        if zip64 {
+            header.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+            header.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+        } else {
+            header.extend_from_slice(&(entry.compressed_size as u32).to_le_bytes());
+            header.extend_from_slice(&(entry.size as u32).to_le_bytes());
+        }
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&(if zip64 { 20u16 } else { 0u16 }).to_le_bytes());
+        header.extend_from_slice(name.as_bytes());
+        if zip64 {
+            header.extend_from_slice(&ZIP64_EXTRA_ID.to_le_bytes());
+            header.extend_from_slice(&16u16.to_le_bytes());
+            header.extend_from_slice(&entry.size.to_le_bytes());
+            header.extend_from_slice(&entry.compressed_size.to_le_bytes());
+        }
+
+        self.write_bytes(&header)?;
+        self.write_bytes(&payload)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Write the central directory and return the underlying writer
+    pub fn finish(mut self) -> Result<W, IoError> {
+        let cd_offset = self.offset;
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            let big_size = entry.size >= 0xFFFF_FFFF || entry.compressed_size >= 0xFFFF_FFFF;
+            let big_offset = entry.header_offset >= 0xFFFF_FFFF;
+            let mut extra = Vec::new();
+            if big_size {
+                extra.extend_from_slice(&entry.size.to_le_bytes());
+                extra.extend_from_slice(&entry.compressed_size.to_le_bytes());
+            }
+            if big_offset {
+                extra.extend_from_slice(&entry.header_offset.to_le_bytes());
+            }
+            let zip64 = !extra.is_empty();
+
+            let mut record = Vec::with_capacity(46 + entry.name.len() + 28);
+            record.extend_from_slice(&CENTRAL_HEADER_SIG.to_le_bytes());
+            let version: u16 = if zip64 { 45 } else { 20 };
+            record.extend_from_slice(&version.to_le_bytes()); // version made by
+            record.extend_from_slice(&version.to_le_bytes()); // version needed
+            record.extend_from_slice(&0u16.to_le_bytes());
+            record.extend_from_slice(&entry.method.to_le_bytes());
+            record.extend_from_slice(&DOS_TIME.to_le_bytes());
+            record.extend_from_slice(&DOS_DATE.to_le_bytes());
+            record.extend_from_slice(&entry.crc32.to_le_bytes());
+            if big_size {
+                record.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+                record.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes());
+            } else {
+                record.extend_from_slice(&(entry.compressed_size as u32).to_le_bytes());
+                record.extend_from_slice(&(entry.size as u32).to_le_bytes());
+            }
+            record.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            let extra_len = if zip64 { extra.len() as u16 + 4 } else { 0 };
+            record.extend_from_slice(&extra_len.to_le_bytes());
+            record.extend_from_slice(&0u16.to_le_bytes()); // comment length
+            record.extend_from_slice(&0u16.to_le_bytes()); // disk number
+            record.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+            record.extend_from_slice(&(0o600u32 << 16).to_le_bytes()); // external attributes
+            let offset = if big_offset { 0xFFFF_FFFF } else { entry.header_offset as u32 };
+            record.extend_from_slice(&offset.to_le_bytes());
+            record.extend_from_slice(entry.name.as_bytes());
+            if zip64 {
+                record.extend_from_slice(&ZIP64_EXTRA_ID.to_le_bytes());
+                record.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+                record.extend_from_slice(&extra);
+            }
+            self.write_bytes(&record)?;
+        }
+        let cd_size = self.offset - cd_offset;
+        let count = entries.len() as u64;
+
+        if count >= 0xFFFF || cd_size >= 0xFFFF_FFFF || cd_offset >= 0xFFFF_FFFF {
+            let zip64_offset = self.offset;
+            let mut record = Vec::with_capacity(56 + 20);
+            record.extend_from_slice(&ZIP64_END_OF_CENTRAL_DIR_SIG.to_le_bytes());
+            record.extend_from_slice(&44u64.to_le_bytes());
+            record.extend_from_slice(&45u16.to_le_bytes());
+            record.extend_from_slice(&45u16.to_le_bytes());
+            record.extend_from_slice(&0u32.to_le_bytes());
+            record.extend_from_slice(&0u32.to_le_bytes());
+            record.extend_from_slice(&count.to_le_bytes());
+            record.extend_from_slice(&count.to_le_bytes());
+            record.extend_from_slice(&cd_size.to_le_bytes());
+            record.extend_from_slice(&cd_offset.to_le_bytes());
+            record.extend_from_slice(&ZIP64_LOCATOR_SIG.to_le_bytes());
+            record.extend_from_slice(&0u32.to_le_bytes());
+            record.extend_from_slice(&zip64_offset.to_le_bytes());
+            record.extend_from_slice(&1u32.to_le_bytes());
+            self.write_bytes(&record)?;
+        }
+
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&END_OF_CENTRAL_DIR_SIG.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        end.extend_from_slice(&(count.min(0xFFFF) as u16).to_le_bytes());
+        end.extend_from_slice(&(count.min(0xFFFF) as u16).to_le_bytes());
+        end.extend_from_slice(&(cd_size.min(0xFFFF_FFFF) as u32).to_le_bytes());
+        end.extend_from_slice(&(cd_offset.min(0xFFFF_FFFF) as u32).to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        self.write_bytes(&end)?;
+
+        self.inner.flush().map_err(io_err)?;
+        Ok(self.inner)
+    }
+}
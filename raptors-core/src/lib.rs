@@ -8,6 +8,8 @@
 #![allow(non_upper_case_globals)]
 
 pub mod array;
+pub mod array_api;
+pub mod arrow;
 pub mod broadcasting;
 pub mod buffer;
 pub mod concatenation;
@@ -16,6 +18,7 @@ pub mod datetime;
 pub mod dlpack;
 pub mod einsum;
 pub mod ffi;
+pub mod fft;
 pub mod indexing;
 pub mod io;
 pub mod iterators;
@@ -28,6 +31,8 @@ pub mod masked;
 pub mod memmap;
 pub mod shape;
 pub mod sorting;
+pub mod sparse;
+pub mod store;
 pub mod string;
 pub mod structured;
 pub mod traits;
@@ -35,8 +40,10 @@ pub mod types;
 pub mod ufunc;
 pub mod utils;
 pub mod performance;
+pub mod polynomial;
+pub mod random;
 
 /// Re-export main types for convenience
-pub use array::{Array, empty, ones, zeros, ArrayBuilder, MemoryOrder, ArrayIterOps};
+pub use array::{Array, empty, ones, zeros, full, ArrayBuilder, MemoryOrder, ArrayIterOps};
 pub use types::DType;
 pub use traits::{ArrayLike, Indexable, Broadcastable, Reducible};
@@ -0,0 +1,326 @@
+//! Eigenvalues
+//!
+//! This module provides `eigvals` for general real square matrices,
+//! equivalent to `numpy.linalg.eigvals`. The matrix is balanced, reduced
+//! to upper Hessenberg form by stabilized elementary similarity transforms,
+//! and its eigenvalues found with the Francis double-shift QR algorithm
+//! (EISPACK's `balanc`, `elmhes` and `hqr`).
+
+use crate::array::Array;
+use crate::types::{Complex128, DType, NpyType};
+
+use super::LinalgError;
+
+/// Iterations allowed per eigenvalue before giving up
+const MAX_ITERATIONS: usize = 60;
+
+/// Square matrix with 1-based indexing, matching the EISPACK formulation
+struct Matrix {
+    n: usize,
+    data: Vec<f64>,
+}
+
+impl Matrix {
+    fn from_row_major(values: &[f64], n: usize) -> Self {
+        let mut data = vec![0.0; (n + 1) * (n + 1)];
+        for i in 0..n {
+            for j in 0..n {
+                data[(i + 1) * (n + 1) + j + 1] = values[i * n + j];
+            }
+        }
+        Matrix { n, data }
+    }
+}
+
+impl std::ops::Index<(usize, usize)> for Matrix {
+    type Output = f64;
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        &self.data[i * (self.n + 1) + j]
+    }
+}
+
+impl std::ops::IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        &mut self.data[i * (self.n + 1) + j]
+    }
+}
+
+/// Scale rows and columns by powers of two so their norms are comparable
+fn balance(a: &mut Matrix) {
+    const RADIX: f64 = 2.0;
+    let n = a.n;
+    let mut done = false;
+    while !done {
+        done = true;
+        for i in 1..=n {
+            let (mut c, mut r) = (0.0, 0.0);
+            for j in 1..=n {
+                if j != i {
+                    c += a[(j, i)].abs();
+                    r += a[(i, j)].abs();
+                }
+            }
+            if c == 0.0 || r == 0.0 {
+                continue;
+            }
+            let s = c + r;
+            let mut f = 1.0;
+            while c < r / RADIX {
+                f *= RADIX;
+                c *= RADIX * RADIX;
+            }
+            while c > r * RADIX {
+                f /= RADIX;
+                c /= RADIX * RADIX;
+            }
+            if (c + r) / f < 0.95 * s {
+                done = false;
+                for j in 1..=n {
+                    a[(i, j)] /= f;
+                    a[(j, i)] *= f;
+                }
+            }
+        }
+    }
+}
+
+/// Reduce to upper Hessenberg form by Gaussian elimination with pivoting
+fn hessenberg(a: &mut Matrix) {
+    let n = a.n;
+    for m in 2..n {
+        let mut x: f64 = 0.0;
+        let mut pivot = m;
+        for j in m..=n {
+            if a[(j, m - 1)].abs() > x.abs() {
+                x = a[(j, m - 1)];
+                pivot = j;
+            }
+        }
+        if pivot != m {
+            for j in m - 1..=n {
+                let tmp = a[(pivot, j)];
+                a[(pivot, j)] = a[(m, j)];
+                a[(m, j)] = tmp;
+            }
+            for j in 1..=n {
+                let tmp = a[(j, pivot)];
+                a[(j, pivot)] = a[(j, m)];
+                a[(j, m)] = tmp;
+            }
+        }
+        if x != 0.0 {
+            for i in m + 1..=n {
+                let mut y = a[(i, m - 1)];
+                if y != 0.0 {
+                    y /= x;
+                    a[(i, m - 1)] = y;
+                    for j in m..=n {
+                        a[(i, j)] -= y * a[(m, j)];
+                    }
+                    for j in 1..=n {
+                        a[(j, m)] += y * a[(j, i)];
+                    }
+                }
+            }
+        }
+    }
+    // Discard the elimination multipliers left below the subdiagonal
+    for i in 3..=n {
+        for j in 1..i - 1 {
+            a[(i, j)] = 0.0;
+        }
+    }
+}
+
+/// Eigenvalues of an upper Hessenberg matrix by the Francis QR algorithm
+fn hqr(a: &mut Matrix) -> Result<Vec<Complex128>, LinalgError> {
+    let n = a.n;
+    let mut wr = vec![0.0; n + 1];
+    let mut wi = vec![0.0; n + 1];
+
+    let mut anorm = 0.0;
+    for i in 1..=n {
+        for j in (i - 1).max(1)..=n {
+            anorm += a[(i, j)].abs();
+        }
+    }
+
+    let mut nn = n;
+    let mut t = 0.0;
+    while nn >= 1 {
+        let mut its = 0;
+        loop {
+            // Look for a single small subdiagonal element
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = a[(l - 1, l - 1)].abs() + a[(l, l)].abs();
+                if s == 0.0 {
+                    s = anorm;
+                }
+                if a[(l, l - 1)].abs() + s == s {
+                    a[(l, l - 1)] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+            let mut x = a[(nn, nn)];
+            if l == nn {
+                // One root found
+                wr[nn] = x + t;
+                wi[nn] = 0.0;
+                nn -= 1;
+            } else {
+                let mut y = a[(nn - 1, nn - 1)];
+                let mut w = a[(nn, nn - 1)] * a[(nn - 1, nn)];
+                if l == nn - 1 {
+                    // Two roots found
+                    let p = 0.5 * (y - x);
+                    let q = p * p + w;
+                    let mut z = q.abs().sqrt();
+                    x += t;
+                    if q >= 0.0 {
+                        z = p + z.copysign(p);
+                        wr[nn - 1] = x + z;
+                        wr[nn] = if z != 0.0 { x - w / z } else { x + z };
+                        wi[nn - 1] = 0.0;
+                        wi[nn] = 0.0;
+                    } else {
+                        wr[nn - 1] = x + p;
+                        wr[nn] = x + p;
+                        wi[nn - 1] = -z;
+                        wi[nn] = z;
+                    }
+                    nn -= 2;
+                } else {
+                    if its == MAX_ITERATIONS {
+                        return Err(LinalgError::ConvergenceFailure);
+                    }
+                    if its == 10 || its == 20 {
+                        // Exceptional shift
+                        t += x;
+                        for i in 1..=nn {
+                            a[(i, i)] -= x;
+                        }
+                        let s = a[(nn, nn - 1)].abs() + a[(nn - 1, nn - 2)].abs();
+                        x = 0.75 * s;
+                        y = x;
+                        w = -0.4375 * s * s;
+                    }
+                    its += 1;
+
+                    // Form the shift and look for two consecutive small subdiagonal elements
+                    let mut m = nn - 2;
+                    let (mut p, mut q, mut r);
+                    loop {
+                        let z = a[(m, m)];
+                        r = x - z;
+                        let s = y - z;
+                        p = (r * s - w) / a[(m + 1, m)] + a[(m, m + 1)];
+                        q = a[(m + 1, m + 1)] - z - r - s;
+                        r = a[(m + 2, m + 1)];
+                        let s = p.abs() + q.abs() + r.abs();
+                        p /= s;
+                        q /= s;
+                        r /= s;
+                        if m == l {
+                            break;
+                        }
+                        let u = a[(m, m - 1)].abs() * (q.abs() + r.abs());
+                        let v = p.abs() * (a[(m - 1, m - 1)].abs() + z.abs() + a[(m + 1, m + 1)].abs());
+                        if u + v == v {
+                            break;
+                        }
+                        m -= 1;
+                    }
+                    for i in m + 2..=nn {
+                        a[(i, i - 2)] = 0.0;
+                        if i != m + 2 {
+                            a[(i, i - 3)] = 0.0;
+                        }
+                    }
+
+                    // Double QR step on rows l..nn and columns m..nn
+                    for k in m..nn {
+                        if k != m {
+                            p = a[(k, k - 1)];
+                            q = a[(k + 1, k - 1)];
+                            r = if k != nn - 1 { a[(k + 2, k - 1)] } else { 0.0 };
+                            x = p.abs() + q.abs() + r.abs();
+                            if x != 0.0 {
+                                p /= x;
+                                q /= x;
+                                r /= x;
+                            }
+                        }
+                        let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                        if s == 0.0 {
+                            continue;
+                        }
+                        if k == m {
+                            if l != m {
+                                a[(k, k - 1)] = -a[(k, k - 1)];
+                            }
+                        } else {
+                            a[(k, k - 1)] = -s * x;
+                        }
+                        p += s;
+                        x = p / s;
+                        y = q / s;
+                        let z = r / s;
+                        q /= p;
+                        r /= p;
+                        for j in k..=nn {
+                            let mut p = a[(k, j)] + q * a[(k + 1, j)];
+                            if k != nn - 1 {
+                                p += r * a[(k + 2, j)];
+                                a[(k + 2, j)] -= p * z;
+                            }
+                            a[(k + 1, j)] -= p * y;
+                            a[(k, j)] -= p * x;
+                        }
+                        let mmin = nn.min(k + 3);
+                        for i in l..=mmin {
+                            let mut p = x * a[(i, k)] + y * a[(i, k + 1)];
+                            if k != nn - 1 {
+                                p += z * a[(i, k + 2)];
+                                a[(i, k + 2)] -= p * r;
+                            }
+                            a[(i, k + 1)] -= p * q;
+                            a[(i, k)] -= p;
+                        }
+                    }
+                }
+            }
+            if nn < 2 || l + 1 >= nn {
+                break;
+            }
+        }
+    }
+
+    Ok((1..=n).map(|i| Complex128::new(wr[i], wi[i])).collect())
+}
+
+/// Eigenvalues of a real row-major `n x n` matrix
+pub(crate) fn eigvals_of(values: &[f64], n: usize) -> Result<Vec<Complex128>, LinalgError> {
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(LinalgError::ConvergenceFailure);
+    }
+    let mut a = Matrix::from_row_major(values, n);
+    balance(&mut a);
+    hessenberg(&mut a);
+    hqr(&mut a)
+}
+
+/// Compute the eigenvalues of a general square matrix
+///
+/// Returns a `CDouble` array of shape `(n,)`. Complex eigenvalues appear
+/// in conjugate pairs.
+pub fn eigvals(a: &Array) -> Result<Array, LinalgError> {
+    if a.ndim() != 2 || a.shape()[0] != a.shape()[1] {
+        return Err(LinalgError::InvalidDimension);
+    }
+    let n = a.shape()[0] as usize;
+    let values = crate::utils::to_f64_vec(a).ok_or(LinalgError::UnsupportedDtype)?;
+    let eigenvalues = eigvals_of(&values, n)?;
+    Ok(Array::from_slice(&eigenvalues, vec![n as i64], DType::new(NpyType::CDouble))?)
+}
@@ -0,0 +1,155 @@
+//! Least squares
+//!
+//! This module provides `lstsq`, equivalent to `numpy.linalg.lstsq`, built
+//! on a one-sided Jacobi singular value decomposition.
+
+use crate::array::Array;
+use crate::types::{DType, NpyType};
+
+use super::LinalgError;
+
+/// Maximum number of Jacobi sweeps before giving up
+const MAX_SWEEPS: usize = 100;
+
+/// Thin singular value decomposition of an `m x n` matrix
+///
+/// Columns are stored separately: `u[j]` is the j-th left singular vector
+/// (length `m`), `v[j]` the j-th right singular vector (length `n`).
+pub(crate) struct Svd {
+    pub(crate) u: Vec<Vec<f64>>,
+    pub(crate) s: Vec<f64>,
+    pub(crate) v: Vec<Vec<f64>>,
+}
+
+/// One-sided Jacobi SVD of a row-major `m x n` matrix
+///
+/// Singular values are returned in descending order.
+pub(crate) fn svd(a: &[f64], m: usize, n: usize) -> Result<Svd, LinalgError> {
+    let mut u: Vec<Vec<f64>> = (0..n).map(|j| (0..m).map(|i| a[i * n + j]).collect()).collect();
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|j| (0..n).map(|i| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    let mut converged = false;
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let alpha: f64 = u[p].iter().map(|x| x * x).sum();
+                let beta: f64 = u[q].iter().map(|x| x * x).sum();
+                let gamma: f64 = u[p].iter().zip(&u[q]).map(|(x, y)| x * y).sum();
+                if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                for cols in [&mut u, &mut v] {
+                    let (left, right) = cols.split_at_mut(q);
+                    for (x, y) in left[p].iter_mut().zip(right[0].iter_mut()) {
+                        let (xp, xq) = (*x, *y);
+                        *x = c * xp - s * xq;
+                        *y = s * xp + c * xq;
+                    }
+                }
+            }
+        }
+        if !rotated {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(LinalgError::ConvergenceFailure);
+    }
+
+    let mut s: Vec<f64> = u.iter().map(|col| col.iter().map(|x| x * x).sum::<f64>().sqrt()).collect();
+    for (col, &sigma) in u.iter_mut().zip(&s) {
+        if sigma > 0.0 {
+            col.iter_mut().for_each(|x| *x /= sigma);
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| s[j].total_cmp(&s[i]));
+    let u = order.iter().map(|&j| u[j].clone()).collect();
+    let v = order.iter().map(|&j| v[j].clone()).collect();
+    s = order.iter().map(|&j| s[j]).collect();
+    Ok(Svd { u, s, v })
+}
+
+/// Result of a least-squares solve
+#[derive(Debug)]
+pub struct LstsqResult {
+    /// Least-squares solution, shape `(n,)` or `(n, k)`
+    pub solution: Array,
+    /// Squared residual norms, one per right-hand side; empty unless the
+    /// system is overdetermined and of full rank
+    pub residuals: Array,
+    /// Effective rank of the coefficient matrix
+    pub rank: usize,
+    /// Singular values of the coefficient matrix in descending order
+    pub singular_values: Array,
+}
+
+/// Solve `a @ x = b` in the least-squares sense
+///
+/// `a` has shape `(m, n)`; `b` has shape `(m,)` or `(m, k)`. Singular
+/// values below `rcond * max(singular values)` are treated as zero; the
+/// default `rcond` is machine epsilon times `max(m, n)`.
+pub fn lstsq(a: &Array, b: &Array, rcond: Option<f64>) -> Result<LstsqResult, LinalgError> {
+    if a.ndim() != 2 || b.ndim() == 0 || b.ndim() > 2 {
+        return Err(LinalgError::InvalidDimension);
+    }
+    let (m, n) = (a.shape()[0] as usize, a.shape()[1] as usize);
+    if b.shape()[0] as usize != m {
+        return Err(LinalgError::ShapeMismatch);
+    }
+    let k = if b.ndim() == 2 { b.shape()[1] as usize } else { 1 };
+    let a_values = crate::utils::to_f64_vec(a).ok_or(LinalgError::UnsupportedDtype)?;
+    let b_values = crate::utils::to_f64_vec(b).ok_or(LinalgError::UnsupportedDtype)?;
+
+    let svd = svd(&a_values, m, n)?;
+    let rcond = rcond.unwrap_or(f64::EPSILON * m.max(n) as f64);
+    let cutoff = rcond * svd.s.first().copied().unwrap_or(0.0);
+    let rank = svd.s.iter().filter(|&&sigma| sigma > cutoff && sigma > 0.0).count();
+
+    // x = V diag(1/s) U^T b over the retained singular values
+    let mut x = vec![0.0; n * k];
+    for j in 0..rank {
+        for col in 0..k {
+            let dot: f64 = (0..m).map(|i| svd.u[j][i] * b_values[i * k + col]).sum();
+            let coef = dot / svd.s[j];
+            for (row, &vj) in svd.v[j].iter().enumerate() {
+                x[row * k + col] += coef * vj;
+            }
+        }
+    }
+
+    let residuals = if rank == n && m > n {
+        (0..k)
+            .map(|col| {
+                (0..m)
+                    .map(|i| {
+                        let fitted: f64 = (0..n).map(|j| a_values[i * n + j] * x[j * k + col]).sum();
+                        let r = b_values[i * k + col] - fitted;
+                        r * r
+                    })
+                    .sum::<f64>()
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let dtype = DType::new(NpyType::Double);
+    let solution_shape = if b.ndim() == 2 { vec![n as i64, k as i64] } else { vec![n as i64] };
+    Ok(LstsqResult {
+        solution: Array::from_slice(&x, solution_shape, dtype.clone())?,
+        residuals: Array::from_slice(&residuals, vec![residuals.len() as i64], dtype.clone())?,
+        rank,
+        singular_values: Array::from_slice(&svd.s[..m.min(n)], vec![m.min(n) as i64], dtype)?,
+    })
+}
@@ -13,6 +13,10 @@ pub enum LinalgError {
     ShapeMismatch,
     /// Invalid dimension
     InvalidDimension,
+    /// Iterative algorithm did not converge
+    ConvergenceFailure,
+    /// Dtype is not supported by this operation
+    UnsupportedDtype,
 }
 
 impl std::fmt::Display for LinalgError {
@@ -21,6 +25,8 @@ impl std::fmt::Display for LinalgError {
             LinalgError::ArrayError(e) => write!(f, "Array error: {}", e),
             LinalgError::ShapeMismatch => write!(f, "Shape mismatch"),
             LinalgError::InvalidDimension => write!(f, "Invalid dimension"),
+            LinalgError::ConvergenceFailure => write!(f, "Algorithm did not converge"),
+            LinalgError::UnsupportedDtype => write!(f, "Unsupported dtype"),
         }
     }
 }
@@ -3,7 +3,10 @@
 //! This module provides linear algebra operations,
 //! equivalent to NumPy's linear algebra functionality
 
+mod eigen;
+mod lstsq;
 mod matrix;
 
+pub use eigen::*;
+pub use lstsq::*;
 pub use matrix::*;
-
@@ -48,12 +48,16 @@ impl MaskedArray {
     /// * `data` - Data array
     /// * `mask` - Boolean mask array (must match data shape)
     ///
+    /// For structured data the mask may instead be a structured array with
+    /// one boolean field per data field, masking individual fields; an
+    /// element then counts as masked when any of its fields is.
+    ///
     /// # Returns
     /// * `Ok(MaskedArray)` if successful
     /// * `Err(MaskedError)` if mask shape doesn't match or mask is not boolean
     pub fn new(data: Array, mask: Array) -> Result<Self, MaskedError> {
         // Validate mask is boolean
-        if mask.dtype().type_() != crate::types::NpyType::Bool {
+        if !is_valid_mask_dtype(data.dtype(), mask.dtype()) {
             return Err(MaskedError::InvalidMask);
         }
         
@@ -101,27 +105,20 @@ impl MaskedArray {
             return Err(MaskedError::ArrayError(ArrayError::InvalidShape));
         }
         
-        unsafe {
-            let mask_ptr = self.mask.data_ptr() as *const bool;
-            Ok(*mask_ptr.add(index))
-        }
+        Ok(self.element_mask(index).iter().any(|&b| b != 0))
+    }
+    
+    /// Mask bytes of element `index`: one byte, or one per field
+    fn element_mask(&self, index: usize) -> &[u8] {
+        let itemsize = self.mask.itemsize();
+        unsafe { std::slice::from_raw_parts(self.mask.data_ptr().add(index * itemsize), itemsize) }
     }
     
     /// Count number of masked elements
     pub fn count_masked(&self) -> usize {
-        let size = self.size();
-        let mut count = 0;
-        
-        unsafe {
-            let mask_ptr = self.mask.data_ptr() as *const bool;
-            for i in 0..size {
-                if *mask_ptr.add(i) {
-                    count += 1;
-                }
-            }
-        }
-        
-        count
+        (0..self.size())
+            .filter(|&i| self.element_mask(i).iter().any(|&b| b != 0))
+            .count()
     }
     
     /// Count number of valid (unmasked) elements
@@ -130,3 +127,23 @@ impl MaskedArray {
     }
 }
 
+/// Whether `mask` can mask data of dtype `data`
+///
+/// Either a plain boolean mask, or for structured data a structured mask
+/// with a boolean field of the same name for every data field.
+fn is_valid_mask_dtype(data: &crate::types::DType, mask: &crate::types::DType) -> bool {
+    use crate::types::NpyType;
+    if mask.type_() == NpyType::Bool {
+        return true;
+    }
+    match (data.fields(), mask.fields()) {
+        (Some(data), Some(mask)) => {
+            data.num_fields() == mask.num_fields()
+                && data.fields().iter().zip(mask.fields()).all(|(d, m)| {
+                    d.name == m.name && m.dtype.type_() == NpyType::Bool && m.shape.is_empty() && m.dtype.itemsize() == 1
+                })
+                && mask.itemsize() == mask.num_fields()
+        }
+        _ => false,
+    }
+}
@@ -1,10 +1,12 @@
 //! Memory-mapped array I/O operations
 
-use crate::array::Array;
-use crate::types::DType;
+use crate::array::{Array, ArrayError};
+use crate::io::NpyHeader;
+use crate::types::{DType, NpyType};
+use std::io::{BufReader, Seek, Write};
 use std::path::Path;
 
-use super::{MemMapArray, MemMapError};
+use super::{open_file, MapMode, MemMapArray, MemMapError};
 
 /// Save array to memory-mapped file
 ///
@@ -53,3 +55,53 @@ pub fn load_memmap(file_path: &Path, dtype: DType, shape: Vec<i64>) -> Result<Me
     MemMapArray::new(file_path, dtype, shape, super::MapMode::ReadOnly)
 }
 
+/// Open a `.npy` file as a memory-mapped array
+///
+/// Equivalent to `numpy.lib.format.open_memmap`. With `MapMode::Create`
+/// (`'w+'`) a new file is written with an NPY header for `dtype` (default
+/// float64), `shape` and `fortran_order`. The other modes read the header
+/// of an existing file and ignore `dtype`, `shape` and `fortran_order`.
+/// In every case the data section is mapped at its offset after the header.
+///
+/// # Arguments
+/// * `file_path` - Path to file
+/// * `mode` - Mapping mode
+/// * `dtype` - Data type of a new file
+/// * `shape` - Shape of a new file (required with `MapMode::Create`)
+/// * `fortran_order` - Whether a new file stores its data in Fortran order
+///
+/// # Returns
+/// * `Ok(MemMapArray)` - Memory-mapped array
+/// * `Err(MemMapError)` if the file cannot be opened or mapped
+pub fn open_memmap(
+    file_path: impl AsRef<Path>,
+    mode: MapMode,
+    dtype: Option<DType>,
+    shape: Option<Vec<i64>>,
+    fortran_order: bool,
+) -> Result<MemMapArray, MemMapError> {
+    let file_path = file_path.as_ref();
+    let (file, header, offset) = if mode == MapMode::Create {
+        let shape = shape.ok_or_else(|| {
+            ArrayError::InvalidValue("shape must be given when creating a memory-mapped file".to_string())
+        })?;
+        let dtype = dtype.unwrap_or_else(|| DType::new(NpyType::Double));
+        let header = NpyHeader::new(dtype, shape, fortran_order);
+        let bytes = header.to_bytes()?;
+        let offset = bytes.len() as u64;
+        let mut file = open_file(file_path, mode, offset + header.data_len() as u64)?;
+        file.write_all(&bytes).map_err(|e| MemMapError::IoError(e.to_string()))?;
+        (file, header, offset)
+    } else {
+        let file = open_file(file_path, mode, 0)?;
+        let mut reader = BufReader::new(&file);
+        let header = NpyHeader::read_from(&mut reader)?;
+        let offset = reader.stream_position().map_err(|e| MemMapError::IoError(e.to_string()))?;
+        (file, header, offset)
+    };
+    if header.needs_byteswap() {
+        return Err(MemMapError::IoError("cannot map data stored in non-native byte order".to_string()));
+    }
+    let mmap = MemMapArray::map(file, file_path, header.dtype, header.shape, header.fortran_order, offset, mode)?;
+    Ok(mmap.with_npy_header())
+}
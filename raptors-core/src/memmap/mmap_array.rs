@@ -1,10 +1,12 @@
 //! Memory-mapped array structure
 
 use crate::array::{Array, ArrayError, ArrayFlags};
+use crate::io::NpyHeader;
 use crate::types::DType;
 use std::path::Path;
 use memmap2::{Mmap, MmapMut, MmapOptions};
 use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
 
 /// Memory mapping mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,6 +17,49 @@ pub enum MapMode {
     ReadWrite,
     /// Copy-on-write mapping
     CopyOnWrite,
+    /// Create or overwrite the file, then map it read-write
+    Create,
+}
+
+impl std::str::FromStr for MapMode {
+    type Err = MemMapError;
+
+    /// Parse a NumPy mode string: `r`, `r+`, `c` or `w+`, or the long
+    /// forms `readonly`, `readwrite`, `copyonwrite` and `write`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "r" | "readonly" => Ok(MapMode::ReadOnly),
+            "r+" | "readwrite" => Ok(MapMode::ReadWrite),
+            "c" | "copyonwrite" => Ok(MapMode::CopyOnWrite),
+            "w+" | "write" => Ok(MapMode::Create),
+            _ => Err(MemMapError::InvalidMode(s.to_string())),
+        }
+    }
+}
+
+/// Expected access pattern of a mapping, passed to `MemMapArray::advise`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemMapAdvice {
+    /// No special treatment
+    Normal,
+    /// Pages will be accessed in order; read ahead aggressively
+    Sequential,
+    /// Pages will be accessed in random order; do not read ahead
+    Random,
+    /// Pages will be needed soon; start reading them in
+    WillNeed,
+}
+
+#[cfg(unix)]
+impl From<MemMapAdvice> for memmap2::Advice {
+    fn from(advice: MemMapAdvice) -> Self {
+        match advice {
+            MemMapAdvice::Normal => memmap2::Advice::Normal,
+            MemMapAdvice::Sequential => memmap2::Advice::Sequential,
+            MemMapAdvice::Random => memmap2::Advice::Random,
+            MemMapAdvice::WillNeed => memmap2::Advice::WillNeed,
+        }
+    }
 }
 
 /// Memory-mapped array error
@@ -28,6 +73,8 @@ pub enum MemMapError {
     MappingFailed(String),
     /// File not found
     FileNotFound,
+    /// Unknown mode string
+    InvalidMode(String),
 }
 
 impl std::fmt::Display for MemMapError {
@@ -37,6 +84,7 @@ impl std::fmt::Display for MemMapError {
             MemMapError::IoError(msg) => write!(f, "I/O error: {}", msg),
             MemMapError::MappingFailed(msg) => write!(f, "Memory mapping failed: {}", msg),
             MemMapError::FileNotFound => write!(f, "File not found"),
+            MemMapError::InvalidMode(mode) => write!(f, "Invalid mode '{}': expected 'r', 'r+', 'c' or 'w+'", mode),
         }
     }
 }
@@ -49,6 +97,116 @@ impl From<ArrayError> for MemMapError {
     }
 }
 
+impl From<crate::io::IoError> for MemMapError {
+    fn from(err: crate::io::IoError) -> Self {
+        MemMapError::IoError(err.to_string())
+    }
+}
+
+fn io_err(e: std::io::Error) -> MemMapError {
+    MemMapError::IoError(e.to_string())
+}
+
+/// Open `file_path` as required by `mode`
+///
+/// `MapMode::Create` creates or truncates the file and sets its length to
+/// `len`; the other modes require the file to exist.
+pub(crate) fn open_file(file_path: &Path, mode: MapMode, len: u64) -> Result<File, MemMapError> {
+    if mode == MapMode::Create {
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(file_path)
+            .map_err(io_err)?;
+        file.set_len(len).map_err(io_err)?;
+        return Ok(file);
+    }
+    if !file_path.exists() {
+        return Err(MemMapError::FileNotFound);
+    }
+    File::options()
+        .read(true)
+        .write(mode == MapMode::ReadWrite)
+        .open(file_path)
+        .map_err(io_err)
+}
+
+/// Map `shape` elements of `dtype` starting `offset` bytes into `file`
+///
+/// `offset` need not be page-aligned: the mapping starts at the enclosing
+/// page boundary and the array at `offset`. Only the bytes of the array are
+/// mapped, and an empty array maps nothing. Fortran-order data gets
+/// column-major strides.
+#[allow(clippy::type_complexity)]
+fn map_array(
+    file: &File,
+    dtype: DType,
+    shape: Vec<i64>,
+    fortran_order: bool,
+    offset: u64,
+    mode: MapMode,
+) -> Result<(Array, Option<Mmap>, Option<MmapMut>), MemMapError> {
+    let itemsize = dtype.itemsize();
+    let required_size = shape.iter().product::<i64>() as usize * itemsize;
+
+    // Check file size
+    let file_size = file.metadata().map_err(io_err)?.len();
+    if file_size < offset + required_size as u64 {
+        return Err(MemMapError::IoError("File too small".to_string()));
+    }
+
+    // Create memory mapping based on mode
+    let mut options = MmapOptions::new();
+    options.offset(offset).len(required_size);
+    let failed = |e: std::io::Error| MemMapError::MappingFailed(e.to_string());
+    let (mmap, mmap_mut, data_ptr): (Option<Mmap>, Option<MmapMut>, *mut u8) = match mode {
+        _ if required_size == 0 => (None, None, std::ptr::NonNull::<u64>::dangling().as_ptr() as *mut u8),
+        MapMode::ReadOnly => {
+            let mmap = unsafe { options.map(file).map_err(failed)? };
+            let ptr = mmap.as_ptr() as *mut u8;
+            (Some(mmap), None, ptr)
+        }
+        MapMode::ReadWrite | MapMode::Create => {
+            let mut mmap_mut = unsafe { options.map_mut(file).map_err(failed)? };
+            let ptr = mmap_mut.as_mut_ptr();
+            (None, Some(mmap_mut), ptr)
+        }
+        MapMode::CopyOnWrite => {
+            let mut mmap_mut = unsafe { options.map_copy(file).map_err(failed)? };
+            let ptr = mmap_mut.as_mut_ptr();
+            (None, Some(mmap_mut), ptr)
+        }
+    };
+
+    // Create array from memory-mapped data
+    let mut array = unsafe {
+        Array::from_external_memory(
+            data_ptr,
+            shape,
+            dtype,
+            false, // Does not own data (memory map owns it)
+        )?
+    };
+    if fortran_order && array.ndim() > 1 {
+        let mut strides = vec![0; array.ndim()];
+        let mut stride = itemsize as i64;
+        for (s, &n) in strides.iter_mut().zip(array.shape()) {
+            *s = stride;
+            stride *= n;
+        }
+        array = array.view(array.shape().to_vec(), strides)?;
+    }
+
+    // Set writeable flag based on mode
+    if mode == MapMode::ReadOnly {
+        array.setflags(ArrayFlags::WRITEABLE, false)?;
+    }
+
+    Ok((array, mmap, mmap_mut))
+}
+
 /// Memory-mapped array
 ///
 /// Wraps an array backed by a memory-mapped file
@@ -56,14 +214,19 @@ pub struct MemMapArray {
     /// The underlying array
     array: Array,
     /// Memory map (read-only) - kept alive for lifetime management
-    #[allow(dead_code)]
     mmap: Option<Mmap>,
     /// Memory map (read-write or copy-on-write)
     mmap_mut: Option<MmapMut>,
-    /// File handle (kept alive for the mapping)
-    _file: Option<File>,
+    /// File handle (kept alive for the mapping, and used to remap it)
+    file: File,
     /// File path (for reference)
     file_path: std::path::PathBuf,
+    /// Byte offset of the array data in the file
+    offset: u64,
+    /// Whether the data is in Fortran order
+    fortran_order: bool,
+    /// Whether the file starts with an NPY header describing the array
+    npy_header: bool,
     /// Mapping mode
     mode: MapMode,
 }
@@ -71,6 +234,10 @@ pub struct MemMapArray {
 impl MemMapArray {
     /// Create a new memory-mapped array
     ///
+    /// The array data starts at the beginning of the file. With
+    /// `MapMode::Create` the file is created (or truncated) with the size
+    /// of the array.
+    ///
     /// # Arguments
     /// * `file_path` - Path to file
     /// * `dtype` - Data type
@@ -86,96 +253,134 @@ impl MemMapArray {
         shape: Vec<i64>,
         mode: MapMode,
     ) -> Result<Self, MemMapError> {
-        let itemsize = dtype.itemsize();
-        let total_elements: usize = shape.iter().product::<i64>() as usize;
-        let required_size = total_elements * itemsize;
-        
-        // Open file
-        let file = if file_path.exists() {
-            match mode {
-                MapMode::ReadOnly => {
-                    File::open(file_path)
-                        .map_err(|e| MemMapError::IoError(e.to_string()))?
-                }
-                MapMode::ReadWrite | MapMode::CopyOnWrite => {
-                    File::options()
-                        .read(true)
-                        .write(mode == MapMode::ReadWrite)
-                        .open(file_path)
-                        .map_err(|e| MemMapError::IoError(e.to_string()))?
-                }
-            }
-        } else {
-            return Err(MemMapError::FileNotFound);
-        };
-        
-        // Check file size
-        let file_size = file.metadata()
-            .map_err(|e| MemMapError::IoError(e.to_string()))?
-            .len() as usize;
-        
-        if file_size < required_size {
-            return Err(MemMapError::IoError("File too small".to_string()));
-        }
-        
-        // Create memory mapping based on mode
-        let (mmap, mmap_mut, data_ptr): (Option<Mmap>, Option<MmapMut>, *mut u8) = match mode {
-            MapMode::ReadOnly => {
-                let mmap = unsafe {
-                    MmapOptions::new()
-                        .len(required_size)
-                        .map(&file)
-                        .map_err(|e| MemMapError::MappingFailed(e.to_string()))?
-                };
-                let ptr = mmap.as_ptr() as *mut u8;
-                (Some(mmap), None, ptr)
-            }
-            MapMode::ReadWrite => {
-                let mmap_mut = unsafe {
-                    MmapOptions::new()
-                        .len(required_size)
-                        .map_mut(&file)
-                        .map_err(|e| MemMapError::MappingFailed(e.to_string()))?
-                };
-                let ptr = mmap_mut.as_ptr() as *mut u8;
-                (None, Some(mmap_mut), ptr)
-            }
-            MapMode::CopyOnWrite => {
-                let mmap_mut = unsafe {
-                    MmapOptions::new()
-                        .len(required_size)
-                        .map_copy(&file)
-                        .map_err(|e| MemMapError::MappingFailed(e.to_string()))?
-                };
-                let ptr = mmap_mut.as_ptr() as *mut u8;
-                (None, Some(mmap_mut), ptr)
-            }
-        };
-        
-        // Create array from memory-mapped data
-        let mut array = unsafe {
-            Array::from_external_memory(
-                data_ptr,
-                shape,
-                dtype,
-                false, // Does not own data (memory map owns it)
-            )?
-        };
-        
-        // Set writeable flag based on mode
-        if mode == MapMode::ReadOnly {
-            array.setflags(ArrayFlags::WRITEABLE, false);
-        }
-        
+        MemMapArray::with_offset(file_path, dtype, shape, mode, 0)
+    }
+
+    /// Create a memory-mapped array over a window of a file
+    ///
+    /// The array data starts `offset` bytes into the file, which need not
+    /// be a multiple of the page size; only the bytes of the array are
+    /// mapped, so a small window of a very large file can be mapped. With
+    /// `MapMode::Create` the file is created with room for the window.
+    ///
+    /// # Arguments
+    /// * `file_path` - Path to file
+    /// * `dtype` - Data type
+    /// * `shape` - Shape of array
+    /// * `mode` - Mapping mode
+    /// * `offset` - Byte offset of the array data in the file
+    pub fn with_offset(
+        file_path: &Path,
+        dtype: DType,
+        shape: Vec<i64>,
+        mode: MapMode,
+        offset: u64,
+    ) -> Result<Self, MemMapError> {
+        let required_size = shape.iter().product::<i64>() as u64 * dtype.itemsize() as u64;
+        let file = open_file(file_path, mode, offset + required_size)?;
+        MemMapArray::map(file, file_path, dtype, shape, false, offset, mode)
+    }
+
+    /// Map `shape` elements of `dtype` starting `offset` bytes into `file`
+    ///
+    /// `file` must have been opened for `mode`.
+    pub(crate) fn map(
+        file: File,
+        file_path: &Path,
+        dtype: DType,
+        shape: Vec<i64>,
+        fortran_order: bool,
+        offset: u64,
+        mode: MapMode,
+    ) -> Result<Self, MemMapError> {
+        let (array, mmap, mmap_mut) = map_array(&file, dtype, shape, fortran_order, offset, mode)?;
         Ok(MemMapArray {
             array,
             mmap,
             mmap_mut,
-            _file: Some(file),
+            file,
             file_path: file_path.to_path_buf(),
+            offset,
+            fortran_order,
+            npy_header: false,
             mode,
         })
     }
+
+    /// Mark the file as an NPY file whose header `resize` keeps up to date
+    pub(crate) fn with_npy_header(mut self) -> Self {
+        self.npy_header = true;
+        self
+    }
+
+    /// Map a different window of the same file
+    ///
+    /// The array is replaced by `shape` elements starting `offset` bytes
+    /// into the file. Pending copy-on-write changes are discarded; for
+    /// read-write maps they are already in the file.
+    pub fn remap(&mut self, offset: u64, shape: Vec<i64>) -> Result<(), MemMapError> {
+        let dtype = self.array.dtype().clone();
+        let (array, mmap, mmap_mut) = map_array(&self.file, dtype, shape, self.fortran_order, offset, self.mode)?;
+        // The old array is replaced before its mapping is released
+        self.array = array;
+        self.mmap = mmap;
+        self.mmap_mut = mmap_mut;
+        self.offset = offset;
+        Ok(())
+    }
+
+    /// Change the shape of a read-write map, growing the file if needed
+    ///
+    /// The file is extended with zeros when the new shape needs more room
+    /// and then remapped at the same offset. For `.npy` files opened with
+    /// `open_memmap` the header is rewritten in place with the new shape.
+    pub fn resize(&mut self, shape: Vec<i64>) -> Result<(), MemMapError> {
+        if !matches!(self.mode, MapMode::ReadWrite | MapMode::Create) {
+            return Err(MemMapError::IoError("only read-write maps can be resized".to_string()));
+        }
+        let end = self.offset + shape.iter().product::<i64>() as u64 * self.array.itemsize() as u64;
+
+        if self.npy_header {
+            let header = NpyHeader::new(self.array.dtype().clone(), shape.clone(), self.fortran_order).to_bytes()?;
+            if header.len() as u64 != self.offset {
+                return Err(MemMapError::IoError("new shape does not fit in the NPY header".to_string()));
+            }
+            self.file.seek(SeekFrom::Start(0)).map_err(io_err)?;
+            self.file.write_all(&header).map_err(io_err)?;
+        }
+
+        let file_size = self.file.metadata().map_err(io_err)?.len();
+        if end > file_size {
+            self.file.set_len(end).map_err(io_err)?;
+        }
+        self.remap(self.offset, shape)?;
+        // An NPY file must end with its data
+        if self.npy_header && end < file_size {
+            self.file.set_len(end).map_err(io_err)?;
+        }
+        Ok(())
+    }
+
+    /// Advise the operating system how the mapping will be accessed
+    ///
+    /// This is a hint only; it has no effect on platforms without
+    /// `madvise`.
+    pub fn advise(&self, advice: MemMapAdvice) -> Result<(), MemMapError> {
+        #[cfg(unix)]
+        {
+            let result = match (&self.mmap, &self.mmap_mut) {
+                (Some(mmap), _) => mmap.advise(advice.into()),
+                (_, Some(mmap_mut)) => mmap_mut.advise(advice.into()),
+                _ => Ok(()),
+            };
+            result.map_err(|e| MemMapError::MappingFailed(e.to_string()))
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = advice;
+            Ok(())
+        }
+    }
     
     /// Get reference to underlying array
     pub fn array(&self) -> &Array {
@@ -192,6 +397,11 @@ impl MemMapArray {
         &self.file_path
     }
     
+    /// Get byte offset of the array data in the file
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+    
     /// Get mapping mode
     pub fn mode(&self) -> MapMode {
         self.mode
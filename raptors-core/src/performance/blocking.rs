@@ -4,6 +4,7 @@
 //! in cache-sized blocks to improve memory access patterns.
 
 use crate::performance::cache::optimal_block_size;
+use crate::performance::executor::cancellation_requested;
 
 /// Block iterator for cache-friendly array processing
 ///
@@ -56,6 +57,8 @@ impl Iterator for BlockIterator {
 }
 
 /// Process a 1D array in blocks for cache efficiency
+///
+/// Inside an executor job, stops between blocks once the job is cancelled.
 pub fn process_blocks_1d<F>(
     size: usize,
     element_size: usize,
@@ -64,11 +67,16 @@ pub fn process_blocks_1d<F>(
     F: FnMut(usize, usize), // f(block_start, block_end)
 {
     for (block_start, block_end) in BlockIterator::new(0, size, element_size) {
+        if cancellation_requested() {
+            return;
+        }
         f(block_start, block_end);
     }
 }
 
 /// Process a 2D array in blocks for cache efficiency
+///
+/// Inside an executor job, stops between blocks once the job is cancelled.
 pub fn process_blocks_2d<F>(
     rows: usize,
     cols: usize,
@@ -84,6 +92,9 @@ pub fn process_blocks_2d<F>(
         let row_end = (row_start + row_block_size).min(rows);
         for col_start in (0..cols).step_by(col_block_size) {
             let col_end = (col_start + col_block_size).min(cols);
+            if cancellation_requested() {
+                return;
+            }
             f(row_start, row_end, col_start, col_end);
         }
     }
@@ -0,0 +1,515 @@
+//! Dedicated executor for array kernels
+//!
+//! An `Executor` runs jobs on its own Rayon thread pool, so a service can
+//! bound the cores its array work uses without touching the global pool
+//! (which `init_thread_pool` can only configure once). Kernels that use
+//! Rayon inside a job run on the executor's threads too.
+//!
+//! Jobs wait in a bounded queue with three priority lanes: a free thread
+//! takes the oldest job of the highest non-empty lane. `try_submit` fails
+//! when the queue is full; `submit` returns a `JobHandle` that waits for room
+//! when polled. `JobHandle` is a plain `std::future::Future`, so it can be
+//! awaited from tokio or any other runtime, or waited on with
+//! `JobHandle::wait`.
+//!
+//! Cancellation is cooperative: a job cancelled before it starts never runs,
+//! and a running job stops at the next check of its `CancellationToken`.
+//! `process_blocks_1d` and `process_blocks_2d` check between blocks, and
+//! kernels can check with `cancellation_requested`. A cancelled job resolves
+//! to `ExecutorError::Cancelled`, whatever it returned.
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Executor error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutorError {
+    /// The executor could not be created
+    Build(String),
+    /// The job queue is full
+    QueueFull,
+    /// The job was cancelled before it finished
+    Cancelled,
+    /// The job panicked, with the panic message
+    Panicked(String),
+}
+
+impl std::fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutorError::Build(msg) => write!(f, "Cannot create executor: {}", msg),
+            ExecutorError::QueueFull => write!(f, "Job queue is full"),
+            ExecutorError::Cancelled => write!(f, "Job was cancelled"),
+            ExecutorError::Panicked(msg) => write!(f, "Job panicked: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ExecutorError {}
+
+/// Priority lane of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// Taken before any other waiting job, e.g. latency-sensitive requests
+    High,
+    /// The default lane
+    #[default]
+    Normal,
+    /// Taken only when no other job is waiting, e.g. batch work
+    Low,
+}
+
+/// Options for a new executor
+#[derive(Debug, Clone)]
+pub struct ExecutorOptions {
+    /// Number of threads; 0 uses one per available core
+    pub num_threads: usize,
+    /// Maximum number of jobs waiting to start, across all lanes
+    pub queue_capacity: usize,
+    /// Prefix of the thread names, followed by the thread index
+    pub thread_name: String,
+}
+
+impl Default for ExecutorOptions {
+    fn default() -> Self {
+        ExecutorOptions {
+            num_threads: 0,
+            queue_capacity: 1024,
+            thread_name: "raptors-executor".to_string(),
+        }
+    }
+}
+
+/// Shared flag requesting that jobs stop
+///
+/// Clones share the flag, so one token can cancel several jobs.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request that every job holding this token stops
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Whether `cancel` was called
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+thread_local! {
+    /// Token of the job running on this thread
+    static CURRENT: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Whether the executor job running on this thread was cancelled
+///
+/// Always `false` outside executor jobs.
+pub fn cancellation_requested() -> bool {
+    CURRENT.with(|current| current.borrow().as_ref().is_some_and(CancellationToken::is_cancelled))
+}
+
+/// Make `token` the current token while `f` runs
+fn with_token<T>(token: &CancellationToken, f: impl FnOnce() -> T) -> T {
+    // Restores the outer token when a job runs nested in another one's join
+    struct Restore(Option<CancellationToken>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(CURRENT.with(|current| current.borrow_mut().replace(token.clone())));
+    f()
+}
+
+/// A type-erased job waiting in a lane
+type Task = Box<dyn FnOnce() + Send>;
+
+struct State {
+    /// Waiting jobs, one lane per priority, oldest first
+    lanes: [VecDeque<Task>; 3],
+    /// Threads taking jobs from the lanes
+    workers: usize,
+    /// Handles waiting for room in the queue, by handle id, oldest first;
+    /// a handle has at most one entry
+    waiters: VecDeque<(u64, Waker)>,
+}
+
+impl State {
+    fn queued(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+
+    fn pop(&mut self) -> Option<Task> {
+        self.lanes.iter_mut().find_map(VecDeque::pop_front)
+    }
+}
+
+struct Shared {
+    pool: ThreadPool,
+    capacity: usize,
+    state: Mutex<State>,
+    /// Id of the next handle
+    next_id: AtomicU64,
+}
+
+impl Shared {
+    /// Queue `task`, or hand it back when the queue is full
+    ///
+    /// With a `(handle id, waker)` pair, a full queue registers the handle
+    /// to be woken once there is room, replacing the waker it registered
+    /// before.
+    fn enqueue(self: &Arc<Self>, priority: Priority, task: Task, waiter: Option<(u64, &Waker)>) -> Result<(), Task> {
+        let start_worker = {
+            let mut state = self.state.lock().unwrap();
+            if state.queued() >= self.capacity {
+                if let Some((id, waker)) = waiter {
+                    match state.waiters.iter_mut().find(|(waiting, _)| *waiting == id) {
+                        Some((_, registered)) if !registered.will_wake(waker) => *registered = waker.clone(),
+                        Some(_) => {}
+                        None => state.waiters.push_back((id, waker.clone())),
+                    }
+                }
+                return Err(task);
+            }
+            if let Some((id, _)) = waiter {
+                // Queued without being woken: the entry would take another handle's wake
+                state.waiters.retain(|(waiting, _)| *waiting != id);
+            }
+            state.lanes[priority as usize].push_back(task);
+            let start = state.workers < self.pool.current_num_threads();
+            if start {
+                state.workers += 1;
+            }
+            start
+        };
+        if start_worker {
+            let shared = Arc::clone(self);
+            self.pool.spawn(move || shared.work());
+        }
+        Ok(())
+    }
+
+    /// Remove the handle `id` from the waiters
+    ///
+    /// A handle that was already woken for a free slot and gives up on it
+    /// passes the wake on to the next waiter.
+    fn withdraw(&self, id: u64) {
+        let next = {
+            let mut state = self.state.lock().unwrap();
+            match state.waiters.iter().position(|(waiting, _)| *waiting == id) {
+                Some(position) => {
+                    state.waiters.remove(position);
+                    None
+                }
+                None if state.queued() < self.capacity => state.waiters.pop_front(),
+                None => None,
+            }
+        };
+        if let Some((_, waker)) = next {
+            waker.wake();
+        }
+    }
+
+    /// Take jobs, highest lane first, until the queue is empty
+    ///
+    /// Each job taken frees one slot, so it wakes one waiting handle.
+    fn work(&self) {
+        loop {
+            let (task, waiter) = {
+                let mut state = self.state.lock().unwrap();
+                match state.pop() {
+                    Some(task) => (task, state.waiters.pop_front()),
+                    None => {
+                        state.workers -= 1;
+                        return;
+                    }
+                }
+            };
+            if let Some((_, waker)) = waiter {
+                waker.wake();
+            }
+            task();
+        }
+    }
+}
+
+/// Executor running jobs on a dedicated Rayon thread pool
+///
+/// Cloning is cheap and shares the pool and the queue. Jobs already queued
+/// still run after the last clone is dropped.
+#[derive(Clone)]
+pub struct Executor {
+    shared: Arc<Shared>,
+}
+
+impl std::fmt::Debug for Executor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Executor")
+            .field("num_threads", &self.num_threads())
+            .field("queue_capacity", &self.queue_capacity())
+            .field("queued", &self.queued())
+            .finish()
+    }
+}
+
+impl Executor {
+    /// Create an executor with its own thread pool
+    pub fn new(options: ExecutorOptions) -> Result<Self, ExecutorError> {
+        if options.queue_capacity == 0 {
+            return Err(ExecutorError::Build("queue capacity must be at least 1".to_string()));
+        }
+        let name = options.thread_name;
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(options.num_threads)
+            .thread_name(move |i| format!("{}-{}", name, i))
+            .build()
+            .map_err(|e| ExecutorError::Build(e.to_string()))?;
+        let state = State {
+            lanes: [VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            workers: 0,
+            waiters: VecDeque::new(),
+        };
+        let shared = Shared {
+            pool,
+            capacity: options.queue_capacity,
+            state: Mutex::new(state),
+            next_id: AtomicU64::new(0),
+        };
+        Ok(Executor { shared: Arc::new(shared) })
+    }
+
+    /// Number of threads in the pool
+    pub fn num_threads(&self) -> usize {
+        self.shared.pool.current_num_threads()
+    }
+
+    /// Maximum number of jobs waiting to start
+    pub fn queue_capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Number of jobs waiting to start
+    pub fn queued(&self) -> usize {
+        self.shared.state.lock().unwrap().queued()
+    }
+
+    /// Submit a job, waiting for room in the queue if it is full
+    ///
+    /// The job is queued right away when there is room; otherwise when the
+    /// returned handle is first polled after room frees up.
+    pub fn submit<T, F>(&self, priority: Priority, f: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.submit_cancellable(priority, &CancellationToken::new(), f)
+    }
+
+    /// Submit a job that stops when `token` is cancelled
+    ///
+    /// Like `submit`, but with a token that can be shared between jobs.
+    pub fn submit_cancellable<T, F>(&self, priority: Priority, token: &CancellationToken, f: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (task, slot) = task(token.clone(), f);
+        let pending = self.shared.enqueue(priority, task, None).err();
+        self.handle(priority, pending, slot, token.clone())
+    }
+
+    /// Submit a job, failing with `ExecutorError::QueueFull` if the queue is full
+    pub fn try_submit<T, F>(&self, priority: Priority, f: F) -> Result<JobHandle<T>, ExecutorError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let token = CancellationToken::new();
+        let (task, slot) = task(token.clone(), f);
+        match self.shared.enqueue(priority, task, None) {
+            Ok(()) => Ok(self.handle(priority, None, slot, token)),
+            Err(_) => Err(ExecutorError::QueueFull),
+        }
+    }
+
+    /// Handle for a job, still `pending` if it waits for room in the queue
+    fn handle<T>(
+        &self,
+        priority: Priority,
+        pending: Option<Task>,
+        slot: Arc<Slot<T>>,
+        token: CancellationToken,
+    ) -> JobHandle<T> {
+        let id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
+        JobHandle { shared: Arc::clone(&self.shared), id, priority, pending, slot, token }
+    }
+}
+
+/// Result of a job, shared between the job and its handle
+struct Slot<T> {
+    state: Mutex<SlotState<T>>,
+}
+
+struct SlotState<T> {
+    result: Option<Result<T, ExecutorError>>,
+    /// Waker of the handle polling for the result
+    waker: Option<Waker>,
+}
+
+impl<T> Slot<T> {
+    fn complete(&self, result: Result<T, ExecutorError>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.result = Some(result);
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<Result<T, ExecutorError>> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Wrap `f` into a task that checks `token` and reports to the returned slot
+fn task<T, F>(token: CancellationToken, f: F) -> (Task, Arc<Slot<T>>)
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let slot = Arc::new(Slot { state: Mutex::new(SlotState { result: None, waker: None }) });
+    let result = Arc::clone(&slot);
+    let task = Box::new(move || {
+        if token.is_cancelled() {
+            return result.complete(Err(ExecutorError::Cancelled));
+        }
+        let outcome = with_token(&token, || panic::catch_unwind(AssertUnwindSafe(f)));
+        result.complete(match outcome {
+            _ if token.is_cancelled() => Err(ExecutorError::Cancelled),
+            Ok(value) => Ok(value),
+            Err(payload) => Err(ExecutorError::Panicked(
+                payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string()),
+            )),
+        });
+    });
+    (task, slot)
+}
+
+/// Handle to a submitted job; a future resolving to its result
+///
+/// Dropping the handle detaches a queued job: it still runs, and its result
+/// is dropped. A job still waiting for room in the queue is dropped with it.
+/// Use `cancel` to stop a job.
+pub struct JobHandle<T> {
+    shared: Arc<Shared>,
+    /// Key of the handle among the waiters
+    id: u64,
+    priority: Priority,
+    /// The job while it waits for room in the queue
+    pending: Option<Task>,
+    slot: Arc<Slot<T>>,
+    token: CancellationToken,
+}
+
+impl<T> std::fmt::Debug for JobHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JobHandle")
+            .field("priority", &self.priority)
+            .field("queued", &self.pending.is_none())
+            .field("cancelled", &self.token.is_cancelled())
+            .finish()
+    }
+}
+
+impl<T> JobHandle<T> {
+    /// Cancel the job (and every other job sharing its token)
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// The job's cancellation token
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Block the current thread until the job finishes
+    ///
+    /// Don't call this from a job on the same executor: with every thread
+    /// waiting, the job waited for never starts.
+    pub fn wait(mut self) -> Result<T, ExecutorError> {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(result) = Pin::new(&mut self).poll(&mut cx) {
+                return result;
+            }
+            thread::park();
+        }
+    }
+}
+
+impl<T> Future for JobHandle<T> {
+    type Output = Result<T, ExecutorError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Some(task) = this.pending.take() {
+            if this.token.is_cancelled() {
+                this.shared.withdraw(this.id);
+                return Poll::Ready(Err(ExecutorError::Cancelled));
+            }
+            if let Err(task) = this.shared.enqueue(this.priority, task, Some((this.id, cx.waker()))) {
+                this.pending = Some(task);
+                return Poll::Pending;
+            }
+        }
+        this.slot.poll(cx)
+    }
+}
+
+impl<T> Drop for JobHandle<T> {
+    fn drop(&mut self) {
+        if self.pending.is_some() {
+            self.shared.withdraw(self.id);
+        }
+    }
+}
+
+/// Waker that unparks a thread blocked in `JobHandle::wait`
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
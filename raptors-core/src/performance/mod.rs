@@ -1,9 +1,11 @@
 //! Performance optimization module
 //!
 //! This module provides performance optimizations including threading,
-//! cache-friendly algorithms, and blocked operations.
+//! cache-friendly algorithms, blocked operations, and a dedicated executor
+//! for submitting kernels.
 
 pub mod cache;
 pub mod blocking;
+pub mod executor;
 pub mod threading;
 
@@ -16,6 +16,9 @@ static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
 ///
 /// If not called, Rayon's default thread pool is used (number of CPU cores).
 /// This function allows customization to match NumPy's threading behavior.
+///
+/// The global pool can only be configured once per process; use
+/// `performance::executor::Executor` for pools of their own.
 pub fn init_thread_pool(num_threads: usize) -> Result<(), rayon::ThreadPoolBuildError> {
     ThreadPoolBuilder::new()
         .num_threads(num_threads)
@@ -0,0 +1,295 @@
+//! Polynomial bases
+//!
+//! Coefficient-level operations for power series and the classical
+//! orthogonal polynomial families, equivalent to the module functions of
+//! `numpy.polynomial` (`chebval`, `legder`, `hermint`, `lagfit`, ...).
+//! Coefficients are ordered from low to high degree.
+
+use crate::array::Array;
+use crate::linalg::{eigvals_of, lstsq};
+use crate::types::{Complex128, DType, NpyType};
+
+use super::PolynomialError;
+
+/// Polynomial family used as the basis of a series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basis {
+    /// Power series `1, x, x², ...`
+    Power,
+    /// Chebyshev polynomials of the first kind
+    Chebyshev,
+    /// Legendre polynomials
+    Legendre,
+    /// Physicists' Hermite polynomials
+    Hermite,
+    /// Probabilists' Hermite polynomials
+    HermiteE,
+    /// Laguerre polynomials
+    Laguerre,
+}
+
+/// Drop trailing (highest-degree) zero coefficients, keeping at least one
+pub(crate) fn trim(c: &[f64]) -> Vec<f64> {
+    let len = c.iter().rposition(|&v| v != 0.0).map_or(1, |i| i + 1);
+    let mut out = c[..len.min(c.len())].to_vec();
+    if out.is_empty() {
+        out.push(0.0);
+    }
+    out
+}
+
+impl Basis {
+    /// Default domain (and window) of the family, as in NumPy's classes
+    pub fn default_domain(self) -> [f64; 2] {
+        match self {
+            Basis::Laguerre => [0.0, 1.0],
+            _ => [-1.0, 1.0],
+        }
+    }
+
+    /// Coefficients `(α, β, γ)` of the recurrence `P[k+1] = (α + βx) P[k] - γ P[k-1]`
+    fn recurrence(self, k: usize) -> (f64, f64, f64) {
+        let kf = k as f64;
+        match self {
+            Basis::Power => (0.0, 1.0, 0.0),
+            Basis::Chebyshev if k == 0 => (0.0, 1.0, 0.0),
+            Basis::Chebyshev => (0.0, 2.0, 1.0),
+            Basis::Legendre => (0.0, (2.0 * kf + 1.0) / (kf + 1.0), kf / (kf + 1.0)),
+            Basis::Hermite => (0.0, 2.0, 2.0 * kf),
+            Basis::HermiteE => (0.0, 1.0, kf),
+            Basis::Laguerre => ((2.0 * kf + 1.0) / (kf + 1.0), -1.0 / (kf + 1.0), kf / (kf + 1.0)),
+        }
+    }
+
+    /// Evaluate the series with coefficients `c` at `x` (Clenshaw recurrence)
+    pub fn val(self, c: &[f64], x: f64) -> f64 {
+        let mut b1 = 0.0;
+        let mut b2 = 0.0;
+        for k in (0..c.len()).rev() {
+            let (alpha, beta, _) = self.recurrence(k);
+            let (_, _, gamma_next) = self.recurrence(k + 1);
+            let b0 = c[k] + (alpha + beta * x) * b1 - gamma_next * b2;
+            b2 = b1;
+            b1 = b0;
+        }
+        b1
+    }
+
+    /// Evaluate the series at every element of `x`
+    ///
+    /// Returns a `Double` array with the shape of `x`.
+    pub fn val_array(self, c: &[f64], x: &Array) -> Result<Array, PolynomialError> {
+        let values = crate::utils::to_f64_vec(x).ok_or(PolynomialError::UnsupportedDtype)?;
+        let out: Vec<f64> = values.iter().map(|&v| self.val(c, v)).collect();
+        Ok(Array::from_slice(&out, x.shape().to_vec(), DType::new(NpyType::Double))?)
+    }
+
+    /// Pseudo-Vandermonde matrix: row `i` holds `P[0](x[i]) .. P[deg](x[i])`
+    ///
+    /// Returned row-major with shape `(x.len(), deg + 1)`.
+    pub fn vander(self, x: &[f64], deg: usize) -> Vec<f64> {
+        let cols = deg + 1;
+        let mut v = vec![0.0; x.len() * cols];
+        for (row, &xi) in v.chunks_mut(cols).zip(x) {
+            row[0] = 1.0;
+            for k in 0..deg {
+                let (alpha, beta, gamma) = self.recurrence(k);
+                let prev = if k > 0 { row[k - 1] } else { 0.0 };
+                row[k + 1] = (alpha + beta * xi) * row[k] - gamma * prev;
+            }
+        }
+        v
+    }
+
+    /// Differentiate `m` times, multiplying by `scl` at each step
+    pub fn der(self, c: &[f64], m: usize, scl: f64) -> Vec<f64> {
+        let mut c = c.to_vec();
+        for _ in 0..m {
+            let n = c.len() - 1;
+            if n == 0 {
+                c = vec![0.0];
+                break;
+            }
+            c.iter_mut().for_each(|v| *v *= scl);
+            let mut der = vec![0.0; n];
+            match self {
+                Basis::Power => {
+                    for j in 1..=n {
+                        der[j - 1] = j as f64 * c[j];
+                    }
+                }
+                Basis::Chebyshev => {
+                    for j in (3..=n).rev() {
+                        der[j - 1] = 2.0 * j as f64 * c[j];
+                        c[j - 2] += j as f64 * c[j] / (j as f64 - 2.0);
+                    }
+                    if n > 1 {
+                        der[1] = 4.0 * c[2];
+                    }
+                    der[0] = c[1];
+                }
+                Basis::Legendre => {
+                    for j in (3..=n).rev() {
+                        der[j - 1] = (2.0 * j as f64 - 1.0) * c[j];
+                        c[j - 2] += c[j];
+                    }
+                    if n > 1 {
+                        der[1] = 3.0 * c[2];
+                    }
+                    der[0] = c[1];
+                }
+                Basis::Hermite => {
+                    for j in 1..=n {
+                        der[j - 1] = 2.0 * j as f64 * c[j];
+                    }
+                }
+                Basis::HermiteE => {
+                    for j in 1..=n {
+                        der[j - 1] = j as f64 * c[j];
+                    }
+                }
+                Basis::Laguerre => {
+                    for j in (2..=n).rev() {
+                        der[j - 1] = -c[j];
+                        c[j - 1] += c[j];
+                    }
+                    der[0] = -c[1];
+                }
+            }
+            c = der;
+        }
+        c
+    }
+
+    /// Integrate `m` times
+    ///
+    /// After the i-th integration the value at `lbnd` is `k[i]` (missing
+    /// constants are zero). Each step multiplies by `scl` first.
+    pub fn int(self, c: &[f64], m: usize, k: &[f64], lbnd: f64, scl: f64) -> Vec<f64> {
+        let mut c = c.to_vec();
+        for i in 0..m {
+            let ki = k.get(i).copied().unwrap_or(0.0);
+            let n = c.len();
+            if n == 1 && c[0] == 0.0 {
+                c[0] += ki;
+                continue;
+            }
+            c.iter_mut().for_each(|v| *v *= scl);
+            let mut tmp = vec![0.0; n + 1];
+            match self {
+                Basis::Power => {
+                    for j in 0..n {
+                        tmp[j + 1] = c[j] / (j as f64 + 1.0);
+                    }
+                }
+                Basis::Chebyshev => {
+                    tmp[1] = c[0];
+                    if n > 1 {
+                        tmp[2] = c[1] / 4.0;
+                    }
+                    for j in 2..n {
+                        tmp[j + 1] = c[j] / (2.0 * (j as f64 + 1.0));
+                        tmp[j - 1] -= c[j] / (2.0 * (j as f64 - 1.0));
+                    }
+                }
+                Basis::Legendre => {
+                    tmp[1] = c[0];
+                    if n > 1 {
+                        tmp[2] = c[1] / 3.0;
+                    }
+                    for j in 2..n {
+                        let t = c[j] / (2.0 * j as f64 + 1.0);
+                        tmp[j + 1] = t;
+                        tmp[j - 1] -= t;
+                    }
+                }
+                Basis::Hermite => {
+                    tmp[1] = c[0] / 2.0;
+                    for j in 1..n {
+                        tmp[j + 1] = c[j] / (2.0 * (j as f64 + 1.0));
+                    }
+                }
+                Basis::HermiteE => {
+                    tmp[1] = c[0];
+                    for j in 1..n {
+                        tmp[j + 1] = c[j] / (j as f64 + 1.0);
+                    }
+                }
+                Basis::Laguerre => {
+                    tmp[0] = c[0];
+                    tmp[1] = -c[0];
+                    for j in 1..n {
+                        tmp[j] += c[j];
+                        tmp[j + 1] = -c[j];
+                    }
+                }
+            }
+            tmp[0] += ki - self.val(&tmp, lbnd);
+            c = tmp;
+        }
+        c
+    }
+
+    /// Least-squares fit of a degree-`deg` series to the points `(x, y)`
+    ///
+    /// Columns of the Vandermonde matrix are scaled to unit norm before
+    /// solving, as NumPy does, with `rcond = len(x) * eps`.
+    pub fn fit(self, x: &[f64], y: &[f64], deg: usize) -> Result<Vec<f64>, PolynomialError> {
+        if x.len() != y.len() {
+            return Err(PolynomialError::ShapeMismatch);
+        }
+        if x.is_empty() {
+            return Err(PolynomialError::InvalidDimension);
+        }
+        let cols = deg + 1;
+        let mut lhs = self.vander(x, deg);
+        let scale: Vec<f64> = (0..cols)
+            .map(|j| {
+                let norm = lhs.iter().skip(j).step_by(cols).map(|v| v * v).sum::<f64>().sqrt();
+                if norm == 0.0 { 1.0 } else { norm }
+            })
+            .collect();
+        for row in lhs.chunks_mut(cols) {
+            for (v, s) in row.iter_mut().zip(&scale) {
+                *v /= s;
+            }
+        }
+        let dtype = DType::new(NpyType::Double);
+        let a = Array::from_slice(&lhs, vec![x.len() as i64, cols as i64], dtype.clone())?;
+        let b = Array::from_slice(y, vec![y.len() as i64], dtype)?;
+        let result = lstsq(&a, &b, Some(x.len() as f64 * f64::EPSILON))?;
+        let solution = crate::utils::to_f64_vec(&result.solution).ok_or(PolynomialError::UnsupportedDtype)?;
+        Ok(solution.iter().zip(&scale).map(|(c, s)| c / s).collect())
+    }
+
+    /// Roots of the series, sorted by real then imaginary part
+    ///
+    /// Computed as the eigenvalues of the companion (comrade) matrix built
+    /// from the family's three-term recurrence.
+    pub fn roots(self, c: &[f64]) -> Result<Vec<Complex128>, PolynomialError> {
+        let c = trim(c);
+        let n = c.len() - 1;
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let mut mat = vec![0.0; n * n];
+        for k in 0..n {
+            let (alpha, beta, gamma) = self.recurrence(k);
+            mat[k * n + k] = -alpha / beta;
+            if k + 1 < n {
+                mat[k * n + k + 1] = 1.0 / beta;
+            }
+            if k > 0 {
+                mat[k * n + k - 1] = gamma / beta;
+            }
+        }
+        // P[n] is eliminated using the series itself: P[n] = -Σ c[j] P[j] / c[n]
+        let (_, beta_last, _) = self.recurrence(n - 1);
+        for j in 0..n {
+            mat[(n - 1) * n + j] -= c[j] / (c[n] * beta_last);
+        }
+        let mut roots = eigvals_of(&mat, n)?;
+        roots.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+        Ok(roots)
+    }
+}
@@ -0,0 +1,14 @@
+//! Polynomial module
+//!
+//! This module provides NumPy's legacy power-series functions (`polyval`,
+//! `polyfit`, `polyder`, `polyint`, `roots`) and `Series` in the power,
+//! Chebyshev, Legendre, Hermite and Laguerre bases with fitting,
+//! evaluation, calculus and domain mapping, equivalent to `numpy.polynomial`
+
+mod basis;
+mod power;
+mod series;
+
+pub use basis::*;
+pub use power::*;
+pub use series::*;
@@ -0,0 +1,172 @@
+//! Power-series polynomials
+//!
+//! This module provides NumPy's legacy polynomial functions (`polyval`,
+//! `polyfit`, `polyder`, `polyint` and `roots`). Unlike the `Basis` and
+//! `Series` APIs, coefficients here are ordered from the highest degree
+//! down, as in `numpy.polyval`.
+
+use crate::array::{Array, ArrayError};
+use crate::linalg::{eigvals_of, LinalgError};
+use crate::types::{Complex128, DType, NpyType};
+
+use super::Basis;
+
+/// Polynomial error
+#[derive(Debug, Clone)]
+pub enum PolynomialError {
+    /// Array error
+    ArrayError(ArrayError),
+    /// Linear algebra error from a fit or root finding
+    LinalgError(LinalgError),
+    /// Input has the wrong number of dimensions
+    InvalidDimension,
+    /// Input lengths do not match
+    ShapeMismatch,
+    /// Domain or window has zero width
+    InvalidDomain,
+    /// Dtype is not a real numeric type
+    UnsupportedDtype,
+}
+
+impl std::fmt::Display for PolynomialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolynomialError::ArrayError(e) => write!(f, "Array error: {}", e),
+            PolynomialError::LinalgError(e) => write!(f, "Linear algebra error: {}", e),
+            PolynomialError::InvalidDimension => write!(f, "Invalid dimension"),
+            PolynomialError::ShapeMismatch => write!(f, "Shape mismatch"),
+            PolynomialError::InvalidDomain => write!(f, "Domain and window must have nonzero width"),
+            PolynomialError::UnsupportedDtype => write!(f, "Unsupported dtype"),
+        }
+    }
+}
+
+impl std::error::Error for PolynomialError {}
+
+impl From<ArrayError> for PolynomialError {
+    fn from(err: ArrayError) -> Self {
+        PolynomialError::ArrayError(err)
+    }
+}
+
+impl From<LinalgError> for PolynomialError {
+    fn from(err: LinalgError) -> Self {
+        PolynomialError::LinalgError(err)
+    }
+}
+
+/// Read a 1-D coefficient array as `f64`
+fn coefficients(p: &Array) -> Result<Vec<f64>, PolynomialError> {
+    if p.ndim() != 1 {
+        return Err(PolynomialError::InvalidDimension);
+    }
+    crate::utils::to_f64_vec(p).ok_or(PolynomialError::UnsupportedDtype)
+}
+
+fn double_array(values: &[f64], shape: Vec<i64>) -> Result<Array, PolynomialError> {
+    Ok(Array::from_slice(values, shape, DType::new(NpyType::Double))?)
+}
+
+/// Evaluate the polynomial `p` at every element of `x`
+///
+/// `p` holds coefficients from the highest degree down. Returns a
+/// `Double` array with the shape of `x`.
+pub fn polyval(p: &Array, x: &Array) -> Result<Array, PolynomialError> {
+    let mut c = coefficients(p)?;
+    c.reverse();
+    Basis::Power.val_array(&c, x)
+}
+
+/// Least-squares polynomial fit of degree `deg`
+///
+/// `x` has shape `(m,)` and `y` shape `(m,)` or `(m, k)`. Returns the
+/// coefficients from the highest degree down, with shape `(deg + 1,)` or
+/// `(deg + 1, k)`.
+pub fn polyfit(x: &Array, y: &Array, deg: usize) -> Result<Array, PolynomialError> {
+    let xs = coefficients(x)?;
+    if y.ndim() == 0 || y.ndim() > 2 {
+        return Err(PolynomialError::InvalidDimension);
+    }
+    if y.shape()[0] as usize != xs.len() {
+        return Err(PolynomialError::ShapeMismatch);
+    }
+    let ys = crate::utils::to_f64_vec(y).ok_or(PolynomialError::UnsupportedDtype)?;
+    let k = if y.ndim() == 2 { y.shape()[1] as usize } else { 1 };
+
+    let mut out = vec![0.0; (deg + 1) * k];
+    for col in 0..k {
+        let column: Vec<f64> = ys.iter().skip(col).step_by(k).copied().collect();
+        let coef = Basis::Power.fit(&xs, &column, deg)?;
+        for (i, c) in coef.iter().rev().enumerate() {
+            out[i * k + col] = *c;
+        }
+    }
+    let shape = if y.ndim() == 2 { vec![deg as i64 + 1, k as i64] } else { vec![deg as i64 + 1] };
+    double_array(&out, shape)
+}
+
+/// Derivative of order `m` of the polynomial `p`
+///
+/// As in NumPy, differentiating a constant yields an empty array.
+pub fn polyder(p: &Array, m: usize) -> Result<Array, PolynomialError> {
+    let mut c = coefficients(p)?;
+    for _ in 0..m {
+        let n = c.len().saturating_sub(1);
+        c = c[..n].iter().enumerate().map(|(i, v)| v * (n - i) as f64).collect();
+    }
+    double_array(&c, vec![c.len() as i64])
+}
+
+/// Antiderivative of order `m` of the polynomial `p`
+///
+/// `k` gives the integration constants, one per order; a single value is
+/// used for every order, and the default is zero.
+pub fn polyint(p: &Array, m: usize, k: Option<&[f64]>) -> Result<Array, PolynomialError> {
+    let mut c = coefficients(p)?;
+    let constants: Vec<f64> = match k {
+        None => vec![0.0; m],
+        Some([single]) => vec![*single; m],
+        Some(values) if values.len() == m => values.to_vec(),
+        Some(_) => return Err(PolynomialError::ShapeMismatch),
+    };
+    for constant in constants {
+        let n = c.len();
+        c = c.iter().enumerate().map(|(i, v)| v / (n - i) as f64).collect();
+        c.push(constant);
+    }
+    double_array(&c, vec![c.len() as i64])
+}
+
+/// Roots of the polynomial `p`
+///
+/// The roots are the eigenvalues of the companion matrix. Returns a
+/// `Double` array when every root is real and a `CDouble` array otherwise,
+/// like `numpy.roots`.
+pub fn roots(p: &Array) -> Result<Array, PolynomialError> {
+    let c = coefficients(p)?;
+    let (first, last) = match (c.iter().position(|&v| v != 0.0), c.iter().rposition(|&v| v != 0.0)) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return double_array(&[], vec![0]),
+    };
+    let trailing_zeros = c.len() - last - 1;
+    let c = &c[first..=last];
+    let n = c.len() - 1;
+
+    let mut companion = vec![0.0; n * n];
+    for j in 0..n {
+        companion[j] = -c[j + 1] / c[0];
+    }
+    for i in 1..n {
+        companion[i * n + i - 1] = 1.0;
+    }
+    let mut found = if n > 0 { eigvals_of(&companion, n)? } else { Vec::new() };
+    found.extend(std::iter::repeat_n(Complex128::new(0.0, 0.0), trailing_zeros));
+
+    let len = vec![found.len() as i64];
+    if found.iter().all(|z| z.im == 0.0) {
+        let real: Vec<f64> = found.iter().map(|z| z.re).collect();
+        double_array(&real, len)
+    } else {
+        Ok(Array::from_slice(&found, len, DType::new(NpyType::CDouble))?)
+    }
+}
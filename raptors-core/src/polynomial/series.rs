@@ -0,0 +1,169 @@
+//! Polynomial series
+//!
+//! `Series` pairs coefficients in a `Basis` with a domain and window, like
+//! the `numpy.polynomial` classes (`Chebyshev`, `Legendre`, ...). Inputs in
+//! the domain are mapped linearly onto the window before the basis is
+//! evaluated, so fits over arbitrary intervals stay well conditioned.
+
+use crate::array::Array;
+use crate::types::{Complex128, DType, NpyType};
+
+use super::basis::trim;
+use super::{Basis, PolynomialError};
+
+/// Polynomial series in an orthogonal or power basis
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    basis: Basis,
+    coef: Vec<f64>,
+    domain: [f64; 2],
+    window: [f64; 2],
+}
+
+fn check_interval(interval: [f64; 2]) -> Result<(), PolynomialError> {
+    if interval[0] == interval[1] || !interval.iter().all(|v| v.is_finite()) {
+        return Err(PolynomialError::InvalidDomain);
+    }
+    Ok(())
+}
+
+impl Series {
+    /// Create a series with the basis's default domain and window
+    ///
+    /// Coefficients are ordered from low to high degree; an empty slice is
+    /// the zero series.
+    pub fn new(basis: Basis, coef: &[f64]) -> Self {
+        let coef = if coef.is_empty() { vec![0.0] } else { coef.to_vec() };
+        Series { basis, coef, domain: basis.default_domain(), window: basis.default_domain() }
+    }
+
+    /// Replace the domain
+    pub fn with_domain(mut self, domain: [f64; 2]) -> Result<Self, PolynomialError> {
+        check_interval(domain)?;
+        self.domain = domain;
+        Ok(self)
+    }
+
+    /// Replace the window
+    pub fn with_window(mut self, window: [f64; 2]) -> Result<Self, PolynomialError> {
+        check_interval(window)?;
+        self.window = window;
+        Ok(self)
+    }
+
+    /// Basis of the series
+    pub fn basis(&self) -> Basis {
+        self.basis
+    }
+
+    /// Coefficients from low to high degree
+    pub fn coef(&self) -> &[f64] {
+        &self.coef
+    }
+
+    /// Domain the series is defined over
+    pub fn domain(&self) -> [f64; 2] {
+        self.domain
+    }
+
+    /// Window the domain is mapped onto
+    pub fn window(&self) -> [f64; 2] {
+        self.window
+    }
+
+    /// Degree of the series (number of coefficients minus one)
+    pub fn degree(&self) -> usize {
+        self.coef.len() - 1
+    }
+
+    /// Series with trailing zero coefficients removed
+    pub fn trim(&self) -> Self {
+        Series { coef: trim(&self.coef), ..self.clone() }
+    }
+
+    /// Offset and scale of the linear map from domain to window
+    ///
+    /// A point `x` in the domain maps to `off + scl * x`.
+    pub fn mapparms(&self) -> (f64, f64) {
+        let [d0, d1] = self.domain;
+        let [w0, w1] = self.window;
+        let off = (d1 * w0 - d0 * w1) / (d1 - d0);
+        let scl = (w1 - w0) / (d1 - d0);
+        (off, scl)
+    }
+
+    /// Evaluate the series at a single point
+    pub fn eval_scalar(&self, x: f64) -> f64 {
+        let (off, scl) = self.mapparms();
+        self.basis.val(&self.coef, off + scl * x)
+    }
+
+    /// Evaluate the series at every element of `x`
+    ///
+    /// Returns a `Double` array with the shape of `x`.
+    pub fn eval(&self, x: &Array) -> Result<Array, PolynomialError> {
+        let values = crate::utils::to_f64_vec(x).ok_or(PolynomialError::UnsupportedDtype)?;
+        let out: Vec<f64> = values.iter().map(|&v| self.eval_scalar(v)).collect();
+        Ok(Array::from_slice(&out, x.shape().to_vec(), DType::new(NpyType::Double))?)
+    }
+
+    /// Derivative of order `m`
+    pub fn deriv(&self, m: usize) -> Self {
+        let (_, scl) = self.mapparms();
+        Series { coef: self.basis.der(&self.coef, m, scl), ..self.clone() }
+    }
+
+    /// Antiderivative of order `m`
+    ///
+    /// `k` gives the value of each successive integral at `lbnd`; missing
+    /// constants are zero. Without `lbnd` the constants apply at the origin
+    /// of the window, as in NumPy.
+    pub fn integ(&self, m: usize, k: &[f64], lbnd: Option<f64>) -> Self {
+        let (off, scl) = self.mapparms();
+        let lbnd = lbnd.map_or(0.0, |x| off + scl * x);
+        Series { coef: self.basis.int(&self.coef, m, k, lbnd, 1.0 / scl), ..self.clone() }
+    }
+
+    /// Least-squares fit of a degree-`deg` series to the points `(x, y)`
+    ///
+    /// The domain defaults to `[min(x), max(x)]`; the window is the basis
+    /// default. Both `x` and `y` must be 1-D of equal length.
+    pub fn fit(
+        basis: Basis,
+        x: &Array,
+        y: &Array,
+        deg: usize,
+        domain: Option<[f64; 2]>,
+    ) -> Result<Self, PolynomialError> {
+        if x.ndim() != 1 || y.ndim() != 1 {
+            return Err(PolynomialError::InvalidDimension);
+        }
+        let xs = crate::utils::to_f64_vec(x).ok_or(PolynomialError::UnsupportedDtype)?;
+        let ys = crate::utils::to_f64_vec(y).ok_or(PolynomialError::UnsupportedDtype)?;
+        let domain = domain.unwrap_or_else(|| {
+            let lo = xs.iter().copied().fold(f64::INFINITY, f64::min);
+            let hi = xs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            [lo, hi]
+        });
+        let series = Series::new(basis, &[0.0]).with_domain(domain)?;
+        let (off, scl) = series.mapparms();
+        let mapped: Vec<f64> = xs.iter().map(|&v| off + scl * v).collect();
+        let coef = basis.fit(&mapped, &ys, deg)?;
+        Ok(Series { coef, ..series })
+    }
+
+    /// Roots of the series in its domain
+    ///
+    /// Returns a `CDouble` array of roots sorted by real then imaginary
+    /// part.
+    pub fn roots(&self) -> Result<Array, PolynomialError> {
+        let (off, scl) = self.mapparms();
+        let roots: Vec<Complex128> = self
+            .basis
+            .roots(&self.coef)?
+            .into_iter()
+            .map(|z| Complex128::new((z.re - off) / scl, z.im / scl))
+            .collect();
+        Ok(Array::from_slice(&roots, vec![roots.len() as i64], DType::new(NpyType::CDouble))?)
+    }
+}
@@ -0,0 +1,205 @@
+//! Bit generators
+//!
+//! Sources of raw random bits. `Pcg64` and `Philox` reproduce NumPy's
+//! `PCG64` and `Philox` bit generators exactly, including how they are
+//! seeded from a `SeedSequence` and how 32-bit draws are buffered.
+
+use super::SeedSequence;
+
+/// Source of uniformly distributed random bits
+pub trait BitGenerator: Send {
+    /// Create a generator seeded from a seed sequence
+    fn from_seed_sequence(seed: &SeedSequence) -> Self
+    where
+        Self: Sized;
+
+    /// Next 64 random bits
+    fn next_u64(&mut self) -> u64;
+
+    /// Next 32 random bits
+    fn next_u32(&mut self) -> u32;
+
+    /// Next double uniformly distributed in `[0, 1)` with 53 bits of precision
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / 9007199254740992.0)
+    }
+}
+
+const PCG_MULTIPLIER: u128 = 0x2360ed051fc65da44385df649fccf645;
+
+/// 128-bit permuted congruential generator with XSL-RR output (NumPy's `PCG64`)
+#[derive(Debug, Clone)]
+pub struct Pcg64 {
+    state: u128,
+    inc: u128,
+    buffered: Option<u32>,
+}
+
+impl Pcg64 {
+    /// Create a generator seeded like `numpy.random.PCG64(seed)`
+    pub fn new(seed: u128) -> Self {
+        Self::from_seed_sequence(&SeedSequence::new(seed))
+    }
+
+    /// Create a generator from an initial state and stream selector
+    pub fn from_state(initstate: u128, initseq: u128) -> Self {
+        let mut rng = Pcg64 {
+            state: 0,
+            inc: (initseq << 1) | 1,
+            buffered: None,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(initstate);
+        rng.step();
+        rng
+    }
+
+    /// Get the raw LCG state and increment
+    pub fn state(&self) -> (u128, u128) {
+        (self.state, self.inc)
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(PCG_MULTIPLIER).wrapping_add(self.inc);
+    }
+
+    /// Advance the stream by `delta` 64-bit draws in `O(log delta)` time
+    pub fn advance(&mut self, mut delta: u128) {
+        let mut acc_mult: u128 = 1;
+        let mut acc_plus: u128 = 0;
+        let mut cur_mult = PCG_MULTIPLIER;
+        let mut cur_plus = self.inc;
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult.wrapping_mul(cur_mult);
+                acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            }
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            delta >>= 1;
+        }
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+        self.buffered = None;
+    }
+}
+
+impl BitGenerator for Pcg64 {
+    fn from_seed_sequence(seed: &SeedSequence) -> Self {
+        let words = seed.generate_state_u64(4);
+        let initstate = ((words[0] as u128) << 64) | words[1] as u128;
+        let initseq = ((words[2] as u128) << 64) | words[3] as u128;
+        Pcg64::from_state(initstate, initseq)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+        let hi = (self.state >> 64) as u64;
+        let lo = self.state as u64;
+        (hi ^ lo).rotate_right((hi >> 58) as u32)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        // Each 64-bit draw yields two 32-bit draws, low half first
+        if let Some(value) = self.buffered.take() {
+            return value;
+        }
+        let next = self.next_u64();
+        self.buffered = Some((next >> 32) as u32);
+        next as u32
+    }
+}
+
+const PHILOX_M0: u64 = 0xd2e7470ee14c6c93;
+const PHILOX_M1: u64 = 0xca5a826395121157;
+const PHILOX_W0: u64 = 0x9e3779b97f4a7c15;
+const PHILOX_W1: u64 = 0xbb67ae8584caa73b;
+const PHILOX_ROUNDS: usize = 10;
+
+fn mulhilo(a: u64, b: u64) -> (u64, u64) {
+    let product = a as u128 * b as u128;
+    ((product >> 64) as u64, product as u64)
+}
+
+/// Apply the Philox4x64-10 bijection to one counter block
+pub fn philox4x64(counter: [u64; 4], key: [u64; 2]) -> [u64; 4] {
+    let mut ctr = counter;
+    let mut key = key;
+    for round in 0..PHILOX_ROUNDS {
+        if round > 0 {
+            key[0] = key[0].wrapping_add(PHILOX_W0);
+            key[1] = key[1].wrapping_add(PHILOX_W1);
+        }
+        let (hi0, lo0) = mulhilo(PHILOX_M0, ctr[0]);
+        let (hi1, lo1) = mulhilo(PHILOX_M1, ctr[2]);
+        ctr = [hi1 ^ ctr[1] ^ key[0], lo1, hi0 ^ ctr[3] ^ key[1], lo0];
+    }
+    ctr
+}
+
+/// Counter-based Philox4x64-10 generator (NumPy's `Philox`)
+#[derive(Debug, Clone)]
+pub struct Philox {
+    counter: [u64; 4],
+    key: [u64; 2],
+    block: [u64; 4],
+    block_pos: usize,
+    buffered: Option<u32>,
+}
+
+impl Philox {
+    /// Create a generator seeded like `numpy.random.Philox(seed)`
+    pub fn new(seed: u128) -> Self {
+        Self::from_seed_sequence(&SeedSequence::new(seed))
+    }
+
+    /// Create a generator from an explicit key and starting counter
+    pub fn with_key(key: [u64; 2], counter: [u64; 4]) -> Self {
+        Philox {
+            counter,
+            key,
+            block: [0; 4],
+            block_pos: 4,
+            buffered: None,
+        }
+    }
+
+    /// Get the key and current counter
+    pub fn state(&self) -> ([u64; 2], [u64; 4]) {
+        (self.key, self.counter)
+    }
+}
+
+impl BitGenerator for Philox {
+    fn from_seed_sequence(seed: &SeedSequence) -> Self {
+        let key = seed.generate_state_u64(2);
+        Philox::with_key([key[0], key[1]], [0; 4])
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.block_pos < 4 {
+            let out = self.block[self.block_pos];
+            self.block_pos += 1;
+            return out;
+        }
+        // 256-bit counter increment with carry
+        for word in self.counter.iter_mut() {
+            *word = word.wrapping_add(1);
+            if *word != 0 {
+                break;
+            }
+        }
+        self.block = philox4x64(self.counter, self.key);
+        self.block_pos = 1;
+        self.block[0]
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        // Each 64-bit draw yields two 32-bit draws, low half first
+        if let Some(value) = self.buffered.take() {
+            return value;
+        }
+        let next = self.next_u64();
+        self.buffered = Some((next >> 32) as u32);
+        next as u32
+    }
+}
@@ -0,0 +1,485 @@
+//! Scalar samplers
+//!
+//! Ports of the algorithms in NumPy's `distributions.c`. Each sampler
+//! consumes bits from the generator in the same order as NumPy, so a
+//! `Generator` seeded like `default_rng(seed)` reproduces NumPy's draws.
+
+use super::{ziggurat, BitGenerator};
+
+/// Uniform integer in `[0, rng]` by Lemire's multiply-shift rejection
+///
+/// Ranges that fit in 32 bits draw 32-bit values, as NumPy does.
+pub(crate) fn bounded_u64<B: BitGenerator + ?Sized>(bg: &mut B, rng: u64) -> u64 {
+    if rng == 0 {
+        0
+    } else if rng < 0xffff_ffff {
+        let rng = rng as u32;
+        let rng_excl = rng as u64 + 1;
+        let mut m = bg.next_u32() as u64 * rng_excl;
+        let mut leftover = m & 0xffff_ffff;
+        if leftover < rng_excl {
+            let threshold = (u32::MAX - rng) as u64 % rng_excl;
+            while leftover < threshold {
+                m = bg.next_u32() as u64 * rng_excl;
+                leftover = m & 0xffff_ffff;
+            }
+        }
+        m >> 32
+    } else if rng == 0xffff_ffff {
+        bg.next_u32() as u64
+    } else if rng == u64::MAX {
+        bg.next_u64()
+    } else {
+        let rng_excl = rng as u128 + 1;
+        let mut m = bg.next_u64() as u128 * rng_excl;
+        let mut leftover = m as u64 as u128;
+        if leftover < rng_excl {
+            let threshold = (u64::MAX - rng) as u128 % rng_excl;
+            while leftover < threshold {
+                m = bg.next_u64() as u128 * rng_excl;
+                leftover = m as u64 as u128;
+            }
+        }
+        (m >> 64) as u64
+    }
+}
+
+/// Uniform integer in `[0, max]` by masked rejection (used for shuffling)
+pub(crate) fn interval<B: BitGenerator + ?Sized>(bg: &mut B, max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    let mask = u64::MAX >> max.leading_zeros();
+    if max <= 0xffff_ffff {
+        loop {
+            let value = bg.next_u32() as u64 & mask;
+            if value <= max {
+                return value;
+            }
+        }
+    }
+    loop {
+        let value = bg.next_u64() & mask;
+        if value <= max {
+            return value;
+        }
+    }
+}
+
+/// Standard normal variate by the ziggurat method
+pub(crate) fn standard_normal<B: BitGenerator + ?Sized>(bg: &mut B) -> f64 {
+    let z = ziggurat::normal();
+    loop {
+        let mut r = bg.next_u64();
+        let idx = (r & 0xff) as usize;
+        r >>= 8;
+        let sign = r & 1;
+        let rabs = (r >> 1) & 0x000f_ffff_ffff_ffff;
+        let mut x = rabs as f64 * z.w[idx];
+        if sign == 1 {
+            x = -x;
+        }
+        if rabs < z.k[idx] {
+            return x;
+        }
+        if idx == 0 {
+            // Tail beyond r, sampled with 1 - U to avoid log(0)
+            loop {
+                let xx = -(1.0 / ziggurat::NORMAL_R) * (-bg.next_f64()).ln_1p();
+                let yy = -(-bg.next_f64()).ln_1p();
+                if yy + yy > xx * xx {
+                    return if (rabs >> 8) & 1 == 1 {
+                        -(ziggurat::NORMAL_R + xx)
+                    } else {
+                        ziggurat::NORMAL_R + xx
+                    };
+                }
+            }
+        } else if (z.f[idx - 1] - z.f[idx]) * bg.next_f64() + z.f[idx] < (-0.5 * x * x).exp() {
+            return x;
+        }
+    }
+}
+
+/// Standard exponential variate by the ziggurat method
+pub(crate) fn standard_exponential<B: BitGenerator + ?Sized>(bg: &mut B) -> f64 {
+    let z = ziggurat::exponential();
+    loop {
+        let mut ri = bg.next_u64() >> 3;
+        let idx = (ri & 0xff) as usize;
+        ri >>= 8;
+        let x = ri as f64 * z.w[idx];
+        if ri < z.k[idx] {
+            return x;
+        }
+        if idx == 0 {
+            return ziggurat::EXPONENTIAL_R - (-bg.next_f64()).ln_1p();
+        }
+        if (z.f[idx - 1] - z.f[idx]) * bg.next_f64() + z.f[idx] < (-x).exp() {
+            return x;
+        }
+    }
+}
+
+/// Standard gamma variate (Marsaglia and Tsang; Johnk-style for shape < 1)
+pub(crate) fn standard_gamma<B: BitGenerator + ?Sized>(bg: &mut B, shape: f64) -> f64 {
+    if shape == 1.0 {
+        return standard_exponential(bg);
+    }
+    if shape == 0.0 {
+        return 0.0;
+    }
+    if shape < 1.0 {
+        loop {
+            let u = bg.next_f64();
+            let v = standard_exponential(bg);
+            if u <= 1.0 - shape {
+                let x = u.powf(1.0 / shape);
+                if x <= v {
+                    return x;
+                }
+            } else {
+                let y = -((1.0 - u) / shape).ln();
+                let x = (1.0 - shape + shape * y).powf(1.0 / shape);
+                if x <= v + y {
+                    return x;
+                }
+            }
+        }
+    }
+    let b = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * b).sqrt();
+    loop {
+        let (x, mut v) = loop {
+            let x = standard_normal(bg);
+            let v = 1.0 + c * x;
+            if v > 0.0 {
+                break (x, v);
+            }
+        };
+        v = v * v * v;
+        let u = bg.next_f64();
+        if u < 1.0 - 0.0331 * (x * x) * (x * x) {
+            return b * v;
+        }
+        if u.ln() < 0.5 * x * x + b * (1.0 - v + v.ln()) {
+            return b * v;
+        }
+    }
+}
+
+/// Beta variate (Johnk's algorithm when both parameters are at most 1)
+pub(crate) fn beta<B: BitGenerator + ?Sized>(bg: &mut B, a: f64, b: f64) -> f64 {
+    if a <= 1.0 && b <= 1.0 {
+        loop {
+            let u = bg.next_f64();
+            let v = bg.next_f64();
+            let x = u.powf(1.0 / a);
+            let y = v.powf(1.0 / b);
+            let xpy = x + y;
+            if xpy <= 1.0 && u + v > 0.0 {
+                if xpy > 0.0 {
+                    return x / xpy;
+                }
+                // Both powers underflowed: work in log space
+                let mut log_x = u.ln() / a;
+                let mut log_y = v.ln() / b;
+                let log_m = log_x.max(log_y);
+                log_x -= log_m;
+                log_y -= log_m;
+                return (log_x - (log_x.exp() + log_y.exp()).ln()).exp();
+            }
+        }
+    }
+    let ga = standard_gamma(bg, a);
+    let gb = standard_gamma(bg, b);
+    ga / (ga + gb)
+}
+
+/// `log(gamma(x))` by Stirling's series, as used by the Poisson sampler
+fn loggam(x: f64) -> f64 {
+    const A: [f64; 10] = [
+        8.333333333333333e-02,
+        -2.777777777777778e-03,
+        7.936507936507937e-04,
+        -5.952380952380952e-04,
+        8.417508417508418e-04,
+        -1.917526917526918e-03,
+        6.41025641025641e-03,
+        -2.955065359477124e-02,
+        1.796443723688307e-01,
+        -1.39243221690590e+00,
+    ];
+    if x == 1.0 || x == 2.0 {
+        return 0.0;
+    }
+    let n = if x < 7.0 { (7.0 - x) as i64 } else { 0 };
+    let mut x0 = x + n as f64;
+    let x2 = (1.0 / x0) * (1.0 / x0);
+    let lg2pi = 1.8378770664093453e+00;
+    let mut gl0 = A[9];
+    for &a in A[..9].iter().rev() {
+        gl0 *= x2;
+        gl0 += a;
+    }
+    let mut gl = gl0 / x0 + 0.5 * lg2pi + (x0 - 0.5) * x0.ln() - x0;
+    if x < 7.0 {
+        for _ in 0..n {
+            gl -= (x0 - 1.0).ln();
+            x0 -= 1.0;
+        }
+    }
+    gl
+}
+
+/// Poisson variate (multiplication for small means, PTRS otherwise)
+pub(crate) fn poisson<B: BitGenerator + ?Sized>(bg: &mut B, lam: f64) -> i64 {
+    if lam >= 10.0 {
+        return poisson_ptrs(bg, lam);
+    }
+    if lam == 0.0 {
+        return 0;
+    }
+    let enlam = (-lam).exp();
+    let mut x = 0;
+    let mut prod = 1.0;
+    loop {
+        prod *= bg.next_f64();
+        if prod > enlam {
+            x += 1;
+        } else {
+            return x;
+        }
+    }
+}
+
+/// Hörmann's transformed rejection with squeeze
+fn poisson_ptrs<B: BitGenerator + ?Sized>(bg: &mut B, lam: f64) -> i64 {
+    let slam = lam.sqrt();
+    let loglam = lam.ln();
+    let b = 0.931 + 2.53 * slam;
+    let a = -0.059 + 0.02483 * b;
+    let invalpha = 1.1239 + 1.1328 / (b - 3.4);
+    let vr = 0.9277 - 3.6224 / (b - 2.0);
+    loop {
+        let u = bg.next_f64() - 0.5;
+        let v = bg.next_f64();
+        let us = 0.5 - u.abs();
+        let k = ((2.0 * a / us + b) * u + lam + 0.43).floor() as i64;
+        if us >= 0.07 && v <= vr {
+            return k;
+        }
+        if k < 0 || (us < 0.013 && v > us) {
+            continue;
+        }
+        if v.ln() + invalpha.ln() - (a / (us * us) + b).ln() <= -lam + k as f64 * loglam - loggam(k as f64 + 1.0) {
+            return k;
+        }
+    }
+}
+
+/// Per-parameter setup for the binomial samplers, reused across draws
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BinomialCache {
+    key: Option<(i64, f64)>,
+    r: f64,
+    q: f64,
+    fm: f64,
+    m: i64,
+    p1: f64,
+    xm: f64,
+    xl: f64,
+    xr: f64,
+    c: f64,
+    laml: f64,
+    lamr: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+}
+
+/// Binomial variate (inversion for small `n * p`, BTPE otherwise)
+pub(crate) fn binomial<B: BitGenerator + ?Sized>(bg: &mut B, p: f64, n: i64, cache: &mut BinomialCache) -> i64 {
+    if n == 0 || p == 0.0 {
+        return 0;
+    }
+    if p <= 0.5 {
+        if p * n as f64 <= 30.0 {
+            binomial_inversion(bg, n, p, cache)
+        } else {
+            binomial_btpe(bg, n, p, cache)
+        }
+    } else {
+        let q = 1.0 - p;
+        if q * n as f64 <= 30.0 {
+            n - binomial_inversion(bg, n, q, cache)
+        } else {
+            n - binomial_btpe(bg, n, q, cache)
+        }
+    }
+}
+
+fn binomial_inversion<B: BitGenerator + ?Sized>(bg: &mut B, n: i64, p: f64, cache: &mut BinomialCache) -> i64 {
+    if cache.key != Some((n, p)) {
+        cache.key = Some((n, p));
+        cache.q = 1.0 - p;
+        cache.r = (n as f64 * cache.q.ln()).exp();
+        cache.c = n as f64 * p;
+        cache.m = (n as f64).min(cache.c + 10.0 * (cache.c * cache.q + 1.0).sqrt()) as i64;
+    }
+    let (q, qn, bound) = (cache.q, cache.r, cache.m);
+    let mut x = 0;
+    let mut px = qn;
+    let mut u = bg.next_f64();
+    while u > px {
+        x += 1;
+        if x > bound {
+            x = 0;
+            px = qn;
+            u = bg.next_f64();
+        } else {
+            u -= px;
+            px = ((n - x + 1) as f64 * p * px) / (x as f64 * q);
+        }
+    }
+    x
+}
+
+fn binomial_btpe<B: BitGenerator + ?Sized>(bg: &mut B, n: i64, p: f64, cache: &mut BinomialCache) -> i64 {
+    if cache.key != Some((n, p)) {
+        let nf = n as f64;
+        cache.key = Some((n, p));
+        cache.r = p.min(1.0 - p);
+        cache.q = 1.0 - cache.r;
+        cache.fm = nf * cache.r + cache.r;
+        cache.m = cache.fm.floor() as i64;
+        cache.p1 = (2.195 * (nf * cache.r * cache.q).sqrt() - 4.6 * cache.q).floor() + 0.5;
+        cache.xm = cache.m as f64 + 0.5;
+        cache.xl = cache.xm - cache.p1;
+        cache.xr = cache.xm + cache.p1;
+        cache.c = 0.134 + 20.5 / (15.3 + cache.m as f64);
+        let a = (cache.fm - cache.xl) / (cache.fm - cache.xl * cache.r);
+        cache.laml = a * (1.0 + a / 2.0);
+        let a = (cache.xr - cache.fm) / (cache.xr * cache.q);
+        cache.lamr = a * (1.0 + a / 2.0);
+        cache.p2 = cache.p1 * (1.0 + 2.0 * cache.c);
+        cache.p3 = cache.p2 + cache.c / cache.laml;
+        cache.p4 = cache.p3 + cache.c / cache.lamr;
+    }
+    let BinomialCache { r, q, m, p1, xm, xl, xr, c, laml, lamr, p2, p3, p4, .. } = *cache;
+    let nf = n as f64;
+    let nrq = nf * r * q;
+
+    let y = loop {
+        let u = bg.next_f64() * p4;
+        let mut v = bg.next_f64();
+        let y;
+        if u <= p1 {
+            // Triangular region: accept immediately
+            break (xm - p1 * v + u).floor() as i64;
+        } else if u <= p2 {
+            // Parallelograms
+            let x = xl + (u - p1) / c;
+            v = v * c + 1.0 - (m as f64 - x + 0.5).abs() / p1;
+            if v > 1.0 {
+                continue;
+            }
+            y = x.floor() as i64;
+        } else if u <= p3 {
+            // Left exponential tail
+            y = (xl + v.ln() / laml).floor() as i64;
+            if y < 0 || v == 0.0 {
+                continue;
+            }
+            v *= (u - p2) * laml;
+        } else {
+            // Right exponential tail
+            y = (xr - v.ln() / lamr).floor() as i64;
+            if y > n || v == 0.0 {
+                continue;
+            }
+            v *= (u - p3) * lamr;
+        }
+
+        let k = (y - m).abs();
+        if !(k > 20 && (k as f64) < nrq / 2.0 - 1.0) {
+            // Explicit evaluation of f(y) / f(m)
+            let s = r / q;
+            let a = s * (nf + 1.0);
+            let mut f = 1.0;
+            if m < y {
+                for i in m + 1..=y {
+                    f *= a / i as f64 - s;
+                }
+            } else if m > y {
+                for i in y + 1..=m {
+                    f /= a / i as f64 - s;
+                }
+            }
+            if v > f {
+                continue;
+            }
+            break y;
+        }
+
+        // Squeeze using upper and lower bounds on log(f(y))
+        let kf = k as f64;
+        let rho = (kf / nrq) * ((kf * (kf / 3.0 + 0.625) + 0.16666666666666666) / nrq + 0.5);
+        let t = -kf * kf / (2.0 * nrq);
+        let big_a = v.ln();
+        if big_a < t - rho {
+            break y;
+        }
+        if big_a > t + rho {
+            continue;
+        }
+        let x1 = y as f64 + 1.0;
+        let f1 = m as f64 + 1.0;
+        let z = nf + 1.0 - m as f64;
+        let w = nf - y as f64 + 1.0;
+        let (x2, f2, z2, w2) = (x1 * x1, f1 * f1, z * z, w * w);
+        let stirling = |v: f64, v2: f64| (13680. - (462. - (132. - (99. - 140. / v2) / v2) / v2) / v2) / v / 166320.;
+        let bound = xm * (f1 / x1).ln()
+            + (nf - m as f64 + 0.5) * (z / w).ln()
+            + (y - m) as f64 * (w * r / (x1 * q)).ln()
+            + stirling(f1, f2)
+            + stirling(z, z2)
+            + stirling(x1, x2)
+            + stirling(w, w2);
+        if big_a > bound {
+            continue;
+        }
+        break y;
+    };
+
+    if p > 0.5 {
+        n - y
+    } else {
+        y
+    }
+}
+
+/// Multinomial counts for `n` trials over probabilities `pvals`, written to `out`
+pub(crate) fn multinomial<B: BitGenerator + ?Sized>(
+    bg: &mut B,
+    n: i64,
+    pvals: &[f64],
+    out: &mut [i64],
+    cache: &mut BinomialCache,
+) {
+    out.fill(0);
+    let d = pvals.len();
+    let mut remaining_p = 1.0;
+    let mut dn = n;
+    for j in 0..d - 1 {
+        out[j] = binomial(bg, pvals[j] / remaining_p, dn, cache);
+        dn -= out[j];
+        if dn <= 0 {
+            break;
+        }
+        remaining_p -= pvals[j];
+    }
+    if dn > 0 {
+        out[d - 1] = dn;
+    }
+}
@@ -0,0 +1,585 @@
+//! Random generator
+//!
+//! `Generator` draws samples from a bit generator into new arrays,
+//! equivalent to NumPy's `numpy.random.Generator`. With the default
+//! `Pcg64` bit generator, `default_rng(seed)` produces the same streams as
+//! `numpy.random.default_rng(seed)`.
+
+use crate::array::{Array, ArrayError};
+use crate::types::{DType, NpyType};
+use crate::utils::element_offsets;
+use rayon::prelude::*;
+
+use super::distributions::{self, BinomialCache};
+use super::{BitGenerator, Pcg64, SeedSequence};
+
+/// Number of elements drawn from each independent stream by the parallel fills
+pub const PARALLEL_CHUNK: usize = 1 << 16;
+
+/// Random generator error
+#[derive(Debug, Clone)]
+pub enum RandomError {
+    /// Array error
+    ArrayError(ArrayError),
+    /// Distribution parameter out of range
+    InvalidParameter(String),
+    /// Generator has no seed sequence to spawn streams from
+    NoSeedSequence,
+    /// Array is not writeable
+    ReadOnly,
+}
+
+impl std::fmt::Display for RandomError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RandomError::ArrayError(e) => write!(f, "Array error: {}", e),
+            RandomError::InvalidParameter(msg) => write!(f, "{}", msg),
+            RandomError::NoSeedSequence => write!(f, "Generator was not seeded from a SeedSequence"),
+            RandomError::ReadOnly => write!(f, "Array is read-only"),
+        }
+    }
+}
+
+impl std::error::Error for RandomError {}
+
+impl From<ArrayError> for RandomError {
+    fn from(err: ArrayError) -> Self {
+        RandomError::ArrayError(err)
+    }
+}
+
+fn check(condition: bool, message: &str) -> Result<(), RandomError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(RandomError::InvalidParameter(message.to_string()))
+    }
+}
+
+/// Allocate an output array, rejecting negative dimensions
+fn output(shape: Vec<i64>, dtype: NpyType) -> Result<Array, RandomError> {
+    if shape.iter().any(|&d| d < 0) {
+        return Err(ArrayError::InvalidShape.into());
+    }
+    Ok(Array::new(shape, DType::new(dtype))?)
+}
+
+/// Element count of a shape
+fn shape_size(shape: &[i64]) -> usize {
+    shape.iter().product::<i64>().max(0) as usize
+}
+
+/// Smallest `2^k - 1` that is at least `value`
+fn gen_mask(value: u64) -> u64 {
+    u64::MAX >> (value | 1).leading_zeros()
+}
+
+/// Create a generator using the default `Pcg64` bit generator
+///
+/// Equivalent to `numpy.random.default_rng(seed)`.
+pub fn default_rng(seed: u128) -> Generator {
+    Generator::from_seed_sequence(SeedSequence::new(seed))
+}
+
+/// Random number generator over a bit generator `B`
+#[derive(Debug, Clone)]
+pub struct Generator<B: BitGenerator = Pcg64> {
+    bit_generator: B,
+    seed_seq: Option<SeedSequence>,
+    binomial: BinomialCache,
+}
+
+impl Generator<Pcg64> {
+    /// Create a generator seeded from fresh entropy
+    pub fn from_entropy() -> Self {
+        Generator::from_seed_sequence(SeedSequence::from_entropy())
+    }
+}
+
+impl<B: BitGenerator> Generator<B> {
+    /// Wrap an already-seeded bit generator
+    ///
+    /// Such a generator cannot `spawn` child streams.
+    pub fn new(bit_generator: B) -> Self {
+        Generator {
+            bit_generator,
+            seed_seq: None,
+            binomial: BinomialCache::default(),
+        }
+    }
+
+    /// Create a generator whose bit generator is seeded from `seed_seq`
+    pub fn from_seed_sequence(seed_seq: SeedSequence) -> Self {
+        Generator {
+            bit_generator: B::from_seed_sequence(&seed_seq),
+            seed_seq: Some(seed_seq),
+            binomial: BinomialCache::default(),
+        }
+    }
+
+    /// Get the underlying bit generator
+    pub fn bit_generator(&self) -> &B {
+        &self.bit_generator
+    }
+
+    /// Get the underlying bit generator mutably
+    pub fn bit_generator_mut(&mut self) -> &mut B {
+        &mut self.bit_generator
+    }
+
+    /// Get the seed sequence this generator was created from, if any
+    pub fn seed_sequence(&self) -> Option<&SeedSequence> {
+        self.seed_seq.as_ref()
+    }
+
+    /// Create `n_children` generators with independent streams
+    ///
+    /// Equivalent to `Generator.spawn`: each child is seeded from a child
+    /// of this generator's seed sequence.
+    pub fn spawn(&mut self, n_children: usize) -> Result<Vec<Generator<B>>, RandomError> {
+        let seed_seq = self.seed_seq.as_mut().ok_or(RandomError::NoSeedSequence)?;
+        Ok(seed_seq.spawn(n_children).into_iter().map(Generator::from_seed_sequence).collect())
+    }
+
+    /// Allocate an array of `shape` and fill it element by element
+    fn fill<T: Copy>(
+        &mut self,
+        shape: Vec<i64>,
+        dtype: NpyType,
+        mut sample: impl FnMut(&mut Self) -> T,
+    ) -> Result<Array, RandomError> {
+        let mut array = output(shape, dtype)?;
+        for v in unsafe { array.as_slice_mut::<T>() } {
+            *v = sample(self);
+        }
+        Ok(array)
+    }
+
+    /// Uniform doubles in `[0, 1)`
+    pub fn random(&mut self, shape: Vec<i64>) -> Result<Array, RandomError> {
+        self.fill(shape, NpyType::Double, |g| g.bit_generator.next_f64())
+    }
+
+    /// Uniform doubles in `[low, high)`
+    pub fn uniform(&mut self, low: f64, high: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        let range = high - low;
+        check(range.is_finite(), "Range exceeds valid bounds")?;
+        self.fill(shape, NpyType::Double, |g| low + range * g.bit_generator.next_f64())
+    }
+
+    /// Uniform `int64` values in `[low, high)`, or `[low, high]` with `endpoint`
+    pub fn integers(&mut self, low: i64, high: i64, shape: Vec<i64>, endpoint: bool) -> Result<Array, RandomError> {
+        let high = if endpoint {
+            high
+        } else {
+            check(high > low, "low >= high")?;
+            high - 1
+        };
+        check(high >= low, "low > high")?;
+        let rng = high.wrapping_sub(low) as u64;
+        self.fill(shape, NpyType::Long, |g| {
+            low.wrapping_add(distributions::bounded_u64(&mut g.bit_generator, rng) as i64)
+        })
+    }
+
+    /// Standard normal samples (ziggurat method)
+    pub fn standard_normal(&mut self, shape: Vec<i64>) -> Result<Array, RandomError> {
+        self.fill(shape, NpyType::Double, |g| distributions::standard_normal(&mut g.bit_generator))
+    }
+
+    /// Normal samples with mean `loc` and standard deviation `scale`
+    pub fn normal(&mut self, loc: f64, scale: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(scale >= 0.0, "scale < 0")?;
+        self.fill(shape, NpyType::Double, |g| {
+            loc + scale * distributions::standard_normal(&mut g.bit_generator)
+        })
+    }
+
+    /// Standard exponential samples (ziggurat method)
+    pub fn standard_exponential(&mut self, shape: Vec<i64>) -> Result<Array, RandomError> {
+        self.fill(shape, NpyType::Double, |g| distributions::standard_exponential(&mut g.bit_generator))
+    }
+
+    /// Exponential samples with mean `scale`
+    pub fn exponential(&mut self, scale: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(scale >= 0.0, "scale < 0")?;
+        self.fill(shape, NpyType::Double, |g| {
+            scale * distributions::standard_exponential(&mut g.bit_generator)
+        })
+    }
+
+    /// Gamma samples with shape `k` and unit scale
+    pub fn standard_gamma(&mut self, k: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        self.gamma(k, 1.0, shape)
+    }
+
+    /// Gamma samples with shape `k` and scale `scale`
+    pub fn gamma(&mut self, k: f64, scale: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(k >= 0.0, "shape < 0")?;
+        check(scale >= 0.0, "scale < 0")?;
+        self.fill(shape, NpyType::Double, |g| {
+            scale * distributions::standard_gamma(&mut g.bit_generator, k)
+        })
+    }
+
+    /// Beta samples with parameters `a` and `b`
+    pub fn beta(&mut self, a: f64, b: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(a > 0.0, "a <= 0")?;
+        check(b > 0.0, "b <= 0")?;
+        self.fill(shape, NpyType::Double, |g| distributions::beta(&mut g.bit_generator, a, b))
+    }
+
+    /// Binomial samples: successes in `n` trials with probability `p`
+    pub fn binomial(&mut self, n: i64, p: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(n >= 0, "n < 0")?;
+        check((0.0..=1.0).contains(&p), "p < 0, p > 1 or p is NaN")?;
+        self.fill(shape, NpyType::Long, |g| {
+            distributions::binomial(&mut g.bit_generator, p, n, &mut g.binomial)
+        })
+    }
+
+    /// Poisson samples with rate `lam`
+    pub fn poisson(&mut self, lam: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        // Largest rate whose samples still fit in int64
+        let lam_max = i64::MAX as f64 - ((i64::MAX as f64).sqrt() * 10.0);
+        check(lam >= 0.0, "lam < 0 or lam is NaN")?;
+        check(lam <= lam_max, "lam value too large")?;
+        self.fill(shape, NpyType::Long, |g| distributions::poisson(&mut g.bit_generator, lam))
+    }
+
+    /// Multinomial counts of `n` trials over `pvals`
+    ///
+    /// The result has shape `shape + [pvals.len()]`. As in NumPy, the last
+    /// probability is implied by the others.
+    pub fn multinomial(&mut self, n: i64, pvals: &[f64], shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(n >= 0, "n < 0")?;
+        check(!pvals.is_empty(), "pvals must have at least 1 element")?;
+        check(pvals.iter().all(|p| (0.0..=1.0).contains(p)), "pvals < 0, pvals > 1 or pvals contains NaNs")?;
+        check(pvals[..pvals.len() - 1].iter().sum::<f64>() <= 1.0 + 1e-12, "sum(pvals[:-1]) > 1.0")?;
+        let d = pvals.len();
+        let count = shape_size(&shape);
+        let mut out_shape = shape;
+        out_shape.push(d as i64);
+        let mut array = output(out_shape, NpyType::Long)?;
+        let values = unsafe { array.as_slice_mut::<i64>() };
+        for i in 0..count {
+            distributions::multinomial(
+                &mut self.bit_generator,
+                n,
+                pvals,
+                &mut values[i * d..(i + 1) * d],
+                &mut self.binomial,
+            );
+        }
+        Ok(array)
+    }
+
+    /// Fisher-Yates shuffle of `data[first..]` drawing indices with Lemire's method
+    fn shuffle_indices(&mut self, data: &mut [i64], first: usize) {
+        for i in (first.max(1)..data.len()).rev() {
+            let j = distributions::bounded_u64(&mut self.bit_generator, i as u64) as usize;
+            data.swap(i, j);
+        }
+    }
+
+    /// Indices `0..pop_size` sampled for `choice`, in NumPy's draw order
+    fn choice_indices(
+        &mut self,
+        pop_size: i64,
+        shape: Vec<i64>,
+        replace: bool,
+        p: Option<&[f64]>,
+        shuffle: bool,
+    ) -> Result<Vec<i64>, RandomError> {
+        let size = shape_size(&shape);
+        check(pop_size > 0 || size == 0, "a cannot be empty unless no samples are taken")?;
+        if let Some(p) = p {
+            check(p.len() as i64 == pop_size, "a and p must have same size")?;
+            check(p.iter().all(|&v| v >= 0.0), "probabilities are not non-negative")?;
+            let atol = f64::EPSILON.sqrt();
+            check((p.iter().sum::<f64>() - 1.0).abs() <= atol, "probabilities do not sum to 1")?;
+        }
+
+        if replace {
+            return Ok(match p {
+                Some(p) => {
+                    let mut cdf: Vec<f64> = p
+                        .iter()
+                        .scan(0.0, |acc, &v| {
+                            *acc += v;
+                            Some(*acc)
+                        })
+                        .collect();
+                    let total = cdf[cdf.len() - 1];
+                    cdf.iter_mut().for_each(|c| *c /= total);
+                    (0..size)
+                        .map(|_| {
+                            let u = self.bit_generator.next_f64();
+                            cdf.partition_point(|&c| c <= u) as i64
+                        })
+                        .collect()
+                }
+                None => {
+                    let rng = (pop_size - 1) as u64;
+                    (0..size)
+                        .map(|_| distributions::bounded_u64(&mut self.bit_generator, rng) as i64)
+                        .collect()
+                }
+            });
+        }
+
+        check(
+            size as i64 <= pop_size,
+            "Cannot take a larger sample than population when replace is false",
+        )?;
+
+        if let Some(p) = p {
+            check(
+                p.iter().filter(|&&v| v > 0.0).count() >= size,
+                "Fewer non-zero entries in p than size",
+            )?;
+            // Repeatedly draw with replacement, zeroing found entries, until
+            // enough unique indices are collected
+            let mut p = p.to_vec();
+            let mut found: Vec<i64> = Vec::with_capacity(size);
+            while found.len() < size {
+                for &idx in &found {
+                    p[idx as usize] = 0.0;
+                }
+                let mut cdf: Vec<f64> = p
+                    .iter()
+                    .scan(0.0, |acc, &v| {
+                        *acc += v;
+                        Some(*acc)
+                    })
+                    .collect();
+                let total = cdf[cdf.len() - 1];
+                cdf.iter_mut().for_each(|c| *c /= total);
+                let draws: Vec<i64> = (0..size - found.len())
+                    .map(|_| {
+                        let u = self.bit_generator.next_f64();
+                        cdf.partition_point(|&c| c <= u) as i64
+                    })
+                    .collect();
+                let mut seen = std::collections::HashSet::new();
+                for idx in draws {
+                    if seen.insert(idx) {
+                        found.push(idx);
+                    }
+                }
+            }
+            return Ok(found);
+        }
+
+        let pop = pop_size as usize;
+        let cutoff = if shuffle { 50 } else { 20 };
+        if pop > 10000 && size > pop / cutoff {
+            // Partial Fisher-Yates over the tail of the population
+            let mut idx: Vec<i64> = (0..pop_size).collect();
+            self.shuffle_indices(&mut idx, pop.saturating_sub(size).max(1));
+            return Ok(idx.split_off(pop - size));
+        }
+
+        // Floyd's algorithm with an open-addressing hash set
+        let mut idx = vec![0i64; size];
+        let mask = gen_mask((1.2 * size as f64) as u64);
+        let mut hash_set = vec![u64::MAX; mask as usize + 1];
+        for j in (pop - size) as u64..pop as u64 {
+            let val = distributions::bounded_u64(&mut self.bit_generator, j);
+            let mut loc = (val & mask) as usize;
+            while hash_set[loc] != u64::MAX && hash_set[loc] != val {
+                loc = (loc + 1) & mask as usize;
+            }
+            let chosen = if hash_set[loc] == u64::MAX {
+                hash_set[loc] = val;
+                val
+            } else {
+                // val was already taken, so take j itself
+                let mut loc = (j & mask) as usize;
+                while hash_set[loc] != u64::MAX {
+                    loc = (loc + 1) & mask as usize;
+                }
+                hash_set[loc] = j;
+                j
+            };
+            idx[(j - (pop - size) as u64) as usize] = chosen as i64;
+        }
+        if shuffle {
+            self.shuffle_indices(&mut idx, 1);
+        }
+        Ok(idx)
+    }
+
+    /// Random sample of integers from `0..n`
+    ///
+    /// Equivalent to `Generator.choice(n, size, replace, p, shuffle=shuffle)`.
+    pub fn choice_n(
+        &mut self,
+        n: i64,
+        shape: Vec<i64>,
+        replace: bool,
+        p: Option<&[f64]>,
+        shuffle: bool,
+    ) -> Result<Array, RandomError> {
+        check(n >= 0, "a must be a positive integer unless no samples are taken")?;
+        let idx = self.choice_indices(n, shape.clone(), replace, p, shuffle)?;
+        let mut array = output(shape, NpyType::Long)?;
+        unsafe { array.as_slice_mut::<i64>() }.copy_from_slice(&idx);
+        Ok(array)
+    }
+
+    /// Random sample of entries of `a` along its first axis
+    ///
+    /// The result has shape `shape + a.shape[1..]` and the dtype of `a`.
+    pub fn choice(
+        &mut self,
+        a: &Array,
+        shape: Vec<i64>,
+        replace: bool,
+        p: Option<&[f64]>,
+        shuffle: bool,
+    ) -> Result<Array, RandomError> {
+        check(a.ndim() >= 1, "a must be a sequence or an integer, not 0-d")?;
+        let idx = self.choice_indices(a.shape()[0], shape.clone(), replace, p, shuffle)?;
+
+        let mut out_shape = shape;
+        out_shape.extend_from_slice(&a.shape()[1..]);
+        let mut out = Array::new(out_shape, a.dtype().clone())?;
+        let itemsize = a.itemsize();
+        let row_offsets = element_offsets(&a.shape()[1..], &a.strides()[1..]);
+        let row_bytes = row_offsets.len() * itemsize;
+        let src = a.data_ptr();
+        let dst = out.data_ptr_mut();
+        for (k, &i) in idx.iter().enumerate() {
+            let base = i as isize * a.strides()[0] as isize;
+            for (e, &offset) in row_offsets.iter().enumerate() {
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        src.offset(base + offset),
+                        dst.add(k * row_bytes + e * itemsize),
+                        itemsize,
+                    );
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Shuffle `array` in place along its first axis
+    pub fn shuffle(&mut self, array: &mut Array) -> Result<(), RandomError> {
+        if !array.is_writeable() {
+            return Err(RandomError::ReadOnly);
+        }
+        if array.ndim() == 0 {
+            return Err(RandomError::InvalidParameter("array must be at least 1-dimensional".to_string()));
+        }
+        let n = array.shape()[0] as u64;
+        let stride0 = array.strides()[0] as isize;
+        let itemsize = array.itemsize();
+        let row_offsets = element_offsets(&array.shape()[1..], &array.strides()[1..]);
+        let data = array.data_ptr_mut();
+        for i in (1..n).rev() {
+            let j = distributions::interval(&mut self.bit_generator, i);
+            if i == j {
+                continue;
+            }
+            for &offset in &row_offsets {
+                unsafe {
+                    let a = data.offset(i as isize * stride0 + offset);
+                    let b = data.offset(j as isize * stride0 + offset);
+                    std::ptr::swap_nonoverlapping(a, b, itemsize);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Random permutation of `0..n` as `int64`
+    pub fn permutation_n(&mut self, n: i64) -> Result<Array, RandomError> {
+        check(n >= 0, "n < 0")?;
+        let values: Vec<i64> = (0..n).collect();
+        let mut array = Array::from_slice(&values, vec![n], DType::new(NpyType::Long))?;
+        self.shuffle(&mut array)?;
+        Ok(array)
+    }
+
+    /// Copy of `array` with its first axis randomly permuted
+    pub fn permutation(&mut self, array: &Array) -> Result<Array, RandomError> {
+        let mut copy = Array::new(array.shape().to_vec(), array.dtype().clone())?;
+        let bytes = crate::utils::to_contiguous_bytes(array);
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), copy.data_ptr_mut(), bytes.len());
+        }
+        self.shuffle(&mut copy)?;
+        Ok(copy)
+    }
+}
+
+impl<B: BitGenerator> Generator<B> {
+    /// Fill `out` in parallel, one spawned child stream per `PARALLEL_CHUNK` elements
+    ///
+    /// The result depends only on the seed and the number of streams
+    /// spawned so far, not on the number of threads. It differs from the
+    /// sequential stream of the same generator.
+    pub fn fill_parallel<T, F>(&mut self, out: &mut [T], sample: F) -> Result<(), RandomError>
+    where
+        T: Send,
+        F: Fn(&mut Generator<B>, &mut [T]) + Sync,
+    {
+        let n_chunks = out.len().div_ceil(PARALLEL_CHUNK);
+        let mut children = self.spawn(n_chunks)?;
+        out.par_chunks_mut(PARALLEL_CHUNK)
+            .zip(children.par_iter_mut())
+            .for_each(|(chunk, child)| sample(child, chunk));
+        Ok(())
+    }
+
+    /// Allocate an array and fill it in parallel from independent streams
+    fn fill_parallel_array<T: Copy + Send>(
+        &mut self,
+        shape: Vec<i64>,
+        dtype: NpyType,
+        sample: impl Fn(&mut B) -> T + Sync,
+    ) -> Result<Array, RandomError> {
+        let mut array = output(shape, dtype)?;
+        self.fill_parallel(unsafe { array.as_slice_mut::<T>() }, |g, chunk| {
+            for v in chunk.iter_mut() {
+                *v = sample(&mut g.bit_generator);
+            }
+        })?;
+        Ok(array)
+    }
+
+    /// Uniform doubles in `[0, 1)`, drawn in parallel
+    pub fn random_parallel(&mut self, shape: Vec<i64>) -> Result<Array, RandomError> {
+        self.fill_parallel_array(shape, NpyType::Double, |bg| bg.next_f64())
+    }
+
+    /// Uniform doubles in `[low, high)`, drawn in parallel
+    pub fn uniform_parallel(&mut self, low: f64, high: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        let range = high - low;
+        check(range.is_finite(), "Range exceeds valid bounds")?;
+        self.fill_parallel_array(shape, NpyType::Double, |bg| low + range * bg.next_f64())
+    }
+
+    /// Uniform `int64` values in `[low, high)`, drawn in parallel
+    pub fn integers_parallel(&mut self, low: i64, high: i64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(high > low, "low >= high")?;
+        let rng = (high - 1).wrapping_sub(low) as u64;
+        self.fill_parallel_array(shape, NpyType::Long, |bg| {
+            low.wrapping_add(distributions::bounded_u64(bg, rng) as i64)
+        })
+    }
+
+    /// Normal samples, drawn in parallel
+    pub fn normal_parallel(&mut self, loc: f64, scale: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(scale >= 0.0, "scale < 0")?;
+        self.fill_parallel_array(shape, NpyType::Double, |bg| loc + scale * distributions::standard_normal(bg))
+    }
+
+    /// Exponential samples, drawn in parallel
+    pub fn exponential_parallel(&mut self, scale: f64, shape: Vec<i64>) -> Result<Array, RandomError> {
+        check(scale >= 0.0, "scale < 0")?;
+        self.fill_parallel_array(shape, NpyType::Double, |bg| scale * distributions::standard_exponential(bg))
+    }
+}
@@ -0,0 +1,16 @@
+//! Random number generation module
+//!
+//! This module provides NumPy-compatible random sampling: the `PCG64` and
+//! `Philox` bit generators, `SeedSequence` seeding and spawning, and a
+//! `Generator` that fills arrays from common distributions, equivalent to
+//! `numpy.random.Generator`
+
+mod bit_generator;
+mod distributions;
+mod generator;
+mod seed_sequence;
+mod ziggurat;
+
+pub use bit_generator::*;
+pub use generator::*;
+pub use seed_sequence::*;
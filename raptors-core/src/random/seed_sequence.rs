@@ -0,0 +1,189 @@
+//! Seed sequences
+//!
+//! A reimplementation of NumPy's `SeedSequence`: user entropy is hashed
+//! into a small pool with the same mixing constants, so the bit generators
+//! derive the same initial state as `numpy.random.default_rng(seed)`.
+//! Spawning appends the child index to the spawn key, giving independent
+//! streams for parallel work.
+
+const DEFAULT_POOL_SIZE: usize = 4;
+const INIT_A: u32 = 0x43b0d7e5;
+const MULT_A: u32 = 0x931e8875;
+const INIT_B: u32 = 0x8b51f9dd;
+const MULT_B: u32 = 0x58f38ded;
+const MIX_MULT_L: u32 = 0xca01f9dd;
+const MIX_MULT_R: u32 = 0x4973f715;
+const XSHIFT: u32 = 16;
+
+/// Split an integer into little-endian 32-bit words (`0` gives `[0]`)
+fn coerce_to_words(mut value: u128) -> Vec<u32> {
+    if value == 0 {
+        return vec![0];
+    }
+    let mut words = Vec::new();
+    while value > 0 {
+        words.push(value as u32);
+        value >>= 32;
+    }
+    words
+}
+
+fn hashmix(value: u32, hash_const: &mut u32) -> u32 {
+    let mut value = value ^ *hash_const;
+    *hash_const = hash_const.wrapping_mul(MULT_A);
+    value = value.wrapping_mul(*hash_const);
+    value ^ (value >> XSHIFT)
+}
+
+fn mix(x: u32, y: u32) -> u32 {
+    let result = MIX_MULT_L.wrapping_mul(x).wrapping_sub(MIX_MULT_R.wrapping_mul(y));
+    result ^ (result >> XSHIFT)
+}
+
+/// Entropy pool that seeds bit generators, equivalent to NumPy's `SeedSequence`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedSequence {
+    entropy: Vec<u32>,
+    spawn_key: Vec<u64>,
+    pool: Vec<u32>,
+    n_children_spawned: u64,
+}
+
+impl SeedSequence {
+    /// Create a seed sequence from an integer seed
+    pub fn new(seed: u128) -> Self {
+        Self::from_words(coerce_to_words(seed), Vec::new())
+    }
+
+    /// Create a seed sequence from a sequence of integer seeds
+    ///
+    /// Equivalent to `SeedSequence([a, b, ...])`; each value contributes
+    /// its 32-bit words in order.
+    pub fn from_seeds(seeds: &[u128]) -> Self {
+        let entropy = seeds.iter().flat_map(|&s| coerce_to_words(s)).collect();
+        Self::from_words(entropy, Vec::new())
+    }
+
+    /// Create a seed sequence from fresh, unpredictable entropy
+    ///
+    /// Mixes the process's randomized hasher state with the current time,
+    /// playing the role of `SeedSequence(None)`.
+    pub fn from_entropy() -> Self {
+        use std::collections::hash_map::RandomState;
+        use std::hash::{BuildHasher, Hasher};
+
+        let mut words = Vec::with_capacity(4);
+        for i in 0..2u64 {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(i);
+            if let Ok(elapsed) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+                hasher.write_u128(elapsed.as_nanos());
+            }
+            let value = hasher.finish();
+            words.push(value as u32);
+            words.push((value >> 32) as u32);
+        }
+        Self::from_words(words, Vec::new())
+    }
+
+    /// Create a seed sequence from entropy words and a spawn key
+    fn from_words(entropy: Vec<u32>, spawn_key: Vec<u64>) -> Self {
+        let mut seq = SeedSequence {
+            entropy,
+            spawn_key,
+            pool: vec![0; DEFAULT_POOL_SIZE],
+            n_children_spawned: 0,
+        };
+        let assembled = seq.assembled_entropy();
+        seq.mix_entropy(&assembled);
+        seq
+    }
+
+    /// Run entropy followed by the spawn key, zero-padded so the two can't collide
+    fn assembled_entropy(&self) -> Vec<u32> {
+        let mut words = self.entropy.clone();
+        let spawn_words: Vec<u32> = self.spawn_key.iter().flat_map(|&k| coerce_to_words(k as u128)).collect();
+        if !spawn_words.is_empty() && words.len() < self.pool.len() {
+            words.resize(self.pool.len(), 0);
+        }
+        words.extend(spawn_words);
+        words
+    }
+
+    fn mix_entropy(&mut self, entropy: &[u32]) {
+        let mut hash_const = INIT_A;
+        let n = self.pool.len();
+        for i in 0..n {
+            self.pool[i] = hashmix(entropy.get(i).copied().unwrap_or(0), &mut hash_const);
+        }
+        for i_src in 0..n {
+            for i_dst in 0..n {
+                if i_src != i_dst {
+                    let hashed = hashmix(self.pool[i_src], &mut hash_const);
+                    self.pool[i_dst] = mix(self.pool[i_dst], hashed);
+                }
+            }
+        }
+        for &word in entropy.iter().skip(n) {
+            for i_dst in 0..n {
+                let hashed = hashmix(word, &mut hash_const);
+                self.pool[i_dst] = mix(self.pool[i_dst], hashed);
+            }
+        }
+    }
+
+    /// Get the entropy words this sequence was created from
+    pub fn entropy(&self) -> &[u32] {
+        &self.entropy
+    }
+
+    /// Get the spawn key identifying this sequence among its siblings
+    pub fn spawn_key(&self) -> &[u64] {
+        &self.spawn_key
+    }
+
+    /// Number of children spawned so far
+    pub fn n_children_spawned(&self) -> u64 {
+        self.n_children_spawned
+    }
+
+    /// Generate `n_words` 32-bit words of seed state
+    pub fn generate_state(&self, n_words: usize) -> Vec<u32> {
+        let mut hash_const = INIT_B;
+        (0..n_words)
+            .map(|i| {
+                let mut value = self.pool[i % self.pool.len()] ^ hash_const;
+                hash_const = hash_const.wrapping_mul(MULT_B);
+                value = value.wrapping_mul(hash_const);
+                value ^ (value >> XSHIFT)
+            })
+            .collect()
+    }
+
+    /// Generate `n_words` 64-bit words of seed state
+    ///
+    /// Pairs of 32-bit words are combined little-endian, matching
+    /// `generate_state(n, np.uint64)`.
+    pub fn generate_state_u64(&self, n_words: usize) -> Vec<u64> {
+        self.generate_state(2 * n_words)
+            .chunks_exact(2)
+            .map(|pair| pair[0] as u64 | ((pair[1] as u64) << 32))
+            .collect()
+    }
+
+    /// Spawn `n_children` independent child sequences
+    ///
+    /// Children continue numbering from any previously spawned ones, so
+    /// repeated calls never hand out the same stream twice.
+    pub fn spawn(&mut self, n_children: usize) -> Vec<SeedSequence> {
+        let start = self.n_children_spawned;
+        self.n_children_spawned += n_children as u64;
+        (start..self.n_children_spawned)
+            .map(|i| {
+                let mut key = self.spawn_key.clone();
+                key.push(i);
+                Self::from_words(self.entropy.clone(), key)
+            })
+            .collect()
+    }
+}
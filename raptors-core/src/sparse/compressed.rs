@@ -0,0 +1,407 @@
+//! Shared kernels for compressed sparse formats
+//!
+//! CSR and CSC store the same structure with the roles of rows and columns
+//! swapped. The functions here operate on the "major" axis (rows for CSR,
+//! columns for CSC) and the "minor" axis, so both formats share one
+//! implementation.
+
+use crate::indexing::{normalize_slice, slice_length, Slice};
+
+use super::SparseError;
+
+/// Compressed storage: `(indptr, indices, data)`
+pub(crate) type Compressed = (Vec<usize>, Vec<usize>, Vec<f64>);
+
+/// Validate compressed index arrays
+pub(crate) fn validate(
+    n_major: usize,
+    n_minor: usize,
+    indptr: &[usize],
+    indices: &[usize],
+    data: &[f64],
+) -> Result<(), SparseError> {
+    if indptr.len() != n_major + 1 {
+        return Err(SparseError::InvalidStructure(format!(
+            "indptr has length {}, expected {}",
+            indptr.len(),
+            n_major + 1
+        )));
+    }
+    if indices.len() != data.len() {
+        return Err(SparseError::InvalidStructure(
+            "indices and data must have the same length".to_string(),
+        ));
+    }
+    if indptr[0] != 0 || indptr[n_major] != data.len() {
+        return Err(SparseError::InvalidStructure(
+            "indptr must start at 0 and end at nnz".to_string(),
+        ));
+    }
+    if indptr.windows(2).any(|w| w[0] > w[1]) {
+        return Err(SparseError::InvalidStructure(
+            "indptr must be non-decreasing".to_string(),
+        ));
+    }
+    if indices.iter().any(|&i| i >= n_minor) {
+        return Err(SparseError::IndexOutOfBounds);
+    }
+    Ok(())
+}
+
+/// Build compressed storage from coordinate triplets
+///
+/// Duplicate coordinates are summed and minor indices are sorted.
+pub(crate) fn compress(n_major: usize, major: &[usize], minor: &[usize], data: &[f64]) -> Compressed {
+    // Counting sort on the major index
+    let mut indptr = vec![0usize; n_major + 1];
+    for &m in major {
+        indptr[m + 1] += 1;
+    }
+    for i in 0..n_major {
+        indptr[i + 1] += indptr[i];
+    }
+    let mut next = indptr.clone();
+    let mut indices = vec![0usize; data.len()];
+    let mut values = vec![0.0; data.len()];
+    for ((&m, &n), &v) in major.iter().zip(minor).zip(data) {
+        let dest = next[m];
+        indices[dest] = n;
+        values[dest] = v;
+        next[m] += 1;
+    }
+    sum_duplicates(n_major, &indptr, &indices, &values)
+}
+
+/// Sort minor indices within each major slot and sum duplicates
+pub(crate) fn sum_duplicates(n_major: usize, indptr: &[usize], indices: &[usize], data: &[f64]) -> Compressed {
+    let mut out_indptr = Vec::with_capacity(n_major + 1);
+    let mut out_indices = Vec::with_capacity(indices.len());
+    let mut out_data = Vec::with_capacity(data.len());
+    out_indptr.push(0);
+    let mut slot: Vec<(usize, f64)> = Vec::new();
+    for m in 0..n_major {
+        slot.clear();
+        slot.extend((indptr[m]..indptr[m + 1]).map(|k| (indices[k], data[k])));
+        slot.sort_by_key(|&(i, _)| i);
+        let mut k = 0;
+        while k < slot.len() {
+            let (idx, mut v) = slot[k];
+            k += 1;
+            while k < slot.len() && slot[k].0 == idx {
+                v += slot[k].1;
+                k += 1;
+            }
+            out_indices.push(idx);
+            out_data.push(v);
+        }
+        out_indptr.push(out_indices.len());
+    }
+    (out_indptr, out_indices, out_data)
+}
+
+/// Check whether minor indices are sorted and unique within each major slot
+pub(crate) fn is_canonical(indptr: &[usize], indices: &[usize]) -> bool {
+    indptr
+        .windows(2)
+        .all(|w| indices[w[0]..w[1]].windows(2).all(|p| p[0] < p[1]))
+}
+
+/// Swap the major and minor axes (CSR <-> CSC of the same matrix)
+pub(crate) fn transpose(n_minor: usize, indptr: &[usize], indices: &[usize], data: &[f64]) -> Compressed {
+    let mut out_indptr = vec![0usize; n_minor + 1];
+    for &i in indices {
+        out_indptr[i + 1] += 1;
+    }
+    for i in 0..n_minor {
+        out_indptr[i + 1] += out_indptr[i];
+    }
+    let mut next = out_indptr.clone();
+    let mut out_indices = vec![0usize; indices.len()];
+    let mut out_data = vec![0.0; data.len()];
+    // Visiting major slots in order keeps the output minor indices sorted
+    for m in 0..indptr.len() - 1 {
+        for k in indptr[m]..indptr[m + 1] {
+            let dest = next[indices[k]];
+            out_indices[dest] = m;
+            out_data[dest] = data[k];
+            next[indices[k]] += 1;
+        }
+    }
+    (out_indptr, out_indices, out_data)
+}
+
+/// Expand compressed storage into row-major dense values
+///
+/// `major_is_row` selects CSR (`true`) or CSC (`false`) interpretation.
+pub(crate) fn to_dense_values(
+    shape: (usize, usize),
+    major_is_row: bool,
+    indptr: &[usize],
+    indices: &[usize],
+    data: &[f64],
+) -> Vec<f64> {
+    let mut values = vec![0.0; shape.0 * shape.1];
+    for m in 0..indptr.len() - 1 {
+        for k in indptr[m]..indptr[m + 1] {
+            let (r, c) = if major_is_row { (m, indices[k]) } else { (indices[k], m) };
+            values[r * shape.1 + c] += data[k];
+        }
+    }
+    values
+}
+
+/// Look up the value at `(major, minor)`, summing duplicates
+pub(crate) fn get(indptr: &[usize], indices: &[usize], data: &[f64], major: usize, minor: usize) -> f64 {
+    (indptr[major]..indptr[major + 1])
+        .filter(|&k| indices[k] == minor)
+        .map(|k| data[k])
+        .sum()
+}
+
+/// Resolve a slice into the list of selected positions along an axis
+pub(crate) fn slice_positions(slice: &Slice, len: usize) -> Result<Vec<usize>, SparseError> {
+    let (start, stop, step) = normalize_slice(slice, len as i64)?;
+    let count = slice_length(start, stop, step);
+    Ok((0..count).map(|i| (start + i * step) as usize).collect())
+}
+
+/// Select a subset of major slots, in the given order
+pub(crate) fn select_major(indptr: &[usize], indices: &[usize], data: &[f64], majors: &[usize]) -> Compressed {
+    let mut out_indptr = Vec::with_capacity(majors.len() + 1);
+    let mut out_indices = Vec::new();
+    let mut out_data = Vec::new();
+    out_indptr.push(0);
+    for &m in majors {
+        out_indices.extend_from_slice(&indices[indptr[m]..indptr[m + 1]]);
+        out_data.extend_from_slice(&data[indptr[m]..indptr[m + 1]]);
+        out_indptr.push(out_indices.len());
+    }
+    (out_indptr, out_indices, out_data)
+}
+
+/// Select a subset of minor positions, renumbering them in the given order
+pub(crate) fn select_minor(
+    n_minor: usize,
+    indptr: &[usize],
+    indices: &[usize],
+    data: &[f64],
+    minors: &[usize],
+) -> Compressed {
+    // Map each old minor index to its new position(s); a slice never repeats
+    // positions, so one slot per index is enough
+    let mut new_pos = vec![usize::MAX; n_minor];
+    for (new, &old) in minors.iter().enumerate() {
+        new_pos[old] = new;
+    }
+    let mut out_indptr = Vec::with_capacity(indptr.len());
+    let mut out_indices = Vec::new();
+    let mut out_data = Vec::new();
+    out_indptr.push(0);
+    for m in 0..indptr.len() - 1 {
+        let mut slot: Vec<(usize, f64)> = (indptr[m]..indptr[m + 1])
+            .filter(|&k| new_pos[indices[k]] != usize::MAX)
+            .map(|k| (new_pos[indices[k]], data[k]))
+            .collect();
+        slot.sort_by_key(|&(i, _)| i);
+        for (i, v) in slot {
+            out_indices.push(i);
+            out_data.push(v);
+        }
+        out_indptr.push(out_indices.len());
+    }
+    (out_indptr, out_indices, out_data)
+}
+
+/// Combine two compressed matrices of identical shape elementwise
+///
+/// With `union` set, entries present in either operand are kept (for
+/// addition and subtraction); otherwise only the intersection is kept (for
+/// multiplication). Explicit zeros produced by the operation are dropped.
+pub(crate) fn binary_op<F: Fn(f64, f64) -> f64>(
+    n_major: usize,
+    a: (&[usize], &[usize], &[f64]),
+    b: (&[usize], &[usize], &[f64]),
+    union: bool,
+    op: F,
+) -> Compressed {
+    let (a_ptr, a_idx, a_val) = sum_duplicates(n_major, a.0, a.1, a.2);
+    let (b_ptr, b_idx, b_val) = sum_duplicates(n_major, b.0, b.1, b.2);
+    let mut out_indptr = Vec::with_capacity(n_major + 1);
+    let mut out_indices = Vec::new();
+    let mut out_data = Vec::new();
+    out_indptr.push(0);
+    for m in 0..n_major {
+        let (mut i, i_end) = (a_ptr[m], a_ptr[m + 1]);
+        let (mut j, j_end) = (b_ptr[m], b_ptr[m + 1]);
+        while i < i_end || j < j_end {
+            let (idx, value) = if j >= j_end || (i < i_end && a_idx[i] < b_idx[j]) {
+                i += 1;
+                if !union {
+                    continue;
+                }
+                (a_idx[i - 1], op(a_val[i - 1], 0.0))
+            } else if i >= i_end || b_idx[j] < a_idx[i] {
+                j += 1;
+                if !union {
+                    continue;
+                }
+                (b_idx[j - 1], op(0.0, b_val[j - 1]))
+            } else {
+                i += 1;
+                j += 1;
+                (a_idx[i - 1], op(a_val[i - 1], b_val[j - 1]))
+            };
+            if value != 0.0 {
+                out_indices.push(idx);
+                out_data.push(value);
+            }
+        }
+        out_indptr.push(out_indices.len());
+    }
+    (out_indptr, out_indices, out_data)
+}
+
+/// Multiply two compressed matrices (Gustavson's algorithm)
+///
+/// Computes `A * B` where both are stored with the same major axis, i.e.
+/// CSR×CSR. `n_minor_b` is the minor dimension of `B`.
+pub(crate) fn matmul(
+    n_minor_b: usize,
+    a: (&[usize], &[usize], &[f64]),
+    b: (&[usize], &[usize], &[f64]),
+) -> Compressed {
+    let (a_ptr, a_idx, a_val) = a;
+    let (b_ptr, b_idx, b_val) = b;
+    let n_major = a_ptr.len() - 1;
+    let mut out_indptr = Vec::with_capacity(n_major + 1);
+    let mut out_indices = Vec::new();
+    let mut out_data = Vec::new();
+    out_indptr.push(0);
+
+    // Dense accumulator plus a marker of which positions are occupied
+    let mut accumulator = vec![0.0; n_minor_b];
+    let mut occupied = vec![false; n_minor_b];
+    let mut touched: Vec<usize> = Vec::new();
+    for m in 0..n_major {
+        for k in a_ptr[m]..a_ptr[m + 1] {
+            let inner = a_idx[k];
+            let scale = a_val[k];
+            for l in b_ptr[inner]..b_ptr[inner + 1] {
+                let col = b_idx[l];
+                if !occupied[col] {
+                    occupied[col] = true;
+                    touched.push(col);
+                }
+                accumulator[col] += scale * b_val[l];
+            }
+        }
+        touched.sort_unstable();
+        for &col in &touched {
+            let value = accumulator[col];
+            if value != 0.0 {
+                out_indices.push(col);
+                out_data.push(value);
+            }
+            accumulator[col] = 0.0;
+            occupied[col] = false;
+        }
+        touched.clear();
+        out_indptr.push(out_indices.len());
+    }
+    (out_indptr, out_indices, out_data)
+}
+
+/// Remove explicitly stored zeros
+pub(crate) fn eliminate_zeros(indptr: &[usize], indices: &[usize], data: &[f64]) -> Compressed {
+    let mut out_indptr = Vec::with_capacity(indptr.len());
+    let mut out_indices = Vec::with_capacity(indices.len());
+    let mut out_data = Vec::with_capacity(data.len());
+    out_indptr.push(0);
+    for m in 0..indptr.len() - 1 {
+        for k in indptr[m]..indptr[m + 1] {
+            if data[k] != 0.0 {
+                out_indices.push(indices[k]);
+                out_data.push(data[k]);
+            }
+        }
+        out_indptr.push(out_indices.len());
+    }
+    (out_indptr, out_indices, out_data)
+}
+
+/// Reduction kind for axis reductions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Reduction {
+    Sum,
+    Mean,
+    Max,
+    Min,
+}
+
+impl Reduction {
+    /// Reduce the stored values of one lane, accounting for `implicit` zeros
+    fn apply(self, values: impl Iterator<Item = f64>, implicit: usize, len: usize) -> f64 {
+        match self {
+            Reduction::Sum => values.sum(),
+            Reduction::Mean => {
+                if len == 0 {
+                    f64::NAN
+                } else {
+                    values.sum::<f64>() / len as f64
+                }
+            }
+            Reduction::Max | Reduction::Min => {
+                let seed = if implicit > 0 { Some(0.0) } else { None };
+                values
+                    .fold(seed, |acc: Option<f64>, v| match acc {
+                        None => Some(v),
+                        Some(a) if self == Reduction::Max => Some(if v > a || v.is_nan() { v } else { a }),
+                        Some(a) => Some(if v < a || v.is_nan() { v } else { a }),
+                    })
+                    .unwrap_or(f64::NAN)
+            }
+        }
+    }
+}
+
+/// Reduce along the major axis of canonical storage, one result per minor position
+pub(crate) fn reduce_over_major(
+    n_major: usize,
+    n_minor: usize,
+    indptr: &[usize],
+    indices: &[usize],
+    data: &[f64],
+    kind: Reduction,
+) -> Vec<f64> {
+    let (t_ptr, _, t_val) = transpose(n_minor, indptr, indices, data);
+    reduce_over_minor(n_major, &t_ptr, &t_val, kind)
+}
+
+/// Reduce along the minor axis of canonical storage, one result per major slot
+pub(crate) fn reduce_over_minor(
+    n_minor: usize,
+    indptr: &[usize],
+    data: &[f64],
+    kind: Reduction,
+) -> Vec<f64> {
+    (0..indptr.len() - 1)
+        .map(|m| {
+            let stored = indptr[m + 1] - indptr[m];
+            kind.apply(data[indptr[m]..indptr[m + 1]].iter().copied(), n_minor - stored, n_minor)
+        })
+        .collect()
+}
+
+/// Reduce over every element of canonical storage
+pub(crate) fn reduce_all(total: usize, data: &[f64], kind: Reduction) -> f64 {
+    kind.apply(data.iter().copied(), total - data.len(), total)
+}
+
+/// Verify that a function maps zero to zero, so it can act on stored values only
+pub(crate) fn check_preserves_zero<F: Fn(f64) -> f64>(f: &F, name: &str) -> Result<(), SparseError> {
+    if f(0.0) == 0.0 {
+        Ok(())
+    } else {
+        Err(SparseError::NotSparsityPreserving(name.to_string()))
+    }
+}
@@ -0,0 +1,214 @@
+//! Coordinate (COO) sparse matrix format
+
+use crate::array::{Array, ArrayError};
+use crate::indexing::IndexError;
+use crate::io::IoError;
+use crate::types::{DType, NpyType};
+
+use super::compressed;
+use super::{CscMatrix, CsrMatrix};
+
+/// Sparse matrix error
+#[derive(Debug, Clone)]
+pub enum SparseError {
+    /// Array error
+    ArrayError(ArrayError),
+    /// I/O error
+    IoError(IoError),
+    /// Index error
+    IndexError(IndexError),
+    /// Shape mismatch between operands
+    ShapeMismatch,
+    /// Row or column index outside the matrix shape
+    IndexOutOfBounds,
+    /// Dense input is not two-dimensional
+    InvalidDimension,
+    /// Axis is not 0 or 1
+    InvalidAxis,
+    /// Dtype cannot be stored in a sparse matrix
+    UnsupportedDtype,
+    /// Index arrays are inconsistent
+    InvalidStructure(String),
+    /// Function does not map zero to zero
+    NotSparsityPreserving(String),
+}
+
+impl std::fmt::Display for SparseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SparseError::ArrayError(e) => write!(f, "Array error: {}", e),
+            SparseError::IoError(e) => write!(f, "I/O error: {}", e),
+            SparseError::IndexError(e) => write!(f, "Index error: {}", e),
+            SparseError::ShapeMismatch => write!(f, "Shape mismatch"),
+            SparseError::IndexOutOfBounds => write!(f, "Index out of bounds"),
+            SparseError::InvalidDimension => write!(f, "Sparse matrices must be two-dimensional"),
+            SparseError::InvalidAxis => write!(f, "Axis must be 0 or 1"),
+            SparseError::UnsupportedDtype => write!(f, "Unsupported dtype"),
+            SparseError::InvalidStructure(msg) => write!(f, "Invalid sparse structure: {}", msg),
+            SparseError::NotSparsityPreserving(name) => {
+                write!(f, "Function '{}' does not preserve sparsity", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SparseError {}
+
+impl From<ArrayError> for SparseError {
+    fn from(err: ArrayError) -> Self {
+        SparseError::ArrayError(err)
+    }
+}
+
+impl From<IoError> for SparseError {
+    fn from(err: IoError) -> Self {
+        SparseError::IoError(err)
+    }
+}
+
+impl From<IndexError> for SparseError {
+    fn from(err: IndexError) -> Self {
+        SparseError::IndexError(err)
+    }
+}
+
+/// Read a dense 2D array as row-major `f64` values
+pub(crate) fn dense_values(array: &Array) -> Result<((usize, usize), Vec<f64>), SparseError> {
+    if array.ndim() != 2 {
+        return Err(SparseError::InvalidDimension);
+    }
+    let values = crate::utils::to_f64_vec(array).ok_or(SparseError::UnsupportedDtype)?;
+    let shape = (array.shape()[0] as usize, array.shape()[1] as usize);
+    Ok((shape, values))
+}
+
+/// Build a dense `f64` array from row-major values
+pub(crate) fn dense_array(shape: Vec<i64>, values: &[f64]) -> Result<Array, SparseError> {
+    Ok(Array::from_slice(values, shape, DType::new(NpyType::Double))?)
+}
+
+/// Sparse matrix in coordinate format
+///
+/// Stores `(row, col, value)` triplets, equivalent to SciPy's `coo_matrix`.
+/// Duplicate entries are allowed and are summed when converting to another
+/// format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CooMatrix {
+    shape: (usize, usize),
+    row: Vec<usize>,
+    col: Vec<usize>,
+    data: Vec<f64>,
+}
+
+impl CooMatrix {
+    /// Create a COO matrix from triplets
+    ///
+    /// # Arguments
+    /// * `shape` - Matrix shape `(rows, cols)`
+    /// * `row` - Row index of each stored value
+    /// * `col` - Column index of each stored value
+    /// * `data` - Stored values
+    pub fn new(
+        shape: (usize, usize),
+        row: Vec<usize>,
+        col: Vec<usize>,
+        data: Vec<f64>,
+    ) -> Result<Self, SparseError> {
+        if row.len() != data.len() || col.len() != data.len() {
+            return Err(SparseError::InvalidStructure(
+                "row, col and data must have the same length".to_string(),
+            ));
+        }
+        if row.iter().any(|&r| r >= shape.0) || col.iter().any(|&c| c >= shape.1) {
+            return Err(SparseError::IndexOutOfBounds);
+        }
+        Ok(CooMatrix { shape, row, col, data })
+    }
+
+    /// Create a COO matrix from the nonzero entries of a dense 2D array
+    pub fn from_dense(array: &Array) -> Result<Self, SparseError> {
+        let (shape, values) = dense_values(array)?;
+        let mut row = Vec::new();
+        let mut col = Vec::new();
+        let mut data = Vec::new();
+        for (i, &v) in values.iter().enumerate() {
+            if v != 0.0 {
+                row.push(i / shape.1);
+                col.push(i % shape.1);
+                data.push(v);
+            }
+        }
+        Ok(CooMatrix { shape, row, col, data })
+    }
+
+    /// Get the matrix shape
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Get the number of stored entries (including duplicates and explicit zeros)
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the row indices
+    pub fn row(&self) -> &[usize] {
+        &self.row
+    }
+
+    /// Get the column indices
+    pub fn col(&self) -> &[usize] {
+        &self.col
+    }
+
+    /// Get the stored values
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// Convert to a dense `float64` array, summing duplicate entries
+    pub fn to_dense(&self) -> Result<Array, SparseError> {
+        let mut values = vec![0.0; self.shape.0 * self.shape.1];
+        for ((&r, &c), &v) in self.row.iter().zip(&self.col).zip(&self.data) {
+            values[r * self.shape.1 + c] += v;
+        }
+        dense_array(vec![self.shape.0 as i64, self.shape.1 as i64], &values)
+    }
+
+    /// Convert to CSR format, summing duplicates and sorting column indices
+    pub fn to_csr(&self) -> CsrMatrix {
+        let (indptr, indices, data) =
+            compressed::compress(self.shape.0, &self.row, &self.col, &self.data);
+        CsrMatrix::from_parts_unchecked(self.shape, indptr, indices, data)
+    }
+
+    /// Convert to CSC format, summing duplicates and sorting row indices
+    pub fn to_csc(&self) -> CscMatrix {
+        let (indptr, indices, data) =
+            compressed::compress(self.shape.1, &self.col, &self.row, &self.data);
+        CscMatrix::from_parts_unchecked(self.shape, indptr, indices, data)
+    }
+
+    /// Transpose the matrix
+    pub fn transpose(&self) -> CooMatrix {
+        CooMatrix {
+            shape: (self.shape.1, self.shape.0),
+            row: self.col.clone(),
+            col: self.row.clone(),
+            data: self.data.clone(),
+        }
+    }
+
+    /// Apply a sparsity-preserving function to every stored value
+    ///
+    /// Fails with `NotSparsityPreserving` if `f(0.0) != 0.0`.
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Result<CooMatrix, SparseError> {
+        compressed::check_preserves_zero(&f, "<closure>")?;
+        Ok(CooMatrix {
+            shape: self.shape,
+            row: self.row.clone(),
+            col: self.col.clone(),
+            data: self.data.iter().map(|&v| f(v)).collect(),
+        })
+    }
+}
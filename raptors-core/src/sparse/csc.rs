@@ -0,0 +1,197 @@
+//! Compressed sparse column (CSC) matrix format
+
+use crate::array::Array;
+use crate::indexing::Slice;
+
+use super::coo::{dense_array, dense_values};
+use super::compressed;
+use super::{CooMatrix, CsrMatrix, SparseError};
+
+/// Sparse matrix in compressed sparse column format
+///
+/// Equivalent to SciPy's `csc_matrix`: the row indices of column `j` are
+/// stored in `indices[indptr[j]..indptr[j + 1]]`, with the matching values in
+/// `data`. Efficient for column slicing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix {
+    shape: (usize, usize),
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    data: Vec<f64>,
+}
+
+impl CscMatrix {
+    /// Create a CSC matrix from its index arrays
+    ///
+    /// # Arguments
+    /// * `shape` - Matrix shape `(rows, cols)`
+    /// * `indptr` - Column pointers, of length `cols + 1`
+    /// * `indices` - Row index of each stored value
+    /// * `data` - Stored values
+    ///
+    /// # Returns
+    /// * `Err(SparseError)` if the arrays are inconsistent with each other or the shape
+    pub fn new(
+        shape: (usize, usize),
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        data: Vec<f64>,
+    ) -> Result<Self, SparseError> {
+        compressed::validate(shape.1, shape.0, &indptr, &indices, &data)?;
+        Ok(CscMatrix { shape, indptr, indices, data })
+    }
+
+    pub(crate) fn from_parts_unchecked(
+        shape: (usize, usize),
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        data: Vec<f64>,
+    ) -> Self {
+        CscMatrix { shape, indptr, indices, data }
+    }
+
+    /// Create a CSC matrix from `(row, col, value)` triplets, summing duplicates
+    pub fn from_triplets(
+        shape: (usize, usize),
+        row: &[usize],
+        col: &[usize],
+        data: &[f64],
+    ) -> Result<Self, SparseError> {
+        Ok(CooMatrix::new(shape, row.to_vec(), col.to_vec(), data.to_vec())?.to_csc())
+    }
+
+    /// Create a CSC matrix from the nonzero entries of a dense 2D array
+    pub fn from_dense(array: &Array) -> Result<Self, SparseError> {
+        let (shape, values) = dense_values(array)?;
+        let mut indptr = Vec::with_capacity(shape.1 + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for j in 0..shape.1 {
+            for i in 0..shape.0 {
+                let v = values[i * shape.1 + j];
+                if v != 0.0 {
+                    indices.push(i);
+                    data.push(v);
+                }
+            }
+            indptr.push(indices.len());
+        }
+        Ok(CscMatrix { shape, indptr, indices, data })
+    }
+
+    /// Get the matrix shape
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Get the number of stored entries
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the column pointer array
+    pub fn indptr(&self) -> &[usize] {
+        &self.indptr
+    }
+
+    /// Get the row indices
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Get the stored values
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// Get the value at `(row, col)`
+    pub fn get(&self, row: usize, col: usize) -> Result<f64, SparseError> {
+        if row >= self.shape.0 || col >= self.shape.1 {
+            return Err(SparseError::IndexOutOfBounds);
+        }
+        Ok(compressed::get(&self.indptr, &self.indices, &self.data, col, row))
+    }
+
+    /// Check whether row indices are sorted with no duplicates in every column
+    pub fn has_canonical_format(&self) -> bool {
+        compressed::is_canonical(&self.indptr, &self.indices)
+    }
+
+    /// Sort row indices and sum duplicate entries
+    pub fn sum_duplicates(&self) -> CscMatrix {
+        let (indptr, indices, data) =
+            compressed::sum_duplicates(self.shape.1, &self.indptr, &self.indices, &self.data);
+        CscMatrix { shape: self.shape, indptr, indices, data }
+    }
+
+    /// Remove explicitly stored zeros
+    pub fn eliminate_zeros(&self) -> CscMatrix {
+        let (indptr, indices, data) =
+            compressed::eliminate_zeros(&self.indptr, &self.indices, &self.data);
+        CscMatrix { shape: self.shape, indptr, indices, data }
+    }
+
+    /// Convert to a dense `float64` array
+    pub fn to_dense(&self) -> Result<Array, SparseError> {
+        let values =
+            compressed::to_dense_values(self.shape, false, &self.indptr, &self.indices, &self.data);
+        dense_array(vec![self.shape.0 as i64, self.shape.1 as i64], &values)
+    }
+
+    /// Convert to COO format
+    pub fn to_coo(&self) -> CooMatrix {
+        let mut col = Vec::with_capacity(self.nnz());
+        for j in 0..self.shape.1 {
+            col.extend(std::iter::repeat_n(j, self.indptr[j + 1] - self.indptr[j]));
+        }
+        CooMatrix::new(self.shape, self.indices.clone(), col, self.data.clone())
+            .expect("CSC structure is valid")
+    }
+
+    /// Convert to CSR format
+    pub fn to_csr(&self) -> CsrMatrix {
+        let (indptr, indices, data) =
+            compressed::transpose(self.shape.0, &self.indptr, &self.indices, &self.data);
+        CsrMatrix::from_parts_unchecked(self.shape, indptr, indices, data)
+    }
+
+    /// Transpose the matrix
+    ///
+    /// The transpose of a CSC matrix shares its index arrays with a CSR
+    /// matrix, so no reordering is needed.
+    pub fn transpose(&self) -> CsrMatrix {
+        CsrMatrix::from_parts_unchecked(
+            (self.shape.1, self.shape.0),
+            self.indptr.clone(),
+            self.indices.clone(),
+            self.data.clone(),
+        )
+    }
+
+    /// Select a range of rows, equivalent to `m[rows, :]`
+    pub fn slice_rows(&self, rows: &Slice) -> Result<CscMatrix, SparseError> {
+        let positions = compressed::slice_positions(rows, self.shape.0)?;
+        let (indptr, indices, data) = compressed::select_minor(
+            self.shape.0,
+            &self.indptr,
+            &self.indices,
+            &self.data,
+            &positions,
+        );
+        Ok(CscMatrix { shape: (positions.len(), self.shape.1), indptr, indices, data })
+    }
+
+    /// Select a range of columns, equivalent to `m[:, cols]`
+    pub fn slice_cols(&self, cols: &Slice) -> Result<CscMatrix, SparseError> {
+        let positions = compressed::slice_positions(cols, self.shape.1)?;
+        let (indptr, indices, data) =
+            compressed::select_major(&self.indptr, &self.indices, &self.data, &positions);
+        Ok(CscMatrix { shape: (self.shape.0, positions.len()), indptr, indices, data })
+    }
+
+    /// Select a block, equivalent to `m[rows, cols]`
+    pub fn slice(&self, rows: &Slice, cols: &Slice) -> Result<CscMatrix, SparseError> {
+        self.slice_cols(cols)?.slice_rows(rows)
+    }
+}
@@ -0,0 +1,198 @@
+//! Compressed sparse row (CSR) matrix format
+
+use crate::array::Array;
+use crate::indexing::Slice;
+
+use super::coo::{dense_array, dense_values};
+use super::compressed;
+use super::{CooMatrix, CscMatrix, SparseError};
+
+/// Sparse matrix in compressed sparse row format
+///
+/// Equivalent to SciPy's `csr_matrix`: the column indices of row `i` are
+/// stored in `indices[indptr[i]..indptr[i + 1]]`, with the matching values in
+/// `data`. Efficient for row slicing and matrix-vector products.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix {
+    shape: (usize, usize),
+    indptr: Vec<usize>,
+    indices: Vec<usize>,
+    data: Vec<f64>,
+}
+
+impl CsrMatrix {
+    /// Create a CSR matrix from its index arrays
+    ///
+    /// # Arguments
+    /// * `shape` - Matrix shape `(rows, cols)`
+    /// * `indptr` - Row pointers, of length `rows + 1`
+    /// * `indices` - Column index of each stored value
+    /// * `data` - Stored values
+    ///
+    /// # Returns
+    /// * `Err(SparseError)` if the arrays are inconsistent with each other or the shape
+    pub fn new(
+        shape: (usize, usize),
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        data: Vec<f64>,
+    ) -> Result<Self, SparseError> {
+        compressed::validate(shape.0, shape.1, &indptr, &indices, &data)?;
+        Ok(CsrMatrix { shape, indptr, indices, data })
+    }
+
+    pub(crate) fn from_parts_unchecked(
+        shape: (usize, usize),
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        data: Vec<f64>,
+    ) -> Self {
+        CsrMatrix { shape, indptr, indices, data }
+    }
+
+    /// Create a CSR matrix from `(row, col, value)` triplets, summing duplicates
+    pub fn from_triplets(
+        shape: (usize, usize),
+        row: &[usize],
+        col: &[usize],
+        data: &[f64],
+    ) -> Result<Self, SparseError> {
+        Ok(CooMatrix::new(shape, row.to_vec(), col.to_vec(), data.to_vec())?.to_csr())
+    }
+
+    /// Create a CSR matrix from the nonzero entries of a dense 2D array
+    pub fn from_dense(array: &Array) -> Result<Self, SparseError> {
+        let (shape, values) = dense_values(array)?;
+        let mut indptr = Vec::with_capacity(shape.0 + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for row in values.chunks(shape.1.max(1)).take(shape.0) {
+            for (j, &v) in row.iter().enumerate() {
+                if v != 0.0 {
+                    indices.push(j);
+                    data.push(v);
+                }
+            }
+            indptr.push(indices.len());
+        }
+        // A matrix with zero columns still needs one pointer per row
+        indptr.resize(shape.0 + 1, 0);
+        Ok(CsrMatrix { shape, indptr, indices, data })
+    }
+
+    /// Get the matrix shape
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Get the number of stored entries
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the row pointer array
+    pub fn indptr(&self) -> &[usize] {
+        &self.indptr
+    }
+
+    /// Get the column indices
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Get the stored values
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// Get the value at `(row, col)`
+    pub fn get(&self, row: usize, col: usize) -> Result<f64, SparseError> {
+        if row >= self.shape.0 || col >= self.shape.1 {
+            return Err(SparseError::IndexOutOfBounds);
+        }
+        Ok(compressed::get(&self.indptr, &self.indices, &self.data, row, col))
+    }
+
+    /// Check whether column indices are sorted with no duplicates in every row
+    pub fn has_canonical_format(&self) -> bool {
+        compressed::is_canonical(&self.indptr, &self.indices)
+    }
+
+    /// Sort column indices and sum duplicate entries
+    pub fn sum_duplicates(&self) -> CsrMatrix {
+        let (indptr, indices, data) =
+            compressed::sum_duplicates(self.shape.0, &self.indptr, &self.indices, &self.data);
+        CsrMatrix { shape: self.shape, indptr, indices, data }
+    }
+
+    /// Remove explicitly stored zeros
+    pub fn eliminate_zeros(&self) -> CsrMatrix {
+        let (indptr, indices, data) =
+            compressed::eliminate_zeros(&self.indptr, &self.indices, &self.data);
+        CsrMatrix { shape: self.shape, indptr, indices, data }
+    }
+
+    /// Convert to a dense `float64` array
+    pub fn to_dense(&self) -> Result<Array, SparseError> {
+        let values =
+            compressed::to_dense_values(self.shape, true, &self.indptr, &self.indices, &self.data);
+        dense_array(vec![self.shape.0 as i64, self.shape.1 as i64], &values)
+    }
+
+    /// Convert to COO format
+    pub fn to_coo(&self) -> CooMatrix {
+        let mut row = Vec::with_capacity(self.nnz());
+        for i in 0..self.shape.0 {
+            row.extend(std::iter::repeat_n(i, self.indptr[i + 1] - self.indptr[i]));
+        }
+        CooMatrix::new(self.shape, row, self.indices.clone(), self.data.clone())
+            .expect("CSR structure is valid")
+    }
+
+    /// Convert to CSC format
+    pub fn to_csc(&self) -> CscMatrix {
+        let (indptr, indices, data) =
+            compressed::transpose(self.shape.1, &self.indptr, &self.indices, &self.data);
+        CscMatrix::from_parts_unchecked(self.shape, indptr, indices, data)
+    }
+
+    /// Transpose the matrix
+    ///
+    /// The transpose of a CSR matrix shares its index arrays with a CSC
+    /// matrix, so no reordering is needed.
+    pub fn transpose(&self) -> CscMatrix {
+        CscMatrix::from_parts_unchecked(
+            (self.shape.1, self.shape.0),
+            self.indptr.clone(),
+            self.indices.clone(),
+            self.data.clone(),
+        )
+    }
+
+    /// Select a range of rows, equivalent to `m[rows, :]`
+    pub fn slice_rows(&self, rows: &Slice) -> Result<CsrMatrix, SparseError> {
+        let positions = compressed::slice_positions(rows, self.shape.0)?;
+        let (indptr, indices, data) =
+            compressed::select_major(&self.indptr, &self.indices, &self.data, &positions);
+        Ok(CsrMatrix { shape: (positions.len(), self.shape.1), indptr, indices, data })
+    }
+
+    /// Select a range of columns, equivalent to `m[:, cols]`
+    pub fn slice_cols(&self, cols: &Slice) -> Result<CsrMatrix, SparseError> {
+        let positions = compressed::slice_positions(cols, self.shape.1)?;
+        let (indptr, indices, data) = compressed::select_minor(
+            self.shape.1,
+            &self.indptr,
+            &self.indices,
+            &self.data,
+            &positions,
+        );
+        Ok(CsrMatrix { shape: (self.shape.0, positions.len()), indptr, indices, data })
+    }
+
+    /// Select a block, equivalent to `m[rows, cols]`
+    pub fn slice(&self, rows: &Slice, cols: &Slice) -> Result<CsrMatrix, SparseError> {
+        self.slice_rows(rows)?.slice_cols(cols)
+    }
+}
@@ -0,0 +1,243 @@
+//! Sparse matrix `.npz` I/O
+//!
+//! Files use the layout written by `scipy.sparse.save_npz`: one NPY member
+//! per index array, plus `format.npy` (the format name as a byte string) and
+//! `shape.npy`. Files written here load with `scipy.sparse.load_npz`, and
+//! vice versa.
+
+use crate::array::Array;
+use crate::io::zip::{ZipIndex, ZipWriter};
+use crate::io::{read_npy, write_npy, IoError};
+use crate::types::{DType, NpyType};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use super::{CooMatrix, CscMatrix, CsrMatrix, SparseError};
+
+/// A sparse matrix in any supported format
+#[derive(Debug, Clone, PartialEq)]
+pub enum SparseMatrix {
+    /// Coordinate format
+    Coo(CooMatrix),
+    /// Compressed sparse row format
+    Csr(CsrMatrix),
+    /// Compressed sparse column format
+    Csc(CscMatrix),
+}
+
+impl SparseMatrix {
+    /// Get the SciPy format name (`"coo"`, `"csr"` or `"csc"`)
+    pub fn format(&self) -> &'static str {
+        match self {
+            SparseMatrix::Coo(_) => "coo",
+            SparseMatrix::Csr(_) => "csr",
+            SparseMatrix::Csc(_) => "csc",
+        }
+    }
+
+    /// Get the matrix shape
+    pub fn shape(&self) -> (usize, usize) {
+        match self {
+            SparseMatrix::Coo(m) => m.shape(),
+            SparseMatrix::Csr(m) => m.shape(),
+            SparseMatrix::Csc(m) => m.shape(),
+        }
+    }
+
+    /// Get the number of stored entries
+    pub fn nnz(&self) -> usize {
+        match self {
+            SparseMatrix::Coo(m) => m.nnz(),
+            SparseMatrix::Csr(m) => m.nnz(),
+            SparseMatrix::Csc(m) => m.nnz(),
+        }
+    }
+
+    /// Convert to a dense `float64` array
+    pub fn to_dense(&self) -> Result<Array, SparseError> {
+        match self {
+            SparseMatrix::Coo(m) => m.to_dense(),
+            SparseMatrix::Csr(m) => m.to_dense(),
+            SparseMatrix::Csc(m) => m.to_dense(),
+        }
+    }
+
+    /// Convert to CSR format
+    pub fn to_csr(&self) -> CsrMatrix {
+        match self {
+            SparseMatrix::Coo(m) => m.to_csr(),
+            SparseMatrix::Csr(m) => m.clone(),
+            SparseMatrix::Csc(m) => m.to_csr(),
+        }
+    }
+
+    /// Convert to CSC format
+    pub fn to_csc(&self) -> CscMatrix {
+        match self {
+            SparseMatrix::Coo(m) => m.to_csc(),
+            SparseMatrix::Csr(m) => m.to_csc(),
+            SparseMatrix::Csc(m) => m.clone(),
+        }
+    }
+}
+
+impl From<CooMatrix> for SparseMatrix {
+    fn from(m: CooMatrix) -> Self {
+        SparseMatrix::Coo(m)
+    }
+}
+
+impl From<CsrMatrix> for SparseMatrix {
+    fn from(m: CsrMatrix) -> Self {
+        SparseMatrix::Csr(m)
+    }
+}
+
+impl From<CscMatrix> for SparseMatrix {
+    fn from(m: CscMatrix) -> Self {
+        SparseMatrix::Csc(m)
+    }
+}
+
+/// Build an index array, using int32 when every value fits (as SciPy does)
+fn index_array(values: &[usize], max_value: usize) -> Result<Array, SparseError> {
+    let shape = vec![values.len() as i64];
+    if max_value <= i32::MAX as usize {
+        let narrow: Vec<i32> = values.iter().map(|&v| v as i32).collect();
+        Ok(Array::from_slice(&narrow, shape, DType::new(NpyType::Int))?)
+    } else {
+        let wide: Vec<i64> = values.iter().map(|&v| v as i64).collect();
+        Ok(Array::from_slice(&wide, shape, DType::new(NpyType::Long))?)
+    }
+}
+
+/// Read an integer index array
+///
+/// Values are read at their own width, so 64-bit indices above 2^53 load
+/// exactly; negative values and values beyond `usize` are out of bounds.
+fn read_indices(array: &Array) -> Result<Vec<usize>, SparseError> {
+    let read: fn(&[u8]) -> Result<usize, SparseError> = match array.dtype().type_() {
+        NpyType::Byte => |b| index(b, i8::from_ne_bytes),
+        NpyType::UByte => |b| index(b, u8::from_ne_bytes),
+        NpyType::Short => |b| index(b, i16::from_ne_bytes),
+        NpyType::UShort => |b| index(b, u16::from_ne_bytes),
+        NpyType::Int => |b| index(b, i32::from_ne_bytes),
+        NpyType::UInt => |b| index(b, u32::from_ne_bytes),
+        NpyType::Long | NpyType::LongLong => |b| index(b, i64::from_ne_bytes),
+        NpyType::ULong | NpyType::ULongLong => |b| index(b, u64::from_ne_bytes),
+        _ => return Err(SparseError::UnsupportedDtype),
+    };
+    crate::utils::to_contiguous_bytes(array)
+        .chunks_exact(array.itemsize().max(1))
+        .map(read)
+        .collect()
+}
+
+/// Convert one index element of type `T` to `usize`
+fn index<T, const N: usize>(bytes: &[u8], from_bytes: fn([u8; N]) -> T) -> Result<usize, SparseError>
+where
+    usize: TryFrom<T>,
+{
+    let bytes = bytes.try_into().map_err(|_| SparseError::UnsupportedDtype)?;
+    usize::try_from(from_bytes(bytes)).map_err(|_| SparseError::IndexOutOfBounds)
+}
+
+/// Save a sparse matrix to a `.npz` file compatible with `scipy.sparse.save_npz`
+///
+/// # Arguments
+/// * `path` - Output file path
+/// * `matrix` - Matrix to save
+/// * `compressed` - Deflate the members (SciPy's default) or store them uncompressed
+pub fn save_npz(path: impl AsRef<Path>, matrix: &SparseMatrix, compressed: bool) -> Result<(), SparseError> {
+    let (rows, cols) = matrix.shape();
+    let nnz = matrix.nnz();
+    let mut members: Vec<(&str, Array)> = Vec::new();
+
+    let max_index = rows.max(cols).max(nnz);
+    let index_members: [(&str, &[usize]); 2] = match matrix {
+        SparseMatrix::Coo(m) => [("row", m.row()), ("col", m.col())],
+        SparseMatrix::Csr(m) => [("indices", m.indices()), ("indptr", m.indptr())],
+        SparseMatrix::Csc(m) => [("indices", m.indices()), ("indptr", m.indptr())],
+    };
+    for (name, values) in index_members {
+        members.push((name, index_array(values, max_index)?));
+    }
+
+    let format = matrix.format();
+    let mut format_array = Array::new(vec![], DType::string_with_itemsize(format.len()))?;
+    unsafe {
+        std::ptr::copy_nonoverlapping(format.as_ptr(), format_array.data_ptr_mut(), format.len());
+    }
+    members.push(("format", format_array));
+    members.push(("shape", Array::from_slice(&[rows as i64, cols as i64], vec![2], DType::new(NpyType::Long))?));
+
+    let data = match matrix {
+        SparseMatrix::Coo(m) => m.data(),
+        SparseMatrix::Csr(m) => m.data(),
+        SparseMatrix::Csc(m) => m.data(),
+    };
+    members.push(("data", Array::from_slice(data, vec![nnz as i64], DType::new(NpyType::Double))?));
+
+    let file = File::create(path).map_err(|e| IoError::FileError(e.to_string()))?;
+    let mut writer = ZipWriter::new(BufWriter::new(file));
+    for (name, array) in &members {
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, array)?;
+        writer.add_entry(&format!("{}.npy", name), &bytes, compressed)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Load a sparse matrix from a `.npz` file written by `scipy.sparse.save_npz`
+///
+/// Values of any real numeric dtype are converted to `float64`.
+pub fn load_npz(path: impl AsRef<Path>) -> Result<SparseMatrix, SparseError> {
+    let file = File::open(path).map_err(|e| IoError::FileError(e.to_string()))?;
+    let mut reader = BufReader::new(file);
+    let index = ZipIndex::read(&mut reader)?;
+
+    let mut member = |name: &str| -> Result<Array, SparseError> {
+        let entry = index
+            .find(&format!("{}.npy", name))
+            .ok_or_else(|| SparseError::InvalidStructure(format!("missing '{}' array", name)))?;
+        let bytes = entry.read_to_vec(&mut reader)?;
+        Ok(read_npy(bytes.as_slice())?)
+    };
+
+    let format_array = member("format")?;
+    if format_array.dtype().type_() != NpyType::String {
+        return Err(SparseError::UnsupportedDtype);
+    }
+    let format_bytes = unsafe {
+        std::slice::from_raw_parts(format_array.data_ptr(), format_array.itemsize())
+    };
+    let format = String::from_utf8_lossy(format_bytes).trim_end_matches('\0').to_string();
+
+    let shape_values = read_indices(&member("shape")?)?;
+    if shape_values.len() != 2 {
+        return Err(SparseError::InvalidDimension);
+    }
+    let shape = (shape_values[0], shape_values[1]);
+    let data = crate::utils::to_f64_vec(&member("data")?).ok_or(SparseError::UnsupportedDtype)?;
+
+    match format.as_str() {
+        "csr" => {
+            let indices = read_indices(&member("indices")?)?;
+            let indptr = read_indices(&member("indptr")?)?;
+            Ok(SparseMatrix::Csr(CsrMatrix::new(shape, indptr, indices, data)?))
+        }
+        "csc" => {
+            let indices = read_indices(&member("indices")?)?;
+            let indptr = read_indices(&member("indptr")?)?;
+            Ok(SparseMatrix::Csc(CscMatrix::new(shape, indptr, indices, data)?))
+        }
+        "coo" => {
+            let row = read_indices(&member("row")?)?;
+            let col = read_indices(&member("col")?)?;
+            Ok(SparseMatrix::Coo(CooMatrix::new(shape, row, col, data)?))
+        }
+        other => Err(SparseError::InvalidStructure(format!("unsupported sparse format '{}'", other))),
+    }
+}
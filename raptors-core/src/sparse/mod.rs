@@ -0,0 +1,16 @@
+//! Sparse array module
+//!
+//! This module provides sparse matrices in COO, CSR and CSC formats,
+//! equivalent to SciPy's `scipy.sparse`, interoperating with dense arrays
+
+mod compressed;
+mod coo;
+mod csr;
+mod csc;
+mod ops;
+mod io;
+
+pub use coo::*;
+pub use csr::*;
+pub use csc::*;
+pub use io::*;
@@ -0,0 +1,310 @@
+//! Sparse matrix operations
+//!
+//! Elementwise ufuncs that preserve sparsity, sparse arithmetic, sparse×dense
+//! and sparse×sparse products, and axis reductions.
+
+use crate::array::Array;
+
+use super::coo::dense_array;
+use super::compressed::{self, Reduction};
+use super::{CscMatrix, CsrMatrix, SparseError};
+
+/// Look up a ufunc that maps zero to zero and can act on stored values only
+///
+/// These are the elementwise functions SciPy exposes on sparse matrices.
+fn sparsity_preserving_ufunc(name: &str) -> Option<fn(f64) -> f64> {
+    let f: fn(f64) -> f64 = match name {
+        "abs" | "absolute" => f64::abs,
+        "negative" => |x| -x,
+        "sqrt" => f64::sqrt,
+        "square" => |x| x * x,
+        "sign" => |x: f64| if x > 0.0 { 1.0 } else if x < 0.0 { -1.0 } else { x },
+        "sin" => f64::sin,
+        "tan" => f64::tan,
+        "arcsin" => f64::asin,
+        "arctan" => f64::atan,
+        "sinh" => f64::sinh,
+        "tanh" => f64::tanh,
+        "arcsinh" => f64::asinh,
+        "arctanh" => f64::atanh,
+        "expm1" => f64::exp_m1,
+        "log1p" => f64::ln_1p,
+        "floor" => f64::floor,
+        "ceil" => f64::ceil,
+        "trunc" => f64::trunc,
+        "rint" => f64::round_ties_even,
+        "deg2rad" => f64::to_radians,
+        "rad2deg" => f64::to_degrees,
+        _ => return None,
+    };
+    Some(f)
+}
+
+fn axis_output(values: Vec<f64>) -> Result<Array, SparseError> {
+    dense_array(vec![values.len() as i64], &values)
+}
+
+/// Read the dense right-hand operand of a sparse×dense product
+///
+/// Returns the values in row-major order with the number of columns (1 for a
+/// vector operand).
+fn dense_operand(array: &Array, inner: usize) -> Result<(Vec<f64>, usize), SparseError> {
+    let cols = match array.ndim() {
+        1 => 1,
+        2 => array.shape()[1] as usize,
+        _ => return Err(SparseError::InvalidDimension),
+    };
+    if array.shape()[0] as usize != inner {
+        return Err(SparseError::ShapeMismatch);
+    }
+    let values = crate::utils::to_f64_vec(array).ok_or(SparseError::UnsupportedDtype)?;
+    Ok((values, cols))
+}
+
+fn product_shape(array: &Array, rows: usize, cols: usize) -> Vec<i64> {
+    if array.ndim() == 1 {
+        vec![rows as i64]
+    } else {
+        vec![rows as i64, cols as i64]
+    }
+}
+
+impl CsrMatrix {
+    /// Apply a function to every stored value
+    ///
+    /// Fails with `NotSparsityPreserving` if `f(0.0) != 0.0`, since the
+    /// implicit zeros would otherwise change.
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Result<CsrMatrix, SparseError> {
+        compressed::check_preserves_zero(&f, "<closure>")?;
+        let data = self.data().iter().map(|&v| f(v)).collect();
+        Ok(CsrMatrix::from_parts_unchecked(self.shape(), self.indptr().to_vec(), self.indices().to_vec(), data))
+    }
+
+    /// Apply a named elementwise ufunc (e.g. `"abs"`, `"sqrt"`, `"sin"`)
+    ///
+    /// Only ufuncs with `f(0) == 0` are accepted; others (such as `"exp"` or
+    /// `"cos"`) would produce a dense result and fail with `NotSparsityPreserving`.
+    pub fn apply_ufunc(&self, name: &str) -> Result<CsrMatrix, SparseError> {
+        let f = sparsity_preserving_ufunc(name)
+            .ok_or_else(|| SparseError::NotSparsityPreserving(name.to_string()))?;
+        self.map(f)
+    }
+
+    /// Multiply every element by a scalar
+    pub fn multiply_scalar(&self, scalar: f64) -> CsrMatrix {
+        let data = self.data().iter().map(|&v| v * scalar).collect();
+        CsrMatrix::from_parts_unchecked(self.shape(), self.indptr().to_vec(), self.indices().to_vec(), data)
+    }
+
+    fn combine<F: Fn(f64, f64) -> f64>(&self, other: &CsrMatrix, union: bool, op: F) -> Result<CsrMatrix, SparseError> {
+        if self.shape() != other.shape() {
+            return Err(SparseError::ShapeMismatch);
+        }
+        let (indptr, indices, data) = compressed::binary_op(
+            self.shape().0,
+            (self.indptr(), self.indices(), self.data()),
+            (other.indptr(), other.indices(), other.data()),
+            union,
+            op,
+        );
+        Ok(CsrMatrix::from_parts_unchecked(self.shape(), indptr, indices, data))
+    }
+
+    /// Elementwise sum of two sparse matrices
+    pub fn add(&self, other: &CsrMatrix) -> Result<CsrMatrix, SparseError> {
+        self.combine(other, true, |a, b| a + b)
+    }
+
+    /// Elementwise difference of two sparse matrices
+    pub fn subtract(&self, other: &CsrMatrix) -> Result<CsrMatrix, SparseError> {
+        self.combine(other, true, |a, b| a - b)
+    }
+
+    /// Elementwise (Hadamard) product of two sparse matrices
+    pub fn multiply(&self, other: &CsrMatrix) -> Result<CsrMatrix, SparseError> {
+        self.combine(other, false, |a, b| a * b)
+    }
+
+    /// Multiply by a dense vector `(n,)` or matrix `(n, k)`, returning a dense array
+    pub fn matmul_dense(&self, other: &Array) -> Result<Array, SparseError> {
+        let (rows, inner) = self.shape();
+        let (values, cols) = dense_operand(other, inner)?;
+        let mut result = vec![0.0; rows * cols];
+        let (indptr, indices, data) = (self.indptr(), self.indices(), self.data());
+        for i in 0..rows {
+            let out = &mut result[i * cols..(i + 1) * cols];
+            for k in indptr[i]..indptr[i + 1] {
+                let rhs = &values[indices[k] * cols..(indices[k] + 1) * cols];
+                for (o, &r) in out.iter_mut().zip(rhs) {
+                    *o += data[k] * r;
+                }
+            }
+        }
+        dense_array(product_shape(other, rows, cols), &result)
+    }
+
+    /// Multiply by another sparse matrix, returning a sparse result
+    pub fn matmul(&self, other: &CsrMatrix) -> Result<CsrMatrix, SparseError> {
+        if self.shape().1 != other.shape().0 {
+            return Err(SparseError::ShapeMismatch);
+        }
+        let (indptr, indices, data) = compressed::matmul(
+            other.shape().1,
+            (self.indptr(), self.indices(), self.data()),
+            (other.indptr(), other.indices(), other.data()),
+        );
+        Ok(CsrMatrix::from_parts_unchecked((self.shape().0, other.shape().1), indptr, indices, data))
+    }
+
+    fn reduce(&self, axis: Option<usize>, kind: Reduction) -> Result<Array, SparseError> {
+        let (rows, cols) = self.shape();
+        let canonical = self.sum_duplicates();
+        let (indptr, indices, data) = (canonical.indptr(), canonical.indices(), canonical.data());
+        match axis {
+            None => axis_output(vec![compressed::reduce_all(rows * cols, data, kind)]),
+            Some(0) => axis_output(compressed::reduce_over_major(rows, cols, indptr, indices, data, kind)),
+            Some(1) => axis_output(compressed::reduce_over_minor(cols, indptr, data, kind)),
+            Some(_) => Err(SparseError::InvalidAxis),
+        }
+    }
+
+    /// Sum of elements, over the whole matrix or along an axis
+    pub fn sum(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Sum)
+    }
+
+    /// Mean of elements, counting implicit zeros
+    pub fn mean(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Mean)
+    }
+
+    /// Maximum of elements, counting implicit zeros
+    pub fn max(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Max)
+    }
+
+    /// Minimum of elements, counting implicit zeros
+    pub fn min(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Min)
+    }
+}
+
+impl CscMatrix {
+    /// Apply a function to every stored value
+    ///
+    /// Fails with `NotSparsityPreserving` if `f(0.0) != 0.0`, since the
+    /// implicit zeros would otherwise change.
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Result<CscMatrix, SparseError> {
+        compressed::check_preserves_zero(&f, "<closure>")?;
+        let data = self.data().iter().map(|&v| f(v)).collect();
+        Ok(CscMatrix::from_parts_unchecked(self.shape(), self.indptr().to_vec(), self.indices().to_vec(), data))
+    }
+
+    /// Apply a named elementwise ufunc (e.g. `"abs"`, `"sqrt"`, `"sin"`)
+    ///
+    /// Only ufuncs with `f(0) == 0` are accepted.
+    pub fn apply_ufunc(&self, name: &str) -> Result<CscMatrix, SparseError> {
+        let f = sparsity_preserving_ufunc(name)
+            .ok_or_else(|| SparseError::NotSparsityPreserving(name.to_string()))?;
+        self.map(f)
+    }
+
+    /// Multiply every element by a scalar
+    pub fn multiply_scalar(&self, scalar: f64) -> CscMatrix {
+        let data = self.data().iter().map(|&v| v * scalar).collect();
+        CscMatrix::from_parts_unchecked(self.shape(), self.indptr().to_vec(), self.indices().to_vec(), data)
+    }
+
+    fn combine<F: Fn(f64, f64) -> f64>(&self, other: &CscMatrix, union: bool, op: F) -> Result<CscMatrix, SparseError> {
+        if self.shape() != other.shape() {
+            return Err(SparseError::ShapeMismatch);
+        }
+        let (indptr, indices, data) = compressed::binary_op(
+            self.shape().1,
+            (self.indptr(), self.indices(), self.data()),
+            (other.indptr(), other.indices(), other.data()),
+            union,
+            op,
+        );
+        Ok(CscMatrix::from_parts_unchecked(self.shape(), indptr, indices, data))
+    }
+
+    /// Elementwise sum of two sparse matrices
+    pub fn add(&self, other: &CscMatrix) -> Result<CscMatrix, SparseError> {
+        self.combine(other, true, |a, b| a + b)
+    }
+
+    /// Elementwise difference of two sparse matrices
+    pub fn subtract(&self, other: &CscMatrix) -> Result<CscMatrix, SparseError> {
+        self.combine(other, true, |a, b| a - b)
+    }
+
+    /// Elementwise (Hadamard) product of two sparse matrices
+    pub fn multiply(&self, other: &CscMatrix) -> Result<CscMatrix, SparseError> {
+        self.combine(other, false, |a, b| a * b)
+    }
+
+    /// Multiply by a dense vector `(n,)` or matrix `(n, k)`, returning a dense array
+    pub fn matmul_dense(&self, other: &Array) -> Result<Array, SparseError> {
+        let (rows, inner) = self.shape();
+        let (values, cols) = dense_operand(other, inner)?;
+        let mut result = vec![0.0; rows * cols];
+        let (indptr, indices, data) = (self.indptr(), self.indices(), self.data());
+        for j in 0..inner {
+            let rhs = &values[j * cols..(j + 1) * cols];
+            for k in indptr[j]..indptr[j + 1] {
+                let out = &mut result[indices[k] * cols..(indices[k] + 1) * cols];
+                for (o, &r) in out.iter_mut().zip(rhs) {
+                    *o += data[k] * r;
+                }
+            }
+        }
+        dense_array(product_shape(other, rows, cols), &result)
+    }
+
+    /// Multiply by another sparse matrix, returning a sparse result
+    pub fn matmul(&self, other: &CscMatrix) -> Result<CscMatrix, SparseError> {
+        if self.shape().1 != other.shape().0 {
+            return Err(SparseError::ShapeMismatch);
+        }
+        // CSC storage of A is CSR storage of A^T, and (AB)^T = B^T A^T
+        let (indptr, indices, data) = compressed::matmul(
+            self.shape().0,
+            (other.indptr(), other.indices(), other.data()),
+            (self.indptr(), self.indices(), self.data()),
+        );
+        Ok(CscMatrix::from_parts_unchecked((self.shape().0, other.shape().1), indptr, indices, data))
+    }
+
+    fn reduce(&self, axis: Option<usize>, kind: Reduction) -> Result<Array, SparseError> {
+        let (rows, cols) = self.shape();
+        let canonical = self.sum_duplicates();
+        let (indptr, indices, data) = (canonical.indptr(), canonical.indices(), canonical.data());
+        match axis {
+            None => axis_output(vec![compressed::reduce_all(rows * cols, data, kind)]),
+            Some(0) => axis_output(compressed::reduce_over_minor(rows, indptr, data, kind)),
+            Some(1) => axis_output(compressed::reduce_over_major(cols, rows, indptr, indices, data, kind)),
+            Some(_) => Err(SparseError::InvalidAxis),
+        }
+    }
+
+    /// Sum of elements, over the whole matrix or along an axis
+    pub fn sum(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Sum)
+    }
+
+    /// Mean of elements, counting implicit zeros
+    pub fn mean(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Mean)
+    }
+
+    /// Maximum of elements, counting implicit zeros
+    pub fn max(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Max)
+    }
+
+    /// Minimum of elements, counting implicit zeros
+    pub fn min(&self, axis: Option<usize>) -> Result<Array, SparseError> {
+        self.reduce(axis, Reduction::Min)
+    }
+}
@@ -0,0 +1,105 @@
+//! Chunk compressors
+//!
+//! The codecs are the numcodecs ones Zarr names in `.zarray`: `zlib`
+//! (a zlib stream) and `lz4` (an LZ4 block behind a little-endian `u32`
+//! holding the uncompressed size).
+
+use super::json::Json;
+use super::lz4;
+use super::StoreError;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Compressor applied to each chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compressor {
+    /// Chunks are stored uncompressed
+    None,
+    /// zlib with a level from 0 (none) to 9 (best)
+    Zlib {
+        /// Compression level
+        level: u32,
+    },
+    /// LZ4 block compression
+    Lz4 {
+        /// Higher values trade compression ratio for speed
+        acceleration: i32,
+    },
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Compressor::Zlib { level: 1 }
+    }
+}
+
+impl Compressor {
+    /// `compressor` entry of `.zarray`
+    pub(crate) fn to_json(self) -> Json {
+        match self {
+            Compressor::None => Json::Null,
+            Compressor::Zlib { level } => {
+                Json::Object(vec![("id".to_string(), Json::Str("zlib".to_string())), ("level".to_string(), Json::Int(level as i64))])
+            }
+            Compressor::Lz4 { acceleration } => Json::Object(vec![
+                ("id".to_string(), Json::Str("lz4".to_string())),
+                ("acceleration".to_string(), Json::Int(acceleration as i64)),
+            ]),
+        }
+    }
+
+    /// Parse the `compressor` entry of `.zarray`
+    pub(crate) fn from_json(value: &Json) -> Result<Compressor, StoreError> {
+        if *value == Json::Null {
+            return Ok(Compressor::None);
+        }
+        let param = |key: &str, default: i64| value.get(key).and_then(Json::as_i64).unwrap_or(default);
+        match value.get("id") {
+            Some(Json::Str(id)) if id == "zlib" => Ok(Compressor::Zlib { level: param("level", 1).clamp(0, 9) as u32 }),
+            Some(Json::Str(id)) if id == "lz4" => Ok(Compressor::Lz4 { acceleration: param("acceleration", 1) as i32 }),
+            Some(Json::Str(id)) => Err(StoreError::UnsupportedCodec(id.clone())),
+            _ => Err(StoreError::InvalidMetadata("compressor has no id".to_string())),
+        }
+    }
+
+    /// Compress a chunk
+    pub(crate) fn encode(self, data: &[u8]) -> Result<Vec<u8>, StoreError> {
+        match self {
+            Compressor::None => Ok(data.to_vec()),
+            Compressor::Zlib { level } => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::new(level.min(9)));
+                encoder.write_all(data).and_then(|_| encoder.finish()).map_err(|e| StoreError::FileError(e.to_string()))
+            }
+            Compressor::Lz4 { acceleration } => {
+                let size = u32::try_from(data.len())
+                    .map_err(|_| StoreError::InvalidMetadata("LZ4 chunks must be smaller than 4 GiB".to_string()))?;
+                let mut out = size.to_le_bytes().to_vec();
+                out.extend(lz4::compress(data, acceleration.max(1) as usize));
+                Ok(out)
+            }
+        }
+    }
+
+    /// Decompress a chunk that should hold `size` bytes
+    pub(crate) fn decode(self, data: &[u8], size: usize) -> Result<Vec<u8>, StoreError> {
+        let bytes = match self {
+            Compressor::None => data.to_vec(),
+            Compressor::Zlib { .. } => {
+                let mut out = Vec::with_capacity(size);
+                ZlibDecoder::new(data).read_to_end(&mut out).map_err(|e| StoreError::CorruptChunk(e.to_string()))?;
+                out
+            }
+            Compressor::Lz4 { .. } => {
+                let header = data.get(..4).ok_or_else(|| StoreError::CorruptChunk("truncated LZ4 header".to_string()))?;
+                let stored = u32::from_le_bytes(header.try_into().unwrap()) as usize;
+                lz4::decompress(&data[4..], stored).map_err(StoreError::CorruptChunk)?
+            }
+        };
+        if bytes.len() != size {
+            return Err(StoreError::CorruptChunk(format!("chunk holds {} bytes, expected {}", bytes.len(), size)));
+        }
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,154 @@
+//! LZ4 block compression
+//!
+//! A self-contained implementation of the LZ4 block format: a sequence of
+//! literal runs, each followed by a back-reference of at least four bytes
+//! into the previous 64 KiB of output. The compressor is the single-pass
+//! greedy matcher of the reference implementation, with `acceleration`
+//! skipping ahead faster through incompressible data.
+
+/// Shortest match the format can encode
+const MIN_MATCH: usize = 4;
+
+/// Matches may not start within this many bytes of the end
+const MFLIMIT: usize = 12;
+
+/// The last bytes of a block are always literals
+const LAST_LITERALS: usize = 5;
+
+/// Farthest back a match may refer
+const MAX_DISTANCE: usize = 65535;
+
+const HASH_LOG: u32 = 16;
+
+fn read_u32(data: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]])
+}
+
+fn hash(sequence: u32) -> usize {
+    (sequence.wrapping_mul(2654435761) >> (32 - HASH_LOG)) as usize
+}
+
+/// Append a length that did not fit in the token's four bits
+fn push_length(out: &mut Vec<u8>, mut len: usize) {
+    while len >= 255 {
+        out.push(255);
+        len -= 255;
+    }
+    out.push(len as u8);
+}
+
+fn push_sequence(out: &mut Vec<u8>, literals: &[u8], match_: Option<(usize, usize)>) {
+    let lit_len = literals.len();
+    let match_len = match_.map_or(0, |(_, len)| len - MIN_MATCH);
+    let token = ((lit_len.min(15) as u8) << 4) | match_len.min(15) as u8;
+    out.push(token);
+    if lit_len >= 15 {
+        push_length(out, lit_len - 15);
+    }
+    out.extend_from_slice(literals);
+    if let Some((offset, _)) = match_ {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            push_length(out, match_len - 15);
+        }
+    }
+}
+
+/// Compress `input` into an LZ4 block
+pub(crate) fn compress(input: &[u8], acceleration: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 16);
+    let mut anchor = 0;
+    if input.len() > MFLIMIT {
+        let mut table = vec![0usize; 1 << HASH_LOG];
+        let limit = input.len() - MFLIMIT;
+        let mut pos = 0;
+        let mut misses = 0usize;
+        while pos < limit {
+            let sequence = read_u32(input, pos);
+            let slot = hash(sequence);
+            // Table entries are positions plus one, so zero means empty
+            let candidate = table[slot];
+            table[slot] = pos + 1;
+            if candidate > 0 && pos - (candidate - 1) <= MAX_DISTANCE && read_u32(input, candidate - 1) == sequence {
+                let mut start = candidate - 1;
+                let mut len = MIN_MATCH;
+                while pos + len < input.len() - LAST_LITERALS && input[start + len] == input[pos + len] {
+                    len += 1;
+                }
+                // Extend backwards over literals that also match
+                let mut pos_back = pos;
+                while pos_back > anchor && start > 0 && input[pos_back - 1] == input[start - 1] {
+                    pos_back -= 1;
+                    start -= 1;
+                    len += 1;
+                }
+                push_sequence(&mut out, &input[anchor..pos_back], Some((pos_back - start, len)));
+                pos = pos_back + len;
+                anchor = pos;
+                misses = 0;
+            } else {
+                // Step further the longer nothing matches
+                misses += 1;
+                pos += 1 + ((misses * acceleration.max(1)) >> 6);
+            }
+        }
+    }
+    push_sequence(&mut out, &input[anchor..], None);
+    out
+}
+
+/// Decompress an LZ4 block that expands to exactly `size` bytes
+pub(crate) fn decompress(input: &[u8], size: usize) -> Result<Vec<u8>, String> {
+    let truncated = || "truncated LZ4 block".to_string();
+    let mut out = Vec::with_capacity(size);
+    let mut pos = 0;
+    let read_length = |pos: &mut usize, mut len: usize| -> Result<usize, String> {
+        loop {
+            let byte = *input.get(*pos).ok_or_else(truncated)?;
+            *pos += 1;
+            len += byte as usize;
+            if byte != 255 {
+                return Ok(len);
+            }
+        }
+    };
+    loop {
+        let token = *input.get(pos).ok_or_else(truncated)?;
+        pos += 1;
+        let mut lit_len = (token >> 4) as usize;
+        if lit_len == 15 {
+            lit_len = read_length(&mut pos, lit_len)?;
+        }
+        let literals = input.get(pos..pos + lit_len).ok_or_else(truncated)?;
+        if out.len() + lit_len > size {
+            return Err("LZ4 block expands beyond the expected size".to_string());
+        }
+        out.extend_from_slice(literals);
+        pos += lit_len;
+        if pos == input.len() {
+            break;
+        }
+        let offset = u16::from_le_bytes(input.get(pos..pos + 2).ok_or_else(truncated)?.try_into().unwrap()) as usize;
+        pos += 2;
+        if offset == 0 || offset > out.len() {
+            return Err(format!("invalid LZ4 match offset {}", offset));
+        }
+        let mut match_len = (token & 15) as usize;
+        if match_len == 15 {
+            match_len = read_length(&mut pos, match_len)?;
+        }
+        match_len += MIN_MATCH;
+        if out.len() + match_len > size {
+            return Err("LZ4 block expands beyond the expected size".to_string());
+        }
+        // Matches may overlap their own output, so copy byte by byte
+        let start = out.len() - offset;
+        for i in 0..match_len {
+            out.push(out[start + i]);
+        }
+    }
+    if out.len() != size {
+        return Err(format!("LZ4 block expands to {} bytes, expected {}", out.len(), size));
+    }
+    Ok(out)
+}
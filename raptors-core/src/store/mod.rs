@@ -0,0 +1,13 @@
+//! Chunked on-disk array store
+//!
+//! This module stores arrays larger than memory as directories in the
+//! Zarr v2 format: JSON metadata plus one compressed file per chunk, read
+//! and written a slice at a time
+
+mod codec;
+mod json;
+mod lz4;
+mod zarr;
+
+pub use codec::*;
+pub use zarr::*;
@@ -0,0 +1,714 @@
+//! Zarr v2 arrays on the local filesystem
+//!
+//! An array is a directory holding a `.zarray` JSON document (shape, chunk
+//! shape, dtype descriptor, compressor, fill value and memory order) and one
+//! file per chunk, named by its chunk indices joined with the dimension
+//! separator (`0.1.2`). Chunks that were never written read as the fill
+//! value. Edge chunks are stored at full chunk size, as Zarr does.
+
+use super::codec::Compressor;
+use super::json::Json;
+use crate::array::{Array, ArrayError, Order};
+use crate::indexing::{normalize_slice, slice_length, IndexError, Slice};
+use crate::io::descr::{byteswap, descr_to_dtype, dtype_to_descr};
+use crate::io::literal::PyLiteral;
+use crate::io::{encode_value, CellValue, IoError};
+use crate::types::{Complex128, DType, NpyType};
+use crate::utils::to_contiguous_bytes;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Name of the metadata document inside an array directory
+const METADATA: &str = ".zarray";
+
+/// Chunked store error
+#[derive(Debug, Clone)]
+pub enum StoreError {
+    /// Array error
+    ArrayError(ArrayError),
+    /// File I/O error
+    FileError(String),
+    /// Malformed or unsupported `.zarray` document
+    InvalidMetadata(String),
+    /// Compressor or filter this store cannot decode
+    UnsupportedCodec(String),
+    /// Selection that does not fit the array
+    InvalidSelection(String),
+    /// Chunk that does not decompress to the expected size
+    CorruptChunk(String),
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::ArrayError(e) => write!(f, "Array error: {}", e),
+            StoreError::FileError(msg) => write!(f, "File error: {}", msg),
+            StoreError::InvalidMetadata(msg) => write!(f, "Invalid Zarr metadata: {}", msg),
+            StoreError::UnsupportedCodec(msg) => write!(f, "Unsupported codec: {}", msg),
+            StoreError::InvalidSelection(msg) => write!(f, "Invalid selection: {}", msg),
+            StoreError::CorruptChunk(msg) => write!(f, "Corrupt chunk: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<ArrayError> for StoreError {
+    fn from(err: ArrayError) -> Self {
+        StoreError::ArrayError(err)
+    }
+}
+
+impl From<IoError> for StoreError {
+    fn from(err: IoError) -> Self {
+        match err {
+            IoError::ArrayError(e) => StoreError::ArrayError(e),
+            IoError::FileError(msg) => StoreError::FileError(msg),
+            other => StoreError::InvalidMetadata(other.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for StoreError {
+    fn from(err: std::io::Error) -> Self {
+        StoreError::FileError(err.to_string())
+    }
+}
+
+/// Layout options for a new Zarr array
+#[derive(Debug, Clone)]
+pub struct ZarrOptions {
+    /// Chunk shape; empty stores the whole array as a single chunk
+    pub chunks: Vec<i64>,
+    /// Compressor applied to every chunk
+    pub compressor: Compressor,
+    /// Value of elements that were never written (`None` means zeros)
+    pub fill_value: Option<CellValue>,
+    /// Element order within a chunk, `Order::C` or `Order::F`
+    pub order: Order,
+    /// Separator between chunk indices in chunk file names, `.` or `/`
+    pub dimension_separator: char,
+}
+
+impl Default for ZarrOptions {
+    fn default() -> Self {
+        ZarrOptions {
+            chunks: Vec::new(),
+            compressor: Compressor::default(),
+            fill_value: None,
+            order: Order::C,
+            dimension_separator: '.',
+        }
+    }
+}
+
+/// A chunked array stored in a Zarr v2 directory
+///
+/// Reads and writes take one `Slice` per leading dimension (missing
+/// trailing dimensions are taken whole) and only touch the chunk files the
+/// selection overlaps. Chunks are compressed, decompressed and read or
+/// written in parallel on the Rayon pool.
+#[derive(Debug, Clone)]
+pub struct ZarrArray {
+    path: PathBuf,
+    shape: Vec<i64>,
+    chunks: Vec<i64>,
+    dtype: DType,
+    /// Byte runs stored in non-native order
+    swaps: Vec<(usize, usize)>,
+    compressor: Compressor,
+    /// One element of fill, in native byte order
+    fill: Vec<u8>,
+    fill_json: Json,
+    order: Order,
+    separator: char,
+}
+
+/// Selected elements of one dimension that fall in one chunk
+struct DimGroup {
+    chunk: i64,
+    /// `(index in the selection, index within the chunk)` pairs
+    entries: Vec<(usize, usize)>,
+}
+
+impl ZarrArray {
+    /// Create a new array directory at `path`
+    ///
+    /// Fails if `path` already holds a Zarr array. No chunks are written, so
+    /// every element starts out as the fill value.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        shape: Vec<i64>,
+        dtype: DType,
+        options: ZarrOptions,
+    ) -> Result<ZarrArray, StoreError> {
+        if shape.iter().any(|&n| n < 0) {
+            return Err(StoreError::ArrayError(ArrayError::InvalidShape));
+        }
+        let chunks = if options.chunks.is_empty() {
+            shape.iter().map(|&n| n.max(1)).collect()
+        } else {
+            options.chunks.clone()
+        };
+        if chunks.len() != shape.len() || chunks.iter().any(|&c| c < 1) {
+            return Err(StoreError::InvalidMetadata(format!(
+                "chunk shape {:?} does not fit array shape {:?}",
+                chunks, shape
+            )));
+        }
+        let (fill, fill_json) = match &options.fill_value {
+            Some(value) => {
+                let fill = encode_fill(value, &dtype)?;
+                let json = fill_to_json(&fill, &dtype);
+                (fill, json)
+            }
+            None => (vec![0; dtype.itemsize()], Json::Null),
+        };
+        let array = ZarrArray {
+            path: path.as_ref().to_path_buf(),
+            shape,
+            chunks,
+            dtype,
+            swaps: Vec::new(),
+            compressor: options.compressor,
+            fill,
+            fill_json,
+            order: options.order,
+            separator: options.dimension_separator,
+        };
+        if !matches!(array.order, Order::C | Order::F) {
+            return Err(StoreError::InvalidMetadata("order must be C or F".to_string()));
+        }
+        if !matches!(array.separator, '.' | '/') {
+            return Err(StoreError::InvalidMetadata(format!("invalid dimension separator '{}'", array.separator)));
+        }
+        let metadata = array.metadata()?.to_pretty();
+        if array.path.join(METADATA).exists() {
+            return Err(StoreError::FileError(format!("{} already holds a Zarr array", array.path.display())));
+        }
+        fs::create_dir_all(&array.path)?;
+        fs::write(array.path.join(METADATA), metadata)?;
+        Ok(array)
+    }
+
+    /// Open an existing array directory
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ZarrArray, StoreError> {
+        let path = path.as_ref().to_path_buf();
+        let text = fs::read_to_string(path.join(METADATA))?;
+        let meta = Json::parse(&text)?;
+        let field = |key: &str| meta.get(key).ok_or_else(|| StoreError::InvalidMetadata(format!("missing '{}'", key)));
+
+        if field("zarr_format")?.as_i64() != Some(2) {
+            return Err(StoreError::InvalidMetadata("only zarr_format 2 is supported".to_string()));
+        }
+        let dims = |key: &str| -> Result<Vec<i64>, StoreError> {
+            match field(key)? {
+                Json::Array(items) => items
+                    .iter()
+                    .map(|v| v.as_i64().filter(|&n| n >= 0))
+                    .collect::<Option<Vec<i64>>>()
+                    .ok_or_else(|| StoreError::InvalidMetadata(format!("invalid '{}'", key))),
+                _ => Err(StoreError::InvalidMetadata(format!("invalid '{}'", key))),
+            }
+        };
+        let shape = dims("shape")?;
+        let chunks = dims("chunks")?;
+        if chunks.len() != shape.len() || chunks.contains(&0) {
+            return Err(StoreError::InvalidMetadata(format!(
+                "chunk shape {:?} does not fit array shape {:?}",
+                chunks, shape
+            )));
+        }
+        let descr = descr_to_dtype(&json_to_descr(field("dtype")?)?)?;
+        let compressor = Compressor::from_json(field("compressor")?)?;
+        match meta.get("filters") {
+            None | Some(Json::Null) => {}
+            Some(Json::Array(items)) if items.is_empty() => {}
+            Some(_) => return Err(StoreError::UnsupportedCodec("filters".to_string())),
+        }
+        let order = match field("order")? {
+            Json::Str(s) if s == "C" => Order::C,
+            Json::Str(s) if s == "F" => Order::F,
+            _ => return Err(StoreError::InvalidMetadata("order must be \"C\" or \"F\"".to_string())),
+        };
+        let separator = match meta.get("dimension_separator") {
+            None | Some(Json::Null) => '.',
+            Some(Json::Str(s)) if s == "." || s == "/" => s.chars().next().unwrap(),
+            Some(_) => return Err(StoreError::InvalidMetadata("invalid dimension_separator".to_string())),
+        };
+        let fill_json = meta.get("fill_value").cloned().unwrap_or(Json::Null);
+        let fill = fill_from_json(&fill_json, &descr.dtype, &descr.swaps)?;
+        Ok(ZarrArray {
+            path,
+            shape,
+            chunks,
+            dtype: descr.dtype,
+            swaps: descr.swaps,
+            compressor,
+            fill,
+            fill_json,
+            order,
+            separator,
+        })
+    }
+
+    /// Directory holding the array
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Array shape
+    pub fn shape(&self) -> &[i64] {
+        &self.shape
+    }
+
+    /// Chunk shape
+    pub fn chunks(&self) -> &[i64] {
+        &self.chunks
+    }
+
+    /// Number of chunks along each dimension
+    pub fn chunk_grid(&self) -> Vec<i64> {
+        self.shape.iter().zip(&self.chunks).map(|(&n, &c)| (n + c - 1) / c).collect()
+    }
+
+    /// Element dtype
+    pub fn dtype(&self) -> &DType {
+        &self.dtype
+    }
+
+    /// Chunk compressor
+    pub fn compressor(&self) -> Compressor {
+        self.compressor
+    }
+
+    /// Element order within a chunk
+    pub fn order(&self) -> Order {
+        self.order
+    }
+
+    /// Name of the chunk file at the given chunk indices
+    pub fn chunk_key(&self, indices: &[i64]) -> String {
+        if indices.is_empty() {
+            return "0".to_string();
+        }
+        let parts: Vec<String> = indices.iter().map(|i| i.to_string()).collect();
+        parts.join(&self.separator.to_string())
+    }
+
+    /// Read the elements selected by `selection` into a new array
+    ///
+    /// An empty selection reads the whole array.
+    pub fn read(&self, selection: &[Slice]) -> Result<Array, StoreError> {
+        let itemsize = self.dtype.itemsize();
+        let (out_shape, groups) = self.plan(selection)?;
+        let out_strides = c_strides(&out_shape);
+        let chunk_strides = self.chunk_strides();
+        let size: usize = out_shape.iter().product::<i64>() as usize;
+        let out = Mutex::new(vec![0u8; size * itemsize]);
+
+        self.touched(&groups).par_iter().try_for_each(|task| -> Result<(), StoreError> {
+            let coords: Vec<i64> = task.iter().map(|g| g.chunk).collect();
+            let chunk = self.load_chunk(&coords)?;
+            let mut out = out.lock().unwrap();
+            for_each_pair(task, &out_strides, &chunk_strides, |dst, src| {
+                let value = match &chunk {
+                    Some(bytes) => &bytes[src * itemsize..(src + 1) * itemsize],
+                    None => &self.fill[..],
+                };
+                out[dst * itemsize..(dst + 1) * itemsize].copy_from_slice(value);
+            });
+            Ok(())
+        })?;
+
+        let out = out.into_inner().unwrap();
+        Ok(Array::from_slice(&out, out_shape, self.dtype.clone())?)
+    }
+
+    /// Write `values` into the elements selected by `selection`
+    ///
+    /// `values` must have the dtype of the store and the shape of the
+    /// selection. Chunks that are only partly covered are read, updated and
+    /// written back; fully covered chunks are written without reading.
+    pub fn write(&self, selection: &[Slice], values: &Array) -> Result<(), StoreError> {
+        let itemsize = self.dtype.itemsize();
+        if values.dtype().type_() != self.dtype.type_() || values.itemsize() != itemsize {
+            return Err(StoreError::InvalidSelection(format!(
+                "cannot write {} values into a {} store",
+                values.dtype(),
+                self.dtype
+            )));
+        }
+        let (out_shape, groups) = self.plan(selection)?;
+        if values.shape() != out_shape.as_slice() {
+            return Err(StoreError::InvalidSelection(format!(
+                "values of shape {:?} do not match selection of shape {:?}",
+                values.shape(),
+                out_shape
+            )));
+        }
+        // Gather once up front: arrays cannot be shared across threads
+        let source = to_contiguous_bytes(values);
+        let src_strides = c_strides(&out_shape);
+        let chunk_strides = self.chunk_strides();
+        let chunk_len: usize = self.chunks.iter().product::<i64>() as usize;
+
+        self.touched(&groups).par_iter().try_for_each(|task| -> Result<(), StoreError> {
+            let coords: Vec<i64> = task.iter().map(|g| g.chunk).collect();
+            let covered = task.iter().enumerate().all(|(d, g)| {
+                let start = g.chunk * self.chunks[d];
+                let extent = (self.shape[d] - start).min(self.chunks[d]);
+                g.entries.len() as i64 == extent
+            });
+            let existing = if covered { None } else { self.load_chunk(&coords)? };
+            let mut chunk = existing.unwrap_or_else(|| self.fill.repeat(chunk_len));
+            for_each_pair(task, &src_strides, &chunk_strides, |src, dst| {
+                chunk[dst * itemsize..(dst + 1) * itemsize].copy_from_slice(&source[src * itemsize..(src + 1) * itemsize]);
+            });
+            self.store_chunk(&coords, chunk)
+        })
+    }
+
+    /// `.zarray` document describing the array
+    fn metadata(&self) -> Result<Json, StoreError> {
+        let dims = |values: &[i64]| Json::Array(values.iter().map(|&n| Json::Int(n)).collect());
+        let order = if self.order == Order::F { "F" } else { "C" };
+        Ok(Json::Object(vec![
+            ("chunks".to_string(), dims(&self.chunks)),
+            ("compressor".to_string(), self.compressor.to_json()),
+            ("dimension_separator".to_string(), Json::Str(self.separator.to_string())),
+            ("dtype".to_string(), descr_to_json(&dtype_to_descr(&self.dtype)?)),
+            ("fill_value".to_string(), self.fill_json.clone()),
+            ("filters".to_string(), Json::Null),
+            ("order".to_string(), Json::Str(order.to_string())),
+            ("shape".to_string(), dims(&self.shape)),
+            ("zarr_format".to_string(), Json::Int(2)),
+        ]))
+    }
+
+    /// Element strides within a chunk, in the array's order
+    fn chunk_strides(&self) -> Vec<usize> {
+        if self.order == Order::F {
+            let mut strides = Vec::with_capacity(self.chunks.len());
+            let mut stride = 1;
+            for &c in &self.chunks {
+                strides.push(stride);
+                stride *= c as usize;
+            }
+            strides
+        } else {
+            c_strides(&self.chunks)
+        }
+    }
+
+    /// Split a selection into per-dimension chunk groups
+    fn plan(&self, selection: &[Slice]) -> Result<(Vec<i64>, Vec<Vec<DimGroup>>), StoreError> {
+        if selection.len() > self.shape.len() {
+            return Err(StoreError::InvalidSelection(format!(
+                "{} slices for a {}-dimensional array",
+                selection.len(),
+                self.shape.len()
+            )));
+        }
+        let full = Slice::full();
+        let mut out_shape = Vec::with_capacity(self.shape.len());
+        let mut groups = Vec::with_capacity(self.shape.len());
+        for (d, (&dim, &chunk)) in self.shape.iter().zip(&self.chunks).enumerate() {
+            let slice = selection.get(d).unwrap_or(&full);
+            let (start, stop, step) = normalize_slice(slice, dim)
+                .map_err(|e: IndexError| StoreError::InvalidSelection(format!("dimension {}: {}", d, e)))?;
+            let len = slice_length(start, stop, step);
+            let mut by_chunk: BTreeMap<i64, Vec<(usize, usize)>> = BTreeMap::new();
+            for j in 0..len {
+                let pos = start + j * step;
+                by_chunk.entry(pos / chunk).or_default().push((j as usize, (pos % chunk) as usize));
+            }
+            out_shape.push(len);
+            groups.push(by_chunk.into_iter().map(|(chunk, entries)| DimGroup { chunk, entries }).collect());
+        }
+        Ok((out_shape, groups))
+    }
+
+    /// Every chunk a planned selection touches, as one group per dimension
+    fn touched<'a>(&self, groups: &'a [Vec<DimGroup>]) -> Vec<Vec<&'a DimGroup>> {
+        let mut tasks = Vec::new();
+        if groups.iter().any(|g| g.is_empty()) {
+            return tasks;
+        }
+        let mut counter = vec![0usize; groups.len()];
+        loop {
+            tasks.push(counter.iter().zip(groups).map(|(&i, g)| &g[i]).collect());
+            if !advance(&mut counter, |d| groups[d].len()) {
+                return tasks;
+            }
+        }
+    }
+
+    fn chunk_path(&self, coords: &[i64]) -> PathBuf {
+        self.path.join(self.chunk_key(coords))
+    }
+
+    /// Decompressed chunk in native byte order, or `None` if never written
+    fn load_chunk(&self, coords: &[i64]) -> Result<Option<Vec<u8>>, StoreError> {
+        let path = self.chunk_path(coords);
+        let raw = match fs::read(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let size = self.chunks.iter().product::<i64>() as usize * self.dtype.itemsize();
+        let mut bytes = self
+            .compressor
+            .decode(&raw, size)
+            .map_err(|e| StoreError::CorruptChunk(format!("{}: {}", path.display(), e)))?;
+        byteswap(&mut bytes, self.dtype.itemsize(), &self.swaps);
+        Ok(Some(bytes))
+    }
+
+    /// Compress and write a chunk given in native byte order
+    fn store_chunk(&self, coords: &[i64], mut bytes: Vec<u8>) -> Result<(), StoreError> {
+        byteswap(&mut bytes, self.dtype.itemsize(), &self.swaps);
+        let encoded = self.compressor.encode(&bytes)?;
+        let path = self.chunk_path(coords);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, encoded)?;
+        Ok(())
+    }
+}
+
+/// Write an array to a new Zarr directory
+pub fn save_zarr<P: AsRef<Path>>(path: P, array: &Array, options: ZarrOptions) -> Result<ZarrArray, StoreError> {
+    let store = ZarrArray::create(path, array.shape().to_vec(), array.dtype().clone(), options)?;
+    store.write(&[], array)?;
+    Ok(store)
+}
+
+/// Read a whole Zarr array into memory
+pub fn load_zarr<P: AsRef<Path>>(path: P) -> Result<Array, StoreError> {
+    ZarrArray::open(path)?.read(&[])
+}
+
+/// Row-major element strides of a shape
+fn c_strides(shape: &[i64]) -> Vec<usize> {
+    let mut strides = vec![1usize; shape.len()];
+    for d in (0..shape.len().saturating_sub(1)).rev() {
+        strides[d] = strides[d + 1] * shape[d + 1] as usize;
+    }
+    strides
+}
+
+/// Step a mixed-radix counter; returns `false` once it wraps around
+fn advance(counter: &mut [usize], radix: impl Fn(usize) -> usize) -> bool {
+    for d in (0..counter.len()).rev() {
+        counter[d] += 1;
+        if counter[d] < radix(d) {
+            return true;
+        }
+        counter[d] = 0;
+    }
+    false
+}
+
+/// Call `f(selection offset, chunk offset)` for every element of a chunk task
+fn for_each_pair(
+    task: &[&DimGroup],
+    sel_strides: &[usize],
+    chunk_strides: &[usize],
+    mut f: impl FnMut(usize, usize),
+) {
+    let mut counter = vec![0usize; task.len()];
+    loop {
+        let mut sel = 0;
+        let mut chunk = 0;
+        for (d, &i) in counter.iter().enumerate() {
+            let (j, local) = task[d].entries[i];
+            sel += j * sel_strides[d];
+            chunk += local * chunk_strides[d];
+        }
+        f(sel, chunk);
+        if !advance(&mut counter, |d| task[d].entries.len()) {
+            return;
+        }
+    }
+}
+
+/// One element of `value` in `dtype`, in native byte order
+fn encode_fill(value: &CellValue, dtype: &DType) -> Result<Vec<u8>, StoreError> {
+    let supported = matches!(
+        dtype.type_(),
+        NpyType::Bool
+            | NpyType::Byte
+            | NpyType::UByte
+            | NpyType::Short
+            | NpyType::UShort
+            | NpyType::Int
+            | NpyType::UInt
+            | NpyType::Long
+            | NpyType::ULong
+            | NpyType::LongLong
+            | NpyType::ULongLong
+            | NpyType::Float
+            | NpyType::Double
+            | NpyType::CFloat
+            | NpyType::CDouble
+            | NpyType::DateTime
+            | NpyType::Timedelta
+            | NpyType::String
+            | NpyType::Unicode
+    );
+    if !supported || dtype.fields().is_some() {
+        return Err(StoreError::InvalidMetadata(format!("fill values are not supported for {}", dtype)));
+    }
+    let mut out = vec![0u8; dtype.itemsize()];
+    encode_value(value, dtype, &mut out).map_err(StoreError::InvalidMetadata)?;
+    Ok(out)
+}
+
+/// JSON spelling of a float fill value; non-finite values are strings
+fn float_json(value: f64) -> Json {
+    if value.is_nan() {
+        Json::Str("NaN".to_string())
+    } else if value.is_infinite() {
+        Json::Str(if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string())
+    } else {
+        Json::Float(value)
+    }
+}
+
+/// `fill_value` entry for a native-order fill element
+fn fill_to_json(fill: &[u8], dtype: &DType) -> Json {
+    let f32_at = |at: usize| f32::from_ne_bytes(fill[at..at + 4].try_into().unwrap()) as f64;
+    let f64_at = |at: usize| f64::from_ne_bytes(fill[at..at + 8].try_into().unwrap());
+    match dtype.type_() {
+        NpyType::Bool => Json::Bool(fill[0] != 0),
+        NpyType::Float => float_json(f32_at(0)),
+        NpyType::Double => float_json(f64_at(0)),
+        NpyType::CFloat => Json::Array(vec![float_json(f32_at(0)), float_json(f32_at(4))]),
+        NpyType::CDouble => Json::Array(vec![float_json(f64_at(0)), float_json(f64_at(8))]),
+        NpyType::ULong | NpyType::ULongLong => {
+            let value = u64::from_ne_bytes(fill.try_into().unwrap());
+            i64::try_from(value).map(Json::Int).unwrap_or(Json::Float(value as f64))
+        }
+        NpyType::String | NpyType::Unicode => Json::Str(base64_encode(fill)),
+        NpyType::Byte => Json::Int(fill[0] as i8 as i64),
+        NpyType::UByte => Json::Int(fill[0] as i64),
+        NpyType::Short => Json::Int(i16::from_ne_bytes(fill.try_into().unwrap()) as i64),
+        NpyType::UShort => Json::Int(u16::from_ne_bytes(fill.try_into().unwrap()) as i64),
+        NpyType::Int => Json::Int(i32::from_ne_bytes(fill.try_into().unwrap()) as i64),
+        NpyType::UInt => Json::Int(u32::from_ne_bytes(fill.try_into().unwrap()) as i64),
+        // Remaining supported types are 64-bit integers, datetimes included
+        _ => Json::Int(i64::from_ne_bytes(fill.try_into().unwrap())),
+    }
+}
+
+/// Native-order fill element for a `fill_value` entry
+fn fill_from_json(value: &Json, dtype: &DType, swaps: &[(usize, usize)]) -> Result<Vec<u8>, StoreError> {
+    let invalid = || StoreError::InvalidMetadata(format!("invalid fill_value for {}", dtype));
+    let number = |v: &Json| match v {
+        Json::Int(i) => Some(*i as f64),
+        Json::Float(f) => Some(*f),
+        Json::Str(s) if s == "NaN" => Some(f64::NAN),
+        Json::Str(s) if s == "Infinity" => Some(f64::INFINITY),
+        Json::Str(s) if s == "-Infinity" => Some(f64::NEG_INFINITY),
+        _ => None,
+    };
+    let raw = matches!(dtype.type_(), NpyType::String | NpyType::Unicode | NpyType::Void) || dtype.fields().is_some();
+    let cell = match value {
+        Json::Null => return Ok(vec![0; dtype.itemsize()]),
+        Json::Str(s) if raw => {
+            let mut bytes = base64_decode(s).filter(|b| b.len() == dtype.itemsize()).ok_or_else(invalid)?;
+            byteswap(&mut bytes, dtype.itemsize(), swaps);
+            return Ok(bytes);
+        }
+        Json::Bool(b) => CellValue::Bool(*b),
+        Json::Int(i) => CellValue::Int(*i),
+        Json::Array(parts) if parts.len() == 2 => {
+            let re = number(&parts[0]).ok_or_else(invalid)?;
+            let im = number(&parts[1]).ok_or_else(invalid)?;
+            CellValue::Complex(Complex128::new(re, im))
+        }
+        other => CellValue::Float(number(other).ok_or_else(invalid)?),
+    };
+    if matches!(cell, CellValue::Int(0) | CellValue::Bool(false)) || cell == CellValue::Float(0.0) {
+        // Zero is representable in every dtype, including ones we cannot encode
+        return Ok(vec![0; dtype.itemsize()]);
+    }
+    encode_fill(&cell, dtype)
+}
+
+/// `dtype` entry for a descr: a type string, or a list of field lists
+fn descr_to_json(descr: &PyLiteral) -> Json {
+    match descr {
+        PyLiteral::Str(s) => Json::Str(s.clone()),
+        PyLiteral::Int(n) => Json::Int(*n),
+        PyLiteral::Tuple(items) | PyLiteral::List(items) => Json::Array(items.iter().map(descr_to_json).collect()),
+        _ => Json::Null,
+    }
+}
+
+/// Descr for a `dtype` entry
+fn json_to_descr(value: &Json) -> Result<PyLiteral, StoreError> {
+    let invalid = || StoreError::InvalidMetadata("invalid dtype".to_string());
+    match value {
+        Json::Str(s) => Ok(PyLiteral::Str(s.clone())),
+        Json::Array(fields) => fields
+            .iter()
+            .map(|field| match field {
+                Json::Array(parts) if (2..=3).contains(&parts.len()) => {
+                    let name = match &parts[0] {
+                        Json::Str(name) => PyLiteral::Str(name.clone()),
+                        _ => return Err(invalid()),
+                    };
+                    let mut entry = vec![name, json_to_descr(&parts[1])?];
+                    if let Some(Json::Array(dims)) = parts.get(2) {
+                        let dims = dims.iter().map(|d| d.as_i64().map(PyLiteral::Int)).collect::<Option<Vec<_>>>();
+                        entry.push(PyLiteral::Tuple(dims.ok_or_else(invalid)?));
+                    }
+                    Ok(PyLiteral::Tuple(entry))
+                }
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(PyLiteral::List),
+        _ => Err(invalid()),
+    }
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let n = group.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= group.len() {
+                out.push(BASE64[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        acc = (acc << 6) | BASE64.iter().position(|&b| b == c)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
@@ -5,19 +5,14 @@ use crate::array::Array;
 use super::StructuredDType;
 
 /// Validate that array has structured dtype
-///
-/// For now, this is a placeholder - full implementation would check
-/// if the array's dtype is actually structured
-pub fn is_structured_array(_array: &Array) -> bool {
-    // Placeholder - would check dtype
-    false
+pub fn is_structured_array(array: &Array) -> bool {
+    array.dtype().fields().is_some()
 }
 
 /// Get structured dtype from array
 ///
 /// Returns the structured dtype if array is structured
-pub fn get_structured_dtype(_array: &Array) -> Option<StructuredDType> {
-    // Placeholder - would extract from array dtype
-    None
+pub fn get_structured_dtype(array: &Array) -> Option<StructuredDType> {
+    array.dtype().fields().cloned()
 }
 
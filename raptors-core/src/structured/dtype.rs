@@ -44,11 +44,32 @@ pub struct Field {
     pub dtype: DType,
     /// Byte offset of field in structure
     pub offset: usize,
+    /// Subarray shape of the field (empty for scalar fields)
+    pub shape: Vec<i64>,
+}
+
+impl Field {
+    /// Create a scalar field at `offset`
+    pub fn new(name: impl Into<String>, dtype: DType, offset: usize) -> Self {
+        Field { name: name.into(), dtype, offset, shape: Vec::new() }
+    }
+
+    /// Make the field a subarray of `shape` elements
+    pub fn with_shape(mut self, shape: Vec<i64>) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Size of the field in bytes
+    pub fn size(&self) -> usize {
+        self.dtype.itemsize() * self.shape.iter().product::<i64>() as usize
+    }
 }
 
 /// Structured dtype definition
 ///
 /// Represents a structured/compound dtype with multiple named fields
+#[derive(Debug, Clone)]
 pub struct StructuredDType {
     /// Field definitions
     fields: Vec<Field>,
@@ -84,11 +105,7 @@ impl StructuredDType {
             let align = dtype.align();
             current_offset = current_offset.div_ceil(align) * align;
             
-            struct_fields.push(Field {
-                name,
-                dtype: dtype.clone(),
-                offset: current_offset,
-            });
+            struct_fields.push(Field::new(name, dtype.clone(), current_offset));
             
             current_offset += field_size;
         }
@@ -103,6 +120,27 @@ impl StructuredDType {
         })
     }
     
+    /// Create a structured dtype with explicit field offsets
+    ///
+    /// Unlike `new`, no alignment is applied: fields sit exactly at their
+    /// offsets (NumPy's packed layout, or one read from a file) and
+    /// `itemsize` may include trailing padding. Fails if a field does not
+    /// fit in `itemsize`.
+    pub fn with_offsets(fields: Vec<Field>, itemsize: usize) -> Result<Self, StructuredError> {
+        if fields.is_empty() {
+            return Err(StructuredError::InvalidFieldName);
+        }
+        for field in &fields {
+            if field.name.is_empty() {
+                return Err(StructuredError::InvalidFieldName);
+            }
+            if field.offset + field.size() > itemsize {
+                return Err(StructuredError::InvalidOffset);
+            }
+        }
+        Ok(StructuredDType { fields, itemsize })
+    }
+
     /// Get number of fields
     pub fn num_fields(&self) -> usize {
         self.fields.len()
@@ -0,0 +1,171 @@
+//! Complex scalar types
+//!
+//! This module provides the element types backing the `CFloat` and `CDouble`
+//! dtypes. The layout matches NumPy's `npy_cfloat`/`npy_cdouble` (and C99
+//! `complex`): the real part followed by the imaginary part.
+
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Complex number with real and imaginary parts of type `T`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Complex<T> {
+    /// Real part
+    pub re: T,
+    /// Imaginary part
+    pub im: T,
+}
+
+/// Element type of the `CDouble` (`complex128`) dtype
+pub type Complex128 = Complex<f64>;
+
+/// Element type of the `CFloat` (`complex64`) dtype
+pub type Complex64 = Complex<f32>;
+
+impl<T> Complex<T> {
+    /// Create a complex number from its parts
+    pub const fn new(re: T, im: T) -> Self {
+        Complex { re, im }
+    }
+}
+
+impl<T: Copy + Neg<Output = T>> Complex<T> {
+    /// Complex conjugate
+    pub fn conj(self) -> Self {
+        Complex { re: self.re, im: -self.im }
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Mul<Output = T>> Complex<T> {
+    /// Squared magnitude `re² + im²`
+    pub fn norm_sqr(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// Multiply both parts by a real scalar
+    pub fn scale(self, factor: T) -> Self {
+        Complex { re: self.re * factor, im: self.im * factor }
+    }
+}
+
+impl Complex<f64> {
+    /// Create a complex number from polar coordinates
+    pub fn from_polar(r: f64, theta: f64) -> Self {
+        Complex { re: r * theta.cos(), im: r * theta.sin() }
+    }
+
+    /// Magnitude (absolute value)
+    pub fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Phase angle in radians
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    /// Narrow to single precision
+    pub fn to_complex64(self) -> Complex<f32> {
+        Complex { re: self.re as f32, im: self.im as f32 }
+    }
+}
+
+impl Complex<f32> {
+    /// Create a complex number from polar coordinates
+    pub fn from_polar(r: f32, theta: f32) -> Self {
+        Complex { re: r * theta.cos(), im: r * theta.sin() }
+    }
+
+    /// Magnitude (absolute value)
+    pub fn abs(self) -> f32 {
+        self.re.hypot(self.im)
+    }
+
+    /// Phase angle in radians
+    pub fn arg(self) -> f32 {
+        self.im.atan2(self.re)
+    }
+
+    /// Widen to double precision
+    pub fn to_complex128(self) -> Complex<f64> {
+        Complex { re: self.re as f64, im: self.im as f64 }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Complex<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Complex { re: self.re + rhs.re, im: self.im + rhs.im }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Complex<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Complex { re: self.re - rhs.re, im: self.im - rhs.im }
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Mul for Complex<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Complex {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>> Div for Complex<T> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let denom = rhs.re * rhs.re + rhs.im * rhs.im;
+        Complex {
+            re: (self.re * rhs.re + self.im * rhs.im) / denom,
+            im: (self.im * rhs.re - self.re * rhs.im) / denom,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Complex<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Complex { re: -self.re, im: -self.im }
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Complex<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.re = self.re + rhs.re;
+        self.im = self.im + rhs.im;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Complex<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.re = self.re - rhs.re;
+        self.im = self.im - rhs.im;
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> MulAssign for Complex<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Default> From<T> for Complex<T> {
+    fn from(re: T) -> Self {
+        Complex { re, im: T::default() }
+    }
+}
+
+impl<T: std::fmt::Display + PartialOrd + Default + Copy + Neg<Output = T>> std::fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.im < T::default() {
+            write!(f, "({}-{}j)", self.re, -self.im)
+        } else {
+            write!(f, "({}+{}j)", self.re, self.im)
+        }
+    }
+}
@@ -3,7 +3,10 @@
 //! This module provides dtype functionality, equivalent to NumPy's
 //! dtype system from descriptor.c and related files
 
+use crate::datetime::TimeUnit;
+use crate::structured::StructuredDType;
 use std::fmt;
+use std::sync::Arc;
 
 /// NumPy-compatible type enumeration
 ///
@@ -81,6 +84,10 @@ pub struct DType {
     /// Custom type metadata (optional)
     #[allow(dead_code)] // Reserved for future use
     custom_metadata: Option<String>,
+    /// Unit of DateTime/Timedelta dtypes (`None` is NumPy's generic unit)
+    time_unit: Option<TimeUnit>,
+    /// Field layout of structured dtypes
+    fields: Option<Arc<StructuredDType>>,
 }
 
 impl DType {
@@ -107,6 +114,8 @@ impl DType {
             NpyType::Half => (2, 2, "float16".to_string()),
             NpyType::String => (1, 1, "string".to_string()), // Variable length, default to 1
             NpyType::Unicode => (4, 4, "unicode".to_string()), // Variable length, default to 4 bytes per char
+            NpyType::DateTime => (8, 8, "datetime64".to_string()),
+            NpyType::Timedelta => (8, 8, "timedelta64".to_string()),
             _ => (8, 8, "object".to_string()), // Default for unimplemented types
         };
         
@@ -117,6 +126,8 @@ impl DType {
             name,
             custom_type_id: None,
             custom_metadata: None,
+            time_unit: None,
+            fields: None,
         }
     }
     
@@ -154,6 +165,8 @@ impl DType {
             name,
             custom_type_id: Some(custom_type_id),
             custom_metadata: None,
+            time_unit: None,
+            fields: None,
         }
     }
     
@@ -169,8 +182,91 @@ impl DType {
             name: format!("string{}", itemsize),
             custom_type_id: None,
             custom_metadata: None,
+            time_unit: None,
+            fields: None,
         }
     }
+
+    /// Create a unicode (UCS4) dtype with custom itemsize
+    ///
+    /// `itemsize` is in bytes, four per character
+    pub fn unicode_with_itemsize(itemsize: usize) -> Self {
+        DType {
+            type_: NpyType::Unicode,
+            itemsize,
+            align: 4,
+            name: format!("unicode{}", itemsize / 4),
+            custom_type_id: None,
+            custom_metadata: None,
+            time_unit: None,
+            fields: None,
+        }
+    }
+
+    /// Create an unstructured void dtype of `itemsize` raw bytes
+    pub fn void_with_itemsize(itemsize: usize) -> Self {
+        DType {
+            type_: NpyType::Void,
+            itemsize,
+            align: 1,
+            name: format!("void{}", itemsize * 8),
+            custom_type_id: None,
+            custom_metadata: None,
+            time_unit: None,
+            fields: None,
+        }
+    }
+
+    /// Create a datetime64 dtype with the given unit
+    pub fn datetime(unit: TimeUnit) -> Self {
+        DType {
+            name: format!("datetime64[{}]", unit.code()),
+            time_unit: Some(unit),
+            ..DType::new(NpyType::DateTime)
+        }
+    }
+
+    /// Create a timedelta64 dtype with the given unit
+    pub fn timedelta(unit: TimeUnit) -> Self {
+        DType {
+            name: format!("timedelta64[{}]", unit.code()),
+            time_unit: Some(unit),
+            ..DType::new(NpyType::Timedelta)
+        }
+    }
+
+    /// Create a structured dtype from a field layout
+    ///
+    /// The alignment is the largest field alignment when every field sits
+    /// at an aligned offset, and 1 for packed layouts.
+    pub fn structured(layout: StructuredDType) -> Self {
+        let aligned = layout.fields().iter().all(|f| f.offset % f.dtype.align() == 0);
+        let align = if aligned {
+            layout.fields().iter().map(|f| f.dtype.align()).max().unwrap_or(1)
+        } else {
+            1
+        };
+        DType {
+            type_: NpyType::Void,
+            itemsize: layout.itemsize(),
+            align,
+            name: format!("void{}", layout.itemsize() * 8),
+            custom_type_id: None,
+            custom_metadata: None,
+            time_unit: None,
+            fields: Some(Arc::new(layout)),
+        }
+    }
+
+    /// Get the unit of a DateTime/Timedelta dtype
+    pub fn time_unit(&self) -> Option<TimeUnit> {
+        self.time_unit
+    }
+
+    /// Get the field layout of a structured dtype
+    pub fn fields(&self) -> Option<&StructuredDType> {
+        self.fields.as_deref()
+    }
 }
 
 impl fmt::Display for DType {
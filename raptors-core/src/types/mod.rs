@@ -3,9 +3,10 @@
 //! This module provides the dtype system, equivalent to NumPy's
 //! dtype and type system implementation
 
+mod complex;
 mod dtype;
 mod user_defined;
 
+pub use complex::*;
 pub use dtype::*;
 pub use user_defined::*;
-
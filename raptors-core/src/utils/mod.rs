@@ -12,3 +12,130 @@ pub fn is_valid_shape(shape: &[i64]) -> bool {
     shape.iter().all(|&dim| dim >= 0) && shape.len() <= crate::array::MAXDIMS
 }
 
+
+/// Compute the byte offset of every element of an array, in C (row-major) order
+///
+/// Offsets are relative to the array's data pointer and honour arbitrary
+/// (including negative) strides, so they can be used to gather the logical
+/// contents of any view.
+pub fn element_offsets(shape: &[i64], strides: &[i64]) -> Vec<isize> {
+    let size = compute_size(shape);
+    let mut offsets = Vec::with_capacity(size);
+    if size == 0 {
+        return offsets;
+    }
+    let ndim = shape.len();
+    let mut coords = vec![0i64; ndim];
+    let mut offset: isize = 0;
+    for _ in 0..size {
+        offsets.push(offset);
+        // Increment coordinates from the innermost axis outwards
+        for axis in (0..ndim).rev() {
+            coords[axis] += 1;
+            offset += strides[axis] as isize;
+            if coords[axis] < shape[axis] {
+                break;
+            }
+            offset -= (strides[axis] * shape[axis]) as isize;
+            coords[axis] = 0;
+        }
+    }
+    offsets
+}
+
+/// Copy the logical contents of an array into a C-contiguous byte buffer
+///
+/// Unlike `Array::copy`, this respects the strides of non-contiguous views.
+pub fn to_contiguous_bytes(array: &crate::array::Array) -> Vec<u8> {
+    let itemsize = array.itemsize();
+    if array.is_c_contiguous() {
+        let len = array.size() * itemsize;
+        if len == 0 {
+            return Vec::new();
+        }
+        return unsafe { std::slice::from_raw_parts(array.data_ptr(), len).to_vec() };
+    }
+    let offsets = element_offsets(array.shape(), array.strides());
+    let mut bytes = Vec::with_capacity(offsets.len() * itemsize);
+    unsafe {
+        let base = array.data_ptr();
+        for &offset in &offsets {
+            bytes.extend_from_slice(std::slice::from_raw_parts(base.offset(offset), itemsize));
+        }
+    }
+    bytes
+}
+
+/// Read a single element of a real numeric (or boolean) type as `f64`
+///
+/// Returns `None` for types that have no lossless-enough real representation
+/// (complex, string, object, ...).
+///
+/// # Safety
+/// `ptr` must point to a valid, readable element of type `ty`.
+pub unsafe fn read_element_f64(ptr: *const u8, ty: crate::types::NpyType) -> Option<f64> {
+    use crate::types::NpyType::*;
+    let value = match ty {
+        Bool => (*ptr != 0) as u8 as f64,
+        Byte => *(ptr as *const i8) as f64,
+        UByte => *ptr as f64,
+        Short => std::ptr::read_unaligned(ptr as *const i16) as f64,
+        UShort => std::ptr::read_unaligned(ptr as *const u16) as f64,
+        Int => std::ptr::read_unaligned(ptr as *const i32) as f64,
+        UInt => std::ptr::read_unaligned(ptr as *const u32) as f64,
+        Long | LongLong => std::ptr::read_unaligned(ptr as *const i64) as f64,
+        ULong | ULongLong => std::ptr::read_unaligned(ptr as *const u64) as f64,
+        Float => std::ptr::read_unaligned(ptr as *const f32) as f64,
+        Double => std::ptr::read_unaligned(ptr as *const f64),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Read every element of a real numeric array as `f64`, in C order
+///
+/// Returns `None` if the dtype is not a real numeric or boolean type.
+pub fn to_f64_vec(array: &crate::array::Array) -> Option<Vec<f64>> {
+    use crate::types::NpyType::*;
+    let ty = array.dtype().type_();
+    if !matches!(
+        ty,
+        Bool | Byte | UByte | Short | UShort | Int | UInt | Long | ULong | LongLong | ULongLong | Float | Double
+    ) {
+        return None;
+    }
+    let offsets = element_offsets(array.shape(), array.strides());
+    let mut values = Vec::with_capacity(offsets.len());
+    unsafe {
+        let base = array.data_ptr();
+        for &offset in &offsets {
+            values.push(read_element_f64(base.offset(offset), ty)?);
+        }
+    }
+    Some(values)
+}
+
+/// Read every element of a numeric array as a `complex128` value, in C order
+///
+/// Real inputs get a zero imaginary part. Returns `None` for non-numeric dtypes.
+pub fn to_complex128_vec(array: &crate::array::Array) -> Option<Vec<crate::types::Complex128>> {
+    use crate::types::{Complex, NpyType};
+    let ty = array.dtype().type_();
+    let offsets = element_offsets(array.shape(), array.strides());
+    let base = array.data_ptr();
+    match ty {
+        NpyType::CDouble => Some(
+            offsets
+                .iter()
+                .map(|&o| unsafe { std::ptr::read_unaligned(base.offset(o) as *const Complex<f64>) })
+                .collect(),
+        ),
+        NpyType::CFloat => Some(
+            offsets
+                .iter()
+                .map(|&o| unsafe { std::ptr::read_unaligned(base.offset(o) as *const Complex<f32>) }.to_complex128())
+                .collect(),
+        ),
+        _ => to_f64_vec(array).map(|values| values.into_iter().map(Complex::from).collect()),
+    }
+}
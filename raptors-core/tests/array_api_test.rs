@@ -0,0 +1,332 @@
+//! Tests for array API standard support
+#![allow(clippy::arc_with_non_send_sync)]
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::array::{Array, Scalar};
+    use raptors_core::array_api::*;
+    use raptors_core::types::{Complex128, NpyType};
+    use raptors_core::DType;
+    use std::sync::Arc;
+
+    fn f64s(data: &[f64], shape: Vec<i64>) -> Array {
+        Array::from_slice(data, shape, DType::new(NpyType::Double)).unwrap()
+    }
+
+    fn i64s(data: &[i64], shape: Vec<i64>) -> Array {
+        Array::from_slice(data, shape, DType::new(NpyType::LongLong)).unwrap()
+    }
+
+    fn read_f64(array: &Array) -> Vec<f64> {
+        let converted = astype(array, &DType::new(NpyType::Double)).unwrap();
+        unsafe { converted.to_vec::<f64>().unwrap() }
+    }
+
+    fn read_i64(array: &Array) -> Vec<i64> {
+        let converted = astype(array, &DType::new(NpyType::LongLong)).unwrap();
+        unsafe { converted.to_vec::<i64>().unwrap() }
+    }
+
+    #[test]
+    fn test_promotion_table() {
+        use NpyType::*;
+        let cases = [
+            (Bool, Byte, Byte),
+            (Byte, UByte, Short),
+            (Short, UInt, LongLong),
+            (Int, UShort, Int),
+            (Long, LongLong, LongLong),
+            (LongLong, ULongLong, Double),
+            (UByte, UInt, UInt),
+            (Byte, Float, Float),
+            (Int, Float, Double),
+            (Float, Double, Double),
+            (Float, CFloat, CFloat),
+            (Double, CFloat, CDouble),
+            (Short, CFloat, CFloat),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(promote_api_types(a, b).unwrap(), expected, "{:?} + {:?}", a, b);
+            assert_eq!(promote_api_types(b, a).unwrap(), expected, "{:?} + {:?}", b, a);
+        }
+        assert!(promote_api_types(Double, Half).is_err());
+    }
+
+    #[test]
+    fn test_can_cast_and_isdtype() {
+        let int8 = DType::new(NpyType::Byte);
+        let int16 = DType::new(NpyType::Short);
+        let uint8 = DType::new(NpyType::UByte);
+        assert!(can_cast(&int8, &int16).unwrap());
+        assert!(!can_cast(&int16, &int8).unwrap());
+        assert!(!can_cast(&int8, &uint8).unwrap());
+        assert!(isdtype(&uint8, "integral").unwrap());
+        assert!(!isdtype(&uint8, "signed integer").unwrap());
+        assert!(isdtype(&DType::new(NpyType::CFloat), "numeric").unwrap());
+        assert!(isdtype(&int8, "bogus").is_err());
+        assert_eq!(api_dtypes().len(), 13);
+    }
+
+    #[test]
+    fn test_info() {
+        let info = iinfo(&DType::new(NpyType::UByte)).unwrap();
+        assert_eq!((info.min, info.max, info.bits), (0, 255, 8));
+        let info = finfo(&DType::new(NpyType::CFloat)).unwrap();
+        assert_eq!(info.bits, 32);
+        assert_eq!(info.eps, f32::EPSILON as f64);
+        assert!(finfo(&DType::new(NpyType::Int)).is_err());
+    }
+
+    #[test]
+    fn test_binary_broadcasting_and_promotion() {
+        let a = i64s(&[1, 2, 3], vec![3, 1]);
+        let b = f64s(&[10.0, 20.0], vec![2]);
+        let sum = binary(BinaryOp::Add, &a, &b).unwrap();
+        assert_eq!(sum.shape(), &[3, 2]);
+        assert_eq!(sum.dtype().type_(), NpyType::Double);
+        assert_eq!(read_f64(&sum), vec![11.0, 21.0, 12.0, 22.0, 13.0, 23.0]);
+
+        let lt = binary(BinaryOp::Less, &a, &b).unwrap();
+        assert_eq!(lt.dtype().type_(), NpyType::Bool);
+    }
+
+    #[test]
+    fn test_integer_semantics() {
+        let a = i64s(&[7, -7, 7, -7, 5], vec![5]);
+        let b = i64s(&[2, 2, -2, -2, 0], vec![5]);
+        assert_eq!(read_i64(&binary(BinaryOp::FloorDivide, &a, &b).unwrap()), vec![3, -4, -4, 3, 0]);
+        assert_eq!(read_i64(&binary(BinaryOp::Remainder, &a, &b).unwrap()), vec![1, 1, -1, -1, 0]);
+        let divide = binary(BinaryOp::Divide, &a, &b).unwrap();
+        assert_eq!(divide.dtype().type_(), NpyType::Double);
+
+        let bytes = Array::from_slice(&[127i8, -128], vec![2], DType::new(NpyType::Byte)).unwrap();
+        let ones = Array::from_slice(&[1i8, 1], vec![2], DType::new(NpyType::Byte)).unwrap();
+        let wrapped = binary(BinaryOp::Add, &bytes, &ones).unwrap();
+        assert_eq!(wrapped.dtype().type_(), NpyType::Byte);
+        assert_eq!(read_i64(&wrapped), vec![-128, -127]);
+
+        let bools = Array::from_slice(&[1u8, 0], vec![2], DType::new(NpyType::Bool)).unwrap();
+        assert!(binary(BinaryOp::Subtract, &bools, &bools).is_err());
+        assert!(unary(UnaryOp::Negative, &bools).is_err());
+    }
+
+    #[test]
+    fn test_unary_functions() {
+        let x = f64s(&[-1.5, 0.5, 2.5, f64::NAN], vec![4]);
+        assert_eq!(read_f64(&unary(UnaryOp::Round, &x).unwrap())[..3], [-2.0, 0.0, 2.0]);
+        let isnan = unary(UnaryOp::IsNan, &x).unwrap();
+        assert_eq!(read_i64(&isnan), vec![0, 0, 0, 1]);
+
+        let ints = i64s(&[1, 4, 9], vec![3]);
+        let roots = unary(UnaryOp::Sqrt, &ints).unwrap();
+        assert_eq!(roots.dtype().type_(), NpyType::Double);
+        assert_eq!(read_f64(&roots), vec![1.0, 2.0, 3.0]);
+
+        let names: Vec<&str> = UnaryOp::ALL.iter().map(|op| op.name()).collect();
+        assert!(names.contains(&"bitwise_invert"));
+        assert_eq!(BinaryOp::from_name("logaddexp"), Some(BinaryOp::LogAddExp));
+    }
+
+    #[test]
+    fn test_complex_functions() {
+        let z = Array::from_slice(&[-4.0f64, 0.0], vec![1], DType::new(NpyType::CDouble)).unwrap();
+        let root = unary(UnaryOp::Sqrt, &z).unwrap();
+        let root = unsafe { root.to_vec::<Complex128>().unwrap() }[0];
+        assert!(root.re.abs() < 1e-12 && (root.im - 2.0).abs() < 1e-12);
+        let magnitude = unary(UnaryOp::Abs, &z).unwrap();
+        assert_eq!(magnitude.dtype().type_(), NpyType::Double);
+        assert_eq!(read_f64(&magnitude), vec![4.0]);
+    }
+
+    #[test]
+    fn test_clip_and_where() {
+        let x = f64s(&[-2.0, 0.5, 3.0], vec![3]);
+        let lo = f64s(&[0.0], vec![]);
+        let hi = f64s(&[1.0], vec![]);
+        assert_eq!(read_f64(&clip(&x, Some(&lo), Some(&hi)).unwrap()), vec![0.0, 0.5, 1.0]);
+
+        let cond = Array::from_slice(&[1u8, 0, 1], vec![3], DType::new(NpyType::Bool)).unwrap();
+        let other = i64s(&[9], vec![1]);
+        let picked = where_(&cond, &x, &other).unwrap();
+        assert_eq!(read_f64(&picked), vec![-2.0, 9.0, 3.0]);
+    }
+
+    #[test]
+    fn test_reductions() {
+        let x = i64s(&[1, 2, 3, 4, 5, 6], vec![2, 3]);
+        let total = sum(&x, None, None, false).unwrap();
+        assert_eq!(total.shape(), &[] as &[i64]);
+        assert_eq!(read_i64(&total), vec![21]);
+        let rows = sum(&x, Some(&[1]), None, true).unwrap();
+        assert_eq!(rows.shape(), &[2, 1]);
+        assert_eq!(read_i64(&rows), vec![6, 15]);
+        assert_eq!(read_i64(&prod(&x, Some(&[0]), None, false).unwrap()), vec![4, 10, 18]);
+        assert_eq!(read_i64(&max(&x, Some(&[-1]), false).unwrap()), vec![3, 6]);
+        assert_eq!(read_f64(&mean(&x, Some(&[0]), false).unwrap()), vec![2.5, 3.5, 4.5]);
+        assert_eq!(read_f64(&var(&x, None, 1.0, false).unwrap()), vec![3.5]);
+        assert!(sum(&x, Some(&[0, 0]), None, false).is_err());
+
+        let empty = f64s(&[], vec![0]);
+        assert!(matches!(min(&empty, None, false), Err(ArrayApiError::EmptyReduction("min"))));
+        assert_eq!(read_f64(&sum(&empty, None, None, false).unwrap()), vec![0.0]);
+    }
+
+    #[test]
+    fn test_cumulative_sum_and_logic() {
+        let x = i64s(&[1, 2, 3, 4], vec![2, 2]);
+        let cumulative = cumulative_sum(&x, Some(0), None, true).unwrap();
+        assert_eq!(cumulative.shape(), &[3, 2]);
+        assert_eq!(read_i64(&cumulative), vec![0, 0, 1, 2, 4, 6]);
+        assert!(cumulative_sum(&x, None, None, false).is_err());
+        assert_eq!(read_i64(&all(&x, None, false).unwrap()), vec![1]);
+        let zeros = i64s(&[0, 1], vec![2]);
+        assert_eq!(read_i64(&any(&zeros, None, false).unwrap()), vec![1]);
+    }
+
+    #[test]
+    fn test_sorting() {
+        let x = f64s(&[3.0, f64::NAN, 1.0, 3.0, 2.0, 0.0], vec![2, 3]);
+        let sorted = sort(&x, -1, false, true).unwrap();
+        let values = read_f64(&sorted);
+        assert_eq!(values[..2], [1.0, 3.0]);
+        assert!(values[2].is_nan());
+        assert_eq!(values[3..], [0.0, 2.0, 3.0]);
+        assert_eq!(read_i64(&argsort(&x, 0, false, true).unwrap()), vec![0, 1, 1, 1, 0, 0]);
+
+        let ties = i64s(&[1, 2, 1, 2], vec![4]);
+        assert_eq!(read_i64(&argsort(&ties, -1, true, true).unwrap()), vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn test_searching() {
+        let x = f64s(&[1.0, 5.0, 2.0, 7.0, 0.0, 3.0], vec![2, 3]);
+        assert_eq!(read_i64(&argmax(&x, None, false).unwrap()), vec![3]);
+        assert_eq!(read_i64(&argmin(&x, Some(1), false).unwrap()), vec![0, 1]);
+
+        let found = nonzero(&x).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(read_i64(&found[1]), vec![0, 1, 2, 0, 2]);
+
+        let sorted = i64s(&[1, 2, 2, 3], vec![4]);
+        let needles = i64s(&[2, 4], vec![2]);
+        assert_eq!(read_i64(&searchsorted(&sorted, &needles, SearchSide::Left, None).unwrap()), vec![1, 4]);
+        assert_eq!(read_i64(&searchsorted(&sorted, &needles, SearchSide::Right, None).unwrap()), vec![3, 4]);
+
+        let picks = i64s(&[2, -3], vec![2]);
+        let taken = take(&x, &picks, Some(1)).unwrap();
+        assert_eq!(read_f64(&taken), vec![2.0, 1.0, 3.0, 7.0]);
+    }
+
+    #[test]
+    fn test_unique() {
+        let x = f64s(&[3.0, 1.0, f64::NAN, 3.0, f64::NAN, 1.0], vec![2, 3]);
+        let unique = unique_all(&x).unwrap();
+        let values = read_f64(&unique.values);
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[..2], [1.0, 3.0]);
+        assert_eq!(read_i64(&unique.indices), vec![1, 0, 2, 4]);
+        assert_eq!(read_i64(&unique.counts), vec![2, 2, 1, 1]);
+        assert_eq!(unique.inverse_indices.shape(), &[2, 3]);
+        assert_eq!(read_i64(&unique.inverse_indices), vec![1, 0, 2, 1, 3, 0]);
+    }
+
+    #[test]
+    fn test_views() {
+        let x = Arc::new(f64s(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![2, 3]));
+        let flipped = flip(&x, Some(&[1])).unwrap();
+        assert_eq!(read_f64(&flipped), vec![2.0, 1.0, 0.0, 5.0, 4.0, 3.0]);
+        assert_eq!(flipped.data_ptr(), unsafe { x.data_ptr().add(16) });
+
+        let transposed = permute_dims(&x, &[1, 0]).unwrap();
+        assert_eq!(transposed.shape(), &[3, 2]);
+        assert_eq!(read_f64(&transposed), vec![0.0, 3.0, 1.0, 4.0, 2.0, 5.0]);
+
+        let expanded = expand_dims(&x, -1).unwrap();
+        assert_eq!(expanded.shape(), &[2, 3, 1]);
+        assert_eq!(squeeze(&Arc::new(expanded), &[2]).unwrap().shape(), &[2, 3]);
+
+        let reshaped = reshape(&x, &[3, -1], Some(false)).unwrap();
+        assert_eq!(reshaped.shape(), &[3, 2]);
+        assert_eq!(reshaped.data_ptr(), x.data_ptr());
+        let strided = Arc::new(transposed);
+        assert!(reshape(&strided, &[6], Some(false)).is_err());
+        assert_eq!(read_f64(&reshape(&strided, &[6], None).unwrap()), vec![0.0, 3.0, 1.0, 4.0, 2.0, 5.0]);
+
+        let moved = moveaxis(&Arc::new(f64s(&[0.0; 24], vec![2, 3, 4])), &[0], &[-1]).unwrap();
+        assert_eq!(moved.shape(), &[3, 4, 2]);
+
+        let rows = unstack(&x, 0).unwrap();
+        assert_eq!(read_f64(&rows[1]), vec![3.0, 4.0, 5.0]);
+
+        let broadcast = broadcast_to(&x, &[4, 2, 3]).unwrap();
+        assert!(!broadcast.is_writeable());
+        assert!(broadcast_to(&x, &[3, 3]).is_err());
+    }
+
+    #[test]
+    fn test_copying_manipulation() {
+        let a = i64s(&[1, 2, 3, 4], vec![2, 2]);
+        let b = f64s(&[5.0, 6.0], vec![1, 2]);
+        let joined = concat(&[&a, &b], Some(0)).unwrap();
+        assert_eq!(joined.shape(), &[3, 2]);
+        assert_eq!(joined.dtype().type_(), NpyType::Double);
+        assert_eq!(read_f64(&joined), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(concat(&[&a, &b], None).unwrap().shape(), &[6]);
+
+        let stacked = stack(&[&a, &a], 1).unwrap();
+        assert_eq!(stacked.shape(), &[2, 2, 2]);
+        assert_eq!(read_i64(&stacked), vec![1, 2, 1, 2, 3, 4, 3, 4]);
+
+        assert_eq!(read_i64(&roll(&a, &[1], None).unwrap()), vec![4, 1, 2, 3]);
+        assert_eq!(read_i64(&roll(&a, &[1], Some(&[1])).unwrap()), vec![2, 1, 4, 3]);
+        assert_eq!(read_i64(&repeat(&a, Repeats::PerElement(&[1, 2]), Some(0)).unwrap()), vec![1, 2, 3, 4, 3, 4]);
+        assert_eq!(read_i64(&repeat(&a, Repeats::Scalar(2), None).unwrap()), vec![1, 1, 2, 2, 3, 3, 4, 4]);
+        let tiled = tile(&a, &[1, 2]).unwrap();
+        assert_eq!(tiled.shape(), &[2, 4]);
+        assert_eq!(read_i64(&tiled), vec![1, 2, 1, 2, 3, 4, 3, 4]);
+    }
+
+    #[test]
+    fn test_linalg() {
+        let a = i64s(&[1, 2, 3, 4, 5, 6], vec![2, 3]);
+        let b = i64s(&[1, 0, 1], vec![3]);
+        let product = matmul(&a, &b).unwrap();
+        assert_eq!(product.shape(), &[2]);
+        assert_eq!(read_i64(&product), vec![4, 10]);
+
+        let batch = f64s(&[1.0, 0.0, 0.0, 1.0, 2.0, 0.0, 0.0, 2.0], vec![2, 2, 2]);
+        let m = f64s(&[1.0, 2.0, 3.0, 4.0], vec![2, 2]);
+        let stacked = matmul(&batch, &m).unwrap();
+        assert_eq!(stacked.shape(), &[2, 2, 2]);
+        assert_eq!(read_f64(&stacked), vec![1.0, 2.0, 3.0, 4.0, 2.0, 4.0, 6.0, 8.0]);
+        assert!(matmul(&a, &a).is_err());
+
+        let dotted = tensordot(&a, &a, TensorAxes::Pairs(vec![0], vec![0])).unwrap();
+        assert_eq!(dotted.shape(), &[3, 3]);
+        assert_eq!(read_i64(&dotted)[0], 17);
+
+        let z = Array::from_slice(&[0.0f64, 1.0], vec![1], DType::new(NpyType::CDouble)).unwrap();
+        let norm = vecdot(&z, &z, -1).unwrap();
+        assert_eq!(unsafe { norm.to_vec::<Complex128>().unwrap() }, vec![Complex128::new(1.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_scalars_and_creation() {
+        let float32 = DType::new(NpyType::Float);
+        assert_eq!(scalar_dtype(&Scalar::Float(1.5), &float32).unwrap().type_(), NpyType::Float);
+        assert_eq!(scalar_dtype(&Scalar::Complex(Default::default()), &float32).unwrap().type_(), NpyType::CFloat);
+        assert_eq!(scalar_dtype(&Scalar::Float(1.5), &DType::new(NpyType::Short)).unwrap().type_(), NpyType::Double);
+        assert!(scalar_dtype(&Scalar::Int(300), &DType::new(NpyType::UByte)).is_err());
+
+        let ints = asarray_scalars(&[Scalar::Int(1), Scalar::Bool(true)], vec![2], None).unwrap();
+        assert_eq!(ints.dtype().type_(), NpyType::LongLong);
+        let big = asarray_scalars(&[Scalar::UInt(u64::MAX)], vec![1], None).unwrap();
+        assert_eq!(big.dtype().type_(), NpyType::ULongLong);
+        let floats = asarray_scalars(&[Scalar::Int(1), Scalar::Float(2.5)], vec![1, 2], None).unwrap();
+        assert_eq!(floats.dtype().type_(), NpyType::Double);
+        assert!(asarray_scalars(&[Scalar::Int(1)], vec![2], None).is_err());
+
+        let cast = astype(&f64s(&[-1.7, 2.9], vec![2]), &DType::new(NpyType::Int)).unwrap();
+        assert_eq!(read_i64(&cast), vec![-1, 2]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Tests for Arrow C Data Interface and IPC interchange
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc shares arrays with the exported structures, not across threads
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::array::Array;
+    use raptors_core::arrow::{
+        export_array, export_masked, import_array, import_masked, load_ipc, read_ipc, save_ipc, write_ipc,
+        ArrowColumn, ArrowError, ArrowType,
+    };
+    use raptors_core::datetime::TimeUnit;
+    use raptors_core::masked::MaskedArray;
+    use raptors_core::types::{DType, NpyType};
+    use std::ffi::CStr;
+    use std::sync::Arc;
+
+    fn bools(values: &[u8]) -> Array {
+        Array::from_slice(values, vec![values.len() as i64], DType::new(NpyType::Bool)).unwrap()
+    }
+
+    fn unicode(strings: &[&str], width: usize) -> Array {
+        let mut bytes = vec![0u8; strings.len() * width * 4];
+        for (i, s) in strings.iter().enumerate() {
+            for (j, c) in s.chars().enumerate() {
+                let at = (i * width + j) * 4;
+                bytes[at..at + 4].copy_from_slice(&(c as u32).to_ne_bytes());
+            }
+        }
+        Array::from_slice(&bytes, vec![strings.len() as i64], DType::unicode_with_itemsize(width * 4)).unwrap()
+    }
+
+    fn strings(array: &Array) -> Vec<String> {
+        let bytes = unsafe { std::slice::from_raw_parts(array.data_ptr(), array.size() * array.itemsize()) };
+        bytes
+            .chunks(array.itemsize())
+            .map(|e| {
+                e.chunks(4)
+                    .map(|c| u32::from_ne_bytes(c.try_into().unwrap()))
+                    .take_while(|&c| c != 0)
+                    .map(|c| char::from_u32(c).unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_format_strings() {
+        let dtype = DType::datetime(TimeUnit::Microsecond);
+        let ty = ArrowType::from_dtype(&dtype).unwrap();
+        assert_eq!(ty.format(), "tsu:");
+        assert_eq!(ArrowType::from_format("tsn:UTC").unwrap(), ArrowType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())));
+        assert_eq!(ArrowType::from_dtype(&DType::new(NpyType::UShort)).unwrap().format(), "S");
+        assert_eq!(ArrowType::from_dtype(&DType::string_with_itemsize(5)).unwrap().format(), "w:5");
+        assert_eq!(ArrowType::from_format("tDm").unwrap().to_dtype().name(), "timedelta64[ms]");
+        assert!(ArrowType::from_dtype(&DType::datetime(TimeUnit::Day)).is_err());
+        assert!(ArrowType::from_dtype(&DType::new(NpyType::CDouble)).is_err());
+        assert!(ArrowType::from_format("+l").is_err());
+    }
+
+    #[test]
+    fn test_export_is_zero_copy_and_released() {
+        let array = Arc::new(Array::from_slice(&[1i64, 2, 3], vec![3], DType::new(NpyType::Long)).unwrap());
+        let (exported, schema) = export_array(array.clone()).unwrap();
+        assert_eq!(Arc::strong_count(&array), 2);
+        assert_eq!(unsafe { CStr::from_ptr(schema.format) }.to_str().unwrap(), "l");
+        assert_eq!((exported.length, exported.null_count, exported.n_buffers), (3, 0, 2));
+        let buffers = unsafe { std::slice::from_raw_parts(exported.buffers, 2) };
+        assert!(buffers[0].is_null());
+        assert_eq!(buffers[1] as *const u8, array.data_ptr());
+
+        // Dropping calls the release callback, which lets go of the array
+        drop(exported);
+        drop(schema);
+        assert_eq!(Arc::strong_count(&array), 1);
+    }
+
+    #[test]
+    fn test_export_nd_as_fixed_size_list() {
+        let values: Vec<f32> = (0..12).map(|v| v as f32).collect();
+        let array = Arc::new(Array::from_slice(&values, vec![2, 3, 2], DType::new(NpyType::Float)).unwrap());
+        let (exported, schema) = export_array(array.clone()).unwrap();
+        assert_eq!(unsafe { CStr::from_ptr(schema.format) }.to_str().unwrap(), "+w:3");
+        let child_schema = unsafe { &**schema.children };
+        assert_eq!(unsafe { CStr::from_ptr(child_schema.format) }.to_str().unwrap(), "+w:2");
+        assert_eq!((exported.length, exported.n_buffers, exported.n_children), (2, 1, 1));
+        let child = unsafe { &**exported.children };
+        let leaf = unsafe { &**child.children };
+        assert_eq!((child.length, leaf.length), (6, 12));
+
+        let imported = unsafe { import_array(exported, &schema) }.unwrap();
+        assert_eq!(imported.shape(), &[2, 3, 2]);
+        assert_eq!(imported.data_ptr(), array.data_ptr());
+        assert!(!imported.is_writeable());
+        assert_eq!(unsafe { imported.to_vec::<f32>().unwrap() }, values);
+    }
+
+    #[test]
+    fn test_import_keeps_exporter_alive() {
+        let array = Arc::new(Array::from_slice(&[1.5f64, 2.5], vec![2], DType::new(NpyType::Double)).unwrap());
+        let ptr = array.data_ptr();
+        let (exported, schema) = export_array(array.clone()).unwrap();
+        let imported = unsafe { import_array(exported, &schema) }.unwrap();
+        drop(schema);
+        drop(array);
+        // The imported view now holds the only reference to the data
+        assert_eq!(imported.data_ptr(), ptr);
+        let tail = imported.view(vec![1], vec![8]).unwrap();
+        drop(imported);
+        assert_eq!(unsafe { tail.to_vec::<f64>().unwrap() }, vec![1.5]);
+    }
+
+    #[test]
+    fn test_validity_bitmap_round_trip() {
+        let data = Array::from_slice(&[10i32, 20, 30, 40], vec![4], DType::new(NpyType::Int)).unwrap();
+        let masked = Arc::new(MaskedArray::new(data, bools(&[0, 1, 0, 1])).unwrap());
+        let (exported, schema) = export_masked(masked.clone()).unwrap();
+        assert_eq!(exported.null_count, 2);
+        let validity = unsafe { *(*exported.buffers as *const u8) };
+        assert_eq!(validity & 0x0f, 0b0101);
+
+        let imported = unsafe { import_masked(exported, &schema) }.unwrap();
+        assert_eq!(imported.count_masked(), 2);
+        assert!(imported.is_masked(1).unwrap() && !imported.is_masked(2).unwrap());
+        assert_eq!(unsafe { imported.data().to_vec::<i32>().unwrap() }, vec![10, 20, 30, 40]);
+
+        // A plain import refuses to drop the nulls
+        let (exported, schema) = export_masked(masked).unwrap();
+        assert!(matches!(unsafe { import_array(exported, &schema) }, Err(ArrowError::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_bool_and_string_round_trip() {
+        let (exported, schema) = export_array(Arc::new(bools(&[1, 0, 0, 1, 1, 1, 0, 1, 1]))).unwrap();
+        assert_eq!(unsafe { CStr::from_ptr(schema.format) }.to_str().unwrap(), "b");
+        let flags = unsafe { import_array(exported, &schema) }.unwrap();
+        assert_eq!(unsafe { flags.to_vec::<u8>().unwrap() }, vec![1, 0, 0, 1, 1, 1, 0, 1, 1]);
+
+        let (exported, schema) = export_array(Arc::new(unicode(&["grüße", "", "ok"], 8))).unwrap();
+        assert_eq!(unsafe { CStr::from_ptr(schema.format) }.to_str().unwrap(), "u");
+        let offsets = unsafe { std::slice::from_raw_parts(*exported.buffers.add(1) as *const i32, 4) };
+        assert_eq!(offsets, &[0, 7, 7, 9]);
+        let text = unsafe { import_array(exported, &schema) }.unwrap();
+        // Narrowed to the longest string
+        assert_eq!(text.dtype().itemsize(), 20);
+        assert_eq!(strings(&text), vec!["grüße", "", "ok"]);
+    }
+
+    #[test]
+    fn test_ipc_round_trip() {
+        let floats = Array::from_slice(&[1.5f64, f64::NAN, -2.0], vec![3], DType::new(NpyType::Double)).unwrap();
+        let columns = vec![
+            ArrowColumn::new("id", Array::from_slice(&[1u16, 2, 3], vec![3], DType::new(NpyType::UShort)).unwrap()),
+            ArrowColumn::from_masked("score", &MaskedArray::new(floats, bools(&[0, 1, 0])).unwrap()),
+            ArrowColumn::new("ok", bools(&[1, 0, 1])),
+            ArrowColumn::new("name", unicode(&["a", "naïve", ""], 5)),
+            ArrowColumn::new(
+                "at",
+                Array::from_slice(&[0i64, 1_500, -7], vec![3], DType::datetime(TimeUnit::Millisecond)).unwrap(),
+            ),
+        ];
+        let mut bytes = Vec::new();
+        write_ipc(&mut bytes, &columns).unwrap();
+        assert!(bytes.starts_with(b"ARROW1\0\0") && bytes.ends_with(b"ARROW1"));
+
+        let read = read_ipc(&bytes[..]).unwrap();
+        let names: Vec<&str> = read.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "score", "ok", "name", "at"]);
+        assert_eq!(unsafe { read[0].values.to_vec::<u16>().unwrap() }, vec![1, 2, 3]);
+        assert!(read[0].mask.is_none());
+        assert_eq!(read[1].null_count(), 1);
+        let score = read[1].clone().into_masked().unwrap();
+        assert!(score.is_masked(1).unwrap());
+        assert_eq!(unsafe { score.data().to_vec::<f64>().unwrap() }[2], -2.0);
+        assert_eq!(unsafe { read[2].values.to_vec::<u8>().unwrap() }, vec![1, 0, 1]);
+        assert_eq!(strings(&read[3].values), vec!["a", "naïve", ""]);
+        assert_eq!(read[4].values.dtype().time_unit(), Some(TimeUnit::Millisecond));
+        assert_eq!(unsafe { read[4].values.to_vec::<i64>().unwrap() }, vec![0, 1_500, -7]);
+    }
+
+    #[test]
+    fn test_ipc_file_and_errors() {
+        let path = std::env::temp_dir().join(format!("raptors_arrow_test_{}.arrow", std::process::id()));
+        let values = Array::from_slice(&[7i8, 8], vec![2], DType::new(NpyType::Byte)).unwrap();
+        save_ipc(&path, &[ArrowColumn::new("x", values)]).unwrap();
+        let read = load_ipc(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(read[0].values.dtype().type_(), NpyType::Byte);
+        assert_eq!(unsafe { read[0].values.to_vec::<i8>().unwrap() }, vec![7, 8]);
+
+        let long = Array::from_slice(&[1i32, 2, 3], vec![3], DType::new(NpyType::Int)).unwrap();
+        let short = Array::from_slice(&[1i32], vec![1], DType::new(NpyType::Int)).unwrap();
+        let grid = Array::from_slice(&[1i32, 2, 3, 4], vec![2, 2], DType::new(NpyType::Int)).unwrap();
+        let complex = Array::new(vec![1], DType::new(NpyType::CDouble)).unwrap();
+        let mut sink = Vec::new();
+        assert!(write_ipc(&mut sink, &[ArrowColumn::new("a", long), ArrowColumn::new("b", short)]).is_err());
+        assert!(write_ipc(&mut sink, &[ArrowColumn::new("g", grid)]).is_err());
+        assert!(matches!(
+            write_ipc(&mut sink, &[ArrowColumn::new("c", complex)]),
+            Err(ArrowError::UnsupportedType(_))
+        ));
+        assert!(matches!(read_ipc(&b"not an arrow file"[..]), Err(ArrowError::InvalidData(_))));
+    }
+}
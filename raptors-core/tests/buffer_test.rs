@@ -2,9 +2,11 @@
 
 use raptors_core::{
     array::Array,
-    buffer::{export_buffer, import_buffer, FormatString},
+    buffer::{export_buffer, import_buffer, import_buffer_with_owner, FormatString},
+    structured::{Field, StructuredDType},
     types::{DType, NpyType},
 };
+use std::sync::Arc;
 
 #[test]
 fn test_export_buffer_basic() {
@@ -22,7 +24,7 @@ fn test_export_buffer_read_only() {
     let mut array = Array::new(vec![2, 2], DType::new(NpyType::Double)).unwrap();
     
     // Make array read-only
-    array.setflags(raptors_core::array::ArrayFlags::WRITEABLE, false);
+    array.setflags(raptors_core::array::ArrayFlags::WRITEABLE, false).unwrap();
     
     let buffer_info = export_buffer(&array).unwrap();
     
@@ -161,8 +163,8 @@ fn test_export_import_roundtrip() {
 
 #[test]
 fn test_buffer_unsupported_type() {
-    // String type not supported in buffer protocol
-    let array = Array::new(vec![2], DType::new(NpyType::String)).unwrap();
+    // Datetimes have no buffer protocol format
+    let array = Array::new(vec![2], DType::new(NpyType::DateTime)).unwrap();
     
     let result = export_buffer(&array);
     assert!(result.is_err());
@@ -234,7 +236,7 @@ fn test_buffer_readonly_flag_preserved() {
     assert!(!buffer_info1.read_only);
     
     // Make read-only
-    array.setflags(raptors_core::array::ArrayFlags::WRITEABLE, false);
+    array.setflags(raptors_core::array::ArrayFlags::WRITEABLE, false).unwrap();
     let buffer_info2 = export_buffer(&array).unwrap();
     assert!(buffer_info2.read_only);
 }
@@ -275,3 +277,92 @@ fn test_buffer_multiple_export_same_array() {
     assert_eq!(buffer_info1.size, buffer_info2.size);
 }
 
+#[test]
+fn test_format_extended_codes() {
+    let cases = [
+        ("?", NpyType::Bool, 1),
+        ("e", NpyType::Half, 2),
+        ("Zf", NpyType::CFloat, 8),
+        ("Zd", NpyType::CDouble, 16),
+        ("<q", NpyType::LongLong, 8),
+        ("<l", NpyType::Int, 4),
+        ("10s", NpyType::String, 10),
+        ("3w", NpyType::Unicode, 12),
+    ];
+    for (format, npy_type, itemsize) in cases {
+        let parsed = FormatString::parse(format).unwrap();
+        let dtype = parsed.to_dtype().unwrap();
+        assert_eq!(dtype.type_(), npy_type, "{}", format);
+        assert_eq!(dtype.itemsize(), itemsize, "{}", format);
+        assert_eq!(parsed.to_string(), format);
+    }
+
+    // Repeat counts are only meaningful on struct fields
+    assert_eq!(FormatString::parse("4d").unwrap().count, Some(4));
+    assert!(FormatString::parse("4d").unwrap().to_dtype().is_err());
+    assert!(FormatString::parse("Zq").is_err());
+    assert!(FormatString::parse("T{d").is_err());
+}
+
+#[test]
+fn test_format_struct_layout() {
+    let parsed = FormatString::parse("T{<i:id:(2)<d:pos:4x3s:tag:}").unwrap();
+    assert_eq!(parsed.type_char, 'T');
+    assert_eq!(parsed.itemsize(), 27);
+    let dtype = parsed.to_dtype().unwrap();
+    let layout = dtype.fields().unwrap();
+    let pos = layout.fields().iter().find(|f| f.name == "pos").unwrap();
+    assert_eq!((pos.offset, pos.shape.clone()), (4, vec![2]));
+    let tag = layout.fields().iter().find(|f| f.name == "tag").unwrap();
+    assert_eq!((tag.offset, tag.dtype.itemsize()), (24, 3));
+
+    // Native mode aligns fields, and unnamed items get default names
+    let aligned = FormatString::parse("bd").unwrap().to_dtype().unwrap();
+    let fields = aligned.fields().unwrap().fields();
+    assert_eq!((fields[1].name.as_str(), fields[1].offset), ("f1", 8));
+}
+
+#[test]
+fn test_structured_export_roundtrip() {
+    let layout = StructuredDType::with_offsets(vec![
+        Field::new("id", DType::new(NpyType::Long), 0),
+        Field::new("pos", DType::new(NpyType::Float), 8).with_shape(vec![2]),
+    ], 20).unwrap();
+    let array = Array::new(vec![3], DType::structured(layout)).unwrap();
+    let info = export_buffer(&array).unwrap();
+    let native = if cfg!(target_endian = "little") { '<' } else { '>' };
+    assert_eq!(info.format, format!("T{{{0}q:id:(2){0}f:pos:4x}}", native));
+
+    let parsed = FormatString::parse(&info.format).unwrap();
+    assert_eq!(parsed.itemsize(), 20);
+    let dtype = parsed.to_dtype().unwrap();
+    assert_eq!(dtype.itemsize(), 20);
+    assert_eq!(dtype.fields().unwrap().fields().len(), 2);
+}
+
+#[test]
+fn test_import_buffer_strides_readonly_and_owner() {
+    let data: Arc<Vec<i32>> = Arc::new((0..6).collect());
+    let ptr = data.as_ptr() as *mut u8;
+
+    // Reversed rows of a 2x3 buffer
+    let array = unsafe {
+        import_buffer_with_owner(ptr.add(12), "i", vec![2, 3], Some(vec![-12, 4]), true, data.clone()).unwrap()
+    };
+    assert_eq!(Arc::strong_count(&data), 2);
+    assert!(!array.is_writeable());
+    assert_eq!(array.strides(), &[-12, 4]);
+    let first = unsafe { *(array.data_ptr() as *const i32) };
+    assert_eq!(first, 3);
+    drop(array);
+    assert_eq!(Arc::strong_count(&data), 1);
+}
+
+#[test]
+fn test_import_buffer_byteswapped() {
+    let mut data: Vec<u8> = [1u32, 256].iter().flat_map(|v| v.to_be_bytes()).collect();
+    let array = unsafe { import_buffer(data.as_mut_ptr(), ">I", vec![2], None, false).unwrap() };
+    assert_ne!(array.data_ptr(), data.as_ptr());
+    assert_eq!(unsafe { array.to_vec::<u32>().unwrap() }, vec![1, 256]);
+}
+
@@ -2,8 +2,19 @@
 
 #[cfg(test)]
 mod tests {
+    use raptors_core::array::*;
     use raptors_core::{empty, ones, zeros};
-    use raptors_core::types::{DType, NpyType};
+    use raptors_core::types::{Complex128, DType, NpyType};
+
+    fn doubles(array: &Array) -> Vec<f64> {
+        assert_eq!(array.dtype().type_(), NpyType::Double);
+        unsafe { array.to_vec::<f64>().unwrap() }
+    }
+
+    fn longs(array: &Array) -> Vec<i64> {
+        assert_eq!(array.dtype().type_(), NpyType::Long);
+        unsafe { array.to_vec::<i64>().unwrap() }
+    }
 
     #[test]
     fn test_empty() {
@@ -31,5 +42,214 @@ mod tests {
         
         assert_eq!(array.size(), 4);
     }
-}
 
+    #[test]
+    fn test_ones_int_dtype() {
+        let array = ones(vec![3], DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_full_every_dtype() {
+        let array = full(vec![2], 7i64, DType::new(NpyType::Short)).unwrap();
+        assert_eq!(unsafe { array.to_vec::<i16>().unwrap() }, vec![7, 7]);
+
+        let array = full(vec![2], true, DType::new(NpyType::Bool)).unwrap();
+        assert_eq!(unsafe { array.to_vec::<u8>().unwrap() }, vec![1, 1]);
+
+        let array = full(vec![2], 0.5, DType::new(NpyType::Float)).unwrap();
+        assert_eq!(unsafe { array.to_vec::<f32>().unwrap() }, vec![0.5, 0.5]);
+
+        let array = full(vec![1], 1.0, DType::new(NpyType::Half)).unwrap();
+        assert_eq!(unsafe { array.to_vec::<u16>().unwrap() }, vec![0x3c00]);
+
+        let z = Complex128::new(1.0, -2.0);
+        let array = full(vec![2], z, DType::new(NpyType::CDouble)).unwrap();
+        assert_eq!(unsafe { array.to_vec::<Complex128>().unwrap() }, vec![z, z]);
+
+        let array = full(vec![2], "ab", DType::string_with_itemsize(3)).unwrap();
+        assert_eq!(unsafe { array.to_vec::<[u8; 3]>().unwrap() }, vec![*b"ab\0", *b"ab\0"]);
+
+        assert!(full(vec![2], "ab", DType::new(NpyType::Double)).is_err());
+    }
+
+    #[test]
+    fn test_builder_fill_value() {
+        let array = ArrayBuilder::new()
+            .with_shape(vec![2, 3])
+            .with_dtype(DType::new(NpyType::Int))
+            .with_order(MemoryOrder::F)
+            .with_fill_value(4i64)
+            .build()
+            .unwrap();
+        assert!(array.is_f_contiguous());
+        assert_eq!(unsafe { array.to_vec::<i32>().unwrap() }, vec![4; 6]);
+    }
+
+    #[test]
+    fn test_like_preserves_layout() {
+        let fortran = Array::new_with_order(vec![2, 3], DType::new(NpyType::Double), Order::F).unwrap();
+        let like = zeros_like(&fortran, None, Order::K).unwrap();
+        assert_eq!(like.strides(), fortran.strides());
+        let like = ones_like(&fortran, Some(DType::new(NpyType::Long)), Order::A).unwrap();
+        assert!(like.is_f_contiguous());
+        assert_eq!(longs(&like), vec![1; 6]);
+        let like = empty_like(&fortran, None, Order::C).unwrap();
+        assert!(like.is_c_contiguous());
+
+        let fortran = Array::new_with_order(vec![2, 3, 4], DType::new(NpyType::Int), Order::F).unwrap();
+        let like = full_like(&fortran, 2.5, Some(DType::new(NpyType::Double)), Order::K).unwrap();
+        assert_eq!(like.shape(), &[2, 3, 4]);
+        assert_eq!(like.strides(), &[8, 16, 48]);
+        assert_eq!(doubles(&like), vec![2.5; 24]);
+    }
+
+    #[test]
+    fn test_arange() {
+        let array = arange(0.0, 5.0, 1.0, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![0, 1, 2, 3, 4]);
+
+        // ceil((stop - start) / step) elements, never one past stop
+        let array = arange(1.0, 2.0, 0.3, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(array.size(), 4);
+        let array = arange(0.0, 1.0, 0.1, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(array.size(), 10);
+
+        let array = arange(5.0, 0.0, -2.0, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![5, 3, 1]);
+        assert_eq!(arange(0.0, -1.0, 1.0, DType::new(NpyType::Double)).unwrap().size(), 0);
+        assert!(arange(0.0, 1.0, 0.0, DType::new(NpyType::Double)).is_err());
+    }
+
+    #[test]
+    fn test_arange_int() {
+        // Exact beyond 2^53, where f64 steps by 2
+        let big = 1i64 << 53;
+        let array = arange_int(big + 1, big + 4, 1, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![big + 1, big + 2, big + 3]);
+        let array = arange_int(i64::MAX, i64::MAX - 5, -2, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![i64::MAX, i64::MAX - 2, i64::MAX - 4]);
+
+        let array = arange_int(5, 0, -2, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![5, 3, 1]);
+        assert_eq!(arange_int(0, 5, -1, DType::new(NpyType::Long)).unwrap().size(), 0);
+        assert!(arange_int(0, 5, 0, DType::new(NpyType::Long)).is_err());
+        assert!(arange_int(i64::MIN, i64::MAX, 1, DType::new(NpyType::Long)).is_err());
+
+        // Other dtypes take the values of `arange`
+        let array = arange_int(0, 3, 1, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_linspace_family() {
+        let (array, step) = linspace_retstep(0.0, 1.0, 5, true, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(step, 0.25);
+
+        let (array, step) = linspace_retstep(0.0, 1.0, 4, false, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![0.0, 0.25, 0.5, 0.75]);
+        assert_eq!(step, 0.25);
+
+        let (array, step) = linspace_retstep(2.0, 3.0, 1, true, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![2.0]);
+        assert!(step.is_nan());
+
+        let array = linspace(0.0, 10.0, 4, true, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![0, 3, 6, 10]);
+
+        let array = logspace(0.0, 3.0, 4, true, 10.0, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![1.0, 10.0, 100.0, 1000.0]);
+        let array = logspace(0.0, 3.0, 3, false, 2.0, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![1.0, 2.0, 4.0]);
+
+        let array = geomspace(-1.0, -1000.0, 4, true, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![-1.0, -10.0, -100.0, -1000.0]);
+        assert!(geomspace(0.0, 1.0, 3, true, DType::new(NpyType::Double)).is_err());
+        assert!(geomspace(-1.0, 1.0, 3, true, DType::new(NpyType::Double)).is_err());
+    }
+
+    #[test]
+    fn test_eye_identity_tri() {
+        let array = eye(2, Some(3), 1, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(array.shape(), &[2, 3]);
+        assert_eq!(longs(&array), vec![0, 1, 0, 0, 0, 1]);
+        let array = eye(3, None, -2, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![0, 0, 0, 0, 0, 0, 1, 0, 0]);
+
+        let array = identity(2, DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&array), vec![1.0, 0.0, 0.0, 1.0]);
+
+        let array = tri(2, Some(3), 0, DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&array), vec![1, 0, 0, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_diag_tril_triu() {
+        let v = Array::from_slice(&[1i64, 2], vec![2], DType::new(NpyType::Long)).unwrap();
+        let matrix = diag(&v, 1).unwrap();
+        assert_eq!(matrix.shape(), &[3, 3]);
+        assert_eq!(longs(&matrix), vec![0, 1, 0, 0, 0, 2, 0, 0, 0]);
+        assert_eq!(longs(&diag(&matrix, 1).unwrap()), vec![1, 2]);
+        assert_eq!(diag(&matrix, -3).unwrap().size(), 0);
+
+        let values: Vec<i64> = (1..=9).collect();
+        let a = Array::from_slice(&values, vec![3, 3], DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&tril(&a, 0).unwrap()), vec![1, 0, 0, 4, 5, 0, 7, 8, 9]);
+        assert_eq!(longs(&triu(&a, 1).unwrap()), vec![0, 2, 3, 0, 0, 6, 0, 0, 0]);
+        assert_eq!(longs(&tril(&a, -1).unwrap()), vec![0, 0, 0, 4, 0, 0, 7, 8, 0]);
+    }
+
+    #[test]
+    fn test_vander() {
+        let x = Array::from_slice(&[1i64, 2, 3], vec![3], DType::new(NpyType::Long)).unwrap();
+        assert_eq!(longs(&vander(&x, None, false).unwrap()), vec![1, 1, 1, 4, 2, 1, 9, 3, 1]);
+        let array = vander(&x, Some(2), true).unwrap();
+        assert_eq!(array.shape(), &[3, 2]);
+        assert_eq!(longs(&array), vec![1, 1, 1, 2, 1, 3]);
+
+        let x = Array::from_slice(&[0.5], vec![1], DType::new(NpyType::Double)).unwrap();
+        assert_eq!(doubles(&vander(&x, Some(3), false).unwrap()), vec![0.25, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_meshgrid() {
+        let x = arange(0.0, 3.0, 1.0, DType::new(NpyType::Long)).unwrap();
+        let y = arange(0.0, 2.0, 1.0, DType::new(NpyType::Double)).unwrap();
+
+        let grids = meshgrid(&[&x, &y], MeshIndexing::Xy, false).unwrap();
+        assert_eq!(grids[0].shape(), &[2, 3]);
+        assert_eq!(longs(&grids[0]), vec![0, 1, 2, 0, 1, 2]);
+        assert_eq!(doubles(&grids[1]), vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+
+        let grids = meshgrid(&[&x, &y], MeshIndexing::Ij, false).unwrap();
+        assert_eq!(grids[0].shape(), &[3, 2]);
+        assert_eq!(longs(&grids[0]), vec![0, 0, 1, 1, 2, 2]);
+
+        let grids = meshgrid(&[&x, &y], MeshIndexing::Xy, true).unwrap();
+        assert_eq!(grids[0].shape(), &[1, 3]);
+        assert_eq!(grids[1].shape(), &[2, 1]);
+    }
+
+    #[test]
+    fn test_mgrid_ogrid() {
+        let grid = mgrid(&[GridRange::new(0.0, 3.0, 1.0), GridRange::new(0.0, 2.0, 1.0)]).unwrap();
+        assert_eq!(grid.shape(), &[2, 3, 2]);
+        assert_eq!(longs(&grid), vec![0, 0, 1, 1, 2, 2, 0, 1, 0, 1, 0, 1]);
+
+        let grid = mgrid(&[GridRange::num(0.0, 1.0, 5)]).unwrap();
+        assert_eq!(doubles(&grid), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+
+        let grids = ogrid(&[GridRange::new(0.0, 2.0, 1.0), GridRange::num(0.0, 1.0, 3)]).unwrap();
+        assert_eq!(grids[0].shape(), &[2, 1]);
+        assert_eq!(grids[1].shape(), &[1, 3]);
+        assert_eq!(doubles(&grids[1]), vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_fromfunction() {
+        let array = fromfunction(vec![2, 3], DType::new(NpyType::Long), |idx| (idx[0] * 10 + idx[1]) as i64).unwrap();
+        assert_eq!(longs(&array), vec![0, 1, 2, 10, 11, 12]);
+        assert!(fromfunction(vec![2], DType::new(NpyType::Int), |_| 0i64).is_err());
+    }
+}
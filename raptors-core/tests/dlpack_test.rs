@@ -1,11 +1,14 @@
 //! Tests for DLPack support
 #![allow(unused_unsafe)]
+#![allow(clippy::arc_with_non_send_sync)]
 
 #[cfg(test)]
 mod tests {
     use raptors_core::{DType, zeros};
     use raptors_core::types::NpyType;
     use raptors_core::dlpack::*;
+    use raptors_core::array::{Array, ArrayFlags};
+    use std::sync::Arc;
 
     #[test]
     fn test_array_to_dlpack() {
@@ -33,19 +36,23 @@ mod tests {
             (NpyType::Float, DLDataTypeCode::Float, 32),
             (NpyType::Int, DLDataTypeCode::Int, 32),
             (NpyType::LongLong, DLDataTypeCode::Int, 64),
+            (NpyType::UByte, DLDataTypeCode::UInt, 8),
+            (NpyType::Half, DLDataTypeCode::Float, 16),
+            (NpyType::CDouble, DLDataTypeCode::Complex, 128),
+            (NpyType::Bool, DLDataTypeCode::Bool, 8),
         ];
         
-        for (npy_type, _expected_code, _expected_bits) in test_cases {
+        for (npy_type, expected_code, expected_bits) in test_cases {
             let dtype = DType::new(npy_type);
             let array = zeros(vec![1], dtype).unwrap();
             
             unsafe {
                 let dlpack = array_to_dlpack(&array).unwrap();
-                let _tensor = &*dlpack;
+                let tensor = &*dlpack;
                 
-                // Would verify dtype conversion when properly implemented
-                // assert_eq!(tensor.dtype.code, expected_code as u8);
-                // assert_eq!(tensor.dtype.bits, expected_bits);
+                assert_eq!(tensor.dtype.code, expected_code as u8);
+                assert_eq!(tensor.dtype.bits, expected_bits);
+                assert_eq!(tensor.dtype.lanes, 1);
                 
                 delete_dlpack_tensor(dlpack);
             }
@@ -179,5 +186,136 @@ mod tests {
             delete_dlpack_tensor(std::ptr::null_mut());
         }
     }
-}
 
+    fn arange_f64(n: usize) -> Array {
+        let mut array = zeros(vec![n as i64], DType::new(NpyType::Double)).unwrap();
+        unsafe {
+            let ptr = array.data_ptr_mut() as *mut f64;
+            for i in 0..n {
+                *ptr.add(i) = i as f64;
+            }
+        }
+        array
+    }
+
+    #[test]
+    fn test_dlpack_element_strides() {
+        let base = Arc::new(zeros(vec![2, 3], DType::new(NpyType::Double)).unwrap());
+        // Transpose: shape (3, 2), byte strides (8, 24)
+        let transposed = Array::view_from_arc(&base, vec![3, 2], vec![8, 24]).unwrap();
+
+        unsafe {
+            let dlpack = array_to_dlpack(&transposed).unwrap();
+            let tensor = &*dlpack;
+            assert_eq!(*tensor.strides.add(0), 1);
+            assert_eq!(*tensor.strides.add(1), 3);
+            delete_dlpack_tensor(dlpack);
+        }
+    }
+
+    #[test]
+    fn test_managed_tensor_deleter_drops_array() {
+        let array = Arc::new(arange_f64(4));
+        let managed = array_to_managed_tensor(array.clone()).unwrap();
+        assert_eq!(Arc::strong_count(&array), 2);
+
+        unsafe {
+            let tensor = &(*managed).dl_tensor;
+            assert_eq!(tensor.data as *const u8, array.data_ptr());
+            assert_eq!(*tensor.shape, 4);
+            assert_eq!(*tensor.strides, 1);
+            assert_eq!(tensor.byte_offset, 0);
+
+            let deleter = (*managed).deleter.unwrap();
+            deleter(managed);
+        }
+        assert_eq!(Arc::strong_count(&array), 1);
+    }
+
+    #[test]
+    fn test_managed_tensor_read_only() {
+        let mut array = arange_f64(2);
+        array.setflags(ArrayFlags::WRITEABLE, false).unwrap();
+        let array = Arc::new(array);
+
+        assert!(matches!(array_to_managed_tensor(array.clone()), Err(DLPackError::ReadOnly)));
+
+        let versioned = array_to_managed_tensor_versioned(array.clone(), DLPACK_FLAG_BITMASK_IS_COPIED).unwrap();
+        unsafe {
+            assert_eq!((*versioned).version.major, DLPACK_MAJOR_VERSION);
+            assert_eq!((*versioned).version.minor, DLPACK_MINOR_VERSION);
+            assert_eq!(
+                (*versioned).flags,
+                DLPACK_FLAG_BITMASK_READ_ONLY | DLPACK_FLAG_BITMASK_IS_COPIED
+            );
+
+            let imported = managed_tensor_versioned_to_array(versioned).unwrap();
+            assert!(!imported.is_writeable());
+            assert_eq!(imported.data_ptr(), array.data_ptr());
+            assert_eq!(Arc::strong_count(&array), 2);
+            drop(imported);
+        }
+        assert_eq!(Arc::strong_count(&array), 1);
+    }
+
+    #[test]
+    fn test_managed_tensor_import_zero_copy() {
+        let array = Arc::new(arange_f64(4));
+        let managed = array_to_managed_tensor(array.clone()).unwrap();
+
+        let imported = unsafe { managed_tensor_to_array(managed).unwrap() };
+        assert!(imported.is_writeable());
+        assert_eq!(imported.shape(), &[4]);
+        assert_eq!(imported.data_ptr(), array.data_ptr());
+
+        // Views of the import keep the tensor alive
+        let imported = Arc::new(imported);
+        let view = Array::view_from_arc_at(&imported, 8, vec![2], vec![16]).unwrap();
+        drop(imported);
+        assert_eq!(Arc::strong_count(&array), 2);
+        unsafe {
+            assert_eq!(*(view.data_ptr() as *const f64), 1.0);
+        }
+        drop(view);
+        assert_eq!(Arc::strong_count(&array), 1);
+    }
+
+    #[test]
+    fn test_managed_tensor_byte_offset() {
+        let array = Arc::new(arange_f64(4));
+        let managed = array_to_managed_tensor(array.clone()).unwrap();
+
+        unsafe {
+            // Last three elements, reversed
+            let tensor = &mut (*managed).dl_tensor;
+            tensor.byte_offset = 24;
+            *tensor.shape = 3;
+            *tensor.strides = -1;
+
+            let imported = managed_tensor_to_array(managed).unwrap();
+            assert_eq!(imported.strides(), &[-8]);
+            let ptr = imported.data_ptr() as *const f64;
+            assert_eq!(*ptr, 3.0);
+            assert_eq!(*ptr.offset(-1), 2.0);
+            assert_eq!(*ptr.offset(-2), 1.0);
+        }
+        assert_eq!(Arc::strong_count(&array), 1);
+    }
+
+    #[test]
+    fn test_managed_tensor_unsupported_version() {
+        let array = Arc::new(arange_f64(2));
+        let versioned = array_to_managed_tensor_versioned(array.clone(), 0).unwrap();
+
+        unsafe {
+            (*versioned).version.major = 2;
+            let result = managed_tensor_versioned_to_array(versioned);
+            assert!(matches!(result, Err(DLPackError::UnsupportedVersion(2))));
+
+            // A failed import leaves the tensor to the caller
+            assert_eq!(Arc::strong_count(&array), 2);
+            ((*versioned).deleter.unwrap())(versioned);
+        }
+        assert_eq!(Arc::strong_count(&array), 1);
+    }
+}
@@ -0,0 +1,356 @@
+//! Tests for the dedicated kernel executor
+//!
+//! These tests verify results, priority lanes, the bounded queue,
+//! cooperative cancellation and the futures returned by submit.
+
+use raptors_core::array::Array;
+use raptors_core::performance::blocking::{process_blocks_1d, process_blocks_2d};
+use raptors_core::performance::executor::{
+    cancellation_requested, CancellationToken, Executor, ExecutorError, ExecutorOptions, Priority,
+};
+use raptors_core::types::{DType, NpyType};
+use raptors_core::ufunc::sum_along_axis;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+fn executor(num_threads: usize, queue_capacity: usize) -> Executor {
+    Executor::new(ExecutorOptions { num_threads, queue_capacity, ..Default::default() }).unwrap()
+}
+
+/// Occupy the only thread of `executor` until the returned sender is dropped
+fn block(executor: &Executor) -> mpsc::Sender<()> {
+    let (release, gate) = mpsc::channel::<()>();
+    let (started, running) = mpsc::channel();
+    executor.submit(Priority::High, move || {
+        started.send(()).unwrap();
+        let _ = gate.recv();
+    });
+    running.recv().unwrap();
+    release
+}
+
+// Minimal executor-agnostic block_on, standing in for tokio or another runtime
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+/// Waker counting how often it was woken
+#[derive(Default)]
+struct CountWakes(AtomicUsize);
+
+impl Wake for CountWakes {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    Pin::new(future).poll(&mut Context::from_waker(&waker))
+}
+
+#[test]
+fn test_submit_and_wait() {
+    let executor = executor(2, 16);
+    assert_eq!(executor.num_threads(), 2);
+    assert_eq!(executor.queue_capacity(), 16);
+    assert_eq!(executor.submit(Priority::Normal, || 6 * 7).wait(), Ok(42));
+}
+
+#[test]
+fn test_future_with_any_runtime() {
+    let executor = executor(2, 16);
+    let result = block_on(async {
+        let a = executor.submit(Priority::Normal, || 1);
+        let b = executor.submit(Priority::High, || 2);
+        a.await.unwrap() + b.await.unwrap()
+    });
+    assert_eq!(result, 3);
+}
+
+#[test]
+fn test_array_kernel() {
+    let executor = executor(2, 16);
+    let mut array = Array::new(vec![1000], DType::new(NpyType::Double)).unwrap();
+    unsafe {
+        let ptr = array.data_ptr_mut() as *mut f64;
+        for i in 0..1000 {
+            *ptr.add(i) = i as f64;
+        }
+    }
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let array = array.copy();
+            executor.submit(Priority::Normal, move || sum_along_axis(&array, None).unwrap())
+        })
+        .collect();
+    for handle in handles {
+        let sum = handle.wait().unwrap();
+        assert_eq!(unsafe { *(sum.data_ptr() as *const f64) }, 499_500.0);
+    }
+}
+
+#[test]
+fn test_priority_lanes() {
+    let executor = executor(1, 16);
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let release = block(&executor);
+
+    let handles: Vec<_> = [Priority::Low, Priority::Normal, Priority::High, Priority::Low, Priority::High]
+        .into_iter()
+        .enumerate()
+        .map(|(i, priority)| {
+            let order = Arc::clone(&order);
+            executor.submit(priority, move || order.lock().unwrap().push(i))
+        })
+        .collect();
+    assert_eq!(executor.queued(), 5);
+    drop(release);
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    // Highest lane first, oldest first within a lane
+    assert_eq!(*order.lock().unwrap(), vec![2, 4, 1, 0, 3]);
+}
+
+#[test]
+fn test_bounded_queue() {
+    let executor = executor(1, 2);
+    let release = block(&executor);
+
+    let first = executor.try_submit(Priority::Normal, || 1).unwrap();
+    let second = executor.submit(Priority::Normal, || 2);
+    assert_eq!(executor.queued(), 2);
+    assert_eq!(executor.try_submit(Priority::High, || 3).unwrap_err(), ExecutorError::QueueFull);
+
+    // `submit` waits for room instead of failing
+    let mut third = executor.submit(Priority::Normal, || 3);
+    assert!(poll_once(&mut third).is_pending());
+    assert_eq!(executor.queued(), 2);
+
+    drop(release);
+    assert_eq!(first.wait(), Ok(1));
+    assert_eq!(second.wait(), Ok(2));
+    assert_eq!(third.wait(), Ok(3));
+    assert_eq!(executor.queued(), 0);
+}
+
+#[test]
+fn test_one_waiter_woken_per_slot() {
+    let executor = executor(1, 1);
+    let (a, b) = (Arc::new(CountWakes::default()), Arc::new(CountWakes::default()));
+    let (wake_a, wake_b) = (Waker::from(Arc::clone(&a)), Waker::from(Arc::clone(&b)));
+    for drop_first in [false, true] {
+        a.0.store(0, Ordering::SeqCst);
+        b.0.store(0, Ordering::SeqCst);
+        let release = block(&executor);
+        let queued = executor.submit(Priority::Normal, || 0);
+        let mut first = executor.submit(Priority::Normal, || 1);
+        let mut second = executor.submit(Priority::Normal, || 2);
+        // Polling again replaces the handle's waker instead of adding one
+        for _ in 0..5 {
+            assert!(Pin::new(&mut first).poll(&mut Context::from_waker(&wake_a)).is_pending());
+        }
+        assert!(Pin::new(&mut second).poll(&mut Context::from_waker(&wake_b)).is_pending());
+
+        // Starting `queued` frees one slot, for the oldest waiter only
+        drop(release);
+        assert_eq!(queued.wait(), Ok(0));
+        assert_eq!((a.0.load(Ordering::SeqCst), b.0.load(Ordering::SeqCst)), (1, 0));
+        if drop_first {
+            // The woken handle gives the slot up, so the next one is woken
+            drop(first);
+        } else {
+            assert_eq!(first.wait(), Ok(1));
+        }
+        assert_eq!(b.0.load(Ordering::SeqCst), 1);
+        assert_eq!(second.wait(), Ok(2));
+    }
+}
+
+#[test]
+fn test_cancel_before_start() {
+    let executor = executor(1, 16);
+    let release = block(&executor);
+    let ran = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&ran);
+    let handle = executor.submit(Priority::Normal, move || counter.fetch_add(1, Ordering::SeqCst));
+    handle.cancel();
+    drop(release);
+    assert_eq!(handle.wait(), Err(ExecutorError::Cancelled));
+    assert_eq!(ran.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_cancel_between_blocks() {
+    let executor = executor(1, 16);
+    let token = CancellationToken::new();
+    let blocks = Arc::new(AtomicUsize::new(0));
+
+    let (job_token, counter) = (token.clone(), Arc::clone(&blocks));
+    let handle = executor.submit_cancellable(Priority::Normal, &token, move || {
+        process_blocks_1d(1_000_000, 1, |_, _| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 2 {
+                job_token.cancel();
+            }
+        });
+        "finished"
+    });
+    assert_eq!(handle.wait(), Err(ExecutorError::Cancelled));
+    assert_eq!(blocks.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_cancel_between_2d_blocks() {
+    let executor = executor(1, 16);
+    let blocks = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&blocks);
+    let (started, running) = mpsc::channel();
+    let (cancelled, gate) = mpsc::channel::<()>();
+
+    let handle = executor.submit(Priority::Normal, move || {
+        process_blocks_2d(4096, 4096, 8, |_, _, _, _| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                started.send(()).unwrap();
+                gate.recv().unwrap();
+            }
+        });
+    });
+    running.recv().unwrap();
+    handle.cancel();
+    cancelled.send(()).unwrap();
+    assert_eq!(handle.wait(), Err(ExecutorError::Cancelled));
+    assert_eq!(blocks.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_shared_token() {
+    let executor = executor(1, 16);
+    let release = block(&executor);
+    let token = CancellationToken::new();
+    let a = executor.submit_cancellable(Priority::Normal, &token, || 1);
+    let b = executor.submit_cancellable(Priority::Low, &token, || 2);
+    let c = executor.submit(Priority::Low, || 3);
+    token.cancel();
+    assert!(a.token().is_cancelled());
+    drop(release);
+    assert_eq!(a.wait(), Err(ExecutorError::Cancelled));
+    assert_eq!(b.wait(), Err(ExecutorError::Cancelled));
+    assert_eq!(c.wait(), Ok(3));
+}
+
+#[test]
+fn test_cancellation_requested() {
+    let executor = executor(1, 16);
+    assert!(!cancellation_requested());
+    let handle = executor.submit(Priority::Normal, || {
+        let before = cancellation_requested();
+        (before, thread::current().name().map(str::to_string))
+    });
+    let (before, name) = handle.wait().unwrap();
+    assert!(!before);
+    assert_eq!(name.as_deref(), Some("raptors-executor-0"));
+}
+
+#[test]
+fn test_blocks_outside_executor() {
+    let blocks = AtomicUsize::new(0);
+    process_blocks_1d(100, 8, |start, end| {
+        blocks.fetch_add(end - start, Ordering::SeqCst);
+    });
+    assert_eq!(blocks.load(Ordering::SeqCst), 100);
+}
+
+#[test]
+fn test_dropped_handle_detaches_job() {
+    let executor = executor(1, 1);
+    let release = block(&executor);
+    let ran = Arc::new(AtomicUsize::new(0));
+    let (queued, waiting) = (Arc::clone(&ran), Arc::clone(&ran));
+    drop(executor.submit(Priority::Normal, move || queued.fetch_add(1, Ordering::SeqCst)));
+    // Never queued: the queue was full
+    drop(executor.submit(Priority::Normal, move || waiting.fetch_add(10, Ordering::SeqCst)));
+    drop(release);
+    executor.submit(Priority::Low, || ()).wait().unwrap();
+    assert_eq!(ran.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_panicking_job() {
+    let executor = executor(1, 16);
+    let handle = executor.submit(Priority::Normal, || -> i32 { panic!("kernel failed") });
+    assert_eq!(handle.wait(), Err(ExecutorError::Panicked("kernel failed".to_string())));
+    // The executor keeps working
+    assert_eq!(executor.submit(Priority::Normal, || 1).wait(), Ok(1));
+}
+
+#[test]
+fn test_executors_are_independent() {
+    let small = executor(1, 4);
+    let large = executor(3, 4);
+    assert_eq!(small.num_threads(), 1);
+    assert_eq!(large.num_threads(), 3);
+
+    let release = block(&small);
+    // A busy executor doesn't hold up another one
+    let handle = large.submit(Priority::Normal, rayon::current_num_threads);
+    assert_eq!(handle.wait(), Ok(3));
+    drop(release);
+}
+
+#[test]
+fn test_invalid_options() {
+    let options = ExecutorOptions { queue_capacity: 0, ..Default::default() };
+    assert!(matches!(Executor::new(options), Err(ExecutorError::Build(_))));
+}
+
+#[test]
+fn test_many_jobs_from_many_threads() {
+    let executor = executor(2, 8);
+    let total = Arc::new(AtomicUsize::new(0));
+    let submitters: Vec<_> = (0..4)
+        .map(|_| {
+            let (executor, total) = (executor.clone(), Arc::clone(&total));
+            thread::spawn(move || {
+                let handles: Vec<_> = (0..50)
+                    .map(|i| {
+                        let total = Arc::clone(&total);
+                        executor.submit(Priority::Normal, move || {
+                            thread::sleep(Duration::from_micros(10));
+                            total.fetch_add(i, Ordering::SeqCst)
+                        })
+                    })
+                    .collect();
+                handles.into_iter().for_each(|handle| {
+                    handle.wait().unwrap();
+                });
+            })
+        })
+        .collect();
+    for submitter in submitters {
+        submitter.join().unwrap();
+    }
+    assert_eq!(total.load(Ordering::SeqCst), 4 * (0..50).sum::<usize>());
+}
@@ -567,6 +567,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pyarray_take_compress_along_axis() {
+        use raptors_core::ffi::{PyArray_Compress, PyArray_Take};
+
+        let array = create_test_array_with_data(vec![3, 4], DType::new(NpyType::Double));
+        let arr_ptr = array_to_pyarray_ptr(&array);
+        let indices = Array::from_slice(&[3i64, 0], vec![2], DType::new(NpyType::Long)).unwrap();
+        let index_ptr = array_to_pyarray_ptr(&indices);
+
+        let columns = unsafe { PyArray_Take(arr_ptr, index_ptr, 1, ptr::null_mut(), 0) };
+        assert_eq!(unsafe { ((*columns).dimensions[0], (*columns).dimensions[1]) }, (3, 2));
+        assert_eq!(pyarray_values::<f64>(columns), vec![3.0, 0.0, 7.0, 4.0, 11.0, 8.0]);
+        let flat = unsafe { PyArray_Take(arr_ptr, index_ptr, 64, ptr::null_mut(), 0) };
+        assert_eq!(pyarray_values::<f64>(flat), vec![3.0, 0.0]);
+        assert!(unsafe { PyArray_Take(arr_ptr, index_ptr, 2, ptr::null_mut(), 0) }.is_null());
+
+        let condition = Array::from_slice(&[false, true, true], vec![3], DType::new(NpyType::Bool)).unwrap();
+        let condition_ptr = array_to_pyarray_ptr(&condition);
+        let rows = unsafe { PyArray_Compress(arr_ptr, condition_ptr, 0, ptr::null_mut()) };
+        assert_eq!(pyarray_values::<f64>(rows), (4..12).map(|v| v as f64).collect::<Vec<_>>());
+
+        unsafe {
+            for p in [columns, flat, rows, arr_ptr, index_ptr, condition_ptr] {
+                free_pyarray(p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pyarray_put_putmask() {
+        use raptors_core::ffi::{PyArray_Put, PyArray_PutMask};
+
+        let array = zeros(vec![2, 3], DType::new(NpyType::Double)).unwrap();
+        let arr_ptr = array_to_pyarray_ptr(&array);
+        let indices = Array::from_slice(&[0i64, 4, 5], vec![3], DType::new(NpyType::Long)).unwrap();
+        let index_ptr = array_to_pyarray_ptr(&indices);
+        let values = Array::from_slice(&[1.5f64, 2.5], vec![2], DType::new(NpyType::Double)).unwrap();
+        let value_ptr = array_to_pyarray_ptr(&values);
+
+        // Values repeat to cover every index
+        assert_eq!(unsafe { PyArray_Put(arr_ptr, index_ptr, value_ptr, 0) }, 0);
+        assert_eq!(pyarray_values::<f64>(arr_ptr), vec![1.5, 0.0, 0.0, 0.0, 2.5, 1.5]);
+
+        // Masked elements take the value at their own flat position
+        let mask = Array::from_slice(&[false, true, false, true, false, false], vec![2, 3], DType::new(NpyType::Bool)).unwrap();
+        let mask_ptr = array_to_pyarray_ptr(&mask);
+        assert_eq!(unsafe { PyArray_PutMask(arr_ptr, mask_ptr, value_ptr) }, 0);
+        assert_eq!(pyarray_values::<f64>(arr_ptr), vec![1.5, 2.5, 0.0, 2.5, 2.5, 1.5]);
+
+        let out_of_bounds = Array::from_slice(&[6i64], vec![1], DType::new(NpyType::Long)).unwrap();
+        let bad_ptr = array_to_pyarray_ptr(&out_of_bounds);
+        assert_eq!(unsafe { PyArray_Put(arr_ptr, bad_ptr, value_ptr, 0) }, -1);
+
+        unsafe {
+            for p in [arr_ptr, index_ptr, value_ptr, mask_ptr, bad_ptr] {
+                free_pyarray(p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pyarray_choose() {
+        use raptors_core::ffi::PyArray_Choose;
+
+        // Two choices of length 4, stacked
+        let choices = Array::from_slice(&[0.0f64, 1.0, 2.0, 3.0, 10.0, 11.0, 12.0, 13.0], vec![2, 4], DType::new(NpyType::Double)).unwrap();
+        let choices_ptr = array_to_pyarray_ptr(&choices);
+        let selector = Array::from_slice(&[1i64, 0, 0, 1], vec![4], DType::new(NpyType::Long)).unwrap();
+        let selector_ptr = array_to_pyarray_ptr(&selector);
+
+        let result = unsafe { PyArray_Choose(selector_ptr, choices_ptr, ptr::null_mut(), 0) };
+        assert!(!result.is_null());
+        assert_eq!(pyarray_values::<f64>(result), vec![10.0, 1.0, 2.0, 13.0]);
+
+        unsafe {
+            for p in [result, choices_ptr, selector_ptr] {
+                free_pyarray(p);
+            }
+        }
+    }
+
     // Concatenation
     #[test]
     fn test_pyarray_concatenate() {
@@ -935,5 +1016,226 @@ mod tests {
             free_pyarray(arr);
         }
     }
-}
 
+    // Creation functions
+    fn pyarray_values<T: Copy>(arr: *mut PyArrayObject) -> Vec<T> {
+        unsafe {
+            let size: i64 = (0..(*arr).nd as usize).map(|i| (*arr).dimensions[i]).product();
+            std::slice::from_raw_parts((*arr).data as *const T, size as usize).to_vec()
+        }
+    }
+
+    #[test]
+    fn test_pyarray_arange_linspace() {
+        use raptors_core::ffi::{PyArray_Arange, PyArray_Geomspace, PyArray_Linspace, PyArray_Logspace};
+
+        let arr = PyArray_Arange(0.0, 5.0, 2.0, 7); // Long
+        assert!(!arr.is_null());
+        assert_eq!(pyarray_values::<i64>(arr), vec![0, 2, 4]);
+        assert!(PyArray_Arange(0.0, 1.0, 0.0, 12).is_null());
+
+        let mut step = 0.0;
+        let lin = PyArray_Linspace(0.0, 1.0, 3, 1, &mut step, 12);
+        assert_eq!(pyarray_values::<f64>(lin), vec![0.0, 0.5, 1.0]);
+        assert_eq!(step, 0.5);
+
+        let log = PyArray_Logspace(0.0, 2.0, 3, 1, 10.0, 12);
+        assert_eq!(pyarray_values::<f64>(log), vec![1.0, 10.0, 100.0]);
+        let geom = PyArray_Geomspace(1.0, 8.0, 4, 1, 12);
+        assert_eq!(pyarray_values::<f64>(geom), vec![1.0, 2.0, 4.0, 8.0]);
+
+        unsafe {
+            free_pyarray(arr);
+            free_pyarray(lin);
+            free_pyarray(log);
+            free_pyarray(geom);
+        }
+    }
+
+    #[test]
+    fn test_pyarray_full_and_like() {
+        use raptors_core::ffi::{PyArray_Full, PyArray_FullLike, PyArray_OnesLike, PyArray_ZerosLike};
+
+        let dims = [2i64, 3i64];
+        let value = 7i32;
+        let arr = unsafe { PyArray_Full(2, dims.as_ptr(), 5, &value as *const i32 as *const libc::c_void, 1) };
+        assert!(!arr.is_null());
+        assert_eq!(pyarray_values::<i32>(arr), vec![7; 6]);
+        unsafe {
+            assert_eq!([(*arr).strides[0], (*arr).strides[1]], [4, 8]);
+        }
+
+        // Order K keeps the Fortran layout; a negative type_num keeps the dtype
+        let zeros = PyArray_ZerosLike(arr, -1, 2);
+        assert_eq!(pyarray_values::<i32>(zeros), vec![0; 6]);
+        unsafe {
+            assert_eq!([(*zeros).strides[0], (*zeros).strides[1]], [4, 8]);
+        }
+        let ones = PyArray_OnesLike(arr, 12, 0);
+        assert_eq!(pyarray_values::<f64>(ones), vec![1.0; 6]);
+        unsafe {
+            assert_eq!([(*ones).strides[0], (*ones).strides[1]], [24, 8]);
+        }
+        let fill = 2.5f64;
+        let filled = PyArray_FullLike(arr, &fill as *const f64 as *const libc::c_void, 12, 0);
+        assert_eq!(pyarray_values::<f64>(filled), vec![2.5; 6]);
+
+        unsafe {
+            free_pyarray(arr);
+            free_pyarray(zeros);
+            free_pyarray(ones);
+            free_pyarray(filled);
+        }
+    }
+
+    #[test]
+    fn test_pyarray_eye_tri_diag() {
+        use raptors_core::ffi::{PyArray_Diag, PyArray_Eye, PyArray_Identity, PyArray_Tri, PyArray_Tril, PyArray_Triu};
+
+        let eye = PyArray_Eye(2, 3, 1, 7);
+        assert_eq!(pyarray_values::<i64>(eye), vec![0, 1, 0, 0, 0, 1]);
+        let ident = PyArray_Identity(2, 12);
+        assert_eq!(pyarray_values::<f64>(ident), vec![1.0, 0.0, 0.0, 1.0]);
+        let tri = PyArray_Tri(2, -1, 0, 7);
+        assert_eq!(pyarray_values::<i64>(tri), vec![1, 0, 1, 1]);
+
+        let diag = PyArray_Diag(eye, 1);
+        assert_eq!(pyarray_values::<i64>(diag), vec![1, 1]);
+
+        let values: Vec<i64> = (1..=4).collect();
+        let square = Array::from_slice(&values, vec![2, 2], DType::new(NpyType::Long)).unwrap();
+        let square_ptr = array_to_pyarray_ptr(&square);
+        let lower = PyArray_Tril(square_ptr, 0);
+        assert_eq!(pyarray_values::<i64>(lower), vec![1, 0, 3, 4]);
+        let upper = PyArray_Triu(square_ptr, 0);
+        assert_eq!(pyarray_values::<i64>(upper), vec![1, 2, 0, 4]);
+
+        unsafe {
+            for arr in [eye, ident, tri, diag, square_ptr, lower, upper] {
+                free_pyarray(arr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pyarray_vander() {
+        use raptors_core::ffi::PyArray_Vander;
+
+        let x = Array::from_slice(&[2.0f64, 3.0], vec![2], DType::new(NpyType::Double)).unwrap();
+        let x_ptr = array_to_pyarray_ptr(&x);
+        let vander = PyArray_Vander(x_ptr, 3, 0);
+        assert_eq!(pyarray_values::<f64>(vander), vec![4.0, 2.0, 1.0, 9.0, 3.0, 1.0]);
+        unsafe {
+            free_pyarray(x_ptr);
+            free_pyarray(vander);
+        }
+    }
+
+    #[test]
+    fn test_pyarray_meshgrid_and_grids() {
+        use raptors_core::ffi::{PyArray_Arange, PyArray_Meshgrid, PyArray_Mgrid, PyArray_Ogrid};
+
+        let inputs = [PyArray_Arange(0.0, 3.0, 1.0, 7), PyArray_Arange(0.0, 2.0, 1.0, 7)];
+        let mut out = [ptr::null_mut(); 2];
+        assert_eq!(PyArray_Meshgrid(2, inputs.as_ptr(), 0, 0, out.as_mut_ptr()), 0);
+        assert_eq!(pyarray_values::<i64>(out[0]), vec![0, 1, 2, 0, 1, 2]);
+        assert_eq!(pyarray_values::<i64>(out[1]), vec![0, 0, 0, 1, 1, 1]);
+
+        let starts = [0.0, 0.0];
+        let stops = [2.0, 1.0];
+        let steps = [1.0, 0.0];
+        let counts = [0i64, 3];
+        let dense = PyArray_Mgrid(2, starts.as_ptr(), stops.as_ptr(), steps.as_ptr(), counts.as_ptr());
+        unsafe {
+            assert_eq!([(*dense).dimensions[0], (*dense).dimensions[1], (*dense).dimensions[2]], [2, 2, 3]);
+        }
+        let mut open = [ptr::null_mut(); 2];
+        assert_eq!(
+            PyArray_Ogrid(2, starts.as_ptr(), stops.as_ptr(), steps.as_ptr(), counts.as_ptr(), open.as_mut_ptr()),
+            0
+        );
+        assert_eq!(pyarray_values::<f64>(open[1]), vec![0.0, 0.5, 1.0]);
+
+        unsafe {
+            for arr in inputs.into_iter().chain(out).chain(open).chain([dense]) {
+                free_pyarray(arr);
+            }
+        }
+    }
+
+    extern "C" fn index_sum(index: *const i64, nd: libc::c_int, out: *mut libc::c_void, _user_data: *mut libc::c_void) {
+        unsafe {
+            let index = std::slice::from_raw_parts(index, nd as usize);
+            *(out as *mut i64) = index.iter().sum();
+        }
+    }
+
+    #[test]
+    fn test_pyarray_fromfunction() {
+        use raptors_core::ffi::PyArray_FromFunction;
+
+        let dims = [2i64, 3i64];
+        let arr = PyArray_FromFunction(2, dims.as_ptr(), 7, Some(index_sum), ptr::null_mut());
+        assert_eq!(pyarray_values::<i64>(arr), vec![0, 1, 2, 1, 2, 3]);
+        assert!(PyArray_FromFunction(2, dims.as_ptr(), 7, None, ptr::null_mut()).is_null());
+        unsafe {
+            free_pyarray(arr);
+        }
+    }
+
+    #[test]
+    fn test_pyarray_savez_and_npz_handle() {
+        use raptors_core::ffi::{
+            PyArray_Arange, PyArray_NpzClose, PyArray_NpzCount, PyArray_NpzLoad, PyArray_NpzName,
+            PyArray_NpzOpen, PyArray_Savez, PyArray_SavezCompressed,
+        };
+        use std::ffi::{CStr, CString};
+
+        let a = PyArray_Arange(0.0, 4.0, 1.0, 7); // Long
+        let b = PyArray_Arange(0.0, 1.0, 0.5, 12); // Double
+        let names = [CString::new("a").unwrap(), CString::new("b").unwrap()];
+        let name_ptrs = [names[0].as_ptr(), names[1].as_ptr()];
+        let arrays = [a, b];
+
+        for (i, compress) in [false, true].into_iter().enumerate() {
+            let path = std::env::temp_dir().join(format!("raptors_ffi_npz_{}_{}.npz", std::process::id(), i));
+            let filename = CString::new(path.to_str().unwrap()).unwrap();
+            let status = if compress {
+                PyArray_SavezCompressed(filename.as_ptr(), 2, name_ptrs.as_ptr(), arrays.as_ptr())
+            } else {
+                PyArray_Savez(filename.as_ptr(), 2, name_ptrs.as_ptr(), arrays.as_ptr())
+            };
+            assert_eq!(status, 0);
+
+            let npz = PyArray_NpzOpen(filename.as_ptr());
+            assert!(!npz.is_null());
+            assert_eq!(PyArray_NpzCount(npz), 2);
+            let first = PyArray_NpzName(npz, 0);
+            assert_eq!(unsafe { CStr::from_ptr(first) }.to_str().unwrap(), "a");
+            assert!(PyArray_NpzName(npz, 2).is_null());
+
+            let la = PyArray_NpzLoad(npz, first);
+            assert_eq!(pyarray_values::<i64>(la), vec![0, 1, 2, 3]);
+            let b_name = CString::new("b.npy").unwrap();
+            let lb = PyArray_NpzLoad(npz, b_name.as_ptr());
+            assert_eq!(pyarray_values::<f64>(lb), vec![0.0, 0.5]);
+            let missing = CString::new("c").unwrap();
+            assert!(PyArray_NpzLoad(npz, missing.as_ptr()).is_null());
+
+            PyArray_NpzClose(npz);
+            unsafe {
+                free_pyarray(la);
+                free_pyarray(lb);
+            }
+            let _ = std::fs::remove_file(&path);
+        }
+
+        let bad = CString::new("/nonexistent/raptors.npz").unwrap();
+        assert!(PyArray_NpzOpen(bad.as_ptr()).is_null());
+        assert_eq!(PyArray_Savez(bad.as_ptr(), 2, name_ptrs.as_ptr(), arrays.as_ptr()), -1);
+        unsafe {
+            free_pyarray(a);
+            free_pyarray(b);
+        }
+    }
+}
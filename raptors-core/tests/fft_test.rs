@@ -0,0 +1,230 @@
+//! Tests for the FFT module
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::fft::*;
+    use raptors_core::types::{Complex128, DType, NpyType};
+    use raptors_core::Array;
+    use std::sync::Arc;
+
+    fn real_array(values: &[f64], shape: Vec<i64>) -> Array {
+        Array::from_slice(values, shape, DType::new(NpyType::Double)).unwrap()
+    }
+
+    fn complex_values(array: &Array) -> Vec<Complex128> {
+        assert_eq!(array.dtype().type_(), NpyType::CDouble);
+        unsafe { array.to_vec::<Complex128>().unwrap() }
+    }
+
+    fn naive_dft(x: &[Complex128], inverse: bool) -> Vec<Complex128> {
+        let n = x.len();
+        let sign = if inverse { 1.0 } else { -1.0 };
+        (0..n)
+            .map(|k| {
+                x.iter().enumerate().fold(Complex128::default(), |acc, (j, &v)| {
+                    let angle = sign * 2.0 * std::f64::consts::PI * ((j * k) % n) as f64 / n as f64;
+                    acc + v * Complex128::from_polar(1.0, angle)
+                })
+            })
+            .collect()
+    }
+
+    fn signal(n: usize) -> Vec<Complex128> {
+        (0..n)
+            .map(|i| Complex128::new((i as f64 * 0.7).sin() + 0.1 * i as f64, (i as f64 * 1.3).cos()))
+            .collect()
+    }
+
+    fn assert_close(actual: &[Complex128], expected: &[Complex128], tol: f64) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((*a - *e).abs() <= tol * (1.0 + e.abs()), "{} != {}", a, e);
+        }
+    }
+
+    #[test]
+    fn test_fft_matches_naive_dft() {
+        // Powers of two, mixed radices, generic radices and Bluestein lengths
+        for n in (1..=40).chain([49, 97, 128, 210, 289, 1000, 1031]) {
+            let x = signal(n);
+            let input = Array::from_slice(&x, vec![n as i64], DType::new(NpyType::CDouble)).unwrap();
+            let result = fft(&input, None, 0, FftNorm::Backward).unwrap();
+            assert_eq!(result.shape(), &[n as i64]);
+            assert_close(&complex_values(&result), &naive_dft(&x, false), 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_bluestein_selected_for_large_primes() {
+        assert!(FftPlan::new(97).is_bluestein());
+        assert!(!FftPlan::new(2 * 3 * 5 * 7 * 11 * 13).is_bluestein());
+    }
+
+    #[test]
+    fn test_ifft_roundtrip_and_norms() {
+        let x = signal(24);
+        let input = Array::from_slice(&x, vec![24], DType::new(NpyType::CDouble)).unwrap();
+        for norm in [FftNorm::Backward, FftNorm::Ortho, FftNorm::Forward] {
+            let spectrum = fft(&input, None, 0, norm).unwrap();
+            let back = ifft(&spectrum, None, 0, norm).unwrap();
+            assert_close(&complex_values(&back), &x, 1e-12);
+        }
+
+        let forward = complex_values(&fft(&input, None, 0, FftNorm::Forward).unwrap());
+        let expected: Vec<_> = naive_dft(&x, false).iter().map(|v| v.scale(1.0 / 24.0)).collect();
+        assert_close(&forward, &expected, 1e-12);
+
+        // Ortho transform preserves energy
+        let ortho = complex_values(&fft(&input, None, 0, FftNorm::Ortho).unwrap());
+        let energy_in: f64 = x.iter().map(|v| v.norm_sqr()).sum();
+        let energy_out: f64 = ortho.iter().map(|v| v.norm_sqr()).sum();
+        assert!((energy_in - energy_out).abs() < 1e-9);
+
+        assert_eq!(FftNorm::from_name("ortho").unwrap(), FftNorm::Ortho);
+        assert!(matches!(FftNorm::from_name("bogus"), Err(FftError::InvalidNorm(_))));
+    }
+
+    #[test]
+    fn test_fft_padding_and_truncation() {
+        let input = real_array(&[1.0, 2.0, 3.0], vec![3]);
+        let padded = fft(&input, Some(5), 0, FftNorm::Backward).unwrap();
+        let expected = naive_dft(
+            &[1.0, 2.0, 3.0, 0.0, 0.0].map(Complex128::from),
+            false,
+        );
+        assert_close(&complex_values(&padded), &expected, 1e-12);
+
+        let truncated = fft(&input, Some(2), 0, FftNorm::Backward).unwrap();
+        assert_close(&complex_values(&truncated), &[Complex128::new(3.0, 0.0), Complex128::new(-1.0, 0.0)], 1e-12);
+
+        assert!(matches!(fft(&input, Some(0), 0, FftNorm::Backward), Err(FftError::InvalidLength)));
+        assert!(matches!(fft(&input, None, 1, FftNorm::Backward), Err(FftError::InvalidAxis)));
+    }
+
+    #[test]
+    fn test_fft_along_axis() {
+        // 2x3 array, transform along axis 0 (columns)
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let input = real_array(&values, vec![2, 3]);
+        let result = complex_values(&fft(&input, None, 0, FftNorm::Backward).unwrap());
+        let expected = [5.0, 7.0, 9.0, -3.0, -3.0, -3.0].map(Complex128::from);
+        assert_close(&result, &expected, 1e-12);
+    }
+
+    #[test]
+    fn test_rfft_irfft() {
+        for n in [8usize, 9, 17] {
+            let x: Vec<f64> = (0..n).map(|i| (i as f64 * 0.3).cos() + i as f64).collect();
+            let input = real_array(&x, vec![n as i64]);
+            let spectrum = rfft(&input, None, 0, FftNorm::Backward).unwrap();
+            assert_eq!(spectrum.shape(), &[(n / 2 + 1) as i64]);
+            let full = naive_dft(&x.iter().map(|&v| Complex128::from(v)).collect::<Vec<_>>(), false);
+            assert_close(&complex_values(&spectrum), &full[..n / 2 + 1], 1e-10);
+
+            let back = irfft(&spectrum, Some(n), 0, FftNorm::Backward).unwrap();
+            assert_eq!(back.dtype().type_(), NpyType::Double);
+            let back = unsafe { back.to_vec::<f64>().unwrap() };
+            for (a, e) in back.iter().zip(&x) {
+                assert!((a - e).abs() < 1e-10);
+            }
+        }
+
+        // Default output length is 2 * (m - 1)
+        let spectrum = rfft(&real_array(&[1.0, 0.0, -1.0, 0.0], vec![4]), None, 0, FftNorm::Backward).unwrap();
+        assert_eq!(irfft(&spectrum, None, 0, FftNorm::Backward).unwrap().shape(), &[4]);
+
+        let complex_input = fft(&real_array(&[1.0, 2.0], vec![2]), None, 0, FftNorm::Backward).unwrap();
+        assert!(matches!(rfft(&complex_input, None, 0, FftNorm::Backward), Err(FftError::UnsupportedDtype)));
+    }
+
+    #[test]
+    fn test_fft2_and_fftn() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64).collect();
+        let input = real_array(&values, vec![3, 4]);
+        let both = fft2(&input, None, None, FftNorm::Backward).unwrap();
+        let rows_then_cols = fft(&fft(&input, None, 1, FftNorm::Backward).unwrap(), None, 0, FftNorm::Backward).unwrap();
+        assert_close(&complex_values(&both), &complex_values(&rows_then_cols), 1e-12);
+
+        let n_dim = fftn(&input, None, None, FftNorm::Backward).unwrap();
+        assert_close(&complex_values(&n_dim), &complex_values(&both), 1e-12);
+        // DC term is the sum of all elements
+        assert!((complex_values(&n_dim)[0].re - 66.0).abs() < 1e-12);
+
+        let back = ifftn(&n_dim, None, None, FftNorm::Backward).unwrap();
+        let expected: Vec<_> = values.iter().map(|&v| Complex128::from(v)).collect();
+        assert_close(&complex_values(&back), &expected, 1e-12);
+
+        let padded = fft2(&input, Some(&[4, 4]), None, FftNorm::Backward).unwrap();
+        assert_eq!(padded.shape(), &[4, 4]);
+        assert!(matches!(
+            fftn(&input, Some(&[4]), Some(&[0, 1]), FftNorm::Backward),
+            Err(FftError::ShapeMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_single_precision_output() {
+        let input = Array::from_slice(&[1.0f32, 2.0, 3.0, 4.0], vec![4], DType::new(NpyType::Float)).unwrap();
+        let spectrum = fft(&input, None, 0, FftNorm::Backward).unwrap();
+        assert_eq!(spectrum.dtype().type_(), NpyType::CFloat);
+        let back = irfft(&rfft(&input, None, 0, FftNorm::Backward).unwrap(), Some(4), 0, FftNorm::Backward).unwrap();
+        assert_eq!(back.dtype().type_(), NpyType::Float);
+    }
+
+    #[test]
+    fn test_fftfreq() {
+        let freq = fftfreq(5, 0.1).unwrap();
+        let values = unsafe { freq.to_vec::<f64>().unwrap() };
+        let expected = [0.0, 2.0, 4.0, -4.0, -2.0];
+        for (a, e) in values.iter().zip(&expected) {
+            assert!((a - e).abs() < 1e-12);
+        }
+        let freq = unsafe { fftfreq(4, 1.0).unwrap().to_vec::<f64>().unwrap() };
+        assert_eq!(freq, vec![0.0, 0.25, -0.5, -0.25]);
+        let rfreq = unsafe { rfftfreq(5, 1.0).unwrap().to_vec::<f64>().unwrap() };
+        assert_eq!(rfreq, vec![0.0, 0.2, 0.4]);
+    }
+
+    #[test]
+    fn test_fftshift() {
+        let input = real_array(&[0.0, 1.0, 2.0, 3.0, 4.0], vec![5]);
+        let shifted = unsafe { fftshift(&input, None).unwrap().to_vec::<f64>().unwrap() };
+        assert_eq!(shifted, vec![3.0, 4.0, 0.0, 1.0, 2.0]);
+        let unshifted = unsafe { ifftshift(&input, None).unwrap().to_vec::<f64>().unwrap() };
+        assert_eq!(unshifted, vec![2.0, 3.0, 4.0, 0.0, 1.0]);
+
+        let grid = real_array(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![2, 3]);
+        let shifted = unsafe { fftshift(&grid, Some(&[1])).unwrap().to_vec::<f64>().unwrap() };
+        assert_eq!(shifted, vec![2.0, 0.0, 1.0, 5.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_plan_cache() {
+        let mut cache = PlanCache::new(2);
+        let first = cache.insert(4, Arc::new(FftPlan::new(4)));
+        cache.insert(6, Arc::new(FftPlan::new(6)));
+        assert!(Arc::ptr_eq(&cache.get(4).unwrap(), &first));
+
+        // 6 is now the least recently used length, so it goes first
+        cache.insert(8, Arc::new(FftPlan::new(8)));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(6).is_none());
+        assert_eq!(cache.get(8).unwrap().len(), 8);
+
+        // Inserting a cached length keeps the existing plan
+        let again = cache.insert(4, Arc::new(FftPlan::new(4)));
+        assert!(Arc::ptr_eq(&again, &first));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cached_plan() {
+        // Other tests share the global cache, so only values are checked
+        assert_eq!(cached_plan(60).len(), 60);
+        for len in 1000..1000 + 2 * PLAN_CACHE_CAPACITY {
+            assert_eq!(cached_plan(len).len(), len);
+        }
+        assert!(plan_cache_size() <= PLAN_CACHE_CAPACITY);
+    }
+}
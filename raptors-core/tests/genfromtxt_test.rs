@@ -0,0 +1,226 @@
+//! Tests for the genfromtxt-style text loader
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::array::Array;
+    use raptors_core::io::{
+        genfromtxt, genfromtxt_masked_reader, genfromtxt_reader, CellValue, ColumnNames, ColumnTypes,
+        GenFromTxtOptions, TextIoError,
+    };
+    use raptors_core::types::{DType, NpyType};
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    fn load(text: &str, options: GenFromTxtOptions) -> Result<Array, TextIoError> {
+        genfromtxt_reader(Cursor::new(text.as_bytes()), options)
+    }
+
+    /// Raw bytes of field `name` in every record
+    fn field_bytes(array: &Array, name: &str) -> Vec<Vec<u8>> {
+        let layout = array.dtype().fields().unwrap();
+        let field = layout.fields().iter().find(|f| f.name == name).unwrap();
+        let itemsize = array.itemsize();
+        let bytes = unsafe { std::slice::from_raw_parts(array.data_ptr(), array.size() * itemsize) };
+        bytes
+            .chunks(itemsize)
+            .map(|record| record[field.offset..field.offset + field.dtype.itemsize()].to_vec())
+            .collect()
+    }
+
+    fn f64_field(array: &Array, name: &str) -> Vec<f64> {
+        field_bytes(array, name).iter().map(|b| f64::from_ne_bytes(b[..].try_into().unwrap())).collect()
+    }
+
+    fn i64_field(array: &Array, name: &str) -> Vec<i64> {
+        field_bytes(array, name).iter().map(|b| i64::from_ne_bytes(b[..].try_into().unwrap())).collect()
+    }
+
+    fn str_field(array: &Array, name: &str) -> Vec<String> {
+        field_bytes(array, name)
+            .iter()
+            .map(|b| {
+                b.chunks(4)
+                    .map(|c| u32::from_ne_bytes(c.try_into().unwrap()))
+                    .take_while(|&c| c != 0)
+                    .map(|c| char::from_u32(c).unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plain_float_table() {
+        let text = "# comment\n1 2 3\n\n4 5 6 # trailing\n";
+        let array = load(text, GenFromTxtOptions::default()).unwrap();
+        assert_eq!(array.shape(), &[2, 3]);
+        assert_eq!(array.dtype().type_(), NpyType::Double);
+        assert_eq!(unsafe { array.to_vec::<f64>().unwrap() }, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        // A single column is squeezed to 1-D
+        let column = load("1\n2\n3\n", GenFromTxtOptions::default()).unwrap();
+        assert_eq!(column.shape(), &[3]);
+    }
+
+    #[test]
+    fn test_names_and_inferred_dtypes() {
+        let text = "# id, name, score\n1, alice, 2.5\n2, bob, 3\n";
+        let options = GenFromTxtOptions {
+            delimiter: Some(",".to_string()),
+            names: ColumnNames::FromHeader,
+            dtype: ColumnTypes::Infer,
+            ..Default::default()
+        };
+        let array = load(text, options).unwrap();
+        assert_eq!(array.shape(), &[2]);
+        let layout = array.dtype().fields().unwrap();
+        let names: Vec<&str> = layout.fields().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "score"]);
+        assert_eq!(layout.fields()[0].dtype.type_(), NpyType::Long);
+        assert_eq!(layout.fields()[1].dtype.type_(), NpyType::Unicode);
+        assert_eq!(layout.fields()[2].dtype.type_(), NpyType::Double);
+        assert_eq!(i64_field(&array, "id"), vec![1, 2]);
+        assert_eq!(str_field(&array, "name"), vec!["alice", "bob"]);
+        assert_eq!(f64_field(&array, "score"), vec![2.5, 3.0]);
+    }
+
+    #[test]
+    fn test_per_column_dtypes_and_usecols() {
+        let text = "a,1,2.5,x\nb,2,3.5,y\nc,3,4.5,z\n";
+        let options = GenFromTxtOptions {
+            delimiter: Some(",".to_string()),
+            usecols: Some(vec![1, -2]),
+            dtype: ColumnTypes::PerColumn(vec![DType::new(NpyType::Int), DType::new(NpyType::Float)]),
+            max_rows: Some(2),
+            ..Default::default()
+        };
+        let array = load(text, options).unwrap();
+        assert_eq!(array.shape(), &[2]);
+        // Records are packed: int32 followed by float32
+        assert_eq!(array.itemsize(), 8);
+        let ints: Vec<i32> = field_bytes(&array, "f1").iter().map(|b| i32::from_ne_bytes(b[..].try_into().unwrap())).collect();
+        let floats: Vec<f32> = field_bytes(&array, "f2").iter().map(|b| f32::from_ne_bytes(b[..].try_into().unwrap())).collect();
+        assert_eq!(ints, vec![1, 2]);
+        assert_eq!(floats, vec![2.5, 3.5]);
+    }
+
+    #[test]
+    fn test_missing_and_filling_values() {
+        let text = "1,,3\n4,N/A,6\n";
+        let mut options = GenFromTxtOptions {
+            delimiter: Some(",".to_string()),
+            missing_values: vec!["N/A".to_string()],
+            ..Default::default()
+        };
+        let array = load(text, options.clone()).unwrap();
+        let values = unsafe { array.to_vec::<f64>().unwrap() };
+        assert!(values[1].is_nan() && values[4].is_nan());
+        assert_eq!(values[5], 6.0);
+
+        options.filling_values.insert(1, CellValue::Float(-9.0));
+        let array = load(text, options).unwrap();
+        assert_eq!(unsafe { array.to_vec::<f64>().unwrap() }, vec![1.0, -9.0, 3.0, 4.0, -9.0, 6.0]);
+    }
+
+    #[test]
+    fn test_masked_output() {
+        let text = "1,,3\n4,5,\n";
+        let options = GenFromTxtOptions {
+            delimiter: Some(",".to_string()),
+            dtype: ColumnTypes::Single(DType::new(NpyType::Long)),
+            ..Default::default()
+        };
+        let masked = genfromtxt_masked_reader(Cursor::new(text.as_bytes()), options).unwrap();
+        assert_eq!(masked.shape(), &[2, 3]);
+        assert_eq!(masked.count_masked(), 2);
+        assert!(masked.is_masked(1).unwrap() && masked.is_masked(5).unwrap());
+        assert!(!masked.is_masked(0).unwrap());
+        // NumPy's default integer filling value
+        assert_eq!(unsafe { masked.data().to_vec::<i64>().unwrap() }, vec![1, -1, 3, 4, 5, -1]);
+    }
+
+    #[test]
+    fn test_masked_structured_output() {
+        let text = "x y\n1 2\n3 -\n";
+        let options = GenFromTxtOptions {
+            names: ColumnNames::FromHeader,
+            missing_values: vec!["-".to_string()],
+            ..Default::default()
+        };
+        let masked = genfromtxt_masked_reader(Cursor::new(text.as_bytes()), options).unwrap();
+        assert_eq!(masked.shape(), &[2]);
+        let mask = masked.mask();
+        assert_eq!(mask.dtype().fields().unwrap().num_fields(), 2);
+        assert_eq!(field_bytes(mask, "x"), vec![vec![0], vec![0]]);
+        assert_eq!(field_bytes(mask, "y"), vec![vec![0], vec![1]]);
+        assert_eq!(masked.count_masked(), 1);
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        let text = "\"Smith, J\",\"said \"\"hi\"\"\",1\n\"#x\",plain,2\n";
+        let options = GenFromTxtOptions {
+            delimiter: Some(",".to_string()),
+            quotechar: Some('"'),
+            dtype: ColumnTypes::Infer,
+            ..Default::default()
+        };
+        let array = load(text, options).unwrap();
+        assert_eq!(str_field(&array, "f0"), vec!["Smith, J", "#x"]);
+        assert_eq!(str_field(&array, "f1"), vec!["said \"hi\"", "plain"]);
+        assert_eq!(i64_field(&array, "f2"), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_converters() {
+        let text = "1 50%\n2 n/a\n";
+        let mut options = GenFromTxtOptions::default();
+        options.converters.insert(
+            1,
+            Arc::new(|s: &str| s.strip_suffix('%').and_then(|p| p.parse::<f64>().ok()).map(|p| CellValue::Float(p / 100.0))),
+        );
+        options.filling_value = Some(CellValue::Float(0.0));
+        let array = load(text, options).unwrap();
+        assert_eq!(unsafe { array.to_vec::<f64>().unwrap() }, vec![1.0, 0.5, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let text = "1 2\n3\n4 5\n";
+        let err = load(text, GenFromTxtOptions::default()).unwrap_err();
+        assert!(matches!(err, TextIoError::ParseError(ref msg) if msg.contains("line 2")));
+
+        let options = GenFromTxtOptions { invalid_raise: false, ..Default::default() };
+        let array = load(text, options).unwrap();
+        assert_eq!(unsafe { array.to_vec::<f64>().unwrap() }, vec![1.0, 2.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_skip_header_and_given_names_from_file() {
+        let path = std::env::temp_dir().join(format!("raptors_genfromtxt_{}.txt", std::process::id()));
+        std::fs::write(&path, "generated table\n1;2.5+1j\n3;-1j\n").unwrap();
+        let options = GenFromTxtOptions {
+            delimiter: Some(";".to_string()),
+            skip_header: 1,
+            names: ColumnNames::Given(vec!["n".to_string(), "z".to_string()]),
+            dtype: ColumnTypes::PerColumn(vec![DType::new(NpyType::Long), DType::new(NpyType::CDouble)]),
+            ..Default::default()
+        };
+        let array = genfromtxt(&path, options).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(i64_field(&array, "n"), vec![1, 3]);
+        let z: Vec<(f64, f64)> = field_bytes(&array, "z")
+            .iter()
+            .map(|b| (f64::from_ne_bytes(b[..8].try_into().unwrap()), f64::from_ne_bytes(b[8..].try_into().unwrap())))
+            .collect();
+        assert_eq!(z, vec![(2.5, 1.0), (0.0, -1.0)]);
+    }
+
+    #[test]
+    fn test_conversion_errors() {
+        let options = GenFromTxtOptions { dtype: ColumnTypes::Single(DType::new(NpyType::Long)), ..Default::default() };
+        assert!(matches!(load("1 2.5\n", options), Err(TextIoError::ParseError(_))));
+        let options = GenFromTxtOptions { usecols: Some(vec![5]), ..Default::default() };
+        assert!(load("1 2\n", options).is_err());
+        assert!(matches!(genfromtxt("/nonexistent/raptors.txt", GenFromTxtOptions::default()), Err(TextIoError::FileError(_))));
+    }
+}
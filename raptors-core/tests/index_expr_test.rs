@@ -0,0 +1,157 @@
+//! Tests for multi-axis index expressions
+#![allow(clippy::arc_with_non_send_sync)]
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::array::Array;
+    use raptors_core::indexing::{is_scalar_index, IndexError, IndexExpr, Slice};
+    use raptors_core::types::{DType, NpyType};
+    use raptors_core::utils::to_contiguous_bytes;
+    use std::sync::Arc;
+
+    fn arange(shape: Vec<i64>) -> Array {
+        let values: Vec<i64> = (0..shape.iter().product::<i64>()).collect();
+        Array::from_slice(&values, shape, DType::new(NpyType::Long)).unwrap()
+    }
+
+    fn ints(values: &[i64], shape: Vec<i64>) -> IndexExpr {
+        IndexExpr::IntArray(Array::from_slice(values, shape, DType::new(NpyType::Long)).unwrap())
+    }
+
+    fn mask(flags: &[bool], shape: Vec<i64>) -> IndexExpr {
+        IndexExpr::BoolArray(Array::from_slice(flags, shape, DType::new(NpyType::Bool)).unwrap())
+    }
+
+    /// Elements in C order, whatever the layout
+    fn values(array: &Array) -> Vec<i64> {
+        to_contiguous_bytes(array).chunks(8).map(|b| i64::from_ne_bytes(b.try_into().unwrap())).collect()
+    }
+
+    #[test]
+    fn test_basic_expressions_are_views() {
+        let array = arange(vec![2, 3, 4]);
+        let row = array.index(&[1.into()]).unwrap();
+        assert!(row.is_view());
+        assert_eq!(row.shape(), &[3, 4]);
+        assert_eq!(values(&row)[..4], [12, 13, 14, 15]);
+
+        let picked = array.index(&[IndexExpr::Ellipsis, (-1).into()]).unwrap();
+        assert_eq!(values(&picked), vec![3, 7, 11, 15, 19, 23]);
+
+        let reversed = array.index(&[0.into(), Slice::new(None, None, Some(2)).into(), Slice::new(None, None, Some(-1)).into()]).unwrap();
+        assert_eq!(reversed.strides(), &[64, -8]);
+        assert_eq!(values(&reversed), vec![3, 2, 1, 0, 11, 10, 9, 8]);
+
+        let expanded = array.index(&[IndexExpr::NewAxis, 0.into(), IndexExpr::Slice(Slice::full()), IndexExpr::NewAxis]).unwrap();
+        assert_eq!(expanded.shape(), &[1, 3, 1, 4]);
+
+        let element = array.index(&[1.into(), 2.into(), 3.into()]).unwrap();
+        assert_eq!(element.ndim(), 0);
+        assert_eq!(values(&element), vec![23]);
+        assert!(is_scalar_index(&[1.into(), 2.into(), 3.into()], 3));
+        assert!(!is_scalar_index(&[1.into(), 2.into(), IndexExpr::Ellipsis, 3.into()], 3));
+    }
+
+    #[test]
+    fn test_views_from_arc_keep_base_alive() {
+        let base = Arc::new(arange(vec![4, 4]));
+        let column = Array::index_from_arc(&base, &[IndexExpr::Slice(Slice::full()), 2.into()]).unwrap();
+        assert_eq!(Arc::strong_count(&base), 2);
+        drop(base);
+        assert_eq!(values(&column), vec![2, 6, 10, 14]);
+    }
+
+    #[test]
+    fn test_integer_array_placement() {
+        let array = arange(vec![2, 3, 4]);
+        // Adjacent index arrays replace their dimensions in place
+        let adjacent = array.index(&[IndexExpr::Slice(Slice::full()), ints(&[0, 2], vec![2]), 1.into()]).unwrap();
+        assert!(!adjacent.is_view());
+        assert_eq!(adjacent.shape(), &[2, 2]);
+        assert_eq!(values(&adjacent), vec![1, 9, 13, 21]);
+
+        // Separated ones move to the front
+        let separated = array.index(&[ints(&[1, 0], vec![2]), IndexExpr::Slice(Slice::full()), ints(&[0, -1], vec![2])]).unwrap();
+        assert_eq!(separated.shape(), &[2, 3]);
+        assert_eq!(values(&separated), vec![12, 16, 20, 3, 7, 11]);
+
+        // Index arrays broadcast against each other
+        let grid = array.index(&[1.into(), ints(&[0, 2], vec![2, 1]), ints(&[1, 2, 3], vec![3])]).unwrap();
+        assert_eq!(grid.shape(), &[2, 3]);
+        assert_eq!(values(&grid), vec![13, 14, 15, 21, 22, 23]);
+
+        // Any integer dtype works
+        let narrow = Array::from_slice(&[3i16, 0], vec![2], DType::new(NpyType::Short)).unwrap();
+        let picked = arange(vec![5]).index(&[IndexExpr::IntArray(narrow)]).unwrap();
+        assert_eq!(values(&picked), vec![3, 0]);
+    }
+
+    #[test]
+    fn test_boolean_masks() {
+        let array = arange(vec![2, 3]);
+        let selected = array.index(&[mask(&[false, true, false, true, true, false], vec![2, 3])]).unwrap();
+        assert_eq!(values(&selected), vec![1, 3, 4]);
+
+        let rows = array.index(&[mask(&[true, false], vec![2])]).unwrap();
+        assert_eq!(rows.shape(), &[1, 3]);
+
+        let columns = array.index(&[IndexExpr::Ellipsis, mask(&[true, false, true], vec![3])]).unwrap();
+        assert_eq!(values(&columns), vec![0, 2, 3, 5]);
+
+        assert_eq!(array.index(&[mask(&[true], vec![])]).unwrap().shape(), &[1, 2, 3]);
+        assert_eq!(array.index(&[mask(&[false], vec![])]).unwrap().shape(), &[0, 2, 3]);
+    }
+
+    #[test]
+    fn test_index_assign() {
+        let array = arange(vec![3, 4]);
+        let zero = Array::from_slice(&[0i64], vec![], DType::new(NpyType::Long)).unwrap();
+        unsafe { array.index_assign(&[IndexExpr::Slice(Slice::full()), Slice::new(None, None, Some(2)).into()], &zero) }.unwrap();
+
+        // Rows broadcast over the selected rows, cast from float
+        let row = Array::from_slice(&[7.0f64, 8.0, 9.0, 10.0], vec![4], DType::new(NpyType::Double)).unwrap();
+        unsafe { array.index_assign(&[ints(&[0, 2], vec![2])], &row) }.unwrap();
+        assert_eq!(values(&array), vec![7, 8, 9, 10, 0, 5, 0, 7, 7, 8, 9, 10]);
+
+        unsafe { array.index_assign(&[mask(&[false, true, false], vec![3]), 1.into()], &zero) }.unwrap();
+        assert_eq!(values(&array)[4..8], [0, 0, 0, 7]);
+    }
+
+    #[test]
+    fn test_index_assign_overlapping() {
+        let array = arange(vec![6]);
+        let source = array.index(&[Slice::range(None, Some(-1)).into()]).unwrap();
+        unsafe { array.index_assign(&[Slice::range(Some(1), None).into()], &source) }.unwrap();
+        assert_eq!(values(&array), vec![0, 0, 1, 2, 3, 4]);
+
+        // Reversed onto itself, and gathered from a view of itself
+        let reversed = array.index(&[Slice::new(None, None, Some(-1)).into()]).unwrap();
+        unsafe { array.index_assign(&[IndexExpr::Ellipsis], &reversed) }.unwrap();
+        assert_eq!(values(&array), vec![4, 3, 2, 1, 0, 0]);
+        let head = array.index(&[Slice::range(None, Some(2)).into()]).unwrap();
+        unsafe { array.index_assign(&[ints(&[1, 0], vec![2])], &head) }.unwrap();
+        assert_eq!(values(&array), vec![3, 4, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let array = arange(vec![2, 3]);
+        assert!(matches!(array.index(&[5.into()]), Err(IndexError::AxisOutOfBounds { index: 5, axis: 0, size: 2 })));
+        assert!(matches!(array.index(&[0.into(), 0.into(), 0.into()]), Err(IndexError::InvalidExpression(_))));
+        assert!(matches!(array.index(&[IndexExpr::Ellipsis, IndexExpr::Ellipsis]), Err(IndexError::InvalidExpression(_))));
+        assert!(matches!(array.index(&[ints(&[0, 1], vec![2]), ints(&[0, 1, 2], vec![3])]), Err(IndexError::InvalidExpression(_))));
+        assert!(matches!(array.index(&[mask(&[true, false, true], vec![3])]), Err(IndexError::InvalidExpression(_))));
+        let floats = Array::from_slice(&[0.0f64], vec![1], DType::new(NpyType::Double)).unwrap();
+        assert!(matches!(array.index(&[IndexExpr::IntArray(floats)]), Err(IndexError::InvalidExpression(_))));
+        let huge = Array::from_slice(&[u64::MAX], vec![1], DType::new(NpyType::ULongLong)).unwrap();
+        assert!(matches!(
+            array.index(&[IndexExpr::IntArray(huge)]),
+            Err(IndexError::AxisOutOfBounds { index: i64::MAX, axis: 0, size: 2 })
+        ));
+        assert!(matches!(array.index(&[Slice::new(None, None, Some(0)).into()]), Err(IndexError::InvalidValue(_))));
+
+        let pair = Array::from_slice(&[1i64, 2], vec![2], DType::new(NpyType::Long)).unwrap();
+        let result = unsafe { array.index_assign(&[0.into()], &pair) };
+        assert_eq!(result.unwrap_err().to_string(), "could not broadcast input array from shape (2,) into shape (3,)");
+    }
+}
@@ -3,9 +3,55 @@
 #[cfg(test)]
 mod tests {
     use raptors_core::zeros;
-    use raptors_core::io::{save_npy, load_npy};
-    use raptors_core::types::{DType, NpyType};
+    use raptors_core::array::{Array, Order};
+    use raptors_core::datetime::TimeUnit;
+    use raptors_core::io::{array_descr, array_typestr, Descr, save_npy, load_npy, read_npy, write_npy, IoError, NpyHeader, NpyReader};
+    use raptors_core::structured::{Field, StructuredDType};
+    use raptors_core::types::{Complex128, DType, NpyType};
     use std::fs;
+    use std::io::{Cursor, Read};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("raptors_io_test_{}_{}.npy", std::process::id(), name))
+    }
+
+    fn roundtrip(name: &str, array: &Array) -> (Vec<u8>, Array) {
+        let path = temp_path(name);
+        save_npy(&path, array).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let loaded = load_npy(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        (bytes, loaded)
+    }
+
+    /// Header text of an NPY file (between the length prefix and the data)
+    fn header_text(bytes: &[u8]) -> String {
+        let (start, len) = match bytes[6] {
+            1 => (10, u16::from_le_bytes([bytes[8], bytes[9]]) as usize),
+            _ => (12, u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize),
+        };
+        String::from_utf8(bytes[start..start + len].to_vec()).unwrap()
+    }
+
+    /// Build a version 1.0 NPY file from a header dict and raw data
+    fn npy_file(dict: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = dict.as_bytes().to_vec();
+        header.resize((10 + header.len() + 1).div_ceil(64) * 64 - 11, b' ');
+        header.push(b'\n');
+        let mut out = b"\x93NUMPY\x01\x00".to_vec();
+        out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn load_bytes(name: &str, bytes: &[u8]) -> Result<Array, IoError> {
+        let path = temp_path(name);
+        fs::write(&path, bytes).unwrap();
+        let result = load_npy(&path);
+        let _ = fs::remove_file(&path);
+        result
+    }
 
     #[test]
     fn test_save_load_roundtrip() {
@@ -77,5 +123,349 @@ mod tests {
         
         let _ = fs::remove_file(test_path);
     }
-}
 
+    #[test]
+    fn test_header_matches_numpy() {
+        let array = Array::from_slice(&[0i64, 1, 2], vec![3], DType::new(NpyType::Long)).unwrap();
+        let (bytes, _) = roundtrip("numpy_header", &array);
+        // np.save(f, np.arange(3)) writes a 128-byte version 1.0 header
+        let mut expected = b"\x93NUMPY\x01\x00v\x00{'descr': '<i8', 'fortran_order': False, 'shape': (3,), }".to_vec();
+        expected.resize(127, b' ');
+        expected.push(b'\n');
+        assert_eq!(&bytes[..128], expected.as_slice());
+        assert_eq!(bytes.len(), 128 + 24);
+    }
+
+    #[test]
+    fn test_all_scalar_dtypes_roundtrip() {
+        let cases = [
+            (DType::new(NpyType::Bool), "'|b1'"),
+            (DType::new(NpyType::Byte), "'|i1'"),
+            (DType::new(NpyType::UShort), "'<u2'"),
+            (DType::new(NpyType::UInt), "'<u4'"),
+            (DType::new(NpyType::ULong), "'<u8'"),
+            (DType::new(NpyType::Half), "'<f2'"),
+            (DType::new(NpyType::Float), "'<f4'"),
+            (DType::new(NpyType::CFloat), "'<c8'"),
+            (DType::new(NpyType::CDouble), "'<c16'"),
+            (DType::string_with_itemsize(5), "'|S5'"),
+            (DType::unicode_with_itemsize(12), "'<U3'"),
+            (DType::void_with_itemsize(3), "'|V3'"),
+            (DType::datetime(TimeUnit::Nanosecond), "'<M8[ns]'"),
+            (DType::timedelta(TimeUnit::Second), "'<m8[s]'"),
+            (DType::new(NpyType::DateTime), "'<M8'"),
+        ];
+        for (i, (dtype, descr)) in cases.iter().enumerate() {
+            let mut array = Array::new(vec![2, 2], dtype.clone()).unwrap();
+            let len = array.size() * array.itemsize();
+            let data = unsafe { std::slice::from_raw_parts_mut(array.data_ptr_mut(), len) };
+            for (j, byte) in data.iter_mut().enumerate() {
+                *byte = (j * 7 % 251) as u8 % if dtype.type_() == NpyType::Bool { 2 } else { 251 };
+            }
+            let expected = data.to_vec();
+            let (bytes, loaded) = roundtrip(&format!("dtype{}", i), &array);
+            assert!(header_text(&bytes).contains(&format!("'descr': {},", descr)), "{}", header_text(&bytes));
+            assert_eq!(loaded.dtype().type_(), dtype.type_());
+            assert_eq!(loaded.itemsize(), dtype.itemsize());
+            assert_eq!(loaded.dtype().time_unit(), dtype.time_unit());
+            let loaded_data = unsafe { std::slice::from_raw_parts(loaded.data_ptr(), len) };
+            assert_eq!(loaded_data, expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_complex_values_roundtrip() {
+        let values = [Complex128::new(1.5, -2.0), Complex128::new(0.0, 3.25)];
+        let array = Array::from_slice(&values, vec![2], DType::new(NpyType::CDouble)).unwrap();
+        let (_, loaded) = roundtrip("complex", &array);
+        assert_eq!(unsafe { loaded.to_vec::<Complex128>().unwrap() }, values.to_vec());
+    }
+
+    #[test]
+    fn test_big_endian_is_swapped() {
+        let mut data = Vec::new();
+        for v in [1i32, -2, 300] {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+        let bytes = npy_file("{'descr': '>i4', 'fortran_order': False, 'shape': (3,), }", &data);
+        let loaded = load_bytes("big_endian", &bytes).unwrap();
+        assert_eq!(unsafe { loaded.to_vec::<i32>().unwrap() }, vec![1, -2, 300]);
+
+        let mut data = Vec::new();
+        for v in [1.5f64, -0.25] {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+        let bytes = npy_file("{'descr': '>c16', 'fortran_order': False, 'shape': (1,), }", &data);
+        let loaded = load_bytes("big_endian_complex", &bytes).unwrap();
+        assert_eq!(unsafe { loaded.to_vec::<Complex128>().unwrap() }, vec![Complex128::new(1.5, -0.25)]);
+    }
+
+    #[test]
+    fn test_header_literal_syntax() {
+        // Double quotes, Python 2 long suffixes, reordered keys and extra
+        // whitespace are all valid Python literals
+        let data: Vec<u8> = (0..6u8).collect();
+        let bytes = npy_file("{ \"shape\" : (2L, 3L) , 'fortran_order':False,'descr':u'|u1'}", &data);
+        let loaded = load_bytes("literal", &bytes).unwrap();
+        assert_eq!(loaded.shape(), &[2, 3]);
+        assert_eq!(unsafe { loaded.to_vec::<u8>().unwrap() }, data);
+
+        let bytes = npy_file("{'descr': '<f8', 'fortran_order': False, 'shape': (), }", &2.5f64.to_le_bytes());
+        let loaded = load_bytes("scalar", &bytes).unwrap();
+        assert_eq!(loaded.ndim(), 0);
+        assert_eq!(unsafe { loaded.to_vec::<f64>().unwrap() }, vec![2.5]);
+    }
+
+    #[test]
+    fn test_invalid_headers_rejected() {
+        let invalid = [
+            "{'descr': '<f8', 'shape': (1,), }",
+            "{'descr': '<f8', 'fortran_order': False, 'shape': (1,), 'extra': 1}",
+            "{'descr': '<f8', 'fortran_order': 0, 'shape': (1,), }",
+            "{'descr': '<f8', 'fortran_order': False, 'shape': (-1,), }",
+            "{'descr': '<f8', 'fortran_order': False, 'shape': (1,)",
+            "{'descr': '|O', 'fortran_order': False, 'shape': (1,), }",
+        ];
+        for (i, dict) in invalid.iter().enumerate() {
+            let bytes = npy_file(dict, &[0; 8]);
+            assert!(load_bytes(&format!("invalid{}", i), &bytes).is_err(), "{}", dict);
+        }
+        let mut bytes = npy_file("{'descr': '<f8', 'fortran_order': False, 'shape': (1,), }", &[0; 8]);
+        bytes[6] = 4;
+        assert!(matches!(load_bytes("bad_version", &bytes), Err(IoError::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn test_fortran_order_roundtrip() {
+        let mut array = Array::new_with_order(vec![2, 3], DType::new(NpyType::Int), Order::F).unwrap();
+        unsafe {
+            let ptr = array.data_ptr_mut() as *mut i32;
+            for i in 0..6 {
+                *ptr.add(i) = i as i32;
+            }
+        }
+        let (bytes, loaded) = roundtrip("fortran", &array);
+        assert!(header_text(&bytes).contains("'fortran_order': True, 'shape': (2, 3), "));
+        // Data is stored in memory (column-major) order
+        assert_eq!(&bytes[bytes.len() - 24..bytes.len() - 20], &0i32.to_le_bytes());
+        assert_eq!(&bytes[bytes.len() - 20..bytes.len() - 16], &1i32.to_le_bytes());
+        assert!(loaded.is_f_contiguous());
+        assert_eq!(loaded.strides(), array.strides());
+        assert_eq!(unsafe { loaded.to_vec::<i32>().unwrap() }, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_strided_view_written_in_logical_order() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64).collect();
+        let base = Array::from_slice(&values, vec![3, 4], DType::new(NpyType::Double)).unwrap();
+        // Transposed view (4, 3) and every other column (3, 2)
+        let transposed = base.view(vec![4, 3], vec![8, 32]).unwrap();
+        let (bytes, loaded) = roundtrip("transposed", &transposed);
+        assert!(header_text(&bytes).contains("'fortran_order': True, 'shape': (4, 3), "));
+        assert_eq!(unsafe { loaded.to_vec::<f64>().unwrap() }, values);
+
+        let strided = base.view(vec![3, 2], vec![32, 16]).unwrap();
+        let (bytes, loaded) = roundtrip("strided", &strided);
+        assert!(header_text(&bytes).contains("'fortran_order': False, 'shape': (3, 2), "));
+        assert_eq!(unsafe { loaded.to_vec::<f64>().unwrap() }, vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+    }
+
+    #[test]
+    fn test_structured_roundtrip() {
+        let fields = vec![
+            Field::new("id", DType::new(NpyType::Int), 0),
+            Field::new("pos", DType::new(NpyType::Double), 8).with_shape(vec![2]),
+            Field::new("tag", DType::string_with_itemsize(3), 24),
+        ];
+        let layout = StructuredDType::with_offsets(fields, 32).unwrap();
+        let mut array = Array::new(vec![2], DType::structured(layout)).unwrap();
+        let len = array.size() * array.itemsize();
+        let data = unsafe { std::slice::from_raw_parts_mut(array.data_ptr_mut(), len) };
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let expected = data.to_vec();
+
+        let (bytes, loaded) = roundtrip("structured", &array);
+        assert!(header_text(&bytes).starts_with(
+            "{'descr': [('id', '<i4'), ('', '|V4'), ('pos', '<f8', (2,)), ('tag', '|S3'), ('', '|V5')], "
+        ));
+        let layout = loaded.dtype().fields().unwrap();
+        assert_eq!(layout.itemsize(), 32);
+        assert_eq!(layout.field_names(), vec!["id", "pos", "tag"]);
+        assert_eq!(layout.get_field_by_name("pos").unwrap().offset, 8);
+        assert_eq!(layout.get_field_by_name("pos").unwrap().shape, vec![2]);
+        let loaded_data = unsafe { std::slice::from_raw_parts(loaded.data_ptr(), len) };
+        assert_eq!(loaded_data, expected.as_slice());
+    }
+
+    #[test]
+    fn test_structured_big_endian_fields_swapped() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&7i16.to_be_bytes());
+        data.extend_from_slice(&1.5f32.to_le_bytes());
+        let bytes = npy_file("{'descr': [('a', '>i2'), ('b', '<f4')], 'fortran_order': False, 'shape': (1,), }", &data);
+        let loaded = load_bytes("structured_be", &bytes).unwrap();
+        let raw = unsafe { std::slice::from_raw_parts(loaded.data_ptr(), 6) };
+        assert_eq!(&raw[..2], &7i16.to_ne_bytes());
+        assert_eq!(&raw[2..], &1.5f32.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_version_2_and_3_headers() {
+        // Over 64 KiB of field names needs a 4-byte header length
+        let fields: Vec<Field> = (0..3000)
+            .map(|i| Field::new(format!("field_with_a_long_name_{:05}", i), DType::new(NpyType::UByte), i))
+            .collect();
+        let layout = StructuredDType::with_offsets(fields, 3000).unwrap();
+        let array = Array::new(vec![1], DType::structured(layout)).unwrap();
+        let (bytes, loaded) = roundtrip("version2", &array);
+        assert_eq!(&bytes[6..8], &[2, 0]);
+        assert_eq!((12 + u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize) % 64, 0);
+        assert_eq!(loaded.dtype().fields().unwrap().num_fields(), 3000);
+
+        // Names outside Latin-1 need a UTF-8 header
+        let layout = StructuredDType::with_offsets(vec![Field::new("温度", DType::new(NpyType::Float), 0)], 4).unwrap();
+        let array = Array::new(vec![1], DType::structured(layout)).unwrap();
+        let (bytes, loaded) = roundtrip("version3", &array);
+        assert_eq!(&bytes[6..8], &[3, 0]);
+        assert_eq!(loaded.dtype().fields().unwrap().field_names(), vec!["温度"]);
+
+        // Latin-1 names stay in version 1.0
+        let layout = StructuredDType::with_offsets(vec![Field::new("café", DType::new(NpyType::Float), 0)], 4).unwrap();
+        let array = Array::new(vec![1], DType::structured(layout)).unwrap();
+        let (bytes, loaded) = roundtrip("version1_latin1", &array);
+        assert_eq!(&bytes[6..8], &[1, 0]);
+        assert_eq!(loaded.dtype().fields().unwrap().field_names(), vec!["café"]);
+    }
+
+    #[test]
+    fn test_npy_header_api() {
+        let header = NpyHeader::new(DType::new(NpyType::Double), vec![4, 5], true);
+        let bytes = header.to_bytes().unwrap();
+        assert_eq!(bytes.len() % 64, 0);
+        let parsed = NpyHeader::read_from(&mut bytes.as_slice()).unwrap();
+        assert!(parsed.fortran_order);
+        assert_eq!(parsed.shape, vec![4, 5]);
+        assert_eq!(parsed.data_len(), 160);
+        assert!(!parsed.needs_byteswap());
+    }
+
+    fn long_rows(rows: i64, cols: i64) -> Array {
+        let values: Vec<i64> = (0..rows * cols).collect();
+        Array::from_slice(&values, vec![rows, cols], DType::new(NpyType::Long)).unwrap()
+    }
+
+    #[test]
+    fn test_read_write_in_memory() {
+        // Several arrays written back to back can be read back in turn
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, &long_rows(2, 3)).unwrap();
+        write_npy(&mut bytes, &Array::from_slice(&[0.5f64], vec![], DType::new(NpyType::Double)).unwrap()).unwrap();
+
+        let mut reader = bytes.as_slice();
+        let first = read_npy(&mut reader).unwrap();
+        assert_eq!(first.shape(), &[2, 3]);
+        assert_eq!(unsafe { first.to_vec::<i64>().unwrap() }, (0..6).collect::<Vec<_>>());
+        let second = read_npy(&mut reader).unwrap();
+        assert_eq!(second.ndim(), 0);
+        assert_eq!(unsafe { second.to_vec::<f64>().unwrap() }, vec![0.5]);
+        assert!(reader.is_empty());
+        // Truncated data is an error
+        assert!(read_npy(&bytes[..140]).is_err());
+    }
+
+    #[test]
+    fn test_npy_reader_chunks() {
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, &long_rows(10, 3)).unwrap();
+        let mut reader = NpyReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.rows(), 10);
+        assert_eq!(reader.row_bytes(), 24);
+
+        let chunks: Vec<Array> = reader.chunks(4).collect::<Result<_, _>>().unwrap();
+        assert_eq!(chunks.iter().map(|c| c.shape()[0]).collect::<Vec<_>>(), vec![4, 4, 2]);
+        assert_eq!(chunks[2].shape(), &[2, 3]);
+        assert_eq!(unsafe { chunks[2].to_vec::<i64>().unwrap() }, (24..30).collect::<Vec<_>>());
+        assert_eq!(reader.remaining(), 0);
+        assert!(reader.read_rows(1).unwrap().is_none());
+        assert!(reader.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_npy_reader_seek_slices() {
+        // The NPY stream may start part way into a larger object
+        let mut bytes = b"prefix".to_vec();
+        write_npy(&mut bytes, &long_rows(8, 2)).unwrap();
+        let mut cursor = Cursor::new(bytes);
+        let mut prefix = [0u8; 6];
+        cursor.read_exact(&mut prefix).unwrap();
+
+        let mut reader = NpyReader::new(cursor).unwrap();
+        let middle = reader.read_slice(3, 6).unwrap();
+        assert_eq!(middle.shape(), &[3, 2]);
+        assert_eq!(unsafe { middle.to_vec::<i64>().unwrap() }, vec![6, 7, 8, 9, 10, 11]);
+        assert_eq!(reader.position(), 6);
+        let start = reader.read_slice(0, 1).unwrap();
+        assert_eq!(unsafe { start.to_vec::<i64>().unwrap() }, vec![0, 1]);
+        reader.seek_row(7).unwrap();
+        assert_eq!(unsafe { reader.read_to_end().unwrap().to_vec::<i64>().unwrap() }, vec![14, 15]);
+        assert!(reader.read_slice(5, 9).is_err());
+        assert!(reader.read_slice(4, 3).is_err());
+    }
+
+    #[test]
+    fn test_npy_reader_fortran_and_byteswapped() {
+        // Fortran-order data is chunked along the last axis
+        let mut array = Array::new_with_order(vec![2, 3], DType::new(NpyType::Int), Order::F).unwrap();
+        unsafe {
+            let ptr = array.data_ptr_mut() as *mut i32;
+            for i in 0..6 {
+                *ptr.add(i) = i as i32;
+            }
+        }
+        let mut bytes = Vec::new();
+        write_npy(&mut bytes, &array).unwrap();
+        let mut reader = NpyReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.rows(), 3);
+        let columns = reader.read_slice(1, 3).unwrap();
+        assert_eq!(columns.shape(), &[2, 2]);
+        assert!(columns.is_f_contiguous());
+        assert_eq!(unsafe { columns.to_vec::<i32>().unwrap() }, vec![2, 3, 4, 5]);
+
+        let mut data = Vec::new();
+        for v in [1i32, -2, 300, 4] {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+        let bytes = npy_file("{'descr': '>i4', 'fortran_order': False, 'shape': (4,), }", &data);
+        let mut reader = NpyReader::new(bytes.as_slice()).unwrap();
+        assert!(reader.header().needs_byteswap());
+        let chunk = reader.read_rows(3).unwrap().unwrap();
+        assert_eq!(unsafe { chunk.to_vec::<i32>().unwrap() }, vec![1, -2, 300]);
+    }
+
+    #[test]
+    fn test_array_interface_descr() {
+        let native = if cfg!(target_endian = "little") { '<' } else { '>' };
+        assert_eq!(array_typestr(&DType::new(NpyType::Half)).unwrap(), format!("{}f2", native));
+        assert_eq!(array_typestr(&DType::datetime(TimeUnit::Nanosecond)).unwrap(), format!("{}M8[ns]", native));
+        assert_eq!(array_descr(&DType::new(NpyType::Bool)).unwrap().repr(), "[('', '|b1')]");
+
+        let layout = StructuredDType::with_offsets(vec![
+            Field::new("a", DType::new(NpyType::Int), 0),
+            Field::new("b", DType::new(NpyType::Double), 4),
+        ], 12).unwrap();
+        let structured = DType::structured(layout);
+        assert_eq!(array_typestr(&structured).unwrap(), "|V12");
+        let parsed = Descr::parse(&array_descr(&structured).unwrap().repr()).unwrap();
+        assert_eq!(parsed.dtype.fields().unwrap().fields().len(), 2);
+        assert!(parsed.is_native());
+
+        let swapped = Descr::parse("'>u2'").unwrap();
+        assert_eq!(swapped.dtype.type_(), NpyType::UShort);
+        let mut data = vec![0u8, 1, 0, 2];
+        swapped.byteswap(&mut data);
+        assert_eq!(data, vec![1, 0, 2, 0]);
+        assert!(Descr::parse("'<x3'").is_err());
+    }
+}
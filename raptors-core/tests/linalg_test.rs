@@ -3,7 +3,9 @@
 #[cfg(test)]
 mod tests {
     use raptors_core::zeros;
-    use raptors_core::linalg::{dot, matmul};
+    use raptors_core::linalg::{dot, eigvals, lstsq, matmul};
+    use raptors_core::types::Complex128;
+    use raptors_core::Array;
     use raptors_core::types::{DType, NpyType};
 
     #[test]
@@ -123,5 +125,58 @@ mod tests {
             assert!((*ptr.add(3) - 50.0).abs() < 1e-10);
         }
     }
-}
 
+    #[test]
+    fn test_lstsq_line_fit() {
+        // numpy.linalg.lstsq documentation example
+        let dtype = DType::new(NpyType::Double);
+        let a = Array::from_slice(&[0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0, 1.0], vec![4, 2], dtype.clone()).unwrap();
+        let b = Array::from_slice(&[-1.0, 0.2, 0.9, 2.1], vec![4], dtype).unwrap();
+
+        let result = lstsq(&a, &b, None).unwrap();
+        let x = unsafe { result.solution.to_vec::<f64>().unwrap() };
+        assert!((x[0] - 1.0).abs() < 1e-12);
+        assert!((x[1] + 0.95).abs() < 1e-12);
+        assert_eq!(result.rank, 2);
+        let residuals = unsafe { result.residuals.to_vec::<f64>().unwrap() };
+        assert_eq!(residuals.len(), 1);
+        assert!((residuals[0] - 0.05).abs() < 1e-12);
+        assert_eq!(result.singular_values.shape(), &[2]);
+    }
+
+    #[test]
+    fn test_lstsq_rank_deficient() {
+        let dtype = DType::new(NpyType::Double);
+        let a = Array::from_slice(&[1.0, 1.0, 2.0, 2.0, 3.0, 3.0], vec![3, 2], dtype.clone()).unwrap();
+        let b = Array::from_slice(&[2.0, 4.0, 6.0], vec![3], dtype).unwrap();
+
+        let result = lstsq(&a, &b, None).unwrap();
+        // Minimum-norm solution splits the weight evenly
+        let x = unsafe { result.solution.to_vec::<f64>().unwrap() };
+        assert!((x[0] - 1.0).abs() < 1e-12);
+        assert!((x[1] - 1.0).abs() < 1e-12);
+        assert_eq!(result.rank, 1);
+        assert_eq!(result.residuals.size(), 0);
+    }
+
+    #[test]
+    fn test_eigvals() {
+        let dtype = DType::new(NpyType::Double);
+        let rotation = Array::from_slice(&[0.0, -1.0, 1.0, 0.0], vec![2, 2], dtype.clone()).unwrap();
+        let values = unsafe { eigvals(&rotation).unwrap().to_vec::<Complex128>().unwrap() };
+        let mut imag: Vec<f64> = values.iter().map(|z| z.im).collect();
+        imag.sort_by(f64::total_cmp);
+        assert!(values.iter().all(|z| z.re.abs() < 1e-12));
+        assert!((imag[0] + 1.0).abs() < 1e-12 && (imag[1] - 1.0).abs() < 1e-12);
+
+        let a = Array::from_slice(&[2.0, 1.0, 0.0, 1.0, 3.0, 1.0, 0.0, 1.0, 4.0], vec![3, 3], dtype).unwrap();
+        let values = unsafe { eigvals(&a).unwrap().to_vec::<Complex128>().unwrap() };
+        let mut real: Vec<f64> = values.iter().map(|z| z.re).collect();
+        real.sort_by(f64::total_cmp);
+        let expected = [3.0 - 3f64.sqrt(), 3.0, 3.0 + 3f64.sqrt()];
+        for (r, e) in real.iter().zip(expected) {
+            assert!((r - e).abs() < 1e-10);
+        }
+        assert!(values.iter().all(|z| z.im == 0.0));
+    }
+}
@@ -5,6 +5,7 @@ mod tests {
     use raptors_core::DType;
     use raptors_core::types::NpyType;
     use raptors_core::memmap::*;
+    use raptors_core::io::NpyHeader;
     use std::path::Path;
     use std::fs;
 
@@ -410,5 +411,231 @@ mod tests {
         
         let _ = fs::remove_file(temp_file);
     }
-}
 
+    fn npy_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("raptors_memmap_test_{}_{}.npy", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_open_memmap_create_and_reopen() {
+        use raptors_core::io::{load_npy, save_npy};
+        use raptors_core::Array;
+
+        let path = npy_path("create");
+        {
+            let mut mmap = open_memmap(&path, MapMode::Create, Some(DType::new(NpyType::Long)), Some(vec![2, 3]), false)
+                .unwrap();
+            assert_eq!(mmap.offset(), 128);
+            unsafe {
+                let ptr = mmap.array_mut().data_ptr_mut() as *mut i64;
+                for i in 0..6 {
+                    *ptr.add(i) = i as i64 * 10;
+                }
+            }
+            mmap.flush().unwrap();
+        }
+        // The file is a regular NPY file
+        let loaded = load_npy(&path).unwrap();
+        assert_eq!(loaded.shape(), &[2, 3]);
+        assert_eq!(unsafe { loaded.to_vec::<i64>().unwrap() }, vec![0, 10, 20, 30, 40, 50]);
+
+        // Files written by save_npy map at their header offset
+        let values: Vec<f64> = (0..4).map(|i| i as f64 + 0.5).collect();
+        save_npy(&path, &Array::from_slice(&values, vec![4], DType::new(NpyType::Double)).unwrap()).unwrap();
+        let mmap = open_memmap(&path, MapMode::ReadOnly, None, None, false).unwrap();
+        assert_eq!(mmap.array().shape(), &[4]);
+        assert_eq!(mmap.array().dtype().type_(), NpyType::Double);
+        assert!(!mmap.array().is_writeable());
+        assert_eq!(unsafe { mmap.array().to_vec::<f64>().unwrap() }, values);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_open_memmap_write_modes() {
+        let path = npy_path("modes");
+        drop(open_memmap(&path, MapMode::Create, None, Some(vec![3]), false).unwrap());
+
+        // Copy-on-write changes stay in memory
+        {
+            let mut mmap = open_memmap(&path, MapMode::CopyOnWrite, None, None, false).unwrap();
+            unsafe { *(mmap.array_mut().data_ptr_mut() as *mut f64) = 7.0 };
+            assert_eq!(unsafe { mmap.array().to_vec::<f64>().unwrap() }, vec![7.0, 0.0, 0.0]);
+        }
+        let mmap = open_memmap(&path, MapMode::ReadOnly, None, None, false).unwrap();
+        assert_eq!(unsafe { mmap.array().to_vec::<f64>().unwrap() }, vec![0.0, 0.0, 0.0]);
+        drop(mmap);
+
+        // Read-write changes reach the file
+        {
+            let mut mmap = open_memmap(&path, MapMode::ReadWrite, None, None, false).unwrap();
+            unsafe { *(mmap.array_mut().data_ptr_mut() as *mut f64).add(2) = 3.5 };
+            mmap.flush().unwrap();
+        }
+        let mmap = open_memmap(&path, MapMode::ReadOnly, None, None, false).unwrap();
+        assert_eq!(unsafe { mmap.array().to_vec::<f64>().unwrap() }, vec![0.0, 0.0, 3.5]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_open_memmap_fortran_order() {
+        let path = npy_path("fortran");
+        {
+            let mut mmap = open_memmap(&path, MapMode::Create, Some(DType::new(NpyType::Int)), Some(vec![2, 3]), true)
+                .unwrap();
+            assert!(mmap.array().is_f_contiguous());
+            assert_eq!(mmap.array().strides(), &[4, 8]);
+            unsafe {
+                let ptr = mmap.array_mut().data_ptr_mut() as *mut i32;
+                for i in 0..6 {
+                    *ptr.add(i) = i as i32;
+                }
+            }
+            mmap.flush().unwrap();
+        }
+        let loaded = raptors_core::io::load_npy(&path).unwrap();
+        assert!(loaded.is_f_contiguous());
+        let mmap = open_memmap(&path, MapMode::ReadOnly, None, None, false).unwrap();
+        assert_eq!(mmap.array().strides(), &[4, 8]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_open_memmap_errors_and_modes() {
+        assert_eq!("r".parse::<MapMode>().unwrap(), MapMode::ReadOnly);
+        assert_eq!("r+".parse::<MapMode>().unwrap(), MapMode::ReadWrite);
+        assert_eq!("c".parse::<MapMode>().unwrap(), MapMode::CopyOnWrite);
+        assert_eq!("w+".parse::<MapMode>().unwrap(), MapMode::Create);
+        assert!(matches!("x".parse::<MapMode>(), Err(MemMapError::InvalidMode(_))));
+
+        let path = npy_path("errors");
+        assert!(matches!(
+            open_memmap(&path, MapMode::ReadOnly, None, None, false),
+            Err(MemMapError::FileNotFound)
+        ));
+        assert!(open_memmap(&path, MapMode::Create, None, None, false).is_err());
+
+        // Not an NPY file
+        fs::write(&path, b"raw bytes, no header").unwrap();
+        assert!(open_memmap(&path, MapMode::ReadOnly, None, None, false).is_err());
+
+        // Non-native byte order cannot be mapped as-is
+        let mut header = NpyHeader::new(DType::new(NpyType::Double), vec![1], false).to_bytes().unwrap();
+        let native = if cfg!(target_endian = "little") { b"'<f8'" } else { b"'>f8'" };
+        let pos = header.windows(5).position(|w| w == native).unwrap();
+        header[pos + 1] = if cfg!(target_endian = "little") { b'>' } else { b'<' };
+        header.extend_from_slice(&[0u8; 8]);
+        fs::write(&path, &header).unwrap();
+        assert!(open_memmap(&path, MapMode::ReadOnly, None, None, false).is_err());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_memmap_with_offset() {
+        let path = npy_path("offset");
+        let bytes: Vec<u8> = (0..10_000).map(|i| (i % 251) as u8).collect();
+        fs::write(&path, &bytes).unwrap();
+
+        // Offsets need not be page-aligned
+        let mmap = MemMapArray::with_offset(&path, DType::new(NpyType::UByte), vec![5], MapMode::ReadOnly, 4099).unwrap();
+        assert_eq!(mmap.offset(), 4099);
+        assert_eq!(unsafe { mmap.array().to_vec::<u8>().unwrap() }, bytes[4099..4104].to_vec());
+
+        {
+            let mut mmap =
+                MemMapArray::with_offset(&path, DType::new(NpyType::Long), vec![2], MapMode::ReadWrite, 4104).unwrap();
+            unsafe { *(mmap.array_mut().data_ptr_mut() as *mut i64).add(1) = -1 };
+            mmap.flush().unwrap();
+        }
+        let written = fs::read(&path).unwrap();
+        assert_eq!(&written[4112..4120], &[0xff; 8]);
+        assert_eq!(&written[4104..4112], &bytes[4104..4112]);
+
+        assert!(MemMapArray::with_offset(&path, DType::new(NpyType::Long), vec![2], MapMode::ReadOnly, 9990).is_err());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_memmap_window_of_large_file() {
+        use std::io::{Seek, SeekFrom, Write};
+
+        // A sparse 1 GiB file; only the mapped window is touched
+        let path = npy_path("window");
+        let len: u64 = 1 << 30;
+        {
+            let mut file = fs::File::create(&path).unwrap();
+            file.set_len(len).unwrap();
+            file.seek(SeekFrom::Start(len - 16)).unwrap();
+            file.write_all(&7i64.to_ne_bytes()).unwrap();
+            file.write_all(&9i64.to_ne_bytes()).unwrap();
+        }
+        let dtype = DType::new(NpyType::Long);
+        let mut mmap = MemMapArray::with_offset(&path, dtype, vec![2], MapMode::ReadOnly, len - 16).unwrap();
+        assert_eq!(unsafe { mmap.array().to_vec::<i64>().unwrap() }, vec![7, 9]);
+
+        // Slide the window without reopening the file
+        mmap.remap(len - 24, vec![2]).unwrap();
+        assert_eq!(mmap.offset(), len - 24);
+        assert_eq!(unsafe { mmap.array().to_vec::<i64>().unwrap() }, vec![0, 7]);
+        assert!(mmap.remap(len - 8, vec![2]).is_err());
+        drop(mmap);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_memmap_resize() {
+        let path = npy_path("resize");
+        let dtype = DType::new(NpyType::Long);
+        let mut mmap = MemMapArray::with_offset(&path, dtype.clone(), vec![4], MapMode::Create, 8).unwrap();
+        unsafe {
+            let ptr = mmap.array_mut().data_ptr_mut() as *mut i64;
+            for i in 0..4 {
+                *ptr.add(i) = i as i64 + 1;
+            }
+        }
+        mmap.resize(vec![3, 2]).unwrap();
+        assert_eq!(mmap.array().shape(), &[3, 2]);
+        assert_eq!(fs::metadata(&path).unwrap().len(), 56);
+        assert_eq!(unsafe { mmap.array().to_vec::<i64>().unwrap() }, vec![1, 2, 3, 4, 0, 0]);
+        drop(mmap);
+
+        let mut readonly = MemMapArray::with_offset(&path, dtype, vec![6], MapMode::ReadOnly, 8).unwrap();
+        assert!(readonly.resize(vec![8]).is_err());
+        drop(readonly);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_open_memmap_resize_rewrites_header() {
+        let path = npy_path("resize_npy");
+        {
+            let mut mmap = open_memmap(&path, MapMode::Create, None, Some(vec![2, 3]), false).unwrap();
+            unsafe { *(mmap.array_mut().data_ptr_mut() as *mut f64) = 1.5 };
+            mmap.resize(vec![1000, 3]).unwrap();
+            unsafe { *(mmap.array_mut().data_ptr_mut() as *mut f64).add(2999) = 2.5 };
+            mmap.flush().unwrap();
+        }
+        let loaded = raptors_core::io::load_npy(&path).unwrap();
+        assert_eq!(loaded.shape(), &[1000, 3]);
+        let values = unsafe { loaded.to_vec::<f64>().unwrap() };
+        assert_eq!((values[0], values[1], values[2999]), (1.5, 0.0, 2.5));
+
+        // Shrinking truncates the file so it stays a valid NPY file
+        let mut mmap = open_memmap(&path, MapMode::ReadWrite, None, None, false).unwrap();
+        mmap.resize(vec![1, 3]).unwrap();
+        drop(mmap);
+        assert_eq!(fs::metadata(&path).unwrap().len(), 128 + 24);
+        assert_eq!(raptors_core::io::load_npy(&path).unwrap().shape(), &[1, 3]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_memmap_advise() {
+        let path = npy_path("advise");
+        let mmap = open_memmap(&path, MapMode::Create, None, Some(vec![1024]), false).unwrap();
+        for advice in [MemMapAdvice::Sequential, MemMapAdvice::Random, MemMapAdvice::WillNeed, MemMapAdvice::Normal] {
+            mmap.advise(advice).unwrap();
+        }
+        drop(mmap);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,144 @@
+//! Tests for NPZ archive I/O
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::array::{Array, Order};
+    use raptors_core::io::{load_npy, save_npy, savez, savez_compressed, IoError, NpzFile};
+    use raptors_core::types::{DType, NpyType};
+    use std::fs;
+    use std::io::Cursor;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("raptors_npz_test_{}_{}.npz", std::process::id(), name))
+    }
+
+    fn doubles(values: &[f64], shape: Vec<i64>) -> Array {
+        Array::from_slice(values, shape, DType::new(NpyType::Double)).unwrap()
+    }
+
+    fn longs(values: &[i64], shape: Vec<i64>) -> Array {
+        Array::from_slice(values, shape, DType::new(NpyType::Long)).unwrap()
+    }
+
+    #[test]
+    fn test_savez_roundtrip() {
+        let path = temp_path("roundtrip");
+        let x = doubles(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], vec![2, 3]);
+        let y = longs(&[10, 20, 30], vec![3]);
+        savez(&path, &[("x", &x), ("y", &y)]).unwrap();
+
+        let mut npz = NpzFile::open(&path).unwrap();
+        assert_eq!(npz.files(), vec!["x".to_string(), "y".to_string()]);
+        assert_eq!(npz.len(), 2);
+        assert!(npz.contains("x") && npz.contains("y.npy") && !npz.contains("z"));
+
+        let lx = npz.load("x").unwrap();
+        assert_eq!(lx.shape(), &[2, 3]);
+        assert_eq!(lx.dtype().type_(), NpyType::Double);
+        assert_eq!(unsafe { lx.to_vec::<f64>().unwrap() }, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let ly = npz.load("y.npy").unwrap();
+        assert_eq!(unsafe { ly.to_vec::<i64>().unwrap() }, vec![10, 20, 30]);
+
+        let all = npz.load_all().unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[1].0, "y");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_members_are_npy_files() {
+        // Each member is byte-identical to the output of save_npy
+        let array = longs(&[1, 2, 3, 4], vec![2, 2]);
+        let npz_path = temp_path("members");
+        let npy_path = npz_path.with_extension("npy");
+        savez(&npz_path, &[("a", &array)]).unwrap();
+        save_npy(&npy_path, &array).unwrap();
+        let npy = fs::read(&npy_path).unwrap();
+        let npz = fs::read(&npz_path).unwrap();
+        assert_eq!(&npz[..4], b"PK\x03\x04");
+        assert!(npz.windows(npy.len()).any(|w| w == npy.as_slice()));
+        assert_eq!(unsafe { load_npy(&npy_path).unwrap().to_vec::<i64>().unwrap() }, vec![1, 2, 3, 4]);
+        let _ = fs::remove_file(&npz_path);
+        let _ = fs::remove_file(&npy_path);
+    }
+
+    #[test]
+    fn test_savez_compressed() {
+        let values = vec![0.5; 4096];
+        let array = doubles(&values, vec![64, 64]);
+        let plain = temp_path("plain");
+        let packed = temp_path("packed");
+        savez(&plain, &[("a", &array)]).unwrap();
+        savez_compressed(&packed, &[("a", &array)]).unwrap();
+        assert!(fs::metadata(&packed).unwrap().len() * 10 < fs::metadata(&plain).unwrap().len());
+
+        let loaded = NpzFile::open(&packed).unwrap().load("a").unwrap();
+        assert_eq!(loaded.shape(), &[64, 64]);
+        assert_eq!(unsafe { loaded.to_vec::<f64>().unwrap() }, values);
+        let _ = fs::remove_file(&plain);
+        let _ = fs::remove_file(&packed);
+    }
+
+    #[test]
+    fn test_header_without_loading() {
+        let path = temp_path("header");
+        let array = Array::new_with_order(vec![3, 5], DType::new(NpyType::Float), Order::F).unwrap();
+        savez_compressed(&path, &[("f", &array)]).unwrap();
+        let mut npz = NpzFile::open(&path).unwrap();
+        let header = npz.header("f").unwrap();
+        assert_eq!(header.shape, vec![3, 5]);
+        assert!(header.fortran_order);
+        assert_eq!(header.dtype.type_(), NpyType::Float);
+        let loaded = npz.load("f").unwrap();
+        assert!(loaded.is_f_contiguous());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_strided_member() {
+        let values: Vec<f64> = (0..12).map(|i| i as f64).collect();
+        let base = doubles(&values, vec![3, 4]);
+        let strided = base.view(vec![3, 2], vec![32, 16]).unwrap();
+
+        let path = temp_path("strided");
+        savez(&path, &[("s", &strided)]).unwrap();
+        let buffer = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        // Archives can be read from any seekable reader
+        let mut npz = NpzFile::new(Cursor::new(buffer)).unwrap();
+        let loaded = npz.load("s").unwrap();
+        assert_eq!(unsafe { loaded.to_vec::<f64>().unwrap() }, vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let path = temp_path("errors");
+        let array = longs(&[1, 2], vec![2]);
+        assert!(savez(&path, &[("a", &array), ("a", &array)]).is_err());
+
+        savez(&path, &[("a", &array)]).unwrap();
+        let mut npz = NpzFile::open(&path).unwrap();
+        assert!(matches!(npz.load("missing"), Err(IoError::FileError(_))));
+        let _ = fs::remove_file(&path);
+
+        assert!(NpzFile::new(Cursor::new(b"not a zip file".to_vec())).is_err());
+    }
+
+    #[test]
+    fn test_corrupted_member_detected() {
+        let path = temp_path("corrupt");
+        let array = longs(&[0x1234_5678_9abc, 7], vec![2]);
+        savez(&path, &[("a", &array)]).unwrap();
+        let mut bytes = fs::read(&path).unwrap();
+        let needle = 0x1234_5678_9abci64.to_le_bytes();
+        let pos = bytes.windows(8).position(|w| w == needle).unwrap();
+        bytes[pos] ^= 0xff;
+        let _ = fs::remove_file(&path);
+
+        let mut npz = NpzFile::new(Cursor::new(bytes)).unwrap();
+        // The header is intact, but the checksum no longer matches
+        assert_eq!(npz.header("a").unwrap().shape, vec![2]);
+        assert!(matches!(npz.load("a"), Err(IoError::InvalidFormat)));
+    }
+}
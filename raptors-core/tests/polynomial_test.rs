@@ -0,0 +1,209 @@
+//! Tests for the polynomial module
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::polynomial::*;
+    use raptors_core::types::{Complex128, DType, NpyType};
+    use raptors_core::Array;
+
+    const BASES: [Basis; 6] = [
+        Basis::Power,
+        Basis::Chebyshev,
+        Basis::Legendre,
+        Basis::Hermite,
+        Basis::HermiteE,
+        Basis::Laguerre,
+    ];
+
+    fn array(values: &[f64]) -> Array {
+        Array::from_slice(values, vec![values.len() as i64], DType::new(NpyType::Double)).unwrap()
+    }
+
+    fn doubles(array: &Array) -> Vec<f64> {
+        assert_eq!(array.dtype().type_(), NpyType::Double);
+        unsafe { array.to_vec::<f64>().unwrap() }
+    }
+
+    fn complexes(array: &Array) -> Vec<Complex128> {
+        assert_eq!(array.dtype().type_(), NpyType::CDouble);
+        unsafe { array.to_vec::<Complex128>().unwrap() }
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64], tol: f64) {
+        assert_eq!(actual.len(), expected.len(), "{:?} vs {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() <= tol, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_polyval() {
+        let x = Array::from_slice(&[5.0, -1.0, 0.0, 2.0], vec![2, 2], DType::new(NpyType::Double)).unwrap();
+        let y = polyval(&array(&[3.0, 0.0, 1.0]), &x).unwrap();
+        assert_eq!(y.shape(), &[2, 2]);
+        assert_eq!(doubles(&y), vec![76.0, 4.0, 1.0, 13.0]);
+
+        let ints = Array::from_slice(&[1i64, 2, 3], vec![3], DType::new(NpyType::Long)).unwrap();
+        assert_eq!(doubles(&polyval(&array(&[1.0, 1.0]), &ints).unwrap()), vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_polyfit() {
+        let x = array(&[-2.0, -1.0, 0.0, 1.0, 2.0, 3.0]);
+        let y: Vec<f64> = [-2.0f64, -1.0, 0.0, 1.0, 2.0, 3.0].iter().map(|v| 2.0 * v * v - 3.0 * v + 1.0).collect();
+        let p = polyfit(&x, &array(&y), 2).unwrap();
+        assert_close(&doubles(&p), &[2.0, -3.0, 1.0], 1e-10);
+
+        // Two right-hand sides fitted at once
+        let y2: Vec<f64> = y.iter().flat_map(|&v| [v, 2.0 * v]).collect();
+        let y2 = Array::from_slice(&y2, vec![6, 2], DType::new(NpyType::Double)).unwrap();
+        let p2 = polyfit(&x, &y2, 2).unwrap();
+        assert_eq!(p2.shape(), &[3, 2]);
+        assert_close(&doubles(&p2), &[2.0, 4.0, -3.0, -6.0, 1.0, 2.0], 1e-10);
+
+        assert!(matches!(polyfit(&x, &array(&[1.0]), 1), Err(PolynomialError::ShapeMismatch)));
+    }
+
+    #[test]
+    fn test_polyder_polyint() {
+        let p = array(&[1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(doubles(&polyder(&p, 1).unwrap()), vec![3.0, 2.0, 1.0]);
+        assert_eq!(doubles(&polyder(&p, 2).unwrap()), vec![6.0, 2.0]);
+        assert_eq!(polyder(&array(&[5.0]), 1).unwrap().size(), 0);
+
+        let q = array(&[1.0, 1.0, 1.0]);
+        assert_close(&doubles(&polyint(&q, 1, None).unwrap()), &[1.0 / 3.0, 0.5, 1.0, 0.0], 1e-15);
+        assert_close(&doubles(&polyint(&q, 2, Some(&[3.0])).unwrap()), &[1.0 / 12.0, 1.0 / 6.0, 0.5, 3.0, 3.0], 1e-15);
+        let back = polyder(&polyint(&q, 2, Some(&[1.0, 2.0])).unwrap(), 2).unwrap();
+        assert_close(&doubles(&back), &[1.0, 1.0, 1.0], 1e-15);
+    }
+
+    #[test]
+    fn test_roots() {
+        let mut real = doubles(&roots(&array(&[1.0, -6.0, 11.0, -6.0])).unwrap());
+        real.sort_by(f64::total_cmp);
+        assert_close(&real, &[1.0, 2.0, 3.0], 1e-10);
+
+        let complex = complexes(&roots(&array(&[1.0, 0.0, 1.0])).unwrap());
+        assert_eq!(complex.len(), 2);
+        assert!(complex.iter().all(|z| z.re.abs() < 1e-12 && (z.im.abs() - 1.0).abs() < 1e-12));
+
+        // Leading zeros are dropped and trailing zeros become zero roots
+        let with_zeros = doubles(&roots(&array(&[0.0, 1.0, -1.0, 0.0, 0.0])).unwrap());
+        assert_close(&with_zeros, &[1.0, 0.0, 0.0], 1e-12);
+        assert_eq!(roots(&array(&[0.0, 0.0])).unwrap().size(), 0);
+        assert_eq!(roots(&array(&[4.0])).unwrap().size(), 0);
+    }
+
+    #[test]
+    fn test_basis_values() {
+        let x: f64 = 0.3;
+        let expected = [
+            (Basis::Power, x * x),
+            (Basis::Chebyshev, 2.0 * x * x - 1.0),
+            (Basis::Legendre, (3.0 * x * x - 1.0) / 2.0),
+            (Basis::Hermite, 4.0 * x * x - 2.0),
+            (Basis::HermiteE, x * x - 1.0),
+            (Basis::Laguerre, (x * x - 4.0 * x + 2.0) / 2.0),
+        ];
+        for (basis, value) in expected {
+            assert!((basis.val(&[0.0, 0.0, 1.0], x) - value).abs() < 1e-15, "{:?}", basis);
+        }
+        // T3(0.5) = -1
+        assert!((Basis::Chebyshev.val(&[0.0, 0.0, 0.0, 1.0], 0.5) + 1.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_vander_matches_val() {
+        let x = [-0.7, 0.1, 0.9];
+        for basis in BASES {
+            let v = basis.vander(&x, 4);
+            for (i, &xi) in x.iter().enumerate() {
+                for j in 0..5 {
+                    let mut c = [0.0; 5];
+                    c[j] = 1.0;
+                    assert!((v[i * 5 + j] - basis.val(&c, xi)).abs() < 1e-12, "{:?}", basis);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_der_int() {
+        let c = [1.0, -2.0, 0.5, 3.0, 0.25];
+        assert_eq!(Basis::Chebyshev.der(&[0.0, 0.0, 0.0, 1.0], 1, 1.0), vec![3.0, 0.0, 6.0]);
+        for basis in BASES {
+            // Derivative agrees with a central difference
+            let d = basis.der(&c, 1, 1.0);
+            let (x, h) = (0.4, 1e-6);
+            let numeric = (basis.val(&c, x + h) - basis.val(&c, x - h)) / (2.0 * h);
+            assert!((basis.val(&d, x) - numeric).abs() < 1e-6, "{:?}", basis);
+
+            // Integration inverts differentiation and honours k at lbnd
+            let i = basis.int(&c, 2, &[1.5, -0.5], 0.7, 1.0);
+            assert_close(&basis.der(&i, 2, 1.0), &c, 1e-12);
+            assert!((basis.val(&i, 0.7) + 0.5).abs() < 1e-12, "{:?}", basis);
+            assert!((basis.val(&basis.der(&i, 1, 1.0), 0.7) - 1.5).abs() < 1e-12, "{:?}", basis);
+        }
+        assert_eq!(Basis::Legendre.der(&[2.0], 1, 1.0), vec![0.0]);
+    }
+
+    #[test]
+    fn test_basis_roots() {
+        let r = Basis::Legendre.roots(&[0.0, 0.0, 1.0]).unwrap();
+        assert_close(&[r[0].re, r[1].re], &[-1.0 / 3f64.sqrt(), 1.0 / 3f64.sqrt()], 1e-14);
+
+        for basis in BASES {
+            let c = [0.5, -1.0, 0.3, 0.2, 0.0];
+            for z in basis.roots(&c).unwrap() {
+                // Real roots evaluate to zero; complex roots come in conjugate pairs
+                if z.im == 0.0 {
+                    assert!(basis.val(&c, z.re).abs() < 1e-9, "{:?}", basis);
+                }
+            }
+            assert_eq!(basis.roots(&c).unwrap().len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_series_fit_and_eval() {
+        let xs: Vec<f64> = (0..21).map(|i| i as f64 * 0.5).collect();
+        let ys: Vec<f64> = xs.iter().map(|x| 1.0 + 2.0 * x - 0.1 * x * x * x).collect();
+        for basis in BASES {
+            let series = Series::fit(basis, &array(&xs), &array(&ys), 3, None).unwrap();
+            assert_eq!(series.domain(), [0.0, 10.0]);
+            assert_eq!(series.window(), basis.default_domain());
+            assert_eq!(series.degree(), 3);
+            let fitted = doubles(&series.eval(&array(&xs)).unwrap());
+            assert_close(&fitted, &ys, 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_series_domain_mapping() {
+        // T1 over [0, 4] is the line x/2 - 1
+        let series = Series::new(Basis::Chebyshev, &[0.0, 1.0]).with_domain([0.0, 4.0]).unwrap();
+        assert_eq!(series.mapparms(), (-1.0, 0.5));
+        assert_eq!(doubles(&series.eval(&array(&[0.0, 2.0, 4.0])).unwrap()), vec![-1.0, 0.0, 1.0]);
+
+        let roots = complexes(&series.roots().unwrap());
+        assert_eq!(roots.len(), 1);
+        assert!((roots[0].re - 2.0).abs() < 1e-14 && roots[0].im == 0.0);
+
+        // Derivative and integral are taken with respect to the domain variable
+        let d = series.deriv(1);
+        assert!((d.eval_scalar(1.0) - 0.5).abs() < 1e-15);
+        let i = series.integ(1, &[2.0], Some(1.0));
+        assert!((i.eval_scalar(1.0) - 2.0).abs() < 1e-14);
+        assert!((i.deriv(1).eval_scalar(3.0) - series.eval_scalar(3.0)).abs() < 1e-14);
+
+        assert!(matches!(series.with_domain([1.0, 1.0]), Err(PolynomialError::InvalidDomain)));
+    }
+
+    #[test]
+    fn test_series_trim() {
+        let series = Series::new(Basis::Hermite, &[1.0, 2.0, 0.0, 0.0]);
+        assert_eq!(series.trim().coef(), &[1.0, 2.0]);
+        assert_eq!(Series::new(Basis::Power, &[]).coef(), &[0.0]);
+    }
+}
@@ -0,0 +1,288 @@
+//! Tests for the random module
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::random::*;
+    use raptors_core::types::{DType, NpyType};
+    use raptors_core::Array;
+
+    fn doubles(array: &Array) -> Vec<f64> {
+        assert_eq!(array.dtype().type_(), NpyType::Double);
+        unsafe { array.to_vec::<f64>().unwrap() }
+    }
+
+    fn longs(array: &Array) -> Vec<i64> {
+        assert_eq!(array.dtype().type_(), NpyType::Long);
+        unsafe { array.to_vec::<i64>().unwrap() }
+    }
+
+    fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn test_random_matches_numpy() {
+        // numpy.random.default_rng(seed).random(3)
+        let mut rng = default_rng(42);
+        assert_eq!(
+            doubles(&rng.random(vec![3]).unwrap()),
+            vec![0.7739560485559633, 0.4388784397520523, 0.8585979199113825]
+        );
+        let mut rng = default_rng(0);
+        assert_eq!(
+            doubles(&rng.random(vec![3]).unwrap()),
+            vec![0.6369616873214543, 0.2697867137638703, 0.04097352393619469]
+        );
+    }
+
+    #[test]
+    fn test_integers_match_numpy() {
+        // numpy.random.default_rng(42).integers(0, 10, 5)
+        let mut rng = default_rng(42);
+        assert_eq!(longs(&rng.integers(0, 10, vec![5], false).unwrap()), vec![0, 7, 6, 4, 4]);
+        let mut rng = default_rng(0);
+        assert_eq!(longs(&rng.integers(0, 100, vec![5], false).unwrap()), vec![85, 63, 51, 26, 30]);
+
+        let values = longs(&rng.integers(-3, 3, vec![1000], true).unwrap());
+        assert!(values.iter().all(|v| (-3..=3).contains(v)));
+        assert!(values.contains(&3) && values.contains(&-3));
+        let wide = longs(&rng.integers(i64::MIN, i64::MAX, vec![100], true).unwrap());
+        assert!(wide.iter().any(|&v| v < 0) && wide.iter().any(|&v| v > 0));
+
+        assert!(matches!(rng.integers(5, 5, vec![1], false), Err(RandomError::InvalidParameter(_))));
+        assert_eq!(longs(&rng.integers(5, 5, vec![2], true).unwrap()), vec![5, 5]);
+    }
+
+    #[test]
+    fn test_standard_normal_matches_numpy() {
+        // numpy.random.default_rng(42).standard_normal(3)
+        let mut rng = default_rng(42);
+        let values = doubles(&rng.standard_normal(vec![3]).unwrap());
+        let expected = [0.30471707975443135, -1.0399841062404955, 0.7504511958064572];
+        for (a, e) in values.iter().zip(&expected) {
+            assert!((a - e).abs() < 1e-12, "{} != {}", a, e);
+        }
+
+        let values = doubles(&rng.normal(2.0, 3.0, vec![20000]).unwrap());
+        let m = mean(&values);
+        let var = values.iter().map(|v| (v - m) * (v - m)).sum::<f64>() / values.len() as f64;
+        assert!((m - 2.0).abs() < 0.1);
+        assert!((var.sqrt() - 3.0).abs() < 0.1);
+        assert!(rng.normal(0.0, -1.0, vec![1]).is_err());
+    }
+
+    #[test]
+    fn test_seed_sequence_spawn() {
+        let mut root = SeedSequence::new(42);
+        let children = root.spawn(2);
+        assert_eq!(children[0].spawn_key(), &[0]);
+        assert_eq!(children[1].spawn_key(), &[1]);
+        assert_eq!(root.spawn(1)[0].spawn_key(), &[2]);
+        assert_eq!(root.n_children_spawned(), 3);
+        assert_ne!(children[0].generate_state(4), children[1].generate_state(4));
+        assert_ne!(children[0].generate_state(4), SeedSequence::new(42).generate_state(4));
+
+        // Spawning is deterministic
+        let mut again = SeedSequence::new(42);
+        assert_eq!(again.spawn(2), children);
+
+        // 64-bit words combine pairs of 32-bit words little-endian
+        let words = root.generate_state(4);
+        let wide = root.generate_state_u64(2);
+        assert_eq!(wide[0], words[0] as u64 | (words[1] as u64) << 32);
+
+        let mut rng = default_rng(7);
+        let mut streams = rng.spawn(2).unwrap();
+        let a = doubles(&streams[0].random(vec![4]).unwrap());
+        let b = doubles(&streams[1].random(vec![4]).unwrap());
+        assert_ne!(a, b);
+        assert!(matches!(Generator::new(Pcg64::new(7)).spawn(1), Err(RandomError::NoSeedSequence)));
+    }
+
+    #[test]
+    fn test_pcg64_advance() {
+        let mut stepped = Pcg64::new(123);
+        for _ in 0..1000 {
+            stepped.next_u64();
+        }
+        let mut advanced = Pcg64::new(123);
+        advanced.advance(1000);
+        assert_eq!(stepped.state(), advanced.state());
+        assert_eq!(stepped.next_u64(), advanced.next_u64());
+    }
+
+    #[test]
+    fn test_philox() {
+        // Random123 known-answer test for Philox4x64-10 with zero counter and key
+        assert_eq!(
+            philox4x64([0; 4], [0; 2]),
+            [0x16554d9eca36314c, 0xdb20fe9d672d0fdc, 0xd7e772cee186176b, 0x7e68b68aec7ba23b]
+        );
+
+        // Draws walk through the block for counter 1, then counter 2
+        let mut bg = Philox::with_key([0, 0], [0; 4]);
+        let first = philox4x64([1, 0, 0, 0], [0, 0]);
+        let second = philox4x64([2, 0, 0, 0], [0, 0]);
+        let draws: Vec<u64> = (0..5).map(|_| bg.next_u64()).collect();
+        assert_eq!(&draws[..4], &first);
+        assert_eq!(draws[4], second[0]);
+
+        let mut rng: Generator<Philox> = Generator::from_seed_sequence(SeedSequence::new(42));
+        let values = doubles(&rng.random(vec![1000]).unwrap());
+        assert!(values.iter().all(|v| (0.0..1.0).contains(v)));
+        assert!((mean(&values) - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_continuous_distributions() {
+        let mut rng = default_rng(1);
+        let n = vec![20000];
+
+        let uniform = doubles(&rng.uniform(-2.0, 6.0, n.clone()).unwrap());
+        assert!(uniform.iter().all(|v| (-2.0..6.0).contains(v)));
+        assert!((mean(&uniform) - 2.0).abs() < 0.1);
+
+        let exp = doubles(&rng.exponential(2.0, n.clone()).unwrap());
+        assert!(exp.iter().all(|&v| v >= 0.0));
+        assert!((mean(&exp) - 2.0).abs() < 0.1);
+
+        for k in [0.5, 1.0, 3.0] {
+            let gamma = doubles(&rng.gamma(k, 2.0, n.clone()).unwrap());
+            assert!((mean(&gamma) - 2.0 * k).abs() < 0.1 * (1.0 + k), "gamma({})", k);
+        }
+        assert_eq!(doubles(&rng.standard_gamma(0.0, vec![2]).unwrap()), vec![0.0, 0.0]);
+
+        for (a, b) in [(0.5, 0.5), (2.0, 5.0)] {
+            let beta = doubles(&rng.beta(a, b, n.clone()).unwrap());
+            assert!(beta.iter().all(|v| (0.0..=1.0).contains(v)));
+            assert!((mean(&beta) - a / (a + b)).abs() < 0.02);
+        }
+        assert!(rng.beta(0.0, 1.0, vec![1]).is_err());
+    }
+
+    #[test]
+    fn test_discrete_distributions() {
+        let mut rng = default_rng(2);
+        let n = vec![20000];
+
+        for lam in [0.0, 3.5, 50.0] {
+            let values = longs(&rng.poisson(lam, n.clone()).unwrap());
+            let m = values.iter().sum::<i64>() as f64 / values.len() as f64;
+            assert!((m - lam).abs() < 0.05 * (1.0 + lam), "poisson({})", lam);
+        }
+        assert!(rng.poisson(-1.0, vec![1]).is_err());
+
+        // Inversion (n * p <= 30), BTPE, and p > 0.5 through the complement
+        for (trials, p) in [(10, 0.3), (1000, 0.4), (100, 0.9)] {
+            let values = longs(&rng.binomial(trials, p, n.clone()).unwrap());
+            assert!(values.iter().all(|&v| (0..=trials).contains(&v)));
+            let m = values.iter().sum::<i64>() as f64 / values.len() as f64;
+            let expected = trials as f64 * p;
+            assert!((m - expected).abs() < 0.02 * expected + 0.05, "binomial({}, {})", trials, p);
+        }
+        assert!(rng.binomial(10, 1.5, vec![1]).is_err());
+
+        let counts = rng.multinomial(20, &[0.2, 0.3, 0.5], vec![4]).unwrap();
+        assert_eq!(counts.shape(), &[4, 3]);
+        for row in longs(&counts).chunks(3) {
+            assert_eq!(row.iter().sum::<i64>(), 20);
+        }
+        assert!(rng.multinomial(5, &[0.8, 0.8, 0.1], vec![1]).is_err());
+    }
+
+    #[test]
+    fn test_choice() {
+        let mut rng = default_rng(3);
+        let picks = longs(&rng.choice_n(10, vec![100], true, None, true).unwrap());
+        assert!(picks.iter().all(|v| (0..10).contains(v)));
+
+        let mut unique = longs(&rng.choice_n(50, vec![50], false, None, true).unwrap());
+        unique.sort();
+        assert_eq!(unique, (0..50).collect::<Vec<_>>());
+
+        // Tail-shuffle path for large populations
+        let mut large = longs(&rng.choice_n(20000, vec![5000], false, None, true).unwrap());
+        large.sort();
+        large.dedup();
+        assert_eq!(large.len(), 5000);
+
+        let p = [0.0, 0.0, 1.0, 0.0];
+        assert_eq!(longs(&rng.choice_n(4, vec![3], true, Some(&p), true).unwrap()), vec![2, 2, 2]);
+        let mut weighted = longs(&rng.choice_n(4, vec![2], false, Some(&[0.5, 0.0, 0.25, 0.25]), true).unwrap());
+        weighted.sort();
+        assert!(weighted.windows(2).all(|w| w[0] != w[1]) && !weighted.contains(&1));
+
+        assert!(rng.choice_n(3, vec![4], false, None, true).is_err());
+        assert!(rng.choice_n(2, vec![1], true, Some(&[0.5, 0.6]), true).is_err());
+        assert!(rng.choice_n(0, vec![1], true, None, true).is_err());
+
+        // Rows of a 2-D population are chosen whole
+        let data: Vec<i32> = (0..6).collect();
+        let a = Array::from_slice(&data, vec![3, 2], DType::new(NpyType::Int)).unwrap();
+        let rows = rng.choice(&a, vec![4], true, None, true).unwrap();
+        assert_eq!(rows.shape(), &[4, 2]);
+        for pair in unsafe { rows.to_vec::<i32>().unwrap() }.chunks(2) {
+            assert_eq!(pair[1], pair[0] + 1);
+            assert_eq!(pair[0] % 2, 0);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_and_permutation() {
+        let mut rng = default_rng(4);
+        let perm = longs(&rng.permutation_n(100).unwrap());
+        assert_ne!(perm, (0..100).collect::<Vec<_>>());
+        let mut sorted = perm.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+
+        let data: Vec<f64> = (0..12).map(|i| i as f64).collect();
+        let mut grid = Array::from_slice(&data, vec![4, 3], DType::new(NpyType::Double)).unwrap();
+        rng.shuffle(&mut grid).unwrap();
+        let shuffled = doubles(&grid);
+        for row in shuffled.chunks(3) {
+            assert_eq!(row[1], row[0] + 1.0);
+            assert_eq!(row[2], row[0] + 2.0);
+        }
+
+        let original = Array::from_slice(&data, vec![12], DType::new(NpyType::Double)).unwrap();
+        let permuted = rng.permutation(&original).unwrap();
+        assert_eq!(doubles(&original), data);
+        let mut values = doubles(&permuted);
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(values, data);
+
+        let mut scalar = Array::new(vec![], DType::new(NpyType::Double)).unwrap();
+        assert!(rng.shuffle(&mut scalar).is_err());
+    }
+
+    #[test]
+    fn test_parallel_fill() {
+        let shape = vec![3 * PARALLEL_CHUNK as i64 + 17];
+        let a = default_rng(5).random_parallel(shape.clone()).unwrap();
+        let b = default_rng(5).random_parallel(shape.clone()).unwrap();
+        assert_eq!(doubles(&a), doubles(&b));
+        assert!((mean(&doubles(&a)) - 0.5).abs() < 0.01);
+
+        // Chunks come from distinct streams
+        let values = doubles(&a);
+        assert_ne!(values[..8], values[PARALLEL_CHUNK..PARALLEL_CHUNK + 8]);
+
+        let mut rng = default_rng(6);
+        let normal = doubles(&rng.normal_parallel(1.0, 2.0, shape.clone()).unwrap());
+        assert!((mean(&normal) - 1.0).abs() < 0.05);
+        let ints = longs(&rng.integers_parallel(0, 6, vec![1000]).unwrap());
+        assert!(ints.iter().all(|v| (0..6).contains(v)));
+        let exp = doubles(&rng.exponential_parallel(1.0, vec![1000]).unwrap());
+        assert!(exp.iter().all(|&v| v >= 0.0));
+
+        let mut out = vec![0u64; 10];
+        rng.fill_parallel(&mut out, |g, chunk| {
+            for v in chunk.iter_mut() {
+                *v = g.bit_generator_mut().next_u64();
+            }
+        })
+        .unwrap();
+        assert!(out.iter().all(|&v| v != 0));
+    }
+}
@@ -0,0 +1,148 @@
+//! Tests for raw binary and flat text I/O (tofile, fromfile, frombuffer, ...)
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::array::{Array, ArrayError, ArrayFlags};
+    use raptors_core::io::{frombuffer, fromfile, fromiter, fromstring, IoError};
+    use raptors_core::types::{DType, NpyType};
+    use std::fs;
+    use std::sync::Arc;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("raptors_raw_io_test_{}_{}.bin", std::process::id(), name))
+    }
+
+    fn doubles(values: &[f64], shape: Vec<i64>) -> Array {
+        Array::from_slice(values, shape, DType::new(NpyType::Double)).unwrap()
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let path = temp_path("binary");
+        let array = doubles(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], vec![2, 3]);
+        array.tofile(&path, "", "").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), 48);
+
+        let all = fromfile(&path, DType::new(NpyType::Double), None, "", 0).unwrap();
+        assert_eq!(all.shape(), &[6]);
+        assert_eq!(unsafe { all.to_vec::<f64>().unwrap() }, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        // Skip two elements, then read at most two
+        let part = fromfile(&path, DType::new(NpyType::Double), Some(2), "", 16).unwrap();
+        assert_eq!(unsafe { part.to_vec::<f64>().unwrap() }, vec![3.0, 4.0]);
+        // Asking for more than the file holds reads what is there
+        let rest = fromfile(&path, DType::new(NpyType::Double), Some(10), "", 32).unwrap();
+        assert_eq!(unsafe { rest.to_vec::<f64>().unwrap() }, vec![5.0, 6.0]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_text_roundtrip() {
+        let path = temp_path("text");
+        let array = doubles(&[1.0, 2.5, -3.25], vec![3]);
+        array.tofile(&path, ", ", "%.2f").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1.00, 2.50, -3.25");
+        let loaded = fromfile(&path, DType::new(NpyType::Double), None, ",", 0).unwrap();
+        assert_eq!(unsafe { loaded.to_vec::<f64>().unwrap() }, vec![1.0, 2.5, -3.25]);
+
+        let ints = Array::from_slice(&[1i32, 22, 333], vec![3], DType::new(NpyType::Int)).unwrap();
+        let mut out = Vec::new();
+        ints.tofile_writer(&mut out, " ", "").unwrap();
+        assert_eq!(out, b"1 22 333");
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_strided_tofile() {
+        let base = doubles(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![2, 3]);
+        // Transposed view: written in logical order
+        let transposed = base.view(vec![3, 2], vec![8, 24]).unwrap();
+        let mut out = Vec::new();
+        transposed.tofile_writer(&mut out, ",", "%g").unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0,3,1,4,2,5");
+    }
+
+    #[test]
+    fn test_frombuffer_zero_copy() {
+        let values: Vec<u8> = [7i32, 8, 9].iter().flat_map(|v| v.to_ne_bytes()).collect();
+        let buffer = Arc::new(values);
+        let array = frombuffer(buffer.clone(), DType::new(NpyType::Int), None, 0).unwrap();
+        assert_eq!(array.data_ptr(), buffer.as_ptr());
+        assert!(!array.is_writeable());
+        assert!(array.owner().is_some());
+
+        // The array keeps the buffer alive, and so do views of it
+        drop(buffer);
+        let tail = array.view(vec![2], vec![4]).unwrap();
+        drop(array);
+        assert!(!tail.is_writeable());
+        assert_eq!(unsafe { tail.to_vec::<i32>().unwrap() }, vec![7, 8]);
+    }
+
+    #[test]
+    fn test_frombuffer_stays_read_only() {
+        let buffer = Arc::new(vec![1u8, 2, 3, 4]);
+        let mut array = frombuffer(buffer, DType::new(NpyType::UByte), None, 0).unwrap();
+        assert!(matches!(array.setflags(ArrayFlags::WRITEABLE, true), Err(ArrayError::InvalidValue(_))));
+        array.update_flags();
+        assert!(!array.is_writeable());
+
+        // Views share the buffer and stay read-only too; copies do not
+        let mut view = array.view(vec![2], vec![1]).unwrap();
+        assert!(view.setflags(ArrayFlags::WRITEABLE, true).is_err());
+        let mut copy = array.copy();
+        assert!(copy.is_writeable());
+        copy.setflags(ArrayFlags::WRITEABLE, false).unwrap();
+        copy.setflags(ArrayFlags::WRITEABLE, true).unwrap();
+    }
+
+    #[test]
+    fn test_frombuffer_count_and_offset() {
+        let bytes: Arc<[u8]> = Arc::from(&[0u8, 1, 2, 3, 4, 5, 6, 7, 8][..]);
+        let array = frombuffer(bytes.clone(), DType::new(NpyType::UShort), Some(2), 1).unwrap();
+        assert_eq!(array.shape(), &[2]);
+        // Byte offset 1 is not aligned for 16-bit elements
+        assert!(!array.flags().contains(ArrayFlags::ALIGNED));
+        let values: Vec<u16> = unsafe { std::slice::from_raw_parts(array.data_ptr(), 4) }
+            .chunks(2)
+            .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(values, vec![u16::from_ne_bytes([1, 2]), u16::from_ne_bytes([3, 4])]);
+
+        let aligned = frombuffer(bytes.clone(), DType::new(NpyType::UByte), None, 4).unwrap();
+        assert_eq!(unsafe { aligned.to_vec::<u8>().unwrap() }, vec![4, 5, 6, 7, 8]);
+
+        let ushort = || DType::new(NpyType::UShort);
+        assert!(frombuffer(bytes.clone(), ushort(), None, 0).is_err());
+        assert!(frombuffer(bytes.clone(), ushort(), Some(5), 0).is_err());
+        assert!(frombuffer(bytes, ushort(), None, 10).is_err());
+    }
+
+    #[test]
+    fn test_fromstring() {
+        let array = fromstring("1 2\n 3\t4", DType::new(NpyType::Long), None, " ").unwrap();
+        assert_eq!(unsafe { array.to_vec::<i64>().unwrap() }, vec![1, 2, 3, 4]);
+        let array = fromstring("1.5, 2.5, 3.5,", DType::new(NpyType::Float), Some(2), ",").unwrap();
+        assert_eq!(unsafe { array.to_vec::<f32>().unwrap() }, vec![1.5, 2.5]);
+
+        assert!(fromstring("1,2", DType::new(NpyType::Long), Some(3), ",").is_err());
+        assert!(fromstring("1,x", DType::new(NpyType::Long), None, ",").is_err());
+        assert!(fromstring("1 2", DType::new(NpyType::Long), None, "").is_err());
+    }
+
+    #[test]
+    fn test_fromiter() {
+        let array = fromiter((0..5).map(|i| i as f64 * 0.5), DType::new(NpyType::Double), None).unwrap();
+        assert_eq!(unsafe { array.to_vec::<f64>().unwrap() }, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        let array = fromiter(1i64.., DType::new(NpyType::Long), Some(3)).unwrap();
+        assert_eq!(unsafe { array.to_vec::<i64>().unwrap() }, vec![1, 2, 3]);
+        let empty = fromiter(std::iter::empty::<i32>(), DType::new(NpyType::Int), None).unwrap();
+        assert_eq!(empty.shape(), &[0]);
+
+        assert!(fromiter(0..2i64, DType::new(NpyType::Long), Some(3)).is_err());
+        assert!(matches!(
+            fromiter(0..2i32, DType::new(NpyType::Double), None),
+            Err(IoError::ArrayError(ArrayError::TypeMismatch))
+        ));
+    }
+}
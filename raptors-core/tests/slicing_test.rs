@@ -38,6 +38,20 @@ mod tests {
         assert_eq!(stop, 9);  // 10 - 1
     }
 
+    #[test]
+    fn test_normalize_slice_negative_step_omitted_stop() {
+        // a[::-1] and a[3::-2] run through index 0 whatever the length
+        for (len, reversed, every_other) in [(0, 0, 0), (1, 1, 1), (5, 5, 2)] {
+            let (start, stop, step) = normalize_slice(&Slice::new(None, None, Some(-1)), len).unwrap();
+            assert_eq!((stop, slice_length(start, stop, step)), (-1, reversed));
+            let (start, stop, step) = normalize_slice(&Slice::new(Some(3), None, Some(-2)), len).unwrap();
+            assert_eq!((stop, slice_length(start, stop, step)), (-1, every_other));
+        }
+        // An explicit negative stop still counts from the end
+        let (start, stop, step) = normalize_slice(&Slice::new(None, Some(-3), Some(-1)), 5).unwrap();
+        assert_eq!((start, stop, slice_length(start, stop, step)), (4, 2, 2));
+    }
+
     #[test]
     fn test_slice_length() {
         assert_eq!(slice_length(0, 10, 1), 10);
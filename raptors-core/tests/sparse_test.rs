@@ -0,0 +1,229 @@
+//! Tests for sparse matrices
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::indexing::Slice;
+    use raptors_core::sparse::{load_npz, save_npz, CooMatrix, CscMatrix, CsrMatrix, SparseError, SparseMatrix};
+    use raptors_core::types::{DType, NpyType};
+    use raptors_core::Array;
+    use std::fs;
+
+    // [[1, 0, 2],
+    //  [0, 0, 3],
+    //  [4, 5, 0]]
+    fn sample_dense() -> Array {
+        let data = [1.0, 0.0, 2.0, 0.0, 0.0, 3.0, 4.0, 5.0, 0.0];
+        Array::from_slice(&data, vec![3, 3], DType::new(NpyType::Double)).unwrap()
+    }
+
+    fn values(array: &Array) -> Vec<f64> {
+        unsafe { array.to_vec::<f64>().unwrap() }
+    }
+
+    #[test]
+    fn test_csr_from_dense() {
+        let csr = CsrMatrix::from_dense(&sample_dense()).unwrap();
+        assert_eq!(csr.shape(), (3, 3));
+        assert_eq!(csr.nnz(), 5);
+        assert_eq!(csr.indptr(), &[0, 2, 3, 5]);
+        assert_eq!(csr.indices(), &[0, 2, 2, 0, 1]);
+        assert_eq!(csr.data(), &[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(csr.get(2, 1).unwrap(), 5.0);
+        assert_eq!(csr.get(1, 1).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_from_dense_integer_dtype() {
+        let data = [0i32, 7, 0, -1];
+        let dense = Array::from_slice(&data, vec![2, 2], DType::new(NpyType::Int)).unwrap();
+        let csc = CscMatrix::from_dense(&dense).unwrap();
+        assert_eq!(csc.indptr(), &[0, 0, 2]);
+        assert_eq!(csc.indices(), &[0, 1]);
+        assert_eq!(csc.data(), &[7.0, -1.0]);
+    }
+
+    #[test]
+    fn test_from_dense_requires_2d() {
+        let dense = Array::from_slice(&[1.0, 2.0], vec![2], DType::new(NpyType::Double)).unwrap();
+        assert!(matches!(CsrMatrix::from_dense(&dense), Err(SparseError::InvalidDimension)));
+    }
+
+    #[test]
+    fn test_triplets_sum_duplicates() {
+        let csr = CsrMatrix::from_triplets((2, 3), &[1, 0, 1], &[2, 1, 2], &[1.0, 4.0, 2.5]).unwrap();
+        assert_eq!(csr.nnz(), 2);
+        assert_eq!(csr.get(1, 2).unwrap(), 3.5);
+        assert!(csr.has_canonical_format());
+
+        let coo = CooMatrix::new((2, 3), vec![1, 1], vec![2, 2], vec![1.0, 1.0]).unwrap();
+        assert_eq!(values(&coo.to_dense().unwrap()), vec![0.0, 0.0, 0.0, 0.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_triplets_out_of_bounds() {
+        let result = CooMatrix::new((2, 2), vec![2], vec![0], vec![1.0]);
+        assert!(matches!(result, Err(SparseError::IndexOutOfBounds)));
+    }
+
+    #[test]
+    fn test_invalid_indptr() {
+        let result = CsrMatrix::new((2, 2), vec![0, 2, 1], vec![0], vec![1.0]);
+        assert!(matches!(result, Err(SparseError::InvalidStructure(_))));
+    }
+
+    #[test]
+    fn test_format_conversions_roundtrip() {
+        let dense = sample_dense();
+        let expected = values(&dense);
+        let csr = CsrMatrix::from_dense(&dense).unwrap();
+        let csc = csr.to_csc();
+        let coo = csc.to_coo();
+
+        assert_eq!(csc.indptr(), &[0, 2, 3, 5]);
+        assert_eq!(csc.indices(), &[0, 2, 2, 0, 1]);
+        assert_eq!(values(&csc.to_dense().unwrap()), expected);
+        assert_eq!(values(&coo.to_dense().unwrap()), expected);
+        assert_eq!(coo.to_csr(), csr);
+        assert_eq!(csc.to_csr(), csr);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let csr = CsrMatrix::from_dense(&sample_dense()).unwrap();
+        let t = csr.transpose();
+        assert_eq!(values(&t.to_dense().unwrap()), vec![1.0, 0.0, 4.0, 0.0, 0.0, 5.0, 2.0, 3.0, 0.0]);
+    }
+
+    #[test]
+    fn test_apply_ufunc_preserves_sparsity() {
+        let csr = CsrMatrix::from_dense(&sample_dense()).unwrap().multiply_scalar(-1.0);
+        let abs = csr.apply_ufunc("abs").unwrap();
+        assert_eq!(abs.indices(), csr.indices());
+        assert_eq!(abs.data(), &[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        assert!(matches!(csr.apply_ufunc("exp"), Err(SparseError::NotSparsityPreserving(_))));
+        assert!(csr.map(|x| x + 1.0).is_err());
+    }
+
+    #[test]
+    fn test_elementwise_arithmetic() {
+        let a = CsrMatrix::from_dense(&sample_dense()).unwrap();
+        let b = CsrMatrix::from_triplets((3, 3), &[0, 1, 2], &[0, 1, 1], &[1.0, 2.0, -5.0]).unwrap();
+
+        let sum = a.add(&b).unwrap();
+        assert_eq!(values(&sum.to_dense().unwrap()), vec![2.0, 0.0, 2.0, 0.0, 2.0, 3.0, 4.0, 0.0, 0.0]);
+        // 5 + (-5) cancels and is not stored
+        assert_eq!(sum.nnz(), 5);
+
+        let product = a.multiply(&b).unwrap();
+        assert_eq!(product.nnz(), 2);
+        assert_eq!(product.get(0, 0).unwrap(), 1.0);
+        assert_eq!(product.get(2, 1).unwrap(), -25.0);
+
+        let diff = a.to_csc().subtract(&b.to_csc()).unwrap();
+        assert_eq!(diff.get(2, 1).unwrap(), 10.0);
+
+        let other = CsrMatrix::from_triplets((2, 3), &[], &[], &[]).unwrap();
+        assert!(matches!(a.add(&other), Err(SparseError::ShapeMismatch)));
+    }
+
+    #[test]
+    fn test_matmul_dense() {
+        let csr = CsrMatrix::from_dense(&sample_dense()).unwrap();
+        let v = Array::from_slice(&[1.0, 2.0, 3.0], vec![3], DType::new(NpyType::Double)).unwrap();
+        assert_eq!(values(&csr.matmul_dense(&v).unwrap()), vec![7.0, 9.0, 14.0]);
+        assert_eq!(values(&csr.to_csc().matmul_dense(&v).unwrap()), vec![7.0, 9.0, 14.0]);
+
+        let m = Array::from_slice(&[1.0, 0.0, 0.0, 1.0, 1.0, 1.0], vec![3, 2], DType::new(NpyType::Double)).unwrap();
+        let result = csr.matmul_dense(&m).unwrap();
+        assert_eq!(result.shape(), &[3, 2]);
+        assert_eq!(values(&result), vec![3.0, 2.0, 3.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_matmul_sparse() {
+        let dense = sample_dense();
+        let csr = CsrMatrix::from_dense(&dense).unwrap();
+        let expected = raptors_core::linalg::matmul(&dense, &dense).unwrap();
+
+        let product = csr.matmul(&csr).unwrap();
+        assert!(product.has_canonical_format());
+        assert_eq!(values(&product.to_dense().unwrap()), values(&expected));
+
+        let csc = csr.to_csc();
+        assert_eq!(values(&csc.matmul(&csc).unwrap().to_dense().unwrap()), values(&expected));
+    }
+
+    #[test]
+    fn test_reductions() {
+        let csr = CsrMatrix::from_dense(&sample_dense()).unwrap();
+        assert_eq!(values(&csr.sum(None).unwrap()), vec![15.0]);
+        assert_eq!(values(&csr.sum(Some(0)).unwrap()), vec![5.0, 5.0, 5.0]);
+        assert_eq!(values(&csr.sum(Some(1)).unwrap()), vec![3.0, 3.0, 9.0]);
+        assert_eq!(values(&csr.mean(Some(1)).unwrap()), vec![1.0, 1.0, 3.0]);
+        assert_eq!(values(&csr.max(Some(0)).unwrap()), vec![4.0, 5.0, 3.0]);
+        assert_eq!(values(&csr.min(Some(1)).unwrap()), vec![0.0, 0.0, 0.0]);
+
+        let csc = csr.to_csc();
+        assert_eq!(values(&csc.sum(Some(0)).unwrap()), vec![5.0, 5.0, 5.0]);
+        assert_eq!(values(&csc.sum(Some(1)).unwrap()), vec![3.0, 3.0, 9.0]);
+        assert!(matches!(csc.sum(Some(2)), Err(SparseError::InvalidAxis)));
+
+        let full = CsrMatrix::from_triplets((1, 2), &[0, 0], &[0, 1], &[-1.0, -3.0]).unwrap();
+        assert_eq!(values(&full.max(None).unwrap()), vec![-1.0]);
+    }
+
+    #[test]
+    fn test_slicing() {
+        let csr = CsrMatrix::from_dense(&sample_dense()).unwrap();
+        let rows = csr.slice_rows(&Slice::new(Some(1), None, None)).unwrap();
+        assert_eq!(rows.shape(), (2, 3));
+        assert_eq!(values(&rows.to_dense().unwrap()), vec![0.0, 0.0, 3.0, 4.0, 5.0, 0.0]);
+
+        let cols = csr.slice_cols(&Slice::new(None, None, Some(-1))).unwrap();
+        assert_eq!(values(&cols.to_dense().unwrap()), vec![2.0, 0.0, 1.0, 3.0, 0.0, 0.0, 0.0, 5.0, 4.0]);
+        assert!(cols.has_canonical_format());
+
+        let csc = csr.to_csc();
+        let block = csc.slice(&Slice::range(Some(0), Some(2)), &Slice::range(Some(1), Some(3))).unwrap();
+        assert_eq!(block.shape(), (2, 2));
+        assert_eq!(values(&block.to_dense().unwrap()), vec![0.0, 2.0, 0.0, 3.0]);
+    }
+
+    #[test]
+    fn test_npz_roundtrip() {
+        let csr = CsrMatrix::from_dense(&sample_dense()).unwrap();
+        let matrices = [
+            SparseMatrix::from(csr.clone()),
+            SparseMatrix::from(csr.to_csc()),
+            SparseMatrix::from(csr.to_coo()),
+        ];
+        for (i, matrix) in matrices.iter().enumerate() {
+            for compressed in [false, true] {
+                let path = std::env::temp_dir().join(format!("raptors_sparse_{}_{}.npz", i, compressed));
+                save_npz(&path, matrix, compressed).unwrap();
+                let loaded = load_npz(&path).unwrap();
+                assert_eq!(&loaded, matrix);
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_npz_roundtrip_large_indices() {
+        // Column indices above 2^53 have no exact float64 representation
+        let cols = (1usize << 53) + 3;
+        let csr = CsrMatrix::new((1, cols), vec![0, 2], vec![1, cols - 2], vec![1.0, 2.0]).unwrap();
+        let matrix = SparseMatrix::from(csr);
+        let path = std::env::temp_dir().join("raptors_sparse_large_indices.npz");
+        save_npz(&path, &matrix, false).unwrap();
+        let loaded = load_npz(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded, matrix);
+    }
+
+    #[test]
+    fn test_load_npz_missing_file() {
+        assert!(load_npz("/tmp/raptors_sparse_does_not_exist.npz").is_err());
+    }
+}
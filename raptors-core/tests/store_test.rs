@@ -0,0 +1,198 @@
+//! Tests for the Zarr v2 chunked array store
+
+#[cfg(test)]
+mod tests {
+    use raptors_core::array::{Array, Order};
+    use raptors_core::indexing::Slice;
+    use raptors_core::io::CellValue;
+    use raptors_core::store::{load_zarr, save_zarr, Compressor, StoreError, ZarrArray, ZarrOptions};
+    use raptors_core::types::{DType, NpyType};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scratch(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("raptors_store_test_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        path
+    }
+
+    fn grid(rows: i64, cols: i64) -> Array {
+        let values: Vec<f64> = (0..rows * cols).map(|v| v as f64).collect();
+        Array::from_slice(&values, vec![rows, cols], DType::new(NpyType::Double)).unwrap()
+    }
+
+    fn chunked(chunks: Vec<i64>, compressor: Compressor, order: Order) -> ZarrOptions {
+        ZarrOptions { chunks, compressor, order, ..ZarrOptions::default() }
+    }
+
+    #[test]
+    fn test_round_trip_orders_and_compressors() {
+        let array = grid(5, 7);
+        let expected = unsafe { array.to_vec::<f64>().unwrap() };
+        let compressors = [Compressor::None, Compressor::Zlib { level: 5 }, Compressor::Lz4 { acceleration: 1 }];
+        for (i, &compressor) in compressors.iter().enumerate() {
+            for order in [Order::C, Order::F] {
+                let path = scratch(&format!("round_trip_{}_{:?}", i, order));
+                let store = save_zarr(&path, &array, chunked(vec![2, 3], compressor, order)).unwrap();
+                assert_eq!(store.chunk_grid(), vec![3, 3]);
+                // Edge chunks are stored at full size
+                assert!(path.join("2.2").exists());
+
+                let loaded = load_zarr(&path).unwrap();
+                let _ = fs::remove_dir_all(&path);
+                assert_eq!(loaded.shape(), &[5, 7]);
+                assert_eq!(unsafe { loaded.to_vec::<f64>().unwrap() }, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fortran_order_within_chunks() {
+        let path = scratch("fortran");
+        let array = Array::from_slice(&[1i16, 2, 3, 4], vec![2, 2], DType::new(NpyType::Short)).unwrap();
+        save_zarr(&path, &array, chunked(vec![2, 2], Compressor::None, Order::F)).unwrap();
+        let raw = fs::read(path.join("0.0")).unwrap();
+        let metadata = fs::read_to_string(path.join(".zarray")).unwrap();
+        let _ = fs::remove_dir_all(&path);
+        let stored: Vec<i16> = raw.chunks(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+        assert_eq!(stored, vec![1, 3, 2, 4]);
+        assert!(metadata.contains("\"order\": \"F\""));
+        assert!(metadata.contains("\"dtype\": \"<i2\""));
+    }
+
+    #[test]
+    fn test_partial_write_touches_only_needed_chunks() {
+        let path = scratch("partial_write");
+        let options = ZarrOptions {
+            chunks: vec![4, 4],
+            fill_value: Some(CellValue::Int(-1)),
+            ..ZarrOptions::default()
+        };
+        let store = ZarrArray::create(&path, vec![10, 10], DType::new(NpyType::Int), options).unwrap();
+        assert!(fs::read_to_string(path.join(".zarray")).unwrap().contains("\"fill_value\": -1"));
+
+        // Rows 2..6, columns 5..7 overlap chunks (0, 1) and (1, 1)
+        let patch = Array::from_slice(&[7i32; 8], vec![4, 2], DType::new(NpyType::Int)).unwrap();
+        store.write(&[Slice::range(Some(2), Some(6)), Slice::range(Some(5), Some(7))], &patch).unwrap();
+        let mut written: Vec<String> = fs::read_dir(&path)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name != ".zarray")
+            .collect();
+        written.sort();
+        assert_eq!(written, vec!["0.1", "1.1"]);
+
+        let all = unsafe { ZarrArray::open(&path).unwrap().read(&[]).unwrap().to_vec::<i32>().unwrap() };
+        let _ = fs::remove_dir_all(&path);
+        for (i, &value) in all.iter().enumerate() {
+            let (row, col) = (i / 10, i % 10);
+            let inside = (2..6).contains(&row) && (5..7).contains(&col);
+            assert_eq!(value, if inside { 7 } else { -1 }, "element ({}, {})", row, col);
+        }
+    }
+
+    #[test]
+    fn test_partial_read_skips_other_chunks() {
+        let path = scratch("partial_read");
+        let store = save_zarr(&path, &grid(6, 6), chunked(vec![3, 3], Compressor::default(), Order::C)).unwrap();
+        // A damaged chunk only matters to selections that reach it
+        fs::write(path.join("1.1"), b"garbage").unwrap();
+
+        let top = store.read(&[Slice::range(Some(0), Some(3)), Slice::range(Some(1), Some(5))]).unwrap();
+        assert_eq!(top.shape(), &[3, 4]);
+        assert_eq!(unsafe { top.to_vec::<f64>().unwrap() }[..4], [1.0, 2.0, 3.0, 4.0]);
+        let result = store.read(&[Slice::range(Some(2), Some(4))]);
+        let _ = fs::remove_dir_all(&path);
+        assert!(matches!(result, Err(StoreError::CorruptChunk(_))));
+    }
+
+    #[test]
+    fn test_stepped_and_reversed_slices() {
+        let path = scratch("stepped");
+        let store = save_zarr(&path, &grid(4, 9), chunked(vec![3, 2], Compressor::Lz4 { acceleration: 1 }, Order::C)).unwrap();
+        let picked = store.read(&[Slice::new(None, None, Some(-1)), Slice::new(Some(1), Some(8), Some(3))]).unwrap();
+        assert_eq!(picked.shape(), &[4, 3]);
+        assert_eq!(
+            unsafe { picked.to_vec::<f64>().unwrap() },
+            vec![28.0, 31.0, 34.0, 19.0, 22.0, 25.0, 10.0, 13.0, 16.0, 1.0, 4.0, 7.0]
+        );
+
+        // Write through a stepped selection and read it back whole
+        let zeros = Array::from_slice(&[0.0f64; 5], vec![1, 5], DType::new(NpyType::Double)).unwrap();
+        store.write(&[Slice::range(Some(-1), None), Slice::new(None, None, Some(2))], &zeros).unwrap();
+        let last = store.read(&[Slice::range(Some(3), None)]).unwrap();
+        let _ = fs::remove_dir_all(&path);
+        assert_eq!(
+            unsafe { last.to_vec::<f64>().unwrap() },
+            vec![0.0, 28.0, 0.0, 30.0, 0.0, 32.0, 0.0, 34.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_open_foreign_metadata() {
+        // Layout as written by zarr-python: big-endian data, nested chunk keys
+        let path = scratch("foreign");
+        fs::create_dir_all(path.join("1")).unwrap();
+        fs::write(
+            path.join(".zarray"),
+            r#"{"chunks": [2, 1], "compressor": null, "dimension_separator": "/", "dtype": ">i4",
+                "fill_value": 9, "filters": null, "order": "C", "shape": [5, 1], "zarr_format": 2}"#,
+        )
+        .unwrap();
+        let chunk: Vec<u8> = [10i32, 11].iter().flat_map(|v| v.to_be_bytes()).collect();
+        fs::write(path.join("1").join("0"), chunk).unwrap();
+
+        let store = ZarrArray::open(&path).unwrap();
+        assert_eq!(store.compressor(), Compressor::None);
+        assert_eq!(store.chunk_key(&[1, 0]), "1/0");
+        let values = unsafe { store.read(&[]).unwrap().to_vec::<i32>().unwrap() };
+        assert_eq!(values, vec![9, 9, 10, 11, 9]);
+
+        // Writes keep the stored byte order
+        let one = Array::from_slice(&[-2i32], vec![1, 1], DType::new(NpyType::Int)).unwrap();
+        store.write(&[Slice::range(Some(4), None)], &one).unwrap();
+        let raw = fs::read(path.join("2").join("0")).unwrap();
+        let _ = fs::remove_dir_all(&path);
+        assert_eq!(raw, [(-2i32).to_be_bytes(), 9i32.to_be_bytes()].concat());
+    }
+
+    #[test]
+    fn test_fill_values_in_metadata() {
+        let path = scratch("fill_nan");
+        let options = ZarrOptions { fill_value: Some(CellValue::Float(f64::NAN)), ..ZarrOptions::default() };
+        ZarrArray::create(&path, vec![3], DType::new(NpyType::Float), options).unwrap();
+        assert!(fs::read_to_string(path.join(".zarray")).unwrap().contains("\"fill_value\": \"NaN\""));
+        let values = unsafe { load_zarr(&path).unwrap().to_vec::<f32>().unwrap() };
+        let _ = fs::remove_dir_all(&path);
+        assert!(values.iter().all(|v| v.is_nan()));
+
+        let path = scratch("fill_text");
+        let options = ZarrOptions { fill_value: Some(CellValue::Str("ab".into())), ..ZarrOptions::default() };
+        ZarrArray::create(&path, vec![2], DType::string_with_itemsize(3), options).unwrap();
+        let metadata = fs::read_to_string(path.join(".zarray")).unwrap();
+        let text = load_zarr(&path).unwrap();
+        let _ = fs::remove_dir_all(&path);
+        assert!(metadata.contains("\"fill_value\": \"YWIA\""));
+        let bytes = unsafe { std::slice::from_raw_parts(text.data_ptr(), 6) };
+        assert_eq!(bytes, b"ab\0ab\0");
+    }
+
+    #[test]
+    fn test_errors() {
+        let path = scratch("errors");
+        let store = ZarrArray::create(&path, vec![4, 4], DType::new(NpyType::Double), ZarrOptions::default()).unwrap();
+        assert!(ZarrArray::create(&path, vec![4], DType::new(NpyType::Double), ZarrOptions::default()).is_err());
+        let slices = [Slice::full(), Slice::full(), Slice::full()];
+        assert!(matches!(store.read(&slices), Err(StoreError::InvalidSelection(_))));
+        assert!(matches!(store.write(&[], &grid(2, 2)), Err(StoreError::InvalidSelection(_))));
+        let ints = Array::from_slice(&[0i64; 16], vec![4, 4], DType::new(NpyType::Long)).unwrap();
+        assert!(matches!(store.write(&[], &ints), Err(StoreError::InvalidSelection(_))));
+
+        let metadata = fs::read_to_string(path.join(".zarray")).unwrap();
+        fs::write(path.join(".zarray"), metadata.replace("\"zlib\"", "\"blosc\"")).unwrap();
+        let reopened = ZarrArray::open(&path);
+        let _ = fs::remove_dir_all(&path);
+        assert!(matches!(reopened, Err(StoreError::UnsupportedCodec(_))));
+        assert!(matches!(ZarrArray::open(&path), Err(StoreError::FileError(_))));
+    }
+}
@@ -3,7 +3,7 @@
 use std::fs;
 use raptors_core::{
     array::Array,
-    io::{save_text, load_text, SaveTextOptions, LoadTextOptions},
+    io::{save_text, load_text, write_text, SaveTextOptions, LoadTextOptions, TextIoError},
     types::{DType, NpyType},
 };
 
@@ -556,3 +556,140 @@ fn test_savetxt_large_precision() {
     let _ = fs::remove_file(&file_path);
 }
 
+
+fn write_to_string(array: &Array, options: SaveTextOptions) -> String {
+    let mut out = Vec::new();
+    write_text(&mut out, array, options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn fmt_options(fmt: &str) -> SaveTextOptions {
+    SaveTextOptions { fmt: fmt.to_string(), ..Default::default() }
+}
+
+#[test]
+fn test_savetxt_printf_formats() {
+    let array = Array::from_slice(&[3.25159f64, -42.0], vec![1, 2], DType::new(NpyType::Double)).unwrap();
+    assert_eq!(write_to_string(&array, fmt_options("%10.4f")), "    3.2516   -42.0000\n");
+    assert_eq!(write_to_string(&array, fmt_options("%+08.2f")), "+0003.25 -0042.00\n");
+    assert_eq!(write_to_string(&array, fmt_options("%e")), "3.251590e+00 -4.200000e+01\n");
+    assert_eq!(write_to_string(&array, fmt_options("%-6d|")), "3     | -42   |\n");
+    assert_eq!(write_to_string(&array, fmt_options("%05d")), "00003 -0042\n");
+    assert_eq!(write_to_string(&array, fmt_options("%s")), "3.25159 -42.0\n");
+
+    let array = Array::from_slice(&[255i64, 8], vec![2], DType::new(NpyType::Long)).unwrap();
+    assert_eq!(write_to_string(&array, fmt_options("%#x")), "0xff\n0x8\n");
+    assert_eq!(write_to_string(&array, fmt_options("%o%%")), "377%\n10%\n");
+
+    let array = Array::from_slice(&[0.0001f64, 1234567.0, 1e-5, 100.0], vec![4], DType::new(NpyType::Double)).unwrap();
+    assert_eq!(write_to_string(&array, fmt_options("%g")), "0.0001\n1.23457e+06\n1e-05\n100\n");
+    assert_eq!(write_to_string(&array, fmt_options("%.3G")), "0.0001\n1.23E+06\n1E-05\n100\n");
+}
+
+#[test]
+fn test_savetxt_default_format_and_row_format() {
+    let array = Array::from_slice(&[1.0f64, 2.5], vec![1, 2], DType::new(NpyType::Double)).unwrap();
+    assert_eq!(
+        write_to_string(&array, SaveTextOptions::default()),
+        "1.000000000000000000e+00 2.500000000000000000e+00\n"
+    );
+    // One conversion per column formats the whole row; the delimiter is unused
+    let options = SaveTextOptions { fmt: "%d | %.1f".to_string(), delimiter: ",".to_string(), ..Default::default() };
+    assert_eq!(write_to_string(&array, options), "1 | 2.5\n");
+    // Per-column formats
+    let options = SaveTextOptions {
+        fmts: vec!["%d".to_string(), "%.3f".to_string()],
+        delimiter: ",".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(write_to_string(&array, options), "1,2.500\n");
+
+    let bad = SaveTextOptions { fmts: vec!["%d".to_string()], ..Default::default() };
+    assert!(write_text(Vec::new(), &array, bad).is_err());
+    assert!(write_text(Vec::new(), &array, fmt_options("%d %d %d")).is_err());
+    assert!(write_text(Vec::new(), &array, fmt_options("%y")).is_err());
+}
+
+#[test]
+fn test_savetxt_complex() {
+    let array = Array::from_slice(&[1.0f64, 2.0, 3.0, -4.0], vec![2], DType::new(NpyType::CDouble)).unwrap();
+    assert_eq!(write_to_string(&array, fmt_options("%.1f")), "(1.0+2.0j)\n(3.0-4.0j)\n");
+    // Two conversions per complex column format the parts separately
+    assert_eq!(write_to_string(&array, fmt_options("%.1f %.1f")), "1.0 2.0\n3.0 -4.0\n");
+}
+
+#[test]
+fn test_savetxt_nd_slices() {
+    let values: Vec<i64> = (0..8).collect();
+    let array = Array::from_slice(&values, vec![2, 2, 2], DType::new(NpyType::Long)).unwrap();
+    assert_eq!(
+        write_to_string(&array, fmt_options("%d")),
+        "#[0, :, :]\n0 1\n2 3\n#[1, :, :]\n4 5\n6 7\n"
+    );
+    // Non-contiguous views are written in logical order
+    let transposed = array.view(vec![2, 4], vec![8, 16]).unwrap();
+    assert_eq!(write_to_string(&transposed, fmt_options("%d")), "0 2 4 6\n1 3 5 7\n");
+}
+
+#[test]
+fn test_savetxt_structured_and_strings() {
+    use raptors_core::structured::StructuredDType;
+    let layout = StructuredDType::new(vec![
+        ("id".to_string(), DType::new(NpyType::Long)),
+        ("name".to_string(), DType::unicode_with_itemsize(16)),
+        ("score".to_string(), DType::new(NpyType::Double)),
+    ])
+    .unwrap();
+    let mut array = Array::new(vec![2], DType::structured(layout.clone())).unwrap();
+    let itemsize = array.itemsize();
+    let offsets: Vec<usize> = layout.fields().iter().map(|f| f.offset).collect();
+    unsafe {
+        let base = array.data_ptr_mut();
+        std::ptr::write_bytes(base, 0, 2 * itemsize);
+        for (r, (id, name, score)) in [(1i64, "ann", 2.5f64), (2, "bo", 3.0)].iter().enumerate() {
+            let record = base.add(r * itemsize);
+            *(record.add(offsets[0]) as *mut i64) = *id;
+            for (k, c) in name.chars().enumerate() {
+                *(record.add(offsets[1] + 4 * k) as *mut u32) = c as u32;
+            }
+            *(record.add(offsets[2]) as *mut f64) = *score;
+        }
+    }
+    let options = SaveTextOptions {
+        fmts: vec!["%d".to_string(), "%s".to_string(), "%.2f".to_string()],
+        delimiter: ",".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(write_to_string(&array, options), "#id,name,score\n1,ann,2.50\n2,bo,3.00\n");
+
+    // Strings need a string conversion
+    let mut strings = Array::new(vec![2], DType::string_with_itemsize(3)).unwrap();
+    unsafe {
+        std::ptr::copy_nonoverlapping(b"abcxy".as_ptr(), strings.data_ptr_mut(), 5);
+        *strings.data_ptr_mut().add(5) = 0;
+    }
+    assert_eq!(write_to_string(&strings, fmt_options("[%5s]")), "[  abc]\n[   xy]\n");
+    assert!(write_text(Vec::new(), &strings, SaveTextOptions::default()).is_err());
+}
+
+#[test]
+fn test_savetxt_newline_and_encoding() {
+    let array = Array::from_slice(&[1i64, 2], vec![2], DType::new(NpyType::Long)).unwrap();
+    let options = SaveTextOptions {
+        fmt: "%d".to_string(),
+        header: Some("café\nsecond".to_string()),
+        footer: Some("end".to_string()),
+        comments: "# ".to_string(),
+        newline: "\r\n".to_string(),
+        encoding: "latin-1".to_string(),
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    write_text(&mut out, &array, options.clone()).unwrap();
+    assert_eq!(out, b"# caf\xe9\r\n# second\r\n1\r\n2\r\n# end\r\n".to_vec());
+
+    let ascii = SaveTextOptions { encoding: "ascii".to_string(), ..options.clone() };
+    assert!(matches!(write_text(Vec::new(), &array, ascii), Err(TextIoError::Unsupported(_))));
+    let unknown = SaveTextOptions { encoding: "ebcdic".to_string(), ..options };
+    assert!(write_text(Vec::new(), &array, unknown).is_err());
+}
@@ -368,3 +368,32 @@ fn test_parallel_min_max_stability() {
     assert_eq!(max_results[1], max_results[2]);
 }
 
+
+#[test]
+fn test_array_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Array>();
+}
+
+#[test]
+fn test_arrays_move_across_threads() {
+    // Each thread gets its own copy of one array
+    let size = 60_000;
+    let data: Vec<f64> = (0..size).map(|i| i as f64).collect();
+    let array = array_from_vec_f64(data, vec![size as i64]);
+    let expected = (0..size).sum::<usize>() as f64;
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let array = array.copy();
+            thread::spawn(move || sum_along_axis(&array, None).unwrap())
+        })
+        .collect();
+
+    // Results are arrays themselves, moved back to this thread
+    for handle in handles {
+        let result = handle.join().unwrap();
+        let value = unsafe { *(result.data_ptr() as *const f64) };
+        assert!((value - expected).abs() < 1.0);
+    }
+}
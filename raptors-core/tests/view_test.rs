@@ -35,6 +35,27 @@ mod tests {
         assert_eq!(Arc::strong_count(&array), 2); // array + view
     }
 
+    #[test]
+    fn test_view_from_arc_at() {
+        let values: Vec<i32> = (0..12).collect();
+        let base = Arc::new(Array::from_slice(&values, vec![3, 4], DType::new(NpyType::Int)).unwrap());
+
+        // Column 2 read bottom to top: starts at element (2, 2)
+        let column = Array::view_from_arc_at(&base, 40, vec![3], vec![-16]).unwrap();
+        assert!(column.base_array().is_some());
+        let read: Vec<i32> = (0..3).map(|i| unsafe { *(column.data_ptr().offset(-16 * i) as *const i32) }).collect();
+        assert_eq!(read, vec![10, 6, 2]);
+
+        // Strided views are copied in C order
+        let copy = column.copy();
+        assert!(copy.is_c_contiguous());
+        assert_eq!(unsafe { copy.to_vec::<i32>().unwrap() }, vec![10, 6, 2]);
+
+        // Views that reach outside the base are rejected
+        assert!(Array::view_from_arc_at(&base, 40, vec![4], vec![-16]).is_err());
+        assert!(Array::view_from_arc_at(&base, 44, vec![2], vec![4]).is_err());
+    }
+
     #[test]
     fn test_view_memory_sharing() {
         let shape = vec![3, 3];
@@ -376,7 +397,7 @@ mod tests {
         let mut base = Array::new(shape.clone(), dtype.clone()).unwrap();
         
         // Make base non-writeable
-        base.setflags(raptors_core::array::ArrayFlags::WRITEABLE, false);
+        base.setflags(raptors_core::array::ArrayFlags::WRITEABLE, false).unwrap();
         assert!(!base.is_writeable());
         
         let base_arc = Arc::new(base);
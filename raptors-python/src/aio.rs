@@ -0,0 +1,485 @@
+//! Asyncio bindings
+//!
+//! `raptors.aio` runs kernels on the Rust thread pool and returns asyncio
+//! futures, so coroutines can await matmul, reductions, sorting, FFTs and
+//! file I/O without blocking the event loop:
+//!
+//! ```python
+//! total = await raptors.aio.sum(a)
+//! product = await a.dot_async(b)
+//! ```
+//!
+//! Submissions go through a bounded queue: at most `queue_limit()` jobs are
+//! handed to the pool at a time, and further calls wait for a slot before
+//! their kernel is submitted, so a burst of requests is held back instead of
+//! piling onto the pool.
+//!
+//! Cancelling a call (or the task awaiting it) stops the waiting. A job still
+//! waiting for a slot never runs; a running kernel can't be interrupted, so
+//! it finishes and its result is dropped.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::panic::PanicException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use raptors_core::array::Array;
+use raptors_core::array_api::{self as api, ArrayApiError, BinaryOp};
+use raptors_core::fft::{self as core_fft, FftError, FftNorm};
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::array::PyArray;
+use crate::array_api::{api_error, axes_arg, dtype_arg, wrap};
+use crate::dtype::PyDType;
+use crate::io;
+
+/// What a finished kernel hands back to Python
+enum Output {
+    Array(Array),
+    Loaded(io::Loaded),
+    Nothing,
+}
+
+impl Output {
+    fn into_py(self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match self {
+            Output::Array(array) => Ok(Py::new(py, wrap(array))?.into_any()),
+            Output::Loaded(loaded) => loaded.into_py(py),
+            Output::Nothing => Ok(py.None()),
+        }
+    }
+}
+
+type Kernel = Box<dyn FnOnce() -> PyResult<Output> + Send>;
+
+/// A submitted kernel and the asyncio future waiting for it
+struct Job {
+    kernel: Kernel,
+    future: Py<PyAny>,
+    event_loop: Py<PyAny>,
+    cancelled: Arc<AtomicBool>,
+}
+
+struct Queue {
+    /// Jobs allowed on the pool at once; 0 selects the default
+    limit: usize,
+    /// Jobs on the pool, queued or running
+    in_flight: usize,
+    /// Jobs waiting for a slot, in submission order
+    waiting: VecDeque<Job>,
+}
+
+impl Queue {
+    fn limit(&self) -> usize {
+        if self.limit == 0 {
+            // Every thread busy, with one more job ready behind it
+            2 * rayon::current_num_threads()
+        } else {
+            self.limit
+        }
+    }
+
+    /// Take waiting jobs for the slots that are free
+    fn admit(&mut self) -> Vec<Job> {
+        let mut admitted = Vec::new();
+        while self.in_flight < self.limit() {
+            match self.waiting.pop_front() {
+                Some(job) => {
+                    self.in_flight += 1;
+                    admitted.push(job);
+                }
+                None => break,
+            }
+        }
+        admitted
+    }
+}
+
+// Never lock this while waiting for the GIL: submission and cancellation lock
+// it with the GIL held.
+static QUEUE: Mutex<Queue> = Mutex::new(Queue { limit: 0, in_flight: 0, waiting: VecDeque::new() });
+
+/// Submit `kernel` and return the asyncio future for its result
+fn submit(py: Python<'_>, kernel: impl FnOnce() -> PyResult<Output> + Send + 'static) -> PyResult<Py<PyAny>> {
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    future.call_method1("add_done_callback", (Cancel { cancelled: Arc::clone(&cancelled) },))?;
+
+    let job = Job {
+        kernel: Box::new(kernel),
+        future: future.clone().unbind(),
+        event_loop: event_loop.unbind(),
+        cancelled,
+    };
+    let admitted = {
+        let mut queue = QUEUE.lock().unwrap();
+        queue.waiting.push_back(job);
+        queue.admit()
+    };
+    admitted.into_iter().for_each(spawn);
+    Ok(future.unbind())
+}
+
+fn spawn(job: Job) {
+    rayon::spawn(move || run(job));
+}
+
+/// Run a job on the pool, pass its slot on and deliver its result
+fn run(job: Job) {
+    let Job { kernel, future, event_loop, cancelled } = job;
+    let output = (!cancelled.load(Ordering::Acquire)).then(|| {
+        panic::catch_unwind(AssertUnwindSafe(kernel)).unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "kernel panicked".to_string());
+            Err(PanicException::new_err(message))
+        })
+    });
+
+    let admitted = {
+        let mut queue = QUEUE.lock().unwrap();
+        queue.in_flight -= 1;
+        queue.admit()
+    };
+    admitted.into_iter().for_each(spawn);
+
+    if let Some(output) = output {
+        Python::attach(|py| deliver(py, &event_loop, future, output));
+    }
+}
+
+/// Hand a result to the event loop thread, which owns the future
+fn deliver(py: Python<'_>, event_loop: &Py<PyAny>, future: Py<PyAny>, output: PyResult<Output>) {
+    let (result, error) = match output.and_then(|output| output.into_py(py)) {
+        Ok(value) => (Some(value), None),
+        Err(e) => (None, Some(e.into_value(py))),
+    };
+    // A closed loop has nobody left to await the result
+    wrap_pyfunction!(resolve, py)
+        .and_then(|resolve| event_loop.call_method1(py, "call_soon_threadsafe", (resolve, future, result, error)))
+        .ok();
+}
+
+/// Complete `future` on its event loop, unless it was cancelled meanwhile
+#[pyfunction]
+#[pyo3(signature = (future, result, error))]
+fn resolve(future: &Bound<'_, PyAny>, result: Option<Py<PyAny>>, error: Option<Py<PyAny>>) -> PyResult<()> {
+    if future.call_method0("done")?.extract()? {
+        return Ok(());
+    }
+    match error {
+        Some(error) => future.call_method1("set_exception", (error,))?,
+        None => future.call_method1("set_result", (result,))?,
+    };
+    Ok(())
+}
+
+/// Done callback that marks a cancelled call and drops it from the queue
+#[pyclass(module = "raptors.aio", frozen)]
+struct Cancel {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl Cancel {
+    fn __call__(&self, future: &Bound<'_, PyAny>) -> PyResult<()> {
+        if !future.call_method0("cancelled")?.extract::<bool>()? {
+            return Ok(());
+        }
+        self.cancelled.store(true, Ordering::Release);
+        let dropped = {
+            let mut queue = QUEUE.lock().unwrap();
+            let (dropped, kept): (VecDeque<Job>, _) = std::mem::take(&mut queue.waiting)
+                .into_iter()
+                .partition(|job| job.cancelled.load(Ordering::Acquire));
+            queue.waiting = kept;
+            dropped
+        };
+        // Release the futures outside the lock
+        drop(dropped);
+        Ok(())
+    }
+}
+
+/// Maximum number of jobs on the thread pool at once
+///
+/// Defaults to twice the number of pool threads.
+#[pyfunction]
+fn queue_limit() -> usize {
+    QUEUE.lock().unwrap().limit()
+}
+
+/// Set the maximum number of jobs on the thread pool at once
+///
+/// Raising the limit submits waiting jobs right away; lowering it lets the
+/// jobs already submitted finish.
+#[pyfunction]
+fn set_queue_limit(limit: usize) -> PyResult<()> {
+    if limit == 0 {
+        return Err(PyErr::new::<PyValueError, _>("queue limit must be at least 1"));
+    }
+    let admitted = {
+        let mut queue = QUEUE.lock().unwrap();
+        queue.limit = limit;
+        queue.admit()
+    };
+    admitted.into_iter().for_each(spawn);
+    Ok(())
+}
+
+/// Number of jobs on the thread pool, queued or running
+#[pyfunction]
+fn in_flight() -> usize {
+    QUEUE.lock().unwrap().in_flight
+}
+
+/// Number of calls waiting for a slot on the thread pool
+#[pyfunction]
+fn waiting() -> usize {
+    QUEUE.lock().unwrap().waiting.len()
+}
+
+/// Matrix product of two arrays
+#[pyfunction]
+#[pyo3(signature = (x1, x2, /))]
+pub(crate) fn matmul(x1: &Bound<'_, PyArray>, x2: &Bound<'_, PyArray>) -> PyResult<Py<PyAny>> {
+    let (a, b) = (PyArray::handle(x1), PyArray::handle(x2));
+    submit(x1.py(), move || api::matmul(&a, &b).map(Output::Array).map_err(api_error))
+}
+
+/// Dot product of two arrays, as `numpy.dot` for up to two dimensions
+#[pyfunction]
+#[pyo3(signature = (a, b, /))]
+pub(crate) fn dot(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<Py<PyAny>> {
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let product: fn(&Array, &Array) -> Result<Array, ArrayApiError> = match (a.ndim(), b.ndim()) {
+        (0, _) | (_, 0) => |a, b| api::binary(BinaryOp::Multiply, a, b),
+        (1..=2, 1..=2) => api::matmul,
+        _ => return Err(PyErr::new::<PyValueError, _>("dot supports arrays of up to two dimensions")),
+    };
+    submit(py, move || product(&a, &b).map(Output::Array).map_err(api_error))
+}
+
+macro_rules! reductions {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[pyfunction]
+            #[pyo3(signature = (x, /, *, axis=None, keepdims=false))]
+            pub(crate) fn $name(x: &Bound<'_, PyArray>, axis: Option<&Bound<'_, PyAny>>, keepdims: bool) -> PyResult<Py<PyAny>> {
+                let (a, axis) = (PyArray::handle(x), axes_arg(axis)?);
+                submit(x.py(), move || {
+                    api::$name(&a, axis.as_deref(), keepdims).map(Output::Array).map_err(api_error)
+                })
+            }
+        )*
+    };
+}
+
+reductions! {
+    /// Largest elements along `axis`
+    max,
+    /// Smallest elements along `axis`
+    min,
+    /// Arithmetic mean along `axis`
+    mean,
+}
+
+macro_rules! accumulations {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[pyfunction]
+            #[pyo3(signature = (x, /, *, axis=None, dtype=None, keepdims=false))]
+            pub(crate) fn $name(
+                x: &Bound<'_, PyArray>,
+                axis: Option<&Bound<'_, PyAny>>,
+                dtype: Option<&PyDType>,
+                keepdims: bool,
+            ) -> PyResult<Py<PyAny>> {
+                let (a, axis, dtype) = (PyArray::handle(x), axes_arg(axis)?, dtype_arg(dtype).cloned());
+                submit(x.py(), move || {
+                    api::$name(&a, axis.as_deref(), dtype.as_ref(), keepdims).map(Output::Array).map_err(api_error)
+                })
+            }
+        )*
+    };
+}
+
+accumulations! {
+    /// Sum along `axis`
+    sum,
+    /// Product along `axis`
+    prod,
+}
+
+macro_rules! deviations {
+    ($($(#[$meta:meta])* $rust:ident($name:literal) => $api:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[pyfunction]
+            #[pyo3(name = $name, signature = (x, /, *, axis=None, correction=0.0, keepdims=false))]
+            pub(crate) fn $rust(
+                x: &Bound<'_, PyArray>,
+                axis: Option<&Bound<'_, PyAny>>,
+                correction: f64,
+                keepdims: bool,
+            ) -> PyResult<Py<PyAny>> {
+                let (a, axis) = (PyArray::handle(x), axes_arg(axis)?);
+                submit(x.py(), move || {
+                    api::$api(&a, axis.as_deref(), correction, keepdims).map(Output::Array).map_err(api_error)
+                })
+            }
+        )*
+    };
+}
+
+deviations! {
+    /// Standard deviation along `axis`
+    std_("std") => std,
+    /// Variance along `axis`
+    var("var") => var,
+}
+
+/// Sorted copy of an array along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=-1, descending=false, stable=true))]
+pub(crate) fn sort(x: &Bound<'_, PyArray>, axis: isize, descending: bool, stable: bool) -> PyResult<Py<PyAny>> {
+    let a = PyArray::handle(x);
+    submit(x.py(), move || api::sort(&a, axis, descending, stable).map(Output::Array).map_err(api_error))
+}
+
+/// Indices that sort an array along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=-1, descending=false, stable=true))]
+pub(crate) fn argsort(x: &Bound<'_, PyArray>, axis: isize, descending: bool, stable: bool) -> PyResult<Py<PyAny>> {
+    let a = PyArray::handle(x);
+    submit(x.py(), move || api::argsort(&a, axis, descending, stable).map(Output::Array).map_err(api_error))
+}
+
+fn fft_error(e: FftError) -> PyErr {
+    PyErr::new::<PyValueError, _>(e.to_string())
+}
+
+type Transform = fn(&Array, Option<usize>, usize, FftNorm) -> Result<Array, FftError>;
+
+/// Submit a one-dimensional transform along `axis` (negative counts from the end)
+fn transform(
+    x: &Bound<'_, PyArray>,
+    transform: Transform,
+    n: Option<usize>,
+    axis: isize,
+    norm: Option<&str>,
+) -> PyResult<Py<PyAny>> {
+    let norm = norm.map_or(Ok(FftNorm::default()), FftNorm::from_name).map_err(fft_error)?;
+    let a = PyArray::handle(x);
+    let axis = if axis < 0 { axis + a.ndim() as isize } else { axis };
+    let axis = usize::try_from(axis).map_err(|_| fft_error(FftError::InvalidAxis))?;
+    submit(x.py(), move || transform(&a, n, axis, norm).map(Output::Array).map_err(fft_error))
+}
+
+macro_rules! transforms {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[pyfunction]
+            #[pyo3(signature = (a, n=None, axis=-1, norm=None))]
+            pub(crate) fn $name(a: &Bound<'_, PyArray>, n: Option<usize>, axis: isize, norm: Option<&str>) -> PyResult<Py<PyAny>> {
+                transform(a, core_fft::$name, n, axis, norm)
+            }
+        )*
+    };
+}
+
+transforms! {
+    /// One-dimensional discrete Fourier transform, as `numpy.fft.fft`
+    fft,
+    /// One-dimensional inverse discrete Fourier transform, as `numpy.fft.ifft`
+    ifft,
+    /// Transform of real input, as `numpy.fft.rfft`
+    rfft,
+    /// Inverse of `rfft`, as `numpy.fft.irfft`
+    irfft,
+}
+
+/// Save an array to a binary file in NPY format
+#[pyfunction]
+pub(crate) fn save(file: PathBuf, arr: &Bound<'_, PyArray>) -> PyResult<Py<PyAny>> {
+    let array = PyArray::handle(arr);
+    submit(arr.py(), move || io::write(file, &array).map(|()| Output::Nothing))
+}
+
+/// Load an array from an NPY file, or an `NpzFile` from an NPZ archive
+#[pyfunction]
+fn load(py: Python<'_>, file: PathBuf) -> PyResult<Py<PyAny>> {
+    submit(py, move || io::read(&file).map(Output::Loaded))
+}
+
+/// Save several arrays into a single uncompressed NPZ archive
+#[pyfunction]
+#[pyo3(signature = (file, *args, **kwds))]
+fn savez(file: PathBuf, args: &Bound<'_, PyTuple>, kwds: Option<&Bound<'_, PyDict>>) -> PyResult<Py<PyAny>> {
+    let arrays = io::named_arrays(args, kwds)?;
+    submit(args.py(), move || io::write_npz(file, &arrays, false).map(|()| Output::Nothing))
+}
+
+/// Save several arrays into a single deflate-compressed NPZ archive
+#[pyfunction]
+#[pyo3(signature = (file, *args, **kwds))]
+fn savez_compressed(file: PathBuf, args: &Bound<'_, PyTuple>, kwds: Option<&Bound<'_, PyDict>>) -> PyResult<Py<PyAny>> {
+    let arrays = io::named_arrays(args, kwds)?;
+    submit(args.py(), move || io::write_npz(file, &arrays, true).map(|()| Output::Nothing))
+}
+
+/// Create the `raptors.aio` submodule and register it with `parent`
+pub fn add_aio_module(parent: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = parent.py();
+    let m = PyModule::new(py, "aio")?;
+
+    // Submission queue
+    m.add_function(wrap_pyfunction!(queue_limit, &m)?)?;
+    m.add_function(wrap_pyfunction!(set_queue_limit, &m)?)?;
+    m.add_function(wrap_pyfunction!(in_flight, &m)?)?;
+    m.add_function(wrap_pyfunction!(waiting, &m)?)?;
+
+    // Linear algebra
+    m.add_function(wrap_pyfunction!(matmul, &m)?)?;
+    m.add_function(wrap_pyfunction!(dot, &m)?)?;
+
+    // Reductions
+    m.add_function(wrap_pyfunction!(sum, &m)?)?;
+    m.add_function(wrap_pyfunction!(prod, &m)?)?;
+    m.add_function(wrap_pyfunction!(mean, &m)?)?;
+    m.add_function(wrap_pyfunction!(max, &m)?)?;
+    m.add_function(wrap_pyfunction!(min, &m)?)?;
+    m.add_function(wrap_pyfunction!(std_, &m)?)?;
+    m.add_function(wrap_pyfunction!(var, &m)?)?;
+
+    // Sorting
+    m.add_function(wrap_pyfunction!(sort, &m)?)?;
+    m.add_function(wrap_pyfunction!(argsort, &m)?)?;
+
+    // Discrete Fourier transforms
+    m.add_function(wrap_pyfunction!(fft, &m)?)?;
+    m.add_function(wrap_pyfunction!(ifft, &m)?)?;
+    m.add_function(wrap_pyfunction!(rfft, &m)?)?;
+    m.add_function(wrap_pyfunction!(irfft, &m)?)?;
+
+    // File I/O
+    m.add_function(wrap_pyfunction!(save, &m)?)?;
+    m.add_function(wrap_pyfunction!(load, &m)?)?;
+    m.add_function(wrap_pyfunction!(savez, &m)?)?;
+    m.add_function(wrap_pyfunction!(savez_compressed, &m)?)?;
+
+    parent.add_submodule(&m)?;
+    // Make `import raptors.aio` work for an extension submodule
+    py.import("sys")?.getattr("modules")?.set_item("raptors.aio", &m)?;
+    Ok(())
+}
@@ -5,56 +5,93 @@
 #![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
 
 use pyo3::prelude::*;
-use pyo3::types::{PyAny, PyList, PyTuple, PySlice};
+use pyo3::types::{PyAny, PyDict, PyList, PyTuple, PySlice};
 use pyo3::ffi;
 use raptors_core::{Array, empty, zeros, ones};
 use raptors_core::types::{DType, NpyType};
-use raptors_core::indexing::{index_array, slice_array, Slice};
+use raptors_core::indexing::Slice;
 use raptors_core::conversion::convert_array;
-use raptors_core::operations::{add, subtract, multiply, divide};
-use raptors_core::operations::{equal, not_equal, less, greater, less_equal, greater_equal};
-use raptors_core::dlpack::{to_dlpack, delete_dlpack_tensor, DLDeviceType, DLTensor};
+use raptors_core::dlpack::DLDeviceType;
 use std::sync::Arc;
-use std::os::raw::c_void;
-use std::ffi::CString;
+use std::os::raw::c_int;
+use raptors_core::array_api::{BinaryOp, UnaryOp};
+use crate::aio;
+use crate::array_api;
 use crate::dtype::PyDType;
+use crate::indexing;
 use crate::iterators;
 
-/// Destructor function for DLPack capsule
-/// 
-/// This function is called by Python when the PyCapsule is deleted.
-/// It ensures the DLTensor is properly freed.
-/// 
-/// Signature matches PyCapsule_Destructor: void(*destructor)(PyObject*)
-unsafe extern "C" fn dlpack_capsule_destructor(capsule: *mut ffi::PyObject) {
-    // Get the pointer from the capsule
-    let name = CString::new("dltensor").unwrap();
-    let tensor_ptr = ffi::PyCapsule_GetPointer(capsule, name.as_ptr());
-    if !tensor_ptr.is_null() {
-        delete_dlpack_tensor(tensor_ptr as *mut DLTensor);
-    }
-}
-
 /// Python Array class
 #[pyclass]
 pub struct PyArray {
-    #[allow(clippy::arc_with_non_send_sync)] // Arc needed for Python reference counting, not thread safety
     pub(crate) inner: Arc<Array>,
 }
 
-// SAFETY: Array contains raw pointers but they are managed safely through Arc
-// The data is owned by the Array struct, and Arc provides thread-safe reference counting
+// SAFETY: the array is shared with Python under the same terms as a
+// `Handle`, see below.
 unsafe impl Send for PyArray {}
 unsafe impl Sync for PyArray {}
 
+/// Shared reference to the array of a `PyArray`, for kernels that run with
+/// the GIL released
+#[derive(Clone)]
+pub(crate) struct Handle(Arc<Array>);
+
+impl From<Arc<Array>> for Handle {
+    fn from(array: Arc<Array>) -> Self {
+        Handle(array)
+    }
+}
+
+impl From<Array> for Handle {
+    fn from(array: Array) -> Self {
+        Handle(Arc::new(array))
+    }
+}
+
+impl std::ops::Deref for Handle {
+    type Target = Array;
+
+    fn deref(&self) -> &Array {
+        &self.0
+    }
+}
+
+// SAFETY: `Array` is not `Sync` because `index_assign` writes through a
+// shared reference. This crate calls it only from `__setitem__` and `out=`
+// arguments, with the GIL held; kernels holding a handle only read. A write
+// racing a kernel on the same memory therefore needs a Python program that
+// mutates an array while another thread computes on it, which is a race
+// there exactly as it is with NumPy.
+unsafe impl Send for Handle {}
+unsafe impl Sync for Handle {}
+
 impl PyArray {
     /// Get reference to inner array (for internal use)
     pub(crate) fn get_inner(&self) -> &Arc<Array> {
         &self.inner
     }
+
+    /// The inner array itself when C-contiguous, otherwise a C-ordered copy
+    fn contiguous(&self) -> Arc<Array> {
+        if self.inner.is_c_contiguous() {
+            Arc::clone(&self.inner)
+        } else {
+            Arc::new(self.inner.copy())
+        }
+    }
 }
 
 impl PyArray {
+    /// Rust-owned handle to the inner array, for kernels that run with the
+    /// GIL released
+    ///
+    /// The borrow of the Python object ends here, so other threads can use
+    /// the array (even `__setitem__`) while the kernel runs.
+    pub(crate) fn handle(bound: &Bound<'_, PyArray>) -> Handle {
+        Handle(Arc::clone(&bound.borrow().inner))
+    }
+
     /// Get reference to inner array from Bound (for internal use)
     pub(crate) fn get_inner_from_bound<'a>(bound: &Bound<'a, PyArray>) -> &'a Arc<Array> {
         // SAFETY: Bound ensures the reference is valid for its lifetime
@@ -62,7 +99,7 @@ impl PyArray {
     }
     
     /// Convert PySlice to internal Slice type
-    fn py_slice_to_slice(py_slice: &Bound<'_, PySlice>) -> PyResult<Slice> {
+    pub(crate) fn py_slice_to_slice(py_slice: &Bound<'_, PySlice>) -> PyResult<Slice> {
         // Get start, stop, step attributes from Python slice object
         let start_attr = py_slice.getattr("start")?;
         let stop_attr = py_slice.getattr("stop")?;
@@ -212,8 +249,9 @@ impl PyArray {
     }
     
     /// Create a copy of the array
-    fn copy(&self) -> PyResult<Self> {
-        let copied = self.inner.copy();
+    fn copy(slf: &Bound<'_, Self>) -> PyResult<Self> {
+        let array = Self::handle(slf);
+        let copied = slf.py().detach(|| array.copy());
         Ok(PyArray {
             inner: Arc::new(copied),
         })
@@ -222,7 +260,8 @@ impl PyArray {
     /// Create a view of the array
     fn view(&self) -> PyResult<Self> {
         // Create a view with the same shape and strides
-        let view = self.inner.view(
+        let view = Array::view_from_arc(
+            self.get_inner(),
             self.get_inner().shape().to_vec(),
             self.get_inner().strides().to_vec()
         ).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
@@ -242,7 +281,7 @@ impl PyArray {
         let itemsize = self.get_inner().itemsize();
         let new_strides = raptors_core::shape::shape::compute_reshape_strides(&resolved_shape, itemsize);
         
-        let reshaped = self.get_inner().view(resolved_shape, new_strides)
+        let reshaped = Array::view_from_arc(&self.contiguous(), resolved_shape, new_strides)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
         
         Ok(PyArray {
@@ -262,7 +301,7 @@ impl PyArray {
             new_strides[i] = self.get_inner().strides()[axis as usize];
         }
         
-        let transposed = self.get_inner().view(new_shape, new_strides)
+        let transposed = Array::view_from_arc(self.get_inner(), new_shape, new_strides)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
         
         Ok(PyArray {
@@ -277,7 +316,7 @@ impl PyArray {
         let itemsize = self.get_inner().itemsize();
         let new_strides = raptors_core::shape::shape::compute_reshape_strides(&flat_shape, itemsize);
         
-        let flattened = self.get_inner().view(flat_shape, new_strides)
+        let flattened = Array::view_from_arc(&self.contiguous(), flat_shape, new_strides)
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
         
         Ok(PyArray {
@@ -287,9 +326,10 @@ impl PyArray {
     
     /// Sum array elements along an axis
     #[pyo3(signature = (axis=None))]
-    fn sum(&self, axis: Option<usize>) -> PyResult<Self> {
+    fn sum(slf: &Bound<'_, Self>, axis: Option<usize>) -> PyResult<Self> {
         use raptors_core::ufunc::reduction::sum_along_axis;
-        let result = sum_along_axis(self.get_inner(), axis)
+        let array = Self::handle(slf);
+        let result = slf.py().detach(|| sum_along_axis(&array, axis))
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
         Ok(PyArray {
             inner: Arc::new(result),
@@ -298,9 +338,10 @@ impl PyArray {
     
     /// Maximum of array elements along an axis
     #[pyo3(signature = (axis=None))]
-    fn max(&self, axis: Option<usize>) -> PyResult<Self> {
+    fn max(slf: &Bound<'_, Self>, axis: Option<usize>) -> PyResult<Self> {
         use raptors_core::ufunc::reduction::max_along_axis;
-        let result = max_along_axis(self.get_inner(), axis)
+        let array = Self::handle(slf);
+        let result = slf.py().detach(|| max_along_axis(&array, axis))
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
         Ok(PyArray {
             inner: Arc::new(result),
@@ -309,15 +350,91 @@ impl PyArray {
     
     /// Minimum of array elements along an axis
     #[pyo3(signature = (axis=None))]
-    fn min(&self, axis: Option<usize>) -> PyResult<Self> {
+    fn min(slf: &Bound<'_, Self>, axis: Option<usize>) -> PyResult<Self> {
         use raptors_core::ufunc::reduction::min_along_axis;
-        let result = min_along_axis(self.get_inner(), axis)
+        let array = Self::handle(slf);
+        let result = slf.py().detach(|| min_along_axis(&array, axis))
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
         Ok(PyArray {
             inner: Arc::new(result),
         })
     }
     
+    /// Matrix product with `other`, as an asyncio future (see `raptors.aio`)
+    fn matmul_async(slf: &Bound<'_, Self>, other: &Bound<'_, Self>) -> PyResult<Py<PyAny>> {
+        aio::matmul(slf, other)
+    }
+    
+    /// Dot product with `other`, as an asyncio future (see `raptors.aio`)
+    fn dot_async(slf: &Bound<'_, Self>, other: &Bound<'_, Self>) -> PyResult<Py<PyAny>> {
+        aio::dot(slf, other)
+    }
+    
+    /// Sum along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=None, *, dtype=None, keepdims=false))]
+    fn sum_async(slf: &Bound<'_, Self>, axis: Option<&Bound<'_, PyAny>>, dtype: Option<&PyDType>, keepdims: bool) -> PyResult<Py<PyAny>> {
+        aio::sum(slf, axis, dtype, keepdims)
+    }
+    
+    /// Product along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=None, *, dtype=None, keepdims=false))]
+    fn prod_async(slf: &Bound<'_, Self>, axis: Option<&Bound<'_, PyAny>>, dtype: Option<&PyDType>, keepdims: bool) -> PyResult<Py<PyAny>> {
+        aio::prod(slf, axis, dtype, keepdims)
+    }
+    
+    /// Mean along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=None, *, keepdims=false))]
+    fn mean_async(slf: &Bound<'_, Self>, axis: Option<&Bound<'_, PyAny>>, keepdims: bool) -> PyResult<Py<PyAny>> {
+        aio::mean(slf, axis, keepdims)
+    }
+    
+    /// Maximum along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=None, *, keepdims=false))]
+    fn max_async(slf: &Bound<'_, Self>, axis: Option<&Bound<'_, PyAny>>, keepdims: bool) -> PyResult<Py<PyAny>> {
+        aio::max(slf, axis, keepdims)
+    }
+    
+    /// Minimum along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=None, *, keepdims=false))]
+    fn min_async(slf: &Bound<'_, Self>, axis: Option<&Bound<'_, PyAny>>, keepdims: bool) -> PyResult<Py<PyAny>> {
+        aio::min(slf, axis, keepdims)
+    }
+    
+    /// Standard deviation along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=None, *, correction=0.0, keepdims=false))]
+    fn std_async(slf: &Bound<'_, Self>, axis: Option<&Bound<'_, PyAny>>, correction: f64, keepdims: bool) -> PyResult<Py<PyAny>> {
+        aio::std_(slf, axis, correction, keepdims)
+    }
+    
+    /// Variance along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=None, *, correction=0.0, keepdims=false))]
+    fn var_async(slf: &Bound<'_, Self>, axis: Option<&Bound<'_, PyAny>>, correction: f64, keepdims: bool) -> PyResult<Py<PyAny>> {
+        aio::var(slf, axis, correction, keepdims)
+    }
+    
+    /// Sorted copy along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=-1, *, descending=false, stable=true))]
+    fn sort_async(slf: &Bound<'_, Self>, axis: isize, descending: bool, stable: bool) -> PyResult<Py<PyAny>> {
+        aio::sort(slf, axis, descending, stable)
+    }
+    
+    /// Indices that sort along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (axis=-1, *, descending=false, stable=true))]
+    fn argsort_async(slf: &Bound<'_, Self>, axis: isize, descending: bool, stable: bool) -> PyResult<Py<PyAny>> {
+        aio::argsort(slf, axis, descending, stable)
+    }
+    
+    /// Discrete Fourier transform along `axis`, as an asyncio future (see `raptors.aio`)
+    #[pyo3(signature = (n=None, axis=-1, norm=None))]
+    fn fft_async(slf: &Bound<'_, Self>, n: Option<usize>, axis: isize, norm: Option<&str>) -> PyResult<Py<PyAny>> {
+        aio::fft(slf, n, axis, norm)
+    }
+    
+    /// Save to an NPY file, as an asyncio future (see `raptors.aio`)
+    fn save_async(slf: &Bound<'_, Self>, file: std::path::PathBuf) -> PyResult<Py<PyAny>> {
+        aio::save(file, slf)
+    }
+    
     /// Convert array to Python list
     fn tolist(&self, py: Python) -> PyResult<Py<PyAny>> {
         use raptors_core::types::NpyType;
@@ -326,10 +443,10 @@ impl PyArray {
         let shape = inner.shape();
         
         // Helper function to convert a value to Python object
-        fn value_to_python(py: Python, ptr: *const u8, dtype: &raptors_core::types::DType, offset: usize) -> PyResult<Py<PyAny>> {
+        fn value_to_python(py: Python, ptr: *const u8, dtype: &raptors_core::types::DType, offset: isize) -> PyResult<Py<PyAny>> {
             use raptors_core::types::NpyType;
             use NpyType::*;
-            let val_ptr = unsafe { ptr.add(offset * dtype.itemsize()) };
+            let val_ptr = unsafe { ptr.offset(offset) };
             match dtype.type_() {
                 Bool => {
                     let val = unsafe { *(val_ptr as *const bool) };
@@ -386,6 +503,18 @@ impl PyArray {
                     let py_obj = unsafe { pyo3::ffi::PyFloat_FromDouble(val) };
                     Ok(unsafe { Py::from_owned_ptr_or_err(py, py_obj)? })
                 }
+                CFloat => {
+                    let parts = val_ptr as *const f32;
+                    let (re, im) = unsafe { (*parts as f64, *parts.add(1) as f64) };
+                    let py_obj = unsafe { pyo3::ffi::PyComplex_FromDoubles(re, im) };
+                    Ok(unsafe { Py::from_owned_ptr_or_err(py, py_obj)? })
+                }
+                CDouble => {
+                    let parts = val_ptr as *const f64;
+                    let (re, im) = unsafe { (*parts, *parts.add(1)) };
+                    let py_obj = unsafe { pyo3::ffi::PyComplex_FromDoubles(re, im) };
+                    Ok(unsafe { Py::from_owned_ptr_or_err(py, py_obj)? })
+                }
                 _ => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
                     "Dtype not supported for tolist"
                 ))
@@ -399,7 +528,7 @@ impl PyArray {
             dtype: &raptors_core::types::DType,
             shape: &[i64],
             strides: &[i64],
-            base_offset: usize,
+            base_offset: isize,
         ) -> PyResult<Py<PyAny>> {
             if shape.is_empty() {
                 // Scalar - return the value
@@ -411,7 +540,7 @@ impl PyArray {
                 let mut list = Vec::new();
                 let dim = shape[0] as usize;
                 for i in 0..dim {
-                    let offset = base_offset + i * strides[0] as usize / dtype.itemsize();
+                    let offset = base_offset + i as isize * strides[0] as isize;
                     let val = value_to_python(py, ptr, dtype, offset)?;
                     list.push(val);
                 }
@@ -428,7 +557,7 @@ impl PyArray {
                 for i in 0..dim {
                     let sub_shape = &shape[1..];
                     let sub_strides = &strides[1..];
-                    let offset = base_offset + i * strides[0] as usize / dtype.itemsize();
+                    let offset = base_offset + i as isize * strides[0] as isize;
                     let sub_list = build_list(py, ptr, dtype, sub_shape, sub_strides, offset)?;
                     py_list.append(sub_list)?;
                 }
@@ -441,17 +570,17 @@ impl PyArray {
     }
     
     /// Convert array to a different dtype
-    fn astype(&self, dtype: &PyDType) -> PyResult<Self> {
+    fn astype(slf: &Bound<'_, Self>, dtype: &PyDType) -> PyResult<Self> {
         let target_dtype = dtype.get_inner().clone();
-        let source_dtype = self.get_inner().dtype();
+        let array = Self::handle(slf);
         
         // If types are the same, just return a copy
-        if source_dtype.type_() == target_dtype.type_() {
-            return self.copy();
+        if array.dtype().type_() == target_dtype.type_() {
+            return Self::copy(slf);
         }
         
         // Use proper type conversion
-        let converted_array = convert_array(self.get_inner(), target_dtype)
+        let converted_array = slf.py().detach(|| convert_array(&array, target_dtype))
             .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(
                 format!("Failed to convert array dtype: {}", e)
             ))?;
@@ -462,130 +591,20 @@ impl PyArray {
     }
     
     /// Get item at index
+    ///
+    /// Supports the full NumPy index grammar: integers, slices, `...`,
+    /// `None`, and integer or boolean arrays, in any combination. Basic
+    /// indexing returns a view; any index array makes a copy.
     fn __getitem__(&self, py: Python, index: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
-        // Try tuple indexing (multi-dimensional: arr[0, 0])
-        // In Python, arr[0, 0] is passed as a tuple to __getitem__
-        // For PyO3 0.27, use Bound::cast instead of downcast
-        if let Ok(tuple) = index.cast::<PyTuple>() {
-            let ndim = self.inner.ndim();
-            if tuple.len() == ndim {
-                let mut indices = Vec::new();
-                let shape = self.inner.shape();
-                for i in 0..tuple.len() {
-                    let item = tuple.get_item(i)?;
-                    let mut idx: i64 = item.extract()?;
-                    
-                    // Normalize negative index
-                    if idx < 0 {
-                        idx += shape[i] as i64;
-                    }
-                    
-                    // Bounds check
-                    if idx < 0 || idx >= shape[i] as i64 {
-                        return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
-                            format!("Index {} out of bounds for dimension {} of size {}", idx, i, shape[i])
-                        ));
-                    }
-                    
-                    indices.push(idx);
-                }
-                
-                let ptr = index_array(&self.inner, &indices)
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!("{}", e)))?;
-                
-                return self.extract_value(py, ptr);
-            } else {
-                return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
-                    format!("Expected {} indices, got {}", ndim, tuple.len())
-                ));
-            }
-        }
-        
-        // Try slice indexing (1D: arr[1:3])
-        if let Ok(py_slice) = index.cast::<PySlice>() {
-            if self.inner.ndim() == 1 {
-                let slice = Self::py_slice_to_slice(&py_slice)?;
-                let sliced_array = slice_array(self.get_inner(), &[slice])
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!("{}", e)))?;
-                let result = PyArray {
-                    inner: Arc::new(sliced_array),
-                };
-                return Ok(Py::new(py, result)?.into());
-            } else {
-                return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
-                    format!("Single slice provided but array has {} dimensions", self.inner.ndim())
-                ));
-            }
-        }
-        
-        // Try integer indexing (including negative indices)
-        if let Ok(mut idx) = index.extract::<i64>() {
-            if self.inner.ndim() == 1 {
-                let shape = self.inner.shape();
-                let dim_size = shape[0] as i64;
-                
-                // Normalize negative index
-                if idx < 0 {
-                    idx += dim_size;
-                }
-                
-                // Bounds check
-                if idx < 0 || idx >= dim_size {
-                    return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
-                        format!("Index {} out of bounds for dimension of size {}", idx, dim_size)
-                    ));
-                }
-                
-                let indices = vec![idx];
-                let ptr = index_array(&self.inner, &indices)
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!("{}", e)))?;
-                
-                // Extract value based on dtype
-                return self.extract_value(py, ptr);
-            } else {
-                return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
-                    format!("Single index provided but array has {} dimensions", self.inner.ndim())
-                ));
-            }
-        }
-        
-        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
-            "Advanced indexing not yet implemented"
-        ))
+        indexing::get_item(py, self, index)
     }
     
     /// Set item at index
+    ///
+    /// Accepts any index `__getitem__` does; the value (a scalar, list or
+    /// array) is cast to the array's dtype and broadcast to the selection.
     fn __setitem__(&mut self, py: Python, index: &Bound<'_, PyAny>, value: &Bound<'_, PyAny>) -> PyResult<()> {
-        // Try integer indexing (including negative indices)
-        if let Ok(mut idx) = index.extract::<i64>() {
-            if self.inner.ndim() == 1 {
-                let shape = self.inner.shape();
-                let dim_size = shape[0] as i64;
-                
-                // Normalize negative index
-                if idx < 0 {
-                    idx += dim_size;
-                }
-                
-                // Bounds check
-                if idx < 0 || idx >= dim_size {
-                    return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(
-                        format!("Index {} out of bounds for dimension of size {}", idx, dim_size)
-                    ));
-                }
-                
-                let indices = vec![idx];
-                let ptr = index_array(&self.inner, &indices)
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!("{}", e)))?;
-                
-                // Set value based on dtype (cast to mutable)
-                return Self::set_value_static(py, ptr as *mut u8, value, self.get_inner().dtype());
-            }
-        }
-        
-        Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
-            "Advanced indexing not yet implemented"
-        ))
+        indexing::set_item(py, self, index, value)
     }
     
     /// String representation
@@ -610,13 +629,32 @@ impl PyArray {
         "PyArray".to_string()
     }
     
-    /// Array protocol - return self for NumPy compatibility
-    fn __array__(&self) -> PyResult<Self> {
-        // Return self - PyArray implements the array protocol
-        // NumPy will call this method to convert array-like objects
-        Ok(PyArray {
-            inner: self.inner.clone(),
-        })
+    /// Array protocol - a NumPy array sharing this array's memory
+    #[pyo3(signature = (dtype=None, copy=None))]
+    fn __array__(&self, py: Python, dtype: Option<Bound<'_, PyAny>>, copy: Option<bool>) -> PyResult<Py<PyAny>> {
+        let array = crate::numpy_interop::to_numpy(py, self)?.into_bound(py);
+        let array = match dtype {
+            Some(dtype) => array.call_method1("astype", (dtype,))?,
+            None if copy == Some(true) => array.call_method0("copy")?,
+            None => array,
+        };
+        Ok(array.unbind())
+    }
+
+    /// Buffer protocol export, used by `memoryview` and other consumers
+    unsafe fn __getbuffer__(slf: Bound<'_, Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        crate::buffer::get_buffer(slf, view, flags)
+    }
+
+    /// Release a buffer obtained through `__getbuffer__`
+    unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {
+        crate::buffer::release_buffer(view)
+    }
+
+    /// Array interface protocol (version 3), used by NumPy to share memory
+    #[getter]
+    fn __array_interface__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        crate::numpy_interop::array_interface(py, self.get_inner())
     }
     
     /// Convert to NumPy array (convenience method)
@@ -632,41 +670,18 @@ impl PyArray {
     
     /// DLPack protocol - export array as DLPack tensor
     /// 
-    /// Returns a PyCapsule containing a DLTensor pointer.
-    /// The capsule name is "dltensor" per DLPack specification.
-    fn __dlpack__(&self, py: Python, _stream: Option<Py<PyAny>>) -> PyResult<Py<PyAny>> {
-        // Convert array to DLPack tensor
-        let tensor_ptr = unsafe {
-            to_dlpack(self.get_inner())
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        };
-        
-        // Create capsule name
-        let name = CString::new("dltensor")
-            .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>("Failed to create capsule name"))?;
-        
-        // Create PyCapsule with destructor
-        // The destructor will be called when the capsule is deleted
-        let capsule_ptr = unsafe {
-            ffi::PyCapsule_New(
-                tensor_ptr as *mut c_void,
-                name.as_ptr(),
-                Some(dlpack_capsule_destructor),
-            )
-        };
-        
-        if capsule_ptr.is_null() {
-            // Cleanup on error
-            unsafe {
-                delete_dlpack_tensor(tensor_ptr);
-            }
-            return Err(PyErr::fetch(py));
-        }
-        
-        // Wrap in Py<PyAny>
-        unsafe {
-            Ok(Py::from_owned_ptr(py, capsule_ptr))
-        }
+    /// Returns a "dltensor" PyCapsule holding a DLManagedTensor, or a
+    /// "dltensor_versioned" capsule when `max_version` allows DLPack 1.x.
+    #[pyo3(signature = (*, stream=None, max_version=None, dl_device=None, copy=None))]
+    fn __dlpack__(
+        &self,
+        py: Python,
+        stream: Option<&Bound<'_, PyAny>>,
+        max_version: Option<(u32, u32)>,
+        dl_device: Option<(i32, i32)>,
+        copy: Option<bool>,
+    ) -> PyResult<Py<PyAny>> {
+        crate::dlpack::to_capsule(py, &self.inner, stream, max_version, dl_device, copy)
     }
     
     /// DLPack protocol - return device information
@@ -683,556 +698,397 @@ impl PyArray {
         let tuple = PyTuple::new(py, [device_type, device_id])?;
         Ok(tuple.into())
     }
+
+    /// NumPy ufunc protocol - run `np.sin(a)`, `np.add.reduce(a)` and so on
+    /// with raptors kernels
+    ///
+    /// Returns `NotImplemented` for ufuncs, methods or keyword arguments
+    /// raptors does not support.
+    #[pyo3(signature = (ufunc, method, *inputs, **kwargs))]
+    fn __array_ufunc__(
+        &self,
+        py: Python,
+        ufunc: &Bound<'_, PyAny>,
+        method: &str,
+        inputs: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Py<PyAny>> {
+        crate::numpy_dispatch::array_ufunc(py, ufunc, method, inputs, kwargs)
+    }
+
+    /// NumPy function protocol - run `np.concatenate`, `np.sort`, `np.mean`
+    /// and so on with raptors
+    ///
+    /// Returns `NotImplemented` for functions or arguments raptors does not
+    /// support.
+    fn __array_function__(
+        &self,
+        py: Python,
+        func: &Bound<'_, PyAny>,
+        types: &Bound<'_, PyAny>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: &Bound<'_, PyDict>,
+    ) -> PyResult<Py<PyAny>> {
+        crate::numpy_dispatch::array_function(py, func, types, args, kwargs)
+    }
+
+    /// Array API namespace of raptors arrays
+    #[pyo3(signature = (*, api_version=None))]
+    fn __array_namespace__(&self, py: Python, api_version: Option<&str>) -> PyResult<Py<PyAny>> {
+        array_api::namespace(py, api_version)
+    }
     
-    /// Addition operator
-    fn __add__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        // Try to extract as PyArray first
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = add(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            // Scalar addition: array + scalar
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = add(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for addition"
-            ))
+    /// Device the array lives on (always the CPU)
+    #[getter]
+    fn device(&self) -> &'static str {
+        "cpu"
+    }
+    
+    /// The array itself; "cpu" is the only device
+    #[pyo3(signature = (device, /, *, stream=None))]
+    fn to_device(slf: Py<Self>, device: &str, stream: Option<&Bound<'_, PyAny>>) -> PyResult<Py<Self>> {
+        if device != "cpu" || stream.is_some_and(|s| !s.is_none()) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                format!("unsupported device {}", device)
+            ));
         }
+        Ok(slf)
     }
     
-    /// Subtraction operator
-    fn __sub__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = subtract(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = subtract(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for subtraction"
-            ))
+    /// Transpose of a two-dimensional array
+    #[getter(T)]
+    fn transposed(&self) -> PyResult<Self> {
+        if self.get_inner().ndim() != 2 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "T is only defined for two-dimensional arrays; use mT for stacks of matrices"
+            ));
         }
+        self.transpose()
+    }
+    
+    /// View with the last two axes swapped
+    #[getter(mT)]
+    fn matrix_transposed(slf: PyRef<'_, Self>) -> PyResult<Self> {
+        array_api::matrix_transpose(slf)
+    }
+    
+    /// Addition operator
+    fn __add__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Add, slf.as_any(), other)
+    }
+    
+    /// Subtraction operator
+    fn __sub__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Subtract, slf.as_any(), other)
     }
     
     /// Multiplication operator
-    fn __mul__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = multiply(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = multiply(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for multiplication"
-            ))
-        }
+    fn __mul__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Multiply, slf.as_any(), other)
     }
     
     /// True division operator
-    fn __truediv__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = divide(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = divide(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for division"
-            ))
+    fn __truediv__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Divide, slf.as_any(), other)
+    }
+    
+    /// Floor division operator
+    fn __floordiv__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::FloorDivide, slf.as_any(), other)
+    }
+    
+    /// Remainder operator
+    fn __mod__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Remainder, slf.as_any(), other)
+    }
+    
+    /// Power operator
+    fn __pow__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, modulo: Option<&Bound<'_, PyAny>>) -> PyResult<Py<PyAny>> {
+        if modulo.is_some_and(|m| !m.is_none()) {
+            return Ok(slf.py().NotImplemented());
         }
+        array_api::binary_operator(slf.py(), BinaryOp::Pow, slf.as_any(), other)
+    }
+    
+    /// Matrix multiplication operator
+    fn __matmul__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::matmul_operator(slf.py(), slf.as_any(), other)
+    }
+    
+    /// Bitwise and operator
+    fn __and__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseAnd, slf.as_any(), other)
+    }
+    
+    /// Bitwise or operator
+    fn __or__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseOr, slf.as_any(), other)
+    }
+    
+    /// Bitwise xor operator
+    fn __xor__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseXor, slf.as_any(), other)
+    }
+    
+    /// Left shift operator
+    fn __lshift__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseLeftShift, slf.as_any(), other)
+    }
+    
+    /// Right shift operator
+    fn __rshift__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseRightShift, slf.as_any(), other)
     }
     
     /// Equality operator
-    fn __eq__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = equal(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            // For non-array types, return False array
-            let shape = self.get_inner().shape().to_vec();
-            let bool_dtype = DType::new(NpyType::Bool);
-            let result = zeros(shape, bool_dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        }
+    fn __eq__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Equal, slf.as_any(), other)
     }
     
     /// Less than operator
-    fn __lt__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = less(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = less(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for comparison"
-            ))
-        }
+    fn __lt__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Less, slf.as_any(), other)
     }
     
     /// Greater than operator
-    fn __gt__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = greater(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = greater(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for comparison"
-            ))
-        }
+    fn __gt__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Greater, slf.as_any(), other)
     }
     
     /// Not equal operator
-    fn __ne__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = not_equal(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            // For non-array types, return True array
-            let shape = self.get_inner().shape().to_vec();
-            let bool_dtype = DType::new(NpyType::Bool);
-            let mut result = ones(shape, bool_dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        }
+    fn __ne__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::NotEqual, slf.as_any(), other)
     }
     
     /// Less than or equal operator
-    fn __le__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = less_equal(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = less_equal(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for comparison"
-            ))
-        }
+    fn __le__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::LessEqual, slf.as_any(), other)
     }
     
     /// Greater than or equal operator
-    fn __ge__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(other_array) = other.downcast::<PyArray>() {
-            let result = greater_equal(self.get_inner(), PyArray::get_inner_from_bound(&other_array))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = greater_equal(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for comparison"
-            ))
-        }
+    fn __ge__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::GreaterEqual, slf.as_any(), other)
     }
     
     /// In-place addition operator
-    fn __iadd__(&mut self, other: &Bound<'_, PyAny>) -> PyResult<()> {
-        let result = if let Ok(arr) = other.downcast::<PyArray>() {
-            add(self.get_inner(), PyArray::get_inner_from_bound(&arr))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else if let Ok(val) = other.extract::<f64>() {
-            // Create scalar array
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            add(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for in-place addition"
-            ));
-        };
-        // Try to modify in-place if we have unique ownership
-        if let Some(inner_mut) = Arc::get_mut(&mut self.inner) {
-            // Copy result data into existing array
-            if inner_mut.shape() == result.shape() && inner_mut.dtype().type_() == result.dtype().type_() {
-                unsafe {
-                    std::ptr::copy_nonoverlapping(
-                        result.data_ptr(),
-                        inner_mut.data_ptr_mut(),
-                        inner_mut.size() * inner_mut.itemsize(),
-                    );
-                }
-                Ok(())
-            } else {
-                // Shape or dtype mismatch - replace the array
-                self.inner = Arc::new(result);
-                Ok(())
-            }
-        } else {
-            // No unique ownership - replace the array
-            self.inner = Arc::new(result);
-            Ok(())
-        }
+    fn __iadd__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::Add, other)
     }
     
     /// In-place subtraction operator
-    fn __isub__(&mut self, other: &Bound<'_, PyAny>) -> PyResult<()> {
-        let result = if let Ok(arr) = other.downcast::<PyArray>() {
-            subtract(self.get_inner(), PyArray::get_inner_from_bound(&arr))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            subtract(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for in-place subtraction"
-            ));
-        };
-        if let Some(inner_mut) = Arc::get_mut(&mut self.inner) {
-            if inner_mut.shape() == result.shape() && inner_mut.dtype().type_() == result.dtype().type_() {
-                unsafe {
-                    std::ptr::copy_nonoverlapping(
-                        result.data_ptr(),
-                        inner_mut.data_ptr_mut(),
-                        inner_mut.size() * inner_mut.itemsize(),
-                    );
-                }
-                Ok(())
-            } else {
-                self.inner = Arc::new(result);
-                Ok(())
-            }
-        } else {
-            self.inner = Arc::new(result);
-            Ok(())
-        }
+    fn __isub__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::Subtract, other)
     }
     
     /// In-place multiplication operator
-    fn __imul__(&mut self, other: &Bound<'_, PyAny>) -> PyResult<()> {
-        let result = if let Ok(arr) = other.downcast::<PyArray>() {
-            multiply(self.get_inner(), PyArray::get_inner_from_bound(&arr))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            multiply(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for in-place multiplication"
-            ));
-        };
-        if let Some(inner_mut) = Arc::get_mut(&mut self.inner) {
-            if inner_mut.shape() == result.shape() && inner_mut.dtype().type_() == result.dtype().type_() {
-                unsafe {
-                    std::ptr::copy_nonoverlapping(
-                        result.data_ptr(),
-                        inner_mut.data_ptr_mut(),
-                        inner_mut.size() * inner_mut.itemsize(),
-                    );
-                }
-                Ok(())
-            } else {
-                self.inner = Arc::new(result);
-                Ok(())
-            }
-        } else {
-            self.inner = Arc::new(result);
-            Ok(())
-        }
+    fn __imul__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::Multiply, other)
     }
     
     /// In-place true division operator
-    fn __itruediv__(&mut self, other: &Bound<'_, PyAny>) -> PyResult<()> {
-        let result = if let Ok(arr) = other.downcast::<PyArray>() {
-            divide(self.get_inner(), PyArray::get_inner_from_bound(&arr))
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            divide(self.get_inner(), &scalar_array)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?
-        } else {
-            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for in-place division"
-            ));
-        };
-        if let Some(inner_mut) = Arc::get_mut(&mut self.inner) {
-            if inner_mut.shape() == result.shape() && inner_mut.dtype().type_() == result.dtype().type_() {
-                unsafe {
-                    std::ptr::copy_nonoverlapping(
-                        result.data_ptr(),
-                        inner_mut.data_ptr_mut(),
-                        inner_mut.size() * inner_mut.itemsize(),
-                    );
-                }
-                Ok(())
-            } else {
-                self.inner = Arc::new(result);
-                Ok(())
-            }
-        } else {
-            self.inner = Arc::new(result);
-            Ok(())
-        }
+    fn __itruediv__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::Divide, other)
+    }
+    
+    /// In-place floor division operator
+    fn __ifloordiv__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::FloorDivide, other)
+    }
+    
+    /// In-place remainder operator
+    fn __imod__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::Remainder, other)
+    }
+    
+    /// In-place power operator
+    fn __ipow__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, _modulo: Option<&Bound<'_, PyAny>>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::Pow, other)
+    }
+    
+    /// In-place bitwise and operator
+    fn __iand__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::BitwiseAnd, other)
+    }
+    
+    /// In-place bitwise or operator
+    fn __ior__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::BitwiseOr, other)
+    }
+    
+    /// In-place bitwise xor operator
+    fn __ixor__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::BitwiseXor, other)
+    }
+    
+    /// In-place left shift operator
+    fn __ilshift__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::BitwiseLeftShift, other)
+    }
+    
+    /// In-place right shift operator
+    fn __irshift__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        Self::in_place(slf, BinaryOp::BitwiseRightShift, other)
     }
     
     /// Right-hand addition (scalar + array)
-    fn __radd__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        // Try to extract scalar value
-        if let Ok(val) = other.extract::<f64>() {
-            // Create scalar array with same shape
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            // Fill with scalar value
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = add(&scalar_array, self.get_inner())
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for right-hand addition"
-            ))
-        }
+    fn __radd__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Add, other, slf.as_any())
     }
     
     /// Right-hand subtraction (scalar - array)
-    fn __rsub__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = subtract(&scalar_array, self.get_inner())
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for right-hand subtraction"
-            ))
-        }
+    fn __rsub__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Subtract, other, slf.as_any())
     }
     
     /// Right-hand multiplication (scalar * array)
-    fn __rmul__(&self, other: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(val) = other.extract::<f64>() {
-            let shape = self.get_inner().shape().to_vec();
-            let dtype = self.get_inner().dtype().clone();
-            let mut scalar_array = empty(shape, dtype)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            unsafe {
-                let ptr = scalar_array.data_ptr_mut() as *mut f64;
-                for i in 0..scalar_array.size() {
-                    *ptr.add(i) = val;
-                }
-            }
-            let result = multiply(&scalar_array, self.get_inner())
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-            Ok(PyArray {
-                inner: Arc::new(result),
-            })
-        } else {
-            Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
-                "Unsupported type for right-hand multiplication"
-            ))
+    fn __rmul__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Multiply, other, slf.as_any())
+    }
+    
+    /// Right-hand true division (scalar / array)
+    fn __rtruediv__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Divide, other, slf.as_any())
+    }
+    
+    /// Right-hand floor division (scalar // array)
+    fn __rfloordiv__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::FloorDivide, other, slf.as_any())
+    }
+    
+    /// Right-hand remainder (scalar % array)
+    fn __rmod__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Remainder, other, slf.as_any())
+    }
+    
+    /// Right-hand power (scalar ** array)
+    fn __rpow__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>, _modulo: Option<&Bound<'_, PyAny>>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::Pow, other, slf.as_any())
+    }
+    
+    /// Right-hand matrix multiplication
+    fn __rmatmul__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::matmul_operator(slf.py(), other, slf.as_any())
+    }
+    
+    /// Right-hand bitwise and
+    fn __rand__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseAnd, other, slf.as_any())
+    }
+    
+    /// Right-hand bitwise or
+    fn __ror__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseOr, other, slf.as_any())
+    }
+    
+    /// Right-hand bitwise xor
+    fn __rxor__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseXor, other, slf.as_any())
+    }
+    
+    /// Right-hand left shift
+    fn __rlshift__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseLeftShift, other, slf.as_any())
+    }
+    
+    /// Right-hand right shift
+    fn __rrshift__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        array_api::binary_operator(slf.py(), BinaryOp::BitwiseRightShift, other, slf.as_any())
+    }
+    
+    /// Negation operator
+    fn __neg__(slf: &Bound<'_, Self>) -> PyResult<Self> {
+        array_api::unary(slf.py(), UnaryOp::Negative, &Self::handle(slf))
+    }
+    
+    /// Unary plus operator
+    fn __pos__(slf: &Bound<'_, Self>) -> PyResult<Self> {
+        array_api::unary(slf.py(), UnaryOp::Positive, &Self::handle(slf))
+    }
+    
+    /// Absolute value
+    fn __abs__(slf: &Bound<'_, Self>) -> PyResult<Self> {
+        array_api::unary(slf.py(), UnaryOp::Abs, &Self::handle(slf))
+    }
+    
+    /// Bitwise inversion operator
+    fn __invert__(slf: &Bound<'_, Self>) -> PyResult<Self> {
+        array_api::unary(slf.py(), UnaryOp::BitwiseInvert, &Self::handle(slf))
+    }
+    
+    /// Truth value of a one-element array
+    fn __bool__(&self, py: Python) -> PyResult<bool> {
+        array_api::to_python_scalar(py, self.get_inner(), NpyType::Bool)?.extract(py)
+    }
+    
+    /// Python int of a one-element array
+    fn __int__(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let target = match self.get_inner().dtype().type_() {
+            NpyType::UByte | NpyType::UShort | NpyType::UInt | NpyType::ULong | NpyType::ULongLong => NpyType::ULongLong,
+            _ => NpyType::LongLong,
+        };
+        array_api::to_python_scalar(py, self.get_inner(), target)
+    }
+    
+    /// Python float of a one-element array
+    fn __float__(&self, py: Python) -> PyResult<Py<PyAny>> {
+        array_api::to_python_scalar(py, self.get_inner(), NpyType::Double)
+    }
+    
+    /// Python complex of a one-element array
+    fn __complex__(&self, py: Python) -> PyResult<Py<PyAny>> {
+        array_api::to_python_scalar(py, self.get_inner(), NpyType::CDouble)
+    }
+    
+    /// Index value of a one-element integer array
+    fn __index__(&self, py: Python) -> PyResult<Py<PyAny>> {
+        use NpyType::*;
+        if !matches!(self.get_inner().dtype().type_(), Byte | UByte | Short | UShort | Int | UInt | Long | ULong | LongLong | ULongLong) {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "only integer arrays can be used as an index"
+            ));
         }
+        self.__int__(py)
     }
 }
 
 impl PyArray {
+    /// Apply `op` in place: the result is cast back to the dtype of the
+    /// array and must keep its shape
+    fn in_place(slf: &Bound<'_, Self>, op: BinaryOp, other: &Bound<'_, PyAny>) -> PyResult<()> {
+        let result = array_api::binary(op, slf.as_any(), other)?;
+        let array = Self::handle(slf);
+        if result.get_inner().shape() != array.shape() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "result of shape {:?} cannot be stored in an array of shape {:?}",
+                result.get_inner().shape(),
+                array.shape()
+            )));
+        }
+        let result = slf.py()
+            .detach(|| raptors_core::array_api::astype(result.get_inner(), array.dtype()))
+            .map_err(array_api::api_error)?;
+        drop(array);
+        let mut this = slf.borrow_mut();
+        // Try to modify in-place if we have unique ownership
+        if let Some(inner_mut) = Arc::get_mut(&mut this.inner) {
+            if inner_mut.is_c_contiguous() && inner_mut.is_writeable() {
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        result.data_ptr(),
+                        inner_mut.data_ptr_mut(),
+                        inner_mut.size() * inner_mut.itemsize(),
+                    );
+                }
+                return Ok(());
+            }
+        }
+        // No unique ownership - replace the array
+        this.inner = Arc::new(result);
+        Ok(())
+    }
+    
     /// Extract value from pointer based on dtype
-    fn extract_value(&self, py: Python, ptr: *const u8) -> PyResult<Py<PyAny>> {
+    pub(crate) fn extract_value(&self, py: Python, ptr: *const u8) -> PyResult<Py<PyAny>> {
         use raptors_core::types::NpyType;
         use NpyType::*;
         match self.get_inner().dtype().type_() {
@@ -1296,61 +1152,5 @@ impl PyArray {
             ))
         }
     }
-    
-    /// Set value at pointer based on dtype (static method)
-    fn set_value_static(_py: Python, ptr: *mut u8, value: &Bound<'_, PyAny>, dtype: &raptors_core::types::DType) -> PyResult<()> {
-        use raptors_core::types::NpyType;
-        use NpyType::*;
-        match dtype.type_() {
-            Bool => {
-                let val: bool = value.extract()?;
-                unsafe { *(ptr as *mut bool) = val; }
-            }
-            Byte => {
-                let val: i8 = value.extract()?;
-                unsafe { *(ptr as *mut i8) = val; }
-            }
-            UByte => {
-                let val: u8 = value.extract()?;
-                unsafe { *ptr = val; }
-            }
-            Short => {
-                let val: i16 = value.extract()?;
-                unsafe { *(ptr as *mut i16) = val; }
-            }
-            UShort => {
-                let val: u16 = value.extract()?;
-                unsafe { *(ptr as *mut u16) = val; }
-            }
-            Int => {
-                let val: i32 = value.extract()?;
-                unsafe { *(ptr as *mut i32) = val; }
-            }
-            UInt => {
-                let val: u32 = value.extract()?;
-                unsafe { *(ptr as *mut u32) = val; }
-            }
-            Long | LongLong => {
-                let val: i64 = value.extract()?;
-                unsafe { *(ptr as *mut i64) = val; }
-            }
-            ULong | ULongLong => {
-                let val: u64 = value.extract()?;
-                unsafe { *(ptr as *mut u64) = val; }
-            }
-            Float | Half => {
-                let val: f32 = value.extract()?;
-                unsafe { *(ptr as *mut f32) = val; }
-            }
-            Double | LongDouble => {
-                let val: f64 = value.extract()?;
-                unsafe { *(ptr as *mut f64) = val; }
-            }
-            _ => return Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
-                "Dtype not supported for indexing"
-            ))
-        }
-        Ok(())
-    }
 }
 
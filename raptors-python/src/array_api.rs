@@ -0,0 +1,1346 @@
+//! Array API standard namespace
+//!
+//! `raptors.array_api` implements the 2023.12 revision of the Python array
+//! API standard, and `PyArray.__array_namespace__` returns it, so
+//! array-API-agnostic libraries can work on raptors arrays directly. The
+//! functions here only convert arguments; the semantics live in
+//! `raptors_core::array_api`. Kernels run with the GIL released once their
+//! inputs are held as `Handle`s.
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
+
+use pyo3::exceptions::{PyIndexError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{PyBool, PyComplex, PyDict, PyFloat, PyInt, PyList, PyTuple};
+use raptors_core::array::{Array, MeshIndexing, Scalar};
+use raptors_core::array_api::{self as api, ArrayApiError, BinaryOp, UnaryOp};
+use raptors_core::types::{DType, NpyType};
+use std::sync::Arc;
+
+use crate::array::{Handle, PyArray};
+use crate::dtype::PyDType;
+
+/// Array API versions `__array_namespace__` accepts
+const SUPPORTED_VERSIONS: [&str; 3] = ["2021.12", "2022.12", api::ARRAY_API_VERSION];
+
+pub(crate) fn api_error(e: ArrayApiError) -> PyErr {
+    match e {
+        ArrayApiError::UnsupportedDtype(_) | ArrayApiError::NoCommonType(..) => {
+            PyErr::new::<PyTypeError, _>(e.to_string())
+        }
+        ArrayApiError::InvalidAxis(..) => PyErr::new::<PyIndexError, _>(e.to_string()),
+        _ => PyErr::new::<PyValueError, _>(e.to_string()),
+    }
+}
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<PyValueError, _>(format!("{}", e))
+}
+
+pub(crate) fn wrap(array: Array) -> PyArray {
+    PyArray { inner: Arc::new(array) }
+}
+
+fn wrap_all(py: Python<'_>, arrays: Vec<Array>) -> PyResult<Py<PyTuple>> {
+    let items = arrays.into_iter().map(|a| Py::new(py, wrap(a))).collect::<PyResult<Vec<_>>>()?;
+    Ok(PyTuple::new(py, items)?.unbind())
+}
+
+fn py_dtype(dtype: DType) -> PyDType {
+    PyDType { inner: dtype }
+}
+
+/// Rust-owned handle to the array of `x`, usable without the GIL
+fn inner(x: &Bound<'_, PyArray>) -> Handle {
+    PyArray::handle(x)
+}
+
+/// The only device: the CPU
+fn check_device(device: Option<&Bound<'_, PyAny>>) -> PyResult<()> {
+    match device {
+        Some(device) if !device.is_none() && device.extract::<String>().ok().as_deref() != Some("cpu") => {
+            Err(value_error(format!("unsupported device {}", device)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Python scalar argument, or `None` for anything else
+fn python_scalar(value: &Bound<'_, PyAny>) -> PyResult<Option<Scalar>> {
+    let is_number = value.is_instance_of::<PyBool>()
+        || value.is_instance_of::<PyInt>()
+        || value.is_instance_of::<PyFloat>()
+        || value.is_instance_of::<PyComplex>();
+    if !is_number {
+        return Ok(None);
+    }
+    Ok(Some(crate::creation::extract_scalar(value)?.0))
+}
+
+/// Array argument; Python scalars become zero-dimensional arrays of the
+/// dtype they take next to `like`
+fn array_arg(value: &Bound<'_, PyAny>, like: Option<&DType>) -> PyResult<Handle> {
+    if let Ok(array) = value.cast::<PyArray>() {
+        return Ok(inner(array));
+    }
+    match (python_scalar(value)?, like) {
+        (Some(scalar), Some(like)) => Ok(Handle::from(api::scalar_array(&scalar, like).map_err(api_error)?)),
+        (Some(scalar), None) => Ok(Handle::from(api::asarray_scalars(&[scalar], vec![], None).map_err(api_error)?)),
+        (None, _) => Err(PyErr::new::<PyTypeError, _>(format!(
+            "expected an array, got '{}'",
+            value.get_type().name()?
+        ))),
+    }
+}
+
+/// Two operands, at least one of them an array
+fn operands(x1: &Bound<'_, PyAny>, x2: &Bound<'_, PyAny>) -> PyResult<(Handle, Handle)> {
+    match (x1.cast::<PyArray>(), x2.cast::<PyArray>()) {
+        (Ok(a), Ok(b)) => Ok((inner(a), inner(b))),
+        (Ok(a), Err(_)) => {
+            let a = inner(a);
+            let b = array_arg(x2, Some(a.dtype()))?;
+            Ok((a, b))
+        }
+        (Err(_), Ok(b)) => {
+            let b = inner(b);
+            Ok((array_arg(x1, Some(b.dtype()))?, b))
+        }
+        (Err(_), Err(_)) => Err(PyErr::new::<PyTypeError, _>("at least one argument must be an array")),
+    }
+}
+
+/// Apply an element-wise function of two operands
+pub(crate) fn binary(op: BinaryOp, x1: &Bound<'_, PyAny>, x2: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+    let (a, b) = operands(x1, x2)?;
+    x1.py().detach(|| api::binary(op, &a, &b)).map(wrap).map_err(api_error)
+}
+
+/// Apply an element-wise function of one array
+pub(crate) fn unary(py: Python<'_>, op: UnaryOp, x: &Handle) -> PyResult<PyArray> {
+    py.detach(|| api::unary(op, x)).map(wrap).map_err(api_error)
+}
+
+/// Binary operator of `PyArray`: `NotImplemented` for operands that are
+/// neither arrays nor Python scalars, so Python tries the other operand
+pub(crate) fn binary_operator(
+    py: Python<'_>,
+    op: BinaryOp,
+    lhs: &Bound<'_, PyAny>,
+    rhs: &Bound<'_, PyAny>,
+) -> PyResult<Py<PyAny>> {
+    let supported = |value: &Bound<'_, PyAny>| -> PyResult<bool> {
+        Ok(value.is_instance_of::<PyArray>() || python_scalar(value)?.is_some())
+    };
+    if !supported(lhs)? || !supported(rhs)? {
+        return Ok(py.NotImplemented());
+    }
+    Ok(Py::new(py, binary(op, lhs, rhs)?)?.into_any())
+}
+
+/// Matrix multiplication operator of `PyArray`: `NotImplemented` unless
+/// both operands are arrays
+pub(crate) fn matmul_operator(py: Python<'_>, lhs: &Bound<'_, PyAny>, rhs: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+    match (lhs.cast::<PyArray>(), rhs.cast::<PyArray>()) {
+        (Ok(a), Ok(b)) => {
+            let (a, b) = (inner(a), inner(b));
+            let product = py.detach(|| api::matmul(&a, &b));
+            Ok(Py::new(py, wrap(product.map_err(api_error)?))?.into_any())
+        }
+        _ => Ok(py.NotImplemented()),
+    }
+}
+
+/// Python scalar of the single element of `x`, for `__bool__`, `__int__`,
+/// `__float__`, `__complex__` and `__index__`
+pub(crate) fn to_python_scalar(py: Python<'_>, x: &Array, target: NpyType) -> PyResult<Py<PyAny>> {
+    if x.size() != 1 {
+        return Err(value_error(format!(
+            "only arrays with one element can be converted to Python scalars (got shape {:?})",
+            x.shape()
+        )));
+    }
+    let converted = api::astype(x, &DType::new(target)).map_err(api_error)?;
+    // SAFETY: the converted array holds one element of the target type
+    unsafe {
+        Ok(match target {
+            NpyType::Bool => PyBool::new(py, *converted.data_ptr() != 0).to_owned().into_any().unbind(),
+            NpyType::LongLong => (*(converted.data_ptr() as *const i64)).into_pyobject(py)?.into_any().unbind(),
+            NpyType::ULongLong => (*(converted.data_ptr() as *const u64)).into_pyobject(py)?.into_any().unbind(),
+            NpyType::CDouble => {
+                let parts = converted.data_ptr() as *const f64;
+                PyComplex::from_doubles(py, *parts, *parts.add(1)).into_any().unbind()
+            }
+            _ => (*(converted.data_ptr() as *const f64)).into_pyobject(py)?.into_any().unbind(),
+        })
+    }
+}
+
+/// `axis` argument: `None`, an int or a sequence of ints
+pub(crate) fn axes_arg(axis: Option<&Bound<'_, PyAny>>) -> PyResult<Option<Vec<isize>>> {
+    match axis {
+        None => Ok(None),
+        Some(axis) if axis.is_none() => Ok(None),
+        Some(axis) => match axis.extract::<isize>() {
+            Ok(axis) => Ok(Some(vec![axis])),
+            Err(_) => Ok(Some(axis.extract::<Vec<isize>>()?)),
+        },
+    }
+}
+
+/// Int or sequence of ints
+fn ints_arg<T: for<'a, 'py> FromPyObject<'a, 'py>>(value: &Bound<'_, PyAny>) -> PyResult<Vec<T>> {
+    match value.extract::<T>() {
+        Ok(n) => Ok(vec![n]),
+        Err(_) => value.extract::<Vec<T>>(),
+    }
+}
+
+pub(crate) fn dtype_arg(dtype: Option<&PyDType>) -> Option<&DType> {
+    dtype.map(|dt| dt.get_inner())
+}
+
+/// Dtype of a dtype or array argument
+fn dtype_of(value: &Bound<'_, PyAny>) -> PyResult<DType> {
+    if let Ok(dtype) = value.cast::<PyDType>() {
+        return Ok(dtype.borrow().get_inner().clone());
+    }
+    if let Ok(array) = value.cast::<PyArray>() {
+        return Ok(PyArray::get_inner_from_bound(array).dtype().clone());
+    }
+    Err(PyErr::new::<PyTypeError, _>(format!("expected a dtype or an array, got '{}'", value.get_type().name()?)))
+}
+
+/// Shape and values of a Python scalar or nested sequence
+fn nested_scalars(value: &Bound<'_, PyAny>, depth: usize) -> PyResult<(Vec<i64>, Vec<Scalar>)> {
+    if depth > 32 {
+        return Err(value_error("array nesting too deep"));
+    }
+    if let Ok(array) = value.cast::<PyArray>() {
+        return nested_scalars(&array.call_method0("tolist")?, depth);
+    }
+    if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+        let mut shape: Option<Vec<i64>> = None;
+        let mut values = Vec::new();
+        let mut len = 0i64;
+        for item in value.try_iter()? {
+            let (item_shape, item_values) = nested_scalars(&item?, depth + 1)?;
+            match &shape {
+                Some(expected) if *expected != item_shape => {
+                    return Err(value_error("sequences of different lengths cannot form an array"))
+                }
+                None => shape = Some(item_shape),
+                _ => {}
+            }
+            values.extend(item_values);
+            len += 1;
+        }
+        let mut shape = shape.unwrap_or_default();
+        shape.insert(0, len);
+        return Ok((shape, values));
+    }
+    match python_scalar(value)? {
+        Some(scalar) => Ok((vec![], vec![scalar])),
+        None => Err(PyErr::new::<PyTypeError, _>(format!(
+            "cannot convert '{}' to an array",
+            value.get_type().name()?
+        ))),
+    }
+}
+
+/// Evenly spaced values within `[start, stop)`
+#[pyfunction]
+#[pyo3(signature = (start, /, stop=None, step=None, *, dtype=None, device=None))]
+fn arange(
+    start: &Bound<'_, PyAny>,
+    stop: Option<&Bound<'_, PyAny>>,
+    step: Option<&Bound<'_, PyAny>>,
+    dtype: Option<&PyDType>,
+    device: Option<&Bound<'_, PyAny>>,
+) -> PyResult<PyArray> {
+    check_device(device)?;
+    let stop = stop.filter(|s| !s.is_none());
+    let all_ints = [Some(start), stop, step].into_iter().flatten().all(|v| v.is_instance_of::<PyInt>());
+    let (start, stop) = match stop {
+        Some(stop) => (start.extract::<f64>()?, stop.extract::<f64>()?),
+        None => (0.0, start.extract::<f64>()?),
+    };
+    let step = step.map(|s| s.extract::<f64>()).transpose()?.unwrap_or(1.0);
+    let default = if all_ints { NpyType::LongLong } else { NpyType::Double };
+    let dtype = dtype_arg(dtype).cloned().unwrap_or_else(|| DType::new(default));
+    raptors_core::array::arange(start, stop, step, dtype).map(wrap).map_err(value_error)
+}
+
+/// Convert the input to an array
+///
+/// Raptors arrays are returned as they are unless a copy or another dtype
+/// is asked for. Buffer exporters, DLPack producers and objects with
+/// `__array_interface__` share memory when possible; Python scalars and
+/// nested sequences are copied.
+#[pyfunction]
+#[pyo3(signature = (obj, /, *, dtype=None, device=None, copy=None))]
+fn asarray(
+    py: Python<'_>,
+    obj: &Bound<'_, PyAny>,
+    dtype: Option<&PyDType>,
+    device: Option<&Bound<'_, PyAny>>,
+    copy: Option<bool>,
+) -> PyResult<Py<PyAny>> {
+    check_device(device)?;
+    let dtype = dtype_arg(dtype);
+    let (array, copied) = if let Ok(array) = obj.cast::<PyArray>() {
+        (Arc::clone(&array.borrow().inner), false)
+    } else if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() || python_scalar(obj)?.is_some() {
+        let (shape, values) = nested_scalars(obj, 0)?;
+        if copy == Some(false) {
+            return Err(value_error("converting a Python object requires a copy but copy=False"));
+        }
+        let array = api::asarray_scalars(&values, shape, dtype).map_err(api_error)?;
+        return Ok(Py::new(py, wrap(array))?.into_any());
+    } else if crate::buffer::is_buffer(obj) {
+        (crate::buffer::from_buffer(obj)?.inner, false)
+    } else if obj.hasattr("__dlpack__")? {
+        (crate::dlpack::from_dlpack(py, obj, None, copy)?.inner, copy == Some(true))
+    } else if obj.hasattr("__array_interface__")? {
+        (crate::numpy_interop::from_numpy(py, obj)?.inner, false)
+    } else {
+        return Err(PyErr::new::<PyTypeError, _>(format!(
+            "cannot convert '{}' to an array",
+            obj.get_type().name()?
+        )));
+    };
+    let converts = dtype.is_some_and(|dt| {
+        dt.type_() != array.dtype().type_() || dt.itemsize() != array.dtype().itemsize()
+    });
+    if (converts || copy == Some(true)) && !copied {
+        if copy == Some(false) {
+            return Err(value_error("changing the dtype requires a copy but copy=False"));
+        }
+        let target = dtype.cloned().unwrap_or_else(|| array.dtype().clone());
+        let converted = api::astype(&array, &target).map_err(api_error)?;
+        return Ok(Py::new(py, wrap(converted))?.into_any());
+    }
+    if obj.is_instance_of::<PyArray>() {
+        return Ok(obj.clone().unbind());
+    }
+    Ok(Py::new(py, PyArray { inner: array })?.into_any())
+}
+
+/// Shape argument: an int or a sequence of ints
+fn shape_arg(shape: &Bound<'_, PyAny>) -> PyResult<Vec<i64>> {
+    ints_arg::<i64>(shape)
+}
+
+fn filled(shape: &Bound<'_, PyAny>, value: i64, dtype: Option<&PyDType>) -> PyResult<PyArray> {
+    let dtype = dtype_arg(dtype).cloned().unwrap_or_else(|| api::default_dtypes().real_floating);
+    raptors_core::full(shape_arg(shape)?, value, dtype).map(wrap).map_err(value_error)
+}
+
+/// Uninitialized array of `shape`
+#[pyfunction]
+#[pyo3(signature = (shape, *, dtype=None, device=None))]
+fn empty(shape: &Bound<'_, PyAny>, dtype: Option<&PyDType>, device: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    check_device(device)?;
+    let dtype = dtype_arg(dtype).cloned().unwrap_or_else(|| api::default_dtypes().real_floating);
+    raptors_core::empty(shape_arg(shape)?, dtype).map(wrap).map_err(value_error)
+}
+
+/// Zero-filled array of `shape`
+#[pyfunction]
+#[pyo3(signature = (shape, *, dtype=None, device=None))]
+fn zeros(shape: &Bound<'_, PyAny>, dtype: Option<&PyDType>, device: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    check_device(device)?;
+    filled(shape, 0, dtype)
+}
+
+/// One-filled array of `shape`
+#[pyfunction]
+#[pyo3(signature = (shape, *, dtype=None, device=None))]
+fn ones(shape: &Bound<'_, PyAny>, dtype: Option<&PyDType>, device: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    check_device(device)?;
+    filled(shape, 1, dtype)
+}
+
+/// Fill value and the dtype it defaults to
+fn fill_value(value: &Bound<'_, PyAny>, dtype: Option<DType>) -> PyResult<(Scalar, DType)> {
+    let Some(scalar) = python_scalar(value)? else {
+        return Err(PyErr::new::<PyTypeError, _>("fill_value must be a bool, int, float or complex"));
+    };
+    let dtype = match dtype {
+        Some(dtype) => dtype,
+        None => api::asarray_scalars(std::slice::from_ref(&scalar), vec![], None).map_err(api_error)?.dtype().clone(),
+    };
+    Ok((scalar, dtype))
+}
+
+/// Array of `shape` filled with `fill_value`
+#[pyfunction]
+#[pyo3(signature = (shape, fill_value, *, dtype=None, device=None))]
+fn full(
+    shape: &Bound<'_, PyAny>,
+    fill_value: &Bound<'_, PyAny>,
+    dtype: Option<&PyDType>,
+    device: Option<&Bound<'_, PyAny>>,
+) -> PyResult<PyArray> {
+    check_device(device)?;
+    let (scalar, dtype) = self::fill_value(fill_value, dtype_arg(dtype).cloned())?;
+    raptors_core::full(shape_arg(shape)?, scalar, dtype).map(wrap).map_err(value_error)
+}
+
+fn like(x: &Bound<'_, PyArray>, dtype: Option<&PyDType>) -> (Vec<i64>, DType) {
+    let x = PyArray::get_inner_from_bound(x);
+    (x.shape().to_vec(), dtype_arg(dtype).cloned().unwrap_or_else(|| x.dtype().clone()))
+}
+
+/// Uninitialized array with the shape of `x`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, dtype=None, device=None))]
+fn empty_like(x: &Bound<'_, PyArray>, dtype: Option<&PyDType>, device: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    check_device(device)?;
+    let (shape, dtype) = like(x, dtype);
+    raptors_core::empty(shape, dtype).map(wrap).map_err(value_error)
+}
+
+/// Zero-filled array with the shape of `x`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, dtype=None, device=None))]
+fn zeros_like(x: &Bound<'_, PyArray>, dtype: Option<&PyDType>, device: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    check_device(device)?;
+    let (shape, dtype) = like(x, dtype);
+    raptors_core::full(shape, 0i64, dtype).map(wrap).map_err(value_error)
+}
+
+/// One-filled array with the shape of `x`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, dtype=None, device=None))]
+fn ones_like(x: &Bound<'_, PyArray>, dtype: Option<&PyDType>, device: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    check_device(device)?;
+    let (shape, dtype) = like(x, dtype);
+    raptors_core::full(shape, 1i64, dtype).map(wrap).map_err(value_error)
+}
+
+/// Array with the shape of `x` filled with `fill_value`
+#[pyfunction]
+#[pyo3(signature = (x, /, fill_value, *, dtype=None, device=None))]
+fn full_like(
+    x: &Bound<'_, PyArray>,
+    fill_value: &Bound<'_, PyAny>,
+    dtype: Option<&PyDType>,
+    device: Option<&Bound<'_, PyAny>>,
+) -> PyResult<PyArray> {
+    check_device(device)?;
+    let (shape, dtype) = like(x, dtype);
+    let (scalar, dtype) = self::fill_value(fill_value, Some(dtype))?;
+    raptors_core::full(shape, scalar, dtype).map(wrap).map_err(value_error)
+}
+
+/// Two-dimensional array with ones on the `k`-th diagonal
+#[pyfunction]
+#[pyo3(signature = (n_rows, n_cols=None, /, *, k=0, dtype=None, device=None))]
+fn eye(
+    n_rows: usize,
+    n_cols: Option<usize>,
+    k: i64,
+    dtype: Option<&PyDType>,
+    device: Option<&Bound<'_, PyAny>>,
+) -> PyResult<PyArray> {
+    check_device(device)?;
+    let dtype = dtype_arg(dtype).cloned().unwrap_or_else(|| api::default_dtypes().real_floating);
+    raptors_core::array::eye(n_rows, n_cols, k, dtype).map(wrap).map_err(value_error)
+}
+
+/// `num` evenly spaced samples over `[start, stop]`
+#[pyfunction]
+#[pyo3(signature = (start, stop, /, num, *, dtype=None, device=None, endpoint=true))]
+fn linspace(
+    start: &Bound<'_, PyAny>,
+    stop: &Bound<'_, PyAny>,
+    num: usize,
+    dtype: Option<&PyDType>,
+    device: Option<&Bound<'_, PyAny>>,
+    endpoint: bool,
+) -> PyResult<PyArray> {
+    check_device(device)?;
+    let defaults = api::default_dtypes();
+    let is_complex = start.is_instance_of::<PyComplex>() || stop.is_instance_of::<PyComplex>();
+    if !is_complex {
+        let dtype = dtype_arg(dtype).cloned().unwrap_or(defaults.real_floating);
+        return raptors_core::array::linspace(start.extract()?, stop.extract()?, num, endpoint, dtype)
+            .map(wrap)
+            .map_err(value_error);
+    }
+    let (a, b): (num_complex_pair::C, num_complex_pair::C) = (start.extract()?, stop.extract()?);
+    let divisions = if endpoint { num.saturating_sub(1) } else { num }.max(1) as f64;
+    let values: Vec<Scalar> = (0..num)
+        .map(|i| {
+            let t = i as f64 / divisions;
+            Scalar::Complex(raptors_core::types::Complex128::new(
+                a.0 + (b.0 - a.0) * t,
+                a.1 + (b.1 - a.1) * t,
+            ))
+        })
+        .collect();
+    let dtype = dtype_arg(dtype).cloned().unwrap_or(defaults.complex_floating);
+    api::asarray_scalars(&values, vec![num as i64], Some(&dtype)).map(wrap).map_err(api_error)
+}
+
+mod num_complex_pair {
+    use pyo3::prelude::*;
+    use pyo3::types::PyComplex;
+
+    /// Real and imaginary parts of a Python number
+    pub(super) struct C(pub f64, pub f64);
+
+    impl<'a, 'py> FromPyObject<'a, 'py> for C {
+        type Error = PyErr;
+
+        fn extract(value: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+            if let Ok(z) = value.cast::<PyComplex>() {
+                return Ok(C(z.real(), z.imag()));
+            }
+            Ok(C(value.extract()?, 0.0))
+        }
+    }
+}
+
+/// Coordinate matrices from one-dimensional coordinate arrays
+#[pyfunction]
+#[pyo3(signature = (*arrays, indexing="xy"))]
+fn meshgrid(py: Python<'_>, arrays: Vec<PyRef<'_, PyArray>>, indexing: &str) -> PyResult<Py<PyTuple>> {
+    let indexing = match indexing {
+        "xy" => MeshIndexing::Xy,
+        "ij" => MeshIndexing::Ij,
+        other => return Err(value_error(format!("indexing must be 'xy' or 'ij' (got '{}')", other))),
+    };
+    let inputs: Vec<&Array> = arrays.iter().map(|a| a.get_inner().as_ref()).collect();
+    let grids = raptors_core::array::meshgrid(&inputs, indexing, false).map_err(value_error)?;
+    wrap_all(py, grids)
+}
+
+/// Lower triangle of the last two axes of `x`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, k=0))]
+fn tril(x: PyRef<'_, PyArray>, k: i64) -> PyResult<PyArray> {
+    raptors_core::array::tril(x.get_inner(), k).map(wrap).map_err(value_error)
+}
+
+/// Upper triangle of the last two axes of `x`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, k=0))]
+fn triu(x: PyRef<'_, PyArray>, k: i64) -> PyResult<PyArray> {
+    raptors_core::array::triu(x.get_inner(), k).map(wrap).map_err(value_error)
+}
+
+/// Copy of `x` cast to `dtype`; with `copy=False`, `x` itself when it
+/// already has that dtype
+#[pyfunction]
+#[pyo3(signature = (x, dtype, /, *, copy=true, device=None))]
+fn astype(
+    py: Python<'_>,
+    x: &Bound<'_, PyArray>,
+    dtype: &PyDType,
+    copy: bool,
+    device: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    check_device(device)?;
+    let array = inner(x);
+    let target = dtype.get_inner();
+    if !copy && target.type_() == array.dtype().type_() && target.itemsize() == array.dtype().itemsize() {
+        return Ok(x.clone().into_any().unbind());
+    }
+    let converted = py.detach(|| api::astype(&array, target)).map_err(api_error)?;
+    Ok(Py::new(py, wrap(converted))?.into_any())
+}
+
+/// Whether `from_` casts to `to` under the promotion rules
+#[pyfunction]
+#[pyo3(signature = (from_, to, /))]
+fn can_cast(from_: &Bound<'_, PyAny>, to: &PyDType) -> PyResult<bool> {
+    api::can_cast(&dtype_of(from_)?, to.get_inner()).map_err(api_error)
+}
+
+/// Machine limits of a floating dtype
+#[pyclass(name = "finfo_object", module = "raptors.array_api", frozen)]
+struct FInfo {
+    #[pyo3(get)]
+    bits: usize,
+    #[pyo3(get)]
+    eps: f64,
+    #[pyo3(get)]
+    max: f64,
+    #[pyo3(get)]
+    min: f64,
+    #[pyo3(get)]
+    smallest_normal: f64,
+    #[pyo3(get)]
+    dtype: Py<PyDType>,
+}
+
+/// Machine limits of an integer dtype
+#[pyclass(name = "iinfo_object", module = "raptors.array_api", frozen)]
+struct IInfo {
+    #[pyo3(get)]
+    bits: usize,
+    #[pyo3(get)]
+    max: i128,
+    #[pyo3(get)]
+    min: i128,
+    #[pyo3(get)]
+    dtype: Py<PyDType>,
+}
+
+/// Machine limits of a floating dtype (or the dtype of an array)
+#[pyfunction]
+#[pyo3(signature = (r#type, /))]
+fn finfo(py: Python<'_>, r#type: &Bound<'_, PyAny>) -> PyResult<FInfo> {
+    let info = api::finfo(&dtype_of(r#type)?).map_err(api_error)?;
+    Ok(FInfo {
+        bits: info.bits,
+        eps: info.eps,
+        max: info.max,
+        min: info.min,
+        smallest_normal: info.smallest_normal,
+        dtype: Py::new(py, py_dtype(info.dtype))?,
+    })
+}
+
+/// Machine limits of an integer dtype (or the dtype of an array)
+#[pyfunction]
+#[pyo3(signature = (r#type, /))]
+fn iinfo(py: Python<'_>, r#type: &Bound<'_, PyAny>) -> PyResult<IInfo> {
+    let info = api::iinfo(&dtype_of(r#type)?).map_err(api_error)?;
+    Ok(IInfo { bits: info.bits, max: info.max, min: info.min, dtype: Py::new(py, py_dtype(info.dtype))? })
+}
+
+/// Whether `dtype` is of `kind`: a dtype, a kind name or a tuple of those
+#[pyfunction]
+#[pyo3(signature = (dtype, kind, /))]
+fn isdtype(dtype: &PyDType, kind: &Bound<'_, PyAny>) -> PyResult<bool> {
+    if let Ok(kinds) = kind.cast::<PyTuple>() {
+        for kind in kinds.iter() {
+            if isdtype(dtype, &kind)? {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+    if let Ok(other) = kind.cast::<PyDType>() {
+        return Ok(dtype.__eq__(other.as_any()));
+    }
+    let name: String = kind.extract()?;
+    api::isdtype(dtype.get_inner(), &name).map_err(api_error)
+}
+
+/// Dtype of the result of combining arrays and dtypes
+#[pyfunction]
+#[pyo3(signature = (*arrays_and_dtypes))]
+fn result_type(arrays_and_dtypes: &Bound<'_, PyTuple>) -> PyResult<PyDType> {
+    let dtypes = arrays_and_dtypes.iter().map(|value| dtype_of(&value)).collect::<PyResult<Vec<_>>>()?;
+    api::result_type(&dtypes).map(py_dtype).map_err(api_error)
+}
+
+macro_rules! unary_functions {
+    ($($name:ident => $op:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Element-wise `", stringify!($name), "` of `x`")]
+            #[pyfunction]
+            #[pyo3(signature = (x, /))]
+            fn $name(x: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+                unary(x.py(), UnaryOp::$op, &inner(x))
+            }
+        )*
+
+        fn add_unary_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+            $(m.add_function(wrap_pyfunction!($name, m)?)?;)*
+            Ok(())
+        }
+    };
+}
+
+macro_rules! binary_functions {
+    ($($name:ident => $op:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Element-wise `", stringify!($name), "` of `x1` and `x2`, broadcast together")]
+            #[pyfunction]
+            #[pyo3(signature = (x1, x2, /))]
+            fn $name(x1: &Bound<'_, PyAny>, x2: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+                binary(BinaryOp::$op, x1, x2)
+            }
+        )*
+
+        fn add_binary_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+            $(m.add_function(wrap_pyfunction!($name, m)?)?;)*
+            Ok(())
+        }
+    };
+}
+
+unary_functions! {
+    abs => Abs,
+    acos => Acos,
+    acosh => Acosh,
+    asin => Asin,
+    asinh => Asinh,
+    atan => Atan,
+    atanh => Atanh,
+    bitwise_invert => BitwiseInvert,
+    ceil => Ceil,
+    conj => Conj,
+    cos => Cos,
+    cosh => Cosh,
+    exp => Exp,
+    expm1 => Expm1,
+    floor => Floor,
+    imag => Imag,
+    isfinite => IsFinite,
+    isinf => IsInf,
+    isnan => IsNan,
+    log => Log,
+    log1p => Log1p,
+    log2 => Log2,
+    log10 => Log10,
+    logical_not => LogicalNot,
+    negative => Negative,
+    positive => Positive,
+    real => Real,
+    round => Round,
+    sign => Sign,
+    signbit => Signbit,
+    sin => Sin,
+    sinh => Sinh,
+    square => Square,
+    sqrt => Sqrt,
+    tan => Tan,
+    tanh => Tanh,
+    trunc => Trunc,
+}
+
+binary_functions! {
+    add => Add,
+    atan2 => Atan2,
+    bitwise_and => BitwiseAnd,
+    bitwise_left_shift => BitwiseLeftShift,
+    bitwise_or => BitwiseOr,
+    bitwise_right_shift => BitwiseRightShift,
+    bitwise_xor => BitwiseXor,
+    copysign => Copysign,
+    divide => Divide,
+    equal => Equal,
+    floor_divide => FloorDivide,
+    greater => Greater,
+    greater_equal => GreaterEqual,
+    hypot => Hypot,
+    less => Less,
+    less_equal => LessEqual,
+    logaddexp => LogAddExp,
+    logical_and => LogicalAnd,
+    logical_or => LogicalOr,
+    logical_xor => LogicalXor,
+    maximum => Maximum,
+    minimum => Minimum,
+    multiply => Multiply,
+    not_equal => NotEqual,
+    pow => Pow,
+    remainder => Remainder,
+    subtract => Subtract,
+}
+
+/// Clamp `x` to `[min, max]`; bounds may be arrays or Python scalars
+#[pyfunction]
+#[pyo3(signature = (x, /, min=None, max=None))]
+fn clip(x: &Bound<'_, PyArray>, min: Option<&Bound<'_, PyAny>>, max: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    let py = x.py();
+    let x = inner(x);
+    let bound = |value: Option<&Bound<'_, PyAny>>| -> PyResult<Option<Handle>> {
+        value.filter(|v| !v.is_none()).map(|v| array_arg(v, Some(x.dtype()))).transpose()
+    };
+    let (min, max) = (bound(min)?, bound(max)?);
+    py.detach(|| api::clip(&x, min.as_deref(), max.as_deref())).map(wrap).map_err(api_error)
+}
+
+/// Elements of `x1` where `condition` holds and of `x2` elsewhere
+#[pyfunction]
+#[pyo3(name = "where", signature = (condition, x1, x2, /))]
+fn where_(condition: &Bound<'_, PyArray>, x1: &Bound<'_, PyAny>, x2: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+    let (a, b) = operands(x1, x2)?;
+    let condition = inner(condition);
+    x1.py().detach(|| api::where_(&condition, &a, &b)).map(wrap).map_err(api_error)
+}
+
+/// Elements of `x` at `indices` along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, indices, /, *, axis=None))]
+fn take(x: &Bound<'_, PyArray>, indices: &Bound<'_, PyArray>, axis: Option<isize>) -> PyResult<PyArray> {
+    let (a, indices) = (inner(x), inner(indices));
+    x.py().detach(|| api::take(&a, &indices, axis)).map(wrap).map_err(api_error)
+}
+
+/// Matrix product of `x1` and `x2`
+#[pyfunction]
+#[pyo3(signature = (x1, x2, /))]
+fn matmul(x1: &Bound<'_, PyArray>, x2: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let (a, b) = (inner(x1), inner(x2));
+    x1.py().detach(|| api::matmul(&a, &b)).map(wrap).map_err(api_error)
+}
+
+/// View of `x` with its last two axes swapped
+#[pyfunction]
+#[pyo3(signature = (x, /))]
+pub(crate) fn matrix_transpose(x: PyRef<'_, PyArray>) -> PyResult<PyArray> {
+    api::matrix_transpose(x.get_inner()).map(wrap).map_err(api_error)
+}
+
+/// Tensor contraction of `x1` and `x2` over `axes`
+#[pyfunction]
+#[pyo3(signature = (x1, x2, /, *, axes=None))]
+fn tensordot(x1: &Bound<'_, PyArray>, x2: &Bound<'_, PyArray>, axes: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    let axes = match axes {
+        None => api::TensorAxes::Count(2),
+        Some(axes) => match axes.extract::<usize>() {
+            Ok(n) => api::TensorAxes::Count(n),
+            Err(_) => {
+                let (a, b): (Bound<'_, PyAny>, Bound<'_, PyAny>) = axes.extract()?;
+                api::TensorAxes::Pairs(ints_arg(&a)?, ints_arg(&b)?)
+            }
+        },
+    };
+    let (a, b) = (inner(x1), inner(x2));
+    x1.py().detach(|| api::tensordot(&a, &b, axes)).map(wrap).map_err(api_error)
+}
+
+/// Dot product of the vectors of `x1` and `x2` along `axis`
+#[pyfunction]
+#[pyo3(signature = (x1, x2, /, *, axis=-1))]
+fn vecdot(x1: &Bound<'_, PyArray>, x2: &Bound<'_, PyArray>, axis: isize) -> PyResult<PyArray> {
+    let (a, b) = (inner(x1), inner(x2));
+    x1.py().detach(|| api::vecdot(&a, &b, axis)).map(wrap).map_err(api_error)
+}
+
+/// Read-only views of `arrays` broadcast against each other
+#[pyfunction]
+#[pyo3(signature = (*arrays))]
+fn broadcast_arrays(py: Python<'_>, arrays: Vec<PyRef<'_, PyArray>>) -> PyResult<Py<PyList>> {
+    let inputs: Vec<Arc<Array>> = arrays.iter().map(|a| a.get_inner().clone()).collect();
+    let views = api::broadcast_arrays(&inputs).map_err(api_error)?;
+    let items = views.into_iter().map(|a| Py::new(py, wrap(a))).collect::<PyResult<Vec<_>>>()?;
+    Ok(PyList::new(py, items)?.unbind())
+}
+
+/// Read-only view of `x` broadcast to `shape`
+#[pyfunction]
+#[pyo3(signature = (x, /, shape))]
+fn broadcast_to(x: PyRef<'_, PyArray>, shape: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+    api::broadcast_to(x.get_inner(), &shape_arg(shape)?).map(wrap).map_err(api_error)
+}
+
+/// Join `arrays` along an existing axis (flattened if `axis` is `None`)
+#[pyfunction]
+#[pyo3(signature = (arrays, /, *, axis=Some(0)))]
+fn concat(arrays: Vec<PyRef<'_, PyArray>>, axis: Option<isize>) -> PyResult<PyArray> {
+    let inputs: Vec<&Array> = arrays.iter().map(|a| a.get_inner().as_ref()).collect();
+    api::concat(&inputs, axis).map(wrap).map_err(api_error)
+}
+
+/// View of `x` with a new axis of length one
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=0))]
+fn expand_dims(x: PyRef<'_, PyArray>, axis: isize) -> PyResult<PyArray> {
+    api::expand_dims(x.get_inner(), axis).map(wrap).map_err(api_error)
+}
+
+/// View of `x` reversed along `axis` (every axis if `None`)
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=None))]
+fn flip(x: PyRef<'_, PyArray>, axis: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    let axis = axes_arg(axis)?;
+    api::flip(x.get_inner(), axis.as_deref()).map(wrap).map_err(api_error)
+}
+
+/// View of `x` with the axes `source` moved to `destination`
+#[pyfunction]
+#[pyo3(signature = (x, source, destination, /))]
+fn moveaxis(x: PyRef<'_, PyArray>, source: &Bound<'_, PyAny>, destination: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+    api::moveaxis(x.get_inner(), &ints_arg(source)?, &ints_arg(destination)?).map(wrap).map_err(api_error)
+}
+
+/// View of `x` with its axes permuted
+#[pyfunction]
+#[pyo3(signature = (x, /, axes))]
+fn permute_dims(x: PyRef<'_, PyArray>, axes: Vec<isize>) -> PyResult<PyArray> {
+    api::permute_dims(x.get_inner(), &axes).map(wrap).map_err(api_error)
+}
+
+/// `x` with each element repeated along `axis` (flattened if `None`)
+#[pyfunction]
+#[pyo3(signature = (x, repeats, /, *, axis=None))]
+fn repeat(x: PyRef<'_, PyArray>, repeats: &Bound<'_, PyAny>, axis: Option<isize>) -> PyResult<PyArray> {
+    let counts: Vec<usize> = match repeats.cast::<PyArray>() {
+        Ok(array) => {
+            let values = api::astype(PyArray::get_inner_from_bound(array), &DType::new(NpyType::LongLong))
+                .map_err(api_error)?;
+            // SAFETY: astype returns a contiguous int64 array
+            let values = unsafe { values.to_vec::<i64>().map_err(value_error)? };
+            values.into_iter().map(|n| usize::try_from(n).map_err(value_error)).collect::<PyResult<_>>()?
+        }
+        Err(_) => vec![repeats.extract::<usize>()?],
+    };
+    let repeats = match (repeats.is_instance_of::<PyArray>(), counts.as_slice()) {
+        (false, &[count]) => api::Repeats::Scalar(count),
+        _ => api::Repeats::PerElement(&counts),
+    };
+    api::repeat(x.get_inner(), repeats, axis).map(wrap).map_err(api_error)
+}
+
+/// `x` with a new shape, as a view when the layout allows
+#[pyfunction]
+#[pyo3(signature = (x, /, shape, *, copy=None))]
+fn reshape(x: PyRef<'_, PyArray>, shape: &Bound<'_, PyAny>, copy: Option<bool>) -> PyResult<PyArray> {
+    api::reshape(x.get_inner(), &shape_arg(shape)?, copy).map(wrap).map_err(api_error)
+}
+
+/// `x` with its elements shifted along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, shift, *, axis=None))]
+fn roll(x: PyRef<'_, PyArray>, shift: &Bound<'_, PyAny>, axis: Option<&Bound<'_, PyAny>>) -> PyResult<PyArray> {
+    let axis = axes_arg(axis)?;
+    api::roll(x.get_inner(), &ints_arg(shift)?, axis.as_deref()).map(wrap).map_err(api_error)
+}
+
+/// View of `x` without the length-one axes `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, axis))]
+fn squeeze(x: PyRef<'_, PyArray>, axis: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+    api::squeeze(x.get_inner(), &ints_arg(axis)?).map(wrap).map_err(api_error)
+}
+
+/// Join arrays of one shape along a new axis
+#[pyfunction]
+#[pyo3(signature = (arrays, /, *, axis=0))]
+fn stack(arrays: Vec<PyRef<'_, PyArray>>, axis: isize) -> PyResult<PyArray> {
+    let inputs: Vec<&Array> = arrays.iter().map(|a| a.get_inner().as_ref()).collect();
+    api::stack(&inputs, axis).map(wrap).map_err(api_error)
+}
+
+/// `x` repeated along each axis
+#[pyfunction]
+#[pyo3(signature = (x, repetitions, /))]
+fn tile(x: PyRef<'_, PyArray>, repetitions: Vec<usize>) -> PyResult<PyArray> {
+    api::tile(x.get_inner(), &repetitions).map(wrap).map_err(api_error)
+}
+
+/// Views of the slices of `x` along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=0))]
+fn unstack(py: Python<'_>, x: PyRef<'_, PyArray>, axis: isize) -> PyResult<Py<PyTuple>> {
+    wrap_all(py, api::unstack(x.get_inner(), axis).map_err(api_error)?)
+}
+
+/// Indices of the largest elements along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=None, keepdims=false))]
+fn argmax(x: &Bound<'_, PyArray>, axis: Option<isize>, keepdims: bool) -> PyResult<PyArray> {
+    let a = inner(x);
+    x.py().detach(|| api::argmax(&a, axis, keepdims)).map(wrap).map_err(api_error)
+}
+
+/// Indices of the smallest elements along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=None, keepdims=false))]
+fn argmin(x: &Bound<'_, PyArray>, axis: Option<isize>, keepdims: bool) -> PyResult<PyArray> {
+    let a = inner(x);
+    x.py().detach(|| api::argmin(&a, axis, keepdims)).map(wrap).map_err(api_error)
+}
+
+/// Indices of the nonzero elements, one array per dimension
+#[pyfunction]
+#[pyo3(signature = (x, /))]
+fn nonzero(py: Python<'_>, x: &Bound<'_, PyArray>) -> PyResult<Py<PyTuple>> {
+    let a = inner(x);
+    wrap_all(py, py.detach(|| api::nonzero(&a)).map_err(api_error)?)
+}
+
+/// Indices at which to insert `x2` into the sorted array `x1`
+#[pyfunction]
+#[pyo3(signature = (x1, x2, /, *, side="left", sorter=None))]
+fn searchsorted(
+    x1: &Bound<'_, PyArray>,
+    x2: &Bound<'_, PyAny>,
+    side: &str,
+    sorter: Option<&Bound<'_, PyArray>>,
+) -> PyResult<PyArray> {
+    let side = match side {
+        "left" => api::SearchSide::Left,
+        "right" => api::SearchSide::Right,
+        other => return Err(value_error(format!("side must be 'left' or 'right' (got '{}')", other))),
+    };
+    let py = x1.py();
+    let x1 = inner(x1);
+    let x2 = array_arg(x2, Some(x1.dtype()))?;
+    let sorter = sorter.map(inner);
+    py.detach(|| api::searchsorted(&x1, &x2, side, sorter.as_deref())).map(wrap).map_err(api_error)
+}
+
+static UNIQUE_ALL: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static UNIQUE_COUNTS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static UNIQUE_INVERSE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+/// Named tuple type for the results of a `unique_*` function
+fn result_tuple<'py>(
+    py: Python<'py>,
+    cell: &'static PyOnceLock<Py<PyAny>>,
+    name: &str,
+    fields: &[&str],
+) -> PyResult<Bound<'py, PyAny>> {
+    let namedtuple = cell.get_or_try_init(py, || -> PyResult<_> {
+        let namedtuple = py.import("collections")?.getattr("namedtuple")?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("module", "raptors.array_api")?;
+        Ok(namedtuple.call((name, fields.to_vec()), Some(&kwargs))?.unbind())
+    })?;
+    Ok(namedtuple.bind(py).clone())
+}
+
+/// Unique values of `x` with their first indices, inverse indices and counts
+#[pyfunction]
+#[pyo3(signature = (x, /))]
+fn unique_all(py: Python<'_>, x: &Bound<'_, PyArray>) -> PyResult<Py<PyAny>> {
+    let a = inner(x);
+    let unique = py.detach(|| api::unique_all(&a)).map_err(api_error)?;
+    let fields = ["values", "indices", "inverse_indices", "counts"];
+    let tuple = result_tuple(py, &UNIQUE_ALL, "UniqueAllResult", &fields)?;
+    let items = (
+        wrap(unique.values),
+        wrap(unique.indices),
+        wrap(unique.inverse_indices),
+        wrap(unique.counts),
+    );
+    Ok(tuple.call1(items)?.unbind())
+}
+
+/// Unique values of `x` and their counts
+#[pyfunction]
+#[pyo3(signature = (x, /))]
+fn unique_counts(py: Python<'_>, x: &Bound<'_, PyArray>) -> PyResult<Py<PyAny>> {
+    let a = inner(x);
+    let (values, counts) = py.detach(|| api::unique_counts(&a)).map_err(api_error)?;
+    let tuple = result_tuple(py, &UNIQUE_COUNTS, "UniqueCountsResult", &["values", "counts"])?;
+    Ok(tuple.call1((wrap(values), wrap(counts)))?.unbind())
+}
+
+/// Unique values of `x` and the index into them of each element of `x`
+#[pyfunction]
+#[pyo3(signature = (x, /))]
+fn unique_inverse(py: Python<'_>, x: &Bound<'_, PyArray>) -> PyResult<Py<PyAny>> {
+    let a = inner(x);
+    let (values, inverse) = py.detach(|| api::unique_inverse(&a)).map_err(api_error)?;
+    let tuple = result_tuple(py, &UNIQUE_INVERSE, "UniqueInverseResult", &["values", "inverse_indices"])?;
+    Ok(tuple.call1((wrap(values), wrap(inverse)))?.unbind())
+}
+
+/// Sorted unique values of `x`
+#[pyfunction]
+#[pyo3(signature = (x, /))]
+fn unique_values(x: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let a = inner(x);
+    x.py().detach(|| api::unique_values(&a)).map(wrap).map_err(api_error)
+}
+
+/// Indices that sort `x` along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=-1, descending=false, stable=true))]
+fn argsort(x: &Bound<'_, PyArray>, axis: isize, descending: bool, stable: bool) -> PyResult<PyArray> {
+    let a = inner(x);
+    x.py().detach(|| api::argsort(&a, axis, descending, stable)).map(wrap).map_err(api_error)
+}
+
+/// Sorted copy of `x` along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=-1, descending=false, stable=true))]
+fn sort(x: &Bound<'_, PyArray>, axis: isize, descending: bool, stable: bool) -> PyResult<PyArray> {
+    let a = inner(x);
+    x.py().detach(|| api::sort(&a, axis, descending, stable)).map(wrap).map_err(api_error)
+}
+
+/// Cumulative sum of `x` along `axis`
+#[pyfunction]
+#[pyo3(signature = (x, /, *, axis=None, dtype=None, include_initial=false))]
+fn cumulative_sum(
+    x: &Bound<'_, PyArray>,
+    axis: Option<isize>,
+    dtype: Option<&PyDType>,
+    include_initial: bool,
+) -> PyResult<PyArray> {
+    let (a, dtype) = (inner(x), dtype_arg(dtype).cloned());
+    x.py()
+        .detach(|| api::cumulative_sum(&a, axis, dtype.as_ref(), include_initial))
+        .map(wrap)
+        .map_err(api_error)
+}
+
+macro_rules! reductions {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[pyfunction]
+            #[pyo3(signature = (x, /, *, axis=None, keepdims=false))]
+            fn $name(x: &Bound<'_, PyArray>, axis: Option<&Bound<'_, PyAny>>, keepdims: bool) -> PyResult<PyArray> {
+                let (a, axis) = (inner(x), axes_arg(axis)?);
+                x.py().detach(|| api::$name(&a, axis.as_deref(), keepdims)).map(wrap).map_err(api_error)
+            }
+        )*
+    };
+}
+
+reductions! {
+    /// Largest elements along `axis`
+    max,
+    /// Smallest elements along `axis`
+    min,
+    /// Arithmetic mean along `axis`
+    mean,
+    /// Whether every element along `axis` is true
+    all,
+    /// Whether any element along `axis` is true
+    any,
+}
+
+macro_rules! accumulations {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[pyfunction]
+            #[pyo3(signature = (x, /, *, axis=None, dtype=None, keepdims=false))]
+            fn $name(
+                x: &Bound<'_, PyArray>,
+                axis: Option<&Bound<'_, PyAny>>,
+                dtype: Option<&PyDType>,
+                keepdims: bool,
+            ) -> PyResult<PyArray> {
+                let (a, axis, dtype) = (inner(x), axes_arg(axis)?, dtype_arg(dtype).cloned());
+                x.py()
+                    .detach(|| api::$name(&a, axis.as_deref(), dtype.as_ref(), keepdims))
+                    .map(wrap)
+                    .map_err(api_error)
+            }
+        )*
+    };
+}
+
+accumulations! {
+    /// Sum along `axis`
+    sum,
+    /// Product along `axis`
+    prod,
+}
+
+macro_rules! deviations {
+    ($($(#[$meta:meta])* $rust:ident($name:literal) => $api:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[pyfunction]
+            #[pyo3(name = $name, signature = (x, /, *, axis=None, correction=0.0, keepdims=false))]
+            fn $rust(
+                x: &Bound<'_, PyArray>,
+                axis: Option<&Bound<'_, PyAny>>,
+                correction: f64,
+                keepdims: bool,
+            ) -> PyResult<PyArray> {
+                let (a, axis) = (inner(x), axes_arg(axis)?);
+                x.py().detach(|| api::$api(&a, axis.as_deref(), correction, keepdims)).map(wrap).map_err(api_error)
+            }
+        )*
+    };
+}
+
+deviations! {
+    /// Standard deviation along `axis`
+    std_("std") => std,
+    /// Variance along `axis`
+    var("var") => var,
+}
+
+/// Capabilities, devices and dtypes of the namespace
+#[pyclass(name = "Info", module = "raptors.array_api", frozen)]
+struct Info;
+
+#[pymethods]
+impl Info {
+    /// Optional features the namespace supports
+    fn capabilities<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let capabilities = PyDict::new(py);
+        capabilities.set_item("boolean indexing", true)?;
+        capabilities.set_item("data-dependent shapes", true)?;
+        Ok(capabilities)
+    }
+
+    /// The default device
+    fn default_device(&self) -> &'static str {
+        "cpu"
+    }
+
+    /// Default dtypes of each kind
+    #[pyo3(signature = (*, device=None))]
+    fn default_dtypes<'py>(&self, py: Python<'py>, device: Option<&Bound<'_, PyAny>>) -> PyResult<Bound<'py, PyDict>> {
+        check_device(device)?;
+        let defaults = api::default_dtypes();
+        let dtypes = PyDict::new(py);
+        dtypes.set_item("real floating", py_dtype(defaults.real_floating))?;
+        dtypes.set_item("complex floating", py_dtype(defaults.complex_floating))?;
+        dtypes.set_item("integral", py_dtype(defaults.integral))?;
+        dtypes.set_item("indexing", py_dtype(defaults.indexing))?;
+        Ok(dtypes)
+    }
+
+    /// Devices arrays can live on
+    fn devices(&self) -> Vec<&'static str> {
+        vec!["cpu"]
+    }
+
+    /// Supported dtypes by name, optionally only those of `kind`
+    #[pyo3(signature = (*, device=None, kind=None))]
+    fn dtypes<'py>(
+        &self,
+        py: Python<'py>,
+        device: Option<&Bound<'_, PyAny>>,
+        kind: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        check_device(device)?;
+        let dtypes = PyDict::new(py);
+        for dtype in api::api_dtypes() {
+            let dtype = py_dtype(dtype);
+            if let Some(kind) = kind.filter(|k| !k.is_none()) {
+                if !isdtype(&dtype, kind)? {
+                    continue;
+                }
+            }
+            dtypes.set_item(dtype.get_inner().name().to_string(), dtype)?;
+        }
+        Ok(dtypes)
+    }
+}
+
+/// Inspection object of the namespace
+#[pyfunction]
+fn __array_namespace_info__() -> Info {
+    Info
+}
+
+/// Check a requested version and return the namespace module
+pub(crate) fn namespace(py: Python<'_>, api_version: Option<&str>) -> PyResult<Py<PyAny>> {
+    if let Some(version) = api_version {
+        if !SUPPORTED_VERSIONS.contains(&version) {
+            return Err(value_error(format!("unsupported array API version '{}'", version)));
+        }
+    }
+    Ok(py.import("raptors.array_api")?.into_any().unbind())
+}
+
+/// Create the `raptors.array_api` submodule and register it with `parent`
+pub fn add_array_api_module(parent: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = parent.py();
+    let m = PyModule::new(py, "array_api")?;
+    m.add("__array_api_version__", api::ARRAY_API_VERSION)?;
+
+    // Constants
+    m.add("e", std::f64::consts::E)?;
+    m.add("inf", f64::INFINITY)?;
+    m.add("nan", f64::NAN)?;
+    m.add("pi", std::f64::consts::PI)?;
+    m.add("newaxis", py.None())?;
+
+    // Data types
+    for dtype in api::api_dtypes() {
+        m.add(dtype.name().to_string(), py_dtype(dtype))?;
+    }
+
+    // Creation functions
+    m.add_function(wrap_pyfunction!(arange, &m)?)?;
+    m.add_function(wrap_pyfunction!(asarray, &m)?)?;
+    m.add_function(wrap_pyfunction!(empty, &m)?)?;
+    m.add_function(wrap_pyfunction!(empty_like, &m)?)?;
+    m.add_function(wrap_pyfunction!(eye, &m)?)?;
+    m.add_function(wrap_pyfunction!(crate::dlpack::from_dlpack, &m)?)?;
+    m.add_function(wrap_pyfunction!(full, &m)?)?;
+    m.add_function(wrap_pyfunction!(full_like, &m)?)?;
+    m.add_function(wrap_pyfunction!(linspace, &m)?)?;
+    m.add_function(wrap_pyfunction!(meshgrid, &m)?)?;
+    m.add_function(wrap_pyfunction!(ones, &m)?)?;
+    m.add_function(wrap_pyfunction!(ones_like, &m)?)?;
+    m.add_function(wrap_pyfunction!(tril, &m)?)?;
+    m.add_function(wrap_pyfunction!(triu, &m)?)?;
+    m.add_function(wrap_pyfunction!(zeros, &m)?)?;
+    m.add_function(wrap_pyfunction!(zeros_like, &m)?)?;
+
+    // Data type functions
+    m.add_class::<FInfo>()?;
+    m.add_class::<IInfo>()?;
+    m.add_function(wrap_pyfunction!(astype, &m)?)?;
+    m.add_function(wrap_pyfunction!(can_cast, &m)?)?;
+    m.add_function(wrap_pyfunction!(finfo, &m)?)?;
+    m.add_function(wrap_pyfunction!(iinfo, &m)?)?;
+    m.add_function(wrap_pyfunction!(isdtype, &m)?)?;
+    m.add_function(wrap_pyfunction!(result_type, &m)?)?;
+
+    // Element-wise functions
+    add_unary_functions(&m)?;
+    add_binary_functions(&m)?;
+    m.add_function(wrap_pyfunction!(clip, &m)?)?;
+
+    // Indexing and linear algebra functions
+    m.add_function(wrap_pyfunction!(take, &m)?)?;
+    m.add_function(wrap_pyfunction!(matmul, &m)?)?;
+    m.add_function(wrap_pyfunction!(matrix_transpose, &m)?)?;
+    m.add_function(wrap_pyfunction!(tensordot, &m)?)?;
+    m.add_function(wrap_pyfunction!(vecdot, &m)?)?;
+
+    // Manipulation functions
+    m.add_function(wrap_pyfunction!(broadcast_arrays, &m)?)?;
+    m.add_function(wrap_pyfunction!(broadcast_to, &m)?)?;
+    m.add_function(wrap_pyfunction!(concat, &m)?)?;
+    m.add_function(wrap_pyfunction!(expand_dims, &m)?)?;
+    m.add_function(wrap_pyfunction!(flip, &m)?)?;
+    m.add_function(wrap_pyfunction!(moveaxis, &m)?)?;
+    m.add_function(wrap_pyfunction!(permute_dims, &m)?)?;
+    m.add_function(wrap_pyfunction!(repeat, &m)?)?;
+    m.add_function(wrap_pyfunction!(reshape, &m)?)?;
+    m.add_function(wrap_pyfunction!(roll, &m)?)?;
+    m.add_function(wrap_pyfunction!(squeeze, &m)?)?;
+    m.add_function(wrap_pyfunction!(stack, &m)?)?;
+    m.add_function(wrap_pyfunction!(tile, &m)?)?;
+    m.add_function(wrap_pyfunction!(unstack, &m)?)?;
+
+    // Searching, set and sorting functions
+    m.add_function(wrap_pyfunction!(argmax, &m)?)?;
+    m.add_function(wrap_pyfunction!(argmin, &m)?)?;
+    m.add_function(wrap_pyfunction!(nonzero, &m)?)?;
+    m.add_function(wrap_pyfunction!(searchsorted, &m)?)?;
+    m.add_function(wrap_pyfunction!(where_, &m)?)?;
+    m.add_function(wrap_pyfunction!(unique_all, &m)?)?;
+    m.add_function(wrap_pyfunction!(unique_counts, &m)?)?;
+    m.add_function(wrap_pyfunction!(unique_inverse, &m)?)?;
+    m.add_function(wrap_pyfunction!(unique_values, &m)?)?;
+    m.add_function(wrap_pyfunction!(argsort, &m)?)?;
+    m.add_function(wrap_pyfunction!(sort, &m)?)?;
+
+    // Statistical and utility functions
+    m.add_function(wrap_pyfunction!(cumulative_sum, &m)?)?;
+    m.add_function(wrap_pyfunction!(max, &m)?)?;
+    m.add_function(wrap_pyfunction!(mean, &m)?)?;
+    m.add_function(wrap_pyfunction!(min, &m)?)?;
+    m.add_function(wrap_pyfunction!(prod, &m)?)?;
+    m.add_function(wrap_pyfunction!(std_, &m)?)?;
+    m.add_function(wrap_pyfunction!(sum, &m)?)?;
+    m.add_function(wrap_pyfunction!(var, &m)?)?;
+    m.add_function(wrap_pyfunction!(all, &m)?)?;
+    m.add_function(wrap_pyfunction!(any, &m)?)?;
+
+    // Inspection
+    m.add_class::<Info>()?;
+    m.add_function(wrap_pyfunction!(__array_namespace_info__, &m)?)?;
+
+    parent.add_submodule(&m)?;
+    // Make `import raptors.array_api` work for an extension submodule
+    py.import("sys")?.getattr("modules")?.set_item("raptors.array_api", &m)?;
+    Ok(())
+}
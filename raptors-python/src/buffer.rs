@@ -0,0 +1,153 @@
+//! Python buffer protocol for PyArray
+//!
+//! Arrays export their memory to `memoryview` and other consumers through
+//! `__getbuffer__`, and `asarray` wraps the memory of any buffer exporter.
+//! Neither direction copies; format strings come from
+//! `raptors_core::buffer::FormatString`.
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
+
+use pyo3::exceptions::{PyBufferError, PyValueError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+use raptors_core::buffer::{export_buffer, import_buffer_with_owner};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use std::sync::Arc;
+
+use crate::array::PyArray;
+
+fn buffer_error(msg: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<PyBufferError, _>(format!("{}", msg))
+}
+
+fn has_flags(flags: c_int, wanted: c_int) -> bool {
+    flags & wanted == wanted
+}
+
+/// Format, shape and strides a `Py_buffer` points into, owned through its
+/// `internal` field until the buffer is released
+struct ExportedView {
+    format: CString,
+    shape: Vec<ffi::Py_ssize_t>,
+    strides: Vec<ffi::Py_ssize_t>,
+}
+
+/// `__getbuffer__`: fill `view` with the memory of `slf`
+///
+/// The view holds a reference to `slf`, which keeps the array's data alive.
+///
+/// # Safety
+/// `view` must point to a `Py_buffer` for the caller to fill.
+pub(crate) unsafe fn get_buffer(slf: Bound<'_, PyArray>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+    if view.is_null() {
+        return Err(buffer_error("view is null"));
+    }
+    let array = slf.borrow().get_inner().clone();
+    let info = export_buffer(&array).map_err(buffer_error)?;
+
+    if has_flags(flags, ffi::PyBUF_WRITABLE) && info.read_only {
+        return Err(buffer_error("array is not writeable"));
+    }
+    let c_contiguous = array.is_c_contiguous();
+    if has_flags(flags, ffi::PyBUF_C_CONTIGUOUS) && !c_contiguous {
+        return Err(buffer_error("array is not C-contiguous"));
+    }
+    if has_flags(flags, ffi::PyBUF_F_CONTIGUOUS) && !array.is_f_contiguous() {
+        return Err(buffer_error("array is not Fortran contiguous"));
+    }
+    if has_flags(flags, ffi::PyBUF_ANY_CONTIGUOUS) && !c_contiguous && !array.is_f_contiguous() {
+        return Err(buffer_error("array is not contiguous"));
+    }
+    // Consumers that do not take strides assume C order
+    if !has_flags(flags, ffi::PyBUF_STRIDES) && !c_contiguous {
+        return Err(buffer_error("array is not C-contiguous"));
+    }
+
+    let exported = Box::new(ExportedView {
+        format: CString::new(info.format).map_err(buffer_error)?,
+        shape: info.shape.iter().map(|&n| n as ffi::Py_ssize_t).collect(),
+        strides: info.strides.iter().map(|&s| s as ffi::Py_ssize_t).collect(),
+    });
+    let nd = has_flags(flags, ffi::PyBUF_ND);
+    (*view).buf = info.ptr as *mut c_void;
+    (*view).len = info.size as ffi::Py_ssize_t;
+    (*view).itemsize = array.itemsize() as ffi::Py_ssize_t;
+    (*view).readonly = info.read_only as c_int;
+    (*view).ndim = if nd { array.ndim() as c_int } else { 0 };
+    (*view).format = if has_flags(flags, ffi::PyBUF_FORMAT) { exported.format.as_ptr() as *mut _ } else { ptr::null_mut() };
+    (*view).shape = if nd { exported.shape.as_ptr() as *mut _ } else { ptr::null_mut() };
+    (*view).strides = if has_flags(flags, ffi::PyBUF_STRIDES) { exported.strides.as_ptr() as *mut _ } else { ptr::null_mut() };
+    (*view).suboffsets = ptr::null_mut();
+    (*view).internal = Box::into_raw(exported) as *mut c_void;
+    (*view).obj = slf.into_any().into_ptr();
+    Ok(())
+}
+
+/// `__releasebuffer__`: free what `get_buffer` allocated for `view`
+///
+/// # Safety
+/// `view` must have been filled by `get_buffer`.
+pub(crate) unsafe fn release_buffer(view: *mut ffi::Py_buffer) {
+    let internal = (*view).internal as *mut ExportedView;
+    if !internal.is_null() {
+        drop(Box::from_raw(internal));
+        (*view).internal = ptr::null_mut();
+    }
+}
+
+/// A buffer held from an exporter, released when the last array using its
+/// memory is dropped
+struct HeldBuffer(Box<ffi::Py_buffer>);
+
+// SAFETY: the buffer is only touched again to release it, with the GIL held
+unsafe impl Send for HeldBuffer {}
+unsafe impl Sync for HeldBuffer {}
+
+impl Drop for HeldBuffer {
+    fn drop(&mut self) {
+        Python::attach(|_| unsafe { ffi::PyBuffer_Release(&mut *self.0) });
+    }
+}
+
+/// Whether `obj` exports the buffer protocol
+pub(crate) fn is_buffer(obj: &Bound<'_, PyAny>) -> bool {
+    unsafe { ffi::PyObject_CheckBuffer(obj.as_ptr()) != 0 }
+}
+
+/// Array sharing the memory of the buffer exporter `obj`
+///
+/// Format, shape, strides and the read-only flag are taken from the
+/// buffer. Data in non-native byte order is copied.
+pub(crate) fn from_buffer(obj: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+    // SAFETY: Py_buffer is a plain C struct that PyObject_GetBuffer fills
+    let mut view: Box<ffi::Py_buffer> = Box::new(unsafe { std::mem::zeroed() });
+    if unsafe { ffi::PyObject_GetBuffer(obj.as_ptr(), &mut *view, ffi::PyBUF_RECORDS_RO) } == -1 {
+        return Err(PyErr::fetch(obj.py()));
+    }
+    let held = HeldBuffer(view);
+
+    let view = &*held.0;
+    let format = if view.format.is_null() {
+        "B".to_string()
+    } else {
+        unsafe { CStr::from_ptr(view.format) }.to_string_lossy().into_owned()
+    };
+    let ndim = view.ndim as usize;
+    let (shape, strides) = if view.shape.is_null() {
+        (vec![(view.len / view.itemsize.max(1)) as i64], None)
+    } else {
+        let shape = unsafe { std::slice::from_raw_parts(view.shape, ndim) };
+        let strides = (!view.strides.is_null())
+            .then(|| unsafe { std::slice::from_raw_parts(view.strides, ndim) }.iter().map(|&s| s as i64).collect());
+        (shape.iter().map(|&n| n as i64).collect(), strides)
+    };
+    let data = view.buf as *mut u8;
+    let read_only = view.readonly != 0;
+
+    // SAFETY: `held` keeps the exporter's memory valid until it is released
+    let array = unsafe { import_buffer_with_owner(data, &format, shape, strides, read_only, Arc::new(held)) }
+        .map_err(|e| PyErr::new::<PyValueError, _>(format!("{}", e)))?;
+    Ok(PyArray { inner: Arc::new(array) })
+}
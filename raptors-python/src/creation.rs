@@ -0,0 +1,475 @@
+//! Array creation Python bindings
+//!
+//! This module provides Python bindings for the array creation routines:
+//! numerical ranges, filled and `*_like` arrays, identity and triangular
+//! matrices, `meshgrid`, the `mgrid`/`ogrid` index objects,
+//! `fromfunction` and `asarray`.
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyComplex, PyFloat, PyInt, PySlice, PyString, PyTuple};
+use raptors_core::array::{self, GridRange, MeshIndexing, Order, Scalar};
+use raptors_core::types::{Complex128, NpyType};
+use raptors_core::{Array, ArrayBuilder, DType, MemoryOrder};
+use std::sync::Arc;
+
+use crate::array::PyArray;
+use crate::dtype::PyDType;
+
+/// Add creation functions to module
+pub fn add_creation_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    // Numerical ranges
+    m.add_function(wrap_pyfunction!(arange, m)?)?;
+    m.add_function(wrap_pyfunction!(linspace, m)?)?;
+    m.add_function(wrap_pyfunction!(logspace, m)?)?;
+    m.add_function(wrap_pyfunction!(geomspace, m)?)?;
+
+    // Filled and prototype-shaped arrays
+    m.add_function(wrap_pyfunction!(full, m)?)?;
+    m.add_function(wrap_pyfunction!(empty_like, m)?)?;
+    m.add_function(wrap_pyfunction!(zeros_like, m)?)?;
+    m.add_function(wrap_pyfunction!(ones_like, m)?)?;
+    m.add_function(wrap_pyfunction!(full_like, m)?)?;
+
+    // Matrices
+    m.add_function(wrap_pyfunction!(eye, m)?)?;
+    m.add_function(wrap_pyfunction!(identity, m)?)?;
+    m.add_function(wrap_pyfunction!(diag, m)?)?;
+    m.add_function(wrap_pyfunction!(tri, m)?)?;
+    m.add_function(wrap_pyfunction!(tril, m)?)?;
+    m.add_function(wrap_pyfunction!(triu, m)?)?;
+    m.add_function(wrap_pyfunction!(vander, m)?)?;
+
+    // Grids
+    m.add_function(wrap_pyfunction!(meshgrid, m)?)?;
+    m.add_function(wrap_pyfunction!(fromfunction, m)?)?;
+    m.add_class::<PyGridFactory>()?;
+    m.add("mgrid", PyGridFactory { sparse: false })?;
+    m.add("ogrid", PyGridFactory { sparse: true })?;
+
+    // Conversion
+    m.add_function(wrap_pyfunction!(asarray, m)?)?;
+
+    Ok(())
+}
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<PyValueError, _>(format!("{}", e))
+}
+
+fn wrap(array: Array) -> PyArray {
+    PyArray { inner: Arc::new(array) }
+}
+
+fn dtype_or(dtype: Option<&PyDType>, default: NpyType) -> DType {
+    match dtype {
+        Some(dt) => dt.get_inner().clone(),
+        None => DType::new(default),
+    }
+}
+
+fn parse_order(order: &str) -> PyResult<Order> {
+    match order {
+        "C" | "c" => Ok(Order::C),
+        "F" | "f" => Ok(Order::F),
+        "A" | "a" => Ok(Order::A),
+        "K" | "k" => Ok(Order::K),
+        _ => Err(value_error(format!("order must be one of 'C', 'F', 'A', or 'K' (got '{}')", order))),
+    }
+}
+
+/// Accept an int or a sequence of ints as a shape
+fn extract_shape(shape: &Bound<'_, PyAny>) -> PyResult<Vec<i64>> {
+    match shape.extract::<i64>() {
+        Ok(n) => Ok(vec![n]),
+        Err(_) => shape.extract::<Vec<i64>>(),
+    }
+}
+
+/// Convert a Python scalar to a fill value and its default dtype
+pub(crate) fn extract_scalar(value: &Bound<'_, PyAny>) -> PyResult<(Scalar, DType)> {
+    if value.is_instance_of::<PyBool>() {
+        Ok((Scalar::Bool(value.extract()?), DType::new(NpyType::Bool)))
+    } else if value.is_instance_of::<PyInt>() {
+        match value.extract::<i64>() {
+            Ok(i) => Ok((Scalar::Int(i), DType::new(NpyType::Long))),
+            Err(_) => Ok((Scalar::UInt(value.extract()?), DType::new(NpyType::ULong))),
+        }
+    } else if value.is_instance_of::<PyFloat>() {
+        Ok((Scalar::Float(value.extract()?), DType::new(NpyType::Double)))
+    } else if let Ok(z) = value.cast::<PyComplex>() {
+        Ok((Scalar::Complex(Complex128::new(z.real(), z.imag())), DType::new(NpyType::CDouble)))
+    } else if value.is_instance_of::<PyString>() {
+        let text: String = value.extract()?;
+        let dtype = DType::unicode_with_itemsize(4 * text.chars().count().max(1));
+        Ok((Scalar::Str(text), dtype))
+    } else {
+        Err(value_error("fill value must be a bool, int, float, complex or str"))
+    }
+}
+
+/// Whether a Python number is an integer
+fn is_int(value: &Bound<'_, PyAny>) -> bool {
+    value.is_instance_of::<PyInt>()
+}
+
+/// Return evenly spaced values within a given interval
+///
+/// `arange(stop)`, `arange(start, stop)` and `arange(start, stop, step)`
+/// behave as in NumPy. The default dtype is int64 when every argument is
+/// an integer and float64 otherwise; integer arguments give exact integer
+/// values.
+#[pyfunction]
+#[pyo3(signature = (start, stop=None, step=None, dtype=None))]
+fn arange(
+    start: &Bound<'_, PyAny>,
+    stop: Option<&Bound<'_, PyAny>>,
+    step: Option<&Bound<'_, PyAny>>,
+    dtype: Option<&PyDType>,
+) -> PyResult<PyArray> {
+    let integral = is_int(start) && stop.is_none_or(is_int) && step.is_none_or(is_int);
+    if integral {
+        let ints = || -> PyResult<(i64, i64, i64)> {
+            let step = step.map_or(Ok(1), |step| step.extract())?;
+            match stop {
+                Some(stop) => Ok((start.extract()?, stop.extract()?, step)),
+                None => Ok((0, start.extract()?, step)),
+            }
+        };
+        // Integers beyond i64 take the floating-point path below
+        if let Ok((begin, end, step)) = ints() {
+            let result = array::arange_int(begin, end, step, dtype_or(dtype, NpyType::Long)).map_err(value_error)?;
+            return Ok(wrap(result));
+        }
+    }
+    let (begin, end) = match stop {
+        Some(stop) => (start.extract::<f64>()?, stop.extract::<f64>()?),
+        None => (0.0, start.extract::<f64>()?),
+    };
+    let step = match step {
+        Some(step) => step.extract::<f64>()?,
+        None => 1.0,
+    };
+    let default = if integral { NpyType::Long } else { NpyType::Double };
+    let result = array::arange(begin, end, step, dtype_or(dtype, default)).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return evenly spaced numbers over a specified interval
+///
+/// With `retstep=True` returns `(samples, step)`.
+#[pyfunction]
+#[pyo3(signature = (start, stop, num=50, endpoint=true, retstep=false, dtype=None))]
+fn linspace(
+    py: Python<'_>,
+    start: f64,
+    stop: f64,
+    num: usize,
+    endpoint: bool,
+    retstep: bool,
+    dtype: Option<&PyDType>,
+) -> PyResult<Py<PyAny>> {
+    let (result, step) = array::linspace_retstep(start, stop, num, endpoint, dtype_or(dtype, NpyType::Double))
+        .map_err(value_error)?;
+    let result = Py::new(py, wrap(result))?;
+    if retstep {
+        Ok((result, step).into_pyobject(py)?.into_any().unbind())
+    } else {
+        Ok(result.into_any())
+    }
+}
+
+/// Return numbers spaced evenly on a log scale
+#[pyfunction]
+#[pyo3(signature = (start, stop, num=50, endpoint=true, base=10.0, dtype=None))]
+fn logspace(
+    start: f64,
+    stop: f64,
+    num: usize,
+    endpoint: bool,
+    base: f64,
+    dtype: Option<&PyDType>,
+) -> PyResult<PyArray> {
+    let result = array::logspace(start, stop, num, endpoint, base, dtype_or(dtype, NpyType::Double))
+        .map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return numbers spaced evenly on a log scale (a geometric progression)
+#[pyfunction]
+#[pyo3(signature = (start, stop, num=50, endpoint=true, dtype=None))]
+fn geomspace(start: f64, stop: f64, num: usize, endpoint: bool, dtype: Option<&PyDType>) -> PyResult<PyArray> {
+    let result =
+        array::geomspace(start, stop, num, endpoint, dtype_or(dtype, NpyType::Double)).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return a new array of given shape filled with `fill_value`
+///
+/// The default dtype follows the type of `fill_value`.
+#[pyfunction]
+#[pyo3(signature = (shape, fill_value, dtype=None, order="C"))]
+fn full(
+    shape: &Bound<'_, PyAny>,
+    fill_value: &Bound<'_, PyAny>,
+    dtype: Option<&PyDType>,
+    order: &str,
+) -> PyResult<PyArray> {
+    let shape = extract_shape(shape)?;
+    let (value, default) = extract_scalar(fill_value)?;
+    let order = match order {
+        "C" | "c" => MemoryOrder::C,
+        "F" | "f" => MemoryOrder::F,
+        _ => return Err(value_error(format!("order must be 'C' or 'F' (got '{}')", order))),
+    };
+    let result = ArrayBuilder::new()
+        .with_shape(shape)
+        .with_dtype(dtype.map(|dt| dt.get_inner().clone()).unwrap_or(default))
+        .with_order(order)
+        .with_fill_value(value)
+        .build()
+        .map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return a new uninitialized array with the same shape as `prototype`
+#[pyfunction]
+#[pyo3(signature = (prototype, dtype=None, order="K"))]
+fn empty_like(prototype: PyRef<'_, PyArray>, dtype: Option<&PyDType>, order: &str) -> PyResult<PyArray> {
+    let dtype = dtype.map(|dt| dt.get_inner().clone());
+    let result = array::empty_like(prototype.get_inner(), dtype, parse_order(order)?).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return an array of zeros with the same shape as `prototype`
+#[pyfunction]
+#[pyo3(signature = (prototype, dtype=None, order="K"))]
+fn zeros_like(prototype: PyRef<'_, PyArray>, dtype: Option<&PyDType>, order: &str) -> PyResult<PyArray> {
+    let dtype = dtype.map(|dt| dt.get_inner().clone());
+    let result = array::zeros_like(prototype.get_inner(), dtype, parse_order(order)?).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return an array of ones with the same shape as `prototype`
+#[pyfunction]
+#[pyo3(signature = (prototype, dtype=None, order="K"))]
+fn ones_like(prototype: PyRef<'_, PyArray>, dtype: Option<&PyDType>, order: &str) -> PyResult<PyArray> {
+    let dtype = dtype.map(|dt| dt.get_inner().clone());
+    let result = array::ones_like(prototype.get_inner(), dtype, parse_order(order)?).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return an array filled with `fill_value` with the same shape as `prototype`
+#[pyfunction]
+#[pyo3(signature = (prototype, fill_value, dtype=None, order="K"))]
+fn full_like(
+    prototype: PyRef<'_, PyArray>,
+    fill_value: &Bound<'_, PyAny>,
+    dtype: Option<&PyDType>,
+    order: &str,
+) -> PyResult<PyArray> {
+    let (value, _) = extract_scalar(fill_value)?;
+    let dtype = dtype.map(|dt| dt.get_inner().clone());
+    let result =
+        array::full_like(prototype.get_inner(), value, dtype, parse_order(order)?).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return a 2-D array with ones on the `k`-th diagonal and zeros elsewhere
+#[pyfunction]
+#[pyo3(name = "eye", signature = (N, M=None, k=0, dtype=None))]
+#[allow(non_snake_case)]
+fn eye(N: usize, M: Option<usize>, k: i64, dtype: Option<&PyDType>) -> PyResult<PyArray> {
+    let result = array::eye(N, M, k, dtype_or(dtype, NpyType::Double)).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return the identity array
+#[pyfunction]
+#[pyo3(signature = (n, dtype=None))]
+fn identity(n: usize, dtype: Option<&PyDType>) -> PyResult<PyArray> {
+    let result = array::identity(n, dtype_or(dtype, NpyType::Double)).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Extract a diagonal or construct a diagonal array
+#[pyfunction]
+#[pyo3(signature = (v, k=0))]
+fn diag(v: PyRef<'_, PyArray>, k: i64) -> PyResult<PyArray> {
+    let result = array::diag(v.get_inner(), k).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// An array with ones at and below the given diagonal and zeros elsewhere
+#[pyfunction]
+#[pyo3(name = "tri", signature = (N, M=None, k=0, dtype=None))]
+#[allow(non_snake_case)]
+fn tri(N: usize, M: Option<usize>, k: i64, dtype: Option<&PyDType>) -> PyResult<PyArray> {
+    let result = array::tri(N, M, k, dtype_or(dtype, NpyType::Double)).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Lower triangle of an array
+#[pyfunction]
+#[pyo3(signature = (m, k=0))]
+fn tril(m: PyRef<'_, PyArray>, k: i64) -> PyResult<PyArray> {
+    let result = array::tril(m.get_inner(), k).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Upper triangle of an array
+#[pyfunction]
+#[pyo3(signature = (m, k=0))]
+fn triu(m: PyRef<'_, PyArray>, k: i64) -> PyResult<PyArray> {
+    let result = array::triu(m.get_inner(), k).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Generate a Vandermonde matrix
+#[pyfunction]
+#[pyo3(name = "vander", signature = (x, N=None, increasing=false))]
+#[allow(non_snake_case)]
+fn vander(x: PyRef<'_, PyArray>, N: Option<usize>, increasing: bool) -> PyResult<PyArray> {
+    let result = array::vander(x.get_inner(), N, increasing).map_err(value_error)?;
+    Ok(wrap(result))
+}
+
+/// Return a list of coordinate matrices from coordinate vectors
+#[pyfunction]
+#[pyo3(signature = (*xi, indexing="xy", sparse=false))]
+fn meshgrid(xi: &Bound<'_, PyTuple>, indexing: &str, sparse: bool) -> PyResult<Vec<PyArray>> {
+    let indexing = match indexing {
+        "xy" => MeshIndexing::Xy,
+        "ij" => MeshIndexing::Ij,
+        _ => return Err(value_error("Valid values for `indexing` are 'xy' and 'ij'.")),
+    };
+    let arrays: Vec<Arc<Array>> = xi
+        .iter()
+        .map(|item| Ok(item.extract::<PyRef<'_, PyArray>>()?.get_inner().clone()))
+        .collect::<PyResult<_>>()?;
+    let refs: Vec<&Array> = arrays.iter().map(|a| a.as_ref()).collect();
+    let result = array::meshgrid(&refs, indexing, sparse).map_err(value_error)?;
+    Ok(result.into_iter().map(wrap).collect())
+}
+
+/// Construct an array by executing a function over each coordinate
+///
+/// As in NumPy, `function` is called once with one index array per axis
+/// (of the requested dtype, float64 by default) and its result returned.
+#[pyfunction]
+#[pyo3(signature = (function, shape, dtype=None))]
+fn fromfunction(
+    py: Python<'_>,
+    function: &Bound<'_, PyAny>,
+    shape: &Bound<'_, PyAny>,
+    dtype: Option<&PyDType>,
+) -> PyResult<Py<PyAny>> {
+    let shape = extract_shape(shape)?;
+    let dtype = dtype_or(dtype, NpyType::Double);
+    let axes = shape
+        .iter()
+        .map(|&n| array::arange(0.0, n as f64, 1.0, dtype.clone()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(value_error)?;
+    let refs: Vec<&Array> = axes.iter().collect();
+    let indices = array::meshgrid(&refs, MeshIndexing::Ij, false).map_err(value_error)?;
+    let args = indices
+        .into_iter()
+        .map(|a| Py::new(py, wrap(a)))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(function.call1(PyTuple::new(py, args)?)?.unbind())
+}
+
+/// Index object behind `mgrid` and `ogrid`
+///
+/// Indexing with slices builds the grid: `mgrid[0:5, 0:1:3j]`. A complex
+/// step gives the number of points with the stop value included.
+#[pyclass(name = "_GridFactory")]
+pub struct PyGridFactory {
+    sparse: bool,
+}
+
+fn slice_range(slice: &Bound<'_, PySlice>) -> PyResult<GridRange> {
+    let field = |name: &str| -> PyResult<Option<Bound<'_, PyAny>>> {
+        let value = slice.getattr(name)?;
+        Ok(if value.is_none() { None } else { Some(value) })
+    };
+    let start = field("start")?.map(|v| v.extract::<f64>()).transpose()?.unwrap_or(0.0);
+    let stop = field("stop")?.ok_or_else(|| value_error("grid slices need a stop value"))?.extract::<f64>()?;
+    match field("step")? {
+        Some(step) => match step.cast::<PyComplex>() {
+            Ok(z) => Ok(GridRange::num(start, stop, z.imag().abs() as usize)),
+            Err(_) => Ok(GridRange::new(start, stop, step.extract()?)),
+        },
+        None => Ok(GridRange::new(start, stop, 1.0)),
+    }
+}
+
+#[pymethods]
+impl PyGridFactory {
+    fn __getitem__(&self, py: Python<'_>, key: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let single = key.cast::<PySlice>().is_ok();
+        let ranges: Vec<GridRange> = if let Ok(slice) = key.cast::<PySlice>() {
+            vec![slice_range(slice)?]
+        } else {
+            key.cast::<PyTuple>()
+                .map_err(|_| value_error("grid index must be a slice or a tuple of slices"))?
+                .iter()
+                .map(|item| {
+                    let slice = item.cast_into::<PySlice>().map_err(|_| value_error("grid index must be a slice"))?;
+                    slice_range(&slice)
+                })
+                .collect::<PyResult<_>>()?
+        };
+        if self.sparse {
+            let mut arrays = array::ogrid(&ranges).map_err(value_error)?;
+            if single {
+                return Ok(Py::new(py, wrap(arrays.remove(0)))?.into_any());
+            }
+            let arrays = arrays.into_iter().map(|a| Py::new(py, wrap(a))).collect::<PyResult<Vec<_>>>()?;
+            Ok(arrays.into_pyobject(py)?.into_any().unbind())
+        } else {
+            Ok(Py::new(py, wrap(array::mgrid(&ranges).map_err(value_error)?))?.into_any())
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        if self.sparse { "ogrid" } else { "mgrid" }.to_string()
+    }
+}
+
+/// Convert the input to an array, sharing memory where possible
+///
+/// Raptors arrays are returned as they are. Buffer exporters (bytes,
+/// bytearray, `array.array`, memoryview, NumPy) and objects with
+/// `__array_interface__` (PIL images) are wrapped without copying; anything
+/// else is converted like `array`. A `dtype` other than the input's gives a
+/// converted copy.
+#[pyfunction]
+#[pyo3(signature = (a, dtype=None))]
+fn asarray(py: Python, a: &Bound<'_, PyAny>, dtype: Option<&PyDType>) -> PyResult<Py<PyAny>> {
+    let array = if let Ok(array) = a.cast::<PyArray>() {
+        PyArray { inner: PyArray::get_inner_from_bound(array).clone() }
+    } else if crate::buffer::is_buffer(a) {
+        match crate::buffer::from_buffer(a) {
+            Ok(array) => array,
+            // NumPy refuses buffers for some dtypes, such as datetime64
+            Err(_) if a.hasattr("__array_interface__")? => crate::numpy_interop::from_numpy(py, a)?,
+            Err(e) => return Err(e),
+        }
+    } else if a.hasattr("__array_interface__")? {
+        crate::numpy_interop::from_numpy(py, a)?
+    } else {
+        return Ok(Py::new(py, crate::array_from_list(py, a, dtype)?)?.into_any());
+    };
+    match dtype.map(|dt| dt.get_inner()) {
+        Some(dt) if dt.type_() != array.inner.dtype().type_() || dt.itemsize() != array.inner.dtype().itemsize() => {
+            let converted = raptors_core::conversion::convert_array(&array.inner, dt.clone()).map_err(value_error)?;
+            Ok(Py::new(py, wrap(converted))?.into_any())
+        }
+        _ if a.is_instance_of::<PyArray>() => Ok(a.clone().unbind()),
+        _ => Ok(Py::new(py, array)?.into_any()),
+    }
+}
@@ -0,0 +1,207 @@
+//! DLPack protocol for PyArray
+//!
+//! `__dlpack__` exports an array as a capsule holding a `DLManagedTensor`
+//! ("dltensor") or, for consumers asking for DLPack 1.x, a
+//! `DLManagedTensorVersioned` ("dltensor_versioned"). The tensor keeps the
+//! array alive until the consumer calls its deleter.
+//!
+//! `from_dlpack` takes the tensor out of a producer's capsule without
+//! copying and renames the capsule to "used_dltensor" (or
+//! "used_dltensor_versioned") so the producer's destructor leaves it alone.
+//! The tensor's deleter runs when the last array using its memory is dropped.
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
+
+use pyo3::exceptions::{PyBufferError, PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use raptors_core::dlpack::{
+    array_to_managed_tensor, array_to_managed_tensor_versioned, managed_tensor_to_array,
+    managed_tensor_versioned_to_array, DLDeviceType, DLManagedTensor, DLManagedTensorVersioned, DLPackError,
+    DLPACK_FLAG_BITMASK_IS_COPIED,
+};
+use raptors_core::Array;
+use std::ffi::CStr;
+use std::os::raw::c_void;
+use std::sync::Arc;
+
+use crate::array::PyArray;
+
+const DLTENSOR: &CStr = c"dltensor";
+const USED_DLTENSOR: &CStr = c"used_dltensor";
+const DLTENSOR_VERSIONED: &CStr = c"dltensor_versioned";
+const USED_DLTENSOR_VERSIONED: &CStr = c"used_dltensor_versioned";
+
+fn buffer_error(msg: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<PyBufferError, _>(format!("{}", msg))
+}
+
+/// Destructor of "dltensor" capsules; does nothing once a consumer has
+/// renamed the capsule and taken the tensor
+unsafe extern "C" fn dltensor_capsule_destructor(capsule: *mut ffi::PyObject) {
+    if ffi::PyCapsule_IsValid(capsule, DLTENSOR.as_ptr()) == 1 {
+        let tensor = ffi::PyCapsule_GetPointer(capsule, DLTENSOR.as_ptr()) as *mut DLManagedTensor;
+        if let Some(deleter) = (*tensor).deleter {
+            deleter(tensor);
+        }
+    }
+}
+
+/// Destructor of "dltensor_versioned" capsules
+unsafe extern "C" fn dltensor_versioned_capsule_destructor(capsule: *mut ffi::PyObject) {
+    if ffi::PyCapsule_IsValid(capsule, DLTENSOR_VERSIONED.as_ptr()) == 1 {
+        let tensor = ffi::PyCapsule_GetPointer(capsule, DLTENSOR_VERSIONED.as_ptr()) as *mut DLManagedTensorVersioned;
+        if let Some(deleter) = (*tensor).deleter {
+            deleter(tensor);
+        }
+    }
+}
+
+/// `__dlpack__`: capsule holding a managed tensor over `array`
+///
+/// A versioned tensor is exported when `max_version` allows DLPack 1.x.
+/// `copy=True` exports a copy, `copy=False` fails rather than copy, and by
+/// default a copy is only made for strides DLPack cannot express.
+pub(crate) fn to_capsule(
+    py: Python<'_>,
+    array: &Arc<Array>,
+    stream: Option<&Bound<'_, PyAny>>,
+    max_version: Option<(u32, u32)>,
+    dl_device: Option<(i32, i32)>,
+    copy: Option<bool>,
+) -> PyResult<Py<PyAny>> {
+    if stream.is_some_and(|s| !s.is_none()) {
+        return Err(PyErr::new::<PyRuntimeError, _>("stream must be None for CPU arrays"));
+    }
+    if let Some(device) = dl_device {
+        if device != (DLDeviceType::CPU as i32, 0) {
+            return Err(buffer_error(format!("unsupported device {:?}", device)));
+        }
+    }
+
+    let versioned = max_version.is_some_and(|(major, _)| major >= 1);
+    let export = |array: Arc<Array>, copied: bool| -> Result<*mut c_void, DLPackError> {
+        if versioned {
+            let flags = if copied { DLPACK_FLAG_BITMASK_IS_COPIED } else { 0 };
+            array_to_managed_tensor_versioned(array, flags).map(|t| t as *mut c_void)
+        } else {
+            array_to_managed_tensor(array).map(|t| t as *mut c_void)
+        }
+    };
+    let tensor = match copy {
+        Some(true) => export(Arc::new(array.copy()), true),
+        Some(false) => export(array.clone(), false),
+        None => match export(array.clone(), false) {
+            Err(DLPackError::UnsupportedStrides) => export(Arc::new(array.copy()), true),
+            result => result,
+        },
+    }
+    .map_err(buffer_error)?;
+
+    let (name, destructor): (&CStr, ffi::PyCapsule_Destructor) = if versioned {
+        (DLTENSOR_VERSIONED, dltensor_versioned_capsule_destructor)
+    } else {
+        (DLTENSOR, dltensor_capsule_destructor)
+    };
+    let capsule = unsafe { ffi::PyCapsule_New(tensor, name.as_ptr(), Some(destructor)) };
+    if capsule.is_null() {
+        // The capsule never took the tensor, so release it here
+        unsafe {
+            if versioned {
+                let tensor = tensor as *mut DLManagedTensorVersioned;
+                ((*tensor).deleter.unwrap())(tensor);
+            } else {
+                let tensor = tensor as *mut DLManagedTensor;
+                ((*tensor).deleter.unwrap())(tensor);
+            }
+        }
+        return Err(PyErr::fetch(py));
+    }
+    Ok(unsafe { Py::from_owned_ptr(py, capsule) })
+}
+
+/// Array sharing the memory of the tensor in `capsule`
+///
+/// The capsule is renamed before the array takes the tensor, so that its
+/// destructor never frees a tensor the array owns, and renamed back if no
+/// array can be made.
+fn from_capsule(capsule: &Bound<'_, PyAny>) -> PyResult<Array> {
+    let ptr = capsule.as_ptr();
+    unsafe {
+        let (name, used) = if ffi::PyCapsule_IsValid(ptr, DLTENSOR_VERSIONED.as_ptr()) == 1 {
+            (DLTENSOR_VERSIONED, USED_DLTENSOR_VERSIONED)
+        } else if ffi::PyCapsule_IsValid(ptr, DLTENSOR.as_ptr()) == 1 {
+            (DLTENSOR, USED_DLTENSOR)
+        } else {
+            return Err(PyErr::new::<PyValueError, _>(
+                "expected a 'dltensor' or 'dltensor_versioned' capsule that has not been consumed",
+            ));
+        };
+        let tensor = ffi::PyCapsule_GetPointer(ptr, name.as_ptr());
+        if ffi::PyCapsule_SetName(ptr, used.as_ptr()) != 0 {
+            return Err(PyErr::fetch(capsule.py()));
+        }
+        let array = if name == DLTENSOR_VERSIONED {
+            managed_tensor_versioned_to_array(tensor as *mut DLManagedTensorVersioned)
+        } else {
+            managed_tensor_to_array(tensor as *mut DLManagedTensor)
+        };
+        // On error the tensor still belongs to the capsule
+        array.map_err(|e| {
+            ffi::PyCapsule_SetName(ptr, name.as_ptr());
+            buffer_error(e)
+        })
+    }
+}
+
+/// Create an array from an object implementing the DLPack protocol
+///
+/// `x` may also be a "dltensor" or "dltensor_versioned" capsule. The array
+/// shares the producer's memory unless `copy=True`.
+#[pyfunction]
+#[pyo3(signature = (x, /, *, device=None, copy=None))]
+pub fn from_dlpack(
+    py: Python<'_>,
+    x: &Bound<'_, PyAny>,
+    device: Option<&Bound<'_, PyAny>>,
+    copy: Option<bool>,
+) -> PyResult<PyArray> {
+    if let Some(device) = device.filter(|d| !d.is_none()) {
+        if device.extract::<String>().ok().as_deref() != Some("cpu") {
+            return Err(PyErr::new::<PyValueError, _>(format!("unsupported device {}", device)));
+        }
+    }
+
+    let capsule = if unsafe { ffi::PyCapsule_CheckExact(x.as_ptr()) } != 0 {
+        x.clone()
+    } else {
+        if !x.hasattr("__dlpack__")? {
+            return Err(PyErr::new::<PyTypeError, _>(format!(
+                "'{}' object does not implement the DLPack protocol",
+                x.get_type().name()?
+            )));
+        }
+        if x.hasattr("__dlpack_device__")? {
+            let (device_type, _): (i32, i32) = x.call_method0("__dlpack_device__")?.extract()?;
+            if device_type != DLDeviceType::CPU as i32 {
+                return Err(buffer_error(format!("unsupported DLPack device type {}", device_type)));
+            }
+        }
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("max_version", (1, 0))?;
+        if let Some(copy) = copy {
+            kwargs.set_item("copy", copy)?;
+        }
+        match x.call_method("__dlpack__", (), Some(&kwargs)) {
+            Ok(capsule) => capsule,
+            // Producers older than DLPack 1.0 take no keywords
+            Err(e) if e.is_instance_of::<PyTypeError>(py) => x.call_method0("__dlpack__")?,
+            Err(e) => return Err(e),
+        }
+    };
+
+    let array = from_capsule(&capsule)?;
+    let array = if copy == Some(true) { array.copy() } else { array };
+    Ok(PyArray { inner: Arc::new(array) })
+}
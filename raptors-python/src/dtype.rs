@@ -35,6 +35,8 @@ impl PyDType {
             "uint64" => NpyType::ULongLong,
             "float32" | "float" => NpyType::Float,
             "float64" | "double" => NpyType::Double,
+            "complex64" => NpyType::CFloat,
+            "complex128" => NpyType::CDouble,
             _ => return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
                 format!("Unknown dtype: {}", type_name)
             )),
@@ -82,6 +84,25 @@ impl PyDType {
     fn __str__(&self) -> String {
         self.__repr__()
     }
+
+    /// Dtypes are equal to dtypes of the same name and to that name
+    pub(crate) fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        if let Ok(other) = other.cast::<PyDType>() {
+            let other = other.borrow();
+            return self.inner.name() == other.inner.name()
+                && self.inner.itemsize() == other.inner.itemsize()
+                && self.inner.custom_type_id() == other.inner.custom_type_id();
+        }
+        other.extract::<String>().is_ok_and(|name| name == self.inner.name())
+    }
+
+    /// Hash consistent with `__eq__`
+    fn __hash__(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.inner.name().hash(&mut hasher);
+        hasher.finish()
+    }
     
     /// Create a custom dtype from a registered custom type ID
     #[staticmethod]
@@ -206,6 +227,8 @@ pub fn add_dtype_constants(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("uint64", PyDType { inner: DType::new(NpyType::ULongLong) })?;
     m.add("float32", PyDType { inner: DType::new(NpyType::Float) })?;
     m.add("float64", PyDType { inner: DType::new(NpyType::Double) })?;
+    m.add("complex64", PyDType { inner: DType::new(NpyType::CFloat) })?;
+    m.add("complex128", PyDType { inner: DType::new(NpyType::CDouble) })?;
     m.add("float_", PyDType { inner: DType::new(NpyType::Double) })?;
     m.add("int_", PyDType { inner: DType::new(NpyType::LongLong) })?;
     
@@ -0,0 +1,170 @@
+//! NumPy indexing for PyArray
+//!
+//! Python index objects are translated to `IndexExpr` items, and the
+//! selection itself is left to `Array::index_from_arc` and
+//! `Array::index_assign` in the core.
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyEllipsis, PyList, PySlice, PyTuple};
+use raptors_core::array::{self, Array};
+use raptors_core::indexing::{is_scalar_index, IndexError, IndexExpr};
+use raptors_core::types::{DType, NpyType};
+use std::sync::Arc;
+
+use crate::array::PyArray;
+
+const INVALID_INDEX: &str = "only integers, slices (`:`), ellipsis (`...`), None (`newaxis`) \
+                             and integer or boolean arrays are valid indices";
+
+fn index_error(msg: impl Into<String>) -> PyErr {
+    PyErr::new::<PyIndexError, _>(msg.into())
+}
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<PyValueError, _>(format!("{}", e))
+}
+
+/// Raise a core indexing error as the exception NumPy would
+fn to_py_err(e: IndexError) -> PyErr {
+    match e {
+        IndexError::InvalidValue(_) => value_error(e),
+        _ => index_error(e.to_string()),
+    }
+}
+
+/// `self[index]`
+pub(crate) fn get_item(py: Python, slf: &PyArray, index: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+    let array = slf.get_inner();
+    let exprs = parse(py, index)?;
+    let result = Array::index_from_arc(array, &exprs).map_err(to_py_err)?;
+    if is_scalar_index(&exprs, array.ndim()) {
+        return slf.extract_value(py, result.data_ptr());
+    }
+    Ok(Py::new(py, PyArray { inner: Arc::new(result) })?.into_any())
+}
+
+/// `self[index] = value`
+pub(crate) fn set_item(
+    py: Python,
+    slf: &PyArray,
+    index: &Bound<'_, PyAny>,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let array = slf.get_inner();
+    let exprs = parse(py, index)?;
+    let value = value_array(py, value, array.dtype())?;
+    // SAFETY: this thread holds the GIL, so no Python code reads or writes
+    // the array meanwhile. As with NumPy, ordering this write against a
+    // kernel another thread runs on the same memory with the GIL released
+    // is up to the Python program.
+    unsafe { array.index_assign(&exprs, &value) }.map_err(to_py_err)
+}
+
+/// Split an index expression into items
+fn parse(py: Python, index: &Bound<'_, PyAny>) -> PyResult<Vec<IndexExpr>> {
+    match index.cast::<PyTuple>() {
+        Ok(tuple) => tuple.iter().map(|item| parse_item(py, &item)).collect(),
+        Err(_) => Ok(vec![parse_item(py, index)?]),
+    }
+}
+
+fn parse_item(py: Python, item: &Bound<'_, PyAny>) -> PyResult<IndexExpr> {
+    if item.is_none() {
+        return Ok(IndexExpr::NewAxis);
+    }
+    if item.is_instance_of::<PyEllipsis>() {
+        return Ok(IndexExpr::Ellipsis);
+    }
+    if let Ok(slice) = item.cast::<PySlice>() {
+        return Ok(IndexExpr::Slice(PyArray::py_slice_to_slice(slice)?));
+    }
+    if item.is_instance_of::<PyBool>() {
+        // A scalar boolean is a 0-d mask
+        let flag = Array::from_slice(&[item.extract::<bool>()? as u8], vec![], DType::new(NpyType::Bool))
+            .map_err(value_error)?;
+        return Ok(IndexExpr::BoolArray(flag));
+    }
+    if let Ok(array) = item.cast::<PyArray>() {
+        return array_item(PyArray::get_inner_from_bound(array));
+    }
+    if item.is_instance_of::<PyList>() {
+        return array_item(&sequence_array(item)?);
+    }
+    if let Ok(i) = item.extract::<i64>() {
+        return Ok(IndexExpr::Int(i));
+    }
+    if item.hasattr("__array_interface__")? {
+        let array = crate::numpy_interop::from_numpy(py, item)?;
+        return array_item(array.get_inner());
+    }
+    Err(index_error(INVALID_INDEX))
+}
+
+/// Expression item for an index array; the core checks its dtype
+fn array_item(array: &Array) -> PyResult<IndexExpr> {
+    Ok(match array.dtype().type_() {
+        NpyType::Bool => IndexExpr::BoolArray(array.copy()),
+        _ => IndexExpr::IntArray(array.copy()),
+    })
+}
+
+/// Array for a (nested) list of ints or bools
+fn sequence_array(list: &Bound<'_, PyAny>) -> PyResult<Array> {
+    fn flatten(item: &Bound<'_, PyAny>, depth: usize, shape: &mut Vec<i64>, out: &mut Vec<(i64, bool)>) -> PyResult<()> {
+        if item.is_instance_of::<PyList>() || item.is_instance_of::<PyTuple>() {
+            let len = item.len()? as i64;
+            if shape.len() == depth {
+                shape.push(len);
+            } else if shape.get(depth) != Some(&len) {
+                return Err(index_error("index lists must not be ragged"));
+            }
+            for element in item.try_iter()? {
+                flatten(&element?, depth + 1, shape, out)?;
+            }
+            return Ok(());
+        }
+        if shape.len() != depth {
+            return Err(index_error("index lists must not be ragged"));
+        }
+        if item.is_instance_of::<PyBool>() {
+            out.push((item.extract::<bool>()? as i64, true));
+        } else {
+            let value = item.extract::<i64>().map_err(|_| index_error(INVALID_INDEX))?;
+            out.push((value, false));
+        }
+        Ok(())
+    }
+
+    let mut shape = Vec::new();
+    let mut values = Vec::new();
+    flatten(list, 0, &mut shape, &mut values)?;
+    let result = if !values.is_empty() && values.iter().all(|&(_, is_bool)| is_bool) {
+        let flags: Vec<u8> = values.iter().map(|&(v, _)| v as u8).collect();
+        Array::from_slice(&flags, shape, DType::new(NpyType::Bool))
+    } else {
+        let ints: Vec<i64> = values.iter().map(|&(v, _)| v).collect();
+        Array::from_slice(&ints, shape, DType::new(NpyType::Long))
+    };
+    result.map_err(value_error)
+}
+
+/// The assigned value as an array; scalars take the destination's `dtype`
+fn value_array(py: Python, value: &Bound<'_, PyAny>, dtype: &DType) -> PyResult<Arc<Array>> {
+    let value = if let Ok(array) = value.cast::<PyArray>() {
+        PyArray::get_inner_from_bound(array).clone()
+    } else if let Ok(tuple) = value.cast::<PyTuple>() {
+        let list = PyList::new(py, tuple.iter())?;
+        crate::array_from_list(py, list.as_any(), None)?.inner
+    } else if value.is_instance_of::<PyList>() {
+        crate::array_from_list(py, value, None)?.inner
+    } else if value.hasattr("__array_interface__")? {
+        crate::numpy_interop::from_numpy(py, value)?.inner
+    } else {
+        let (scalar, _) = crate::creation::extract_scalar(value)?;
+        Arc::new(array::full(vec![], scalar, dtype.clone()).map_err(value_error)?)
+    };
+    Ok(value)
+}
@@ -0,0 +1,240 @@
+//! File I/O Python bindings
+//!
+//! This module provides Python bindings for the NPY and NPZ formats:
+//! `save`, `load`, `savez`, `savez_compressed` and the lazily loading
+//! `NpzFile` returned by `load` for archives. Reading and writing happen
+//! with the GIL released.
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
+
+use pyo3::exceptions::{PyKeyError, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use raptors_core::io::{load_npy, save_npy, savez as core_savez, savez_compressed as core_savez_compressed};
+use raptors_core::io::{IoError, NpzFile};
+use raptors_core::Array;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::array::{Handle, PyArray};
+
+/// Add file I/O functions to module
+pub fn add_io_functions(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(save, m)?)?;
+    m.add_function(wrap_pyfunction!(load, m)?)?;
+    m.add_function(wrap_pyfunction!(savez, m)?)?;
+    m.add_function(wrap_pyfunction!(savez_compressed, m)?)?;
+    m.add_class::<PyNpzFile>()?;
+    Ok(())
+}
+
+pub(crate) fn io_error(e: IoError) -> PyErr {
+    match e {
+        IoError::FileError(msg) => PyErr::new::<PyOSError, _>(msg),
+        e => PyErr::new::<PyValueError, _>(format!("{}", e)),
+    }
+}
+
+/// Append `ext` to a path that does not already end with it, as NumPy does
+fn with_extension(file: PathBuf, ext: &str) -> PathBuf {
+    if file.to_string_lossy().ends_with(ext) {
+        file
+    } else {
+        let mut name = file.into_os_string();
+        name.push(ext);
+        PathBuf::from(name)
+    }
+}
+
+/// Save an array to a binary file in NPY format
+///
+/// A `.npy` extension is appended to the file name if it is missing.
+#[pyfunction]
+fn save(file: PathBuf, arr: &Bound<'_, PyArray>) -> PyResult<()> {
+    let array = PyArray::handle(arr);
+    arr.py().detach(|| write(file, &array))
+}
+
+/// Write `array` to an NPY file; the body of `save`, without the GIL
+pub(crate) fn write(file: PathBuf, array: &Array) -> PyResult<()> {
+    save_npy(with_extension(file, ".npy"), array).map_err(io_error)
+}
+
+/// Contents of a file read by `load`
+pub(crate) enum Loaded {
+    Array(Array),
+    Archive(NpzFile),
+}
+
+impl Loaded {
+    pub(crate) fn into_py(self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match self {
+            Loaded::Array(array) => Ok(Py::new(py, PyArray { inner: Arc::new(array) })?.into_any()),
+            Loaded::Archive(npz) => Ok(Py::new(py, PyNpzFile::new(npz))?.into_any()),
+        }
+    }
+}
+
+/// Read an NPY file or open an NPZ archive, told apart by their magic bytes;
+/// the body of `load`, without the GIL
+pub(crate) fn read(file: &Path) -> PyResult<Loaded> {
+    let mut magic = [0u8; 4];
+    File::open(file)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .map_err(|e| PyErr::new::<PyOSError, _>(e.to_string()))?;
+    if &magic == b"PK\x03\x04" || &magic == b"PK\x05\x06" {
+        NpzFile::open(file).map(Loaded::Archive).map_err(io_error)
+    } else {
+        load_npy(file).map(Loaded::Array).map_err(io_error)
+    }
+}
+
+/// Load an array from an NPY file, or an `NpzFile` from an NPZ archive
+#[pyfunction]
+fn load(py: Python<'_>, file: PathBuf) -> PyResult<Py<PyAny>> {
+    py.detach(|| read(&file))?.into_py(py)
+}
+
+/// Collect `savez` arguments as `(name, array)` pairs
+///
+/// Positional arrays are named `arr_0`, `arr_1`, ... as in NumPy.
+pub(crate) fn named_arrays(args: &Bound<'_, PyTuple>, kwds: Option<&Bound<'_, PyDict>>) -> PyResult<Vec<(String, Handle)>> {
+    let mut arrays = Vec::with_capacity(args.len());
+    for (i, arg) in args.iter().enumerate() {
+        let arr = arg.extract::<PyRef<PyArray>>()?;
+        arrays.push((format!("arr_{}", i), Handle::from(arr.inner.clone())));
+    }
+    if let Some(kwds) = kwds {
+        for (key, value) in kwds.iter() {
+            let name: String = key.extract()?;
+            if arrays.iter().any(|(n, _)| *n == name) {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "Cannot use un-named variables and keyword {}",
+                    name
+                )));
+            }
+            let arr = value.extract::<PyRef<PyArray>>()?;
+            arrays.push((name, Handle::from(arr.inner.clone())));
+        }
+    }
+    Ok(arrays)
+}
+
+fn write_archive(
+    file: PathBuf,
+    args: &Bound<'_, PyTuple>,
+    kwds: Option<&Bound<'_, PyDict>>,
+    compress: bool,
+) -> PyResult<()> {
+    let arrays = named_arrays(args, kwds)?;
+    args.py().detach(|| write_npz(file, &arrays, compress))
+}
+
+/// Write named arrays to an NPZ archive; the body of `savez` and
+/// `savez_compressed`, without the GIL
+pub(crate) fn write_npz(file: PathBuf, arrays: &[(String, Handle)], compress: bool) -> PyResult<()> {
+    let members: Vec<(&str, &Array)> = arrays.iter().map(|(name, arr)| (name.as_str(), &**arr)).collect();
+    let path = with_extension(file, ".npz");
+    let result = if compress {
+        core_savez_compressed(path, &members)
+    } else {
+        core_savez(path, &members)
+    };
+    result.map_err(io_error)
+}
+
+/// Save several arrays into a single uncompressed NPZ archive
+///
+/// Positional arrays are stored as `arr_0`, `arr_1`, ...; keyword arrays
+/// under their keyword. A `.npz` extension is appended if missing.
+#[pyfunction]
+#[pyo3(signature = (file, *args, **kwds))]
+fn savez(file: PathBuf, args: &Bound<'_, PyTuple>, kwds: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+    write_archive(file, args, kwds, false)
+}
+
+/// Save several arrays into a single deflate-compressed NPZ archive
+#[pyfunction]
+#[pyo3(signature = (file, *args, **kwds))]
+fn savez_compressed(file: PathBuf, args: &Bound<'_, PyTuple>, kwds: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+    write_archive(file, args, kwds, true)
+}
+
+/// A lazily loaded NPZ archive
+///
+/// Behaves like a read-only mapping from array names to arrays; each array
+/// is read from the archive when it is accessed.
+#[pyclass(name = "NpzFile")]
+pub struct PyNpzFile {
+    inner: Option<NpzFile>,
+    files: Vec<String>,
+}
+
+impl PyNpzFile {
+    fn new(npz: NpzFile) -> Self {
+        let files = npz.files();
+        PyNpzFile { inner: Some(npz), files }
+    }
+
+    fn archive(&mut self) -> PyResult<&mut NpzFile> {
+        self.inner
+            .as_mut()
+            .ok_or_else(|| PyErr::new::<PyValueError, _>("I/O operation on closed NpzFile"))
+    }
+}
+
+#[pymethods]
+impl PyNpzFile {
+    /// Names of the arrays in the archive
+    #[getter]
+    fn files(&self) -> Vec<String> {
+        self.files.clone()
+    }
+
+    /// Names of the arrays in the archive
+    fn keys(&self) -> Vec<String> {
+        self.files.clone()
+    }
+
+    fn __getitem__(&mut self, py: Python<'_>, key: &str) -> PyResult<PyArray> {
+        let archive = self.archive()?;
+        if !archive.contains(key) {
+            return Err(PyErr::new::<PyKeyError, _>(format!("{} is not a file in the archive", key)));
+        }
+        let array = py.detach(|| archive.load(key)).map_err(io_error)?;
+        Ok(PyArray { inner: Arc::new(array) })
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.inner.as_ref().is_some_and(|npz| npz.contains(key))
+    }
+
+    fn __len__(&self) -> usize {
+        self.files.len()
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(PyList::new(py, &self.files)?.as_any().try_iter()?.into_any())
+    }
+
+    /// Close the archive; members can no longer be loaded
+    fn close(&mut self) {
+        self.inner = None;
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&mut self, _args: &Bound<'_, PyTuple>) -> bool {
+        self.close();
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        format!("NpzFile(files={:?})", self.files)
+    }
+}
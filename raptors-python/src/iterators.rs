@@ -19,7 +19,7 @@ pub struct PyArrayIterator {
     pub(crate) size: usize,
 }
 
-// SAFETY: PyArrayIterator only contains owned data (Arc, usize) which is thread-safe
+// SAFETY: the iterator reads the array with the GIL held, like `PyArray`
 unsafe impl Send for PyArrayIterator {}
 unsafe impl Sync for PyArrayIterator {}
 
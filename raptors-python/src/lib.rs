@@ -6,7 +6,15 @@ pub mod array;
 pub mod dtype;
 mod ufunc;
 pub mod iterators;
+mod indexing;
+mod buffer;
+mod dlpack;
 mod numpy_interop;
+mod creation;
+mod io;
+mod array_api;
+mod numpy_dispatch;
+mod aio;
 
 use pyo3::prelude::*;
 
@@ -36,6 +44,12 @@ fn raptors(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Add DType class alias
     m.add("DType", m.getattr("PyDType")?)?;
     
+    // Add array creation functions
+    creation::add_creation_functions(m)?;
+    
+    // Add file I/O functions
+    io::add_io_functions(m)?;
+    
     // Add ufunc functions
     ufunc::add_ufuncs(m)?;
     
@@ -43,6 +57,15 @@ fn raptors(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(numpy_interop::from_numpy, m)?)?;
     m.add_function(wrap_pyfunction!(numpy_interop::to_numpy, m)?)?;
     
+    // Add DLPack interop functions
+    m.add_function(wrap_pyfunction!(dlpack::from_dlpack, m)?)?;
+    
+    // Add the array API namespace (raptors.array_api)
+    array_api::add_array_api_module(m)?;
+    
+    // Add the asyncio layer (raptors.aio)
+    aio::add_aio_module(m)?;
+    
     // Add custom dtype functions
     m.add_function(wrap_pyfunction!(dtype::register_custom_dtype, m)?)?;
     m.add_function(wrap_pyfunction!(dtype::get_custom_dtype_id, m)?)?;
@@ -198,7 +221,7 @@ pub fn array_from_list(py: Python, data: &Bound<'_, PyAny>, dtype: Option<&dtype
     }
     
     Ok(array::PyArray {
-        #[allow(clippy::arc_with_non_send_sync)]
+        #[allow(clippy::arc_with_non_send_sync)] // Arc needed for Python reference counting
         inner: Arc::new(array),
     })
 }
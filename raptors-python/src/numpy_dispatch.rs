@@ -0,0 +1,877 @@
+//! NumPy dispatch protocols
+//!
+//! `PyArray.__array_ufunc__` and `PyArray.__array_function__` route NumPy
+//! calls such as `np.sin(a)` or `np.mean(a, axis=0)` to raptors kernels, so
+//! the work stays in raptors and the results are raptors arrays. Calls with
+//! options raptors does not support return `NotImplemented`, which makes
+//! NumPy raise its usual `TypeError`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::call::PyCallArgs;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyComplex, PyDict, PyFloat, PyInt, PyList, PyTuple, PyType};
+use raptors_core::array_api::{BinaryOp, UnaryOp};
+use raptors_core::indexing::IndexExpr;
+
+use crate::array::PyArray;
+use crate::array_api;
+use crate::dtype::PyDType;
+
+/// Raptors kernel behind a NumPy ufunc
+enum Kernel {
+    Unary(UnaryOp),
+    Binary(BinaryOp),
+    Matmul,
+}
+
+/// Kernel of the NumPy ufunc called `name`
+fn ufunc_kernel(name: &str) -> Option<Kernel> {
+    let name = match name {
+        "absolute" | "fabs" => "abs",
+        "arccos" => "acos",
+        "arccosh" => "acosh",
+        "arcsin" => "asin",
+        "arcsinh" => "asinh",
+        "arctan" => "atan",
+        "arctanh" => "atanh",
+        "arctan2" => "atan2",
+        "invert" | "bitwise_not" => "bitwise_invert",
+        "conjugate" => "conj",
+        "rint" => "round",
+        "true_divide" => "divide",
+        "power" => "pow",
+        "mod" => "remainder",
+        "left_shift" => "bitwise_left_shift",
+        "right_shift" => "bitwise_right_shift",
+        "matmul" => return Some(Kernel::Matmul),
+        other => other,
+    };
+    UnaryOp::from_name(name)
+        .map(Kernel::Unary)
+        .or_else(|| BinaryOp::from_name(name).map(Kernel::Binary))
+}
+
+/// Namespace function implementing `ufunc.reduce` for a ufunc
+fn reduction(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "add" => "sum",
+        "multiply" => "prod",
+        "maximum" => "max",
+        "minimum" => "min",
+        "logical_and" => "all",
+        "logical_or" => "any",
+        _ => return None,
+    })
+}
+
+/// Arguments of a NumPy call bound to parameter names
+struct Params<'py> {
+    values: Vec<(&'static str, Bound<'py, PyAny>)>,
+}
+
+impl<'py> Params<'py> {
+    /// Bind `args` and `kwargs` to `names`; `None` if an argument does not
+    /// match a parameter
+    fn bind(
+        names: &[&'static str],
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Option<Self>> {
+        if args.len() > names.len() {
+            return Ok(None);
+        }
+        let mut values: Vec<_> = names.iter().copied().zip(args.iter()).collect();
+        for (key, value) in kwargs.into_iter().flat_map(|kwargs| kwargs.iter()) {
+            let key: String = key.extract()?;
+            match names.iter().find(|name| **name == key) {
+                Some(name) if !values.iter().any(|(bound, _)| bound == name) => values.push((name, value)),
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(Params { values }))
+    }
+
+    /// Argument passed for `name`, which may be `None`
+    fn get(&self, name: &str) -> Option<&Bound<'py, PyAny>> {
+        self.values.iter().find(|(bound, _)| *bound == name).map(|(_, value)| value)
+    }
+
+    /// Argument passed for `name` unless it is `None`
+    fn value(&self, name: &str) -> Option<&Bound<'py, PyAny>> {
+        self.get(name).filter(|value| !value.is_none())
+    }
+
+    /// Whether any of `names` was passed something other than `None`
+    fn any(&self, names: &[&str]) -> bool {
+        names.iter().any(|name| self.value(name).is_some())
+    }
+
+    /// Whether `name` was passed something other than `default`
+    fn differs<T>(&self, name: &str, default: T) -> PyResult<bool>
+    where
+        T: PartialEq + for<'a> FromPyObject<'a, 'py>,
+    {
+        match self.value(name) {
+            Some(value) => Ok(value.extract::<T>().map_or(true, |value| value != default)),
+            None => Ok(false),
+        }
+    }
+}
+
+fn is_python_scalar(value: &Bound<'_, PyAny>) -> bool {
+    value.is_instance_of::<PyBool>()
+        || value.is_instance_of::<PyInt>()
+        || value.is_instance_of::<PyFloat>()
+        || value.is_instance_of::<PyComplex>()
+}
+
+/// `value` as a raptors array, or `None` if it cannot be converted
+fn array<'py>(xp: &Bound<'py, PyModule>, value: &Bound<'py, PyAny>) -> Option<Bound<'py, PyAny>> {
+    if value.is_instance_of::<PyArray>() {
+        return Some(value.clone());
+    }
+    xp.getattr("asarray").and_then(|asarray| asarray.call1((value,))).ok()
+}
+
+/// `value` as an operand of a raptors kernel: arrays are converted, Python
+/// scalars are kept so they combine with the dtype of the other operand
+fn operand<'py>(xp: &Bound<'py, PyModule>, value: &Bound<'py, PyAny>) -> Option<Bound<'py, PyAny>> {
+    if is_python_scalar(value) {
+        return Some(value.clone());
+    }
+    array(xp, value)
+}
+
+/// Every array of a sequence argument, or `None` if one cannot be converted
+fn arrays<'py>(xp: &Bound<'py, PyModule>, values: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyList>>> {
+    let mut converted = Vec::new();
+    for value in values.try_iter()? {
+        match array(xp, &value?) {
+            Some(value) => converted.push(value),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(PyList::new(values.py(), converted)?))
+}
+
+/// A NumPy `dtype` argument as a raptors dtype, or `None` if raptors has no
+/// matching dtype
+fn dtype<'py>(value: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = value.py();
+    if value.is_instance_of::<PyDType>() {
+        return Ok(Some(value.clone()));
+    }
+    let name = if let Ok(ty) = value.cast::<PyType>() {
+        match ty.name()?.to_string().as_str() {
+            "bool" => "bool".to_string(),
+            "int" => "int64".to_string(),
+            "float" => "float64".to_string(),
+            "complex" => "complex128".to_string(),
+            // NumPy scalar types such as numpy.float32 or numpy.bool_
+            other => other.trim_end_matches('_').to_string(),
+        }
+    } else {
+        value.str()?.to_string()
+    };
+    Ok(py.import("raptors")?.getattr("DType")?.call1((name,)).ok())
+}
+
+/// Call the namespace function `name`
+fn call<'py>(
+    xp: &Bound<'py, PyModule>,
+    name: &str,
+    args: impl PyCallArgs<'py>,
+    kwargs: &[(&str, &Bound<'py, PyAny>)],
+) -> PyResult<Bound<'py, PyAny>> {
+    let options = PyDict::new(xp.py());
+    for (key, value) in kwargs {
+        options.set_item(key, value)?;
+    }
+    xp.getattr(name)?.call(args, Some(&options))
+}
+
+/// NumPy's `out` argument: a single raptors array, or `None` when not given
+fn output<'py>(out: Option<&Bound<'py, PyAny>>) -> Option<Option<Bound<'py, PyAny>>> {
+    let Some(out) = out.filter(|out| !out.is_none()) else {
+        return Some(None);
+    };
+    let out = match out.cast::<PyTuple>() {
+        Ok(items) if items.len() == 1 => items.get_item(0).ok()?,
+        Ok(_) => return None,
+        Err(_) => out.clone(),
+    };
+    if out.is_none() {
+        Some(None)
+    } else if out.is_instance_of::<PyArray>() {
+        Some(Some(out))
+    } else {
+        None
+    }
+}
+
+/// Write `result` into `out`, casting it to the dtype of `out`
+fn store<'py>(out: &Bound<'py, PyAny>, result: &Bound<'py, PyAny>) -> PyResult<()> {
+    let out = PyArray::handle(out.cast::<PyArray>()?);
+    let result = PyArray::handle(result.cast::<PyArray>()?);
+    if out.shape() != result.shape() {
+        return Err(PyErr::new::<PyValueError, _>(format!(
+            "output array of shape {:?} does not match the result shape {:?}",
+            out.shape(),
+            result.shape()
+        )));
+    }
+    // SAFETY: the copy runs with the GIL held, so no Python code touches
+    // `out` meanwhile; see `indexing::set_item`.
+    unsafe { out.index_assign(&[IndexExpr::Ellipsis], &result) }
+        .map_err(|e| PyErr::new::<PyValueError, _>(format!("{}", e)))
+}
+
+/// `PyArray.__array_ufunc__`
+pub(crate) fn array_ufunc(
+    py: Python<'_>,
+    ufunc: &Bound<'_, PyAny>,
+    method: &str,
+    inputs: &Bound<'_, PyTuple>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    let name: String = ufunc.getattr("__name__")?.extract()?;
+    let xp = py.import("raptors.array_api")?;
+    let result = match method {
+        "__call__" => call_ufunc(&xp, &name, inputs, kwargs)?,
+        "reduce" => reduce_ufunc(&xp, &name, inputs, kwargs)?,
+        "accumulate" => accumulate_ufunc(&xp, &name, inputs, kwargs)?,
+        _ => None,
+    };
+    Ok(result.map_or_else(|| py.NotImplemented(), |result| result.unbind()))
+}
+
+/// `ufunc(*inputs, out=..., where=..., dtype=...)`
+fn call_ufunc<'py>(
+    xp: &Bound<'py, PyModule>,
+    name: &str,
+    inputs: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = xp.py();
+    let Some(kernel) = ufunc_kernel(name) else {
+        return Ok(None);
+    };
+    let names = ["out", "where", "dtype", "casting", "order", "subok", "signature"];
+    let Some(params) = Params::bind(&names, &PyTuple::empty(py), kwargs)? else {
+        return Ok(None);
+    };
+    if params.any(&["signature"])
+        || params.differs("casting", "same_kind".to_string())?
+        || params.differs("order", "K".to_string())?
+        || params.differs("subok", true)?
+    {
+        return Ok(None);
+    }
+    let Some(out) = output(params.get("out")) else {
+        return Ok(None);
+    };
+
+    let mut operands = Vec::with_capacity(inputs.len());
+    for input in inputs.iter() {
+        match operand(xp, &input) {
+            Some(input) => operands.push(input),
+            None => return Ok(None),
+        }
+    }
+    let mut result = match (kernel, operands.as_slice()) {
+        (Kernel::Unary(op), [x]) if x.is_instance_of::<PyArray>() => {
+            let x = x.cast::<PyArray>()?;
+            Bound::new(py, array_api::unary(py, op, &PyArray::handle(x))?)?.into_any()
+        }
+        (Kernel::Binary(op), [x1, x2]) if x1.is_instance_of::<PyArray>() || x2.is_instance_of::<PyArray>() => {
+            Bound::new(py, array_api::binary(op, x1, x2)?)?.into_any()
+        }
+        (Kernel::Matmul, [x1, x2]) => call(xp, "matmul", (x1, x2), &[])?,
+        _ => return Ok(None),
+    };
+
+    if let Some(dtype_arg) = params.value("dtype") {
+        let Some(dtype) = dtype(dtype_arg)? else {
+            return Ok(None);
+        };
+        result = call(xp, "astype", (&result, dtype), &[])?;
+    }
+    if let Some(mask) = params.value("where").filter(|mask| !mask.is_instance_of::<PyBool>() || !mask.is_truthy().unwrap_or(false)) {
+        let Some(mask) = array(xp, mask) else {
+            return Ok(None);
+        };
+        // Elements the mask leaves out keep the value of `out` (zero without it)
+        let fallback = match &out {
+            Some(out) => out.clone(),
+            None => call(xp, "zeros_like", (&result,), &[])?,
+        };
+        result = call(xp, "where", (mask, &result, fallback), &[])?;
+    }
+    match out {
+        Some(out) => {
+            store(&out, &result)?;
+            Ok(Some(out))
+        }
+        None => Ok(Some(result)),
+    }
+}
+
+/// `ufunc.reduce(array, axis=0, dtype=None, out=None, keepdims=False)`
+fn reduce_ufunc<'py>(
+    xp: &Bound<'py, PyModule>,
+    name: &str,
+    inputs: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = xp.py();
+    let Some(function) = reduction(name) else {
+        return Ok(None);
+    };
+    let names = ["array", "axis", "dtype", "out", "keepdims", "initial", "where"];
+    let Some(params) = Params::bind(&names, inputs, kwargs)? else {
+        return Ok(None);
+    };
+    if params.any(&["initial"]) || params.differs("where", true)? {
+        return Ok(None);
+    }
+    let (Some(x), Some(out)) = (params.get("array").and_then(|x| array(xp, x)), output(params.get("out"))) else {
+        return Ok(None);
+    };
+    let zero = 0i64.into_pyobject(py)?.into_any();
+    let mut options = vec![("axis", params.get("axis").unwrap_or(&zero))];
+    if let Some(keepdims) = params.get("keepdims") {
+        options.push(("keepdims", keepdims));
+    }
+    let dtype_arg = match params.value("dtype") {
+        Some(_) if !matches!(function, "sum" | "prod") => return Ok(None),
+        Some(dtype_arg) => match dtype(dtype_arg)? {
+            Some(dtype) => Some(dtype),
+            None => return Ok(None),
+        },
+        None => None,
+    };
+    if let Some(dtype) = &dtype_arg {
+        options.push(("dtype", dtype));
+    }
+    let result = call(xp, function, (x,), &options)?;
+    match out {
+        Some(out) => {
+            store(&out, &result)?;
+            Ok(Some(out))
+        }
+        None => Ok(Some(result)),
+    }
+}
+
+/// `np.add.accumulate(array, axis=0, dtype=None, out=None)`
+fn accumulate_ufunc<'py>(
+    xp: &Bound<'py, PyModule>,
+    name: &str,
+    inputs: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = xp.py();
+    if name != "add" {
+        return Ok(None);
+    }
+    let Some(params) = Params::bind(&["array", "axis", "dtype", "out"], inputs, kwargs)? else {
+        return Ok(None);
+    };
+    let (Some(x), Some(out)) = (params.get("array").and_then(|x| array(xp, x)), output(params.get("out"))) else {
+        return Ok(None);
+    };
+    let zero = 0i64.into_pyobject(py)?.into_any();
+    let axis = params.value("axis").unwrap_or(&zero);
+    let mut options = vec![("axis", axis)];
+    let dtype_arg = match params.value("dtype") {
+        Some(dtype_arg) => match dtype(dtype_arg)? {
+            Some(dtype) => Some(dtype),
+            None => return Ok(None),
+        },
+        None => None,
+    };
+    if let Some(dtype) = &dtype_arg {
+        options.push(("dtype", dtype));
+    }
+    let result = call(xp, "cumulative_sum", (x,), &options)?;
+    match out {
+        Some(out) => {
+            store(&out, &result)?;
+            Ok(Some(out))
+        }
+        None => Ok(Some(result)),
+    }
+}
+
+/// Parameters of the NumPy functions `__array_function__` implements
+fn signature(name: &str) -> Option<&'static [&'static str]> {
+    const REDUCTION: &[&str] = &["a", "axis", "dtype", "out", "keepdims", "initial", "where"];
+    const EXTREMUM: &[&str] = &["a", "axis", "out", "keepdims", "initial", "where"];
+    const LOGICAL: &[&str] = &["a", "axis", "out", "keepdims", "where"];
+    const DEVIATION: &[&str] = &["a", "axis", "dtype", "out", "ddof", "keepdims", "where", "mean", "correction"];
+    const SORT: &[&str] = &["a", "axis", "kind", "order", "stable"];
+    const JOIN: &[&str] = &["arrays", "axis", "out", "dtype", "casting"];
+    const LIKE: &[&str] = &["a", "dtype", "order", "subok", "shape", "device"];
+    Some(match name {
+        "sum" | "prod" => REDUCTION,
+        "max" | "amax" | "min" | "amin" => EXTREMUM,
+        "all" | "any" => LOGICAL,
+        "mean" => &["a", "axis", "dtype", "out", "keepdims", "where"],
+        "std" | "var" => DEVIATION,
+        "argmax" | "argmin" => &["a", "axis", "out", "keepdims"],
+        "cumsum" => &["a", "axis", "dtype", "out"],
+        "sort" | "argsort" => SORT,
+        "concatenate" | "stack" => JOIN,
+        "reshape" => &["a", "shape", "order", "newshape", "copy"],
+        "transpose" => &["a", "axes"],
+        "dot" => &["a", "b", "out"],
+        "tensordot" => &["a", "b", "axes"],
+        "squeeze" => &["a", "axis"],
+        "expand_dims" => &["a", "axis"],
+        "flip" => &["m", "axis"],
+        "roll" => &["a", "shift", "axis"],
+        "repeat" => &["a", "repeats", "axis"],
+        "tile" => &["A", "reps"],
+        "where" => &["condition", "x", "y"],
+        "clip" => &["a", "a_min", "a_max", "out", "min", "max"],
+        "nonzero" => &["a"],
+        "take" => &["a", "indices", "axis", "out", "mode"],
+        "searchsorted" => &["a", "v", "side", "sorter"],
+        "unique" => &["ar", "return_index", "return_inverse", "return_counts", "axis", "equal_nan", "sorted"],
+        "broadcast_to" => &["array", "shape", "subok"],
+        "moveaxis" => &["a", "source", "destination"],
+        "zeros_like" | "ones_like" | "empty_like" => LIKE,
+        "full_like" => &["a", "fill_value", "dtype", "order", "subok", "shape", "device"],
+        "round" | "around" => &["a", "decimals", "out"],
+        "real" | "imag" => &["val"],
+        "astype" => &["x", "dtype", "copy", "device"],
+        "copy" => &["a", "order", "subok"],
+        "shape" => &["a"],
+        "ndim" => &["a"],
+        "array_equal" => &["a1", "a2", "equal_nan"],
+        "isclose" | "allclose" => &["a", "b", "rtol", "atol", "equal_nan"],
+        _ => return None,
+    })
+}
+
+/// `PyArray.__array_function__`
+pub(crate) fn array_function(
+    py: Python<'_>,
+    func: &Bound<'_, PyAny>,
+    types: &Bound<'_, PyAny>,
+    args: &Bound<'_, PyTuple>,
+    kwargs: &Bound<'_, PyDict>,
+) -> PyResult<Py<PyAny>> {
+    // Only raptors arrays and NumPy arrays take part
+    for ty in types.try_iter()? {
+        let ty = ty?;
+        let ty = ty.cast::<PyType>()?;
+        let is_ndarray = ty.module()? == "numpy" && ty.name()? == "ndarray";
+        if !ty.is_subclass_of::<PyArray>()? && !is_ndarray {
+            return Ok(py.NotImplemented());
+        }
+    }
+    let module: String = func.getattr("__module__")?.extract().unwrap_or_default();
+    let name: String = func.getattr("__name__")?.extract()?;
+    if module != "numpy" {
+        return Ok(py.NotImplemented());
+    }
+    let Some(names) = signature(&name) else {
+        return Ok(py.NotImplemented());
+    };
+    let Some(params) = Params::bind(names, args, Some(kwargs))? else {
+        return Ok(py.NotImplemented());
+    };
+    let xp = py.import("raptors.array_api")?;
+    Ok(match numpy_function(&xp, &name, &params)? {
+        Some(result) => result.unbind(),
+        None => py.NotImplemented(),
+    })
+}
+
+/// Run the NumPy function `name` with raptors; `None` for calls raptors
+/// does not support
+fn numpy_function<'py>(xp: &Bound<'py, PyModule>, name: &str, p: &Params<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = xp.py();
+    // Options no supported function implements
+    if p.any(&["out", "initial", "mean", "order"]) && !matches!(name, "reshape" | "copy" | "zeros_like" | "ones_like" | "empty_like" | "full_like")
+        || p.differs("where", true)?
+        || p.differs("subok", true)?
+    {
+        return Ok(None);
+    }
+    macro_rules! arg {
+        ($name:literal) => {
+            match p.get($name).and_then(|value| array(xp, value)) {
+                Some(value) => value,
+                None => return Ok(None),
+            }
+        };
+    }
+    macro_rules! dtype_option {
+        ($options:ident, $dtype:ident) => {
+            let $dtype = match p.value("dtype") {
+                Some(value) => match dtype(value)? {
+                    Some(dtype) => Some(dtype),
+                    None => return Ok(None),
+                },
+                None => None,
+            };
+            if let Some(dtype) = &$dtype {
+                $options.push(("dtype", dtype));
+            }
+        };
+    }
+    let none = py.None().into_bound(py);
+    let zero = 0i64.into_pyobject(py)?.into_any();
+    let minus_one = (-1i64).into_pyobject(py)?.into_any();
+    let flat = PyTuple::new(py, [-1i64])?.into_any();
+    macro_rules! keepdims {
+        ($options:ident) => {
+            if let Some(keepdims) = p.get("keepdims") {
+                $options.push(("keepdims", keepdims));
+            }
+        };
+    }
+
+    let result = match name {
+        "sum" | "prod" | "max" | "amax" | "min" | "amin" | "all" | "any" | "mean" => {
+            let a = arg!("a");
+            let function = name.trim_start_matches('a');
+            let function = if name == "all" || name == "any" { name } else { function };
+            let mut options = vec![("axis", p.get("axis").unwrap_or(&none))];
+            keepdims!(options);
+            dtype_option!(options, dtype_arg);
+            if name == "mean" {
+                // mean has no dtype parameter; compute in the requested dtype
+                let a = match &dtype_arg {
+                    Some(dtype) => call(xp, "astype", (&a, dtype), &[])?,
+                    None => a,
+                };
+                options.retain(|(key, _)| *key != "dtype");
+                call(xp, "mean", (a,), &options)?
+            } else {
+                call(xp, function, (a,), &options)?
+            }
+        }
+        "std" | "var" => {
+            let a = arg!("a");
+            let mut a = a;
+            if let Some(value) = p.value("dtype") {
+                let Some(dtype) = dtype(value)? else {
+                    return Ok(None);
+                };
+                a = call(xp, "astype", (&a, dtype), &[])?;
+            }
+            let correction = p.value("correction").or(p.value("ddof")).unwrap_or(&zero);
+            let mut options = vec![("axis", p.get("axis").unwrap_or(&none)), ("correction", correction)];
+            keepdims!(options);
+            call(xp, name, (a,), &options)?
+        }
+        "argmax" | "argmin" => {
+            let a = arg!("a");
+            let mut options = vec![("axis", p.get("axis").unwrap_or(&none))];
+            keepdims!(options);
+            call(xp, name, (a,), &options)?
+        }
+        "cumsum" => {
+            let mut a = arg!("a");
+            let axis = match p.value("axis") {
+                Some(axis) => axis,
+                None => {
+                    a = call(xp, "reshape", (&a, &flat), &[])?;
+                    &zero
+                }
+            };
+            let mut options = vec![("axis", axis)];
+            dtype_option!(options, dtype_arg);
+            call(xp, "cumulative_sum", (a,), &options)?
+        }
+        "sort" | "argsort" => {
+            if p.value("kind").is_some_and(|kind| kind.extract::<String>().is_err()) {
+                return Ok(None);
+            }
+            let mut a = arg!("a");
+            let axis = match p.get("axis") {
+                Some(axis) if axis.is_none() => {
+                    a = call(xp, "reshape", (&a, &flat), &[])?;
+                    &minus_one
+                }
+                Some(axis) => axis,
+                None => &minus_one,
+            };
+            let stable = PyBool::new(py, true).to_owned().into_any();
+            let stable = p.value("stable").unwrap_or(&stable);
+            call(xp, name, (a,), &[("axis", axis), ("stable", stable)])?
+        }
+        "concatenate" | "stack" => {
+            if p.any(&["dtype"]) || p.differs("casting", "same_kind".to_string())? {
+                return Ok(None);
+            }
+            let Some(arrays) = p.get("arrays").map(|a| arrays(xp, a)).transpose()?.flatten() else {
+                return Ok(None);
+            };
+            let function = if name == "stack" { "stack" } else { "concat" };
+            call(xp, function, (arrays,), &[("axis", p.get("axis").unwrap_or(&zero))])?
+        }
+        "reshape" => {
+            if p.differs("order", "C".to_string())? {
+                return Ok(None);
+            }
+            let Some(shape) = p.value("shape").or(p.value("newshape")) else {
+                return Ok(None);
+            };
+            let copy = p.get("copy").unwrap_or(&none);
+            call(xp, "reshape", (arg!("a"), shape), &[("copy", copy)])?
+        }
+        "transpose" => {
+            let a = arg!("a");
+            let axes = match p.value("axes") {
+                Some(axes) => axes.clone(),
+                None => {
+                    let ndim: usize = a.getattr("ndim")?.extract()?;
+                    PyTuple::new(py, (0..ndim).rev())?.into_any()
+                }
+            };
+            call(xp, "permute_dims", (a, axes), &[])?
+        }
+        "dot" => {
+            let (a, b) = (arg!("a"), arg!("b"));
+            let ndims: (usize, usize) = (a.getattr("ndim")?.extract()?, b.getattr("ndim")?.extract()?);
+            match ndims {
+                (0, _) | (_, 0) => call(xp, "multiply", (a, b), &[])?,
+                (1..=2, 1..=2) => call(xp, "matmul", (a, b), &[])?,
+                _ => return Ok(None),
+            }
+        }
+        "tensordot" => {
+            let two = 2i64.into_pyobject(py)?.into_any();
+            call(xp, "tensordot", (arg!("a"), arg!("b")), &[("axes", p.value("axes").unwrap_or(&two))])?
+        }
+        "squeeze" => {
+            let a = arg!("a");
+            let axis = match p.value("axis") {
+                Some(axis) => axis.clone(),
+                None => {
+                    let shape: Vec<i64> = a.getattr("shape")?.extract()?;
+                    let axes = shape.iter().enumerate().filter(|(_, &len)| len == 1).map(|(axis, _)| axis).collect::<Vec<_>>();
+                    PyTuple::new(py, axes)?.into_any()
+                }
+            };
+            call(xp, "squeeze", (a,), &[("axis", &axis)])?
+        }
+        "expand_dims" => {
+            let Some(axis) = p.value("axis").filter(|axis| axis.is_instance_of::<PyInt>()) else {
+                return Ok(None);
+            };
+            call(xp, "expand_dims", (arg!("a"),), &[("axis", axis)])?
+        }
+        "flip" => call(xp, "flip", (arg!("m"),), &[("axis", p.get("axis").unwrap_or(&none))])?,
+        "roll" => {
+            let Some(shift) = p.value("shift") else {
+                return Ok(None);
+            };
+            call(xp, "roll", (arg!("a"), shift), &[("axis", p.get("axis").unwrap_or(&none))])?
+        }
+        "repeat" => {
+            let Some(repeats) = p.value("repeats") else {
+                return Ok(None);
+            };
+            let repeats = if repeats.is_instance_of::<PyInt>() {
+                repeats.clone()
+            } else {
+                match array(xp, repeats) {
+                    Some(repeats) => repeats,
+                    None => return Ok(None),
+                }
+            };
+            call(xp, "repeat", (arg!("a"), repeats), &[("axis", p.get("axis").unwrap_or(&none))])?
+        }
+        "tile" => {
+            let Some(reps) = p.value("reps") else {
+                return Ok(None);
+            };
+            let reps = match reps.extract::<usize>() {
+                Ok(n) => PyTuple::new(py, [n])?.into_any(),
+                Err(_) => reps.clone(),
+            };
+            call(xp, "tile", (arg!("A"), reps), &[])?
+        }
+        "where" => match (p.get("x"), p.get("y")) {
+            (Some(x), Some(y)) => {
+                let (Some(x), Some(y)) = (operand(xp, x), operand(xp, y)) else {
+                    return Ok(None);
+                };
+                call(xp, "where", (arg!("condition"), x, y), &[])?
+            }
+            (None, None) => call(xp, "nonzero", (arg!("condition"),), &[])?,
+            _ => return Ok(None),
+        },
+        "clip" => {
+            let bound = |names: [&str; 2]| -> Option<Option<Bound<'py, PyAny>>> {
+                match names.iter().find_map(|name| p.value(name)) {
+                    Some(value) => operand(xp, value).map(Some),
+                    None => Some(None),
+                }
+            };
+            let (Some(min), Some(max)) = (bound(["a_min", "min"]), bound(["a_max", "max"])) else {
+                return Ok(None);
+            };
+            let (min, max) = (min.unwrap_or_else(|| none.clone()), max.unwrap_or_else(|| none.clone()));
+            call(xp, "clip", (arg!("a"),), &[("min", &min), ("max", &max)])?
+        }
+        "nonzero" => call(xp, "nonzero", (arg!("a"),), &[])?,
+        "take" => {
+            if p.differs("mode", "raise".to_string())? {
+                return Ok(None);
+            }
+            let mut a = arg!("a");
+            let axis = match p.value("axis") {
+                Some(axis) => axis,
+                None => {
+                    a = call(xp, "reshape", (&a, &flat), &[])?;
+                    &zero
+                }
+            };
+            let indices = arg!("indices");
+            let scalar = indices.getattr("ndim")?.extract::<usize>()? == 0;
+            let indices = if scalar { call(xp, "reshape", (&indices, PyTuple::new(py, [1i64])?), &[])? } else { indices };
+            let taken = call(xp, "take", (a, indices), &[("axis", axis)])?;
+            if scalar {
+                call(xp, "squeeze", (taken,), &[("axis", axis)])?
+            } else {
+                taken
+            }
+        }
+        "searchsorted" => {
+            let Some(v) = p.get("v").and_then(|v| operand(xp, v)) else {
+                return Ok(None);
+            };
+            let left = "left".into_pyobject(py)?.into_any();
+            let side = p.value("side").unwrap_or(&left);
+            let sorter = match p.value("sorter") {
+                Some(sorter) => match array(xp, sorter) {
+                    Some(sorter) => sorter,
+                    None => return Ok(None),
+                },
+                None => none.clone(),
+            };
+            call(xp, "searchsorted", (arg!("a"), v), &[("side", side), ("sorter", &sorter)])?
+        }
+        "unique" => {
+            if p.any(&["axis"]) || p.differs("equal_nan", true)? || p.differs("sorted", true)? {
+                return Ok(None);
+            }
+            let ar = call(xp, "reshape", (arg!("ar"), &flat), &[])?;
+            let flags: Vec<bool> = ["return_index", "return_inverse", "return_counts"]
+                .iter()
+                .map(|flag| p.value(flag).map_or(Ok(false), |value| value.is_truthy()))
+                .collect::<PyResult<_>>()?;
+            if !flags.iter().any(|&flag| flag) {
+                call(xp, "unique_values", (ar,), &[])?
+            } else {
+                let unique = call(xp, "unique_all", (ar,), &[])?;
+                let mut items = vec![unique.getattr("values")?];
+                for (flag, field) in flags.iter().zip(["indices", "inverse_indices", "counts"]) {
+                    if *flag {
+                        items.push(unique.getattr(field)?);
+                    }
+                }
+                PyTuple::new(py, items)?.into_any()
+            }
+        }
+        "broadcast_to" => {
+            let Some(shape) = p.value("shape") else {
+                return Ok(None);
+            };
+            call(xp, "broadcast_to", (arg!("array"), shape), &[])?
+        }
+        "moveaxis" => {
+            let (Some(source), Some(destination)) = (p.value("source"), p.value("destination")) else {
+                return Ok(None);
+            };
+            call(xp, "moveaxis", (arg!("a"), source, destination), &[])?
+        }
+        "zeros_like" | "ones_like" | "empty_like" | "full_like" => {
+            if p.any(&["shape"]) || p.differs("order", "K".to_string())? {
+                return Ok(None);
+            }
+            let mut options = Vec::new();
+            dtype_option!(options, dtype_arg);
+            match (name, p.value("fill_value")) {
+                ("full_like", Some(fill_value)) => call(xp, name, (arg!("a"), fill_value), &options)?,
+                ("full_like", None) => return Ok(None),
+                _ => call(xp, name, (arg!("a"),), &options)?,
+            }
+        }
+        "round" | "around" => {
+            if p.differs("decimals", 0i64)? {
+                return Ok(None);
+            }
+            call(xp, "round", (arg!("a"),), &[])?
+        }
+        "real" | "imag" => call(xp, name, (arg!("val"),), &[])?,
+        "astype" => {
+            let Some(dtype) = p.value("dtype").map(dtype).transpose()?.flatten() else {
+                return Ok(None);
+            };
+            let copy = PyBool::new(py, true).to_owned().into_any();
+            call(xp, "astype", (arg!("x"), dtype), &[("copy", p.value("copy").unwrap_or(&copy))])?
+        }
+        "copy" => {
+            if p.differs("order", "K".to_string())? {
+                return Ok(None);
+            }
+            let copy = PyBool::new(py, true).to_owned().into_any();
+            call(xp, "asarray", (arg!("a"),), &[("copy", &copy)])?
+        }
+        "shape" => arg!("a").getattr("shape")?,
+        "ndim" => arg!("a").getattr("ndim")?,
+        "array_equal" => {
+            let (a1, a2) = (arg!("a1"), arg!("a2"));
+            if a1.getattr("shape")?.ne(a2.getattr("shape")?)? {
+                return Ok(Some(PyBool::new(py, false).to_owned().into_any()));
+            }
+            let mut equal = call(xp, "equal", (&a1, &a2), &[])?;
+            if p.value("equal_nan").map_or(Ok(false), |value| value.is_truthy())? {
+                let both_nan = call(xp, "logical_and", (call(xp, "isnan", (&a1,), &[])?, call(xp, "isnan", (&a2,), &[])?), &[])?;
+                equal = call(xp, "logical_or", (equal, both_nan), &[])?;
+            }
+            PyBool::new(py, call(xp, "all", (equal,), &[])?.is_truthy()?).to_owned().into_any()
+        }
+        "isclose" | "allclose" => {
+            let (Some(a), Some(b)) = (p.get("a").and_then(|a| operand(xp, a)), p.get("b").and_then(|b| operand(xp, b))) else {
+                return Ok(None);
+            };
+            let rtol = match p.value("rtol") {
+                Some(rtol) => rtol.extract::<f64>()?,
+                None => 1e-5,
+            };
+            let atol = match p.value("atol") {
+                Some(atol) => atol.extract::<f64>()?,
+                None => 1e-8,
+            };
+            // |a - b| <= atol + rtol * |b|, with exact matches covering infinities
+            let difference = call(xp, "abs", (call(xp, "subtract", (&a, &b), &[])?,), &[])?;
+            let b_abs = call(xp, "abs", (call(xp, "asarray", (&b,), &[])?,), &[])?;
+            let tolerance = call(xp, "add", (call(xp, "multiply", (b_abs, rtol), &[])?, atol), &[])?;
+            let mut close = call(xp, "logical_or", (
+                call(xp, "less_equal", (difference, tolerance), &[])?,
+                call(xp, "equal", (&a, &b), &[])?,
+            ), &[])?;
+            if p.value("equal_nan").map_or(Ok(false), |value| value.is_truthy())? {
+                let a_nan = call(xp, "isnan", (call(xp, "asarray", (&a,), &[])?,), &[])?;
+                let b_nan = call(xp, "isnan", (call(xp, "asarray", (&b,), &[])?,), &[])?;
+                let both_nan = call(xp, "logical_and", (a_nan, b_nan), &[])?;
+                close = call(xp, "logical_or", (close, both_nan), &[])?;
+            }
+            if name == "allclose" {
+                PyBool::new(py, call(xp, "all", (close,), &[])?.is_truthy()?).to_owned().into_any()
+            } else {
+                close
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(result))
+}
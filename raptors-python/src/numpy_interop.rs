@@ -1,334 +1,169 @@
 //! NumPy interoperability
 //!
-//! This module provides seamless interoperability with NumPy arrays.
+//! Arrays cross between Raptors and NumPy through the `__array_interface__`
+//! protocol, so every dtype NumPy describes with a type string is supported:
+//! bool, signed and unsigned integers, float16 through longdouble, complex,
+//! datetime64/timedelta64 with their unit, fixed-width bytes and str, and
+//! structured dtypes.
+//!
+//! Memory is shared in both directions. An array built by `from_numpy` keeps
+//! the exporting object alive as the owner of its data, and NumPy keeps the
+//! exporting `PyArray` alive as the `base` of the array `to_numpy` returns.
+//! Strides are taken as they are, negative or not, and read-only arrays stay
+//! read-only. Only data in non-native byte order is copied.
+
+#![allow(clippy::arc_with_non_send_sync)] // Arc used for Python reference counting, not thread safety
 
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use numpy::{PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
-use raptors_core::{Array, DType};
-use raptors_core::types::NpyType;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyString, PyTuple};
+use raptors_core::io::{array_descr, array_typestr, Descr, PyLiteral};
+use raptors_core::utils::to_contiguous_bytes;
+use raptors_core::Array;
+use std::any::Any;
 use std::sync::Arc;
 use crate::array::PyArray;
 
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<PyValueError, _>(format!("{}", e))
+}
+
+fn type_error(e: impl std::fmt::Display) -> PyErr {
+    PyErr::new::<PyTypeError, _>(format!("{}", e))
+}
+
 /// Convert NumPy array to Raptors array
+///
+/// Accepts any object exposing `__array_interface__`; anything else is
+/// passed through `numpy.asarray` first. The result shares memory with
+/// `np_array` unless its data is byte-swapped.
 #[pyfunction]
-pub fn from_numpy(_py: Python, np_array: &Bound<'_, PyAny>) -> PyResult<PyArray> {
-    // Try to get as PyArrayDyn first (most general)
-    #[allow(deprecated)] // TODO: Migrate to Bound::cast when numpy crate supports it
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<f64>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::Double);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        // Copy data
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut f64;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)] // Arc needed for Python reference counting
-            inner: Arc::new(array),
-        });
-    }
-    
-    // Try float32
-    #[allow(deprecated)] // TODO: Migrate to Bound::cast when numpy crate supports it
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<f32>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::Float);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut f32;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)] // Arc needed for Python reference counting
-            inner: Arc::new(array),
-        });
-    }
-    
-    // Try int32
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<i32>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::Int);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut i32;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
+pub fn from_numpy(py: Python, np_array: &Bound<'_, PyAny>) -> PyResult<PyArray> {
+    if let Ok(array) = np_array.cast::<PyArray>() {
+        return Ok(PyArray { inner: PyArray::get_inner_from_bound(array).clone() });
     }
-    
-    // Try int64
-    #[allow(deprecated)] // TODO: Migrate to Bound::cast when numpy crate supports it
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<i64>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::LongLong);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut i64;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)] // Arc needed for Python reference counting
-            inner: Arc::new(array),
-        });
-    }
-    
-    // Try int16
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<i16>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::Short);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut i16;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
+    if !np_array.hasattr("__array_interface__")? {
+        let converted = py.import("numpy")?.call_method1("asarray", (np_array,))?;
+        return from_numpy(py, &converted);
     }
-    
-    // Try int8
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<i8>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::Byte);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut i8;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
-    }
-    
-    // Try uint64
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<u64>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::ULongLong);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut u64;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
-    }
-    
-    // Try uint32
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<u32>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::UInt);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut u32;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
+    let interface = np_array.getattr("__array_interface__")?;
+    let interface = interface
+        .cast::<PyDict>()
+        .map_err(|_| type_error("__array_interface__ must be a dict"))?;
+    let required = |key: &str| {
+        interface
+            .get_item(key)?
+            .ok_or_else(|| value_error(format!("__array_interface__ has no '{}'", key)))
+    };
+
+    let shape: Vec<i64> = required("shape")?.extract()?;
+    let typestr: String = required("typestr")?.extract()?;
+    let descr = match interface.get_item("descr")? {
+        Some(descr) if is_structured(&descr)? => descr.repr()?,
+        _ => PyString::new(py, &typestr).repr()?,
+    };
+    let descr = Descr::parse(descr.to_str()?).map_err(type_error)?;
+
+    let data = required("data")?;
+    let (address, readonly): (usize, bool) = data
+        .extract()
+        .map_err(|_| type_error("__array_interface__ data must be a (pointer, read-only flag) tuple"))?;
+    let strides = match interface.get_item("strides")? {
+        Some(strides) if !strides.is_none() => strides.extract()?,
+        _ => c_strides(&shape, descr.dtype.itemsize() as i64),
+    };
+
+    if shape.iter().product::<i64>() == 0 {
+        // Nothing to share
+        let array = Array::new(shape, descr.dtype).map_err(value_error)?;
+        return Ok(PyArray { inner: Arc::new(array) });
     }
-    
-    // Try uint16
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<u16>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::UShort);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut u16;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
+    if address == 0 {
+        return Err(value_error("__array_interface__ data pointer is null"));
     }
-    
-    // Try uint8
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<u8>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::UByte);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut u8;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
+
+    let owner: Arc<dyn Any + Send + Sync> = Arc::new(np_array.clone().unbind());
+    // SAFETY: the interface describes memory of `np_array`, which `owner`
+    // keeps alive for as long as the array exists
+    let array = unsafe {
+        Array::from_owner(address as *mut u8, shape, strides, descr.dtype.clone(), owner, !readonly)
     }
-    
-    // Try bool
-    #[allow(deprecated)]
-    if let Ok(np_arr) = np_array.downcast::<PyArrayDyn<bool>>() {
-        let shape = np_arr.shape().to_vec();
-        let readonly = np_arr.readonly();
-        let data = readonly.as_slice()?;
-        
-        let dtype = DType::new(NpyType::Bool);
-        let mut array = Array::new(shape.iter().map(|&x| x as i64).collect(), dtype)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-        
-        unsafe {
-            let dst = array.data_ptr_mut() as *mut bool;
-            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
-        }
-        
-        return Ok(PyArray {
-            #[allow(clippy::arc_with_non_send_sync)]
-            inner: Arc::new(array),
-        });
+    .map_err(value_error)?;
+    if descr.is_native() {
+        return Ok(PyArray { inner: Arc::new(array) });
     }
-    
-    Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
-        "Unsupported NumPy array type"
-    ))
+
+    // Non-native byte order is swapped into a native copy
+    let mut bytes = to_contiguous_bytes(&array);
+    descr.byteswap(&mut bytes);
+    let native = Array::from_slice(&bytes, array.shape().to_vec(), descr.dtype).map_err(value_error)?;
+    Ok(PyArray { inner: Arc::new(native) })
 }
 
 /// Convert Raptors array to NumPy array
+///
+/// The result shares memory with `array`, which NumPy keeps alive as its
+/// `base`.
 #[pyfunction]
 pub fn to_numpy(py: Python, array: &PyArray) -> PyResult<Py<PyAny>> {
-    let inner = array.get_inner();
-    let shape: Vec<usize> = inner.shape().iter().map(|&x| x as usize).collect();
-    
-    match inner.dtype().type_() {
-        NpyType::Double => {
-            // Create NumPy array with correct shape using unsafe new
-            let np_array = unsafe { PyArrayDyn::<f64>::new(py, shape.as_slice(), false) };
-            // Copy data
-            unsafe {
-                let src = inner.data_ptr() as *const f64;
-                let dst = np_array.as_slice_mut()
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to get mutable slice: {}", e)))?
-                    .as_mut_ptr();
-                std::ptr::copy_nonoverlapping(src, dst, inner.size());
-            }
-            Ok(np_array.into())
-        }
-        NpyType::Float => {
-            // Create NumPy array with correct shape using unsafe new
-            let np_array = unsafe { PyArrayDyn::<f32>::new(py, shape.as_slice(), false) };
-            // Copy data
-            unsafe {
-                let src = inner.data_ptr() as *const f32;
-                let dst = np_array.as_slice_mut()
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to get mutable slice: {}", e)))?
-                    .as_mut_ptr();
-                std::ptr::copy_nonoverlapping(src, dst, inner.size());
-            }
-            Ok(np_array.into())
-        }
-        NpyType::Int => {
-            let np_array = unsafe { PyArrayDyn::<i32>::new(py, shape.as_slice(), false) };
-            unsafe {
-                let src = inner.data_ptr() as *const i32;
-                let dst = np_array.as_slice_mut()
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to get mutable slice: {}", e)))?
-                    .as_mut_ptr();
-                std::ptr::copy_nonoverlapping(src, dst, inner.size());
-            }
-            Ok(np_array.into())
-        }
-        NpyType::UInt => {
-            let np_array = unsafe { PyArrayDyn::<u32>::new(py, shape.as_slice(), false) };
-            unsafe {
-                let src = inner.data_ptr() as *const u32;
-                let dst = np_array.as_slice_mut()
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to get mutable slice: {}", e)))?
-                    .as_mut_ptr();
-                std::ptr::copy_nonoverlapping(src, dst, inner.size());
-            }
-            Ok(np_array.into())
-        }
-        NpyType::Bool => {
-            let np_array = unsafe { PyArrayDyn::<bool>::new(py, shape.as_slice(), false) };
-            unsafe {
-                let src = inner.data_ptr() as *const bool;
-                let dst = np_array.as_slice_mut()
-                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Failed to get mutable slice: {}", e)))?
-                    .as_mut_ptr();
-                std::ptr::copy_nonoverlapping(src, dst, inner.size());
+    let exporter = Py::new(py, PyArray { inner: array.inner.clone() })?;
+    Ok(py.import("numpy")?.call_method1("asarray", (exporter,))?.unbind())
+}
+
+/// `__array_interface__` dict (version 3) describing `array`
+pub(crate) fn array_interface<'py>(py: Python<'py>, array: &Array) -> PyResult<Bound<'py, PyDict>> {
+    let dtype = array.dtype();
+    let descr = array_descr(dtype).map_err(type_error)?;
+    let interface = PyDict::new(py);
+    interface.set_item("version", 3)?;
+    interface.set_item("shape", PyTuple::new(py, array.shape())?)?;
+    interface.set_item("typestr", array_typestr(dtype).map_err(type_error)?)?;
+    interface.set_item("descr", literal_object(py, &descr)?)?;
+    interface.set_item("data", (array.data_ptr() as usize, !array.is_writeable()))?;
+    if array.is_c_contiguous() {
+        interface.set_item("strides", py.None())?;
+    } else {
+        interface.set_item("strides", PyTuple::new(py, array.strides())?)?;
+    }
+    Ok(interface)
+}
+
+/// Python object of a literal: `descr` lists of `(name, format)` tuples,
+/// nested for structured fields
+fn literal_object<'py>(py: Python<'py>, literal: &PyLiteral) -> PyResult<Bound<'py, PyAny>> {
+    let items = |items: &[PyLiteral]| items.iter().map(|item| literal_object(py, item)).collect::<PyResult<Vec<_>>>();
+    Ok(match literal {
+        PyLiteral::None => py.None().into_bound(py),
+        PyLiteral::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        PyLiteral::Int(i) => i.into_pyobject(py)?.into_any(),
+        PyLiteral::Float(f) => PyFloat::new(py, *f).into_any(),
+        PyLiteral::Str(s) => PyString::new(py, s).into_any(),
+        PyLiteral::Tuple(values) => PyTuple::new(py, items(values)?)?.into_any(),
+        PyLiteral::List(values) => PyList::new(py, items(values)?)?.into_any(),
+        PyLiteral::Dict(pairs) => {
+            let dict = PyDict::new(py);
+            for (key, value) in pairs {
+                dict.set_item(literal_object(py, key)?, literal_object(py, value)?)?;
             }
-            Ok(np_array.into())
+            dict.into_any()
         }
-        _ => Err(PyErr::new::<pyo3::exceptions::PyNotImplementedError, _>(
-            "Conversion to NumPy not yet implemented for this dtype"
-        ))
+    })
+}
+
+/// Whether a `descr` list describes named fields rather than a plain type
+fn is_structured(descr: &Bound<'_, PyAny>) -> PyResult<bool> {
+    if descr.len()? != 1 {
+        return Ok(true);
     }
+    let name = descr.get_item(0)?.get_item(0)?;
+    Ok(!name.extract::<String>().map(|n| n.is_empty()).unwrap_or(false))
 }
 
+/// Byte strides of a C-contiguous array
+fn c_strides(shape: &[i64], itemsize: i64) -> Vec<i64> {
+    let mut strides = vec![itemsize; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1].max(1);
+    }
+    strides
+}
@@ -58,14 +58,16 @@ pub fn add_ufuncs(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
 /// Add two arrays (NumPy-named)
 #[pyfunction]
-fn add(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn add(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     add_arrays(a, b)
 }
 
 /// Add two arrays (legacy name)
 #[pyfunction]
-fn add_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
-    let result = operations::add(a.get_inner(), b.get_inner())
+fn add_arrays(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| operations::add(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -74,14 +76,16 @@ fn add_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Subtract two arrays (NumPy-named)
 #[pyfunction]
-fn subtract(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn subtract(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     subtract_arrays(a, b)
 }
 
 /// Subtract two arrays (legacy name)
 #[pyfunction]
-fn subtract_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
-    let result = operations::subtract(a.get_inner(), b.get_inner())
+fn subtract_arrays(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| operations::subtract(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -90,14 +94,16 @@ fn subtract_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Multiply two arrays (NumPy-named)
 #[pyfunction]
-fn multiply(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn multiply(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     multiply_arrays(a, b)
 }
 
 /// Multiply two arrays (legacy name)
 #[pyfunction]
-fn multiply_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
-    let result = operations::multiply(a.get_inner(), b.get_inner())
+fn multiply_arrays(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| operations::multiply(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -106,14 +112,16 @@ fn multiply_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Divide two arrays (NumPy-named)
 #[pyfunction]
-fn divide(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn divide(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     divide_arrays(a, b)
 }
 
 /// Divide two arrays (legacy name)
 #[pyfunction]
-fn divide_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
-    let result = operations::divide(a.get_inner(), b.get_inner())
+fn divide_arrays(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| operations::divide(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -122,14 +130,16 @@ fn divide_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Check if arrays are equal (NumPy-named)
 #[pyfunction]
-fn equal(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn equal(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     equal_arrays(a, b)
 }
 
 /// Check if arrays are equal (legacy name)
 #[pyfunction]
-fn equal_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
-    let result = operations::equal(a.get_inner(), b.get_inner())
+fn equal_arrays(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| operations::equal(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -138,14 +148,16 @@ fn equal_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Check if a < b (NumPy-named)
 #[pyfunction]
-fn less(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn less(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     less_arrays(a, b)
 }
 
 /// Check if a < b (legacy name)
 #[pyfunction]
-fn less_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
-    let result = operations::less(a.get_inner(), b.get_inner())
+fn less_arrays(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| operations::less(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -154,22 +166,24 @@ fn less_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Check if a > b (NumPy-named)
 #[pyfunction]
-fn greater(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn greater(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     greater_arrays(a, b)
 }
 
 /// Check if a > b (legacy name)
 #[pyfunction]
-fn greater_arrays(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn greater_arrays(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     // Use less with swapped arguments
     less_arrays(b, a)
 }
 
 /// Check if arrays are not equal (NumPy-named)
 #[pyfunction]
-fn not_equal(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn not_equal(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     use raptors_core::operations::not_equal as core_not_equal;
-    let result = core_not_equal(a.get_inner(), b.get_inner())
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| core_not_equal(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -178,9 +192,11 @@ fn not_equal(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Check if a <= b (NumPy-named)
 #[pyfunction]
-fn less_equal(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn less_equal(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     use raptors_core::operations::less_equal as core_less_equal;
-    let result = core_less_equal(a.get_inner(), b.get_inner())
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| core_less_equal(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -189,9 +205,11 @@ fn less_equal(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Check if a >= b (NumPy-named)
 #[pyfunction]
-fn greater_equal(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
+fn greater_equal(a: &Bound<'_, PyArray>, b: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     use raptors_core::operations::greater_equal as core_greater_equal;
-    let result = core_greater_equal(a.get_inner(), b.get_inner())
+    let py = a.py();
+    let (a, b) = (PyArray::handle(a), PyArray::handle(b));
+    let result = py.detach(|| core_greater_equal(&a, &b))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -200,13 +218,13 @@ fn greater_equal(a: &PyArray, b: &PyArray) -> PyResult<PyArray> {
 
 /// Compute sine
 #[pyfunction]
-fn sin(a: &PyArray) -> PyResult<PyArray> {
+fn sin(a: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     let ufunc = create_sin_ufunc();
-    let inner = a.get_inner();
+    let inner = PyArray::handle(a);
     let output_dtype = inner.dtype().clone();
     let mut output = empty(inner.shape().to_vec(), output_dtype)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-    create_unary_ufunc_loop(&ufunc, a.get_inner(), &mut output)
+    a.py().detach(|| create_unary_ufunc_loop(&ufunc, &inner, &mut output))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(output),
@@ -215,13 +233,13 @@ fn sin(a: &PyArray) -> PyResult<PyArray> {
 
 /// Compute cosine
 #[pyfunction]
-fn cos(a: &PyArray) -> PyResult<PyArray> {
+fn cos(a: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     let ufunc = create_cos_ufunc();
-    let inner = a.get_inner();
+    let inner = PyArray::handle(a);
     let output_dtype = inner.dtype().clone();
     let mut output = empty(inner.shape().to_vec(), output_dtype)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-    create_unary_ufunc_loop(&ufunc, a.get_inner(), &mut output)
+    a.py().detach(|| create_unary_ufunc_loop(&ufunc, &inner, &mut output))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(output),
@@ -230,13 +248,13 @@ fn cos(a: &PyArray) -> PyResult<PyArray> {
 
 /// Compute tangent
 #[pyfunction]
-fn tan(a: &PyArray) -> PyResult<PyArray> {
+fn tan(a: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     let ufunc = create_tan_ufunc();
-    let inner = a.get_inner();
+    let inner = PyArray::handle(a);
     let output_dtype = inner.dtype().clone();
     let mut output = empty(inner.shape().to_vec(), output_dtype)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-    create_unary_ufunc_loop(&ufunc, a.get_inner(), &mut output)
+    a.py().detach(|| create_unary_ufunc_loop(&ufunc, &inner, &mut output))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(output),
@@ -245,13 +263,13 @@ fn tan(a: &PyArray) -> PyResult<PyArray> {
 
 /// Compute exponential
 #[pyfunction]
-fn exp(a: &PyArray) -> PyResult<PyArray> {
+fn exp(a: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     let ufunc = create_exp_ufunc();
-    let inner = a.get_inner();
+    let inner = PyArray::handle(a);
     let output_dtype = inner.dtype().clone();
     let mut output = empty(inner.shape().to_vec(), output_dtype)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-    create_unary_ufunc_loop(&ufunc, a.get_inner(), &mut output)
+    a.py().detach(|| create_unary_ufunc_loop(&ufunc, &inner, &mut output))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(output),
@@ -260,13 +278,13 @@ fn exp(a: &PyArray) -> PyResult<PyArray> {
 
 /// Compute natural logarithm
 #[pyfunction]
-fn log(a: &PyArray) -> PyResult<PyArray> {
+fn log(a: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     let ufunc = create_log_ufunc();
-    let inner = a.get_inner();
+    let inner = PyArray::handle(a);
     let output_dtype = inner.dtype().clone();
     let mut output = empty(inner.shape().to_vec(), output_dtype)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-    create_unary_ufunc_loop(&ufunc, a.get_inner(), &mut output)
+    a.py().detach(|| create_unary_ufunc_loop(&ufunc, &inner, &mut output))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(output),
@@ -275,13 +293,13 @@ fn log(a: &PyArray) -> PyResult<PyArray> {
 
 /// Compute square root
 #[pyfunction]
-fn sqrt(a: &PyArray) -> PyResult<PyArray> {
+fn sqrt(a: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     let ufunc = create_sqrt_ufunc();
-    let inner = a.get_inner();
+    let inner = PyArray::handle(a);
     let output_dtype = inner.dtype().clone();
     let mut output = empty(inner.shape().to_vec(), output_dtype)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-    create_unary_ufunc_loop(&ufunc, a.get_inner(), &mut output)
+    a.py().detach(|| create_unary_ufunc_loop(&ufunc, &inner, &mut output))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(output),
@@ -290,13 +308,13 @@ fn sqrt(a: &PyArray) -> PyResult<PyArray> {
 
 /// Compute absolute value
 #[pyfunction]
-fn abs(a: &PyArray) -> PyResult<PyArray> {
+fn abs(a: &Bound<'_, PyArray>) -> PyResult<PyArray> {
     let ufunc = create_abs_ufunc();
-    let inner = a.get_inner();
+    let inner = PyArray::handle(a);
     let output_dtype = inner.dtype().clone();
     let mut output = empty(inner.shape().to_vec(), output_dtype)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
-    create_unary_ufunc_loop(&ufunc, a.get_inner(), &mut output)
+    a.py().detach(|| create_unary_ufunc_loop(&ufunc, &inner, &mut output))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(output),
@@ -306,8 +324,9 @@ fn abs(a: &PyArray) -> PyResult<PyArray> {
 /// Sum array elements
 #[pyfunction]
 #[pyo3(signature = (a, axis=None))]
-fn sum(a: &PyArray, axis: Option<usize>) -> PyResult<PyArray> {
-    let result = sum_along_axis(a.get_inner(), axis)
+fn sum(a: &Bound<'_, PyArray>, axis: Option<usize>) -> PyResult<PyArray> {
+    let inner = PyArray::handle(a);
+    let result = a.py().detach(|| sum_along_axis(&inner, axis))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -317,8 +336,9 @@ fn sum(a: &PyArray, axis: Option<usize>) -> PyResult<PyArray> {
 /// Mean of array elements
 #[pyfunction]
 #[pyo3(signature = (a, axis=None))]
-fn mean(a: &PyArray, axis: Option<usize>) -> PyResult<PyArray> {
-    let result = mean_along_axis(a.get_inner(), axis)
+fn mean(a: &Bound<'_, PyArray>, axis: Option<usize>) -> PyResult<PyArray> {
+    let inner = PyArray::handle(a);
+    let result = a.py().detach(|| mean_along_axis(&inner, axis))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -328,8 +348,9 @@ fn mean(a: &PyArray, axis: Option<usize>) -> PyResult<PyArray> {
 /// Minimum of array elements
 #[pyfunction]
 #[pyo3(signature = (a, axis=None))]
-fn min(a: &PyArray, axis: Option<usize>) -> PyResult<PyArray> {
-    let result = min_along_axis(a.get_inner(), axis)
+fn min(a: &Bound<'_, PyArray>, axis: Option<usize>) -> PyResult<PyArray> {
+    let inner = PyArray::handle(a);
+    let result = a.py().detach(|| min_along_axis(&inner, axis))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
@@ -339,8 +360,9 @@ fn min(a: &PyArray, axis: Option<usize>) -> PyResult<PyArray> {
 /// Maximum of array elements
 #[pyfunction]
 #[pyo3(signature = (a, axis=None))]
-fn max(a: &PyArray, axis: Option<usize>) -> PyResult<PyArray> {
-    let result = max_along_axis(a.get_inner(), axis)
+fn max(a: &Bound<'_, PyArray>, axis: Option<usize>) -> PyResult<PyArray> {
+    let inner = PyArray::handle(a);
+    let result = a.py().detach(|| max_along_axis(&inner, axis))
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e)))?;
     Ok(PyArray {
         inner: Arc::new(result),
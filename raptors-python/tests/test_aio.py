@@ -0,0 +1,257 @@
+"""Tests for the asyncio layer (raptors.aio)"""
+
+import asyncio
+import math
+
+import pytest
+import raptors
+import raptors.aio as aio
+import raptors.array_api as xp
+
+
+def run(coro):
+    return asyncio.run(coro)
+
+
+@pytest.fixture
+def queue_limit():
+    """Restore the queue limit a test changes"""
+    limit = aio.queue_limit()
+    yield
+    aio.set_queue_limit(limit)
+
+
+class TestResults:
+    """Awaited calls give the same results as the sync API"""
+
+    def test_matmul(self):
+        a = xp.asarray([[1.0, 2.0], [3.0, 4.0]])
+
+        async def main():
+            return await aio.matmul(a, a), await a.matmul_async(a)
+
+        for result in run(main()):
+            assert isinstance(result, raptors.PyArray)
+            assert result.tolist() == [[7.0, 10.0], [15.0, 22.0]]
+
+    def test_dot(self):
+        a, b = xp.asarray([1.0, 2.0]), xp.asarray([3.0, 4.0])
+
+        async def main():
+            return await a.dot_async(b), await aio.dot(xp.asarray(2.0), b)
+
+        vector, scaled = run(main())
+        assert vector.tolist() == 11.0
+        assert scaled.tolist() == [6.0, 8.0]
+
+    def test_reductions(self):
+        a = xp.asarray([[1.0, 2.0], [3.0, 4.0]])
+
+        async def main():
+            return await asyncio.gather(
+                aio.sum(a),
+                aio.prod(a, axis=0),
+                aio.mean(a, axis=1, keepdims=True),
+                aio.max(a),
+                aio.min(a, axis=1),
+                aio.var(a, correction=1),
+                a.std_async(),
+                a.sum_async(0),
+            )
+
+        total, prod, mean, largest, smallest, var, std, column = run(main())
+        assert total.tolist() == 10.0
+        assert prod.tolist() == [3.0, 8.0]
+        assert mean.tolist() == [[1.5], [3.5]]
+        assert largest.tolist() == 4.0
+        assert smallest.tolist() == [1.0, 3.0]
+        assert var.tolist() == pytest.approx(5 / 3)
+        assert std.tolist() == pytest.approx(math.sqrt(1.25))
+        assert column.tolist() == [4.0, 6.0]
+
+    def test_sort(self):
+        a = xp.asarray([[3, 1, 2], [0, 5, 4]])
+
+        async def main():
+            return await aio.sort(a), await a.sort_async(axis=0, descending=True), await a.argsort_async()
+
+        ascending, descending, indices = run(main())
+        assert ascending.tolist() == [[1, 2, 3], [0, 4, 5]]
+        assert descending.tolist() == [[3, 5, 4], [0, 1, 2]]
+        assert indices.tolist() == [[1, 2, 0], [0, 2, 1]]
+
+    def test_fft(self):
+        a = xp.asarray([1.0, 2.0, 3.0, 4.0])
+
+        async def main():
+            spectrum = await a.fft_async()
+            return spectrum, await aio.ifft(spectrum), await aio.irfft(await aio.rfft(a))
+
+        spectrum, inverse, real = run(main())
+        assert spectrum.tolist() == pytest.approx([10, -2 + 2j, -2, -2 - 2j])
+        assert [x.real for x in inverse.tolist()] == pytest.approx([1.0, 2.0, 3.0, 4.0])
+        assert real.tolist() == pytest.approx([1.0, 2.0, 3.0, 4.0])
+
+    def test_fft_axis_and_norm(self):
+        a = xp.ones((2, 4))
+
+        async def main():
+            return await aio.fft(a, axis=0, norm="ortho")
+
+        assert run(main()).tolist()[0] == pytest.approx([math.sqrt(2)] * 4)
+
+    def test_io(self, tmp_path):
+        a = xp.asarray([[1.0, 2.0], [3.0, 4.0]])
+
+        async def main():
+            assert await a.save_async(tmp_path / "a") is None
+            await aio.savez(tmp_path / "b", a, b=a)
+            await aio.savez_compressed(tmp_path / "c.npz", c=a)
+            return await aio.load(tmp_path / "a.npy"), await aio.load(tmp_path / "b.npz"), await aio.load(tmp_path / "c.npz")
+
+        loaded, archive, compressed = run(main())
+        assert loaded.tolist() == a.tolist()
+        assert sorted(archive.files) == ["arr_0", "b"]
+        assert compressed["c"].tolist() == a.tolist()
+        assert raptors.load(tmp_path / "a.npy").tolist() == a.tolist()
+
+    def test_kernel_errors_raise_on_await(self):
+        async def main():
+            future = aio.matmul(xp.ones((2, 3)), xp.ones((2, 3)))
+            with pytest.raises(ValueError):
+                await future
+
+        run(main())
+
+    def test_argument_errors_raise_on_call(self):
+        async def main():
+            with pytest.raises(ValueError):
+                aio.fft(xp.ones(4), norm="unknown")
+            with pytest.raises(ValueError):
+                aio.dot(xp.ones((2, 2, 2)), xp.ones((2, 2, 2)))
+
+        run(main())
+
+    def test_missing_file(self, tmp_path):
+        async def main():
+            with pytest.raises(OSError):
+                await aio.load(tmp_path / "missing.npy")
+
+        run(main())
+
+    def test_needs_running_loop(self):
+        with pytest.raises(RuntimeError):
+            aio.sum(xp.ones(3))
+
+
+class TestEventLoop:
+    """The event loop keeps running while kernels run on the pool"""
+
+    def test_loop_stays_responsive(self):
+        a = xp.ones((300, 300))
+
+        async def main():
+            ticks = 0
+
+            async def ticker():
+                nonlocal ticks
+                while True:
+                    await asyncio.sleep(0)
+                    ticks += 1
+
+            task = asyncio.create_task(ticker())
+            await aio.matmul(a, a)
+            task.cancel()
+            return ticks
+
+        assert run(main()) > 0
+
+    def test_many_concurrent_calls(self):
+        arrays = [xp.full((100,), float(i)) for i in range(50)]
+
+        async def main():
+            return await asyncio.gather(*(aio.sum(a) for a in arrays))
+
+        assert [r.tolist() for r in run(main())] == [100.0 * i for i in range(50)]
+        assert aio.in_flight() == 0
+
+
+class TestBackpressure:
+    """At most queue_limit() jobs are on the pool; further calls wait"""
+
+    def test_default_limit(self):
+        assert aio.queue_limit() >= 1
+
+    def test_calls_wait_for_a_slot(self, queue_limit):
+        aio.set_queue_limit(2)
+        a = xp.ones((1000, 1000))
+
+        async def main():
+            futures = [aio.sum(a) for _ in range(6)]
+            assert aio.in_flight() <= 2
+            assert aio.in_flight() + aio.waiting() <= 6
+            assert aio.waiting() >= 3
+            return await asyncio.gather(*futures)
+
+        assert [r.tolist() for r in run(main())] == [1_000_000.0] * 6
+        assert (aio.in_flight(), aio.waiting()) == (0, 0)
+
+    def test_raising_the_limit_admits_waiting_calls(self, queue_limit):
+        aio.set_queue_limit(1)
+        a = xp.ones((500, 500))
+
+        async def main():
+            futures = [aio.sum(a) for _ in range(4)]
+            aio.set_queue_limit(8)
+            assert aio.waiting() == 0
+            return await asyncio.gather(*futures)
+
+        assert len(run(main())) == 4
+
+    def test_invalid_limit(self):
+        with pytest.raises(ValueError):
+            aio.set_queue_limit(0)
+
+
+class TestCancellation:
+    """Cancelled calls stop waiting; their results are dropped"""
+
+    def test_waiting_call_never_runs(self, queue_limit, tmp_path):
+        aio.set_queue_limit(1)
+        a = xp.ones((1000, 1000))
+
+        async def main():
+            running = aio.sum(a)
+            cancelled = aio.save(tmp_path / "never", a)
+            cancelled.cancel()
+            await asyncio.sleep(0)
+            assert aio.waiting() == 0
+            await running
+            with pytest.raises(asyncio.CancelledError):
+                await cancelled
+
+        run(main())
+        assert not (tmp_path / "never.npy").exists()
+
+    def test_running_call_result_dropped(self, queue_limit):
+        aio.set_queue_limit(1)
+        a = xp.ones((1000, 1000))
+
+        async def main():
+            future = aio.sum(a)
+            future.cancel()
+            # The kernel finishes and frees its slot for later calls
+            return await aio.mean(a)
+
+        assert run(main()).tolist() == 1.0
+        assert aio.in_flight() == 0
+
+    def test_task_cancellation(self):
+        a = xp.ones((2000, 2000))
+
+        async def main():
+            with pytest.raises(asyncio.TimeoutError):
+                await asyncio.wait_for(aio.std(a), timeout=0.001)
+            return await aio.sum(xp.ones(3))
+
+        assert run(main()).tolist() == 3.0
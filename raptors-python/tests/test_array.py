@@ -29,6 +29,96 @@ class TestArrayCreation:
         assert arr.size == 12
 
 
+class TestCreationRoutines:
+    """Tests for numerical ranges and other creation routines"""
+    
+    def test_arange(self):
+        """Test arange with integer and float arguments"""
+        assert raptors.arange(5).tolist() == [0, 1, 2, 3, 4]
+        assert raptors.arange(5).dtype.name == "int64"
+        assert raptors.arange(5, 0, -2).tolist() == [5, 3, 1]
+        # Exact beyond 2**53, where float64 cannot hold every integer
+        assert raptors.arange(2**53 + 1, 2**53 + 4).tolist() == [2**53 + 1, 2**53 + 2, 2**53 + 3]
+        arr = raptors.arange(0, 1, 0.1)
+        assert arr.shape == (10,)
+        assert arr.dtype.name == "float64"
+    
+    def test_linspace_retstep(self):
+        """Test linspace with endpoint and retstep"""
+        arr, step = raptors.linspace(0, 1, 5, retstep=True)
+        assert arr.tolist() == [0.0, 0.25, 0.5, 0.75, 1.0]
+        assert step == 0.25
+        assert raptors.linspace(0, 1, 4, endpoint=False).tolist() == [0.0, 0.25, 0.5, 0.75]
+    
+    def test_logspace_geomspace(self):
+        """Test logarithmically spaced ranges"""
+        assert raptors.logspace(0, 2, 3).tolist() == [1.0, 10.0, 100.0]
+        assert raptors.logspace(0, 2, 3, base=2.0).tolist() == [1.0, 2.0, 4.0]
+        assert raptors.geomspace(1, 1000, 4).tolist() == [1.0, 10.0, 100.0, 1000.0]
+    
+    def test_full(self):
+        """Test full infers the dtype from the fill value"""
+        arr = raptors.full((2, 3), 7)
+        assert arr.shape == (2, 3)
+        assert arr.dtype.name == "int64"
+        assert arr.tolist() == [[7, 7, 7], [7, 7, 7]]
+        arr = raptors.full(2, 1.5, order="F")
+        assert arr.tolist() == [1.5, 1.5]
+        assert raptors.full(2, 1, dtype=raptors.float64).tolist() == [1.0, 1.0]
+    
+    def test_like_functions(self):
+        """Test the *_like family keeps shape and layout"""
+        proto = raptors.full((2, 3), 3, order="F")
+        arr = raptors.zeros_like(proto)
+        assert arr.is_f_contiguous
+        assert arr.tolist() == [[0, 0, 0], [0, 0, 0]]
+        assert raptors.ones_like(proto, order="C").is_c_contiguous
+        assert raptors.full_like(proto, 9).tolist() == [[9, 9, 9], [9, 9, 9]]
+        assert raptors.empty_like(proto, dtype=raptors.float64).dtype.name == "float64"
+    
+    def test_eye_identity(self):
+        """Test eye with offset diagonal and identity"""
+        assert raptors.eye(2, 3, k=1).tolist() == [[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        assert raptors.identity(2).tolist() == [[1.0, 0.0], [0.0, 1.0]]
+    
+    def test_diag_and_triangles(self):
+        """Test diag, tri, tril and triu"""
+        assert raptors.diag(raptors.arange(1, 3), 1).tolist() == [[0, 1, 0], [0, 0, 2], [0, 0, 0]]
+        assert raptors.tri(2, 3).tolist() == [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]
+        ones = raptors.full((2, 2), 1)
+        assert raptors.tril(ones).tolist() == [[1, 0], [1, 1]]
+        assert raptors.triu(ones, 1).tolist() == [[0, 1], [0, 0]]
+    
+    def test_vander(self):
+        """Test vander with decreasing and increasing powers"""
+        x = raptors.arange(1, 4)
+        assert raptors.vander(x).tolist() == [[1, 1, 1], [4, 2, 1], [9, 3, 1]]
+        assert raptors.vander(x, 2, increasing=True).tolist() == [[1, 1], [1, 2], [1, 3]]
+    
+    def test_meshgrid(self):
+        """Test meshgrid with xy and ij indexing"""
+        xv, yv = raptors.meshgrid(raptors.arange(3), raptors.arange(2))
+        assert xv.tolist() == [[0, 1, 2], [0, 1, 2]]
+        assert yv.tolist() == [[0, 0, 0], [1, 1, 1]]
+        xv, yv = raptors.meshgrid(raptors.arange(3), raptors.arange(2), indexing="ij")
+        assert xv.shape == (3, 2)
+        with pytest.raises(ValueError):
+            raptors.meshgrid(raptors.arange(3), indexing="yx")
+    
+    def test_mgrid_ogrid(self):
+        """Test slice-indexed grid helpers"""
+        assert raptors.mgrid[0:3, 0:2].shape == (2, 3, 2)
+        assert raptors.mgrid[0:1:5j].tolist() == [0.0, 0.25, 0.5, 0.75, 1.0]
+        rows, cols = raptors.ogrid[0:3, 0:1:3j]
+        assert rows.shape == (3, 1)
+        assert cols.tolist() == [[0.0, 0.5, 1.0]]
+    
+    def test_fromfunction(self):
+        """Test fromfunction passes index arrays"""
+        arr = raptors.fromfunction(lambda i, j: i, (2, 3))
+        assert arr.tolist() == [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]]
+
+
 class TestArrayProperties:
     """Tests for array properties"""
     
@@ -155,6 +245,84 @@ class TestArrayIndexing:
         value = arr[2]
         assert value == 42.0
 
+    def test_basic_indexing_views(self):
+        """Test mixed integer, slice, ellipsis and newaxis indices"""
+        arr = raptors.arange(24).reshape([2, 3, 4])
+        assert arr[1, 2, 3] == 23
+        assert arr[-1, -1, -1] == 23
+        assert arr[1].tolist() == [[12, 13, 14, 15], [16, 17, 18, 19], [20, 21, 22, 23]]
+        assert arr[:, 1].tolist() == [[4, 5, 6, 7], [16, 17, 18, 19]]
+        assert arr[..., 1].tolist() == [[1, 5, 9], [13, 17, 21]]
+        assert arr[0, ::2, ::-1].tolist() == [[3, 2, 1, 0], [11, 10, 9, 8]]
+        assert arr[None, 0, :, None].shape == (1, 3, 1, 4)
+        assert arr[...].shape == (2, 3, 4)
+
+    def test_partial_index_shares_memory(self):
+        """Test that basic indexing returns a view of the original data"""
+        arr = raptors.arange(10)
+        view = arr[2:5]
+        view[0] = 100
+        assert arr[2] == 100
+        # The view keeps its base alive once the base is gone
+        row = raptors.zeros([3, 4])[1]
+        assert row.tolist() == [0.0, 0.0, 0.0, 0.0]
+
+    def test_integer_array_indexing(self):
+        """Test advanced indexing with integer lists and arrays"""
+        arr = raptors.arange(24).reshape([2, 3, 4])
+        assert arr[[0, 1], [1, 2]].tolist() == [[4, 5, 6, 7], [20, 21, 22, 23]]
+        assert arr[:, [0, 2], 1].tolist() == [[1, 9], [13, 21]]
+        # Index arrays separated by a slice move to the front
+        picked = arr[[1, 0], :, [0, 3]]
+        assert picked.shape == (2, 3)
+        assert picked.tolist() == [[12, 16, 20], [3, 7, 11]]
+        assert raptors.arange(5)[raptors.arange(3)].tolist() == [0, 1, 2]
+
+    def test_boolean_mask_indexing(self):
+        """Test advanced indexing with boolean masks"""
+        arr = raptors.arange(24).reshape([2, 3, 4])
+        mask = raptors.arange(24.0).reshape([2, 3, 4]) > 20.0
+        assert arr[mask].tolist() == [21, 22, 23]
+        assert arr[0][[True, False, True]].tolist() == [[0, 1, 2, 3], [8, 9, 10, 11]]
+        assert arr[True].shape == (1, 2, 3, 4)
+        assert arr[False].shape == (0, 2, 3, 4)
+
+    def test_setitem_broadcasting(self):
+        """Test assigning scalars and arrays into any selection"""
+        arr = raptors.arange(10)
+        arr[::2] = 0
+        arr[[1, 3]] = [7, 8]
+        arr[raptors.arange(10.0) > 5.0] = -1
+        assert arr.tolist() == [0, 7, 0, 8, 0, 5, -1, -1, -1, -1]
+
+        grid = raptors.zeros([3, 4])
+        grid[1] = [1, 2, 3, 4]
+        grid[:, 0] = 9
+        grid[..., -1] = raptors.arange(3)
+        assert grid.tolist() == [[9.0, 0.0, 0.0, 0.0], [9.0, 2.0, 3.0, 1.0], [9.0, 0.0, 0.0, 2.0]]
+
+    def test_setitem_overlapping(self):
+        """Test assignment from a view of the same array"""
+        arr = raptors.arange(6)
+        arr[1:] = arr[:-1]
+        assert arr.tolist() == [0, 0, 1, 2, 3, 4]
+
+    def test_indexing_errors(self):
+        """Test invalid indices"""
+        arr = raptors.arange(24).reshape([2, 3, 4])
+        with pytest.raises(IndexError):
+            arr[5]
+        with pytest.raises(IndexError):
+            arr[0, 0, 0, 0]
+        with pytest.raises(IndexError):
+            arr[..., ...]
+        with pytest.raises(IndexError):
+            arr[[0, 1], [0, 1, 2]]
+        with pytest.raises(IndexError):
+            arr[1.5]
+        with pytest.raises(ValueError):
+            raptors.zeros([3, 4])[0] = [1, 2]
+
 
 class TestArrayIterator:
     """Tests for array iteration"""
@@ -0,0 +1,501 @@
+"""Conformance tests for the raptors.array_api namespace (array API 2023.12)"""
+
+import math
+
+import pytest
+import raptors
+import raptors.array_api as xp
+
+
+CREATION = [
+    "arange", "asarray", "empty", "empty_like", "eye", "from_dlpack", "full",
+    "full_like", "linspace", "meshgrid", "ones", "ones_like", "tril", "triu",
+    "zeros", "zeros_like",
+]
+DATA_TYPES = ["astype", "can_cast", "finfo", "iinfo", "isdtype", "result_type"]
+ELEMENTWISE = [
+    "abs", "acos", "acosh", "add", "asin", "asinh", "atan", "atan2", "atanh",
+    "bitwise_and", "bitwise_left_shift", "bitwise_invert", "bitwise_or",
+    "bitwise_right_shift", "bitwise_xor", "ceil", "clip", "conj", "copysign",
+    "cos", "cosh", "divide", "equal", "exp", "expm1", "floor", "floor_divide",
+    "greater", "greater_equal", "hypot", "imag", "isfinite", "isinf", "isnan",
+    "less", "less_equal", "log", "log1p", "log2", "log10", "logaddexp",
+    "logical_and", "logical_not", "logical_or", "logical_xor", "maximum",
+    "minimum", "multiply", "negative", "not_equal", "positive", "pow", "real",
+    "remainder", "round", "sign", "signbit", "sin", "sinh", "square", "sqrt",
+    "subtract", "tan", "tanh", "trunc",
+]
+INDEXING = ["take"]
+LINALG = ["matmul", "matrix_transpose", "tensordot", "vecdot"]
+MANIPULATION = [
+    "broadcast_arrays", "broadcast_to", "concat", "expand_dims", "flip",
+    "moveaxis", "permute_dims", "repeat", "reshape", "roll", "squeeze",
+    "stack", "tile", "unstack",
+]
+SEARCHING = ["argmax", "argmin", "nonzero", "searchsorted", "where"]
+SETS = ["unique_all", "unique_counts", "unique_inverse", "unique_values"]
+SORTING = ["argsort", "sort"]
+STATISTICAL = ["cumulative_sum", "max", "mean", "min", "prod", "std", "sum", "var"]
+UTILITY = ["all", "any"]
+DTYPES = [
+    "bool", "int8", "int16", "int32", "int64", "uint8", "uint16", "uint32",
+    "uint64", "float32", "float64", "complex64", "complex128",
+]
+
+
+class TestNamespace:
+    """Tests for the namespace itself"""
+
+    @pytest.mark.parametrize(
+        "name",
+        CREATION + DATA_TYPES + ELEMENTWISE + INDEXING + LINALG + MANIPULATION
+        + SEARCHING + SETS + SORTING + STATISTICAL + UTILITY,
+    )
+    def test_required_function(self, name):
+        """Test every function the standard requires is present"""
+        assert callable(getattr(xp, name))
+
+    @pytest.mark.parametrize("name", DTYPES)
+    def test_required_dtype(self, name):
+        """Test every dtype object the standard requires is present"""
+        assert getattr(xp, name).name == name
+
+    def test_constants(self):
+        """Test the required constants"""
+        assert xp.e == math.e
+        assert xp.pi == math.pi
+        assert xp.inf == math.inf
+        assert math.isnan(xp.nan)
+        assert xp.newaxis is None
+
+    def test_array_namespace(self):
+        """Test arrays report the namespace and the supported versions"""
+        arr = raptors.zeros([2])
+        assert arr.__array_namespace__() is xp
+        assert arr.__array_namespace__(api_version="2023.12") is xp
+        assert xp.__array_api_version__ == "2023.12"
+        with pytest.raises(ValueError):
+            arr.__array_namespace__(api_version="2019.01")
+
+    def test_namespace_info(self):
+        """Test __array_namespace_info__"""
+        info = xp.__array_namespace_info__()
+        assert info.capabilities()["boolean indexing"]
+        assert info.default_device() == "cpu"
+        assert info.devices() == ["cpu"]
+        defaults = info.default_dtypes()
+        assert defaults["real floating"] == xp.float64
+        assert defaults["complex floating"] == xp.complex128
+        assert defaults["integral"] == xp.int64
+        assert defaults["indexing"] == xp.int64
+        assert list(info.dtypes()) == DTYPES
+        assert list(info.dtypes(kind="real floating")) == ["float32", "float64"]
+        assert list(info.dtypes(kind=("bool", "complex floating"))) == [
+            "bool", "complex64", "complex128",
+        ]
+
+    def test_device(self):
+        """Test device, to_device and the device keyword"""
+        arr = xp.ones(3, device="cpu")
+        assert arr.device == "cpu"
+        assert arr.to_device("cpu") is arr
+        with pytest.raises(ValueError):
+            xp.ones(3, device="gpu")
+
+
+class TestDataTypes:
+    """Tests for dtype objects and data type functions"""
+
+    @pytest.mark.parametrize(
+        "a, b, expected",
+        [
+            ("int8", "uint8", "int16"),
+            ("int16", "uint32", "int64"),
+            ("uint8", "uint16", "uint16"),
+            ("float32", "float64", "float64"),
+            ("float32", "complex64", "complex64"),
+            ("float64", "complex64", "complex128"),
+            ("bool", "bool", "bool"),
+        ],
+    )
+    def test_result_type(self, a, b, expected):
+        """Test the type promotion table"""
+        assert xp.result_type(getattr(xp, a), getattr(xp, b)) == getattr(xp, expected)
+
+    def test_mixed_kind_promotion(self):
+        """Test mixed-kind promotion, which the standard leaves open, follows NumPy"""
+        assert xp.result_type(xp.int64, xp.float32) == xp.float64
+        assert xp.result_type(xp.int8, xp.float32) == xp.float32
+        assert xp.result_type(xp.int64, xp.uint64) == xp.float64
+        assert xp.add(xp.ones(2, dtype=xp.int32), xp.ones(2, dtype=xp.float32)).dtype == xp.float64
+
+    def test_dtype_equality(self):
+        """Test dtype objects compare and hash by name"""
+        assert xp.int64 == raptors.int64
+        assert xp.int64 != xp.int32
+        assert hash(xp.float32) == hash(raptors.float32)
+        assert raptors.DType("complex64") == xp.complex64
+
+    def test_can_cast_and_isdtype(self):
+        """Test can_cast and isdtype"""
+        assert xp.can_cast(xp.int8, xp.int64)
+        assert not xp.can_cast(xp.int64, xp.int8)
+        assert not xp.can_cast(xp.float64, xp.int64)
+        assert xp.can_cast(xp.ones(2, dtype=xp.float32), xp.complex64)
+        assert xp.isdtype(xp.uint8, "integral")
+        assert xp.isdtype(xp.float32, ("bool", "real floating"))
+        assert not xp.isdtype(xp.complex64, "real floating")
+        assert xp.isdtype(xp.int32, xp.int32)
+
+    def test_finfo_iinfo(self):
+        """Test machine limits"""
+        info = xp.finfo(xp.float32)
+        assert info.bits == 32
+        assert info.eps == pytest.approx(1.1920929e-07)
+        assert xp.finfo(xp.complex128).dtype == xp.float64
+        assert xp.iinfo(xp.int8).min == -128
+        assert xp.iinfo(xp.uint64).max == 2**64 - 1
+        with pytest.raises(TypeError):
+            xp.iinfo(xp.float64)
+
+    def test_astype(self):
+        """Test astype conversions and copy semantics"""
+        arr = xp.asarray([1.7, -1.7])
+        assert xp.astype(arr, xp.int32).tolist() == [1, -1]
+        assert xp.astype(arr, xp.float64, copy=False) is arr
+        assert xp.astype(arr, xp.float64) is not arr
+        assert xp.astype(xp.asarray([0, 2]), xp.bool).tolist() == [False, True]
+
+
+class TestCreation:
+    """Tests for creation functions"""
+
+    def test_asarray_inference(self):
+        """Test dtype inference from Python objects"""
+        assert xp.asarray(True).dtype == xp.bool
+        assert xp.asarray([1, 2]).dtype == xp.int64
+        assert xp.asarray([1, 2.5]).dtype == xp.float64
+        assert xp.asarray([1, 2j]).dtype == xp.complex128
+        assert xp.asarray([[1, 2], [3, 4]]).shape == (2, 2)
+        assert xp.asarray([]).shape == (0,)
+        with pytest.raises(ValueError):
+            xp.asarray([[1, 2], [3]])
+
+    def test_asarray_copy(self):
+        """Test asarray returns or copies existing arrays"""
+        arr = xp.ones(3)
+        assert xp.asarray(arr) is arr
+        copied = xp.asarray(arr, copy=True)
+        copied[0] = 5.0
+        assert arr.tolist() == [1.0, 1.0, 1.0]
+        assert xp.asarray(arr, dtype=xp.float32).dtype == xp.float32
+        with pytest.raises(ValueError):
+            xp.asarray(arr, dtype=xp.float32, copy=False)
+        with pytest.raises(ValueError):
+            xp.asarray([1, 2], copy=False)
+
+    def test_asarray_buffer(self):
+        """Test asarray shares memory with buffer exporters"""
+        data = bytearray(8)
+        arr = xp.asarray(data)
+        arr[0] = 7
+        assert data[0] == 7
+
+    def test_filled(self):
+        """Test zeros, ones, full and the _like variants"""
+        assert xp.zeros((2, 3)).shape == (2, 3)
+        assert xp.ones(2, dtype=xp.int8).tolist() == [1, 1]
+        assert xp.full((2,), 3).dtype == xp.int64
+        assert xp.full((2,), 1.5).tolist() == [1.5, 1.5]
+        assert xp.full((1,), 1j).dtype == xp.complex128
+        base = xp.ones((2, 2), dtype=xp.int32)
+        assert xp.zeros_like(base).dtype == xp.int32
+        assert xp.ones_like(base, dtype=xp.float32).dtype == xp.float32
+        assert xp.full_like(base, 4).tolist() == [[4, 4], [4, 4]]
+        assert xp.empty_like(base).shape == (2, 2)
+
+    def test_ranges(self):
+        """Test arange, linspace and eye"""
+        assert xp.arange(4).tolist() == [0, 1, 2, 3]
+        assert xp.arange(4).dtype == xp.int64
+        assert xp.arange(1, 2, 0.5).tolist() == [1.0, 1.5]
+        assert xp.linspace(0, 1, 5).tolist() == [0.0, 0.25, 0.5, 0.75, 1.0]
+        assert xp.linspace(0, 1, 4, endpoint=False).tolist() == [0.0, 0.25, 0.5, 0.75]
+        assert xp.linspace(0, 2j, 3).tolist() == [0j, 1j, 2j]
+        assert xp.eye(2, 3, k=1).tolist() == [[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+
+    def test_meshgrid_tril_triu(self):
+        """Test meshgrid, tril and triu"""
+        x, y = xp.meshgrid(xp.arange(3), xp.arange(2))
+        assert x.shape == (2, 3)
+        assert y.tolist() == [[0, 0, 0], [1, 1, 1]]
+        i, _ = xp.meshgrid(xp.arange(3), xp.arange(2), indexing="ij")
+        assert i.shape == (3, 2)
+        full = xp.ones((3, 3))
+        assert xp.tril(full).tolist()[0] == [1.0, 0.0, 0.0]
+        assert xp.triu(full, k=1).tolist()[2] == [0.0, 0.0, 0.0]
+
+
+class TestElementwise:
+    """Tests for element-wise functions and operators"""
+
+    def test_integer_arithmetic(self):
+        """Test integer arrays keep integer semantics"""
+        a = xp.asarray([7, -7])
+        assert (a + 1).dtype == xp.int64
+        assert (a // 2).tolist() == [3, -4]
+        assert (a % 3).tolist() == [1, 2]
+        assert (a * a).tolist() == [49, 49]
+        assert (-a).tolist() == [-7, 7]
+        assert (a / 2).dtype == xp.float64
+
+    def test_wrapping_and_bitwise(self):
+        """Test fixed-width integers wrap and bitwise operators"""
+        a = xp.asarray([127], dtype=xp.int8)
+        assert (a + 1).tolist() == [-128]
+        b = xp.asarray([0b1100, 0b1010])
+        assert (b & 0b1000).tolist() == [8, 8]
+        assert (b | 1).tolist() == [13, 11]
+        assert (b ^ b).tolist() == [0, 0]
+        assert (b << 1).tolist() == [24, 20]
+        assert (~xp.asarray([0])).tolist() == [-1]
+        assert (~xp.asarray([True, False])).tolist() == [False, True]
+
+    def test_python_scalars(self):
+        """Test Python scalars take the dtype of the array"""
+        a = xp.ones(2, dtype=xp.float32)
+        assert (a + 1).dtype == xp.float32
+        assert (2.5 * a).dtype == xp.float32
+        assert (a + 1j).dtype == xp.complex64
+        assert (xp.ones(2, dtype=xp.int8) + 1.0).dtype == xp.float64
+        assert (1 - xp.asarray([3])).tolist() == [-2]
+        with pytest.raises(ValueError):
+            xp.ones(2, dtype=xp.uint8) + 300
+
+    def test_comparisons(self):
+        """Test comparison operators return bool arrays"""
+        a = xp.asarray([1, 2, 3])
+        assert (a < 2).tolist() == [True, False, False]
+        assert (a >= 2).tolist() == [False, True, True]
+        assert (a == xp.asarray([1, 0, 3])).tolist() == [True, False, True]
+        assert (a != 2).dtype == xp.bool
+
+    def test_broadcasting(self):
+        """Test binary functions broadcast their operands"""
+        col = xp.reshape(xp.arange(3), (3, 1))
+        row = xp.arange(2)
+        assert xp.add(col, row).shape == (3, 2)
+        with pytest.raises(ValueError):
+            xp.add(xp.ones(3), xp.ones(2))
+
+    def test_math(self):
+        """Test a selection of mathematical functions"""
+        assert xp.sqrt(xp.asarray([4.0])).tolist() == [2.0]
+        assert xp.exp(xp.asarray([0.0])).tolist() == [1.0]
+        assert xp.round(xp.asarray([0.5, 1.5, 2.5])).tolist() == [0.0, 2.0, 2.0]
+        assert xp.sign(xp.asarray([-3, 0, 2])).tolist() == [-1, 0, 1]
+        assert xp.isnan(xp.asarray([xp.nan, 1.0])).tolist() == [True, False]
+        assert xp.logaddexp(xp.asarray([1000.0]), xp.asarray([1000.0])).tolist()[0] == pytest.approx(1000.0 + math.log(2))
+        assert xp.atan2(xp.asarray([1.0]), xp.asarray([1.0])).tolist()[0] == pytest.approx(math.pi / 4)
+        assert xp.maximum(xp.asarray([1, 5]), xp.asarray([4, 2])).tolist() == [4, 5]
+
+    def test_complex(self):
+        """Test complex element-wise functions"""
+        z = xp.asarray([3 + 4j])
+        assert xp.abs(z).tolist() == [5.0]
+        assert xp.abs(z).dtype == xp.float64
+        assert xp.conj(z).tolist() == [3 - 4j]
+        assert xp.real(z).tolist() == [3.0]
+        assert xp.imag(z).tolist() == [4.0]
+        assert xp.sqrt(xp.asarray([-4 + 0j])).tolist() == [2j]
+
+    def test_clip_and_where(self):
+        """Test clip and where"""
+        a = xp.asarray([1, 5, 9])
+        assert xp.clip(a, 2, 6).tolist() == [2, 5, 6]
+        assert xp.clip(a, max=4).tolist() == [1, 4, 4]
+        assert xp.where(a > 4, a, 0).tolist() == [0, 5, 9]
+
+    def test_in_place(self):
+        """Test in-place operators keep the dtype of the array"""
+        a = xp.asarray([1, 2, 3])
+        a += 2
+        assert a.tolist() == [3, 4, 5]
+        assert a.dtype == xp.int64
+        a *= a
+        assert a.tolist() == [9, 16, 25]
+        b = xp.ones(2, dtype=xp.float32)
+        b /= 2
+        assert b.dtype == xp.float32
+        with pytest.raises(ValueError):
+            c = xp.ones(2)
+            c += xp.ones((2, 2))
+
+    def test_python_conversions(self):
+        """Test bool, int, float, complex and index of one-element arrays"""
+        assert bool(xp.asarray([True])) is True
+        assert int(xp.asarray(7)) == 7
+        assert float(xp.asarray(2.5)) == 2.5
+        assert complex(xp.asarray(1 + 2j)) == 1 + 2j
+        assert [10, 20, 30][xp.asarray(1)] == 20
+        with pytest.raises(TypeError):
+            [1][xp.asarray(0.0)]
+        with pytest.raises(ValueError):
+            bool(xp.ones(2))
+
+    def test_unsupported_operand(self):
+        """Test unsupported operands raise TypeError"""
+        with pytest.raises(TypeError):
+            xp.ones(2) + "a"
+
+
+class TestLinearAlgebra:
+    """Tests for linear algebra functions"""
+
+    def test_matmul(self):
+        """Test matmul and the @ operator"""
+        a = xp.reshape(xp.arange(6), (2, 3))
+        assert (a @ a.mT).tolist() == [[5, 14], [14, 50]]
+        assert xp.matmul(xp.arange(3), xp.arange(3)).tolist() == 5
+        assert xp.matmul(xp.ones((4, 2, 3)), xp.ones((3, 5))).shape == (4, 2, 5)
+        with pytest.raises(ValueError):
+            xp.matmul(xp.ones((2, 3)), xp.ones((2, 3)))
+
+    def test_transposes(self):
+        """Test T and mT"""
+        a = xp.ones((2, 3, 4))
+        assert a.mT.shape == (2, 4, 3)
+        assert xp.matrix_transpose(a).shape == (2, 4, 3)
+        assert xp.ones((2, 3)).T.shape == (3, 2)
+        with pytest.raises(ValueError):
+            a.T
+
+    def test_tensordot_vecdot(self):
+        """Test tensordot and vecdot"""
+        assert xp.tensordot(xp.ones((2, 3)), xp.ones((3, 4)), axes=1).shape == (2, 4)
+        assert xp.tensordot(xp.ones((2, 3)), xp.ones((3, 2)), axes=([1], [0])).shape == (2, 2)
+        assert xp.vecdot(xp.asarray([1j]), xp.asarray([1j])).tolist() == 1 + 0j
+        assert xp.vecdot(xp.ones((2, 3)), xp.ones(3)).tolist() == [3.0, 3.0]
+
+
+class TestManipulation:
+    """Tests for manipulation functions"""
+
+    def test_views(self):
+        """Test functions that return views"""
+        a = xp.reshape(xp.arange(6), (2, 3))
+        assert xp.expand_dims(a, axis=1).shape == (2, 1, 3)
+        assert xp.squeeze(xp.ones((1, 3, 1)), axis=(0, 2)).shape == (3,)
+        assert xp.flip(a, axis=1).tolist() == [[2, 1, 0], [5, 4, 3]]
+        assert xp.flip(a).tolist() == [[5, 4, 3], [2, 1, 0]]
+        assert xp.permute_dims(a, (1, 0)).shape == (3, 2)
+        assert xp.moveaxis(xp.ones((2, 3, 4)), 0, -1).shape == (3, 4, 2)
+        assert xp.broadcast_to(xp.arange(3), (2, 3)).tolist() == [[0, 1, 2], [0, 1, 2]]
+        first, second = xp.broadcast_arrays(xp.ones((2, 1)), xp.ones(3))
+        assert first.shape == second.shape == (2, 3)
+        assert [row.tolist() for row in xp.unstack(a)] == [[0, 1, 2], [3, 4, 5]]
+
+    def test_reshape(self):
+        """Test reshape inference and copy semantics"""
+        a = xp.arange(6)
+        assert xp.reshape(a, (-1, 2)).shape == (3, 2)
+        with pytest.raises(ValueError):
+            xp.reshape(a, (4, -1))
+        with pytest.raises(ValueError):
+            xp.reshape(xp.permute_dims(xp.reshape(a, (2, 3)), (1, 0)), (6,), copy=False)
+
+    def test_copies(self):
+        """Test concat, stack, roll, repeat and tile"""
+        a = xp.asarray([1, 2])
+        assert xp.concat([a, xp.asarray([3])]).tolist() == [1, 2, 3]
+        assert xp.concat([xp.ones((1, 2)), xp.zeros((1, 2))], axis=None).tolist() == [1.0, 1.0, 0.0, 0.0]
+        assert xp.concat([a, xp.asarray([0.5])]).dtype == xp.float64
+        assert xp.stack([a, a], axis=1).tolist() == [[1, 1], [2, 2]]
+        assert xp.roll(xp.arange(4), 1).tolist() == [3, 0, 1, 2]
+        assert xp.repeat(a, 2).tolist() == [1, 1, 2, 2]
+        assert xp.repeat(a, xp.asarray([1, 3])).tolist() == [1, 2, 2, 2]
+        assert xp.tile(a, (2,)).tolist() == [1, 2, 1, 2]
+
+
+class TestSearchingSortingSets:
+    """Tests for searching, sorting and set functions"""
+
+    def test_argmax_argmin(self):
+        """Test argmax and argmin"""
+        a = xp.asarray([[1, 9, 3], [7, 2, 8]])
+        assert xp.argmax(a).tolist() == 1
+        assert xp.argmin(a, axis=1).tolist() == [0, 1]
+        assert xp.argmax(a, axis=0, keepdims=True).shape == (1, 3)
+
+    def test_nonzero_take_searchsorted(self):
+        """Test nonzero, take and searchsorted"""
+        rows, cols = xp.nonzero(xp.asarray([[0, 1], [2, 0]]))
+        assert rows.tolist() == [0, 1]
+        assert cols.tolist() == [1, 0]
+        assert xp.take(xp.asarray([10, 20, 30]), xp.asarray([2, 0])).tolist() == [30, 10]
+        sorted_ = xp.asarray([1, 3, 3, 5])
+        assert xp.searchsorted(sorted_, xp.asarray([3])).tolist() == [1]
+        assert xp.searchsorted(sorted_, xp.asarray([3]), side="right").tolist() == [3]
+
+    def test_sort(self):
+        """Test sort and argsort"""
+        a = xp.asarray([3.0, xp.nan, 1.0, 2.0])
+        assert xp.sort(a).tolist()[:3] == [1.0, 2.0, 3.0]
+        assert math.isnan(xp.sort(a).tolist()[3])
+        assert xp.argsort(xp.asarray([2, 1, 2]), descending=True).tolist() == [0, 2, 1]
+        assert xp.sort(xp.asarray([[3, 1], [2, 4]]), axis=0).tolist() == [[2, 1], [3, 4]]
+
+    def test_unique(self):
+        """Test the unique_* functions and their named tuples"""
+        a = xp.asarray([3, 1, 3, 2])
+        result = xp.unique_all(a)
+        assert result.values.tolist() == [1, 2, 3]
+        assert result.indices.tolist() == [1, 3, 0]
+        assert result.inverse_indices.tolist() == [2, 0, 2, 1]
+        assert result.counts.tolist() == [1, 1, 2]
+        values, counts = xp.unique_counts(a)
+        assert counts.tolist() == [1, 1, 2]
+        assert xp.unique_inverse(a).inverse_indices.tolist() == [2, 0, 2, 1]
+        assert xp.unique_values(a).tolist() == [1, 2, 3]
+
+
+class TestStatistical:
+    """Tests for statistical and utility functions"""
+
+    def test_sum_prod(self):
+        """Test sum and prod dtypes and axes"""
+        a = xp.asarray([[1, 2], [3, 4]], dtype=xp.int8)
+        assert xp.sum(a).dtype == xp.int64
+        assert xp.sum(a, axis=0).tolist() == [4, 6]
+        assert xp.sum(a, axis=(0, 1), keepdims=True).shape == (1, 1)
+        assert xp.prod(a, dtype=xp.float64).tolist() == 24.0
+        assert xp.sum(xp.ones(3, dtype=xp.float32)).dtype == xp.float32
+
+    def test_min_max_mean(self):
+        """Test max, min and mean"""
+        a = xp.asarray([[1.0, 5.0], [3.0, 2.0]])
+        assert xp.max(a).tolist() == 5.0
+        assert xp.min(a, axis=1).tolist() == [1.0, 2.0]
+        assert xp.mean(a, axis=0).tolist() == [2.0, 3.5]
+        with pytest.raises(ValueError):
+            xp.max(xp.ones(0))
+
+    def test_var_std(self):
+        """Test var and std with a correction"""
+        a = xp.asarray([1.0, 2.0, 3.0, 4.0])
+        assert xp.var(a).tolist() == 1.25
+        assert xp.var(a, correction=1).tolist() == pytest.approx(5 / 3)
+        assert xp.std(a).tolist() == pytest.approx(math.sqrt(1.25))
+
+    def test_cumulative_sum(self):
+        """Test cumulative_sum"""
+        a = xp.asarray([1, 2, 3])
+        assert xp.cumulative_sum(a).tolist() == [1, 3, 6]
+        assert xp.cumulative_sum(a, include_initial=True).tolist() == [0, 1, 3, 6]
+
+    def test_all_any(self):
+        """Test all and any"""
+        a = xp.asarray([[True, False], [True, True]])
+        assert xp.all(a, axis=0).tolist() == [True, False]
+        assert xp.any(a, axis=1).tolist() == [True, True]
+        assert xp.all(xp.ones(0)).tolist() is True
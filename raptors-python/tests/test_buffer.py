@@ -0,0 +1,131 @@
+"""Python pytest tests for the buffer protocol and asarray"""
+
+import array
+import ctypes
+import gc
+import struct
+
+import pytest
+import raptors
+
+
+class TestBufferExport:
+    """Tests for memoryview over Raptors arrays"""
+
+    def test_memoryview_layout(self):
+        """Test format, shape, strides and contents of an exported buffer"""
+        arr = raptors.arange(6.0).reshape([2, 3])
+        view = memoryview(arr)
+        assert view.format == "d"
+        assert view.shape == (2, 3)
+        assert view.strides == (24, 8)
+        assert not view.readonly
+        assert view.tolist() == [[0.0, 1.0, 2.0], [3.0, 4.0, 5.0]]
+
+    def test_memoryview_shares_memory(self):
+        """Test writes through a memoryview reach the array"""
+        arr = raptors.zeros([2, 2], dtype=raptors.float64)
+        memoryview(arr)[1, 0] = 4.0
+        assert arr.tolist() == [[0.0, 0.0], [4.0, 0.0]]
+
+    def test_memoryview_strided(self):
+        """Test non-contiguous arrays export their strides"""
+        arr = raptors.arange(6.0).reshape([2, 3])
+        view = memoryview(arr.transpose())
+        assert view.strides == (8, 24)
+        assert not view.c_contiguous
+        assert view.tolist() == [[0.0, 3.0], [1.0, 4.0], [2.0, 5.0]]
+
+    def test_memoryview_keeps_array_alive(self):
+        """Test the buffer holds a reference to the array"""
+        view = memoryview(raptors.arange(3.0))
+        gc.collect()
+        assert view.tolist() == [0.0, 1.0, 2.0]
+
+    def test_memoryview_readonly(self):
+        """Test read-only arrays export read-only buffers"""
+        arr = raptors.asarray(b"abc")
+        assert memoryview(arr).readonly
+
+    def test_bool_format(self):
+        """Test bool arrays use the '?' format"""
+        arr = raptors.asarray(memoryview(b"\x01\x00").cast("?"))
+        assert memoryview(arr).format == "?"
+
+
+class TestAsarray:
+    """Tests for asarray over buffer exporters"""
+
+    def test_bytes(self):
+        """Test bytes become a read-only uint8 array"""
+        arr = raptors.asarray(b"\x01\x02\x03")
+        assert arr.dtype.name == "uint8"
+        assert arr.tolist() == [1, 2, 3]
+        with pytest.raises(ValueError):
+            arr[0] = 5
+
+    def test_bytearray_shares_memory(self):
+        """Test writes to the array reach the bytearray"""
+        data = bytearray(2)
+        raptors.asarray(data)[1] = 7
+        assert data == bytearray(b"\x00\x07")
+
+    def test_array_module(self):
+        """Test array.array is shared and locked while in use"""
+        data = array.array("d", [1.5, 2.5])
+        arr = raptors.asarray(data)
+        arr[0] = 9.0
+        assert data[0] == 9.0
+        with pytest.raises(BufferError):
+            data.append(3.0)
+        del arr
+        gc.collect()
+        data.append(3.0)
+
+    def test_negative_strides(self):
+        """Test reversed memoryviews are wrapped without copying"""
+        arr = raptors.arange(6.0).reshape([2, 3])
+        reversed_rows = raptors.asarray(memoryview(arr)[::-1])
+        assert reversed_rows.tolist() == [[3.0, 4.0, 5.0], [0.0, 1.0, 2.0]]
+        reversed_rows[0, 0] = -1.0
+        assert arr[1, 0] == -1.0
+
+    def test_dtype_conversion(self):
+        """Test a different dtype gives a converted copy"""
+        arr = raptors.asarray(array.array("i", [1, 2, 3]), dtype=raptors.float64)
+        assert arr.dtype.name == "float64"
+        assert arr.tolist() == [1.0, 2.0, 3.0]
+
+    def test_passthrough_and_lists(self):
+        """Test arrays are returned as is and lists are converted"""
+        arr = raptors.arange(3.0)
+        assert raptors.asarray(arr) is arr
+        assert raptors.asarray([1.0, 2.0]).tolist() == [1.0, 2.0]
+
+    def test_array_interface(self):
+        """Test objects with only __array_interface__, like PIL images"""
+        source = raptors.arange(6.0).reshape([2, 3])
+
+        class Image:
+            __array_interface__ = source.__array_interface__
+
+        assert raptors.asarray(Image()).tolist() == source.tolist()
+
+    def test_structured_roundtrip(self):
+        """Test struct layouts survive export and import"""
+        data = bytearray(struct.pack("<id", 7, 2.5))
+        address = ctypes.addressof(ctypes.c_char.from_buffer(data))
+
+        class Record:
+            __array_interface__ = {
+                "version": 3, "shape": (1,), "typestr": "|V12", "data": (address, False),
+                "descr": [("a", "<i4"), ("b", "<f8")],
+            }
+
+        arr = raptors.asarray(Record())
+        view = memoryview(arr)
+        assert view.format == "T{<i:a:<d:b:}"
+        assert view.itemsize == 12
+        back = raptors.asarray(view)
+        assert back.__array_interface__["descr"] == [("a", "<i4"), ("b", "<f8")]
+        assert bytes(memoryview(back)) == bytes(data)
@@ -0,0 +1,160 @@
+"""Python pytest tests for the DLPack protocol and from_dlpack"""
+
+import ctypes
+import gc
+
+import pytest
+import raptors
+
+
+def capsule_name(capsule):
+    """Name of a PyCapsule"""
+    get_name = ctypes.pythonapi.PyCapsule_GetName
+    get_name.restype = ctypes.c_char_p
+    get_name.argtypes = [ctypes.py_object]
+    return get_name(capsule)
+
+
+def data_address(arr):
+    return arr.__array_interface__["data"][0]
+
+
+class LegacyProducer:
+    """Producer that predates DLPack 1.0 and takes no keywords"""
+
+    def __init__(self, arr):
+        self.arr = arr
+
+    def __dlpack__(self):
+        return self.arr.__dlpack__()
+
+    def __dlpack_device__(self):
+        return self.arr.__dlpack_device__()
+
+
+class TestDLPackExport:
+    """Tests for __dlpack__ and __dlpack_device__"""
+
+    def test_device(self):
+        assert raptors.arange(3.0).__dlpack_device__() == (1, 0)
+
+    def test_capsule_names(self):
+        """Test the capsule kind follows max_version"""
+        arr = raptors.arange(3.0)
+        assert capsule_name(arr.__dlpack__()) == b"dltensor"
+        assert capsule_name(arr.__dlpack__(max_version=(0, 8))) == b"dltensor"
+        assert capsule_name(arr.__dlpack__(max_version=(1, 0))) == b"dltensor_versioned"
+
+    def test_unconsumed_capsule_is_freed(self):
+        """Test dropping a capsule nobody consumed releases the array"""
+        arr = raptors.arange(3.0)
+        for _ in range(100):
+            arr.__dlpack__()
+            arr.__dlpack__(max_version=(1, 0))
+        gc.collect()
+        assert arr.tolist() == [0.0, 1.0, 2.0]
+
+    def test_stream_must_be_none(self):
+        with pytest.raises(RuntimeError):
+            raptors.arange(3.0).__dlpack__(stream=1)
+
+    def test_unsupported_device(self):
+        arr = raptors.arange(3.0)
+        assert capsule_name(arr.__dlpack__(dl_device=(1, 0))) == b"dltensor"
+        with pytest.raises(BufferError):
+            arr.__dlpack__(dl_device=(2, 0))
+
+    def test_readonly_needs_versioned(self):
+        """Test read-only arrays are only exported with the read-only flag"""
+        arr = raptors.asarray(b"abc")
+        with pytest.raises(BufferError):
+            arr.__dlpack__()
+        back = raptors.from_dlpack(arr.__dlpack__(max_version=(1, 0)))
+        assert back.__array_interface__["data"][1]
+        assert bytes(memoryview(back)) == b"abc"
+
+
+class TestFromDLPack:
+    """Tests for raptors.from_dlpack"""
+
+    def test_shares_memory(self):
+        arr = raptors.arange(4.0)
+        back = raptors.from_dlpack(arr)
+        assert data_address(back) == data_address(arr)
+        memoryview(back)[1] = 9.0
+        assert arr.tolist() == [0.0, 9.0, 2.0, 3.0]
+
+    def test_capsule_renamed(self):
+        """Test consumed capsules are renamed and cannot be used twice"""
+        arr = raptors.arange(3.0)
+        for max_version, used in [(None, b"used_dltensor"), ((1, 0), b"used_dltensor_versioned")]:
+            capsule = arr.__dlpack__(max_version=max_version)
+            back = raptors.from_dlpack(capsule)
+            assert capsule_name(capsule) == used
+            assert back.tolist() == [0.0, 1.0, 2.0]
+            with pytest.raises(ValueError):
+                raptors.from_dlpack(capsule)
+
+    def test_keeps_producer_alive(self):
+        arr = raptors.arange(6.0).reshape([2, 3])
+        address = data_address(arr)
+        back = raptors.from_dlpack(arr)
+        del arr
+        gc.collect()
+        assert data_address(back) == address
+        assert back.tolist() == [[0.0, 1.0, 2.0], [3.0, 4.0, 5.0]]
+
+    def test_strided(self):
+        """Test element strides survive the round trip"""
+        arr = raptors.arange(6.0).reshape([2, 3]).transpose()
+        back = raptors.from_dlpack(arr)
+        assert back.__array_interface__["strides"] == (8, 24)
+        assert back.tolist() == [[0.0, 3.0], [1.0, 4.0], [2.0, 5.0]]
+
+    def test_dtypes(self):
+        for dtype in [raptors.bool_, raptors.int8, raptors.uint16, raptors.int32,
+                      raptors.int64, raptors.float32, raptors.float64]:
+            arr = raptors.zeros([2, 2], dtype=dtype)
+            back = raptors.from_dlpack(arr)
+            assert str(back.dtype) == str(arr.dtype)
+            assert back.__array_interface__["typestr"] == arr.__array_interface__["typestr"]
+
+    def test_copy(self):
+        arr = raptors.arange(3.0)
+        back = raptors.from_dlpack(arr, copy=True)
+        assert data_address(back) != data_address(arr)
+        assert back.tolist() == [0.0, 1.0, 2.0]
+
+    def test_legacy_producer(self):
+        arr = raptors.arange(3.0)
+        back = raptors.from_dlpack(LegacyProducer(arr))
+        assert data_address(back) == data_address(arr)
+
+    def test_device(self):
+        arr = raptors.arange(3.0)
+        assert raptors.from_dlpack(arr, device="cpu").tolist() == [0.0, 1.0, 2.0]
+        with pytest.raises(ValueError):
+            raptors.from_dlpack(arr, device="cuda")
+
+    def test_not_a_producer(self):
+        with pytest.raises(TypeError):
+            raptors.from_dlpack([1.0, 2.0])
+
+
+class TestNumPyDLPack:
+    """Tests for DLPack exchange with NumPy"""
+
+    def test_from_numpy(self):
+        np = pytest.importorskip("numpy")
+        src = np.arange(12, dtype=np.float32).reshape(3, 4)[:, ::2]
+        back = raptors.from_dlpack(src)
+        assert data_address(back) == src.__array_interface__["data"][0]
+        assert back.tolist() == src.tolist()
+
+    def test_to_numpy(self):
+        np = pytest.importorskip("numpy")
+        arr = raptors.arange(6.0).reshape([2, 3])
+        out = np.from_dlpack(arr)
+        assert out.__array_interface__["data"][0] == data_address(arr)
+        out[0, 0] = 7.0
+        assert arr.tolist()[0][0] == 7.0
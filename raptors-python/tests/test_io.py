@@ -0,0 +1,73 @@
+"""Python pytest tests for file I/O bindings"""
+
+import pytest
+import raptors
+
+
+class TestNpy:
+    """Tests for save/load of single arrays"""
+
+    def test_save_load_roundtrip(self, tmp_path):
+        """Test an array survives save and load"""
+        raptors.save(str(tmp_path / "a.npy"), raptors.arange(6))
+        loaded = raptors.load(str(tmp_path / "a.npy"))
+        assert loaded.shape == (6,)
+        assert loaded.dtype.name == "int64"
+        assert loaded.tolist() == [0, 1, 2, 3, 4, 5]
+        raptors.save(str(tmp_path / "b.npy"), raptors.eye(2, 3))
+        assert raptors.load(str(tmp_path / "b.npy")).tolist() == [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+
+    def test_save_appends_extension(self, tmp_path):
+        """Test save adds a missing .npy extension"""
+        raptors.save(str(tmp_path / "c"), raptors.ones([2]))
+        assert (tmp_path / "c.npy").exists()
+
+    def test_load_missing_file(self, tmp_path):
+        """Test loading a missing file raises OSError"""
+        with pytest.raises(OSError):
+            raptors.load(str(tmp_path / "missing.npy"))
+
+
+class TestNpz:
+    """Tests for savez, savez_compressed and NpzFile"""
+
+    def test_savez_named_and_positional(self, tmp_path):
+        """Test positional arrays are named arr_0, arr_1, ..."""
+        path = str(tmp_path / "archive.npz")
+        raptors.savez(path, raptors.arange(3), raptors.ones([2]), weights=raptors.full((2,), 0.5))
+        npz = raptors.load(path)
+        assert isinstance(npz, raptors.NpzFile)
+        assert npz.files == ["arr_0", "arr_1", "weights"]
+        assert len(npz) == 3
+        assert list(npz) == npz.files
+        assert npz["arr_0"].tolist() == [0, 1, 2]
+        assert npz["weights"].tolist() == [0.5, 0.5]
+
+    def test_savez_compressed(self, tmp_path):
+        """Test compressed archives are smaller and load identically"""
+        arr = raptors.zeros([100, 100])
+        raptors.savez(str(tmp_path / "plain.npz"), a=arr)
+        raptors.savez_compressed(str(tmp_path / "packed.npz"), a=arr)
+        assert (tmp_path / "packed.npz").stat().st_size < (tmp_path / "plain.npz").stat().st_size
+        loaded = raptors.load(str(tmp_path / "packed.npz"))["a"]
+        assert loaded.shape == (100, 100)
+        assert all(v == 0.0 for row in loaded.tolist() for v in row)
+
+    def test_savez_appends_extension(self, tmp_path):
+        """Test savez adds a missing .npz extension"""
+        raptors.savez(str(tmp_path / "c"), x=raptors.ones([1]))
+        assert (tmp_path / "c.npz").exists()
+
+    def test_mapping_interface(self, tmp_path):
+        """Test membership, key errors and the context manager"""
+        path = str(tmp_path / "m.npz")
+        raptors.savez(path, x=raptors.arange(4))
+        with raptors.load(path) as npz:
+            assert "x" in npz
+            assert "x.npy" in npz
+            assert "y" not in npz
+            assert npz.keys() == ["x"]
+            with pytest.raises(KeyError):
+                npz["y"]
+        with pytest.raises(ValueError):
+            npz["x"]
@@ -0,0 +1,246 @@
+"""Tests for the NumPy dispatch protocols (__array_ufunc__ and __array_function__)"""
+
+import math
+
+import pytest
+import raptors
+import raptors.array_api as xp
+
+
+class FakeUfunc:
+    """Stand-in for a NumPy ufunc; the protocols only look at its name"""
+
+    def __init__(self, name):
+        self.__name__ = name
+
+
+def numpy_function(name):
+    """Stand-in for a NumPy function with the given name"""
+
+    def func(*args, **kwargs):
+        raise AssertionError("dispatch should not call the NumPy implementation")
+
+    func.__name__ = name
+    func.__module__ = "numpy"
+    return func
+
+
+def ufunc(name, *inputs, method="__call__", **kwargs):
+    arr = next(x for x in inputs if isinstance(x, raptors.PyArray))
+    return arr.__array_ufunc__(FakeUfunc(name), method, *inputs, **kwargs)
+
+
+def function(name, *args, **kwargs):
+    first = args[0][0] if isinstance(args[0], list) else args[0]
+    return first.__array_function__(numpy_function(name), (type(first),), args, kwargs)
+
+
+class TestArrayUfunc:
+    """ufunc calls run raptors kernels and return raptors arrays"""
+
+    def test_unary(self):
+        result = ufunc("sin", xp.asarray([0.0, math.pi / 2]))
+        assert isinstance(result, raptors.PyArray)
+        assert result.tolist() == pytest.approx([0.0, 1.0])
+
+    def test_binary(self):
+        result = ufunc("add", xp.asarray([1, 2]), xp.asarray([10, 20]))
+        assert isinstance(result, raptors.PyArray)
+        assert result.tolist() == [11, 22]
+
+    def test_python_scalar_operand(self):
+        result = ufunc("multiply", 2, xp.asarray([1.5, 2.5], dtype=xp.float32))
+        assert result.dtype == xp.float32
+        assert result.tolist() == [3.0, 5.0]
+
+    def test_numpy_names(self):
+        arr = xp.asarray([-1.0, 4.0])
+        assert ufunc("absolute", arr).tolist() == [1.0, 4.0]
+        assert ufunc("power", arr, 2).tolist() == [1.0, 16.0]
+        assert ufunc("true_divide", arr, 2.0).tolist() == [-0.5, 2.0]
+        assert ufunc("invert", xp.asarray([0], dtype=xp.int8)).tolist() == [-1]
+
+    def test_matmul(self):
+        a = xp.asarray([[1.0, 2.0], [3.0, 4.0]])
+        assert ufunc("matmul", a, a).tolist() == [[7.0, 10.0], [15.0, 22.0]]
+
+    def test_out(self):
+        out = xp.zeros(2, dtype=xp.float32)
+        result = ufunc("add", xp.asarray([1.0, 2.0]), 1.0, out=(out,))
+        assert result is out
+        assert out.tolist() == [2.0, 3.0]
+
+    def test_out_shape_mismatch(self):
+        with pytest.raises(ValueError):
+            ufunc("add", xp.asarray([1.0, 2.0]), 1.0, out=(xp.zeros(3),))
+
+    def test_where(self):
+        out = xp.full(3, -1.0)
+        mask = xp.asarray([True, False, True])
+        ufunc("negative", xp.asarray([1.0, 2.0, 3.0]), out=(out,), where=mask)
+        assert out.tolist() == [-1.0, -1.0, -3.0]
+
+    def test_dtype(self):
+        result = ufunc("add", xp.asarray([1, 2]), 1, dtype="float32")
+        assert result.dtype == xp.float32
+
+    def test_reduce(self):
+        arr = xp.asarray([[1, 2], [3, 4]])
+        assert ufunc("add", arr, method="reduce").tolist() == [4, 6]
+        assert ufunc("maximum", arr, method="reduce", axis=1).tolist() == [2, 4]
+        assert ufunc("multiply", arr, method="reduce", axis=None).tolist() == 24
+
+    def test_accumulate(self):
+        arr = xp.asarray([1, 2, 3])
+        assert ufunc("add", arr, method="accumulate").tolist() == [1, 3, 6]
+
+    @pytest.mark.parametrize("name, kwargs", [
+        ("not_a_ufunc", {}),
+        ("sin", {"casting": "unsafe"}),
+        ("sin", {"order": "F"}),
+        ("sin", {"out": ([1.0],)}),
+    ])
+    def test_unsupported(self, name, kwargs):
+        assert ufunc(name, xp.asarray([1.0]), **kwargs) is NotImplemented
+
+    def test_unsupported_method(self):
+        arr = xp.asarray([1.0])
+        assert ufunc("add", arr, arr, method="outer") is NotImplemented
+        assert ufunc("subtract", arr, method="reduce") is NotImplemented
+        assert ufunc("add", arr, method="reduce", initial=1) is NotImplemented
+
+
+class TestArrayFunction:
+    """NumPy functions run in raptors and return raptors arrays"""
+
+    def test_concatenate(self):
+        a, b = xp.asarray([[1, 2]]), xp.asarray([[3, 4]])
+        result = function("concatenate", [a, b])
+        assert isinstance(result, raptors.PyArray)
+        assert result.tolist() == [[1, 2], [3, 4]]
+        assert function("concatenate", [a, b], axis=1).tolist() == [[1, 2, 3, 4]]
+
+    def test_stack(self):
+        a, b = xp.asarray([1, 2]), xp.asarray([3, 4])
+        assert function("stack", [a, b], axis=1).tolist() == [[1, 3], [2, 4]]
+
+    def test_sort(self):
+        arr = xp.asarray([[3, 1], [2, 0]])
+        assert function("sort", arr).tolist() == [[1, 3], [0, 2]]
+        assert function("sort", arr, axis=0).tolist() == [[2, 0], [3, 1]]
+        assert function("sort", arr, axis=None).tolist() == [0, 1, 2, 3]
+        assert function("argsort", xp.asarray([3, 1, 2])).tolist() == [1, 2, 0]
+
+    def test_reductions(self):
+        arr = xp.asarray([[1.0, 2.0], [3.0, 4.0]])
+        assert function("sum", arr).tolist() == 10.0
+        assert function("sum", arr, axis=0).tolist() == [4.0, 6.0]
+        assert function("mean", arr, axis=1, keepdims=True).tolist() == [[1.5], [3.5]]
+        assert function("amax", arr).tolist() == 4.0
+        assert function("min", arr, 1).tolist() == [1.0, 3.0]
+        assert function("prod", arr).tolist() == 24.0
+        assert function("argmax", arr).tolist() == 3
+
+    def test_deviations(self):
+        arr = xp.asarray([1.0, 2.0, 3.0, 4.0])
+        assert function("var", arr).tolist() == pytest.approx(1.25)
+        assert function("var", arr, ddof=1).tolist() == pytest.approx(5 / 3)
+        assert function("std", arr).tolist() == pytest.approx(math.sqrt(1.25))
+
+    def test_cumsum(self):
+        arr = xp.asarray([[1, 2], [3, 4]])
+        assert function("cumsum", arr).tolist() == [1, 3, 6, 10]
+        assert function("cumsum", arr, axis=0).tolist() == [[1, 2], [4, 6]]
+
+    def test_shape_functions(self):
+        arr = xp.asarray([[1, 2, 3], [4, 5, 6]])
+        assert function("reshape", arr, (3, 2)).tolist() == [[1, 2], [3, 4], [5, 6]]
+        assert function("transpose", arr).tolist() == [[1, 4], [2, 5], [3, 6]]
+        assert function("squeeze", xp.asarray([[1], [2]])).tolist() == [1, 2]
+        assert function("expand_dims", arr, 0).shape == (1, 2, 3)
+        assert function("flip", xp.asarray([1, 2, 3])).tolist() == [3, 2, 1]
+        assert function("roll", xp.asarray([1, 2, 3]), 1).tolist() == [3, 1, 2]
+        assert function("tile", xp.asarray([1, 2]), 2).tolist() == [1, 2, 1, 2]
+        assert function("moveaxis", arr, 0, 1).shape == (3, 2)
+
+    def test_dot(self):
+        a = xp.asarray([[1.0, 2.0], [3.0, 4.0]])
+        assert function("dot", a, a).tolist() == [[7.0, 10.0], [15.0, 22.0]]
+        assert function("dot", xp.asarray([1.0, 2.0]), xp.asarray([3.0, 4.0])).tolist() == 11.0
+
+    def test_where(self):
+        cond = xp.asarray([True, False, True])
+        result = function("where", cond, xp.asarray([1, 2, 3]), 0)
+        assert result.tolist() == [1, 0, 3]
+        (indices,) = function("where", cond)
+        assert indices.tolist() == [0, 2]
+
+    def test_clip(self):
+        arr = xp.asarray([1, 5, 10])
+        assert function("clip", arr, 2, 8).tolist() == [2, 5, 8]
+        assert function("clip", arr, max=4).tolist() == [1, 4, 4]
+
+    def test_take(self):
+        arr = xp.asarray([[1, 2], [3, 4]])
+        assert function("take", arr, xp.asarray([3, 0])).tolist() == [4, 1]
+        assert function("take", arr, xp.asarray([1]), axis=1).tolist() == [[2], [4]]
+
+    def test_unique(self):
+        arr = xp.asarray([3, 1, 3, 2])
+        assert function("unique", arr).tolist() == [1, 2, 3]
+        values, counts = function("unique", arr, return_counts=True)
+        assert values.tolist() == [1, 2, 3]
+        assert counts.tolist() == [1, 1, 2]
+
+    def test_like(self):
+        arr = xp.asarray([1.0, 2.0])
+        assert function("zeros_like", arr).tolist() == [0.0, 0.0]
+        assert function("ones_like", arr, dtype="int32").dtype == xp.int32
+        assert function("full_like", arr, 7.0).tolist() == [7.0, 7.0]
+
+    def test_predicates(self):
+        a = xp.asarray([1.0, 2.0])
+        assert function("array_equal", a, xp.asarray([1.0, 2.0])) is True
+        assert function("allclose", a, a + 1e-9) is True
+        assert function("isclose", a, xp.asarray([1.0, 2.5])).tolist() == [True, False]
+
+    def test_unsupported_function(self):
+        assert function("einsum", xp.asarray([1])) is NotImplemented
+
+    @pytest.mark.parametrize("name, kwargs", [
+        ("sum", {"initial": 1}),
+        ("sort", {"order": "x"}),
+        ("reshape", {"shape": (1,), "order": "F"}),
+        ("mean", {"where": False}),
+        ("sum", {"unknown": 1}),
+    ])
+    def test_unsupported_arguments(self, name, kwargs):
+        assert function(name, xp.asarray([1.0]), **kwargs) is NotImplemented
+
+    def test_foreign_types(self):
+        arr = xp.asarray([1, 2])
+        result = arr.__array_function__(numpy_function("sum"), (raptors.PyArray, dict), (arr,), {})
+        assert result is NotImplemented
+
+
+class TestNumPy:
+    """Real NumPy calls dispatch to raptors"""
+
+    @pytest.fixture
+    def np(self):
+        return pytest.importorskip("numpy")
+
+    def test_ufunc(self, np):
+        result = np.sin(xp.asarray([0.0, 1.0]))
+        assert isinstance(result, raptors.PyArray)
+        assert result.tolist() == pytest.approx([0.0, math.sin(1.0)])
+
+    def test_functions(self, np):
+        arr = xp.asarray([[3.0, 1.0], [2.0, 4.0]])
+        assert isinstance(np.concatenate([arr, arr]), raptors.PyArray)
+        assert np.sort(arr).tolist() == [[1.0, 3.0], [2.0, 4.0]]
+        assert np.mean(arr) == pytest.approx(2.5)
+
+    def test_unsupported_raises(self, np):
+        with pytest.raises(TypeError):
+            np.add.outer(xp.asarray([1.0]), xp.asarray([1.0]))
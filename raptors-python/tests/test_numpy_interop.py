@@ -33,3 +33,80 @@ class TestNumPyInterop:
         assert raptors.int64.name == "int64"
         assert raptors.int32.name == "int32"
 
+
+
+class TestNumPyZeroCopy:
+    """Tests for dtype coverage and memory sharing through __array_interface__"""
+
+    @pytest.mark.parametrize("dtype", [
+        "bool", "int8", "int16", "int32", "int64", "uint8", "uint16", "uint32",
+        "uint64", "float16", "float32", "float64", "complex64", "complex128",
+        "datetime64[ns]", "datetime64[D]", "timedelta64[s]", "S5", "U3",
+    ])
+    def test_roundtrip_dtypes(self, dtype):
+        """Every dtype survives a round trip with its values"""
+        np_arr = np.arange(6).astype(dtype).reshape(2, 3)
+        arr = raptors.from_numpy(np_arr)
+        assert arr.shape == (2, 3)
+        back = raptors.to_numpy(arr)
+        assert back.dtype == np_arr.dtype
+        np.testing.assert_array_equal(back, np_arr)
+
+    def test_structured_dtype(self):
+        """Structured dtypes keep their fields"""
+        np_arr = np.array([(1, 2.5), (3, 4.5)], dtype=[("a", "<i4"), ("b", "<f8")])
+        back = raptors.to_numpy(raptors.from_numpy(np_arr))
+        assert back.dtype.names == ("a", "b")
+        np.testing.assert_array_equal(back["b"], [2.5, 4.5])
+
+    def test_from_numpy_shares_memory(self):
+        """Writes through the Raptors array show up in NumPy, and it keeps NumPy's memory alive"""
+        np_arr = np.zeros(4)
+        arr = raptors.from_numpy(np_arr)
+        arr[1] = 7.0
+        assert np_arr[1] == 7.0
+        np_arr[2] = 3.0
+        assert arr[2] == 3.0
+        del np_arr
+        assert arr.tolist() == [0.0, 7.0, 3.0, 0.0]
+
+    def test_to_numpy_shares_memory(self):
+        """The NumPy array views the Raptors data and keeps it alive"""
+        arr = raptors.zeros([3], dtype=raptors.float64)
+        np_arr = raptors.to_numpy(arr)
+        np_arr[0] = 5.0
+        assert arr[0] == 5.0
+        del arr
+        np.testing.assert_array_equal(np_arr, [5.0, 0.0, 0.0])
+        assert np.asarray(raptors.from_numpy(np_arr)).ctypes.data == np_arr.ctypes.data
+
+    def test_strided_and_negative_strides(self):
+        """Non-contiguous and reversed arrays are shared, not copied"""
+        base = np.arange(12.0).reshape(3, 4)
+        for view in (base[:, ::2], base[::-1], base.T, base[::-2, ::-3]):
+            arr = raptors.from_numpy(view)
+            assert arr.shape == view.shape
+            np.testing.assert_array_equal(raptors.to_numpy(arr), view)
+            assert np.shares_memory(raptors.to_numpy(arr), base)
+
+    def test_readonly_respected(self):
+        """Read-only NumPy arrays give read-only Raptors arrays, and back"""
+        np_arr = np.arange(3.0)
+        np_arr.flags.writeable = False
+        arr = raptors.from_numpy(np_arr)
+        with pytest.raises(ValueError):
+            arr[0] = 1.0
+        assert not raptors.to_numpy(arr).flags.writeable
+
+    def test_byteswapped_input_is_copied(self):
+        """Non-native byte order is converted to native values"""
+        np_arr = np.arange(4, dtype=">i4")
+        arr = raptors.from_numpy(np_arr)
+        assert arr.tolist() == [0, 1, 2, 3]
+        np.testing.assert_array_equal(raptors.to_numpy(arr), [0, 1, 2, 3])
+
+    def test_array_protocol(self):
+        """np.asarray and np.array accept Raptors arrays"""
+        arr = raptors.ones([2, 2], dtype=raptors.float32)
+        assert np.asarray(arr).dtype == np.float32
+        assert np.array(arr, dtype=np.float64).dtype == np.float64
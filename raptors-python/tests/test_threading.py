@@ -0,0 +1,150 @@
+"""Tests that compute-bound bindings release the GIL"""
+
+import os
+import sys
+import threading
+import time
+
+import pytest
+import raptors
+import raptors.array_api as xp
+
+
+def large():
+    return xp.ones((2000, 2000))
+
+
+def runs_concurrently(kernel, attempts=20):
+    """Whether another Python thread makes progress while `kernel` runs
+
+    A ticker thread counts while it holds the GIL and releases it between
+    counts. The switch interval is raised so that the ticker cannot force
+    the GIL away from this thread, which makes the count unchanged across
+    a kernel that holds the GIL. A kernel that releases it lets the ticker
+    count; a few attempts cover calls that finish before the ticker is
+    scheduled.
+    """
+    ticks = 0
+    started, stop = threading.Event(), threading.Event()
+
+    def ticker():
+        nonlocal ticks
+        started.set()
+        while not stop.wait(0.001):
+            ticks += 1
+
+    interval = sys.getswitchinterval()
+    sys.setswitchinterval(100.0)
+    thread = threading.Thread(target=ticker)
+    thread.start()
+    started.wait()
+    try:
+        for _ in range(attempts):
+            before = ticks
+            kernel()
+            if ticks != before:
+                return True
+        return False
+    finally:
+        stop.set()
+        thread.join()
+        sys.setswitchinterval(interval)
+
+
+class TestReleasesGil:
+    """Kernels run while other Python threads keep running"""
+
+    @pytest.mark.parametrize("kernel", [
+        lambda a: xp.sum(a),
+        lambda a: xp.mean(a, axis=0),
+        lambda a: xp.std(a),
+        lambda a: a.sum(),
+        lambda a: raptors.sum(a),
+    ], ids=["sum", "mean", "std", "method_sum", "ufunc_sum"])
+    def test_reductions(self, kernel):
+        a = large()
+        assert runs_concurrently(lambda: kernel(a))
+
+    @pytest.mark.parametrize("kernel", [
+        lambda a: xp.sin(a),
+        lambda a: a + a,
+        lambda a: -a,
+        lambda a: raptors.sqrt(a),
+    ], ids=["sin", "add", "negative", "ufunc_sqrt"])
+    def test_elementwise(self, kernel):
+        a = large()
+        assert runs_concurrently(lambda: kernel(a))
+
+    def test_matmul(self):
+        a = xp.ones((300, 300))
+        assert runs_concurrently(lambda: a @ a)
+
+    def test_sort(self):
+        a = xp.flip(xp.arange(1_000_000, dtype=xp.float64))
+        assert runs_concurrently(lambda: xp.sort(a))
+
+    def test_io(self, tmp_path):
+        a = large()
+        path = tmp_path / "a.npz"
+        raptors.savez_compressed(path, a=a)
+        assert runs_concurrently(lambda: raptors.savez_compressed(path, a=a))
+        assert runs_concurrently(lambda: raptors.load(path)["a"])
+
+
+class TestConcurrentUse:
+    """Arrays stay usable from other threads while kernels read them"""
+
+    def test_setitem_during_kernel(self):
+        a = large()
+        stop = threading.Event()
+
+        def worker():
+            while not stop.is_set():
+                xp.sum(a)
+
+        thread = threading.Thread(target=worker)
+        thread.start()
+        try:
+            for i in range(20):
+                a[0, 0] = float(i)
+        finally:
+            stop.set()
+            thread.join()
+        assert a[0, 0] == 19.0
+
+    def test_threads_agree(self):
+        a = xp.reshape(xp.arange(100_000, dtype=xp.float64), (100, 1000))
+        results = [None] * 8
+
+        def worker(i):
+            results[i] = xp.sum(a, axis=1).tolist()
+
+        threads = [threading.Thread(target=worker, args=(i,)) for i in range(8)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        assert all(result == results[0] for result in results)
+
+    @pytest.mark.slow
+    @pytest.mark.skipif((os.cpu_count() or 1) < 2, reason="needs at least two CPUs")
+    def test_threads_scale(self):
+        a = large()
+        calls = 8
+
+        def work(count):
+            for _ in range(count):
+                xp.sin(a)
+
+        begin = time.perf_counter()
+        work(calls)
+        serial = time.perf_counter() - begin
+
+        threads = [threading.Thread(target=work, args=(calls // 2,)) for _ in range(2)]
+        begin = time.perf_counter()
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+        parallel = time.perf_counter() - begin
+        assert parallel < serial * 0.8